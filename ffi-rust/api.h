@@ -11,6 +11,8 @@ typedef struct {
 
 ByteBuffer accuracy_to_privacy_usage(const uint8_t *request_ptr, int32_t request_length);
 
+ByteBuffer analysis_accuracies(const uint8_t *request_ptr, int32_t request_length);
+
 ByteBuffer compute_privacy_usage(const uint8_t *request_ptr, int32_t request_length);
 
 ByteBuffer expand_component(const uint8_t *request_ptr, int32_t request_length);
@@ -23,8 +25,14 @@ ByteBuffer privacy_usage_to_accuracy(const uint8_t *request_ptr, int32_t request
 
 ByteBuffer validate_analysis(const uint8_t *request_ptr, int32_t request_length);
 
+ByteBuffer validate_component(const uint8_t *request_ptr, int32_t request_length);
+
 ByteBuffer release(const uint8_t *request_ptr, int32_t request_length);
 
+ByteBuffer release_partition(const uint8_t *request_ptr, int32_t request_length);
+
+ByteBuffer estimate_resources(const uint8_t *request_ptr, int32_t request_length);
+
 void smartnoise_destroy_bytebuffer(ByteBuffer buffer);
 
 // direct api
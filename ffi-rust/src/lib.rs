@@ -12,9 +12,12 @@ mod direct_api;
 
 use smartnoise_validator::utilities::serial::{
     serialize_error, parse_release, serialize_release, parse_argument_properties,
-    serialize_value_properties, parse_indexmap_release_node, serialize_component_expansion
+    serialize_value_properties, parse_indexmap_release_node, serialize_component_expansion,
+    parse_index_key
 };
 use crate::utilities::{ptr_to_buffer, buffer_to_ptr};
+#[cfg(feature = "use-runtime")]
+use crate::utilities::get_node_privacy_usages;
 use smartnoise_validator::base::Release;
 use std::collections::HashMap;
 use indexmap::map::IndexMap;
@@ -156,7 +159,12 @@ pub extern "C" fn generate_report(
                     let computation_graph = computation_graph
                         .ok_or_else(|| Error::from("computation_graph must be defined"))?.value;
 
-                    smartnoise_validator::generate_report(privacy_definition, computation_graph, release)
+                    if request.alpha > 0. {
+                        smartnoise_validator::generate_report_with_intervals(
+                            privacy_definition, computation_graph, release, request.alpha)
+                    } else {
+                        smartnoise_validator::generate_report(privacy_definition, computation_graph, release)
+                    }
                 };
 
                 match run() {
@@ -337,6 +345,58 @@ pub extern "C" fn get_properties(
     buffer_to_ptr(response)
 }
 
+/// FFI wrapper for [analysis_accuracies](../fn.analysis_accuracies.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestAnalysisAccuracies](../proto/struct.RequestAnalysisAccuracies.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseAnalysisAccuracies](../proto/struct.ResponseAnalysisAccuracies.html)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn analysis_accuracies(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseAnalysisAccuracies {
+        value: match proto::RequestAnalysisAccuracies::decode(request_buffer) {
+            Ok(request) => {
+                let proto::RequestAnalysisAccuracies {
+                    analysis, release, alpha
+                } = request;
+
+                let run = || -> Result<proto::AnalysisAccuracies> {
+                    let proto::Analysis {
+                        privacy_definition, computation_graph
+                    } = analysis
+                        .ok_or_else(|| Error::from("analysis must be defined"))?;
+                    let release = parse_release(release
+                        .ok_or_else(|| Error::from("release must be defined"))?);
+
+                    let privacy_definition = privacy_definition
+                        .ok_or_else(|| Error::from("privacy_definition must be defined"))?;
+                    let computation_graph = computation_graph
+                        .ok_or_else(|| Error::from("computation_graph must be defined"))?.value;
+
+                    smartnoise_validator::analysis_accuracies(privacy_definition, computation_graph, release, alpha)
+                };
+
+                match run() {
+                    Ok(x) =>
+                        Some(proto::response_analysis_accuracies::Value::Data(x)),
+                    Err(err) =>
+                        Some(proto::response_analysis_accuracies::Value::Error(serialize_error(err))),
+                }
+            }
+            Err(_) =>
+                Some(proto::response_analysis_accuracies::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
 /// FFI wrapper for [expand_component](../fn.expand_component.html)
 ///
 /// # Arguments
@@ -392,8 +452,71 @@ pub extern "C" fn expand_component(
     buffer_to_ptr(response)
 }
 
+/// FFI wrapper for [validate_component](../fn.validate_component.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestValidateComponent](../proto/struct.RequestValidateComponent.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferValidator struct](struct.ByteBufferValidator.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseValidateComponent](../proto/struct.ResponseValidateComponent.html)
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn validate_component(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseValidateComponent {
+        value: match proto::RequestValidateComponent::decode(request_buffer) {
+            Ok(request) => {
+                let proto::RequestValidateComponent {
+                    component, properties, arguments, privacy_definition, component_id,
+                } = request;
+
+                let run = || -> Result<proto::response_validate_component::Validated> {
+                    let component = component
+                        .ok_or_else(|| Error::from("component must be defined"))?;
+
+                    let public_arguments = arguments
+                        .map_or_else(IndexMap::new, parse_indexmap_release_node);
+
+                    let properties = properties
+                        .map_or_else(IndexMap::new, parse_argument_properties);
+
+                    let (properties, warnings) = smartnoise_validator::validate_component(
+                        component,
+                        properties,
+                        public_arguments,
+                        privacy_definition,
+                        component_id)?;
+
+                    Ok(proto::response_validate_component::Validated {
+                        properties: Some(serialize_value_properties(properties)),
+                        warnings: warnings.into_iter().map(serialize_error).collect(),
+                    })
+                };
+
+                match run() {
+                    Ok(x) =>
+                        Some(proto::response_validate_component::Value::Data(x)),
+                    Err(err) =>
+                        Some(proto::response_validate_component::Value::Error(serialize_error(err))),
+                }
+            }
+            Err(_) =>
+                Some(proto::response_validate_component::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
 /// FFI wrapper for [release](fn.release.html)
 ///
+/// If the request has a submission filter, only nodes from that submission are returned, as in [release_submission](smartnoise_runtime::release_submission).
+/// If privacy usages are requested, the actual privacy usage of each released node is returned alongside the release,
+/// as in [compute_node_privacy_usages](smartnoise_validator::compute_node_privacy_usages).
+///
 /// # Arguments
 /// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestRelease](proto/struct.RequestRelease.html)
 /// - `request_length` - the length of the array
@@ -412,11 +535,89 @@ pub extern "C" fn release(
         value: match proto::RequestRelease::decode(request_buffer) {
             Ok(request) => {
                 let proto::RequestRelease {
-                    analysis, release, stack_trace, filter_level
+                    analysis, release, stack_trace, filter_level, submission_filter, include_privacy_usages
                 } = request;
 
 
-                let run = || -> Result<(Release, Vec<proto::Error>)> {
+                let run = || -> Result<(Release, Vec<proto::Error>, HashMap<u32, proto::PrivacyUsages>)> {
+                    let proto::Analysis {
+                        privacy_definition, computation_graph
+                    } = analysis
+                        .ok_or_else(|| Error::from("analysis must be defined"))?;
+                    let computation_graph = computation_graph
+                        .ok_or_else(|| Error::from("computation_graph must be defined"))?.value;
+                    let release = parse_release(release
+                        .ok_or_else(|| Error::from("release must be defined"))?);
+                    let filter_level = proto::FilterLevel::from_i32(filter_level)
+                        .ok_or_else(|| Error::from(format!("unrecognized filter level {:?}", filter_level)))?;
+
+                    let usage_analysis = if include_privacy_usages {
+                        Some((privacy_definition.clone(), computation_graph.clone()))
+                    } else { None };
+
+                    let (release, warnings) = match submission_filter {
+                        Some(proto::SubmissionFilter { submission }) => smartnoise_runtime::release_submission(
+                            privacy_definition, computation_graph, release, filter_level, submission)?,
+                        None => smartnoise_runtime::release(
+                            privacy_definition, computation_graph, release, filter_level)?
+                    };
+
+                    let privacy_usages = match usage_analysis {
+                        Some((privacy_definition, computation_graph)) => get_node_privacy_usages(
+                            privacy_definition, computation_graph, release.clone())?,
+                        None => HashMap::new()
+                    };
+
+                    Ok((release, warnings.into_iter().map(serialize_error).collect(), privacy_usages))
+                };
+
+                match run() {
+                    Ok((release, warnings, privacy_usages)) => Some(proto::response_release::Value::Data(proto::response_release::Success {
+                        release: Some(serialize_release(release)),
+                        warnings: if stack_trace { warnings } else { Vec::new() },
+                        privacy_usages,
+                    })),
+                    Err(err) => if stack_trace {
+                        Some(proto::response_release::Value::Error(serialize_error(err)))
+                    } else {
+                        Some(proto::response_release::Value::Error(serialize_error("unspecified error while executing analysis".into())))
+                    }
+                }
+            }
+            Err(_) => Some(proto::response_release::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+
+/// FFI wrapper for [release_partition](fn.release_partition.html)
+///
+/// If privacy usages are requested, the actual privacy usage of each released node is returned alongside the release,
+/// as in [compute_node_privacy_usages](smartnoise_validator::compute_node_privacy_usages).
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestReleasePartition](proto/struct.RequestReleasePartition.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferRuntime struct](struct.ByteBufferRuntime.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseRelease](proto/struct.ResponseRelease.html)
+#[cfg(feature = "use-runtime")]
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn release_partition(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseRelease {
+        value: match proto::RequestReleasePartition::decode(request_buffer) {
+            Ok(request) => {
+                let proto::RequestReleasePartition {
+                    analysis, release, partition_key, stack_trace, filter_level, include_privacy_usages
+                } = request;
+
+                let run = || -> Result<(Release, Vec<proto::Error>, HashMap<u32, proto::PrivacyUsages>)> {
                     let proto::Analysis {
                         privacy_definition, computation_graph
                     } = analysis
@@ -425,19 +626,32 @@ pub extern "C" fn release(
                         .ok_or_else(|| Error::from("computation_graph must be defined"))?.value;
                     let release = parse_release(release
                         .ok_or_else(|| Error::from("release must be defined"))?);
+                    let partition_key = parse_index_key(partition_key
+                        .ok_or_else(|| Error::from("partition_key must be defined"))?);
                     let filter_level = proto::FilterLevel::from_i32(filter_level)
                         .ok_or_else(|| Error::from(format!("unrecognized filter level {:?}", filter_level)))?;
 
-                    let (release, warnings) = smartnoise_runtime::release(
-                        privacy_definition, computation_graph, release, filter_level)?;
+                    let usage_analysis = if include_privacy_usages {
+                        Some((privacy_definition.clone(), computation_graph.clone()))
+                    } else { None };
+
+                    let (release, warnings) = smartnoise_runtime::release_partition(
+                        privacy_definition, computation_graph, release, partition_key, filter_level)?;
 
-                    Ok((release, warnings.into_iter().map(serialize_error).collect()))
+                    let privacy_usages = match usage_analysis {
+                        Some((privacy_definition, computation_graph)) => get_node_privacy_usages(
+                            privacy_definition, computation_graph, release.clone())?,
+                        None => HashMap::new()
+                    };
+
+                    Ok((release, warnings.into_iter().map(serialize_error).collect(), privacy_usages))
                 };
 
                 match run() {
-                    Ok((release, warnings)) => Some(proto::response_release::Value::Data(proto::response_release::Success {
+                    Ok((release, warnings, privacy_usages)) => Some(proto::response_release::Value::Data(proto::response_release::Success {
                         release: Some(serialize_release(release)),
                         warnings: if stack_trace { warnings } else { Vec::new() },
+                        privacy_usages,
                     })),
                     Err(err) => if stack_trace {
                         Some(proto::response_release::Value::Error(serialize_error(err)))
@@ -453,4 +667,430 @@ pub extern "C" fn release(
 }
 
 
+/// FFI wrapper for [estimate_resources](fn.estimate_resources.html)
+///
+/// # Arguments
+/// - `request_ptr` - a pointer to an array containing the serialized protobuf of [RequestEstimateResources](proto/struct.RequestEstimateResources.html)
+/// - `request_length` - the length of the array
+///
+/// # Returns
+/// a [ByteBufferRuntime struct](struct.ByteBufferRuntime.html) containing a pointer to and length of the serialized protobuf of [proto::ResponseEstimateResources](proto/struct.ResponseEstimateResources.html)
+#[cfg(feature = "use-runtime")]
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn estimate_resources(
+    request_ptr: *const u8, request_length: i32,
+) -> ffi_support::ByteBuffer {
+    let request_buffer = unsafe { ptr_to_buffer(request_ptr, request_length) };
+
+    let response = proto::ResponseEstimateResources {
+        value: match proto::RequestEstimateResources::decode(request_buffer) {
+            Ok(request) => {
+                let proto::RequestEstimateResources {
+                    analysis, release, stack_trace
+                } = request;
+
+                let run = || -> Result<proto::ResourceEstimates> {
+                    let proto::Analysis {
+                        privacy_definition, computation_graph
+                    } = analysis
+                        .ok_or_else(|| Error::from("analysis must be defined"))?;
+                    let computation_graph = computation_graph
+                        .ok_or_else(|| Error::from("computation_graph must be defined"))?.value;
+                    let release = parse_release(release
+                        .ok_or_else(|| Error::from("release must be defined"))?);
+
+                    let (estimates, warnings) = smartnoise_runtime::estimate_resources(
+                        privacy_definition, computation_graph, release)?;
+
+                    Ok(proto::ResourceEstimates {
+                        warnings: if stack_trace { warnings.into_iter().map(serialize_error).collect() } else { Vec::new() },
+                        ..estimates
+                    })
+                };
+
+                match run() {
+                    Ok(estimates) => Some(proto::response_estimate_resources::Value::Data(estimates)),
+                    Err(err) => if stack_trace {
+                        Some(proto::response_estimate_resources::Value::Error(serialize_error(err)))
+                    } else {
+                        Some(proto::response_estimate_resources::Value::Error(serialize_error("unspecified error while estimating resources".into())))
+                    }
+                }
+            }
+            Err(_) => Some(proto::response_estimate_resources::Value::Error(serialize_error("unable to parse protobuf".into())))
+        }
+    };
+    buffer_to_ptr(response)
+}
+
+
 ffi_support::define_bytebuffer_destructor!(smartnoise_destroy_bytebuffer);
+
+
+#[cfg(test)]
+mod test_validate_component {
+    use indexmap::map::IndexMap;
+    use prost::Message;
+
+    use smartnoise_validator::base::{IndexKey, ReleaseNode, Value};
+    use smartnoise_validator::proto;
+    use smartnoise_validator::utilities::inference::infer_property;
+    use smartnoise_validator::utilities::serial::{
+        parse_value, parse_value_properties, serialize_argument_properties, serialize_index_key,
+        serialize_release_node
+    };
+
+    fn float_column(data: Vec<f64>) -> Value {
+        parse_value(proto::Value {
+            data: Some(proto::value::Data::Array(proto::Array {
+                shape: vec![data.len() as u64, 1],
+                flattened: Some(proto::Array1d {
+                    data: Some(proto::array1d::Data::F64(proto::Array1dF64 { data }))
+                }),
+            }))
+        })
+    }
+
+    fn validate_clamp(lower: f64, upper: f64) -> proto::response_validate_component::Value {
+        // private data of unknown bounds
+        let mut data_property = infer_property(
+            &float_column(vec![1., 2., 3.]), None, 0).unwrap();
+        if let smartnoise_validator::base::ValueProperties::Array(array) = &mut data_property {
+            array.releasable = false;
+            array.nature = None;
+        }
+        let mut properties = IndexMap::<IndexKey, _>::new();
+        properties.insert("data".into(), data_property);
+
+        let arguments = vec![("lower", lower), ("upper", upper)].into_iter()
+            .map(|(name, value)| (IndexKey::from(name), ReleaseNode {
+                value: Value::from(value), privacy_usages: None, public: true
+            }))
+            .collect::<IndexMap<IndexKey, ReleaseNode>>();
+
+        let request = proto::RequestValidateComponent {
+            component: Some(proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(vec!["data", "lower", "upper"].into_iter()
+                    .map(IndexKey::from).zip(0..).collect())),
+                variant: Some(proto::component::Variant::Clamp(proto::Clamp {})),
+                omit: false,
+                submission: 0,
+            }),
+            properties: Some(serialize_argument_properties(properties)),
+            arguments: Some(proto::IndexmapReleaseNode {
+                keys: arguments.keys().cloned().map(serialize_index_key).collect(),
+                values: arguments.values().cloned().map(serialize_release_node).collect(),
+            }),
+            privacy_definition: None,
+            component_id: 3,
+        };
+
+        let mut request_buffer = Vec::new();
+        request.encode(&mut request_buffer).unwrap();
+
+        let response = super::validate_component(request_buffer.as_ptr(), request_buffer.len() as i32);
+        proto::ResponseValidateComponent::decode(response.destroy_into_vec().as_slice())
+            .unwrap().value.unwrap()
+    }
+
+    #[test]
+    fn test_clamp() {
+        let validated = match validate_clamp(0., 10.) {
+            proto::response_validate_component::Value::Data(validated) => validated,
+            proto::response_validate_component::Value::Error(err) => panic!("{:?}", err)
+        };
+
+        let properties = parse_value_properties(validated.properties.unwrap());
+        let properties = properties.array().unwrap();
+        assert_eq!(properties.node_id, 3);
+        assert!(!properties.releasable);
+
+        let nature = properties.nature.as_ref().unwrap().continuous().unwrap();
+        assert_eq!(nature.lower.float().unwrap(), &vec![Some(0.)]);
+        assert_eq!(nature.upper.float().unwrap(), &vec![Some(10.)]);
+    }
+
+    #[test]
+    fn test_clamp_error() {
+        match validate_clamp(10., 0.) {
+            proto::response_validate_component::Value::Error(err) =>
+                assert!(err.message.contains("node_id 3"), "{}", err.message),
+            proto::response_validate_component::Value::Data(_) => panic!("lower must not exceed upper")
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test_analysis_accuracies {
+    use prost::Message;
+
+    use smartnoise_validator::base::IndexKey;
+    use smartnoise_validator::bindings::{Analysis, privacy_usage};
+    use smartnoise_validator::proto;
+    use smartnoise_validator::utilities::serial::{parse_index_key, serialize_release};
+
+    fn string_literal(analysis: &mut Analysis, values: &[&str]) -> u32 {
+        analysis.literal()
+            .value(ndarray::arr1(&values.iter().map(|v| v.to_string()).collect::<Vec<_>>()).into_dyn().into())
+            .value_public(true)
+            .build()
+    }
+
+    /// dp sum of a column of data imputed and clamped to [0, 10]
+    fn dp_sum_column(analysis: &mut Analysis, data: u32, name: &str, epsilon: f64) -> u32 {
+        let names = string_literal(analysis, &[name]);
+        let column = analysis.index(data).names(names).build();
+
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(column).lower(lower).upper(upper).build();
+        let imputed = analysis.impute(clamped).build();
+        analysis.dp_sum(imputed, privacy_usage(epsilon, 0.)).mechanism("Laplace".to_string()).build()
+    }
+
+    #[test]
+    fn test_two_mechanisms() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let column_names = string_literal(&mut analysis, &["a", "b"]);
+        let data_types = string_literal(&mut analysis, &["float", "float"]);
+        let data = analysis.materialize("data.csv".to_string())
+            .column_names(column_names)
+            .data_types(data_types)
+            .build();
+        let sum_a = dp_sum_column(&mut analysis, data, "a", 1.);
+        let sum_b = dp_sum_column(&mut analysis, data, "b", 0.5);
+
+        let request = proto::RequestAnalysisAccuracies {
+            analysis: Some(proto::Analysis {
+                privacy_definition: Some(analysis.privacy_definition),
+                computation_graph: Some(proto::ComputationGraph { value: analysis.components }),
+            }),
+            release: Some(serialize_release(analysis.release)),
+            alpha: 0.05,
+        };
+        let mut request_buffer = Vec::new();
+        request.encode(&mut request_buffer).unwrap();
+
+        let response = super::analysis_accuracies(request_buffer.as_ptr(), request_buffer.len() as i32);
+        let accuracies = match proto::ResponseAnalysisAccuracies::decode(response.destroy_into_vec().as_slice())
+            .unwrap().value.unwrap() {
+            proto::response_analysis_accuracies::Value::Data(accuracies) => accuracies.values,
+            proto::response_analysis_accuracies::Value::Error(err) => panic!("{:?}", err)
+        };
+
+        assert_eq!(accuracies.len(), 2);
+        let accuracy = |node_id: u32, name: &str| {
+            let node_accuracies = accuracies.get(&node_id).unwrap();
+            let variables = node_accuracies.variables.iter().cloned()
+                .map(parse_index_key).collect::<Vec<IndexKey>>();
+            assert_eq!(variables, vec![IndexKey::from(name)]);
+
+            let values = &node_accuracies.accuracies.as_ref().unwrap().values;
+            assert_eq!(values.len(), 1);
+            assert!(values[0].value.is_finite() && values[0].value > 0.);
+            assert_eq!(values[0].alpha, 0.05);
+            values[0].value
+        };
+
+        // half the epsilon doubles the noise
+        let accuracy_a = accuracy(sum_a, "a");
+        let accuracy_b = accuracy(sum_b, "b");
+        assert!((accuracy_b - 2. * accuracy_a).abs() < 1e-8, "{} should be double {}", accuracy_b, accuracy_a);
+    }
+}
+
+
+#[cfg(all(test, feature = "use-runtime"))]
+mod test_release {
+    use prost::Message;
+
+    use smartnoise_validator::bindings::{Analysis, privacy_usage};
+    use smartnoise_validator::proto;
+    use smartnoise_validator::utilities::serial::serialize_release;
+
+    #[test]
+    fn test_submission_filter() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(ndarray::arr1(&[1., 2., 3.]).into_dyn().into())
+            .value_public(true)
+            .build();
+        analysis.submission_count += 1;
+        let sum = analysis.sum(data).build();
+
+        let request = proto::RequestRelease {
+            analysis: Some(proto::Analysis {
+                privacy_definition: Some(analysis.privacy_definition),
+                computation_graph: Some(proto::ComputationGraph { value: analysis.components }),
+            }),
+            release: Some(serialize_release(analysis.release)),
+            stack_trace: true,
+            filter_level: proto::FilterLevel::Public as i32,
+            submission_filter: Some(proto::SubmissionFilter { submission: 1 }),
+            include_privacy_usages: false,
+        };
+        let mut request_buffer = Vec::new();
+        request.encode(&mut request_buffer).unwrap();
+
+        let response = super::release(request_buffer.as_ptr(), request_buffer.len() as i32);
+        let release = match proto::ResponseRelease::decode(response.destroy_into_vec().as_slice())
+            .unwrap().value.unwrap() {
+            proto::response_release::Value::Data(success) => success.release.unwrap(),
+            proto::response_release::Value::Error(err) => panic!("{:?}", err)
+        };
+
+        // the public literal from the prior submission is not returned
+        assert_eq!(release.values.keys().copied().collect::<Vec<u32>>(), vec![sum]);
+    }
+
+    #[test]
+    fn test_estimate_resources() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(ndarray::arr2(&[[1., 2.], [3., 4.], [5., 6.]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let sum = analysis.sum(data).build();
+
+        let request = proto::RequestEstimateResources {
+            analysis: Some(proto::Analysis {
+                privacy_definition: Some(analysis.privacy_definition),
+                computation_graph: Some(proto::ComputationGraph { value: analysis.components }),
+            }),
+            release: Some(serialize_release(analysis.release)),
+            stack_trace: true,
+        };
+        let mut request_buffer = Vec::new();
+        request.encode(&mut request_buffer).unwrap();
+
+        let response = super::estimate_resources(request_buffer.as_ptr(), request_buffer.len() as i32);
+        let estimates = match proto::ResponseEstimateResources::decode(response.destroy_into_vec().as_slice())
+            .unwrap().value.unwrap() {
+            proto::response_estimate_resources::Value::Data(estimates) => estimates,
+            proto::response_estimate_resources::Value::Error(err) => panic!("{:?}", err)
+        };
+
+        assert!(estimates.complete);
+        assert_eq!(estimates.memory[&data], 48);
+        assert_eq!(estimates.memory[&sum], 16);
+        assert_eq!(estimates.elements_read[&sum], 6);
+        assert_eq!(estimates.total_memory, 64);
+    }
+
+    #[test]
+    fn test_privacy_usages() {
+        use smartnoise_validator::utilities::privacy::get_epsilon;
+        use smartnoise_validator::utilities::serial::parse_release;
+
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        // each mechanism spends half of the usage charged to the analysis
+        analysis.privacy_definition.group_size = 2;
+        let data = analysis.literal()
+            .value(ndarray::Array::from_shape_fn((10, 1), |(i, _)| i as f64).into_dyn().into())
+            .value_public(false)
+            .build();
+        let count = analysis.count(data).build();
+        let first = analysis.laplace_mechanism(count, privacy_usage(0.5, 0.)).build();
+        let second = analysis.laplace_mechanism(count, privacy_usage(0.25, 0.)).build();
+
+        let request = proto::RequestRelease {
+            analysis: Some(proto::Analysis {
+                privacy_definition: Some(analysis.privacy_definition.clone()),
+                computation_graph: Some(proto::ComputationGraph { value: analysis.components.clone() }),
+            }),
+            release: Some(serialize_release(analysis.release)),
+            stack_trace: true,
+            filter_level: proto::FilterLevel::Public as i32,
+            submission_filter: None,
+            include_privacy_usages: true,
+        };
+        let mut request_buffer = Vec::new();
+        request.encode(&mut request_buffer).unwrap();
+
+        let response = super::release(request_buffer.as_ptr(), request_buffer.len() as i32);
+        let success = match proto::ResponseRelease::decode(response.destroy_into_vec().as_slice())
+            .unwrap().value.unwrap() {
+            proto::response_release::Value::Data(success) => success,
+            proto::response_release::Value::Error(err) => panic!("{:?}", err)
+        };
+
+        // the map holds the usage charged to the analysis
+        let epsilon = |node_id: u32| get_epsilon(&success.privacy_usages[&node_id].values[0]).unwrap();
+        assert!((epsilon(first) - 0.5).abs() < 1e-12);
+        assert!((epsilon(second) - 0.25).abs() < 1e-12);
+
+        // while each mechanism spends half of it, as each record may contribute to two rows
+        let release = parse_release(success.release.clone().unwrap());
+        let mechanism_epsilon = |node_id: u32| get_epsilon(
+            &release[&node_id].privacy_usages.as_ref().unwrap()[0]).unwrap();
+        assert!((mechanism_epsilon(first) - 0.25).abs() < 1e-12);
+        assert!((mechanism_epsilon(second) - 0.125).abs() < 1e-12);
+
+        // without a partition, the map composes to the usage of the analysis
+        let total = success.privacy_usages.values()
+            .flat_map(|usages| usages.values.iter())
+            .map(|usage| get_epsilon(usage).unwrap())
+            .sum::<f64>();
+        let usage = smartnoise_validator::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, parse_release(success.release.unwrap())).unwrap();
+        assert!((total - get_epsilon(&usage).unwrap()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_release_partition_privacy_usages() {
+        use smartnoise_validator::base::IndexKey;
+        use smartnoise_validator::utilities::privacy::get_epsilon;
+        use smartnoise_validator::utilities::serial::{parse_release, serialize_index_key};
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(ndarray::Array::from_shape_fn((10, 1), |(i, _)| i as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let by = analysis.literal()
+            .value(ndarray::Array::from_shape_fn((10, 1), |(i, _)| ["a", "b"][i % 2].to_string()).into_dyn().into())
+            .value_public(true)
+            .build();
+        let partitioned = analysis.partition(data).by(by).build();
+        analysis.sum(partitioned).build();
+
+        // a mechanism outside of the partition is released along with the first partition
+        let private = analysis.literal()
+            .value(ndarray::Array::from_shape_fn((10, 1), |(i, _)| i as f64).into_dyn().into())
+            .value_public(false)
+            .build();
+        let count = analysis.count(private).build();
+        let dp_count = analysis.laplace_mechanism(count, privacy_usage(1., 0.)).build();
+
+        let request = proto::RequestReleasePartition {
+            analysis: Some(proto::Analysis {
+                privacy_definition: Some(analysis.privacy_definition.clone()),
+                computation_graph: Some(proto::ComputationGraph { value: analysis.components.clone() }),
+            }),
+            release: Some(serialize_release(analysis.release)),
+            partition_key: Some(serialize_index_key(IndexKey::Tuple(vec![IndexKey::from("a".to_string())]))),
+            stack_trace: true,
+            filter_level: proto::FilterLevel::All as i32,
+            include_privacy_usages: true,
+        };
+        let mut request_buffer = Vec::new();
+        request.encode(&mut request_buffer).unwrap();
+
+        let response = super::release_partition(request_buffer.as_ptr(), request_buffer.len() as i32);
+        let success = match proto::ResponseRelease::decode(response.destroy_into_vec().as_slice())
+            .unwrap().value.unwrap() {
+            proto::response_release::Value::Data(success) => success,
+            proto::response_release::Value::Error(err) => panic!("{:?}", err)
+        };
+
+        assert!(parse_release(success.release.unwrap()).contains_key(&dp_count));
+        assert_eq!(success.privacy_usages.len(), 1);
+        assert!((get_epsilon(&success.privacy_usages[&dp_count].values[0]).unwrap() - 1.).abs() < 1e-12);
+    }
+}
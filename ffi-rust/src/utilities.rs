@@ -1,5 +1,9 @@
 
 use prost::Message;
+#[cfg(feature = "use-runtime")]
+use smartnoise_validator::{base::Release, errors::*, proto};
+#[cfg(feature = "use-runtime")]
+use std::collections::HashMap;
 
 // useful tutorial for proto over ffi here:
 // https://github.com/mozilla/application-services/blob/master/docs/howtos/passing-protobuf-data-over-ffi.md
@@ -27,4 +31,20 @@ pub fn buffer_to_ptr<T>(buffer: T) -> ffi_support::ByteBuffer
             ffi_support::ByteBuffer::new_with_size(0)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Compute the actual privacy usage of each released node, as returned in a release response.
+#[doc(hidden)]
+#[cfg(feature = "use-runtime")]
+pub fn get_node_privacy_usages(
+    privacy_definition: Option<proto::PrivacyDefinition>,
+    computation_graph: HashMap<u32, proto::Component>,
+    release: Release,
+) -> Result<HashMap<u32, proto::PrivacyUsages>> {
+    let privacy_definition = privacy_definition
+        .ok_or_else(|| Error::from("privacy_definition must be defined to compute privacy usages"))?;
+    Ok(smartnoise_validator::compute_node_privacy_usages(privacy_definition, computation_graph, release)?
+        .into_iter()
+        .map(|(node_id, usages)| (node_id, proto::PrivacyUsages { values: usages }))
+        .collect())
+}
@@ -0,0 +1,15 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use smartnoise_runtime::utilities::{get_bits, get_bytes};
+
+// get_bytes allocates a String per byte, and a character per bit,
+// while get_bits allocates a single buffer of packed bits
+fn bench_random_bits(c: &mut Criterion) {
+    c.bench_function("get_bytes 1024 bits", |b| b.iter(|| get_bytes(128).unwrap()
+        .chars().filter(|bit| *bit == '1').count()));
+    c.bench_function("get_bits 1024 bits", |b| b.iter(|| get_bits(1024).unwrap()
+        .iter().filter(|bit| *bit).count()));
+}
+
+criterion_group!(benches, bench_random_bits);
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ndarray::{arr0, Array1, Array2};
+
+use smartnoise_runtime::utilities::broadcast_map;
+
+fn bench_broadcast_map(c: &mut Criterion) {
+    let data = Array2::from_shape_fn((100_000, 10), |(i, j)| (i * j) as f64).into_dyn();
+    let column = Array1::from_shape_fn(100_000, |i| i as f64).into_dyn();
+    let scalar = arr0(2.).into_dyn();
+
+    c.bench_function("broadcast_map 2d x 2d", |b| b.iter(|| broadcast_map(
+        black_box(data.clone()), black_box(data.clone()), &|l, r| l + r).unwrap()));
+    c.bench_function("broadcast_map 2d x 1d", |b| b.iter(|| broadcast_map(
+        black_box(data.clone()), black_box(column.clone()), &|l, r| l + r).unwrap()));
+    c.bench_function("broadcast_map 2d x 0d", |b| b.iter(|| broadcast_map(
+        black_box(data.clone()), black_box(scalar.clone()), &|l, r| l + r).unwrap()));
+}
+
+criterion_group!(benches, bench_broadcast_map);
+criterion_main!(benches);
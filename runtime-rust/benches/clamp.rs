@@ -0,0 +1,36 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ndarray::{Array1, Array2, ArrayD};
+
+use smartnoise_runtime::components::clamp::clamp_numeric_float;
+use smartnoise_validator::Float;
+
+// the prior implementation, which clamps each generalized column with a filtered iterator
+fn clamp_by_column(mut data: ArrayD<Float>, lower: ArrayD<Float>, upper: ArrayD<Float>) -> ArrayD<Float> {
+    data.gencolumns_mut().into_iter()
+        .zip(lower.iter())
+        .zip(upper.iter())
+        .for_each(|((mut column, min), max)| column.iter_mut()
+            .filter(|v| !v.is_nan())
+            .for_each(|v| *v = min.max(max.min(*v))));
+    data
+}
+
+fn bench_clamp(c: &mut Criterion) {
+    let data = Array2::from_shape_fn((1_000_000, 10), |(i, j)| match i % 100 {
+        0 => Float::NAN,
+        _ => ((i * j) % 1000) as Float
+    }).into_dyn();
+    let lower = Array1::from_elem(10, 100.).into_dyn();
+    let upper = Array1::from_elem(10, 900.).into_dyn();
+
+    let mut group = c.benchmark_group("clamp 1e6 x 10");
+    group.sample_size(10);
+    group.bench_function("by column", |b| b.iter(|| clamp_by_column(
+        black_box(data.clone()), lower.clone(), upper.clone())));
+    group.bench_function("vectorized", |b| b.iter(|| clamp_numeric_float(
+        black_box(data.clone()), lower.clone(), upper.clone()).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, bench_clamp);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use ndarray::Array1;
+
+use smartnoise_runtime::utilities::mechanisms::{apply_elementwise, laplace_mechanism};
+
+fn bench_laplace_elementwise(c: &mut Criterion) {
+    let n = 1_000_000;
+    let sensitivities = Array1::from_elem(n, 1.);
+    let mut values = Array1::zeros(n);
+
+    let mut group = c.benchmark_group("laplace");
+    group.sample_size(10);
+    // compare runs with and without the use-rayon feature
+    group.bench_function("apply_elementwise 10^6", |b| b.iter(|| apply_elementwise(
+        values.view_mut(), sensitivities.view(),
+        |v, sens| laplace_mechanism(v, 1., sens, false)).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, bench_laplace_elementwise);
+criterion_main!(benches);
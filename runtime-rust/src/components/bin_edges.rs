@@ -0,0 +1,66 @@
+use smartnoise_validator::base::{Array, IndexKey, Jagged, ReleaseNode, Value};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::proto;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+
+impl Evaluable for proto::BinEdges {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?;
+        let lower = arguments.remove::<IndexKey>(&"lower".into());
+        let upper = arguments.remove::<IndexKey>(&"upper".into());
+
+        macro_rules! get_edge {
+            ($edge:expr, $variant:ident) => {
+                match $edge {
+                    Some(edge) => match edge.array()? {
+                        Array::$variant(edge) => Some(edge.first().cloned()
+                            .ok_or_else(|| Error::from("edges must be scalar"))?),
+                        _ => return Err("edges must share the atomic type of the data".into())
+                    },
+                    None => None
+                }
+            }
+        }
+
+        Ok(ReleaseNode::new(Value::Jagged(match data {
+            Array::Float(data) => Jagged::Float(vec![bin_edges(
+                data.iter().copied().filter(|v| !v.is_nan()).collect(),
+                get_edge!(lower, Float), get_edge!(upper, Float))]),
+            Array::Int(data) => Jagged::Int(vec![bin_edges(
+                data.iter().copied().collect(),
+                get_edge!(lower, Int), get_edge!(upper, Int))]),
+            _ => return Err("data: atomic type must be numeric".into())
+        })))
+    }
+}
+
+/// Converts a set of cut-points into sorted, deduplicated bin edges.
+///
+/// Cut-points outside of the open interval between `lower` and `upper` are discarded,
+/// and `lower` and `upper` are added as the outermost edges.
+///
+/// # Arguments
+/// * `cut_points` - Cut-points, in any order.
+/// * `lower` - Optional lowest edge.
+/// * `upper` - Optional highest edge.
+///
+/// # Return
+/// Bin edges, in increasing order.
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::components::bin_edges::bin_edges;
+/// let edges = bin_edges(vec![5., 2., 5., 12.], Some(0.), Some(10.));
+/// assert_eq!(edges, vec![0., 2., 5., 10.]);
+/// ```
+pub fn bin_edges<T: PartialOrd + Copy>(mut cut_points: Vec<T>, lower: Option<T>, upper: Option<T>) -> Vec<T> {
+    cut_points.retain(|v| lower.map(|lower| *v > lower).unwrap_or(true)
+        && upper.map(|upper| *v < upper).unwrap_or(true));
+    cut_points.sort_by(|l, r| l.partial_cmp(r).unwrap());
+    cut_points.dedup();
+
+    lower.into_iter().chain(cut_points).chain(upper).collect()
+}
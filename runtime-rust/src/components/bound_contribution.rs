@@ -0,0 +1,68 @@
+use smartnoise_validator::errors::*;
+
+use ndarray::prelude::*;
+use crate::NodeArguments;
+use smartnoise_validator::base::{Array, ReleaseNode};
+use smartnoise_validator::utilities::take_argument;
+use crate::components::Evaluable;
+use ndarray::{ArrayD, Axis, Array1};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use smartnoise_validator::proto;
+
+use smartnoise_validator::utilities::array::slow_select;
+use crate::utilities::to_nd;
+
+
+impl Evaluable for proto::BoundContribution {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let indices = match take_argument(&mut arguments, "key")?.array()? {
+            Array::Int(key) => bounded_indices(key, self.max_contributions)?,
+            Array::Str(key) => bounded_indices(key, self.max_contributions)?,
+            Array::Bool(key) => bounded_indices(key, self.max_contributions)?,
+            _ => return Err("key: atomic type must be integer, string or boolean".into())
+        };
+
+        Ok(ReleaseNode::new(match take_argument(&mut arguments, "data")?.array()? {
+            Array::Str(data) => slow_select(&data, Axis(0), &indices).into(),
+            Array::Float(data) => slow_select(&data, Axis(0), &indices).into(),
+            Array::F32(data) => slow_select(&data, Axis(0), &indices).into(),
+            Array::Int(data) => slow_select(&data, Axis(0), &indices).into(),
+            Array::Bool(data) => slow_select(&data, Axis(0), &indices).into(),
+        }))
+    }
+}
+
+/// Find the rows to keep, such that each individual contributes at most `max_contributions` rows.
+///
+/// The first rows contributed by each individual are kept.
+///
+/// # Arguments
+/// * `key` - Column identifying the individual that contributed each row.
+/// * `max_contributions` - Maximum number of rows to keep for each individual.
+///
+/// # Return
+/// Indices of the rows to keep, in their original order.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_runtime::components::bound_contribution::bounded_indices;
+///
+/// let key = arr1(&[1, 2, 1, 1, 2, 3]).into_dyn();
+/// assert_eq!(bounded_indices(key, 2).unwrap(), vec![0, 1, 2, 4, 5]);
+/// ```
+pub fn bounded_indices<K: Eq + Hash>(key: ArrayD<K>, max_contributions: u32) -> Result<Vec<usize>> {
+    let key: Array1<K> = to_nd(key, 1)?.into_dimensionality::<Ix1>()?;
+
+    let mut contributions = HashMap::<&K, u32>::new();
+    Ok(key.iter().enumerate()
+        .filter(|(_, key)| {
+            let count = contributions.entry(*key).or_insert(0);
+            *count += 1;
+            *count <= max_contributions
+        })
+        .map(|(index, _)| index)
+        .collect())
+}
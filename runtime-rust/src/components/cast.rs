@@ -6,7 +6,8 @@ use crate::components::Evaluable;
 use ndarray::ArrayD;
 use smartnoise_validator::{proto, Float, Integer};
 use crate::utilities::noise;
-use smartnoise_validator::utilities::take_argument;
+use smartnoise_validator::utilities::{standardize_numeric_argument, take_argument};
+use crate::utilities::get_num_columns;
 
 
 impl Evaluable for proto::Cast {
@@ -14,9 +15,13 @@ impl Evaluable for proto::Cast {
         let data = take_argument(&mut arguments, "data")?.array()?;
         match self.atomic_type.to_lowercase().as_str() {
             // if casting to bool, identify what value should map to true, then cast
-            "bool" => {
-                let true_label = take_argument(&mut arguments, "true_label")?.array()?;
-                Ok(cast_bool(&data, &true_label)?.into())
+            "bool" => match take_argument(&mut arguments, "true_label") {
+                Ok(true_label) => Ok(cast_bool(&data, &true_label.array()?)?.into()),
+                // otherwise compare numeric data against a threshold
+                Err(_) => {
+                    let threshold = take_argument(&mut arguments, "threshold")?.array()?.cast_float()?;
+                    Ok(cast_bool_threshold(&data, threshold)?.into())
+                }
             },
             "float" | "real" => Ok(Value::Array(Array::Float(cast_float(&data)?))),
             "int" | "integer" => {
@@ -58,11 +63,55 @@ pub fn cast_bool(data: &Array, positive: &Array) -> Result<ArrayD<bool>> {
     }
 }
 
+/// Cast numeric data to type `bool`.
+///
+/// Elements greater than or equal to the threshold of their column are mapped to `true`, all others to `false`.
+/// `NAN` is never greater than or equal to the threshold, so is mapped to `false`.
+///
+/// # Arguments
+/// * `data` - Numeric data to be cast to `bool`.
+/// * `threshold` - Smallest value mapped to `true`, for each column.
+///
+/// # Return
+/// Data cast to `bool`.
+///
+/// # Example
+/// ```
+/// use ndarray::{arr1, arr2};
+/// use smartnoise_validator::base::Array;
+/// use smartnoise_runtime::components::cast::cast_bool_threshold;
+///
+/// let data = Array::Float(arr2(&[[0.5, 1.], [2.5, 3.]]).into_dyn());
+/// let cast = cast_bool_threshold(&data, arr1(&[1., 4.]).into_dyn()).unwrap();
+/// assert_eq!(cast, arr2(&[[false, false], [true, false]]).into_dyn());
+/// ```
+pub fn cast_bool_threshold(data: &Array, threshold: ArrayD<Float>) -> Result<ArrayD<bool>> {
+    fn compare<T: Copy>(
+        data: &ArrayD<T>, threshold: ArrayD<Float>, to_float: impl Fn(T) -> Float
+    ) -> Result<ArrayD<bool>> {
+        let threshold = standardize_numeric_argument(threshold, get_num_columns(data)?)?;
+        let mut cast = ArrayD::from_elem(data.shape(), false);
+        cast.gencolumns_mut().into_iter()
+            .zip(data.gencolumns().into_iter().zip(threshold.iter()))
+            .for_each(|(mut cast, (column, threshold))| cast.iter_mut().zip(column.iter())
+                .for_each(|(cast, value)| *cast = to_float(*value) >= *threshold));
+        Ok(cast)
+    }
+
+    match data {
+        Array::Float(data) => compare(data, threshold, |v| v),
+        Array::F32(data) => compare(data, threshold, Float::from),
+        Array::Int(data) => compare(data, threshold, |v| v as Float),
+        _ => Err("data must be numeric to cast to bool with a threshold".into())
+    }
+}
+
 /// Cast data to type `f64`.
 ///
 /// If data are `bool`, map `true => 1.` and `false => 0.`
 ///
 /// If data are `String`, attempt to parse as `f64` and return `NAN` otherwise.
+/// Surrounding whitespace is ignored when parsing.
 ///
 /// If data are `i64`, convert integers to their `f64` representation.
 ///
@@ -71,14 +120,25 @@ pub fn cast_bool(data: &Array, positive: &Array) -> Result<ArrayD<bool>> {
 ///
 /// # Return
 /// Data cast to `Float`.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_validator::base::Array;
+/// use smartnoise_runtime::components::cast::cast_float;
+///
+/// let data = Array::Str(arr1(&["1.5", " 2 ", "abc", ""]).mapv(String::from).into_dyn());
+/// let cast = cast_float(&data).unwrap();
+/// assert_eq!(cast.slice(ndarray::s![..2]), arr1(&[1.5, 2.]));
+/// assert!(cast[2].is_nan() && cast[3].is_nan());
+/// ```
 pub fn cast_float(data: &Array) -> Result<ArrayD<Float>> {
     Ok(match data {
-        Array::Str(data) => data.mapv(|v| match v.parse::<Float>() {
-            Ok(v) => v, Err(_) => Float::NAN
-        }),
+        Array::Str(data) => data.mapv(|v| parse_float(&v).unwrap_or(Float::NAN)),
         Array::Bool(data) => data.mapv(|v| if v {1.} else {0.}),
         Array::Int(data) => data.mapv(|v| v as Float),
         Array::Float(data) => data.clone(),
+        Array::F32(data) => data.mapv(Float::from),
     })
 }
 
@@ -86,7 +146,8 @@ pub fn cast_float(data: &Array) -> Result<ArrayD<Float>> {
 ///
 /// If data are `bool`, map `true => 1` and `false => 0`
 ///
-/// If data are `String`, attempt to parse as `i64` and impute a uniform `i64` between `lower` and `upper` otherwise.
+/// If data are `String`, attempt to parse as `i64`, then as a rounded `f64`.
+/// Strings that cannot be parsed are null, and a uniform `i64` between `lower` and `upper` is imputed in their place.
 ///
 /// If data are `f64`, round non-`NAN` values to their `i64` representation,
 /// impute uniform `i64` between `lower` and `upper` for values that are `NAN`.
@@ -98,17 +159,46 @@ pub fn cast_float(data: &Array) -> Result<ArrayD<Float>> {
 ///
 /// # Return
 /// Data cast to `i64`.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_validator::base::Array;
+/// use smartnoise_runtime::components::cast::cast_int;
+///
+/// let data = Array::Str(arr1(&["1", "2.6", "abc"]).mapv(String::from).into_dyn());
+/// let cast = cast_int(&data, 10, 10).unwrap();
+/// assert_eq!(cast, arr1(&[1, 3, 10]).into_dyn());
+/// ```
 pub fn cast_int(data: &Array, lower: Integer, upper: Integer) -> Result<ArrayD<Integer>> {
+    let round = |v: Float| if v.is_finite() {Some(v.round() as Integer)} else {None};
     Ok(match data {
-        Array::Str(data) => data
-            .mapv(|v| v.parse::<Integer>().unwrap_or_else(|_| noise::sample_uniform_int(lower, upper).unwrap())),
-        Array::Float(data) => data
-            .mapv(|v| if !v.is_nan() {v.round() as Integer} else {noise::sample_uniform_int(lower, upper).unwrap()}),
+        Array::Str(data) => impute_int(
+            data.mapv(|v| v.trim().parse::<Integer>().ok().or_else(|| parse_float(&v).and_then(round))),
+            lower, upper)?,
+        Array::Float(data) => impute_int(data.mapv(round), lower, upper)?,
+        Array::F32(data) => impute_int(data.mapv(|v| round(Float::from(v))), lower, upper)?,
         Array::Bool(data) => data.mapv(|v| if v {1} else {0}),
         Array::Int(data) => data.clone()
     })
 }
 
+/// Parse a string as a float, ignoring surrounding whitespace.
+fn parse_float(value: &str) -> Option<Float> {
+    value.trim().parse::<Float>().ok()
+}
+
+/// Replace null (`None`) values with a uniform `i64` between `lower` and `upper`.
+fn impute_int(data: ArrayD<Option<Integer>>, lower: Integer, upper: Integer) -> Result<ArrayD<Integer>> {
+    let imputed = data.iter()
+        .map(|v| match v {
+            Some(v) => Ok(*v),
+            None => noise::sample_uniform_int(lower, upper)
+        })
+        .collect::<Result<Vec<Integer>>>()?;
+    Ok(ArrayD::from_shape_vec(data.shape(), imputed)?)
+}
+
 /// Cast data to type `String`.
 ///
 /// Regardless of data type, simply convert to `String`.
@@ -122,7 +212,55 @@ pub fn cast_str(data: &Array) -> Result<ArrayD<String>> {
     Ok(match data {
         Array::Str(data) => data.clone(),
         Array::Float(data) => data.mapv(|v| v.to_string()),
+        Array::F32(data) => data.mapv(|v| v.to_string()),
         Array::Bool(data) => data.mapv(|v| v.to_string()),
         Array::Int(data) => data.mapv(|v| v.to_string())
     })
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod test_cast {
+    use ndarray::{arr1, arr2, Array2};
+    use smartnoise_validator::base::Array;
+    use smartnoise_validator::proto;
+
+    use crate::components::Evaluable;
+
+    #[test]
+    fn test_bool_threshold() {
+        let data = Array2::from_shape_fn((1000, 1), |(i, _)| i as f64 / 1000.).into_dyn();
+        let arguments = indexmap::indexmap![
+            "data".into() => data.into(),
+            "threshold".into() => arr1(&[0.75]).into_dyn().into()
+        ];
+        let cast = proto::Cast { atomic_type: "bool".to_string() }
+            .evaluate(&None, arguments).unwrap()
+            .value.array().unwrap().bool().unwrap();
+
+        // a quarter of the uniformly spaced values are at least the threshold
+        assert_eq!(cast.iter().filter(|v| **v).count(), 250);
+        assert!(cast[[750, 0]] && !cast[[749, 0]]);
+    }
+
+    #[test]
+    fn test_unparseable_strings() {
+        let data = arr2(&[
+            ["1", "abc"],
+            [" 2.5", "4"],
+            ["", "1e2"],
+            ["inf", "-3"],
+        ]).mapv(String::from).into_dyn();
+
+        // unparseable strings are null in the float column
+        let cast = super::cast_float(&Array::Str(data.clone())).unwrap();
+        let nulls = cast.mapv(|v| v.is_nan());
+        assert_eq!(nulls, arr2(&[[false, true], [false, false], [true, false], [false, false]]).into_dyn());
+        assert_eq!(cast[[1, 0]], 2.5);
+        assert_eq!(cast[[2, 1]], 100.);
+
+        // unparseable and non-finite strings are imputed in the integer column
+        let cast = super::cast_int(&Array::Str(data), -10, -10).unwrap();
+        assert_eq!(cast, arr2(&[[1, -10], [3, 4], [-10, 100], [-10, -3]]).into_dyn());
+    }
+}
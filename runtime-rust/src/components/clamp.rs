@@ -4,10 +4,12 @@ use crate::NodeArguments;
 use smartnoise_validator::base::{Value, Array, Jagged, ReleaseNode, IndexKey};
 use smartnoise_validator::utilities::{standardize_numeric_argument, standardize_categorical_argument, standardize_null_target_argument, take_argument};
 use crate::components::Evaluable;
-use ndarray::ArrayD;
+use ndarray::{ArrayD, IxDyn, Zip};
 use crate::utilities::get_num_columns;
+use crate::utilities::nulls::Nullable;
 use smartnoise_validator::{proto, Float, Integer};
 use std::hash::Hash;
+use noisy_float::types::n64;
 
 impl Evaluable for proto::Clamp {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
@@ -17,9 +19,8 @@ impl Evaluable for proto::Clamp {
                 (Value::Array(data), Value::Jagged(categories), Value::Array(nulls)) => Ok(match (data, categories, nulls) {
                     (Array::Bool(data), Jagged::Bool(categories), Array::Bool(nulls)) =>
                         clamp_categorical(data, categories, nulls)?.into(),
-                    (Array::Float(_), Jagged::Float(_), Array::Float(_)) =>
-                        return Err("float clamping is not supported".into()),
-//                        clamp_categorical(&data, &categories, &nulls)?.into(),
+                    (Array::Float(data), Jagged::Float(categories), Array::Float(nulls)) =>
+                        clamp_categorical_float(data, categories, nulls)?.into(),
                     (Array::Int(data), Jagged::Int(categories), Array::Int(nulls)) =>
                         clamp_categorical(data, categories, nulls)?.into(),
                     (Array::Str(data), Jagged::Str(categories), Array::Str(nulls)) =>
@@ -71,19 +72,15 @@ pub fn clamp_numeric_float(
     mut data: ArrayD<Float>, lower: ArrayD<Float>, upper: ArrayD<Float>
 )-> Result<ArrayD<Float>> {
 
-    let num_columns = get_num_columns(&data)?;
+    let (lower, upper) = row_bounds(&data, lower, upper)?;
+    let shape = data.shape().to_vec();
 
-    // iterate over the generalized columns
-    data.gencolumns_mut().into_iter()
-        // pair generalized columns with arguments
-        .zip(standardize_numeric_argument(lower, num_columns)?.into_iter())
-        .zip(standardize_numeric_argument(upper, num_columns)?.into_iter())
-        // for each pairing, iterate over the cells
-        .for_each(|((mut column, min), max)| column.iter_mut()
-            // ignore nan values
-            .filter(|v| !v.is_nan())
-            // mutate the cell via the operator
-            .for_each(|v| *v = min.max(max.min(*v))));
+    // a single pass over the cells, with the bounds broadcast down the rows
+    Zip::from(&mut data)
+        .and(lower.broadcast(shape.clone()).ok_or("lower: could not broadcast to data")?)
+        .and(upper.broadcast(shape).ok_or("upper: could not broadcast to data")?)
+        // select rather than branch, so that nan values are skipped without preventing vectorization
+        .apply(|v, min, max| *v = if v.is_null() { *v } else { min.max(max.min(*v)) });
 
     Ok(data)
 }
@@ -115,21 +112,33 @@ pub fn clamp_numeric_integer(
     mut data: ArrayD<Integer>, lower: ArrayD<Integer>, upper: ArrayD<Integer>
 )-> Result<ArrayD<Integer>> {
 
-    let num_columns = get_num_columns(&data)?;
+    let (lower, upper) = row_bounds(&data, lower, upper)?;
+    let shape = data.shape().to_vec();
 
-    // iterate over the generalized columns
-    data.gencolumns_mut().into_iter()
-        // pair generalized columns with arguments
-        .zip(standardize_numeric_argument(lower, num_columns)?.into_iter())
-        .zip(standardize_numeric_argument(upper, num_columns)?.into_iter())
-        // for each pairing, iterate over the cells
-        .for_each(|((mut column, min), max)| column.iter_mut()
-            // mutate the cell via the operator
-            .for_each(|v| *v = *min.max(max.min(v))));
+    // a single pass over the cells, with the bounds broadcast down the rows
+    Zip::from(&mut data)
+        .and(lower.broadcast(shape.clone()).ok_or("lower: could not broadcast to data")?)
+        .and(upper.broadcast(shape).ok_or("upper: could not broadcast to data")?)
+        .apply(|v, min, max| *v = *min.max(max.min(v)));
 
     Ok(data)
 }
 
+/// Standardize per-column bounds into rows that broadcast against the data.
+fn row_bounds<T: Clone, U>(
+    data: &ArrayD<U>, lower: ArrayD<T>, upper: ArrayD<T>
+) -> Result<(ArrayD<T>, ArrayD<T>)> {
+    let num_columns = get_num_columns(data)?;
+    let lower = standardize_numeric_argument(lower, num_columns)?;
+    let upper = standardize_numeric_argument(upper, num_columns)?;
+
+    // the bounds of a scalar are also scalar
+    Ok(match data.ndim() {
+        0 => (lower.into_shape(IxDyn(&[]))?, upper.into_shape(IxDyn(&[]))?),
+        _ => (lower, upper)
+    })
+}
+
 /// Clamps each column of categorical data to desired set.
 ///
 /// Clamping for categorical data is not as obvious a concept as clamping for numeric data.
@@ -186,3 +195,62 @@ pub fn clamp_categorical<T: Ord + Hash + Clone>(
 
     Ok(data)
 }
+
+/// Clamps each column of float data to a set of categories.
+///
+/// Membership is tested under the total ordering of non-NaN floats, so `-0.` and `0.` are the same category.
+/// NaN is never a category, so NaN data is always mapped to the null value.
+///
+/// # Arguments
+/// * `data` - Data to be clamped.
+/// * `categories` - For each column, the set of categories you want to be represented. May not contain NaN.
+/// * `null_value` - For each column, the value to which elements not included in `categories` will be mapped.
+///
+/// # Return
+/// Data clamped to the categories.
+///
+/// # Example
+/// ```
+/// use ndarray::{ArrayD, arr2, arr1};
+/// use smartnoise_runtime::components::clamp::clamp_categorical_float;
+/// let data: ArrayD<f64> = arr2(&[[0.5, 1.], [2.5, f64::NAN], [-0., 3.]]).into_dyn();
+/// let categories: Vec<Vec<f64>> = vec![vec![0.5, 0.], vec![1., 2.]];
+/// let null_value: ArrayD<f64> = arr1(&[-1., -2.]).into_dyn();
+///
+/// let clamped_data = clamp_categorical_float(data, categories, null_value).unwrap();
+/// assert_eq!(clamped_data, arr2(&[[0.5, 1.], [-1., -2.], [-0., -2.]]).into_dyn());
+/// ```
+pub fn clamp_categorical_float(
+    mut data: ArrayD<Float>,
+    categories: Vec<Vec<Float>>,
+    null_value: ArrayD<Float>
+) -> Result<ArrayD<Float>> {
+    let num_columns = get_num_columns(&data)?;
+
+    if categories.iter().flatten().any(Nullable::is_null) {
+        return Err("categories may not contain NaN".into())
+    }
+    // sort the categories of each column, to search them under a total ordering
+    let categories = standardize_categorical_argument(categories.into_iter()
+        .map(|categories| categories.into_iter().map(n64).collect())
+        .collect(), num_columns)?.into_iter()
+        .map(|mut categories| {
+            categories.sort();
+            categories
+        })
+        .collect::<Vec<_>>();
+
+    // iterate over the generalized columns
+    data.gencolumns_mut().into_iter()
+        // pair generalized columns with arguments
+        .zip(categories)
+        .zip(standardize_null_target_argument(null_value, num_columns)?)
+        // for each pairing, iterate over the cells
+        .for_each(|((mut column, categories), null)| column.iter_mut()
+            // ignore known values
+            .filter(|v| v.is_null() || categories.binary_search(&n64(**v)).is_err())
+            // mutate the cell via the operator
+            .for_each(|v| *v = null));
+
+    Ok(data)
+}
@@ -29,7 +29,7 @@ impl Evaluable for proto::ColumnBind {
         }
 
         Ok(ReleaseNode::new(match arrays.first().ok_or_else(|| "must have at least one argument")? {
-            Array::Float(_) => col_stack!(stack, float),
+            Array::Float(_) | Array::F32(_) => col_stack!(stack, float),
             Array::Int(_) => col_stack!(stack, int),
             Array::Bool(_) => col_stack!(stack, bool),
             Array::Str(_) => col_stack!(slow_stack, string)
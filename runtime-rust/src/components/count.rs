@@ -1,16 +1,17 @@
 use smartnoise_validator::errors::*;
 
 use crate::NodeArguments;
-use smartnoise_validator::base::{Value, Array, ReleaseNode};
+use smartnoise_validator::base::{Value, Array, ReleaseNode, IndexKey};
 use crate::components::Evaluable;
 use ndarray::{ArrayD, Axis, arr0};
 use smartnoise_validator::{proto, Integer};
 use smartnoise_validator::utilities::take_argument;
 use std::collections::HashSet;
 use crate::utilities::get_num_columns;
+use crate::utilities::nulls::Nullable;
 use std::iter::FromIterator;
 use std::hash::Hash;
-use noisy_float::types::n64;
+use indexmap::IndexMap;
 
 
 impl Evaluable for proto::Count {
@@ -18,7 +19,8 @@ impl Evaluable for proto::Count {
         Ok(ReleaseNode::new(if self.distinct {
             match take_argument(&mut arguments, "data")?.array()? {
                 Array::Bool(data) => count_distinct(&data)?.into(),
-                Array::Float(data) => count_distinct(&data.mapv(|v| n64(v as f64)))?.into(),
+                Array::Float(data) => count_distinct(&data.mapv(|v| float_to_distinct_key(v as f64)))?.into(),
+                Array::F32(data) => count_distinct(&data.mapv(|v| float_to_distinct_key(v as f64)))?.into(),
                 Array::Int(data) => count_distinct(&data)?.into(),
                 Array::Str(data) => count_distinct(&data)?.into()
             }
@@ -27,13 +29,11 @@ impl Evaluable for proto::Count {
                 Value::Array(array) => match array {
                     Array::Bool(data) => count(&data)?.into(),
                     Array::Float(data) => count(&data)?.into(),
+                    Array::F32(data) => count(&data)?.into(),
                     Array::Int(data) => count(&data)?.into(),
                     Array::Str(data) => count(&data)?.into()
                 },
-                Value::Dataframe(dataframe) => match dataframe.get_index(0) {
-                    Some(value) => arr0(value.1.ref_array()?.num_records()? as Integer).into_dyn().into(),
-                    None => return Err("indexmap may not be empty".into())
-                },
+                Value::Dataframe(dataframe) => count_dataframe(&dataframe)?.into(),
                 _ => return Err("Count is only implemented on arrays and dataframes".into())
             }
         }))
@@ -60,6 +60,46 @@ pub fn count<T>(data: &ArrayD<T>) -> Result<ArrayD<Integer>> {
     Ok(ndarray::Array::from_shape_vec(vec![], vec![data.len_of(Axis(0)) as Integer])?)
 }
 
+/// Gets number of rows of a dataframe.
+///
+/// Every column of the dataframe must have the same number of records.
+///
+/// # Arguments
+/// * `dataframe` - Dataframe for which you want a count.
+///
+/// # Return
+/// Number of rows in the dataframe.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use indexmap::IndexMap;
+/// use smartnoise_validator::base::{IndexKey, Value};
+/// use smartnoise_runtime::components::count::count_dataframe;
+/// let mut dataframe = IndexMap::<IndexKey, Value>::new();
+/// dataframe.insert("a".into(), arr1(&[1, 2, 3]).into_dyn().into());
+/// dataframe.insert("b".into(), arr1(&[true, false, true]).into_dyn().into());
+/// assert_eq!(count_dataframe(&dataframe).unwrap().first(), Some(&3));
+///
+/// // ragged dataframes are malformed
+/// dataframe.insert("c".into(), arr1(&[1., 2.]).into_dyn().into());
+/// assert!(count_dataframe(&dataframe).is_err());
+/// ```
+pub fn count_dataframe(dataframe: &IndexMap<IndexKey, Value>) -> Result<ArrayD<Integer>> {
+    let num_records = dataframe.iter()
+        .map(|(name, column)| column.ref_array()
+            .and_then(|column| column.num_records())
+            .map_err(|e| Error::from(format!("column {:?}: {}", name, e))))
+        .collect::<Result<Vec<usize>>>()?;
+
+    let count = *num_records.first()
+        .ok_or_else(|| Error::from("dataframe may not be empty"))?;
+    if num_records.iter().any(|v| *v != count) {
+        return Err(format!("all columns of the dataframe must have the same number of records, but found {:?}", num_records).into())
+    }
+    Ok(arr0(count as Integer).into_dyn())
+}
+
 /// Gets number of unique values in the data.
 ///
 /// # Arguments
@@ -93,3 +133,74 @@ pub fn count_distinct<T: Eq + Hash>(data: &ArrayD<T>) -> Result<ArrayD<Integer>>
         Err(_) => Err("unable to package Count result into an array".into())
     }
 }
+
+/// Maps a float to a key that is equal for floats that are not distinct.
+///
+/// Signed zeros are treated as the same value, and all NaNs fall into a single bucket.
+///
+/// # Arguments
+/// * `value` - Float to be counted.
+///
+/// # Return
+/// Bit representation of the canonicalized float.
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::components::count::float_to_distinct_key;
+/// assert_eq!(float_to_distinct_key(-0.), float_to_distinct_key(0.));
+/// assert_eq!(float_to_distinct_key(f64::NAN), float_to_distinct_key(-f64::NAN));
+/// assert_ne!(float_to_distinct_key(1.), float_to_distinct_key(-1.));
+/// ```
+pub fn float_to_distinct_key(value: f64) -> u64 {
+    if value.is_null() {
+        f64::NAN.to_bits()
+    } else if value == 0. {
+        0u64
+    } else {
+        value.to_bits()
+    }
+}
+
+#[cfg(test)]
+mod test_count {
+    use indexmap::{IndexMap, indexmap};
+    use ndarray::{arr1, arr2, arr0};
+
+    use smartnoise_validator::base::{IndexKey, Value};
+    use smartnoise_validator::proto;
+
+    use crate::components::Evaluable;
+    use crate::components::count::{count_distinct, float_to_distinct_key};
+
+    #[test]
+    fn test_count_distinct_floats() {
+        let data = arr2(&[
+            [f64::NAN, 0., 1.],
+            [-f64::NAN, -0., 1.],
+            [0., 0., -1.],
+            [f64::NAN, -0., f64::INFINITY],
+        ]).mapv(float_to_distinct_key).into_dyn();
+        assert_eq!(count_distinct(&data).unwrap(), arr2(&[[2, 1, 3]]).into_dyn());
+
+        let data = arr1(&[-0., 0., f64::NAN, f64::NAN]).mapv(float_to_distinct_key).into_dyn();
+        assert_eq!(count_distinct(&data).unwrap(), arr0(2).into_dyn());
+    }
+
+    #[test]
+    fn test_count_ragged_dataframe() {
+        let count = proto::Count { distinct: false };
+        let evaluate = |dataframe: IndexMap<IndexKey, Value>| count.evaluate(
+            &None, indexmap!["data".into() => Value::Dataframe(dataframe)]);
+
+        let release = evaluate(indexmap![
+            "a".into() => arr1(&[1, 2, 3]).into_dyn().into(),
+            "b".into() => arr1(&["x", "y", "z"]).mapv(String::from).into_dyn().into()]).unwrap();
+        assert_eq!(release.value.ref_array().unwrap().first_int().unwrap(), 3);
+
+        // the second column is shorter than the first
+        assert!(evaluate(indexmap![
+            "a".into() => arr1(&[1, 2, 3]).into_dyn().into(),
+            "b".into() => arr1(&[1., 2.]).into_dyn().into()]).is_err());
+        assert!(evaluate(IndexMap::new()).is_err());
+    }
+}
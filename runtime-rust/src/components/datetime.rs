@@ -0,0 +1,122 @@
+use smartnoise_validator::errors::*;
+
+use crate::NodeArguments;
+use smartnoise_validator::base::ReleaseNode;
+use crate::components::Evaluable;
+use ndarray::ArrayD;
+use smartnoise_validator::{proto, Integer};
+use crate::utilities::get_num_columns;
+use smartnoise_validator::utilities::{take_argument, standardize_null_target_argument};
+use smartnoise_validator::utilities::datetime::{parse_timezone, parse_datetime, floor_datetime, check_unit};
+use chrono::FixedOffset;
+
+
+impl Evaluable for proto::ParseDateTime {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.string()?;
+        let null = take_argument(&mut arguments, "null_value")?.array()?.int()?;
+        let timezone = parse_timezone(&self.timezone)?;
+
+        Ok(ReleaseNode::new(to_datetime(&data, &self.format, &timezone, null)?.into()))
+    }
+}
+
+impl Evaluable for proto::DateBin {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.int()?;
+        let timezone = parse_timezone(&self.timezone)?;
+
+        Ok(ReleaseNode::new(date_bin(&data, &self.unit, &timezone)?.into()))
+    }
+}
+
+/// Parses strings into timestamps, in seconds since the unix epoch.
+///
+/// # Arguments
+/// * `data` - Strings to parse.
+/// * `format` - strftime-style format of the strings.
+/// * `timezone` - Offset that strings without an offset in the format are interpreted in.
+/// * `null` - Timestamp to map strings that cannot be parsed to, for each column.
+///
+/// # Return
+/// Timestamps, in seconds since the unix epoch.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_runtime::components::datetime::to_datetime;
+/// use smartnoise_validator::utilities::datetime::parse_timezone;
+///
+/// let data = arr1(&["1970-01-02", "not a date"]).mapv(|v| v.to_string()).into_dyn();
+/// let timezone = parse_timezone("UTC").unwrap();
+/// let parsed = to_datetime(&data, "%Y-%m-%d", &timezone, arr1(&[-1]).into_dyn()).unwrap();
+/// assert_eq!(parsed, arr1(&[86400, -1]).into_dyn());
+/// ```
+pub fn to_datetime(
+    data: &ArrayD<String>, format: &str, timezone: &FixedOffset, null: ArrayD<Integer>,
+) -> Result<ArrayD<Integer>> {
+    let null = standardize_null_target_argument(null, get_num_columns(data)?)?;
+
+    let mut parsed = ArrayD::<Integer>::zeros(data.shape());
+    parsed.gencolumns_mut().into_iter()
+        .zip(data.gencolumns().into_iter().zip(null))
+        .for_each(|(mut parsed, (column, null))| parsed.iter_mut().zip(column.iter())
+            .for_each(|(parsed, value)| *parsed = parse_datetime(value, format, timezone).unwrap_or(null)));
+    Ok(parsed)
+}
+
+/// Rounds timestamps down to the start of the day, week, month or year containing them.
+///
+/// # Arguments
+/// * `data` - Timestamps, in seconds since the unix epoch.
+/// * `unit` - One of "day", "week", "month" or "year". Weeks start on Monday.
+/// * `timezone` - Offset whose local midnight the bins are aligned to.
+///
+/// # Return
+/// Timestamp at the start of the bin containing each timestamp.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_runtime::components::datetime::date_bin;
+/// use smartnoise_validator::utilities::datetime::parse_timezone;
+///
+/// let data = arr1(&[86399, 86400, 129600]).into_dyn();
+/// let binned = date_bin(&data, "day", &parse_timezone("UTC").unwrap()).unwrap();
+/// assert_eq!(binned, arr1(&[0, 86400, 86400]).into_dyn());
+/// ```
+pub fn date_bin(data: &ArrayD<Integer>, unit: &str, timezone: &FixedOffset) -> Result<ArrayD<Integer>> {
+    check_unit(unit)?;
+    let mut binned = data.clone();
+    binned.iter_mut()
+        .try_for_each(|v| floor_datetime(*v, unit, timezone).map(|floored| *v = floored))?;
+    Ok(binned)
+}
+
+#[cfg(test)]
+mod test_datetime {
+    use ndarray::arr2;
+    use smartnoise_validator::utilities::datetime::parse_timezone;
+
+    use crate::components::datetime::{date_bin, to_datetime};
+
+    #[test]
+    fn test_iso_8601_to_days() {
+        let data = arr2(&[
+            ["2020-03-15T13:45:30Z", "2020-03-15T23:59:59-05:00"],
+            ["2020-03-16T00:00:00Z", "2020-03-16"],
+        ]).mapv(|v| v.to_string()).into_dyn();
+        let timezone = parse_timezone("UTC").unwrap();
+        let null = arr2(&[[-1, -2]]).into_dyn();
+
+        let timestamps = to_datetime(&data, "%Y-%m-%dT%H:%M:%S%#z", &timezone, null).unwrap();
+        // the final string is missing a time, so maps to the null of the second column
+        assert_eq!(timestamps, arr2(&[[1584279930, 1584334799], [1584316800, -2]]).into_dyn());
+
+        // 2020-03-16T04:59:59Z is on the 16th in UTC, but the 15th in UTC-05:00
+        let days = date_bin(&timestamps, "day", &timezone).unwrap();
+        assert_eq!(days, arr2(&[[1584230400, 1584316800], [1584316800, -86400]]).into_dyn());
+        let days = date_bin(&timestamps, "day", &parse_timezone("-05:00").unwrap()).unwrap();
+        assert_eq!(days, arr2(&[[1584248400, 1584248400], [1584248400, -68400]]).into_dyn());
+    }
+}
@@ -1,7 +1,8 @@
 use smartnoise_validator::errors::*;
 
 use crate::NodeArguments;
-use smartnoise_validator::base::{Array, Jagged, ReleaseNode};
+use smartnoise_validator::base::{Array, Jagged, OutOfRange, ReleaseNode};
+use std::cmp::Ordering;
 use crate::components::Evaluable;
 use ndarray::ArrayD;
 use smartnoise_validator::{proto, Integer};
@@ -16,14 +17,15 @@ impl Evaluable for proto::Digitize {
         let data = take_argument(&mut arguments, "data")?.array()?;
         let edges = take_argument(&mut arguments, "edges")?.jagged()?;
         let null = take_argument(&mut arguments, "null_value")?.array()?.int()?;
+        let out_of_range = self.out_of_range.parse::<OutOfRange>()?;
         let num_columns = data.num_columns()? as i64;
 
         Ok(ReleaseNode::new(match (data, edges) {
             (Array::Float(data), Jagged::Float(edges)) =>
-                digitize(data, standardize_float_argument(edges, num_columns)?, inclusive_left, null)?.into(),
+                digitize(data, standardize_float_argument(edges, num_columns)?, inclusive_left, null, &out_of_range)?.into(),
 
             (Array::Int(data), Jagged::Int(edges)) =>
-                digitize(data, standardize_categorical_argument(edges, num_columns)?, inclusive_left, null)?.into(),
+                digitize(data, standardize_categorical_argument(edges, num_columns)?, inclusive_left, null, &out_of_range)?.into(),
 
             _ => return Err("data and edges must both be float or integer".into())
         }))
@@ -38,7 +40,8 @@ impl Evaluable for proto::Digitize {
 /// * `data` - Data to be binned.
 /// * `edges` - Values representing the edges of bins.
 /// * `inclusive_left` - Whether or not the left edge of the bin is inclusive, i.e. the bins are of the form [lower, upper).
-/// * `null` - Value to which to map if there is no valid bin (e.g. if the element falls outside the bin range).
+/// * `null` - Value to which to map if there is no valid bin (e.g. if the element is NaN).
+/// * `out_of_range` - Policy for elements outside of the bin range.
 ///
 /// # Return
 /// Binned data.
@@ -47,6 +50,7 @@ impl Evaluable for proto::Digitize {
 /// ```
 /// use ndarray::{ArrayD, arr2, arr1};
 /// use smartnoise_runtime::components::digitize::{bin_index, digitize};
+/// use smartnoise_validator::base::OutOfRange;
 /// use smartnoise_validator::utilities::standardize_float_argument;
 /// use smartnoise_runtime::utilities::get_num_columns;
 /// use smartnoise_validator::Float;
@@ -60,15 +64,18 @@ impl Evaluable for proto::Digitize {
 /// let num_columns = get_num_columns(&data).unwrap();
 /// let edges = standardize_float_argument(edges, num_columns).unwrap();
 ///
-/// let digitization = digitize(data, edges, inclusive_left, null).unwrap();
-/// println!("digitize {:?}", digitization);
+/// let digitization = digitize(data.clone(), edges.clone(), inclusive_left.clone(), null.clone(), &OutOfRange::Null).unwrap();
 /// assert_eq!(digitization, arr1(&[1, 2, 2, 4, -1]).into_dyn());
+///
+/// let digitization = digitize(data, edges, inclusive_left, null, &OutOfRange::Clamp).unwrap();
+/// assert_eq!(digitization, arr1(&[1, 2, 2, 4, 4]).into_dyn());
 /// ```
 pub fn digitize<T: std::cmp::PartialOrd + Clone + Div<T, Output=T> + Add<T, Output=T> + Copy + Default>(
     data: ArrayD<T>,
     edges: Vec<Vec<T>>,
     inclusive_left: ArrayD<bool>,
     null: ArrayD<Integer>,
+    out_of_range: &OutOfRange,
 ) -> Result<ArrayD<Integer>> {
     let mut digitization = ArrayD::default(data.shape());
 
@@ -87,9 +94,10 @@ pub fn digitize<T: std::cmp::PartialOrd + Clone + Div<T, Output=T> + Add<T, Outp
         .for_each(|(((mut col_dig, col_data), (edges, null)), inclusive_left)|
             col_dig.iter_mut().zip(col_data.iter()).for_each(|(digit, datum)|
                 // mutate the cell via the operator
-                *digit = bin_index(datum, &edges, *inclusive_left)
-                    .map(|v| v as Integer)
-                    .unwrap_or(*null)));
+                *digit = match out_of_range {
+                    OutOfRange::Null => bin_index(datum, edges, *inclusive_left),
+                    OutOfRange::Clamp => clamped_bin_index(datum, edges, *inclusive_left)
+                }.map(|v| v as Integer).unwrap_or(*null)));
 
     Ok(digitization)
 }
@@ -127,8 +135,9 @@ pub fn bin_index<T: PartialOrd + Clone>(
     edges: &[T],
     inclusive_left: bool,
 ) -> Option<usize> {
-    // checks for nullity
-    if edges.is_empty() || datum < &edges[0] || datum > &edges[edges.len() - 1] {
+    // checks for nullity, including values that are not comparable, like NaN
+    if edges.is_empty() || datum.partial_cmp(datum).is_none()
+        || datum < &edges[0] || datum > &edges[edges.len() - 1] {
         return None;
     }
 
@@ -163,9 +172,52 @@ pub fn bin_index<T: PartialOrd + Clone>(
     Some(idx)
 }
 
+/// Given datum and bin definition, finds index of appropriate bin, or the nearest end bin.
+///
+/// Bins will be of the form [lower, upper) or (lower, upper] and are constructed
+/// from `edges` and `inclusive_left`.
+/// Data outside of the bin range is assigned to the first or last bin.
+///
+/// # Arguments
+/// * `data` - Data to be binned.
+/// * `edges` - Values representing the edges of bins.
+/// * `inclusive_left` - Whether or not the left edge of the bin is inclusive, i.e. the bins are of the form [lower, upper).
+///
+/// # Return
+/// Index of appropriate bin, if the datum is comparable and there is at least one bin.
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::components::digitize::clamped_bin_index;
+///
+/// let edges = vec![0., 1., 2.];
+/// assert_eq!(clamped_bin_index(&-1., &edges, true), Some(0));
+/// assert_eq!(clamped_bin_index(&2., &edges, true), Some(1));
+/// assert_eq!(clamped_bin_index(&f64::NAN, &edges, true), None);
+/// ```
+pub fn clamped_bin_index<T: PartialOrd + Clone>(
+    datum: &T,
+    edges: &[T],
+    inclusive_left: bool,
+) -> Option<usize> {
+    if edges.len() < 2 { return None }
+
+    match datum.partial_cmp(&edges[0])? {
+        Ordering::Less => return Some(0),
+        Ordering::Equal if !inclusive_left => return Some(0),
+        _ => ()
+    }
+    match datum.partial_cmp(&edges[edges.len() - 1])? {
+        Ordering::Greater => return Some(edges.len() - 2),
+        Ordering::Equal if inclusive_left => return Some(edges.len() - 2),
+        _ => ()
+    }
+    bin_index(datum, edges, inclusive_left)
+}
+
 #[cfg(test)]
 mod test_bin_index {
-    use crate::components::digitize::bin_index;
+    use crate::components::digitize::{bin_index, clamped_bin_index};
 
     #[test]
     fn test_edges() {
@@ -185,4 +237,23 @@ mod test_bin_index {
             .for_each(|(datum, truth)|
                 assert_eq!(bin_index(datum, &edges, false), *truth));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_clamped_edges() {
+        let data = [-1., 0., 1.1, 2., 2.9, 4.1, 5., 6.4, f64::NAN];
+        let edges = vec![0., 1., 2., 3., 4., 5.];
+
+        // values on the outer edges and beyond both ends are assigned to the end bins
+        data.iter()
+            .zip([Some(0), Some(0), Some(1), Some(2), Some(2), Some(4), Some(4), Some(4), None].iter())
+            .for_each(|(datum, truth)| assert_eq!(clamped_bin_index(datum, &edges, true), *truth));
+
+        data.iter()
+            .zip([Some(0), Some(0), Some(1), Some(1), Some(2), Some(4), Some(4), Some(4), None].iter())
+            .for_each(|(datum, truth)| assert_eq!(clamped_bin_index(datum, &edges, false), *truth));
+
+        // NaN is never binned
+        assert_eq!(bin_index(&f64::NAN, &edges, true), None);
+        assert_eq!(clamped_bin_index(&0.5, &[0.], true), None);
+    }
+}
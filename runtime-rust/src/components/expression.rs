@@ -0,0 +1,49 @@
+use ndarray::{arr0, ArrayD};
+use smartnoise_validator::{proto, Float};
+use smartnoise_validator::base::{IndexKey, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::expression::Expression;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::broadcast_map;
+
+impl Evaluable for proto::Expression {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, arguments: NodeArguments) -> Result<ReleaseNode> {
+        let expression = Expression::parse(&self.expression)?;
+
+        expression.evaluate(
+            &|value| Ok(arr0(value).into_dyn()),
+            &|name| arguments.get::<IndexKey>(&name.into())
+                .ok_or_else(|| Error::from(format!("{}: missing", name)))?
+                .ref_array()?.clone().cast_float(),
+            &|value: ArrayD<Float>| Ok(value.mapv(|v| -v)),
+            &|operator, left, right| broadcast_map(left, right, &|l, r| operator.apply(*l, *r)))
+            .map(|value| ReleaseNode::new(value.into()))
+    }
+}
+
+#[cfg(test)]
+mod test_expression {
+    use ndarray::{arr0, arr2};
+
+    use smartnoise_validator::base::{IndexKey, Value};
+    use smartnoise_validator::proto;
+
+    use crate::components::Evaluable;
+    use crate::NodeArguments;
+
+    #[test]
+    fn test_evaluate() {
+        let arguments: NodeArguments = indexmap::indexmap![
+            IndexKey::from("a") => Value::from(arr2(&[[1., 4.], [3., 6.]]).into_dyn()),
+            IndexKey::from("b") => Value::from(arr0(2 as smartnoise_validator::Integer).into_dyn()),
+            IndexKey::from("c") => Value::from(arr2(&[[0.5, 2.]]).into_dyn())
+        ];
+        let result = proto::Expression { expression: "(a - b) / c + -1".to_string() }
+            .evaluate(&None, arguments).unwrap();
+
+        assert_eq!(result.value.array().unwrap().float().unwrap(),
+                   arr2(&[[-3., 0.], [1., 1.]]).into_dyn());
+    }
+}
@@ -20,6 +20,7 @@ impl Evaluable for proto::Filter {
         Ok(ReleaseNode::new(match take_argument(&mut arguments, "data")?.array()? {
             Array::Str(data) => filter(data, mask)?.into(),
             Array::Float(data) => filter(data, mask)?.into(),
+            Array::F32(data) => filter(data, mask)?.into(),
             Array::Int(data) => filter(data, mask)?.into(),
             Array::Bool(data) => filter(data, mask)?.into(),
         }))
@@ -49,9 +50,32 @@ pub fn filter<T: Clone + Default>(data: ArrayD<T>, mask: ArrayD<bool>) -> Result
 
     let columnar_mask: Array1<bool> = to_nd(mask, 1)?.into_dimensionality::<Ix1>()?;
 
+    if data.ndim() == 0 || columnar_mask.len() != data.len_of(Axis(0)) {
+        return Err("mask: must have the same number of rows as data".into())
+    }
+
     let mask_indices: Vec<usize> = columnar_mask.iter().enumerate()
         .filter(|(_index, &v)| v)
         .map(|(index, _)| index)
         .collect();
     Ok(slow_select(&data, Axis(0), &mask_indices))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_filter {
+    use ndarray::{arr1, arr2};
+
+    use crate::components::filter::filter;
+
+    #[test]
+    fn test_mask_shape() {
+        let data = arr2(&[[1, 2], [3, 4], [5, 6], [7, 8]]).into_dyn();
+
+        // a one-column mask is equivalent to a vector mask
+        let mask = arr2(&[[false], [true], [false], [true]]).into_dyn();
+        assert_eq!(filter(data.clone(), mask).unwrap(), arr2(&[[3, 4], [7, 8]]).into_dyn());
+
+        // the mask must cover every row
+        assert!(filter(data, arr1(&[true, false]).into_dyn()).is_err());
+    }
+}
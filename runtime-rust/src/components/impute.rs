@@ -12,6 +12,7 @@ use crate::NodeArguments;
 use crate::utilities;
 use crate::utilities::get_num_columns;
 use crate::utilities::noise;
+use crate::utilities::nulls::Nullable;
 
 impl Evaluable for proto::Impute {
     fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
@@ -138,7 +139,7 @@ pub fn impute_float_uniform<'a, I: Iterator<Item=&'a mut Float>>(
 ) -> Result<()> {
     column
         // ignore nan values
-        .filter(|v| v.is_nan())
+        .filter(|v| v.is_null())
         // mutate the cell via the operator
         .try_for_each(|v| noise::sample_uniform(
             lower as f64, upper as f64, enforce_constant_time)
@@ -204,7 +205,7 @@ pub fn impute_float_gaussian<'a, I: Iterator<Item=&'a mut Float>>(
 ) -> Result<()> {
     column
         // ignore nan values
-        .filter(|v| v.is_nan())
+        .filter(|v| v.is_null())
         // mutate the cell via the operator
         .try_for_each(|v| noise::sample_gaussian_truncated(
             *min as f64, *max as f64, *shift as f64, *scale as f64,
@@ -212,6 +213,106 @@ pub fn impute_float_gaussian<'a, I: Iterator<Item=&'a mut Float>>(
             .map(|n| *v = n as Float))
 }
 
+/// Returns data with imputed values in place of `f64::NAN`.
+/// Values are imputed from an exponential distribution starting at `lower`, truncated at `upper`.
+///
+/// # Arguments
+/// * `data` - Data for which you would like to impute the `NAN` values.
+/// * `lower` - Lower bound on imputation range for each column.
+/// * `upper` - Upper bound on imputation range for each column.
+/// * `scale` - The mean of the untruncated exponential distribution for each column.
+///
+/// # Return
+/// Data with `NAN` values replaced with imputed values.
+///
+/// # Example
+/// ```
+/// use ndarray::{ArrayD, arr1};
+/// use smartnoise_runtime::components::impute::impute_float_exponential_arrayd;
+/// let data: ArrayD<f64> = arr1(&[1., f64::NAN, 3., f64::NAN]).into_dyn();
+/// let lower = arr1(&[0.0]).into_dyn();
+/// let upper = arr1(&[10.0]).into_dyn();
+/// let scale = arr1(&[2.0]).into_dyn();
+/// let imputed = impute_float_exponential_arrayd(data, lower, upper, scale, false).unwrap();
+/// assert!(imputed.iter().all(|v| 0. <= *v && *v <= 10.));
+/// ```
+pub fn impute_float_exponential_arrayd(
+    mut data: ArrayD<Float>,
+    lower: ArrayD<Float>, upper: ArrayD<Float>,
+    scale: ArrayD<Float>,
+    enforce_constant_time: bool
+) -> Result<ArrayD<Float>> {
+
+    let num_columns = get_num_columns(&data)?;
+
+    // iterate over the generalized columns
+    data.gencolumns_mut().into_iter()
+        // pair generalized columns with arguments
+        .zip(standardize_numeric_argument(lower, num_columns)?.iter()
+            .zip(standardize_numeric_argument(upper, num_columns)?.iter()))
+        .zip(standardize_numeric_argument(scale, num_columns)?.iter())
+        // for each pairing, iterate over the cells
+        .try_for_each(|((mut column, (min, max)), scale)| column.iter_mut()
+            // ignore non-nan values
+            .filter(|v| v.is_null())
+            // mutate the cell via the operator
+            .try_for_each(|v| noise::sample_exponential_truncated(
+                *min, *max, *scale, enforce_constant_time)
+                .map(|n| *v = n)))?;
+
+    Ok(data)
+}
+
+/// Returns data with imputed values in place of `f64::NAN`.
+/// Values are imputed from a triangular distribution between `lower` and `upper`.
+///
+/// # Arguments
+/// * `data` - Data for which you would like to impute the `NAN` values.
+/// * `lower` - Lower bound on imputation range for each column.
+/// * `upper` - Upper bound on imputation range for each column.
+/// * `mode` - The peak of the triangular distribution for each column.
+///
+/// # Return
+/// Data with `NAN` values replaced with imputed values.
+///
+/// # Example
+/// ```
+/// use ndarray::{ArrayD, arr1};
+/// use smartnoise_runtime::components::impute::impute_float_triangular_arrayd;
+/// let data: ArrayD<f64> = arr1(&[1., f64::NAN, 3., f64::NAN]).into_dyn();
+/// let lower = arr1(&[0.0]).into_dyn();
+/// let upper = arr1(&[10.0]).into_dyn();
+/// let mode = arr1(&[4.0]).into_dyn();
+/// let imputed = impute_float_triangular_arrayd(data, lower, upper, mode, false).unwrap();
+/// assert!(imputed.iter().all(|v| 0. <= *v && *v <= 10.));
+/// ```
+pub fn impute_float_triangular_arrayd(
+    mut data: ArrayD<Float>,
+    lower: ArrayD<Float>, upper: ArrayD<Float>,
+    mode: ArrayD<Float>,
+    enforce_constant_time: bool
+) -> Result<ArrayD<Float>> {
+
+    let num_columns = get_num_columns(&data)?;
+
+    // iterate over the generalized columns
+    data.gencolumns_mut().into_iter()
+        // pair generalized columns with arguments
+        .zip(standardize_numeric_argument(lower, num_columns)?.iter()
+            .zip(standardize_numeric_argument(upper, num_columns)?.iter()))
+        .zip(standardize_numeric_argument(mode, num_columns)?.iter())
+        // for each pairing, iterate over the cells
+        .try_for_each(|((mut column, (min, max)), mode)| column.iter_mut()
+            // ignore non-nan values
+            .filter(|v| v.is_null())
+            // mutate the cell via the operator
+            .try_for_each(|v| noise::sample_triangular(
+                *min, *max, *mode, enforce_constant_time)
+                .map(|n| *v = n)))?;
+
+    Ok(data)
+}
+
 /// Returns data with imputed values in place on `null_value`.
 ///
 /// # Arguments
@@ -246,7 +347,7 @@ pub fn impute_categorical_arrayd<T: Clone>(
     mut data: ArrayD<T>, categories: Vec<Vec<T>>,
     weights: Option<Vec<Vec<Float>>>, null_value: Vec<Vec<T>>,
     enforce_constant_time: bool
-) -> Result<ArrayD<T>> where T: Clone + PartialEq + Default + Ord + Hash {
+) -> Result<ArrayD<T>> where T: Clone + PartialEq + Default + Ord + Hash + Nullable {
 
     let num_columns = get_num_columns(&data)?;
 
@@ -274,10 +375,10 @@ fn impute_categorical<'a, T: 'a, I: Iterator<Item=&'a mut T>>(
     categories: &Vec<T>, probabilities: &Vec<Float>, null_values: &Vec<T>,
     enforce_constant_time: bool
 ) -> Result<()>
-    where T: Clone + PartialEq + Default + Ord + Hash {
+    where T: Clone + PartialEq + Default + Ord + Hash + Nullable {
     column
         // ignore non null values
-        .filter(|v| null_values.contains(v))
+        .filter(|v| v.is_null_in(null_values))
         // mutate the cell via the operator
         .try_for_each(|v| utilities::sample_from_set(
             &categories, &probabilities, enforce_constant_time)
@@ -7,8 +7,8 @@ use smartnoise_validator::proto;
 use smartnoise_validator::utilities::array::{slow_stack, slow_select};
 use ndarray::prelude::*;
 
-use smartnoise_validator::components::index::{to_name_vec};
-use smartnoise_validator::utilities::take_argument;
+use smartnoise_validator::components::index::{assert_columns_exist, to_index_keys, to_name_vec};
+use smartnoise_validator::utilities::{prepend, take_argument};
 use crate::utilities::to_nd;
 use indexmap::map::IndexMap;
 
@@ -24,17 +24,17 @@ impl Evaluable for proto::Index {
         let mut indexed = match data {
             // if value is an indexmap, we'll be stacking arrays column-wise
             Value::Dataframe(indexmap) | Value::Partitions(indexmap) => {
-                let column_names = if let Ok(names) = take_argument(&mut arguments, "names") {
+                let column_names = if let Some(Value::Dataframe(names)) = arguments.get::<IndexKey>(&"names".into()) {
+                    // composite keys with mixed types are passed as a dataframe
+                    if !is_partition {
+                        return Err("names: a dataframe of names may only index into partitions".into())
+                    }
+                    dimensionality = 2;
+                    vec![IndexKey::from_value(Value::Dataframe(names.clone()))?]
+
+                } else if let Ok(names) = take_argument(&mut arguments, "names") {
                     dimensionality = names.ref_array()?.shape().len() + 1;
-                    let mut indices = match names.array()? {
-                        Array::Str(names) => to_name_vec(names)?
-                            .into_iter().map(IndexKey::from).collect(),
-                        Array::Int(names) => to_name_vec(names)?
-                            .into_iter().map(IndexKey::from).collect(),
-                        Array::Bool(names) => to_name_vec(names)?
-                            .into_iter().map(IndexKey::from).collect(),
-                        Array::Float(_) => return Err("cannot index by floats".into()),
-                    };
+                    let mut indices = to_index_keys(names.array()?).map_err(prepend("names:"))?;
                     if is_partition && dimensionality == 2 {
                         indices = vec![IndexKey::Tuple(indices)]
                     }
@@ -57,6 +57,12 @@ impl Evaluable for proto::Index {
                     return Err("names, indices, or mask must be supplied when indexing on partitions or dataframes".into())
                 };
 
+                if !is_partition {
+                    assert_columns_exist(&indexmap, &column_names)?;
+                }
+                if self.as_dataframe {
+                    return Ok(ReleaseNode::new(Value::Dataframe(select_columns(indexmap, &column_names))))
+                }
                 column_stack(indexmap, &column_names)
             }
 
@@ -79,6 +85,7 @@ impl Evaluable for proto::Index {
                 Ok(match array {
                     Array::Int(data) => data.select(Axis(1), &indices).into(),
                     Array::Float(data) => data.select(Axis(1), &indices).into(),
+                    Array::F32(data) => data.select(Axis(1), &indices).into(),
                     Array::Bool(data) => data.select(Axis(1), &indices).into(),
                     Array::Str(data) => slow_select(&data, Axis(1), &indices).into(),
                 })
@@ -92,6 +99,7 @@ impl Evaluable for proto::Index {
             if !is_partition && dimensionality == 1 && array.shape().len() == 2 {
                 match array {
                     Array::Float(array) => array.index_axis_inplace(Axis(1), 0),
+                    Array::F32(array) => array.index_axis_inplace(Axis(1), 0),
                     Array::Int(array) => array.index_axis_inplace(Axis(1), 0),
                     Array::Bool(array) => array.index_axis_inplace(Axis(1), 0),
                     Array::Str(array) => array.index_axis_inplace(Axis(1), 0),
@@ -113,6 +121,16 @@ pub fn mask_columns(column_names: &[IndexKey], mask: &[bool]) -> Result<Vec<Inde
         .collect::<Vec<IndexKey>>())
 }
 
+/// Move the named columns out of the dataframe, into a new dataframe.
+fn select_columns(
+    mut dataframe: IndexMap<IndexKey, Value>,
+    column_names: &[IndexKey],
+) -> IndexMap<IndexKey, Value> {
+    column_names.iter()
+        .filter_map(|name| dataframe.remove(name).map(|column| (name.clone(), column)))
+        .collect()
+}
+
 fn column_stack(
     mut dataframe: IndexMap<IndexKey, Value>,
     column_names: &[IndexKey],
@@ -133,7 +151,12 @@ fn column_stack(
         .collect::<Result<Vec<&Value>>>()?;
 
     let data_type = match values.first() {
+        // single precision is only preserved if every column is single precision
         Some(value) => match value.ref_array()? {
+            Array::F32(_) => if values.iter()
+                .all(|value| matches!(value, Value::Array(Array::F32(_)))) {
+                DataType::F32
+            } else { DataType::Float },
             Array::Float(_) => DataType::Float,
             Array::Int(_) => DataType::Int,
             Array::Bool(_) => DataType::Bool,
@@ -143,7 +166,7 @@ fn column_stack(
     };
 
     match data_type {
-        DataType::Unknown => unreachable!(),
+        DataType::Unknown | DataType::DateTime => unreachable!(),
         DataType::Float => {
             let chunks = column_names.iter()
                 .map(|column_name| dataframe.remove(column_name)
@@ -154,6 +177,16 @@ fn column_stack(
             Ok(ndarray::stack(Axis(1), &chunks.iter()
                 .map(|chunk| chunk.view()).collect::<Vec<ArrayViewD<_>>>())?.into())
         }
+        DataType::F32 => {
+            let chunks = column_names.iter()
+                .map(|column_name| dataframe.remove(column_name)
+                    .ok_or_else(|| Error::from(format!("one of the provided column names does not exist: {:?}", column_name)))
+                    .and_then(|array| to_2d(array.array()?.f32()?)))
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(ndarray::stack(Axis(1), &chunks.iter()
+                .map(|chunk| chunk.view()).collect::<Vec<ArrayViewD<_>>>())?.into())
+        }
         DataType::Int => {
             let chunks = column_names.iter()
                 .map(|column_name| dataframe.remove(column_name)
@@ -186,3 +219,44 @@ fn column_stack(
         }
     }
 }
+
+#[cfg(test)]
+mod test_index {
+    use indexmap::{IndexMap, indexmap};
+    use ndarray::{arr1, Array1};
+
+    use smartnoise_validator::base::{IndexKey, ReleaseNode, Value};
+    use smartnoise_validator::errors::*;
+    use smartnoise_validator::proto;
+
+    use crate::components::Evaluable;
+
+    fn select(names: &[&str], as_dataframe: bool) -> Result<ReleaseNode> {
+        let dataframe: IndexMap<IndexKey, Value> = indexmap![
+            "a".into() => arr1(&[1, 2, 3]).into_dyn().into(),
+            "b".into() => arr1(&[4, 5, 6]).into_dyn().into(),
+            "c".into() => arr1(&["x", "y", "z"]).mapv(String::from).into_dyn().into()];
+        let names = Array1::from(names.iter().map(|v| v.to_string()).collect::<Vec<String>>());
+        proto::Index { as_dataframe }.evaluate(&None, indexmap![
+            "data".into() => Value::Dataframe(dataframe),
+            "names".into() => names.into_dyn().into()])
+    }
+
+    #[test]
+    fn test_select() {
+        let stacked = select(&["b", "a"], false).unwrap().value.array().unwrap().int().unwrap();
+        assert_eq!(stacked.shape(), &[3, 2]);
+        assert_eq!(stacked[[0, 0]], 4);
+
+        match select(&["c", "a"], true).unwrap().value {
+            Value::Dataframe(dataframe) => assert_eq!(
+                dataframe.keys().cloned().collect::<Vec<IndexKey>>(),
+                vec![IndexKey::from("c"), IndexKey::from("a")]),
+            _ => panic!("selection must be a dataframe")
+        }
+
+        let error = select(&["a", "d"], true).unwrap_err().to_string();
+        assert!(error.contains("Available columns are: \"a\", \"b\", \"c\""));
+        assert!(select(&[], false).is_err());
+    }
+}
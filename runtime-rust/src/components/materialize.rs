@@ -2,58 +2,648 @@ use smartnoise_validator::errors::*;
 use smartnoise_validator::components::Named;
 
 use crate::NodeArguments;
-use smartnoise_validator::base::{Value, ReleaseNode, IndexKey};
+use smartnoise_validator::base::{Value, ReleaseNode, IndexKey, Array, DataType};
+use smartnoise_validator::components::materialize::{check_data_types, parse_data_types};
 use indexmap::IndexMap;
+use ndarray::ArrayD;
 use crate::components::Evaluable;
 
-use smartnoise_validator::{proto};
+use smartnoise_validator::{proto, Float, Integer};
 
 impl Evaluable for proto::Materialize {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, arguments: NodeArguments) -> Result<ReleaseNode> {
 
-        let column_names = self.get_names(
-            arguments.iter().map(|(k, v)| (k.clone(), v)).collect(),
-            IndexMap::new(), None)?;
+        // when column names are not supplied, they are read from the header
+        let column_names = if arguments.contains_key::<IndexKey>(&"column_names".into())
+            || arguments.contains_key::<IndexKey>(&"num_columns".into()) {
+            Some(self.get_names(
+                arguments.iter().map(|(k, v)| (k.clone(), v)).collect(),
+                IndexMap::new(), None)?)
+        } else { None };
 
-        // num columns is sufficient shared information to build the dataframes
-        let num_columns = column_names.len();
+        let data_types = arguments.get::<IndexKey>(&"data_types".into())
+            .map(parse_data_types).transpose()?;
 
-        let mut response = (0..num_columns)
-            .map(|_| Vec::new())
-            .collect::<Vec<Vec<String>>>();
+        // declared types take precedence over inferred types
+        let infer_types = data_types.is_none();
 
-        let mut reader = match csv::ReaderBuilder::new()
-            .has_headers(self.skip_row)
-            .from_path(self.file_path.clone()) {
-            Ok(reader) => reader,
+        if let Some(records) = arguments.get::<IndexKey>(&"records".into()) {
+            let records = records.ref_array()?.first_string()
+                .map_err(|e| Error::from(format!("records: {}", e)))?;
+            let dataframe = read_json_records(&records, column_names,
+                                              self.infer_types && infer_types, self.single_precision)?;
+            return Ok(ReleaseNode::new(Value::Dataframe(match data_types {
+                Some(data_types) => apply_data_types(dataframe, &data_types)?,
+                None => dataframe
+            })))
+        }
+
+        let file = match std::fs::File::open(&self.file_path) {
+            Ok(file) => file,
             Err(_) => return Err("the provided file path could not be found".into())
         };
 
-        // parse from csv into response
-        reader.deserialize().try_for_each(|result: std::result::Result<Vec<String>, _>| {
+        let dataframe = match self.file_format.to_lowercase().as_str() {
+            "csv" => {
+                let delimiter = match self.delimiter.as_bytes() {
+                    [] => b',',
+                    [delimiter] => *delimiter,
+                    _ => return Err("delimiter must be a single byte".into())
+                };
+                read_csv(file, self.skip_row, delimiter, column_names,
+                         self.infer_types && infer_types, self.single_precision)?
+            }
+            #[cfg(feature = "use-arrow")]
+            "parquet" => crate::utilities::arrow::read_parquet(file, column_names, data_types.as_deref())?,
+            #[cfg(feature = "use-arrow")]
+            "arrow" => crate::utilities::arrow::read_arrow_ipc(file, column_names, data_types.as_deref())?,
+            #[cfg(not(feature = "use-arrow"))]
+            "parquet" | "arrow" => return Err("the runtime must be built with the use-arrow feature to load parquet or arrow files".into()),
+            other => return Err(format!("file_format: unrecognized format {:?}", other).into())
+        };
+
+        Ok(ReleaseNode::new(Value::Dataframe(match data_types {
+            Some(data_types) => apply_data_types(dataframe, &data_types)?,
+            None => dataframe
+        })))
+    }
+}
 
-            // parse each record into the smartnoise internal format
-            match result {
-                Ok(record) => record.into_iter().enumerate()
-                    .filter(|(idx, _)| idx < &num_columns)
-                    .for_each(|(idx, value)| response[idx].push(value)),
-                Err(e) => return Err(format!("{:?}", e).into())
+/// Check that each column of a loaded dataframe has its declared atomic type.
+///
+/// Columns of strings are parsed into their declared type.
+/// Empty strings are parsed as NaN into float columns, and are otherwise an error.
+///
+/// # Arguments
+/// * `dataframe` - columns of the loaded data
+/// * `data_types` - declared atomic type of each column
+///
+/// # Return
+/// The dataframe with each column of its declared type, or an error naming the first column that does not match.
+///
+/// # Example
+/// ```
+/// use indexmap::indexmap;
+/// use ndarray::arr1;
+/// use smartnoise_validator::base::{Array, DataType, IndexKey, Value};
+/// use smartnoise_runtime::components::materialize::apply_data_types;
+///
+/// let dataframe = indexmap![
+///     IndexKey::from("age") => Value::Array(Array::Str(arr1(&["34".to_string(), "27".to_string()]).into_dyn())),
+///     IndexKey::from("name") => Value::Array(Array::Str(arr1(&["Lee".to_string(), "Doe".to_string()]).into_dyn()))
+/// ];
+/// let typed = apply_data_types(dataframe.clone(), &[DataType::Int, DataType::Str]).unwrap();
+/// assert_eq!(typed.get(&IndexKey::from("age")).unwrap().ref_array().unwrap().data_type(), DataType::Int);
+///
+/// // names cannot be parsed as integers
+/// let error = apply_data_types(dataframe, &[DataType::Int, DataType::Int]).unwrap_err();
+/// assert!(error.to_string().starts_with("column 1 (name)"));
+/// ```
+pub fn apply_data_types(
+    dataframe: IndexMap<IndexKey, Value>, data_types: &[DataType]
+) -> Result<IndexMap<IndexKey, Value>> {
+    check_data_types(&dataframe.keys().cloned().collect::<Vec<IndexKey>>(), data_types)?;
+
+    dataframe.into_iter().zip(data_types.iter()).enumerate()
+        .map(|(idx, ((name, column), data_type))| {
+            let column = match (column.array()?, data_type) {
+                (Array::Str(column), DataType::Str) => Array::Str(column),
+                (Array::Str(column), data_type) => parse_array(&column, data_type)
+                    .map_err(|e| Error::from(format!("column {} ({}): {}", idx, name.to_string(), e)))?,
+                (column, data_type) if &column.data_type() == data_type => column,
+                (Array::F32(column), DataType::Float) => Array::F32(column),
+                (column, data_type) => return Err(format!(
+                    "column {} ({}): declared as {:?}, but loaded as {:?}",
+                    idx, name.to_string(), data_type, column.data_type()).into())
             };
-            Ok::<_, Error>(())
+            Ok((name, Value::Array(column)))
+        })
+        .collect()
+}
+
+/// Parse a column of strings into an atomic type.
+fn parse_array(column: &ArrayD<String>, data_type: &DataType) -> Result<Array> {
+    let error = |row: usize, value: &str| Error::from(format!(
+        "value {:?} on row {} cannot be parsed as {:?}", value, row, data_type));
+
+    Ok(match data_type {
+        DataType::Bool => Array::Bool(column.iter().enumerate()
+            .map(|(row, v)| match v.trim().to_lowercase().as_str() {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(error(row, v))
+            })
+            .collect::<Result<Vec<bool>>>()
+            .map(|values| ndarray::Array::from(values).into_dyn())?),
+        DataType::Int => Array::Int(column.iter().enumerate()
+            .map(|(row, v)| v.trim().parse::<Integer>().map_err(|_| error(row, v)))
+            .collect::<Result<Vec<Integer>>>()
+            .map(|values| ndarray::Array::from(values).into_dyn())?),
+        DataType::Float => Array::Float(column.iter().enumerate()
+            .map(|(row, v)| match v.trim() {
+                "" => Ok(Float::NAN),
+                v => v.parse::<Float>().map_err(|_| error(row, v))
+            })
+            .collect::<Result<Vec<Float>>>()
+            .map(|values| ndarray::Array::from(values).into_dyn())?),
+        DataType::Str => Array::Str(column.clone()),
+        _ => return Err(format!("{:?} may not be declared as a data type", data_type).into())
+    })
+}
+
+/// Read a csv into a dataframe.
+///
+/// # Arguments
+/// * `reader` - source of the csv
+/// * `has_headers` - when set, the first line is a header
+/// * `delimiter` - byte that separates fields
+/// * `column_names` - names of the columns. If not set, names are read from the header, or are the column indices if there is no header
+/// * `infer_types` - when set, columns are loaded as bool, int, float or string. Otherwise, all columns are loaded as string
+/// * `single_precision` - when set, inferred float columns are loaded as single precision floats
+///
+/// # Return
+/// A dataframe with one column for each column name.
+pub fn read_csv<R: std::io::Read>(
+    reader: R, has_headers: bool, delimiter: u8,
+    column_names: Option<Vec<IndexKey>>, infer_types: bool, single_precision: bool
+) -> Result<IndexMap<IndexKey, Value>> {
+
+    // every record, including the header, must have the same number of fields
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(has_headers)
+        .delimiter(delimiter)
+        .from_reader(reader);
+
+    let header = if has_headers {
+        Some(reader.headers()
+            .map_err(|e| Error::from(format!("{:?}", e)))?
+            .iter().map(|name| IndexKey::from(name.to_string()))
+            .collect::<Vec<IndexKey>>())
+    } else { None };
+
+    let mut records = reader.records();
+
+    // without names or a header, the number of columns is determined by the first record
+    let first_record = match (&column_names, &header) {
+        (None, None) => records.next().transpose()
+            .map_err(|e| Error::from(format!("{:?}", e)))?,
+        _ => None
+    };
+
+    let column_names = match (column_names, header, &first_record) {
+        (Some(column_names), _, _) => column_names,
+        (None, Some(header), _) => header,
+        (None, None, Some(record)) => (0..record.len() as Integer).map(IndexKey::from).collect(),
+        (None, None, None) => Vec::new()
+    };
+
+    // num columns is sufficient shared information to build the dataframes
+    let num_columns = column_names.len();
+
+    let mut response = (0..num_columns)
+        .map(|_| Vec::new())
+        .collect::<Vec<Vec<String>>>();
+
+    // name the offending column of a record with the wrong number of fields
+    let mismatch = |num_fields: usize, line: u64| match column_names.get(num_fields) {
+        Some(name) => Error::from(format!("column {} ({}) is missing on line {}, which has {} fields for {} columns",
+                                          num_fields, name.to_string(), line, num_fields, num_columns)),
+        None => Error::from(format!("column {} is not declared, but is present on line {}, which has {} fields for {} columns",
+                                    num_columns, line, num_fields, num_columns))
+    };
+
+    // parse from csv into response
+    first_record.into_iter().map(Ok).chain(records).try_for_each(|result| {
+
+        // parse each record into the smartnoise internal format
+        let record = result.map_err(|e| match e.kind() {
+            csv::ErrorKind::UnequalLengths { pos, expected_len, len } => {
+                let line = pos.as_ref().map(|pos| pos.line()).unwrap_or(0);
+                if *len as usize == num_columns {
+                    Error::from(format!("line {} has {} fields, but the first line has {} fields", line, len, expected_len))
+                } else { mismatch(*len as usize, line) }
+            }
+            _ => Error::from(format!("{:?}", e))
         })?;
 
-        let num_nonempty_columns = response.iter()
-            .filter(|col| !col.is_empty()).count();
+        if record.len() != num_columns {
+            return Err(mismatch(record.len(), record.position().map(|position| position.line()).unwrap_or(0)))
+        }
+
+        record.into_iter().enumerate()
+            .for_each(|(idx, value)| response[idx].push(value.to_string()));
+        Ok::<_, Error>(())
+    })?;
+
+    column_names.into_iter()
+        .zip(response).enumerate()
+        .map(|(idx, (key, column)): (usize, (IndexKey, Vec<String>))| Ok((key.clone(), Value::Array(if infer_types {
+            infer_array(column, single_precision)
+                .map_err(|e| Error::from(format!("column {} ({}): {}", idx, key.to_string(), e)))?
+        } else {
+            Array::Str(ndarray::Array::from(column).into_dyn())
+        }))))
+        .collect::<Result<IndexMap<IndexKey, Value>>>()
+}
+
+/// Read a JSON array of objects into a dataframe, where each object is a row.
+///
+/// # Arguments
+/// * `records` - JSON text of an array of objects
+/// * `column_names` - names of the columns. If not set, names are the keys of the objects, in sorted order
+/// * `infer_types` - when set, columns are loaded as bool, int, float or string. Otherwise, all columns are loaded as string
+/// * `single_precision` - when set, inferred float columns are loaded as single precision floats
+///
+/// # Return
+/// A dataframe with one column for each column name.
+/// A key that is missing from an object, or is null, is a missing value.
+/// Missing values are loaded as NaN in float columns and as empty strings in string columns.
+/// Inferred bool and int columns cannot represent missing values, so they are an error.
+pub fn read_json_records(
+    records: &str, column_names: Option<Vec<IndexKey>>, infer_types: bool, single_precision: bool
+) -> Result<IndexMap<IndexKey, Value>> {
+    let records: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_str(records)
+        .map_err(|e| Error::from(format!("records: must be a JSON array of objects: {}", e)))?;
+
+    let column_names = match column_names {
+        Some(column_names) => column_names,
+        None => records.iter().flat_map(|record| record.keys())
+            .collect::<std::collections::BTreeSet<&String>>().into_iter()
+            .map(|key| IndexKey::from(key.to_string())).collect()
+    };
+    let keys = column_names.iter()
+        .map(|name| match name {
+            IndexKey::Str(name) => Ok(name.clone()),
+            name => Err(format!("column {}: names of JSON columns must be strings", name.to_string()).into())
+        })
+        .collect::<Result<Vec<String>>>()?;
+
+    for (row, record) in records.iter().enumerate() {
+        if let Some(key) = record.keys().find(|key| !keys.contains(key)) {
+            return Err(format!("column {:?} is not declared, but is present in record {}", key, row).into())
+        }
+    }
+
+    column_names.into_iter().zip(keys.iter()).enumerate()
+        .map(|(idx, (name, key))| {
+            let column = records.iter()
+                .map(|record| record.get(key).filter(|value| !value.is_null()))
+                .collect::<Vec<Option<&serde_json::Value>>>();
+            let column = if infer_types {
+                infer_json_array(&column, single_precision)
+                    .map_err(|e| Error::from(format!("column {} ({}): {}", idx, key, e)))?
+            } else { None }
+                .map(Ok)
+                .unwrap_or_else(|| column.iter().enumerate()
+                    .map(|(row, value)| json_to_string(*value)
+                        .map_err(|e| Error::from(format!("column {} ({}), record {}: {}", idx, key, row, e))))
+                    .collect::<Result<Vec<String>>>()
+                    .map(|column| Array::Str(ndarray::Array::from(column).into_dyn())))?;
+            Ok((name, Value::Array(column)))
+        })
+        .collect()
+}
+
+/// Load a column of JSON values as the narrowest atomic type that represents every value, if any.
+fn infer_json_array(column: &[Option<&serde_json::Value>], single_precision: bool) -> Result<Option<Array>> {
+    let present = column.iter().filter_map(|v| *v).collect::<Vec<&serde_json::Value>>();
+    if present.is_empty() {
+        return Ok(None)
+    }
+    let num_missing = column.len() - present.len();
+
+    if present.iter().all(|v| v.is_boolean()) {
+        check_missing(num_missing, DataType::Bool)?;
+        return Ok(Some(Array::Bool(ndarray::Array::from(present.iter()
+            .map(|v| v.as_bool().unwrap())
+            .collect::<Vec<bool>>()).into_dyn())))
+    }
+
+    if present.iter().all(|v| v.is_i64()) {
+        check_missing(num_missing, DataType::Int)?;
+        return Ok(Some(Array::Int(ndarray::Array::from(present.iter()
+            .map(|v| v.as_i64().unwrap() as Integer)
+            .collect::<Vec<Integer>>()).into_dyn())))
+    }
+
+    if present.iter().all(|v| v.is_number()) {
+        let values = column.iter()
+            .map(|v| v.and_then(serde_json::Value::as_f64).unwrap_or(Float::NAN));
+        return Ok(Some(if single_precision {
+            Array::F32(ndarray::Array::from(values.map(|v| v as f32).collect::<Vec<f32>>()).into_dyn())
+        } else {
+            Array::Float(ndarray::Array::from(values.collect::<Vec<Float>>()).into_dyn())
+        }))
+    }
+
+    Ok(None)
+}
+
+/// Bool and int columns cannot represent missing values.
+///
+/// Rather than widening the column to another type, the type must be declared.
+fn check_missing(num_missing: usize, data_type: DataType) -> Result<()> {
+    if num_missing > 0 {
+        return Err(format!("{} missing values cannot be represented in a column inferred as {:?}. Declare the type of the column with data_types",
+                           num_missing, data_type).into())
+    }
+    Ok(())
+}
+
+/// Render a JSON value as the text of a csv field. Missing values are empty strings.
+fn json_to_string(value: Option<&serde_json::Value>) -> Result<String> {
+    Ok(match value {
+        None => String::new(),
+        Some(serde_json::Value::String(value)) => value.clone(),
+        Some(serde_json::Value::Bool(value)) => value.to_string(),
+        Some(serde_json::Value::Number(value)) => value.to_string(),
+        Some(_) => return Err("nested arrays and objects may not be loaded".into())
+    })
+}
+
+/// Load a column of strings as the narrowest atomic type that represents every value.
+///
+/// Missing values are empty strings, and are loaded as NaN in float columns.
+/// Bool and int columns cannot represent missing values, so they are an error.
+fn infer_array(column: Vec<String>, single_precision: bool) -> Result<Array> {
+    let values = column.iter().map(|v| v.trim()).collect::<Vec<&str>>();
+    let present = values.iter().filter(|v| !v.is_empty()).collect::<Vec<_>>();
+
+    if present.is_empty() {
+        return Ok(Array::Str(ndarray::Array::from(column).into_dyn()))
+    }
+    let num_missing = values.len() - present.len();
+
+    let parse_bool = |v: &str| match v.to_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None
+    };
+
+    if present.iter().all(|v| parse_bool(v).is_some()) {
+        check_missing(num_missing, DataType::Bool)?;
+        return Ok(Array::Bool(ndarray::Array::from(values.iter()
+            .map(|v| parse_bool(v).unwrap())
+            .collect::<Vec<bool>>()).into_dyn()))
+    }
+
+    if present.iter().all(|v| v.parse::<Integer>().is_ok()) {
+        check_missing(num_missing, DataType::Int)?;
+        return Ok(Array::Int(ndarray::Array::from(values.iter()
+            .map(|v| v.parse::<Integer>().unwrap())
+            .collect::<Vec<Integer>>()).into_dyn()))
+    }
+
+    if single_precision && present.iter().all(|v| v.parse::<f32>().is_ok()) {
+        return Ok(Array::F32(ndarray::Array::from(values.iter()
+            .map(|v| v.parse::<f32>().unwrap_or(f32::NAN))
+            .collect::<Vec<f32>>()).into_dyn()))
+    }
+
+    if present.iter().all(|v| v.parse::<Float>().is_ok()) {
+        return Ok(Array::Float(ndarray::Array::from(values.iter()
+            .map(|v| v.parse::<Float>().unwrap_or(Float::NAN))
+            .collect::<Vec<Float>>()).into_dyn()))
+    }
+
+    Ok(Array::Str(ndarray::Array::from(column).into_dyn()))
+}
+
+#[cfg(test)]
+mod test_materialize {
+    use smartnoise_validator::base::{Array, DataType, IndexKey, Value};
+    use smartnoise_validator::proto;
+
+    use crate::components::Evaluable;
+    use crate::components::materialize::{apply_data_types, read_csv, read_json_records};
+
+    const CSV: &str = "name;age;height;member\n\
+        \"Smith; Jane\";34;1.62;true\n\
+        Lee;27;;False\n\
+        \"Doe, \"\"JD\"\"\";51;1.80;TRUE\n";
+
+    fn column<'a>(dataframe: &'a indexmap::IndexMap<IndexKey, Value>, name: &str) -> &'a Array {
+        dataframe.get::<IndexKey>(&name.into()).unwrap().ref_array().unwrap()
+    }
+
+    #[test]
+    fn test_read_csv_inferred() {
+        let dataframe = read_csv(CSV.as_bytes(), true, b';', None, true, false).unwrap();
+
+        assert_eq!(dataframe.len(), 4);
+        assert_eq!(dataframe.keys().cloned().collect::<Vec<_>>(),
+                   vec![IndexKey::from("name"), "age".into(), "height".into(), "member".into()]);
+
+        match column(&dataframe, "name") {
+            Array::Str(names) => assert_eq!(names.iter().cloned().collect::<Vec<_>>(),
+                                            vec!["Smith; Jane", "Lee", "Doe, \"JD\""]),
+            _ => panic!("name must be inferred as string")
+        }
+        assert!(matches!(column(&dataframe, "age"), Array::Int(_)));
+        match column(&dataframe, "height") {
+            Array::Float(heights) => assert!(heights[1].is_nan()),
+            _ => panic!("height must be inferred as float")
+        }
+        match column(&dataframe, "member") {
+            Array::Bool(members) => assert_eq!(members.iter().cloned().collect::<Vec<_>>(), vec![true, false, true]),
+            _ => panic!("member must be inferred as bool")
+        }
+    }
+
+    #[test]
+    fn test_read_csv_inferred_missing() {
+        // integers with missing values are not widened to float
+        let error = read_csv("a,b\n1,2\n,4\n".as_bytes(), true, b',', None, true, false).unwrap_err();
+        assert!(error.to_string().starts_with("column 0 (a): 1 missing values cannot be represented in a column inferred as Int"), "{}", error);
+        let error = read_csv("a,b\ntrue,1\n,2\n".as_bytes(), true, b',', None, true, false).unwrap_err();
+        assert!(error.to_string().contains("inferred as Bool"), "{}", error);
+
+        // the type may be declared instead
+        let dataframe = read_csv("a,b\n1,2\n,4\n".as_bytes(), true, b',', None, false, false).unwrap();
+        let dataframe = apply_data_types(dataframe, &[DataType::Float, DataType::Int]).unwrap();
+        match column(&dataframe, "a") {
+            Array::Float(values) => assert!(values[0] == 1. && values[1].is_nan()),
+            _ => panic!("a must be declared as float")
+        }
+    }
+
+    #[test]
+    fn test_read_csv_names() {
+        // supplied names take precedence over the header, and all columns are loaded as strings
+        let names = vec![IndexKey::from("a"), "b".into(), "c".into(), "d".into()];
+        let dataframe = read_csv(CSV.as_bytes(), true, b';', Some(names.clone()), false, false).unwrap();
+        assert_eq!(dataframe.keys().cloned().collect::<Vec<_>>(), names);
+        assert!(matches!(column(&dataframe, "b"), Array::Str(_)));
+
+        // without names or a header, columns are named by index
+        let dataframe = read_csv("1,2\n3,4\n".as_bytes(), false, b',', None, true, false).unwrap();
+        assert_eq!(dataframe.keys().cloned().collect::<Vec<_>>(), vec![IndexKey::from(0), IndexKey::from(1)]);
+        match dataframe.get(&IndexKey::from(1)).unwrap().ref_array().unwrap() {
+            Array::Int(values) => assert_eq!(values.iter().cloned().collect::<Vec<_>>(), vec![2, 4]),
+            _ => panic!("column must be inferred as int")
+        }
+    }
+
+    #[test]
+    fn test_read_csv_single_precision() {
+        let dataframe = read_csv(CSV.as_bytes(), true, b';', None, true, true).unwrap();
+        match column(&dataframe, "height") {
+            Array::F32(heights) => assert!(heights[1].is_nan() && heights[2] == 1.80),
+            _ => panic!("height must be inferred as single precision float")
+        }
+        // only floats are affected
+        assert!(matches!(column(&dataframe, "age"), Array::Int(_)));
+    }
+
+    #[test]
+    fn test_read_csv_column_mismatch() {
+        // too few names for the columns in the file
+        let error = read_csv(CSV.as_bytes(), true, b';', Some(vec!["a".into(), "b".into()]), false, false).unwrap_err();
+        assert!(error.to_string().starts_with("column 2 is not declared, but is present on line 2"), "{}", error);
+
+        // too many names for the columns in the file
+        let names = vec![IndexKey::from("a"), "b".into(), "c".into(), "d".into(), "e".into()];
+        let error = read_csv(CSV.as_bytes(), true, b';', Some(names), false, false).unwrap_err();
+        assert!(error.to_string().starts_with("column 4 (e) is missing on line 2"), "{}", error);
+
+        // a record with a missing field
+        let error = read_csv("a,b\n1,2\n3\n".as_bytes(), true, b',', None, true, false).unwrap_err();
+        assert!(error.to_string().starts_with("column 1 (b) is missing on line 3"), "{}", error);
+
+        // a header with more fields than the records, even if the supplied names match the records
+        let error = read_csv("a,b,c\n1,2\n".as_bytes(), true, b',', Some(vec!["a".into(), "b".into()]), false, false).unwrap_err();
+        assert!(error.to_string().starts_with("line 2 has 2 fields, but the first line has 3 fields"), "{}", error);
+    }
+
+    #[test]
+    fn test_declared_data_types() {
+        let dataframe = read_csv(CSV.as_bytes(), true, b';', None, false, false).unwrap();
+        let dataframe = apply_data_types(dataframe, &[DataType::Str, DataType::Int, DataType::Float, DataType::Bool]).unwrap();
+        assert!(matches!(column(&dataframe, "age"), Array::Int(_)));
+        match column(&dataframe, "height") {
+            Array::Float(heights) => assert!(heights[1].is_nan()),
+            _ => panic!("height must be declared as float")
+        }
+        assert!(matches!(column(&dataframe, "member"), Array::Bool(_)));
+
+        // heights are not integers
+        let dataframe = read_csv(CSV.as_bytes(), true, b';', None, false, false).unwrap();
+        let error = apply_data_types(dataframe.clone(), &[DataType::Str, DataType::Int, DataType::Int, DataType::Bool]).unwrap_err();
+        assert!(error.to_string().starts_with("column 2 (height)"), "{}", error);
+
+        // a type must be declared for every column
+        let error = apply_data_types(dataframe, &[DataType::Str, DataType::Int, DataType::Float]).unwrap_err();
+        assert!(error.to_string().contains("column 3 (member)"), "{}", error);
+    }
+
+    const RECORDS: &str = r#"[
+        {"name": "Jane", "age": 34, "height": 1.62, "member": true},
+        {"name": "Lee", "age": 27, "member": false},
+        {"name": null, "age": 51, "height": 2, "member": true, "nickname": "JD"}
+    ]"#;
+
+    #[test]
+    fn test_read_json_records() {
+        let dataframe = read_json_records(RECORDS, None, true, false).unwrap();
+
+        // names are sorted
+        assert_eq!(dataframe.keys().cloned().collect::<Vec<_>>(),
+                   vec![IndexKey::from("age"), "height".into(), "member".into(), "name".into(), "nickname".into()]);
+        match column(&dataframe, "age") {
+            Array::Int(ages) => assert_eq!(ages.iter().cloned().collect::<Vec<_>>(), vec![34, 27, 51]),
+            _ => panic!("age must be inferred as int")
+        }
+        // integers and floats mix, and missing values are null
+        match column(&dataframe, "height") {
+            Array::Float(heights) => assert!(heights[0] == 1.62 && heights[1].is_nan() && heights[2] == 2.),
+            _ => panic!("height must be inferred as float")
+        }
+        assert!(matches!(column(&dataframe, "member"), Array::Bool(_)));
+        // strings with missing values are empty
+        match column(&dataframe, "nickname") {
+            Array::Str(nicknames) => assert_eq!(nicknames.iter().cloned().collect::<Vec<_>>(), vec!["", "", "JD"]),
+            _ => panic!("nickname must be inferred as string")
+        }
+        match column(&dataframe, "name") {
+            Array::Str(names) => assert_eq!(names.iter().cloned().collect::<Vec<_>>(), vec!["Jane", "Lee", ""]),
+            _ => panic!("name must be inferred as string")
+        }
+    }
+
+    #[test]
+    fn test_read_json_records_declared() {
+        // declared names select and order the columns
+        let names = vec![IndexKey::from("member"), "age".into(), "height".into(), "name".into(), "nickname".into()];
+        let dataframe = read_json_records(RECORDS, Some(names.clone()), false, false).unwrap();
+        assert_eq!(dataframe.keys().cloned().collect::<Vec<_>>(), names);
+        assert!(dataframe.values().all(|column| matches!(column.ref_array().unwrap(), Array::Str(_))));
+
+        let typed = apply_data_types(dataframe, &[
+            DataType::Bool, DataType::Int, DataType::Float, DataType::Str, DataType::Str]).unwrap();
+        match column(&typed, "height") {
+            Array::Float(heights) => assert!(heights[1].is_nan()),
+            _ => panic!("height must be parsed as float")
+        }
+
+        let error = read_json_records(RECORDS, Some(names[..4].to_vec()), true, false).unwrap_err();
+        assert!(error.to_string().starts_with("column \"nickname\" is not declared, but is present in record 2"), "{}", error);
+
+        // ages are integers, and one is missing
+        let error = read_json_records(r#"[{"age": 1}, {"age": null}]"#, None, true, false).unwrap_err();
+        assert!(error.to_string().starts_with("column 0 (age): 1 missing values"), "{}", error);
+
+        assert!(read_json_records(r#"[{"a": [1]}]"#, None, true, false).is_err());
+        assert!(read_json_records(r#"{"a": 1}"#, None, true, false).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_records() {
+        let records = Value::Array(Array::Str(ndarray::arr0(RECORDS.to_string()).into_dyn()));
+        let evaluate = |infer_types: bool| proto::Materialize { public: true, infer_types, ..Default::default() }
+            .evaluate(&None, indexmap::indexmap![IndexKey::from("records") => records.clone()])
+            .unwrap().value.dataframe().unwrap();
+
+        // records are loaded as strings unless types are inferred
+        assert!(matches!(column(&evaluate(false), "age"), Array::Str(_)));
+        assert!(matches!(column(&evaluate(true), "age"), Array::Int(_)));
+    }
+
+    #[cfg(feature = "use-arrow")]
+    #[test]
+    fn test_evaluate_parquet() {
+        use std::sync::Arc;
+        use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+
+        let path = std::env::temp_dir().join(format!("smartnoise_test_materialize_{}.parquet", std::process::id()));
+        let batch = RecordBatch::try_from_iter(vec![
+            ("age", Arc::new(Int32Array::from(vec![34, 27, 51])) as ArrayRef),
+            ("visits", Arc::new(Int32Array::from(vec![Some(1), None, Some(3)])) as ArrayRef),
+        ]).unwrap();
+        let mut writer = parquet::arrow::ArrowWriter::try_new(
+            std::fs::File::create(&path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let component = proto::Materialize {
+            public: true,
+            file_path: path.to_str().unwrap().to_string(),
+            file_format: "parquet".to_string(),
+            ..Default::default()
+        };
+        let data_types = Value::Array(Array::Str(ndarray::arr1(&["int".to_string(), "float".to_string()]).into_dyn()));
+        let typed = component.evaluate(&None, indexmap::indexmap![IndexKey::from("data_types") => data_types]);
+        let untyped = component.evaluate(&None, indexmap::IndexMap::new());
+        std::fs::remove_file(&path).unwrap();
 
-        if 0 < num_nonempty_columns && num_nonempty_columns < num_columns {
-            (num_nonempty_columns..num_columns).for_each(|idx|
-                response[idx] = (0..response[0].len()).map(|_| "".to_string()).collect::<Vec<String>>())
+        let dataframe = typed.unwrap().value.dataframe().unwrap();
+        assert!(matches!(column(&dataframe, "age"), Array::Int(_)));
+        match column(&dataframe, "visits") {
+            Array::Float(visits) => assert!(visits[1].is_nan() && visits[2] == 3.),
+            _ => panic!("visits must be loaded as float")
         }
 
-        Ok(ReleaseNode::new(Value::Dataframe(column_names.into_iter()
-            .zip(response.into_iter())
-            .map(|(key, value): (IndexKey, Vec<String>)|
-                (key, ndarray::Array::from(value).into_dyn().into()))
-            .collect::<IndexMap<IndexKey, Value>>())))
+        // without a declared type, an integer field with nulls is an error
+        let error = untyped.unwrap_err();
+        assert!(error.to_string().starts_with("visits: 1 nulls"), "{}", error);
     }
 }
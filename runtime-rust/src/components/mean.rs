@@ -1,18 +1,29 @@
 use smartnoise_validator::errors::*;
 
 use crate::NodeArguments;
-use smartnoise_validator::base::{ReleaseNode};
+use smartnoise_validator::base::{IndexKey, ReleaseNode};
 use smartnoise_validator::utilities::take_argument;
 use crate::components::Evaluable;
 use ndarray::{ArrayD, Array};
-use crate::utilities::get_num_columns;
+use crate::components::sum::weighted_sum;
+use crate::utilities::{check_overflow, get_num_columns};
+use crate::utilities::nulls::assert_non_null;
 use smartnoise_validator::{proto, Float};
 
 impl Evaluable for proto::Mean {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
-        Ok(ReleaseNode::new(mean(
-            &take_argument(&mut arguments, "data")?.array()?.float()?
-        )?.into()))
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        assert_non_null(&data, "Mean")?;
+        let protect_overflow = privacy_definition.as_ref()
+            .map(|privacy_definition| privacy_definition.protect_overflow)
+            .unwrap_or(false);
+        let means = match arguments.remove::<IndexKey>(&"weights".into()) {
+            Some(weights) => weighted_mean(&data, &weights.array()?.float()?)?,
+            // the running mean is slower, but never exceeds the bounds of the data
+            None => if protect_overflow { stable_mean(&data)? } else { mean(&data)? }
+        };
+        check_overflow(privacy_definition, &means, "Mean")?;
+        Ok(ReleaseNode::new(means.into()))
     }
 }
 
@@ -38,7 +49,46 @@ pub fn mean(data: &ArrayD<Float>) -> Result<ArrayD<Float>> {
         .map(|column| column.mean()).collect::<Option<Vec<Float>>>()
         .ok_or_else(|| Error::from("attempted mean of an empty column"))?;
 
-    // ensure means are of correct dimension
+    shape_means(data, means)
+}
+
+/// Calculates the arithmetic mean of each column in the provided data, without overflowing.
+///
+/// The mean is updated as a running average, where each update is a convex combination of the prior mean and the next value.
+/// Unlike a sum followed by a division, no intermediate value exceeds the bounds of the data.
+///
+/// # Arguments
+/// * `data` - Data for which you want the mean.
+///
+/// # Return
+/// Arithmetic mean(s) of the data in question.
+///
+/// # Example
+/// ```
+/// use ndarray::prelude::*;
+/// use smartnoise_runtime::components::mean::stable_mean;
+/// let data = arr2(&[ [f64::MAX, 10.], [f64::MAX, 20.] ]).into_dyn();
+/// let means = stable_mean(&data).unwrap();
+/// assert_eq!(means, arr2(&[[f64::MAX, 15.]]).into_dyn());
+/// ```
+pub fn stable_mean(data: &ArrayD<Float>) -> Result<ArrayD<Float>> {
+    let means = data.gencolumns().into_iter()
+        .map(|column| {
+            if column.is_empty() {
+                return Err(Error::from("attempted mean of an empty column"))
+            }
+            Ok(column.iter().enumerate().fold(0., |mean, (index, value)| {
+                let count = (index + 1) as Float;
+                mean * ((count - 1.) / count) + value / count
+            }))
+        })
+        .collect::<Result<Vec<Float>>>()?;
+
+    shape_means(data, means)
+}
+
+/// Package the means of each column into an aggregate of the correct dimension.
+fn shape_means(data: &ArrayD<Float>, means: Vec<Float>) -> Result<ArrayD<Float>> {
     let array = match data.ndim() {
         1 => Array::from_shape_vec(vec![], means),
         2 => Array::from_shape_vec(vec![1 as usize, get_num_columns(&data)? as usize], means),
@@ -51,15 +101,91 @@ pub fn mean(data: &ArrayD<Float>) -> Result<ArrayD<Float>> {
     }
 }
 
+/// Calculates the weighted arithmetic mean of each column in the provided data.
+///
+/// # Arguments
+/// * `data` - Data for which you want the weighted mean.
+/// * `weights` - One non-negative weight for each record.
+///
+/// # Return
+/// Weighted arithmetic mean(s) of the data in question.
+///
+/// # Example
+/// ```
+/// use ndarray::prelude::*;
+/// use smartnoise_runtime::components::mean::weighted_mean;
+/// let data = arr2(&[ [1.,10.], [2., 20.], [3., 30.] ]).into_dyn();
+/// let weights = arr1(&[1., 0., 3.]).into_dyn();
+/// let means = weighted_mean(&data, &weights).unwrap();
+/// assert_eq!(means, arr2(&[[2.5, 25.]]).into_dyn());
+/// ```
+pub fn weighted_mean(data: &ArrayD<Float>, weights: &ArrayD<Float>) -> Result<ArrayD<Float>> {
+    let total = weights.sum();
+    if total == 0. {
+        return Err("attempted weighted mean with weights summing to zero".into())
+    }
+    Ok(weighted_sum(data, weights)? / total)
+}
+
 
 #[cfg(test)]
 mod test_mean {
-    use ndarray::{arr2};
-    use crate::components::mean::mean;
+    use ndarray::{arr1, arr2};
+    use smartnoise_validator::{proto, Float};
+    use crate::components::mean::{mean, stable_mean, weighted_mean};
+    use crate::components::Evaluable;
+    use crate::components::sum::test_sum::{arguments_overflow, privacy_definition};
     #[test]
     fn test_mean() {
         let data = arr2(&[ [1.,10.], [2., 20.], [3., 30.] ]).into_dyn();
         let means = mean(&data).unwrap();
         assert!(means == arr2(&[[2., 20.]]).into_dyn());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_mean_overflow() {
+        // the running mean does not overflow when protected
+        let release = proto::Mean {}.evaluate(&privacy_definition(true), arguments_overflow()).unwrap();
+        assert_eq!(release.value.array().unwrap().float().unwrap(), arr2(&[[Float::MAX, 1.5]]).into_dyn());
+
+        // without protections, the intermediate sum overflows before division
+        let release = proto::Mean {}.evaluate(&privacy_definition(false), arguments_overflow()).unwrap();
+        assert!(release.value.array().unwrap().float().unwrap()[[0, 0]].is_infinite());
+    }
+
+    #[test]
+    fn test_stable_mean() {
+        // columns whose naive sums overflow
+        let data = arr2(&[
+            [Float::MAX, -Float::MAX, Float::MAX / 2.],
+            [Float::MAX / 2., -Float::MAX / 2., Float::MAX],
+            [Float::MAX / 4., Float::MAX, Float::MAX],
+            [Float::MAX / 4., -Float::MAX / 2., Float::MAX / 2.]]).into_dyn();
+        assert!(mean(&data).unwrap().iter().any(|v| !v.is_finite()));
+
+        // reference: the mean of the data scaled into range
+        let scale = 1e-10;
+        let reference = mean(&data.mapv(|v| v * scale)).unwrap().mapv(|v| v / scale);
+        let means = stable_mean(&data).unwrap();
+        means.iter().zip(reference.iter())
+            .for_each(|(actual, expected)| assert!(
+                (actual - expected).abs() <= expected.abs() * 1e-12,
+                "mean {} does not match reference {}", actual, expected));
+
+        // small data matches the naive mean
+        let data = arr2(&[ [1.,10.], [2., 20.], [3., 30.] ]).into_dyn();
+        assert_eq!(stable_mean(&data).unwrap(), mean(&data).unwrap());
+        assert!(stable_mean(&ndarray::Array::zeros((0, 1)).into_dyn()).is_err());
+    }
+
+    #[test]
+    fn test_weighted_mean() {
+        let data = arr2(&[ [1.,10.], [2., 20.], [3., 30.] ]).into_dyn();
+        let weights = arr1(&[0.5, 0.25, 0.25]).into_dyn();
+        // reference: the mean of the data with the first record repeated twice
+        let reference = mean(&arr2(&[ [1.,10.], [1.,10.], [2., 20.], [3., 30.] ]).into_dyn()).unwrap();
+        assert_eq!(weighted_mean(&data, &weights).unwrap(), reference);
+
+        assert!(weighted_mean(&data, &arr1(&[0., 0., 0.]).into_dyn()).is_err());
+    }
+}
@@ -1,7 +1,9 @@
-use ndarray::{arr0};
+use ndarray::{arr0, Array1};
+use noisy_float::types::N64;
 
 use smartnoise_validator::{Float, Integer, proto};
 use smartnoise_validator::base::{Array, ReleaseNode, Value};
+use smartnoise_validator::components::stability_mechanism::get_stability_sensitivity;
 use smartnoise_validator::errors::*;
 use smartnoise_validator::utilities::{array::broadcast_ndarray, privacy::{get_delta, get_epsilon, spread_privacy_usage}, take_argument};
 
@@ -44,14 +46,71 @@ impl Evaluable for proto::LaplaceMechanism {
 
         data.gencolumns_mut().into_iter()
             .zip(sensitivity.gencolumns().into_iter().zip(epsilon.into_iter()))
-            .try_for_each(|(mut data_column, (sensitivity, epsilon))| data_column.iter_mut()
-                .zip(sensitivity.iter())
-                .try_for_each(|(v, sens)|
+            .try_for_each(|(data_column, (sensitivity, epsilon))| {
+                let count = data_column.len();
+                utilities::mechanisms::apply_elementwise(data_column, sensitivity, |v, sens|
+                    if self.resolution > 0. {
+                        utilities::mechanisms::discrete_laplace_mechanism(
+                            v, epsilon,
+                            utilities::mechanisms::get_grid_sensitivity(sens, self.resolution, count, 1),
+                            self.resolution,
+                            enforce_constant_time)
+                    } else {
+                        utilities::mechanisms::laplace_mechanism(
+                            v, epsilon, sens,
+                            enforce_constant_time,
+                        )
+                    })
+            })?;
+
+        Ok(ReleaseNode {
+            value: data.into(),
+            privacy_usages: Some(usages),
+            public: true,
+        })
+    }
+}
+
+impl Evaluable for proto::TruncatedLaplaceMechanism {
+    fn evaluate(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        mut arguments: NodeArguments
+    ) -> Result<ReleaseNode> {
+
+        let enforce_constant_time = privacy_definition.as_ref()
+            .map(|v| v.protect_elapsed_time).unwrap_or(false);
+
+        let mut data = take_argument(&mut arguments, "data")?.array()?.cast_float()?;
+        let num_columns = get_num_columns(&data)?;
+        let num_rows = get_num_rows(&data)?;
 
-                    utilities::mechanisms::laplace_mechanism(
-                        *v as Float, epsilon, *sens as f64,
+        let sensitivity = take_argument(&mut arguments, "sensitivity")?.array()?.cast_float()?;
+        let sens_num_columns = get_num_columns(&sensitivity)?;
+        let sens_num_rows = get_num_rows(&sensitivity)?;
+        if num_columns != sens_num_columns {
+            return Err(Error::from(format!("data has {:?} columns, while the sensitivity has {:?} columns. This is likely an error from substituting data into the graph.", num_columns, sens_num_columns)))
+        }
+        if num_rows != sens_num_rows {
+            return Err(Error::from(format!("data has {:?} rows, while the sensitivity has {:?} rows. This is likely an error from substituting data into the graph.", num_rows, sens_num_rows)))
+        }
+        if data.ndim() > 2 {
+            return Err(Error::from("data may not have dimensionality greater than 2"))
+        }
+
+        let usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
+        let epsilon = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
+        let delta = usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?;
+
+        data.gencolumns_mut().into_iter()
+            .zip(sensitivity.gencolumns())
+            .zip(epsilon.into_iter().zip(delta))
+            .try_for_each(|((data_column, sensitivity), (epsilon, delta))|
+                utilities::mechanisms::apply_elementwise(data_column, sensitivity, |v, sens|
+                    utilities::mechanisms::truncated_laplace_mechanism(
+                        v, epsilon, delta, sens,
                         enforce_constant_time,
-                    ).map(|noise| *v = noise as Float)))?;
+                    )))?;
 
         Ok(ReleaseNode {
             value: data.into(),
@@ -61,6 +120,63 @@ impl Evaluable for proto::LaplaceMechanism {
     }
 }
 
+impl Evaluable for proto::StabilityMechanism {
+    fn evaluate(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        mut arguments: NodeArguments
+    ) -> Result<ReleaseNode> {
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or_else(|| Error::from("privacy_definition must be defined"))?;
+        let sensitivity = get_stability_sensitivity(privacy_definition)?;
+
+        let usages = spread_privacy_usage(&self.privacy_usage, 1)?;
+        let epsilon = get_epsilon(&usages[0])?;
+        let delta = get_delta(&usages[0])?;
+
+        macro_rules! stability {
+            ($data:expr) => {{
+                let (categories, counts) = utilities::mechanisms::stability_mechanism(
+                    Array1::from($data.into_raw_vec()).view(), epsilon, delta, sensitivity)?;
+                (Array1::from(categories).into_dyn(), Array1::from(counts).into_dyn())
+            }}
+        }
+
+        let (categories, counts): (Value, Value) = match take_argument(&mut arguments, "data")?.array()? {
+            Array::Bool(data) => {
+                let (categories, counts) = stability!(data);
+                (categories.into(), counts.into())
+            },
+            Array::Float(data) => {
+                // floats are ordered to be counted, which is not possible for NaN
+                let data = data.iter().map(|v| N64::try_new(*v)).collect::<Option<Vec<N64>>>()
+                    .ok_or_else(|| Error::from("data: may not contain NaN"))?;
+                let (categories, counts) = stability!(Array1::from(data));
+                (categories.mapv(|v| v.raw()).into(), counts.into())
+            },
+            Array::Int(data) => {
+                let (categories, counts) = stability!(data);
+                (categories.into(), counts.into())
+            },
+            Array::Str(data) => {
+                let (categories, counts) = stability!(data);
+                (categories.into(), counts.into())
+            },
+            _ => return Err("data: atomic type is not supported".into())
+        };
+
+        Ok(ReleaseNode {
+            value: Value::Dataframe(indexmap::indexmap![
+                "categories".into() => categories,
+                "counts".into() => counts
+            ]),
+            privacy_usages: Some(usages),
+            public: true,
+        })
+    }
+}
+
 impl Evaluable for proto::GaussianMechanism {
     fn evaluate(
         &self,
@@ -88,22 +204,36 @@ impl Evaluable for proto::GaussianMechanism {
             return Err(Error::from("data may not have dimensionality greater than 2"))
         }
 
-        let usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
+        // the sensitivity of a joint gaussian is already calibrated to all columns
+        let usages = spread_privacy_usage(
+            &self.privacy_usage, if self.joint { 1 } else { num_columns as usize })?;
 
-        let epsilon = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
-        let delta = usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?;
+        let epsilon = usages.iter().map(get_epsilon).cycle()
+            .take(num_columns as usize).collect::<Result<Vec<f64>>>()?;
+        let delta = usages.iter().map(get_delta).cycle()
+            .take(num_columns as usize).collect::<Result<Vec<f64>>>()?;
+
+        // a joint sensitivity is shared by every statistic, and grows with each when rounded to the grid
+        let num_statistics = data.len();
 
         data.gencolumns_mut().into_iter()
             .zip(sensitivity.gencolumns().into_iter())
             .zip(epsilon.into_iter().zip(delta.into_iter()))
-            .try_for_each(|((mut data_column, sensitivity), (epsilon, delta))| data_column.iter_mut()
-                .zip(sensitivity.iter())
-                .try_for_each(|(v, sens)|
-
-                    utilities::mechanisms::gaussian_mechanism(
-                        *v as Float, epsilon, delta, *sens as f64, self.analytic,
-                        enforce_constant_time,
-                    ).map(|noise| *v = noise as Float)))?;
+            .try_for_each(|((data_column, sensitivity), (epsilon, delta))| {
+                let count = if self.joint { num_statistics } else { data_column.len() };
+                utilities::mechanisms::apply_elementwise(data_column, sensitivity, |v, sens|
+                    if self.resolution > 0. {
+                        utilities::mechanisms::discrete_gaussian_mechanism(
+                            v, epsilon, delta,
+                            utilities::mechanisms::get_grid_sensitivity(sens, self.resolution, count, 2),
+                            self.resolution, self.analytic)
+                    } else {
+                        utilities::mechanisms::gaussian_mechanism(
+                            v, epsilon, delta, sens, self.analytic,
+                            enforce_constant_time,
+                        )
+                    })
+            })?;
 
         Ok(ReleaseNode {
             value: data.into(),
@@ -229,6 +359,7 @@ impl Evaluable for proto::ExponentialMechanism {
         Ok(ReleaseNode {
             value: match candidates {
                 Array::Float(candidates) => apply_exponential!(candidates),
+                Array::F32(candidates) => apply_exponential!(candidates),
                 Array::Int(candidates) => apply_exponential!(candidates),
                 Array::Str(candidates) => apply_exponential!(candidates),
                 Array::Bool(candidates) => apply_exponential!(candidates)
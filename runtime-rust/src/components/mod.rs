@@ -13,15 +13,20 @@ use crate::NodeArguments;
 use smartnoise_validator::base::ReleaseNode;
 
 use smartnoise_validator::proto;
+use smartnoise_validator::components::preserves_precision;
 
 //pub mod bin;
+pub mod bin_edges;
+pub mod bound_contribution;
 pub mod cast;
 pub mod clamp;
 pub mod count;
 pub mod covariance;
 pub mod column_bind;
+pub mod datetime;
 pub mod digitize;
 pub mod dp_gumbel_median;
+pub mod expression;
 pub mod filter;
 pub mod histogram;
 pub mod impute;
@@ -32,15 +37,22 @@ pub mod mean;
 pub mod mechanisms;
 pub mod partition;
 pub mod quantile;
+pub mod rank;
 pub mod raw_moment;
 pub mod reshape;
 pub mod resize;
+pub mod simplex_projection;
+pub mod subsample;
 pub mod sum;
+pub mod sum_of_squares;
+pub mod synthesize_from_marginals;
+pub mod text;
 pub mod theil_sen;
 pub mod to_dataframe;
 pub mod transforms;
 pub mod union;
 pub mod variance;
+pub mod weighted_sample;
 
 /// Evaluable component trait
 ///
@@ -70,6 +82,11 @@ impl Evaluable for proto::component::Variant {
         privacy_definition: &Option<proto::PrivacyDefinition>,
         arguments: NodeArguments
     ) -> Result<ReleaseNode> {
+        // all computation is carried out in double precision
+        let arguments = if preserves_precision(self) { arguments } else {
+            arguments.into_iter().map(|(k, v)| (k, v.to_double())).collect()
+        };
+
         macro_rules! evaluate {
             ($( $variant:ident ),*) => {
                 {
@@ -85,16 +102,17 @@ impl Evaluable for proto::component::Variant {
 
         evaluate!(
             // INSERT COMPONENT LIST
-            Cast, Clamp, ColumnBind, Count, Covariance, Digitize, Filter, Histogram, Impute, Index,
-            Materialize, Mean, Partition,
-            Quantile, RawMoment, Reshape, Resize, Sum, ToDataframe, Union, Variance,
+            BinEdges, BoundContribution, Cast, Clamp, ColumnBind, Count, Covariance, DateBin, Digitize, Expression, Filter, Histogram, Impute, Index,
+            Materialize, Mean, ParseDateTime, Partition,
+            Quantile, Rank, RawMoment, Reshape, Resize, SimplexProjection, StringLength, Subsample, Substring, Sum, SumOfSquares, SynthesizeFromMarginals,
+            ToDataframe, Union, Variance, WeightedSample,
 
             ExponentialMechanism, GaussianMechanism,
             LaplaceMechanism, SnappingMechanism,
-            SimpleGeometricMechanism,
+            SimpleGeometricMechanism, StabilityMechanism, TruncatedLaplaceMechanism,
 
-            Abs, Add, LogicalAnd, Divide, Equal, GreaterThan, LessThan, Log, Modulo, Multiply,
-            Negate, Negative, LogicalOr, Power, RowMax, RowMin, Subtract, TheilSen, DpGumbelMedian
+            Abs, Add, LogicalAnd, Ceil, Divide, Equal, Exp, Floor, FloorDivide, GreaterThan, LessThan, Log, Modulo, Multiply,
+            Negate, Negative, LogicalOr, Power, Round, RowMax, RowMin, Sqrt, Subtract, TheilSen, DpGumbelMedian
         );
 
         Err(format!("Component type not implemented: {:?}", self).into())
@@ -1,9 +1,9 @@
 use smartnoise_validator::errors::*;
 
 use crate::NodeArguments;
-use smartnoise_validator::base::{Array, ReleaseNode, Value, IndexKey};
+use smartnoise_validator::base::{Array, ReleaseNode, Value, IndexKey, Jagged};
 use smartnoise_validator::utilities::{take_argument, get_common_value};
-use smartnoise_validator::components::partition::{even_split_lengths, make_dense_partition_keys};
+use smartnoise_validator::components::partition::{even_split_lengths, make_dense_partition_keys, make_composite_partition_keys};
 use crate::components::Evaluable;
 use ndarray::{ArrayD, Axis};
 
@@ -16,7 +16,20 @@ use indexmap::map::IndexMap;
 impl Evaluable for proto::Partition {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         let data = take_argument(&mut arguments, "data")?;
+        // when releasing a single partition, only the rows of the named partition are selected
+        let key = arguments.remove::<IndexKey>(&"names".into())
+            .map(IndexKey::from_value).transpose()?;
+
         Ok(ReleaseNode::new(match arguments.remove::<IndexKey>(&"by".into()) {
+            // composite keys with mixed types, one element from each column
+            Some(Value::Dataframe(by)) => {
+                let categories = take_argument(&mut arguments, "categories")?.dataframe()?;
+                let partitions = make_composite_partition_keys(categories.into_iter()
+                    .map(|(_, categories)| categories.jagged())
+                    .collect::<Result<Vec<Jagged>>>()?)?;
+
+                Value::Partitions(partition_by(&data, composite_keys(by)?, partitions, key.as_ref())?)
+            },
             Some(by) => {
                 let categories = take_argument(&mut arguments, "categories")?.jagged()?;
                 let partitions = make_dense_partition_keys(
@@ -24,11 +37,11 @@ impl Evaluable for proto::Partition {
 
                 match by.array()? {
                     Array::Int(by) =>
-                        Value::Partitions(partition_by(&data, by.mapv(IndexKey::from), partitions)?),
+                        Value::Partitions(partition_by(&data, by.mapv(IndexKey::from), partitions, key.as_ref())?),
                     Array::Bool(by) =>
-                        Value::Partitions(partition_by(&data, by.mapv(IndexKey::from), partitions)?),
+                        Value::Partitions(partition_by(&data, by.mapv(IndexKey::from), partitions, key.as_ref())?),
                     Array::Str(by) =>
-                        Value::Partitions(partition_by(&data, by.mapv(IndexKey::from), partitions)?),
+                        Value::Partitions(partition_by(&data, by.mapv(IndexKey::from), partitions, key.as_ref())?),
                     _ => return Err("by and categories must share the same type".into())
                 }
             },
@@ -36,12 +49,35 @@ impl Evaluable for proto::Partition {
                 let num_partitions = take_argument(&mut arguments, "num_partitions")?
                     .array()?.first_int()?;
 
-                Value::Partitions(partition_evenly(&data, num_partitions as i64)?)
+                Value::Partitions(match key {
+                    Some(key) => partition_evenly_by_key(&data, num_partitions as i64, &key)?,
+                    None => partition_evenly(&data, num_partitions as i64)?
+                })
             }
         }))
     }
 }
 
+/// Collect the keys of each column of a dataframe into a two-dimensional array of keys,
+/// with one row per record.
+fn composite_keys(by: IndexMap<IndexKey, Value>) -> Result<ArrayD<IndexKey>> {
+    let columns = by.into_iter()
+        .map(|(name, column)| Ok(match column.array()? {
+            Array::Int(column) => column.mapv(IndexKey::from),
+            Array::Bool(column) => column.mapv(IndexKey::from),
+            Array::Str(column) => column.mapv(IndexKey::from),
+            Array::Float(_) | Array::F32(_) => return Err(format!(
+                "by: column {}: partitioning based on floats is not supported", name.to_string()).into())
+        }.iter().cloned().collect::<Vec<IndexKey>>()))
+        .collect::<Result<Vec<Vec<IndexKey>>>>()?;
+
+    let num_records = get_common_value(&columns.iter().map(Vec::len).collect::<Vec<usize>>())
+        .ok_or_else(|| Error::from("by: columns must share the same length"))?;
+
+    Ok(ndarray::Array2::from_shape_fn(
+        (num_records, columns.len()), |(row, column)| columns[column][row].clone()).into_dyn())
+}
+
 // to make the nested indexmaps more readable
 type ColName = IndexKey;
 
@@ -117,6 +153,9 @@ pub fn partition_evenly(data: &Value, num_partitions: i64) -> Result<IndexMap<In
             Array::Float(data) =>
                 partition_ndarray_evenly(data, num_partitions).into_iter()
                     .map(|(idx, data)| (idx, data.into())).collect::<IndexMap<IndexKey, Value>>(),
+            Array::F32(data) =>
+                partition_ndarray_evenly(data, num_partitions).into_iter()
+                    .map(|(idx, data)| (idx, data.into())).collect::<IndexMap<IndexKey, Value>>(),
             Array::Int(data) =>
                 partition_ndarray_evenly(data, num_partitions).into_iter()
                     .map(|(idx, data)| (idx, data.into())).collect::<IndexMap<IndexKey, Value>>(),
@@ -132,8 +171,52 @@ pub fn partition_evenly(data: &Value, num_partitions: i64) -> Result<IndexMap<In
 
 }
 
+/// Select the rows of a single partition, as if the data were partitioned evenly into num_partitions partitions.
+///
+/// # Arguments
+/// * `data` - Array or dataframe to partition.
+/// * `num_partitions` - Number of partitions the data is split into.
+/// * `key` - Key of the partition to select.
+///
+/// # Return
+/// Indexmap containing only the selected partition.
+pub fn partition_evenly_by_key(
+    data: &Value, num_partitions: i64, key: &IndexKey
+) -> Result<IndexMap<IndexKey, Value>> {
+    let number_rows = match data {
+        Value::Dataframe(data) => get_common_value(&data.values()
+            .map(|v| v.ref_array()?.num_records())
+            .collect::<Result<Vec<usize>>>()?)
+            .ok_or_else(|| Error::from("columns of a dataframe must share the same length"))?,
+        data => data.ref_array()?.num_records()?
+    };
+
+    let lengths = even_split_lengths(number_rows as i64, num_partitions);
+    let index = match key {
+        IndexKey::Int(index) if (0..num_partitions).contains(index) => *index as usize,
+        _ => return Err(format!("partition {} does not exist", key.to_string()).into())
+    };
+    let offset = lengths[..index].iter().sum::<i64>() as usize;
+
+    select_partitions(data, &indexmap::indexmap![
+        key.clone() => (offset..offset + lengths[index] as usize).collect::<Vec<usize>>()])
+}
+
+/// Split the rows of data by the key of each row.
+///
+/// Partitions are sorted by key, so that partitioning the same data always yields identically-ordered partitions.
+/// Rows within each partition keep the order of the data.
+///
+/// # Arguments
+/// * `data` - Array or dataframe to partition.
+/// * `by` - Key of each row of the data, or a row of keys for each row of the data.
+/// * `partition_keys` - Keys of partitions that are present, even if no rows take the key.
+/// * `key` - If set, only the partition with this key is selected.
+///
+/// # Return
+/// Partitions of the data, keyed by the partition keys.
 pub fn partition_by(
-    data: &Value, by: ArrayD<IndexKey>, partition_keys: Vec<IndexKey>
+    data: &Value, by: ArrayD<IndexKey>, partition_keys: Vec<IndexKey>, key: Option<&IndexKey>
 ) -> Result<IndexMap<IndexKey, Value>> {
 
     let mut indices = partition_keys.into_iter()
@@ -149,41 +232,121 @@ pub fn partition_by(
         .for_each(|(idx, cat)| indices.entry(cat.clone())
             .or_insert_with(Vec::new).push(idx));
 
-    // partition either an array or a dataframe
-    fn value_partitioner(data: &Value, indices: &IndexMap<IndexKey, Vec<usize>>) -> Result<IndexMap<IndexKey, Value>> {
-        Ok(match data {
-            Value::Array(data) => match data {
-                Array::Int(data) => indices.into_iter()
-                    .map(|(cat, idxs)| (cat.clone(), data.select(ndarray::Axis(0), idxs).into()))
-                    .collect::<IndexMap<IndexKey, Value>>(),
-                Array::Float(data) => indices.into_iter()
-                    .map(|(cat, idxs)| (cat.clone(), data.select(ndarray::Axis(0), idxs).into()))
-                    .collect::<IndexMap<IndexKey, Value>>(),
-                Array::Bool(data) => indices.into_iter()
-                    .map(|(cat, idxs)| (cat.clone(), data.select(ndarray::Axis(0), idxs).into()))
-                    .collect::<IndexMap<IndexKey, Value>>(),
-                Array::Str(data) => indices.into_iter()
-                    .map(|(cat, idxs)| (cat.clone(), slow_select(&data, ndarray::Axis(0), idxs).into()))
-                    .collect::<IndexMap<IndexKey, Value>>()
-            },
+    // keys observed in the data, but not among the partition keys, would otherwise follow the order the data was read in
+    indices.sort_keys();
 
-            Value::Dataframe(data) => {
-                let columnar_partitions = data.into_iter().map(|(k, v)|
-                    Ok((k.clone(), value_partitioner(v, indices)?)))
-                    .collect::<Result<IndexMap<ColName, IndexMap<IndexKey, Value>>>>()?;
-
-                indices.iter()
-                    .map(|(cat, _)| (
-                        cat.clone(),
-                        Value::Dataframe(columnar_partitions.iter().map(|(colname, partitions)|
-                            (colname.clone(), partitions.get(&cat.clone()).unwrap().clone())
-                        ).collect::<IndexMap<ColName, Value>>())
-                    ))
-                    .collect::<IndexMap<IndexKey, Value>>()
-            },
-            _ => return Err("data: must be a dataframe or array".into())
-        })
-    };
+    if let Some(key) = key {
+        let rows = indices.remove(key)
+            .ok_or_else(|| Error::from(format!("partition {} does not exist", key.to_string())))?;
+        indices = indexmap::indexmap![key.clone() => rows];
+    }
+
+    select_partitions(data, &indices)
+}
 
-    value_partitioner(data, &indices)
-}
\ No newline at end of file
+/// Select the rows of each partition from either an array or a dataframe.
+fn select_partitions(data: &Value, indices: &IndexMap<IndexKey, Vec<usize>>) -> Result<IndexMap<IndexKey, Value>> {
+    Ok(match data {
+        Value::Array(data) => match data {
+            Array::Int(data) => indices.into_iter()
+                .map(|(cat, idxs)| (cat.clone(), data.select(ndarray::Axis(0), idxs).into()))
+                .collect::<IndexMap<IndexKey, Value>>(),
+            Array::Float(data) => indices.into_iter()
+                .map(|(cat, idxs)| (cat.clone(), data.select(ndarray::Axis(0), idxs).into()))
+                .collect::<IndexMap<IndexKey, Value>>(),
+            Array::F32(data) => indices.into_iter()
+                .map(|(cat, idxs)| (cat.clone(), data.select(ndarray::Axis(0), idxs).into()))
+                .collect::<IndexMap<IndexKey, Value>>(),
+            Array::Bool(data) => indices.into_iter()
+                .map(|(cat, idxs)| (cat.clone(), data.select(ndarray::Axis(0), idxs).into()))
+                .collect::<IndexMap<IndexKey, Value>>(),
+            Array::Str(data) => indices.into_iter()
+                .map(|(cat, idxs)| (cat.clone(), slow_select(&data, ndarray::Axis(0), idxs).into()))
+                .collect::<IndexMap<IndexKey, Value>>()
+        },
+
+        Value::Dataframe(data) => {
+            let columnar_partitions = data.into_iter().map(|(k, v)|
+                Ok((k.clone(), select_partitions(v, indices)?)))
+                .collect::<Result<IndexMap<ColName, IndexMap<IndexKey, Value>>>>()?;
+
+            indices.iter()
+                .map(|(cat, _)| (
+                    cat.clone(),
+                    Value::Dataframe(columnar_partitions.iter().map(|(colname, partitions)|
+                        (colname.clone(), partitions.get(&cat.clone()).unwrap().clone())
+                    ).collect::<IndexMap<ColName, Value>>())
+                ))
+                .collect::<IndexMap<IndexKey, Value>>()
+        },
+        _ => return Err("data: must be a dataframe or array".into())
+    })
+}
+
+#[cfg(test)]
+mod test_partition {
+    use indexmap::indexmap;
+    use ndarray::arr1;
+
+    use smartnoise_validator::base::{IndexKey, Jagged, Value};
+    use smartnoise_validator::errors::*;
+    use smartnoise_validator::proto;
+
+    use crate::components::Evaluable;
+    use crate::NodeArguments;
+
+    fn evaluate(arguments: NodeArguments) -> Result<Vec<(IndexKey, Value)>> {
+        match (proto::Partition {}).evaluate(&None, arguments)?.value {
+            Value::Partitions(partitions) => Ok(partitions.into_iter().collect()),
+            _ => panic!("partition must release partitions")
+        }
+    }
+
+    fn partition() -> Vec<(IndexKey, Value)> {
+        evaluate(indexmap![
+            IndexKey::from("data") => arr1(&[1., 2., 3., 4., 5., 6.]).into_dyn().into(),
+            IndexKey::from("by") => arr1(&["WA", "CA", "OR", "CA", "WA", "OR"]).mapv(|v| v.to_string()).into_dyn().into(),
+            IndexKey::from("categories") => Value::Jagged(Jagged::Str(vec![
+                vec!["WA".to_string(), "OR".to_string(), "CA".to_string()]]))]).unwrap()
+    }
+
+    #[test]
+    fn test_deterministic_order() {
+        let partitions = partition();
+
+        // keys are sorted, regardless of the order of the categories or the data
+        assert_eq!(partitions.iter().map(|(key, _)| key.clone()).collect::<Vec<IndexKey>>(),
+                   vec!["CA".into(), "OR".into(), "WA".into()]);
+        // rows within a partition keep their order
+        assert_eq!(partitions[2].1.ref_array().unwrap().ref_float().unwrap().iter().copied().collect::<Vec<f64>>(),
+                   vec![1., 5.]);
+
+        // partitioning the same data again yields identically-ordered keys and values
+        assert_eq!(partition(), partitions);
+    }
+
+    #[test]
+    fn test_select_partition() {
+        let by = |names: Value| evaluate(indexmap![
+            IndexKey::from("data") => arr1(&[1., 2., 3., 4., 5., 6.]).into_dyn().into(),
+            IndexKey::from("by") => arr1(&["WA", "CA", "OR", "CA", "WA", "OR"]).mapv(|v| v.to_string()).into_dyn().into(),
+            IndexKey::from("categories") => Value::Jagged(Jagged::Str(vec![
+                vec!["WA".to_string(), "OR".to_string(), "CA".to_string()]])),
+            IndexKey::from("names") => names]);
+
+        // only the named partition is built
+        let partitions = by("WA".to_string().into()).unwrap();
+        assert_eq!(partitions.len(), 1);
+        assert_eq!(partitions[0], partition()[2]);
+        assert!(by("NY".to_string().into()).is_err());
+
+        let evenly = |names: Value| evaluate(indexmap![
+            IndexKey::from("data") => arr1(&[1., 2., 3., 4., 5.]).into_dyn().into(),
+            IndexKey::from("num_partitions") => 2.into(),
+            IndexKey::from("names") => names]);
+
+        let partitions = evenly(1.into()).unwrap();
+        assert_eq!(partitions, vec![(IndexKey::from(1), arr1(&[4., 5.]).into_dyn().into())]);
+        assert!(evenly(2.into()).is_err());
+    }
+}
@@ -8,7 +8,7 @@ use noisy_float::types::n64;
 use num::{FromPrimitive, ToPrimitive};
 
 use smartnoise_validator::{Float, proto};
-use smartnoise_validator::base::{Array, IndexKey, ReleaseNode, Value};
+use smartnoise_validator::base::{Array, IndexKey, Interpolation, ReleaseNode, Value};
 use smartnoise_validator::errors::*;
 use smartnoise_validator::utilities::take_argument;
 
@@ -43,13 +43,16 @@ impl Evaluable for proto::Quantile {
                     _ => return Err("data must be either f64 or i64".into())
                 }
             },
-            None => match data {
-                Array::Float(data) =>
-                    quantile(data.mapv(|v| n64(v as f64)), self.alpha, &self.interpolation)?
-                        .mapv(|v| v.raw() as Float).into(),
-                Array::Int(data) =>
-                    quantile(data, self.alpha, &self.interpolation)?.into(),
-                _ => return Err("data must be either f64 or i64".into())
+            None => {
+                let interpolation = self.interpolation.parse::<Interpolation>()?;
+                match data {
+                    Array::Float(data) =>
+                        quantile(data.mapv(|v| n64(v as f64)), self.alpha, &interpolation)?
+                            .mapv(|v| v.raw() as Float).into(),
+                    Array::Int(data) =>
+                        quantile(data, self.alpha, &interpolation)?.into(),
+                    _ => return Err("data must be either f64 or i64".into())
+                }
             }
         }).map(ReleaseNode::new)
     }
@@ -61,6 +64,7 @@ impl Evaluable for proto::Quantile {
 /// # Arguments
 /// * `data` - Array of data for which you would like the quantile.
 /// * `alpha` - Desired quantile.
+/// * `interpolation` - Strategy for interpolating a quantile that lies between two order statistics.
 ///
 /// # Return
 /// Quantile of interest for each column of your data.
@@ -71,25 +75,25 @@ impl Evaluable for proto::Quantile {
 /// use smartnoise_runtime::components::quantile::quantile;
 /// use noisy_float::types::n64;
 /// use smartnoise_validator::Float;
+/// use smartnoise_validator::base::Interpolation;
 /// let data: ArrayD<Float> = arr2(&[ [0., 1., 2.], [2., 3., 4.] ]).into_dyn();
-/// let median = quantile(data.mapv(|v| n64(v as f64)), 0.5, &"midpoint".to_string()).unwrap();
+/// let median = quantile(data.mapv(|v| n64(v as f64)), 0.5, &Interpolation::Midpoint).unwrap();
 /// println!("{:?}", median);
 /// assert_eq!(median, arr1(& [1.0, 2.0, 3.0] ).into_dyn().mapv(|v| n64(v as f64)));
 /// ```
 pub fn quantile<T: FromPrimitive + Ord + Clone + Sub<Output=T> + Mul<Output=T> + Div<Output=T> + Add<Output=T> + Rem<Output=T> + ToPrimitive>(
-    mut data: ArrayD<T>, alpha: f64, interpolation: &str
+    mut data: ArrayD<T>, alpha: f64, interpolation: &Interpolation
 ) -> Result<ArrayD<T>> {
     if 0. > alpha || alpha > 1. {
         return Err("q must be within [0, 1]".into());
     }
 
-    match match interpolation.to_lowercase().as_str() {
-        "lower" => data.quantile_axis_mut(Axis(0), n64(alpha), &interpolate::Lower),
-        "upper" => data.quantile_axis_mut(Axis(0), n64(alpha), &interpolate::Higher),
-        "midpoint" => data.quantile_axis_mut(Axis(0), n64(alpha), &interpolate::Midpoint),
-        "nearest" => data.quantile_axis_mut(Axis(0), n64(alpha), &interpolate::Nearest),
-        "linear" => data.quantile_axis_mut(Axis(0), n64(alpha), &interpolate::Linear),
-        _ => return Err(format!("interpolation type not recognized: {}", interpolation).into())
+    match match interpolation {
+        Interpolation::Lower => data.quantile_axis_mut(Axis(0), n64(alpha), &interpolate::Lower),
+        Interpolation::Higher => data.quantile_axis_mut(Axis(0), n64(alpha), &interpolate::Higher),
+        Interpolation::Nearest => data.quantile_axis_mut(Axis(0), n64(alpha), &interpolate::Nearest),
+        Interpolation::Midpoint => data.quantile_axis_mut(Axis(0), n64(alpha), &interpolate::Midpoint),
+        Interpolation::Linear => data.quantile_axis_mut(Axis(0), n64(alpha), &interpolate::Linear),
     }  {
         Ok(quantiles) => Ok(quantiles),
         Err(_) => Err("unable to compute quantiles".into())
@@ -268,4 +272,46 @@ mod test_quantile_utilities {
 
         // println!("utilities {:?}", utilities);
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod test_quantile {
+    use ndarray::arr1;
+    use noisy_float::types::n64;
+
+    use smartnoise_validator::base::Interpolation;
+
+    use crate::components::quantile::quantile;
+
+    /// Reference quantile of sorted data, by interpolating between the order statistics that bracket it.
+    fn reference(sorted: &[f64], alpha: f64, interpolation: Interpolation) -> f64 {
+        let index = alpha * (sorted.len() - 1) as f64;
+        let (lower, higher) = (sorted[index.floor() as usize], sorted[index.ceil() as usize]);
+        let fraction = index.fract();
+        match interpolation {
+            Interpolation::Lower => lower,
+            Interpolation::Higher => higher,
+            Interpolation::Nearest => if fraction < 0.5 { lower } else { higher },
+            Interpolation::Midpoint => (lower + higher) / 2.,
+            Interpolation::Linear => lower + (higher - lower) * fraction,
+        }
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let sorted = [1., 2., 4., 8., 16., 32.];
+        let interpolations = [
+            Interpolation::Lower, Interpolation::Higher, Interpolation::Nearest,
+            Interpolation::Midpoint, Interpolation::Linear];
+
+        for interpolation in interpolations.iter() {
+            for alpha in &[0., 0.1, 0.3, 0.5, 0.7, 0.9, 1.] {
+                let actual = quantile(arr1(&sorted).into_dyn().mapv(n64), *alpha, interpolation).unwrap();
+                let expected = reference(&sorted, *alpha, *interpolation);
+                assert!((actual.first().unwrap().raw() - expected).abs() < 1e-10,
+                        "{:?} quantile at {} is {}, expected {}", interpolation, alpha, actual, expected);
+            }
+        }
+    }
+}
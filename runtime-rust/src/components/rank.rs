@@ -0,0 +1,107 @@
+use smartnoise_validator::errors::*;
+
+use crate::NodeArguments;
+use smartnoise_validator::base::{Array, ReleaseNode};
+use crate::components::Evaluable;
+use ndarray::ArrayD;
+use smartnoise_validator::{proto, Float};
+use smartnoise_validator::utilities::take_argument;
+use std::cmp::Ordering;
+
+
+impl Evaluable for proto::Rank {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = match take_argument(&mut arguments, "data")?.array()? {
+            Array::Int(data) => data.mapv(|v| v as Float),
+            data => data.float()?
+        };
+        Ok(ReleaseNode::new(rank(&data, self.normalize).into()))
+    }
+}
+
+/// Ranks each element within its column.
+///
+/// Ranks start at one, and tied elements share the average of the ranks they span.
+/// `NAN` values are not ranked, and remain `NAN`.
+///
+/// The rank of every element depends on every other element in the column,
+/// so this transform is not differentially private, and is meant to feed a downstream mechanism.
+///
+/// # Arguments
+/// * `data` - Data to rank.
+/// * `normalize` - Whether to rescale ranks from `[1, n]` to `[0, 1]`. A column with one ranked element is mapped to zero.
+///
+/// # Return
+/// Rank of each element within its column.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_runtime::components::rank::rank;
+///
+/// let data = arr1(&[3., 1., 3., 2.]).into_dyn();
+/// assert_eq!(rank(&data, false), arr1(&[3.5, 1., 3.5, 2.]).into_dyn());
+/// assert_eq!(rank(&data, true), arr1(&[5. / 6., 0., 5. / 6., 1. / 3.]).into_dyn());
+/// ```
+pub fn rank(data: &ArrayD<Float>, normalize: bool) -> ArrayD<Float> {
+    let mut ranks = ArrayD::from_elem(data.shape(), Float::NAN);
+
+    ranks.gencolumns_mut().into_iter()
+        .zip(data.gencolumns())
+        .for_each(|(mut ranks, column)| {
+            // indices of non-null elements, in order of their values
+            let mut order = (0..column.len())
+                .filter(|i| !column[*i].is_nan())
+                .collect::<Vec<usize>>();
+            order.sort_by(|l, r| column[*l].partial_cmp(&column[*r]).unwrap_or(Ordering::Equal));
+
+            let num_ranked = order.len();
+            let mut start = 0;
+            while start < num_ranked {
+                // find the run of elements tied with the element at start
+                let mut end = start + 1;
+                while end < num_ranked && column[order[end]] == column[order[start]] {
+                    end += 1;
+                }
+                // average of the one-based ranks start + 1 ..= end
+                let average = (start + end + 1) as Float / 2.;
+                let average = match (normalize, num_ranked) {
+                    (false, _) => average,
+                    (true, 1) => 0.,
+                    (true, _) => (average - 1.) / (num_ranked - 1) as Float
+                };
+                order[start..end].iter().for_each(|i| ranks[*i] = average);
+                start = end;
+            }
+        });
+    ranks
+}
+
+#[cfg(test)]
+mod test_rank {
+    use ndarray::{arr1, arr2};
+
+    use crate::components::rank::rank;
+
+    #[test]
+    fn test_ties() {
+        let data = arr2(&[
+            [2., 5.],
+            [1., f64::NAN],
+            [2., 5.],
+            [2., 4.],
+            [7., 5.],
+        ]).into_dyn();
+
+        let ranks = rank(&data, false);
+        assert_eq!(ranks.slice(ndarray::s![.., 0]), arr1(&[3., 1., 3., 3., 5.]));
+        assert_eq!(ranks[[0, 1]], 3.);
+        assert!(ranks[[1, 1]].is_nan());
+        assert_eq!(ranks.slice(ndarray::s![2.., 1]), arr1(&[3., 1., 3.]));
+
+        // normalized ranks are within [0, 1]
+        let ranks = rank(&data, true);
+        assert_eq!(ranks.slice(ndarray::s![.., 0]), arr1(&[0.5, 0., 0.5, 0.5, 1.]));
+        assert_eq!(ranks.slice(ndarray::s![2.., 1]), arr1(&[2. / 3., 0., 2. / 3.]));
+    }
+}
@@ -51,6 +51,17 @@ impl Evaluable for proto::Reshape {
                         .collect::<IndexMap<IndexKey, Value>>()))
                 }
             }
+            Array::F32(data) => {
+                let mut reshaped = reshape(&data, self.symmetric, &layout, &self.shape)?;
+                match reshaped.len() {
+                    0 => Err("at least one record is required to reshape".into()),
+                    1 => Ok(reshaped.remove(0).into()),
+                    _ => Ok(Value::Partitions(reshaped.into_iter().enumerate()
+                        .map(|(idx, data)|
+                            (IndexKey::from(idx as Integer), data.into()))
+                        .collect::<IndexMap<IndexKey, Value>>()))
+                }
+            }
             Array::Str(data) => {
                 let mut reshaped = reshape(&data, self.symmetric, &layout, &self.shape)?;
                 match reshaped.len() {
@@ -3,6 +3,7 @@ use std::hash::Hash;
 
 use ndarray::{ArrayD, Axis};
 use ndarray::prelude::*;
+use noisy_float::types::n64;
 
 use smartnoise_validator::{Float, Integer, proto};
 use smartnoise_validator::base::{Array, IndexKey, Jagged, ReleaseNode, Value};
@@ -11,11 +12,12 @@ use smartnoise_validator::utilities::{standardize_numeric_argument, take_argumen
 use smartnoise_validator::utilities::array::{slow_select, slow_stack};
 
 use crate::components::Evaluable;
-use crate::components::impute::{impute_categorical_arrayd, impute_float_gaussian_arrayd, impute_float_uniform_arrayd};
+use crate::components::impute::{impute_categorical_arrayd, impute_float_exponential_arrayd, impute_float_gaussian_arrayd, impute_float_triangular_arrayd, impute_float_uniform_arrayd};
 use crate::NodeArguments;
 use crate::utilities::create_subset;
 use crate::utilities::get_num_columns;
-use crate::utilities::noise::{sample_binomial, sample_uniform_int};
+use crate::utilities::noise::{sample_binomial, sample_gaussian_truncated, sample_uniform_int};
+use crate::utilities::nulls::Nullable;
 
 pub enum RowResizeConfig {
     NumRows(Integer),
@@ -24,6 +26,15 @@ pub enum RowResizeConfig {
     None
 }
 
+/// Distribution and bounds that synthetic records are imputed from.
+pub struct Imputation<T> {
+    pub distribution: String,
+    pub lower: ArrayD<T>,
+    pub upper: ArrayD<T>,
+    pub shift: Option<ArrayD<Float>>,
+    pub scale: Option<ArrayD<Float>>
+}
+
 impl Evaluable for proto::Resize {
     fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
 
@@ -55,9 +66,19 @@ impl Evaluable for proto::Resize {
                 // match on types of various arguments and ensure they are consistent with each other
                 (Value::Array(data), Value::Jagged(categories)) =>
                     Ok(match (data, categories) {
-                        (Array::Float(_), Jagged::Float(_)) =>
-                            return Err("categorical resizing over floats in not currently supported- try continuous imputation instead".into()),
-//                            resize_categorical(&data, &n, &categories, &probabilities)?.into(),
+                        (Array::Float(data), Jagged::Float(categories)) => {
+                            // floats are totally ordered once NaN is excluded
+                            if data.iter().chain(categories.iter().flatten()).any(Nullable::is_null) {
+                                return Err("data and categories may not contain NaN when resizing categorically".into())
+                            }
+                            resize_categorical(
+                                data.mapv(n64), resize_config, number_cols,
+                                categories.into_iter()
+                                    .map(|cats| cats.into_iter().map(n64).collect())
+                                    .collect(),
+                                weights, privacy_definition)?
+                                .mapv(|v| v.raw()).into()
+                        },
                         (Array::Int(data), Jagged::Int(categories)) =>
                             resize_categorical(
                                 data, resize_config, number_cols, categories, weights,
@@ -78,34 +99,35 @@ impl Evaluable for proto::Resize {
         // If "categories" constraint is not populated, data are treated as numeric and imputation (if necessary)
         // is done according to a continuous distribution.
         else {
+            // If there is no valid distribution argument provided, generate uniform by default
+            let distribution = match take_argument(&mut arguments, "distribution") {
+                Ok(distribution) => distribution.array()?.first_string()?,
+                Err(_) => "uniform".to_string()
+            };
+            let shift = match take_argument(&mut arguments, "shift") {
+                Ok(shift) => Some(shift.array()?.cast_float()?),
+                Err(_) => None
+            };
+            let scale = match take_argument(&mut arguments, "scale") {
+                Ok(scale) => Some(scale.array()?.cast_float()?),
+                Err(_) => None
+            };
+
             match (
                 take_argument(&mut arguments, "data")?.array()?,
                 take_argument(&mut arguments, "lower")?.array()?,
                 take_argument(&mut arguments, "upper")?.array()?
             ) {
-                (Array::Float(data), Array::Float(lower), Array::Float(upper)) => {
-                    // If there is no valid distribution argument provided, generate uniform by default
-                    let distribution = match take_argument(&mut arguments, "distribution") {
-                        Ok(distribution) => distribution.array()?.first_string()?,
-                        Err(_) => "uniform".to_string()
-                    };
-                    let shift = match take_argument(&mut arguments, "shift") {
-                        Ok(shift) => Some(shift.array()?.float()?),
-                        Err(_) => None
-                    };
-                    let scale = match take_argument(&mut arguments, "scale") {
-                        Ok(scale) => Some(scale.array()?.float()?),
-                        Err(_) => None
-                    };
+                (Array::Float(data), Array::Float(lower), Array::Float(upper)) =>
                     Ok(resize_float(
                         data, resize_config, number_cols, &distribution,
                         lower, upper, shift, scale,
-                        privacy_definition)?.into())
-                }
+                        privacy_definition)?.into()),
                 (Array::Int(data), Array::Int(lower), Array::Int(upper)) =>
                     Ok(resize_integer(
                         data, resize_config, number_cols,
-                        lower, upper, privacy_definition)?.into()),
+                        Imputation { distribution, lower, upper, shift, scale },
+                        privacy_definition)?.into()),
                 _ => Err("data, lower, and upper must be of a homogeneous numeric type".into())
             }
         }.map(ReleaseNode::new)
@@ -138,8 +160,8 @@ fn get_sample_count(p: f64, n_actual: i64, privacy_definition: &proto::PrivacyDe
 /// * `distribution` - The distribution to be used when imputing records
 /// * `lower` - A lower bound on data elements
 /// * `upper` - An upper bound on data elements
-/// * `shift` - The shift (expectation) argument for the Gaussian distribution
-/// * `scale` - The scale (standard deviation) argument for the Gaussian distribution
+/// * `shift` - The shift (expectation) argument for the Gaussian distribution, or the mode of the triangular distribution
+/// * `scale` - The scale (standard deviation) argument for the Gaussian distribution, or the mean of the exponential distribution
 /// * `minimum_rows` - The minimum number of rows in the output dataset. Same as number_rows, but will not sample down.
 /// * `p` - Maximum proportion of dataset included in output
 ///
@@ -166,7 +188,6 @@ pub fn resize_float(
         let synthetic_base: ArrayD<Float> = ndarray::ArrayD::from_elem(shape, Float::NAN).into_dyn();
 
         // generate synthetic data
-        // NOTE: only uniform and gaussian supported at this time
         match distribution.to_lowercase().as_str() {
             "uniform" => impute_float_uniform_arrayd(synthetic_base, lower.clone(), upper.clone(), enforce_constant_time),
             "gaussian" => impute_float_gaussian_arrayd(
@@ -174,6 +195,14 @@ pub fn resize_float(
                 shift.ok_or_else(|| Error::from("shift must be defined for gaussian imputation"))?,
                 scale.ok_or_else(|| Error::from("scale must be defined for gaussian imputation"))?,
                 enforce_constant_time),
+            "exponential" => impute_float_exponential_arrayd(
+                synthetic_base, lower.clone(), upper.clone(),
+                scale.ok_or_else(|| Error::from("scale must be defined for exponential imputation"))?,
+                enforce_constant_time),
+            "triangular" => impute_float_triangular_arrayd(
+                synthetic_base, lower.clone(), upper.clone(),
+                shift.ok_or_else(|| Error::from("shift must be defined for triangular imputation"))?,
+                enforce_constant_time),
             _ => Err("unrecognized distribution".into())
         }
     };
@@ -278,8 +307,8 @@ pub fn resize_float(
 /// # Arguments
 /// * `data` - The data to be resized
 /// * `n` - An estimate of the size of the data -- this could be the guess of the user, or the result of a DP release
-/// * `lower` - A lower bound on data elements
-/// * `upper` - An upper bound on data elements
+/// * `imputation` - The distribution to be used when imputing records, either "uniform" or "gaussian", with bounds on data elements
+///   and the shift (expectation) and scale (standard deviation) of the Gaussian distribution
 ///
 /// # Return
 /// A resized version of data consistent with the provided `n`
@@ -287,9 +316,10 @@ pub fn resize_integer(
     mut data: ArrayD<Integer>,
     resize_config: RowResizeConfig,
     number_cols: Option<i64>,
-    lower: ArrayD<Integer>, upper: ArrayD<Integer>,
+    imputation: Imputation<Integer>,
     privacy_definition: &Option<proto::PrivacyDefinition>
 ) -> Result<ArrayD<Integer>> {
+    let Imputation { distribution, lower, upper, shift, scale } = imputation;
 
     let target_num_cols = match number_cols {
         Some(v) => v,
@@ -304,14 +334,35 @@ pub fn resize_integer(
     let enforce_constant_time = privacy_definition.as_ref()
         .map(|v| v.protect_elapsed_time).unwrap_or(false);
 
+    // the Gaussian is parameterized for every column, so that columns may be added
+    let gaussian = match distribution.to_lowercase().as_str() {
+        "uniform" => None,
+        "gaussian" => Some((
+            standardize_numeric_argument(shift
+                .ok_or_else(|| Error::from("shift must be defined for gaussian imputation"))?, target_num_cols)?
+                .into_dimensionality::<Ix1>()?.to_vec(),
+            standardize_numeric_argument(scale
+                .ok_or_else(|| Error::from("scale must be defined for gaussian imputation"))?, target_num_cols)?
+                .into_dimensionality::<Ix1>()?.to_vec())),
+        _ => return Err("unrecognized distribution for integer data".into())
+    };
+
     let make_synthetic = |
-        shape, lower: &Vec<Integer>, upper: &Vec<Integer>
+        shape, offset: usize
     | -> Result<ArrayD<Integer>> {
 
         let mut synthetic = ndarray::ArrayD::zeros(shape);
-        synthetic.gencolumns_mut().into_iter().zip(lower.into_iter().zip(upper.into_iter()))
-            .try_for_each(|(mut column, (min, max))| column.iter_mut()
-                .try_for_each(|v| sample_uniform_int(*min, *max).map(|s| *v = s)))?;
+        synthetic.gencolumns_mut().into_iter().enumerate()
+            .try_for_each(|(index, mut column)| {
+                let (min, max) = (lower[offset + index], upper[offset + index]);
+                column.iter_mut().try_for_each(|v| match &gaussian {
+                    // round a truncated Gaussian draw, which remains within the integer bounds
+                    Some((shift, scale)) => sample_gaussian_truncated(
+                        min as f64, max as f64, shift[offset + index], scale[offset + index],
+                        enforce_constant_time).map(|s| *v = s.round() as Integer),
+                    None => sample_uniform_int(min, max).map(|s| *v = s)
+                })
+            })?;
         Ok(synthetic)
     };
 
@@ -326,10 +377,7 @@ pub fn resize_integer(
                 let mut synthetic_shape = data.shape().to_vec();
                 synthetic_shape[1] = (target_num_cols - actual_num_cols) as usize;
 
-                let synthetic = make_synthetic(
-                    synthetic_shape,
-                    &lower[actual_num_cols as usize..].to_vec(),
-                    &upper[actual_num_cols as usize..].to_vec())?;
+                let synthetic = make_synthetic(synthetic_shape, actual_num_cols as usize)?;
 
                 // combine real and synthetic data
                 ndarray::stack(Axis(1), &[data.view(), synthetic.view()])
@@ -351,7 +399,7 @@ pub fn resize_integer(
                 // initialize synthetic data with correct shape
                 let mut synthetic_shape = data.shape().to_vec();
                 synthetic_shape[0] = (target_num_rows - actual_num_rows) as usize;
-                let synthetic = make_synthetic(synthetic_shape, &lower, &upper)?;
+                let synthetic = make_synthetic(synthetic_shape, 0)?;
 
                 // combine real and synthetic data
                 ndarray::stack(Axis(0), &[data.view(), synthetic.view()])
@@ -393,7 +441,7 @@ pub fn resize_integer(
             // Aug(phi, max(0, n - m, ...)
             let mut synthetic_shape = stacked.shape().to_vec();
             synthetic_shape[0] = (number_rows - sample_count).max(0) as usize;
-            let synthetic = make_synthetic(synthetic_shape, &lower, &upper)?;
+            let synthetic = make_synthetic(synthetic_shape, 0)?;
 
             // union(sample, synthetic)
             slow_stack(Axis(0), &[sample.view(), synthetic.view()])
@@ -421,7 +469,7 @@ pub fn resize_categorical<T>(
     categories: Vec<Vec<T>>,
     weights: Option<Vec<Vec<Float>>>,
     privacy_definition: &Option<proto::PrivacyDefinition>
-) -> Result<ArrayD<T>> where T: Clone + PartialEq + Default + Ord + Hash {
+) -> Result<ArrayD<T>> where T: Clone + PartialEq + Default + Ord + Hash + Nullable {
 
     let enforce_constant_time = privacy_definition.as_ref()
         .map(|v| v.protect_elapsed_time).unwrap_or(false);
@@ -565,3 +613,124 @@ pub fn create_sampling_indices(k: i64, n: i64, enforce_constant_time: bool) -> R
     // create set of sampling indices
     create_subset(&index_vec, &weight_vec, k as usize, enforce_constant_time)
 }
+
+/// Creates a set of indices sampled without replacement, with probability proportional to their weights.
+///
+/// Each draw is made from the remaining indices, so an index is never repeated.
+/// This is the importance-weighted counterpart to `create_sampling_indices`.
+///
+/// # Arguments
+///
+/// * `k` - The size of the desired subset
+/// * `weights` - Non-negative weight of each index in the set from which you want to subset
+/// * `enforce_constant_time` - Whether to force the sampling of each key to run in constant time.
+///
+/// # Return
+/// A vector of indices representing the subset, in the order they were drawn
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::components::resize::create_weighted_sampling_indices;
+/// let subset_indices = create_weighted_sampling_indices(2, &[0., 1., 3., 0.5], false).unwrap();
+/// assert_eq!(subset_indices.len(), 2);
+/// assert!(!subset_indices.contains(&0));
+/// ```
+pub fn create_weighted_sampling_indices(k: i64, weights: &[f64], enforce_constant_time: bool) -> Result<Vec<usize>> {
+    if k < 0 {
+        return Err("k must be non-negative".into())
+    }
+    let index_vec: Vec<usize> = (0..weights.len()).collect();
+    create_subset(&index_vec, weights, k as usize, enforce_constant_time)
+}
+
+#[cfg(test)]
+mod test_resize {
+    use ndarray::{arr1, arr2};
+    use smartnoise_validator::base::{Array, Jagged, Value};
+    use smartnoise_validator::proto;
+
+    use crate::components::Evaluable;
+
+    fn resize_float(data: Value, number_rows: i64) -> Array {
+        let categories = Value::Jagged(Jagged::Float(vec![vec![0.5, 1.5, 2.5]]));
+        let arguments = indexmap::indexmap![
+            "data".into() => data,
+            "categories".into() => categories,
+            "number_rows".into() => arr1(&[number_rows]).into_dyn().into()
+        ];
+        proto::Resize {}.evaluate(&None, arguments).unwrap().value.array().unwrap()
+    }
+
+    #[test]
+    fn test_resize_categorical_float() {
+        let data: Value = arr2(&[[0.5], [1.5], [2.5], [1.5], [0.5]]).into_dyn().into();
+
+        // rows are imputed from the categories
+        let upsized = resize_float(data.clone(), 10).float().unwrap();
+        assert_eq!(upsized.shape(), &[10, 1]);
+        assert_eq!(upsized.slice(ndarray::s![..5, ..]), arr2(&[[0.5], [1.5], [2.5], [1.5], [0.5]]));
+        assert!(upsized.iter().all(|v| [0.5, 1.5, 2.5].contains(v)));
+
+        // rows are subsampled from the data
+        let downsized = resize_float(data, 3).float().unwrap();
+        assert_eq!(downsized.shape(), &[3, 1]);
+        assert!(downsized.iter().all(|v| [0.5, 1.5, 2.5].contains(v)));
+    }
+
+    #[test]
+    fn test_resize_categorical_float_nan() {
+        let arguments = indexmap::indexmap![
+            "data".into() => arr2(&[[0.5], [f64::NAN]]).into_dyn().into(),
+            "categories".into() => Value::Jagged(Jagged::Float(vec![vec![0.5, 1.5]])),
+            "number_rows".into() => arr1(&[3]).into_dyn().into()
+        ];
+        assert!(proto::Resize {}.evaluate(&None, arguments).is_err());
+    }
+
+    #[test]
+    fn test_resize_float_distributions() {
+        let data = arr2(&[[1.], [2.]]).into_dyn();
+        let (lower, upper) = (arr1(&[0.]).into_dyn(), arr1(&[2.]).into_dyn());
+        let (shift, scale) = (arr1(&[1.5]).into_dyn(), arr1(&[1.]).into_dyn());
+
+        ["uniform", "gaussian", "exponential", "triangular"].iter().for_each(|distribution| {
+            let resized = super::resize_float(
+                data.clone(), super::RowResizeConfig::NumRows(1000), None, distribution,
+                lower.clone(), upper.clone(), Some(shift.clone()), Some(scale.clone()), &None).unwrap();
+            assert_eq!(resized.shape(), &[1000, 1]);
+            // synthetic rows are within the bounds
+            assert!(resized.iter().all(|v| (0. ..=2.).contains(v)), "{} out of bounds", distribution);
+        });
+
+        // the exponential distribution requires a scale
+        assert!(super::resize_float(
+            data, super::RowResizeConfig::NumRows(10), None, "exponential",
+            lower, upper, Some(shift), None, &None).is_err());
+    }
+
+    #[test]
+    fn test_resize_integer_distributions() {
+        let data = arr2(&[[1], [2]]).into_dyn();
+        let (lower, upper) = (arr1(&[0]).into_dyn(), arr1(&[3]).into_dyn());
+        let (shift, scale) = (arr1(&[1.5]).into_dyn(), arr1(&[2.]).into_dyn());
+
+        let imputation = |distribution: &str| super::Imputation {
+            distribution: distribution.to_string(),
+            lower: lower.clone(), upper: upper.clone(),
+            shift: Some(shift.clone()), scale: Some(scale.clone())
+        };
+
+        ["uniform", "gaussian"].iter().for_each(|distribution| {
+            let resized = super::resize_integer(
+                data.clone(), super::RowResizeConfig::NumRows(1000), None,
+                imputation(distribution), &None).unwrap();
+            assert_eq!(resized.shape(), &[1000, 1]);
+            assert!(resized.iter().all(|v| (0..=3).contains(v)), "{} out of bounds", distribution);
+        });
+
+        // only uniform and gaussian are supported for integers
+        assert!(super::resize_integer(
+            data, super::RowResizeConfig::NumRows(10), None,
+            imputation("triangular"), &None).is_err());
+    }
+}
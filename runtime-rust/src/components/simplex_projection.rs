@@ -0,0 +1,94 @@
+use ndarray::ArrayD;
+
+use smartnoise_validator::{proto, Float};
+use smartnoise_validator::base::ReleaseNode;
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+
+impl Evaluable for proto::SimplexProjection {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let mut data: ArrayD<Float> = take_argument(&mut arguments, "data")?.array()?.cast_float()?;
+        if data.ndim() > 2 {
+            return Err("data may not have dimensionality greater than 2".into())
+        }
+
+        data.gencolumns_mut().into_iter().try_for_each(|mut column| {
+            let projected = project_simplex(&column.to_vec())?;
+            column.iter_mut().zip(projected).for_each(|(v, p)| *v = p);
+            Ok::<_, Error>(())
+        })?;
+
+        Ok(ReleaseNode::new(data.into()))
+    }
+}
+
+/// Projects a vector onto the probability simplex.
+///
+/// Returns the vector nearest to `values` in Euclidean distance whose elements are non-negative and sum to one.
+/// The projection shifts every element by a common threshold, and then clamps negative elements to zero.
+/// The threshold is found by sorting, as in
+/// [Duchi et al. (2008)](https://stanford.edu/~jduchi/projects/DuchiShSiCh08.pdf).
+///
+/// # Arguments
+/// * `values` - Vector to project.
+///
+/// # Return
+/// The projection of `values` onto the probability simplex.
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::components::simplex_projection::project_simplex;
+/// let projected = project_simplex(&[0.5, 0.7, -0.1]).unwrap();
+/// assert!((projected[0] - 0.4).abs() < 1e-12 && (projected[1] - 0.6).abs() < 1e-12);
+/// assert_eq!(projected[2], 0.);
+/// ```
+pub fn project_simplex(values: &[Float]) -> Result<Vec<Float>> {
+    if values.is_empty() {
+        return Err("values may not be empty".into())
+    }
+    if values.iter().any(|v| !v.is_finite()) {
+        return Err("values must be finite".into())
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable_by(|l, r| r.partial_cmp(l).unwrap());
+
+    // the threshold is set by the largest prefix of sorted values that remain positive once shifted
+    let mut cumulative_sum = 0.;
+    let mut threshold = 0.;
+    for (i, value) in sorted.into_iter().enumerate() {
+        cumulative_sum += value;
+        let candidate = (cumulative_sum - 1.) / (i + 1) as Float;
+        if value - candidate <= 0. {
+            break
+        }
+        threshold = candidate;
+    }
+
+    Ok(values.iter().map(|v| (v - threshold).max(0.)).collect())
+}
+
+#[cfg(test)]
+mod test_simplex_projection {
+    use crate::components::simplex_projection::project_simplex;
+
+    #[test]
+    fn test_project_simplex() {
+        // points on the simplex are unchanged
+        assert_eq!(project_simplex(&[0.25, 0.75]).unwrap(), vec![0.25, 0.75]);
+        // points off of the simplex are shifted evenly, then clamped
+        assert_eq!(project_simplex(&[1., 1.]).unwrap(), vec![0.5, 0.5]);
+        assert_eq!(project_simplex(&[3., 0., -1.]).unwrap(), vec![1., 0., 0.]);
+        assert_eq!(project_simplex(&[-1., -1., -1., -1.]).unwrap(), vec![0.25; 4]);
+
+        let projected = project_simplex(&[0.3, -0.2, 0.9, 0.4, 0.1]).unwrap();
+        assert!(projected.iter().all(|v| (0. ..=1.).contains(v)));
+        assert!((projected.iter().sum::<f64>() - 1.).abs() < 1e-12);
+
+        assert!(project_simplex(&[]).is_err());
+        assert!(project_simplex(&[f64::NAN]).is_err());
+    }
+}
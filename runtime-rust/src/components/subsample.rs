@@ -0,0 +1,60 @@
+use ndarray::{ArrayD, Axis};
+use smartnoise_validator::base::{Array, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::proto;
+use smartnoise_validator::utilities::take_argument;
+
+use crate::components::Evaluable;
+use crate::components::filter::filter;
+use crate::NodeArguments;
+use crate::utilities::noise::sample_bit_prob;
+
+impl Evaluable for proto::Subsample {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let enforce_constant_time = privacy_definition.as_ref()
+            .map(|v| v.protect_elapsed_time).unwrap_or(false);
+        let proportion = take_argument(&mut arguments, "proportion")?.array()?.first_float()?;
+
+        Ok(ReleaseNode::new(match take_argument(&mut arguments, "data")?.array()? {
+            Array::Str(data) => subsample(data, proportion, enforce_constant_time)?.into(),
+            Array::Float(data) => subsample(data, proportion, enforce_constant_time)?.into(),
+            Array::F32(data) => subsample(data, proportion, enforce_constant_time)?.into(),
+            Array::Int(data) => subsample(data, proportion, enforce_constant_time)?.into(),
+            Array::Bool(data) => subsample(data, proportion, enforce_constant_time)?.into(),
+        }))
+    }
+}
+
+/// Keeps each row of the data independently with probability `proportion`.
+///
+/// # Arguments
+/// * `data` - Data to be subsampled.
+/// * `proportion` - Probability with which each row is kept.
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time.
+///
+/// # Return
+/// The rows of the data that were kept, in their original order.
+///
+/// # Example
+/// ```
+/// use ndarray::arr2;
+/// use smartnoise_runtime::components::subsample::subsample;
+///
+/// let data = arr2(&[ [1, 2], [3, 4], [5, 6] ]).into_dyn();
+/// assert_eq!(subsample(data.clone(), 1., false).unwrap(), data);
+/// assert!(subsample(data, 0.5, false).unwrap().shape()[0] <= 3);
+/// ```
+pub fn subsample<T: Clone + Default>(data: ArrayD<T>, proportion: f64, enforce_constant_time: bool) -> Result<ArrayD<T>> {
+    if !(proportion > 0. && proportion <= 1.) {
+        return Err("proportion: must be within (0, 1]".into())
+    }
+    if data.ndim() == 0 {
+        return Err("data: must be at least one-dimensional".into())
+    }
+
+    let mask = (0..data.len_of(Axis(0)))
+        .map(|_| sample_bit_prob(proportion, enforce_constant_time))
+        .collect::<Result<Vec<bool>>>()?;
+
+    filter(data, ndarray::Array1::from(mask).into_dyn())
+}
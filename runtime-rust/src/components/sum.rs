@@ -1,20 +1,33 @@
 use smartnoise_validator::errors::*;
 
 use crate::NodeArguments;
-use smartnoise_validator::base::{Array, ReleaseNode};
+use smartnoise_validator::base::{Array, IndexKey, ReleaseNode};
 use smartnoise_validator::utilities::{take_argument};
 use crate::components::Evaluable;
 use smartnoise_validator::proto;
-use ndarray::{ArrayD};
-use std::ops::Add;
-use crate::utilities::get_num_columns;
+use ndarray::{ArrayD, Axis};
+use std::ops::{Add, Mul};
+use crate::utilities::{check_overflow, get_num_columns};
+use crate::utilities::nulls::assert_non_null;
 use num::Zero;
 
 impl Evaluable for proto::Sum {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
-        match take_argument(&mut arguments, "data")?.array()? {
-            Array::Float(data) => Ok(sum(&data)?.into()),
-            Array::Int(data) => Ok(sum(&data)?.into()),
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let weights = arguments.remove::<IndexKey>(&"weights".into())
+            .map(|weights| weights.array()).transpose()?;
+
+        match (take_argument(&mut arguments, "data")?.array()?, weights) {
+            (Array::Float(data), weights) => {
+                assert_non_null(&data, "Sum")?;
+                let sums = match weights {
+                    Some(weights) => weighted_sum(&data, &weights.float()?)?,
+                    None => sum(&data)?
+                };
+                check_overflow(privacy_definition, &sums, "Sum")?;
+                Ok(sums.into())
+            },
+            (Array::Int(data), Some(weights)) => Ok(weighted_sum(&data, &weights.int()?)?.into()),
+            (Array::Int(data), None) => Ok(sum(&data)?.into()),
             _ => return Err("data must be either f64 or i64".into())
         }.map(ReleaseNode::new)
     }
@@ -54,3 +67,110 @@ pub fn sum<T: Add<T, Output=T> + Zero + Copy>(data: &ArrayD<T>) -> Result<ArrayD
         Err(_) => Err("unable to package Sum result into an array".into())
     }
 }
+
+/// Calculates the weighted sum for each column of the data.
+///
+/// # Arguments
+/// * `data` - Data for which you would like the weighted sum of each column.
+/// * `weights` - One weight for each record.
+///
+/// # Return
+/// Weighted sum of each column of the data.
+///
+/// # Example
+/// ```
+/// use ndarray::prelude::*;
+/// use smartnoise_runtime::components::sum::weighted_sum;
+/// let data = arr2(&[ [1.,10.], [2., 20.], [3., 30.] ]).into_dyn();
+/// let weights = arr1(&[1., 0., 2.]).into_dyn();
+/// let sums = weighted_sum(&data, &weights).unwrap();
+/// assert!(sums == arr2(&[[7., 70.]]).into_dyn());
+/// ```
+pub fn weighted_sum<T: Add<T, Output=T> + Mul<T, Output=T> + Zero + Copy>(
+    data: &ArrayD<T>, weights: &ArrayD<T>
+) -> Result<ArrayD<T>> {
+    if data.len_of(Axis(0)) != weights.len() {
+        return Err("weights must have one weight for each record".into())
+    }
+
+    // iterate over the generalized columns
+    let sums = data.gencolumns().into_iter()
+        .map(|column| column.iter().zip(weights.iter())
+            .fold(T::zero(), |sum, (v, w)| sum + *v * *w))
+        .collect::<Vec<T>>();
+
+    let array = match data.ndim() {
+        1 => ndarray::Array::from_shape_vec(vec![], sums),
+        2 => ndarray::Array::from_shape_vec(vec![1, get_num_columns(data)? as usize], sums),
+        _ => return Err("invalid data shape for Sum".into())
+    };
+
+    match array {
+        Ok(array) => Ok(array),
+        Err(_) => Err("unable to package Sum result into an array".into())
+    }
+}
+
+
+#[cfg(test)]
+pub mod test_sum {
+    use ndarray::{arr1, arr2};
+
+    use smartnoise_validator::base::Value;
+    use smartnoise_validator::{proto, Float};
+
+    use crate::components::Evaluable;
+    use crate::NodeArguments;
+
+    pub fn privacy_definition(protect_overflow: bool) -> Option<proto::PrivacyDefinition> {
+        Some(proto::PrivacyDefinition {
+            group_size: 1,
+            neighboring: proto::privacy_definition::Neighboring::AddRemove as i32,
+            strict_parameter_checks: false,
+            protect_overflow,
+            protect_elapsed_time: false,
+            protect_memory_utilization: false,
+            protect_floating_point: false,
+            protect_sensitivity: true,
+            candidate_spacing_ratio: 0.,
+            default_mechanism: proto::privacy_definition::MechanismPreference::Pure as i32,
+        })
+    }
+
+    pub fn arguments(data: Value) -> NodeArguments {
+        let mut arguments = NodeArguments::new();
+        arguments.insert("data".into(), data);
+        arguments
+    }
+
+    pub fn arguments_overflow() -> NodeArguments {
+        arguments(arr2(&[[Float::MAX, 1.], [Float::MAX, 2.]]).into_dyn().into())
+    }
+
+    #[test]
+    fn test_sum_overflow() {
+        let component = proto::Sum {};
+
+        let error = component.evaluate(&privacy_definition(true), arguments_overflow()).unwrap_err();
+        assert!(error.to_string().contains("not finite"));
+
+        // without protections, the overflowed sum is released
+        let release = component.evaluate(&privacy_definition(false), arguments_overflow()).unwrap();
+        let sums = release.value.array().unwrap().float().unwrap();
+        assert!(sums[[0, 0]].is_infinite());
+        assert_eq!(sums[[0, 1]], 3.);
+    }
+
+    #[test]
+    fn test_weighted_sum() {
+        let mut arguments = arguments(arr2(&[[1, 10], [2, 20], [3, 30]]).into_dyn().into());
+        arguments.insert("weights".into(), arr1(&[3, 0, 1]).into_dyn().into());
+        let release = proto::Sum {}.evaluate(&privacy_definition(true), arguments).unwrap();
+        assert_eq!(release.value.array().unwrap().int().unwrap(), arr2(&[[6, 60]]).into_dyn());
+
+        // weights must be given for every record
+        let mut arguments = arguments_overflow();
+        arguments.insert("weights".into(), arr1(&[1.]).into_dyn().into());
+        assert!(proto::Sum {}.evaluate(&privacy_definition(false), arguments).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use smartnoise_validator::errors::*;
+
+use crate::NodeArguments;
+use smartnoise_validator::base::{Array, ReleaseNode};
+use smartnoise_validator::utilities::{take_argument};
+use crate::components::Evaluable;
+use smartnoise_validator::{proto, Integer};
+use ndarray::{ArrayD};
+use std::ops::{Add, Mul};
+use crate::utilities::check_overflow;
+use crate::components::sum::sum;
+use num::Zero;
+
+impl Evaluable for proto::SumOfSquares {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        match take_argument(&mut arguments, "data")?.array()? {
+            Array::Float(data) => {
+                let sums = sum_of_squares(&data)?;
+                check_overflow(privacy_definition, &sums, "SumOfSquares")?;
+                Ok(sums.into())
+            },
+            Array::Int(data) => {
+                // squares of integers may overflow, even when their sum would not
+                if data.iter().any(|v| v.checked_mul(*v).is_none()) {
+                    return Err("SumOfSquares: the square of the data overflows".into())
+                }
+                Ok(sum_of_squares::<Integer>(&data)?.into())
+            },
+            _ => return Err("data must be either f64 or i64".into())
+        }.map(ReleaseNode::new)
+    }
+}
+
+/// Calculates the sum of squares for each column of the data.
+///
+/// # Arguments
+/// * `data` - Data for which you would like the sum of squares of each column.
+///
+/// # Return
+/// Sum of squares of each column of the data.
+///
+/// # Example
+/// ```
+/// use ndarray::prelude::*;
+/// use smartnoise_runtime::components::sum_of_squares::sum_of_squares;
+/// let data = arr2(&[ [1.,10.], [2., 20.], [3., 30.] ]).into_dyn();
+/// let sums = sum_of_squares(&data).unwrap();
+/// assert!(sums == arr2(&[[14., 1400.]]).into_dyn());
+/// ```
+pub fn sum_of_squares<T: Add<T, Output=T> + Mul<T, Output=T> + Zero + Copy>(data: &ArrayD<T>) -> Result<ArrayD<T>> {
+    sum(&data.mapv(|v| v * v))
+}
+
+
+#[cfg(test)]
+pub mod test_sum_of_squares {
+    use ndarray::{arr1, arr2};
+
+    use smartnoise_validator::{proto, Float, Integer};
+
+    use crate::components::Evaluable;
+    use crate::components::sum::test_sum::{arguments, privacy_definition};
+
+    #[test]
+    fn test_reference() {
+        let component = proto::SumOfSquares {};
+        let data = arr2(&[[-1.5, 2.], [0.5, -3.], [2., 0.]]).into_dyn();
+
+        let release = component.evaluate(&privacy_definition(true), arguments(data.clone().into())).unwrap();
+        let sums = release.value.array().unwrap().float().unwrap();
+        // reference sums of squares, column by column
+        let reference = data.gencolumns().into_iter()
+            .map(|column| column.iter().map(|v| v * v).sum::<Float>())
+            .collect::<Vec<Float>>();
+        assert_eq!(sums.shape(), &[1, 2]);
+        assert_eq!(sums.iter().cloned().collect::<Vec<Float>>(), reference);
+
+        let release = component.evaluate(&privacy_definition(true), arguments(arr1(&[-3, 4]).into_dyn().into())).unwrap();
+        assert_eq!(release.value.array().unwrap().first_int().unwrap(), 25);
+
+        assert!(component.evaluate(&privacy_definition(true), arguments(arr1(&[Integer::MAX]).into_dyn().into())).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use indexmap::IndexMap;
+use ndarray::{Array1, ArrayD};
+
+use smartnoise_validator::{Float, proto};
+use smartnoise_validator::base::{IndexKey, Jagged, ReleaseNode, Value};
+use smartnoise_validator::components::synthesize_from_marginals::is_reserved;
+use smartnoise_validator::errors::*;
+use smartnoise_validator::utilities::prepend;
+
+use crate::components::Evaluable;
+use crate::NodeArguments;
+use crate::utilities::sample_from_set;
+
+impl Evaluable for proto::SynthesizeFromMarginals {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, arguments: NodeArguments) -> Result<ReleaseNode> {
+        let enforce_constant_time = privacy_definition.as_ref()
+            .map(|v| v.protect_elapsed_time).unwrap_or(false);
+
+        // arguments are read in place, because the order of the marginals is aligned with the categories
+        let categories = arguments.get::<IndexKey>(&"categories".into())
+            .ok_or_else(|| Error::from("categories: missing"))?.ref_jagged()?.clone();
+        let number_rows = arguments.get::<IndexKey>(&"number_rows".into())
+            .ok_or_else(|| Error::from("number_rows: missing"))?.ref_array()?.first_int()? as usize;
+
+        let marginals = arguments.into_iter()
+            .filter(|(name, _)| !is_reserved(name))
+            .collect::<Vec<(IndexKey, Value)>>();
+        if categories.num_columns() != marginals.len() as i64 {
+            return Err("categories: must contain one set of categories for each marginal".into())
+        }
+
+        Ok(ReleaseNode::new(Value::Dataframe(marginals.into_iter().enumerate()
+            .map(|(index, (name, counts))| {
+                let counts = counts.array()?.cast_float()?;
+                let counts = counts.iter().copied().collect::<Vec<Float>>();
+                let column: Value = match &categories {
+                    Jagged::Bool(categories) => synthesize_column(&categories[index], &counts, number_rows, enforce_constant_time)?.into(),
+                    Jagged::Int(categories) => synthesize_column(&categories[index], &counts, number_rows, enforce_constant_time)?.into(),
+                    Jagged::Float(categories) => synthesize_column(&categories[index], &counts, number_rows, enforce_constant_time)?.into(),
+                    Jagged::Str(categories) => synthesize_column(&categories[index], &counts, number_rows, enforce_constant_time)?.into(),
+                };
+                Ok((name, column))
+            })
+            .collect::<Result<IndexMap<IndexKey, Value>>>()?)))
+    }
+}
+
+/// Sample a synthetic column from the released counts of each category.
+///
+/// Each record is sampled independently, with probability proportional to the count of its category.
+/// Negative counts, which may be introduced by noise, are set to zero.
+///
+/// # Arguments
+/// * `categories` - Categories of the synthetic column.
+/// * `counts` - Released count of each category.
+/// * `number_rows` - Number of records to sample.
+/// * `enforce_constant_time` - Whether to force the sampling to run in constant time.
+///
+/// # Return
+/// Synthetic column of `number_rows` records.
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::components::synthesize_from_marginals::synthesize_column;
+/// let column = synthesize_column(&["a", "b"], &[0., 10.], 5, false).unwrap();
+/// assert!(column.iter().all(|v| *v == "b"));
+///
+/// // a noisy count may be negative
+/// let column = synthesize_column(&[1, 2], &[-3., 4.], 5, false).unwrap();
+/// assert!(column.iter().all(|v| *v == 2));
+/// ```
+pub fn synthesize_column<T: Clone>(
+    categories: &[T], counts: &[Float], number_rows: usize, enforce_constant_time: bool,
+) -> Result<ArrayD<T>> {
+    if categories.len() != counts.len() {
+        return Err(format!("counts: has {} counts, but {} categories", counts.len(), categories.len()).into())
+    }
+    if counts.iter().any(|v| v.is_nan()) {
+        return Err("counts: may not contain nulls".into())
+    }
+    let weights = counts.iter().map(|count| count.max(0.)).collect::<Vec<Float>>();
+    if weights.iter().all(|weight| *weight == 0.) {
+        return Err("counts: at least one count must be positive".into())
+    }
+
+    Ok(Array1::from((0..number_rows)
+        .map(|_| sample_from_set(categories, &weights, enforce_constant_time))
+        .collect::<Result<Vec<T>>>()
+        .map_err(prepend("counts:"))?).into_dyn())
+}
+
+#[cfg(test)]
+mod test_synthesize_from_marginals {
+    use indexmap::indexmap;
+    use ndarray::arr1;
+
+    use smartnoise_validator::base::{IndexKey, Jagged, Value};
+    use smartnoise_validator::proto;
+
+    use crate::components::Evaluable;
+
+    #[test]
+    fn test_marginals() {
+        let n = 10_000;
+        let arguments = indexmap![
+            IndexKey::from("categories") => Value::Jagged(Jagged::Str(vec![
+                vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                vec!["x".to_string(), "y".to_string()]])),
+            IndexKey::from("number_rows") => Value::from(n as i64),
+            IndexKey::from("letter") => arr1(&[50., 30., 20.]).into_dyn().into(),
+            IndexKey::from("axis") => arr1(&[-4, 90]).into_dyn().into()];
+
+        let release = proto::SynthesizeFromMarginals {}.evaluate(&None, arguments).unwrap();
+        let synthetic = match release.value {
+            Value::Dataframe(synthetic) => synthetic,
+            _ => panic!("synthetic data must be a dataframe")
+        };
+        assert_eq!(synthetic.keys().cloned().collect::<Vec<IndexKey>>(),
+                   vec![IndexKey::from("letter"), IndexKey::from("axis")]);
+
+        // the proportions of the synthetic data approximate the normalized counts
+        let proportion = |name: &str, category: &str| {
+            let column = synthetic.get(&IndexKey::from(name)).unwrap().ref_array().unwrap().ref_string().unwrap();
+            assert_eq!(column.len(), n);
+            column.iter().filter(|v| *v == category).count() as f64 / n as f64
+        };
+        assert!((proportion("letter", "a") - 0.5).abs() < 0.02);
+        assert!((proportion("letter", "b") - 0.3).abs() < 0.02);
+        assert!((proportion("letter", "c") - 0.2).abs() < 0.02);
+        // the negative count is treated as zero
+        assert_eq!(proportion("axis", "x"), 0.);
+    }
+}
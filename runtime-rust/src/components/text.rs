@@ -0,0 +1,91 @@
+use smartnoise_validator::errors::*;
+
+use crate::NodeArguments;
+use smartnoise_validator::base::ReleaseNode;
+use crate::components::Evaluable;
+use ndarray::ArrayD;
+use smartnoise_validator::{proto, Integer};
+use smartnoise_validator::utilities::take_argument;
+
+
+impl Evaluable for proto::StringLength {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.string()?;
+        Ok(ReleaseNode::new(string_length(&data).into()))
+    }
+}
+
+impl Evaluable for proto::Substring {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.string()?;
+        Ok(ReleaseNode::new(substring(&data, self.start as usize, self.length as usize).into()))
+    }
+}
+
+/// Counts the characters in each string.
+///
+/// Characters are unicode scalar values, so multibyte characters are counted once.
+///
+/// # Arguments
+/// * `data` - Strings to measure.
+///
+/// # Return
+/// Number of characters in each string.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_runtime::components::text::string_length;
+///
+/// let data = arr1(&["abc", "né", ""]).mapv(String::from).into_dyn();
+/// assert_eq!(string_length(&data), arr1(&[3, 2, 0]).into_dyn());
+/// ```
+pub fn string_length(data: &ArrayD<String>) -> ArrayD<Integer> {
+    data.mapv(|v| v.chars().count() as Integer)
+}
+
+/// Selects a range of characters from each string.
+///
+/// Indices past the end of a string are ignored, so strings shorter than `start` become empty.
+///
+/// # Arguments
+/// * `data` - Strings to select from.
+/// * `start` - Index of the first character to keep.
+/// * `length` - Maximum number of characters to keep.
+///
+/// # Return
+/// Characters in `[start, start + length)` of each string.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_runtime::components::text::substring;
+///
+/// let data = arr1(&["abcdef", "né", "x"]).mapv(String::from).into_dyn();
+/// let selected = substring(&data, 1, 3);
+/// assert_eq!(selected, arr1(&["bcd", "é", ""]).mapv(String::from).into_dyn());
+/// ```
+pub fn substring(data: &ArrayD<String>, start: usize, length: usize) -> ArrayD<String> {
+    data.mapv(|v| v.chars().skip(start).take(length).collect())
+}
+
+#[cfg(test)]
+mod test_text {
+    use ndarray::arr2;
+
+    use crate::components::text::{string_length, substring};
+
+    #[test]
+    fn test_multibyte() {
+        let data = arr2(&[
+            ["日本語", "ü"],
+            ["🦀rust", ""],
+        ]).mapv(String::from).into_dyn();
+
+        assert_eq!(string_length(&data), arr2(&[[3, 1], [5, 0]]).into_dyn());
+        assert_eq!(substring(&data, 1, 2),
+                   arr2(&[["本語", ""], ["ru", ""]]).mapv(String::from).into_dyn());
+        // ranges past the end of every string are empty
+        assert!(substring(&data, 10, 5).iter().all(String::is_empty));
+    }
+}
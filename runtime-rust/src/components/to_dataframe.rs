@@ -32,6 +32,12 @@ impl Evaluable for proto::ToDataframe {
                     .map(|(idx, name)| Ok((name.to_string().into(), get_ith_column(&standardized, idx)?.into())))
                     .collect::<Result<IndexMap<IndexKey, Value>>>()?
             }
+            Array::F32(array) => {
+                let standardized = standardize_columns(array, num_columns)?;
+                column_names.into_iter().enumerate()
+                    .map(|(idx, name)| Ok((name.to_string().into(), get_ith_column(&standardized, idx)?.into())))
+                    .collect::<Result<IndexMap<IndexKey, Value>>>()?
+            }
             Array::Int(array) => {
                 let standardized = standardize_columns(array, num_columns)?;
                 column_names.into_iter().enumerate()
@@ -53,14 +53,30 @@ impl Evaluable for proto::And {
     }
 }
 
+impl Evaluable for proto::Ceil {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(Float::ceil).into()))
+    }
+}
+
 impl Evaluable for proto::Divide {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
             (Value::Array(left), Value::Array(right)) => match (left, right) {
+                // float quotients with a zero denominator are null
                 (Array::Float(x), Array::Float(y)) =>
-                    Ok(broadcast_map(x, y, &|l, r| l / r)?.into()),
-                (Array::Int(x), Array::Int(y)) =>
-                    Ok(broadcast_map(x, y, &|l, r| l / r)?.into()),
+                    Ok(broadcast_map(x, y, &|l: &Float, r: &Float| if *r == 0. { Float::NAN } else { l / r })?.into()),
+                (Array::Int(x), Array::Int(y)) => {
+                    if y.iter().any(|v| v == &0) {
+                        return Err("Divide: integer divisor may not be zero".into())
+                    }
+                    let quotients = broadcast_map(x, y, &|l: &Integer, r: &Integer| l.checked_div(*r))?;
+                    if quotients.iter().any(Option::is_none) {
+                        return Err("Divide: integer quotient overflowed".into())
+                    }
+                    Ok(quotients.mapv(Option::unwrap).into())
+                }
                 _ => Err("Divide: Either the argument types are mismatched or non-numeric.".into())
             },
             _ => Err("Divide: Both arguments must be arrays.".into())
@@ -88,6 +104,39 @@ impl Evaluable for proto::Equal {
     }
 }
 
+impl Evaluable for proto::Exp {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(Float::exp).into()))
+    }
+}
+
+impl Evaluable for proto::Floor {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(Float::floor).into()))
+    }
+}
+
+impl Evaluable for proto::FloorDivide {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
+            (Value::Array(left), Value::Array(right)) => match (left, right) {
+                (Array::Float(x), Array::Float(y)) =>
+                    Ok(broadcast_map(x, y, &|l: &Float, r: &Float| (l / r).floor())?.into()),
+                (Array::Int(x), Array::Int(y)) => {
+                    if y.iter().any(|v| v == &0) {
+                        return Err("FloorDivide: divisor may not be zero".into())
+                    }
+                    Ok(broadcast_map(x, y, &|l: &Integer, r: &Integer| num::Integer::div_floor(l, r))?.into())
+                }
+                _ => Err("FloorDivide: Either the argument types are mismatched or non-numeric.".into())
+            },
+            _ => Err("FloorDivide: Both arguments must be arrays.".into())
+        }.map(ReleaseNode::new)
+    }
+}
+
 impl Evaluable for proto::GreaterThan {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
@@ -134,6 +183,9 @@ impl Evaluable for proto::Modulo {
                 (Array::Float(x), Array::Float(y)) =>
                     Ok(broadcast_map(x, y, &|l: &Float, r: &Float| l.rem_euclid(*r))?.into()),
                 (Array::Int(x), Array::Int(y)) => {
+                    if y.iter().any(|v| v == &0) {
+                        return Err("Modulo: divisor may not be zero".into())
+                    }
                     Ok(broadcast_map(x, y, &|l: &Integer, r: &Integer| l.rem_euclid(*r))?.into())
                 }
                 _ => Err("Modulo: Either the argument types are mismatched or non-numeric.".into())
@@ -212,6 +264,13 @@ impl Evaluable for proto::Power {
     }
 }
 
+impl Evaluable for proto::Round {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(Float::round).into()))
+    }
+}
+
 impl Evaluable for proto::RowMax {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
@@ -242,6 +301,13 @@ impl Evaluable for proto::RowMin {
     }
 }
 
+impl Evaluable for proto::Sqrt {
+    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let data = take_argument(&mut arguments, "data")?.array()?.float()?;
+        Ok(ReleaseNode::new(data.mapv(Float::sqrt).into()))
+    }
+}
+
 impl Evaluable for proto::Subtract {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         match (take_argument(&mut arguments, "left")?, take_argument(&mut arguments, "right")?) {
@@ -256,3 +322,70 @@ impl Evaluable for proto::Subtract {
         }.map(ReleaseNode::new)
     }
 }
+
+
+#[cfg(test)]
+mod test_transforms {
+    use ndarray::arr1;
+    use smartnoise_validator::base::Value;
+    use smartnoise_validator::proto;
+
+    use crate::components::Evaluable;
+
+    fn evaluate(component: &dyn Evaluable, data: Vec<f64>) -> Vec<f64> {
+        let arguments = indexmap::indexmap!["data".into() => Value::from(arr1(&data).into_dyn())];
+        component.evaluate(&None, arguments).unwrap()
+            .value.array().unwrap().float().unwrap().into_raw_vec()
+    }
+
+    #[test]
+    fn test_unary() {
+        let data = vec![-1.5, -0.4, 0.5, 2.];
+        assert_eq!(evaluate(&proto::Ceil {}, data.clone()), vec![-1., -0., 1., 2.]);
+        assert_eq!(evaluate(&proto::Floor {}, data.clone()), vec![-2., -1., 0., 2.]);
+        assert_eq!(evaluate(&proto::Round {}, data.clone()), vec![-2., -0., 1., 2.]);
+        assert_eq!(evaluate(&proto::Exp {}, vec![0., 1.]), vec![1., 1f64.exp()]);
+
+        let sqrt = evaluate(&proto::Sqrt {}, data);
+        assert!(sqrt[0].is_nan() && sqrt[1].is_nan());
+        assert_eq!(sqrt[2..], [0.5f64.sqrt(), 2f64.sqrt()]);
+    }
+
+    #[test]
+    fn test_integer_division() {
+        let divide = |component: &dyn Evaluable, divisor: i64| component.evaluate(&None, indexmap::indexmap![
+            "left".into() => Value::from(arr1(&[-7, -1, 0, 5]).into_dyn()),
+            "right".into() => Value::from(arr1(&[divisor]).into_dyn())
+        ]).map(|release| release.value.array().unwrap().int().unwrap().into_raw_vec());
+
+        assert_eq!(divide(&proto::Modulo {}, 3).unwrap(), vec![2, 2, 0, 2]);
+        assert_eq!(divide(&proto::Modulo {}, -3).unwrap(), vec![2, 2, 0, 2]);
+        assert_eq!(divide(&proto::FloorDivide {}, 3).unwrap(), vec![-3, -1, 0, 1]);
+        assert_eq!(divide(&proto::FloorDivide {}, -3).unwrap(), vec![2, 0, 0, -2]);
+        assert!(divide(&proto::Modulo {}, 0).is_err());
+        assert!(divide(&proto::FloorDivide {}, 0).is_err());
+
+        // division truncates toward zero
+        assert_eq!(divide(&proto::Divide {}, 3).unwrap(), vec![-2, 0, 0, 1]);
+        assert!(divide(&proto::Divide {}, 0).is_err());
+
+        // the quotient of the smallest integer by negative one is not representable
+        let overflow = proto::Divide {}.evaluate(&None, indexmap::indexmap![
+            "left".into() => Value::from(arr1(&[i64::MIN]).into_dyn()),
+            "right".into() => Value::from(arr1(&[-1]).into_dyn())
+        ]);
+        assert!(overflow.is_err());
+    }
+
+    #[test]
+    fn test_float_division() {
+        let quotient = proto::Divide {}.evaluate(&None, indexmap::indexmap![
+            "left".into() => Value::from(arr1(&[1., -1., 0., 3.]).into_dyn()),
+            "right".into() => Value::from(arr1(&[2., 0., 0., -0.]).into_dyn())
+        ]).unwrap().value.array().unwrap().float().unwrap().into_raw_vec();
+
+        // a zero denominator gives a null, rather than an infinity
+        assert_eq!(quotient[0], 0.5);
+        assert!(quotient[1..].iter().all(|v| v.is_nan()), "{:?}", quotient);
+    }
+}
@@ -13,42 +13,69 @@ use crate::utilities::to_nd;
 impl Evaluable for proto::Union {
     fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, arguments: NodeArguments) -> Result<ReleaseNode> {
 
-        if !self.flatten {
+        if !self.flatten && !self.by_column {
             return Ok(ReleaseNode::new(Value::Partitions(arguments)))
         }
 
+        // arrays are either concatenated row-wise or column-wise
+        let axis = Axis(if self.by_column { 1 } else { 0 });
+
         let arrays = arguments.into_iter()
             .map(|(_, v)| v.array()).collect::<Result<Vec<Array>>>()?;
 
         Ok(ReleaseNode::new(match arrays.first().ok_or_else(|| "must have at least one partition")? {
-            Array::Float(_) => {
+            Array::Float(_) | Array::F32(_) => {
                 let inputs = arrays.into_iter()
                     .map(|v| v.float().and_then(|v| to_nd(v, 2)))
                     .collect::<Result<Vec<ndarray::ArrayD<Float>>>>()?;
-                stack(Axis(0), &inputs.iter().map(|v| v.view())
+                stack(axis, &inputs.iter().map(|v| v.view())
                     .collect::<Vec<ArrayViewD<Float>>>())?.into()
             },
             Array::Int(_) => {
                 let inputs = arrays.into_iter()
                     .map(|v| v.int().and_then(|v| to_nd(v, 2)))
                     .collect::<Result<Vec<ndarray::ArrayD<Integer>>>>()?;
-                stack(Axis(0), &inputs.iter().map(|v| v.view())
+                stack(axis, &inputs.iter().map(|v| v.view())
                     .collect::<Vec<ArrayViewD<Integer>>>())?.into()
             },
             Array::Bool(_) => {
                 let inputs = arrays.into_iter()
                     .map(|v| v.bool().and_then(|v| to_nd(v, 2)))
                     .collect::<Result<Vec<ndarray::ArrayD<bool>>>>()?;
-                stack(Axis(0), &inputs.iter().map(|v| v.view())
+                stack(axis, &inputs.iter().map(|v| v.view())
                     .collect::<Vec<ArrayViewD<bool>>>())?.into()
             },
             Array::Str(_) => {
                 let inputs = arrays.into_iter()
                     .map(|v| v.string().and_then(|v| to_nd(v, 2)))
                     .collect::<Result<Vec<ndarray::ArrayD<String>>>>()?;
-                slow_stack(Axis(0), &inputs.iter().map(|v| v.view())
+                slow_stack(axis, &inputs.iter().map(|v| v.view())
                     .collect::<Vec<ArrayViewD<String>>>())?.into()
             }
         }))
     }
 }
+
+#[cfg(test)]
+mod test_union {
+    use ndarray::{arr1, arr2};
+
+    use smartnoise_validator::base::{IndexKey, Value};
+    use smartnoise_validator::proto;
+
+    use crate::components::Evaluable;
+    use crate::NodeArguments;
+
+    #[test]
+    fn test_by_column() {
+        let arguments: NodeArguments = indexmap::indexmap![
+            IndexKey::from(0) => Value::from(arr1(&[1., 2., 3.]).into_dyn()),
+            IndexKey::from(1) => Value::from(arr2(&[[4.], [5.], [6.]]).into_dyn())
+        ];
+        let union = proto::Union { flatten: false, by_column: true }
+            .evaluate(&None, arguments).unwrap();
+
+        assert_eq!(union.value.array().unwrap().float().unwrap(),
+                   arr2(&[[1., 4.], [2., 5.], [3., 6.]]).into_dyn());
+    }
+}
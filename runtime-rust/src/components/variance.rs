@@ -5,17 +5,19 @@ use smartnoise_validator::base::ReleaseNode;
 use smartnoise_validator::utilities::take_argument;
 use crate::components::Evaluable;
 use ndarray::{ArrayD, Array};
-use crate::utilities::get_num_columns;
+use crate::utilities::{check_overflow, get_num_columns};
 use smartnoise_validator::{proto, Float};
 use crate::components::mean::mean;
 
 impl Evaluable for proto::Variance {
-    fn evaluate(&self, _privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
         let delta_degrees_of_freedom = if self.finite_sample_correction { 1 } else { 0 } as usize;
-        Ok(ReleaseNode::new(variance(
+        let variances = variance(
             &take_argument(&mut arguments, "data")?.array()?.float()?,
             delta_degrees_of_freedom
-        )?.into()))
+        )?;
+        check_overflow(privacy_definition, &variances, "Variance")?;
+        Ok(ReleaseNode::new(variances.into()))
     }
 }
 
@@ -56,4 +58,22 @@ pub fn variance(data: &ArrayD<Float>, delta_degrees_of_freedom: usize) -> Result
         Ok(array) => Ok(array),
         Err(_) => Err("unable to package Variance result into an array".into())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_variance {
+    use ndarray::arr2;
+    use smartnoise_validator::{proto, Float};
+
+    use crate::components::Evaluable;
+    use crate::components::sum::test_sum::{arguments, privacy_definition};
+
+    #[test]
+    fn test_variance_overflow() {
+        // the mean is finite, but the squared deviations overflow
+        let data = arr2(&[[Float::MAX], [-Float::MAX]]).into_dyn();
+        let error = proto::Variance { finite_sample_correction: false }
+            .evaluate(&privacy_definition(true), arguments(data.into())).unwrap_err();
+        assert!(error.to_string().contains("not finite"));
+    }
+}
@@ -0,0 +1,59 @@
+use ndarray::{ArrayD, Axis};
+use smartnoise_validator::base::{Array, ReleaseNode};
+use smartnoise_validator::errors::*;
+use smartnoise_validator::proto;
+use smartnoise_validator::utilities::{array::slow_select, take_argument};
+
+use crate::components::Evaluable;
+use crate::components::resize::create_weighted_sampling_indices;
+use crate::NodeArguments;
+
+impl Evaluable for proto::WeightedSample {
+    fn evaluate(&self, privacy_definition: &Option<proto::PrivacyDefinition>, mut arguments: NodeArguments) -> Result<ReleaseNode> {
+        let enforce_constant_time = privacy_definition.as_ref()
+            .map(|v| v.protect_elapsed_time).unwrap_or(false);
+        let weights = take_argument(&mut arguments, "weights")?.array()?.cast_float()?
+            .iter().copied().collect::<Vec<f64>>();
+        let size = take_argument(&mut arguments, "size")?.array()?.first_int()?;
+
+        Ok(ReleaseNode::new(match take_argument(&mut arguments, "data")?.array()? {
+            Array::Str(data) => weighted_sample(data, &weights, size, enforce_constant_time)?.into(),
+            Array::Float(data) => weighted_sample(data, &weights, size, enforce_constant_time)?.into(),
+            Array::F32(data) => weighted_sample(data, &weights, size, enforce_constant_time)?.into(),
+            Array::Int(data) => weighted_sample(data, &weights, size, enforce_constant_time)?.into(),
+            Array::Bool(data) => weighted_sample(data, &weights, size, enforce_constant_time)?.into(),
+        }))
+    }
+}
+
+/// Draws `size` rows of the data without replacement, with probability proportional to their weights.
+///
+/// # Arguments
+/// * `data` - Data to be sampled.
+/// * `weights` - Non-negative weight of each row.
+/// * `size` - Number of rows to draw.
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time.
+///
+/// # Return
+/// The rows of the data that were drawn, in the order they were drawn.
+///
+/// # Example
+/// ```
+/// use ndarray::arr2;
+/// use smartnoise_runtime::components::weighted_sample::weighted_sample;
+///
+/// let data = arr2(&[ [1, 2], [3, 4], [5, 6] ]).into_dyn();
+/// let sample = weighted_sample(data, &[1., 0., 1.], 2, false).unwrap();
+/// assert_eq!(sample.shape(), &[2, 2]);
+/// assert!(!sample.iter().any(|v| *v == 3));
+/// ```
+pub fn weighted_sample<T: Clone + Default>(data: ArrayD<T>, weights: &[f64], size: i64, enforce_constant_time: bool) -> Result<ArrayD<T>> {
+    if data.ndim() == 0 {
+        return Err("data: must be at least one-dimensional".into())
+    }
+    if weights.len() != data.len_of(Axis(0)) {
+        return Err("weights: must have one weight for each record".into())
+    }
+
+    Ok(slow_select(&data, Axis(0), &create_weighted_sampling_indices(size, weights, enforce_constant_time)?))
+}
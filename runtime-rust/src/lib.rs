@@ -21,8 +21,10 @@ pub mod base;
 use std::collections::{HashMap, HashSet};
 use std::vec::Vec;
 
-use smartnoise_validator::base::{Value, ReleaseNode, Release, IndexKey, ComponentExpansion, ValueProperties};
+use smartnoise_validator::base::{Value, ReleaseNode, Release, IndexKey, ComponentExpansion, ValueProperties, DataType};
+use smartnoise_validator::{Float, Integer};
 use smartnoise_validator::utilities::{get_sinks, get_input_properties, get_dependents};
+use smartnoise_validator::utilities::privacy::max_privacy_usage;
 
 use crate::components::Evaluable;
 
@@ -78,6 +80,9 @@ pub fn release(
     // for if the filtering level is set to retain values
     let original_ids: HashSet<u32> = HashSet::from_iter(release.keys().cloned());
 
+    // nodes supplied by the user, rather than inserted by an expansion
+    let user_node_ids: HashSet<u32> = HashSet::from_iter(computation_graph.keys().cloned());
+
     // track node parents. Each key is a node id, and the value is the set of node ids that use it
     let mut parents = get_dependents(&computation_graph);
 
@@ -158,7 +163,10 @@ pub fn release(
         computation_graph.extend(expansion.computation_graph);
         properties.extend(expansion.properties);
         release.extend(expansion.releases);
-        warnings.extend(expansion.warnings);
+        // warnings about expanded nodes are not actionable by the user
+        if user_node_ids.contains(&component_id) {
+            warnings.extend(expansion.warnings);
+        }
 
         // if nodes were added to the traversal, then evaluate the new nodes first
         if !expansion.traversal.is_empty() {
@@ -257,3 +265,1552 @@ pub fn release(
 
     Ok((release, warnings))
 }
+
+/// Execute the computation, and return only the nodes from one submission
+///
+/// In an interactive session, each query is added to the analysis as a new submission.
+/// Filtering the release by submission avoids returning the releases of all prior queries with each query.
+///
+/// # Arguments
+/// * `analysis` - a computational graph and definition of privacy, in prost protobuf format
+/// * `release` - a collection of precomputed values for components in the graph
+/// * `filter_level` - configure the amount of information included in the return
+/// * `submission` - the submission of the components whose nodes are returned
+///
+/// # Return
+/// a collection of computed values for components in the graph from the given submission
+pub fn release_submission(
+    privacy_definition: Option<proto::PrivacyDefinition>,
+    computation_graph: HashMap<u32, proto::Component>,
+    release: Release,
+    filter_level: proto::FilterLevel,
+    submission: u32
+) -> Result<(Release, Vec<Error>)> {
+    let submissions = computation_graph.iter()
+        .map(|(node_id, component)| (*node_id, component.submission))
+        .collect::<HashMap<u32, u32>>();
+
+    let (mut release, warnings) = self::release(
+        privacy_definition, computation_graph, release, filter_level)?;
+
+    // nodes that are not in the analysis do not belong to any submission
+    release.retain(|node_id, _| submissions.get(node_id) == Some(&submission));
+
+    Ok((release, warnings))
+}
+
+/// Estimate the resources used to evaluate each node, without evaluating the analysis
+///
+/// Output sizes are derived from the statically propagated properties of each node,
+/// as the number of records times the number of columns times the size of the atomic type.
+/// Strings are counted by the size of their handle, so the memory of string data is underestimated.
+/// Nodes whose output size depends on the data, like the output of Materialize or Filter, are not estimated,
+/// until a component like Resize fixes the number of records.
+///
+/// # Arguments
+/// * `analysis` - a computational graph and definition of privacy, in prost protobuf format
+/// * `release` - a collection of precomputed values for components in the graph
+///
+/// # Return
+/// estimates of the memory and number of elements read by each node, where they are known
+pub fn estimate_resources(
+    privacy_definition: Option<proto::PrivacyDefinition>,
+    computation_graph: HashMap<u32, proto::Component>,
+    release: Release
+) -> Result<(proto::ResourceEstimates, Vec<Error>)> {
+    let arguments = computation_graph.iter()
+        .map(|(node_id, component)| (*node_id, component.arguments().values().copied().collect()))
+        .collect::<HashMap<u32, Vec<u32>>>();
+
+    let (properties, warnings) = smartnoise_validator::get_properties(
+        privacy_definition, computation_graph, release, Vec::new())?;
+
+    let memory = properties.iter()
+        .filter_map(|(node_id, properties)| Some((*node_id, estimate_memory(properties)?)))
+        .collect::<HashMap<u32, u64>>();
+
+    let elements_read = arguments.iter()
+        .filter_map(|(node_id, argument_ids)| Some((*node_id, argument_ids.iter()
+            .map(|argument_id| estimate_elements(properties.get(argument_id)?))
+            .sum::<Option<u64>>()?)))
+        .collect::<HashMap<u32, u64>>();
+
+    Ok((proto::ResourceEstimates {
+        total_memory: memory.values().sum(),
+        complete: arguments.keys().all(|node_id| memory.contains_key(node_id) && elements_read.contains_key(node_id)),
+        memory,
+        elements_read,
+        warnings: Vec::new()
+    }, warnings))
+}
+
+fn estimate_elements(properties: &ValueProperties) -> Option<u64> {
+    match properties {
+        ValueProperties::Array(array) =>
+            Some(array.num_records? as u64 * array.num_columns? as u64),
+        ValueProperties::Jagged(jagged) =>
+            Some(jagged.num_records.as_ref()?.iter().sum::<i64>() as u64),
+        ValueProperties::Dataframe(dataframe) =>
+            dataframe.children.values().map(estimate_elements).sum(),
+        ValueProperties::Partitions(partitions) =>
+            partitions.children.values().map(estimate_elements).sum(),
+        ValueProperties::Function(_) => None
+    }
+}
+
+fn estimate_memory(properties: &ValueProperties) -> Option<u64> {
+    let atomic_size = |data_type: &DataType| Some(match data_type {
+        DataType::Bool => std::mem::size_of::<bool>(),
+        DataType::Str => std::mem::size_of::<String>(),
+        DataType::Float => std::mem::size_of::<Float>(),
+        DataType::F32 => std::mem::size_of::<f32>(),
+        DataType::Int | DataType::DateTime => std::mem::size_of::<Integer>(),
+        DataType::Unknown => return None
+    } as u64);
+
+    match properties {
+        ValueProperties::Array(array) =>
+            Some(estimate_elements(properties)? * atomic_size(&array.data_type)?),
+        ValueProperties::Jagged(jagged) =>
+            Some(estimate_elements(properties)? * atomic_size(&jagged.data_type)?),
+        ValueProperties::Dataframe(dataframe) =>
+            dataframe.children.values().map(estimate_memory).sum(),
+        ValueProperties::Partitions(partitions) =>
+            partitions.children.values().map(estimate_memory).sum(),
+        ValueProperties::Function(_) => None
+    }
+}
+
+/// Execute the computation for a single partition, and merge it into a release accumulated over prior partitions
+///
+/// Every node that evaluates to partitioned data in a full release is evaluated on just one partition.
+/// Nodes that consume partitioned data, but are not partitioned themselves, are not evaluated.
+/// Once every partition has been released, they may be evaluated by passing the accumulated release to [release](fn.release.html).
+///
+/// Each Partition component only selects the rows of the requested partition, and only that partition is evaluated downstream,
+/// so memory usage is bounded by the size of the data and the largest partition, instead of by the size of every partition.
+///
+/// # Arguments
+/// * `analysis` - a computational graph and definition of privacy, in prost protobuf format
+/// * `release` - the release accumulated over prior partitions, as well as any other precomputed values
+/// * `partition_key` - the key of the partition to evaluate
+/// * `filter_level` - configure the amount of information included in the return
+///
+/// # Return
+/// the accumulated release, where partitioned nodes contain the partitions released so far
+pub fn release_partition(
+    privacy_definition: Option<proto::PrivacyDefinition>,
+    mut computation_graph: HashMap<u32, proto::Component>,
+    mut release: Release,
+    partition_key: IndexKey,
+    filter_level: proto::FilterLevel
+) -> Result<(Release, Vec<Error>)> {
+
+    // set aside the partitions accumulated from prior calls
+    let mut accumulated = release.keys()
+        .filter(|node_id| matches!(release[node_id].value, Value::Partitions(_)))
+        .cloned().collect::<Vec<u32>>().into_iter()
+        .map(|node_id| (node_id, release.remove(&node_id).unwrap()))
+        .collect::<Release>();
+
+    // the node ids that evaluate to partitioned data
+    let partitioned_ids = smartnoise_validator::get_properties(
+        privacy_definition.clone(),
+        computation_graph.clone(),
+        release.clone(),
+        Vec::new())?.0.into_iter()
+        .filter(|(_, properties)| properties.partitions().is_ok())
+        .map(|(node_id, _)| node_id)
+        .collect::<HashSet<u32>>();
+    accumulated.retain(|node_id, release_node| partitioned_ids.contains(node_id) || {
+        release.insert(*node_id, release_node.clone());
+        false
+    });
+
+    // don't evaluate nodes that consume partitioned data, but are not partitioned themselves
+    let parents = get_dependents(&computation_graph);
+    let mut traversal = Vec::from_iter(partitioned_ids.iter().cloned());
+    let mut consumer_ids = HashSet::new();
+    while let Some(node_id) = traversal.pop() {
+        parents.get(&node_id).into_iter().flatten()
+            .filter(|parent_id| !partitioned_ids.contains(parent_id) && consumer_ids.insert(**parent_id))
+            .for_each(|parent_id| traversal.push(*parent_id));
+    }
+    computation_graph.retain(|node_id, _| !consumer_ids.contains(node_id));
+    let consumed = consumer_ids.iter()
+        .filter_map(|node_id| Some((*node_id, release.remove(node_id)?)))
+        .collect::<Release>();
+
+    // select the partition from the outputs of each component that partitions data
+    let mut maximum_id = computation_graph.keys().max().cloned().unwrap_or(0);
+    let root_ids = partitioned_ids.iter()
+        .filter(|node_id| !computation_graph.get(node_id).unwrap().arguments().values()
+            .any(|argument_id| partitioned_ids.contains(argument_id)))
+        .cloned().collect::<Vec<u32>>();
+
+    for root_id in root_ids {
+        let mut component = computation_graph.remove(&root_id).unwrap();
+        let (omit, submission) = (component.omit, component.submission);
+
+        maximum_id += 1;
+        let id_name = maximum_id;
+        let (literal, literal_release) = smartnoise_validator::utilities::get_literal(
+            Value::from_index_key(partition_key.clone())?, submission)?;
+        computation_graph.insert(id_name, literal);
+        release.insert(id_name, literal_release);
+
+        // the Partition component only selects the rows of the named partition
+        if let Some(proto::component::Variant::Partition(_)) = component.variant {
+            let mut arguments = component.arguments();
+            arguments.insert("names".into(), id_name);
+            component.arguments = Some(proto::ArgumentNodeIds::new(arguments));
+        }
+
+        maximum_id += 1;
+        let id_partitions = maximum_id;
+        computation_graph.insert(id_partitions, proto::Component { omit: true, ..component });
+
+        computation_graph.insert(root_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap::indexmap![
+                "data".into() => id_partitions, "names".into() => id_name
+            ])),
+            variant: Some(proto::component::Variant::Index(proto::Index { as_dataframe: false })),
+            omit,
+            submission,
+        });
+    }
+
+    let (mut release, warnings) = crate::release(
+        privacy_definition, computation_graph, release, filter_level)?;
+
+    // merge the partitioned nodes into the accumulated partitions
+    for node_id in &partitioned_ids {
+        let partition = match release.remove(node_id) {
+            Some(partition) => partition,
+            None => continue
+        };
+
+        let accumulated_node = accumulated.remove(node_id);
+        let (mut partitions, prior_usages, prior_public) = match accumulated_node {
+            Some(ReleaseNode { value, privacy_usages, public }) =>
+                (value.partitions()?, privacy_usages, public),
+            None => (IndexMap::new(), None, true)
+        };
+
+        if partitions.contains_key(&partition_key) {
+            return Err(format!("partition {} has already been released for node {}", partition_key.to_string(), node_id).into())
+        }
+        partitions.insert(partition_key.clone(), partition.value);
+
+        // partitions are disjoint, so by parallel composition, the usage is the max over partitions
+        let privacy_usages = match (prior_usages, partition.privacy_usages) {
+            (Some(prior), Some(usages)) => Some(prior.into_iter().zip(usages)
+                .map(|(l, r)| max_privacy_usage(Ok(l), Ok(r)))
+                .collect::<Result<Vec<proto::PrivacyUsage>>>()?),
+            (prior, usages) => prior.or(usages)
+        };
+
+        accumulated.insert(*node_id, ReleaseNode {
+            value: Value::Partitions(partitions),
+            privacy_usages,
+            public: prior_public && partition.public,
+        });
+    }
+
+    release.extend(accumulated);
+    release.extend(consumed);
+    Ok((release, warnings))
+}
+
+#[cfg(test)]
+mod test_release {
+    use ndarray::Array;
+    use smartnoise_validator::bindings::{Analysis, privacy_usage};
+    use smartnoise_validator::Float;
+
+    use crate::proto;
+
+    /// index a single column of a dataframe by name
+    fn index_by_name(analysis: &mut Analysis, data: u32, name: &str) -> u32 {
+        index_by_value(analysis, data, ndarray::arr1(&[name.to_string()]).into_dyn().into())
+    }
+
+    fn index_by_value(analysis: &mut Analysis, data: u32, names: smartnoise_validator::base::Value) -> u32 {
+        let names = analysis.literal()
+            .value(names)
+            .value_public(true)
+            .build();
+        let column = analysis.index(data).names(names).build();
+        column
+    }
+
+    /// release a dp sum over data in [0, 10] that has been shifted by 5 into [-5, 5]
+    fn release_dp_sum_offset(data: &[Float]) -> Float {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+
+        let data = analysis.literal()
+            .value(Array::from_shape_vec((data.len(), 1), data.to_vec()).unwrap().into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+
+        let offset = analysis.literal().value(5.0.into()).value_public(true).build();
+        let dp_sum = analysis.dp_sum(clamped, privacy_usage(1., 0.))
+            .mechanism("Laplace".to_string())
+            .offset(offset)
+            .build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        release.get(&dp_sum).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap()
+            .first_float().unwrap()
+    }
+
+    #[test]
+    fn test_dp_sum_offset() {
+        let data = [1., 2., 3., 4., 5., 6., 7., 8., 9., 10.];
+        let actual: Float = data.iter().sum();
+
+        let num_trials = 1000;
+        let mean = (0..num_trials)
+            .map(|_| release_dp_sum_offset(&data))
+            .sum::<Float>() / num_trials as Float;
+
+        // the laplace noise has a standard deviation of sqrt(2) * 5, so the mean has a standard deviation of ~0.22
+        assert!((mean - actual).abs() < 1., "mean of corrected sums {} should be near {}", mean, actual);
+    }
+
+    #[test]
+    fn test_dp_survival_counts() {
+        use smartnoise_validator::base::{Jagged, Value};
+        use smartnoise_validator::components::dp_survival_counts::{kaplan_meier, survival_counts};
+
+        // categories of public data are inferred from the observed values, so populate every cell
+        let mut analysis = Analysis::new();
+        let times = analysis.literal()
+            .value(Array::from_shape_fn((24, 1), |(i, _)| (i / 2) as Float).into_dyn().into())
+            .value_public(true)
+            .build();
+        let events = analysis.literal()
+            .value(Array::from_shape_fn((24, 1), |(i, _)| i % 4 != 0).into_dyn().into())
+            .value_public(true)
+            .build();
+        let edges = analysis.literal()
+            .value(Value::Jagged(Jagged::Float(vec![vec![0., 2.5, 5., 7.5, 10.]])))
+            .value_public(true)
+            .build();
+        let dp_survival_counts = analysis.dp_survival_counts(times, events, edges, privacy_usage(1., 0.))
+            .build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        let cells = release.get(&dp_survival_counts).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap().clone()
+            .cast_float().unwrap()
+            .iter().copied().collect::<Vec<Float>>();
+
+        // a censored and event count for each of four bins, and for times past the last edge
+        assert_eq!(cells.len(), 10);
+
+        let (at_risk, events) = survival_counts(&cells).unwrap();
+        assert_eq!(at_risk.len(), 4);
+        assert_eq!(events.len(), 4);
+        assert!(at_risk.windows(2).all(|w| w[0] >= w[1]));
+
+        let survival = kaplan_meier(&at_risk, &events).unwrap();
+        assert!(survival.windows(2).all(|w| w[0] >= w[1]), "survival curve must be non-increasing: {:?}", survival);
+    }
+
+    #[test]
+    fn test_dp_frequencies() {
+        use smartnoise_validator::base::{Jagged, Value};
+
+        for project in &[false, true] {
+            let mut analysis = Analysis::new();
+            let data = analysis.literal()
+                .value(Array::from_shape_fn((20, 1), |(i, _)| i as Float * 0.5).into_dyn().into())
+                .value_public(true)
+                .build();
+            let edges = analysis.literal()
+                .value(Value::Jagged(Jagged::Float(vec![vec![0., 2.5, 5., 7.5, 10.]])))
+                .value_public(true)
+                .build();
+            // a small epsilon makes noisy counts that do not sum to n likely
+            let dp_frequencies = analysis.dp_frequencies(data, privacy_usage(0.1, 0.))
+                .edges(edges)
+                .project(*project)
+                .build();
+
+            let (release, warnings) = crate::release(
+                Some(analysis.privacy_definition),
+                analysis.components,
+                analysis.release,
+                proto::FilterLevel::All).unwrap();
+
+            let frequencies = release.get(&dp_frequencies).unwrap_or_else(|| panic!("{:?}", warnings)).value
+                .ref_array().unwrap().ref_float().unwrap()
+                .iter().copied().collect::<Vec<Float>>();
+
+            // the data lie within the edges, so there is no bin for values outside of them
+            assert_eq!(frequencies.len(), 4);
+            assert!(frequencies.iter().all(|v| (0. ..=1.).contains(v)), "{:?}", frequencies);
+            if *project {
+                assert!((frequencies.iter().sum::<Float>() - 1.).abs() < 1e-12, "{:?}", frequencies);
+            }
+        }
+    }
+
+    #[test]
+    fn test_dp_frequencies_zero_count() {
+        use smartnoise_validator::base::{Jagged, Value};
+
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((20, 1), |(i, _)| i as Float * 0.5).into_dyn().into())
+            .value_public(true)
+            .build();
+        let edges = analysis.literal()
+            .value(Value::Jagged(Jagged::Float(vec![vec![0., 2.5, 5., 7.5, 10.]])))
+            .value_public(true)
+            .build();
+        // dividing by a count of zero would make the frequencies infinite, and the projection undefined
+        let count = analysis.literal().value(0.into()).value_public(true).build();
+        let dp_frequencies = analysis.dp_frequencies(data, privacy_usage(1., 0.))
+            .edges(edges).count(count)
+            .project(true)
+            .build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        let frequencies = release.get(&dp_frequencies).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap().ref_float().unwrap()
+            .iter().copied().collect::<Vec<Float>>();
+
+        assert!(frequencies.iter().all(|v| (0. ..=1.).contains(v)), "{:?}", frequencies);
+        assert!((frequencies.iter().sum::<Float>() - 1.).abs() < 1e-12, "{:?}", frequencies);
+    }
+
+    #[test]
+    fn test_map_columns() {
+        use smartnoise_validator::base::{IndexKey, Value};
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Value::Dataframe(indexmap::indexmap![
+                "a".into() => ndarray::arr2(&[[-1.], [2.], [4.], [12.]]).into_dyn().into(),
+                "b".into() => ndarray::arr2(&[[3.], [5.], [7.], [9.]]).into_dyn().into()
+            ]))
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+
+        // clamp each column of the dataframe
+        analysis.component_count += 1;
+        let clamped = analysis.component_count;
+        analysis.components.insert(clamped, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap::indexmap![
+                "data".into() => data, "lower".into() => lower, "upper".into() => upper
+            ])),
+            variant: Some(proto::component::Variant::MapColumns(Box::new(proto::MapColumns {
+                component: Some(Box::new(proto::Component {
+                    arguments: None,
+                    variant: Some(proto::component::Variant::Clamp(proto::Clamp {})),
+                    omit: true,
+                    submission: 0,
+                }))
+            }))),
+            omit: false,
+            submission: 0,
+        });
+        // the mean is mapped over each clamped column
+        let means = analysis.mean(clamped).build();
+        let dp_means = analysis.dp_mean(clamped, privacy_usage(1., 0.))
+            .mechanism("Laplace".to_string())
+            .build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        let means = release.get(&means).unwrap_or_else(|| panic!("{:?}", warnings)).value.ref_partitions().unwrap();
+        assert_eq!(means.keys().cloned().collect::<Vec<_>>(), vec![IndexKey::from("a"), IndexKey::from("b")]);
+        let means = means.values()
+            .map(|v| v.ref_array().unwrap().first_float().unwrap())
+            .collect::<Vec<Float>>();
+        assert_eq!(means, vec![4., 6.]);
+
+        // each column is released under its own privacy usage
+        let dp_means = release.get(&dp_means).unwrap_or_else(|| panic!("{:?}", warnings)).value.ref_partitions().unwrap();
+        assert_eq!(dp_means.len(), 2);
+        assert!(dp_means.values().all(|v| v.ref_array().unwrap().first_float().unwrap().is_finite()));
+    }
+
+    #[test]
+    fn test_histogram_of_days() {
+        use smartnoise_validator::base::{Jagged, Value};
+
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(ndarray::arr2(&[["2020-03-15T13:45:30"], ["2020-03-15T23:00:00"], ["2020-03-16T08:30:00"], ["unknown"]])
+                .mapv(|v| v.to_string()).into_dyn().into())
+            .value_public(true)
+            .build();
+        let null_value = analysis.literal()
+            .value(ndarray::arr1(&[0]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let categories = analysis.literal()
+            .value(Value::Jagged(Jagged::Int(vec![vec![1584230400, 1584316800]])))
+            .value_public(true)
+            .build();
+        let timestamps = analysis.parse_date_time(data, null_value, "%Y-%m-%dT%H:%M:%S".to_string()).build();
+        let days = analysis.date_bin(timestamps, "day".to_string()).build();
+        let histogram = analysis.histogram(days).categories(categories).null_value(null_value).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        let counts = release.get(&histogram).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap().clone()
+            .int().unwrap()
+            .iter().copied().collect::<Vec<_>>();
+        // two on the 15th, one on the 16th, and one that could not be parsed
+        assert_eq!(counts, vec![2, 1, 1]);
+    }
+
+    /// release the mean of a csv column, loaded in either single or double precision
+    fn release_csv_mean(single_precision: bool) -> (Float, bool) {
+        use smartnoise_validator::base::Array as SnArray;
+
+        let path = std::env::temp_dir().join(format!(
+            "smartnoise_test_mean_{}_{}.csv", std::process::id(), single_precision));
+        let csv = (0..1000).map(|i| format!("{}\n", i as Float * 0.1 + 0.0137)).collect::<String>();
+        std::fs::write(&path, format!("x\n{}", csv)).unwrap();
+
+        let mut analysis = Analysis::new();
+        let names = analysis.literal()
+            .value(ndarray::arr1(&["x".to_string()]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let data = analysis.materialize(path.to_str().unwrap().to_string())
+            .column_names(names)
+            .public(true)
+            .infer_types(true)
+            .single_precision(single_precision)
+            .build();
+        let column = index_by_name(&mut analysis, data, "x");
+        let mean = analysis.mean(column).build();
+
+        let release = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All);
+        std::fs::remove_file(&path).unwrap();
+        let (release, warnings) = release.unwrap();
+
+        let is_single = matches!(release.get(&column).unwrap_or_else(|| panic!("{:?}", warnings)).value.ref_array().unwrap(), SnArray::F32(_));
+        let mean = release.get(&mean).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap()
+            .clone().float().unwrap()
+            .iter().copied().next().unwrap();
+        (mean, is_single)
+    }
+
+    #[test]
+    fn test_single_precision_mean() {
+        let (mean_f64, is_single) = release_csv_mean(false);
+        assert!(!is_single);
+        let (mean_f32, is_single) = release_csv_mean(true);
+        assert!(is_single, "single precision must be preserved through indexing");
+
+        assert!((mean_f64 - 49.9637).abs() < 1e-9);
+        assert!((mean_f32 - mean_f64).abs() < 1e-4, "{} should be near {}", mean_f32, mean_f64);
+    }
+
+    #[test]
+    fn test_dp_truncated_mean() {
+        // individual i contributes i % 5 + 1 rows, each with value i % 10
+        let rows = (0..200).flat_map(|i| (0..i % 5 + 1).map(move |_| (i, i % 10))).collect::<Vec<_>>();
+        let path = std::env::temp_dir().join(format!("smartnoise_test_truncated_{}.csv", std::process::id()));
+        std::fs::write(&path, rows.iter()
+            .map(|(user, x)| format!("{},{}\n", user, x)).collect::<String>()).unwrap();
+
+        // at most two rows are kept for each individual
+        let mut counts = std::collections::HashMap::new();
+        let (sum, count) = rows.iter()
+            .filter(|(user, _)| {
+                let count = counts.entry(*user).or_insert(0);
+                *count += 1;
+                *count <= 2
+            })
+            .fold((0., 0.), |(sum, count), (_, x)| (sum + *x as Float, count + 1.));
+        // individuals with more rows have larger values, so truncation shifts the mean
+        let mean = rows.iter().map(|(_, x)| *x as Float).sum::<Float>() / rows.len() as Float;
+        assert!((mean - sum / count).abs() > 0.4);
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let names = analysis.literal()
+            .value(ndarray::arr1(&["user".to_string(), "x".to_string()]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let data = analysis.materialize(path.to_str().unwrap().to_string())
+            .column_names(names)
+            .skip_row(false)
+            .build();
+        // string keys identify individuals
+        let user = index_by_name(&mut analysis, data, "user");
+        let x = index_by_name(&mut analysis, data, "x");
+        let x = analysis.to_float(x).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let x = analysis.clamp(x).lower(lower).upper(upper).build();
+        let x = analysis.impute(x).build();
+
+        let dp_mean = analysis.dp_truncated_mean(x, user, 2, privacy_usage(1e4, 0.))
+            .mechanism("Laplace".to_string())
+            .build();
+
+        let release = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::Public);
+        std::fs::remove_file(&path).unwrap();
+        let (release, warnings) = release.unwrap();
+
+        let dp_mean = release.get(&dp_mean).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap().clone()
+            .float().unwrap()
+            .iter().copied().next().unwrap();
+        assert!((dp_mean - sum / count).abs() < 0.05, "{} should be near {}", dp_mean, sum / count);
+    }
+
+    #[test]
+    fn test_filter_predicate() {
+        let path = std::env::temp_dir().join(format!("smartnoise_test_filter_{}.csv", std::process::id()));
+        std::fs::write(&path, "1,x\n6,x\n7,y\n9,x\n").unwrap();
+
+        let mut analysis = Analysis::new();
+        let names = analysis.literal()
+            .value(ndarray::arr1(&["a".to_string(), "b".to_string()]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let data = analysis.materialize(path.to_str().unwrap().to_string())
+            .column_names(names)
+            .skip_row(false)
+            .build();
+        let a = index_by_name(&mut analysis, data, "a");
+        let a = analysis.to_float(a).build();
+        let b = index_by_name(&mut analysis, data, "b");
+
+        // a > 5 AND "x" == b
+        let five = analysis.literal().value(5.0.into()).value_public(true).build();
+        let x = analysis.literal().value("x".to_string().into()).value_public(true).build();
+        let greater_than = analysis.greater_than(a, five).build();
+        let equal = analysis.equal(x, b).build();
+        let mask = analysis.logical_and(greater_than, equal).build();
+        let filtered = analysis.filter(a, mask).build();
+
+        let release = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All);
+        std::fs::remove_file(&path).unwrap();
+        let (release, warnings) = release.unwrap();
+
+        let filtered = release.get(&filtered).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap().clone()
+            .float().unwrap()
+            .iter().copied().collect::<Vec<Float>>();
+        assert_eq!(filtered, vec![6., 9.]);
+    }
+
+    #[test]
+    fn test_partition_by_quantiles() {
+        let mut analysis = Analysis::new();
+        let by = Array::from_shape_fn((20, 1), |(i, _)| i as Float);
+        let data = analysis.literal()
+            .value(by.clone().into_dyn().into())
+            .value_public(true)
+            .build();
+        let by = analysis.literal()
+            .value(by.into_dyn().into())
+            .value_public(true)
+            .build();
+        let cut_points = analysis.literal()
+            .value(ndarray::arr1(&[5., 10., 15.]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let partitioned = analysis.partition_by_quantiles(data, by, cut_points).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        let partitions = release.get(&partitioned).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_partitions().unwrap().clone();
+        let lengths = partitions.values()
+            .map(|partition| partition.ref_array().unwrap().num_records().unwrap())
+            .collect::<Vec<_>>();
+        // three cut-points make four strata
+        assert_eq!(lengths, vec![5, 5, 5, 5]);
+        // the partitions are disjoint and cover every row
+        assert_eq!(lengths.iter().sum::<usize>(), 20);
+    }
+
+    #[test]
+    fn test_group_by() {
+        use smartnoise_validator::base::IndexKey;
+
+        for statistic in &["sum", "mean"] {
+            let mut analysis = Analysis::new();
+            analysis.privacy_definition.protect_floating_point = false;
+            let data = analysis.literal()
+                .value(Array::from_shape_fn((90, 1), |(i, _)| (i % 10) as Float).into_dyn().into())
+                .value_public(true)
+                .build();
+            let by = analysis.literal()
+                .value(Array::from_shape_fn((90, 1), |(i, _)| ["a", "b", "c"][i % 3].to_string()).into_dyn().into())
+                .value_public(true)
+                .build();
+            let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+            let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+            let data = analysis.clamp(data).lower(lower).upper(upper).build();
+
+            let group_by = analysis.group_by(data, by, privacy_usage(1e4, 0.))
+                .statistic(statistic.to_string())
+                .mechanism("Laplace".to_string())
+                .build();
+
+            let (release, warnings) = crate::release(
+                Some(analysis.privacy_definition),
+                analysis.components,
+                analysis.release,
+                proto::FilterLevel::Public).unwrap();
+
+            let groups = release.get(&group_by).unwrap_or_else(|| panic!("{:?}", warnings)).value
+                .ref_partitions().unwrap().clone();
+            // each group yields a release
+            assert_eq!(groups.len(), 3);
+            ["a", "b", "c"].iter().enumerate().for_each(|(offset, key)| {
+                // keys of a two-dimensional column are tuples
+                let estimate = groups.get(&IndexKey::Tuple(vec![IndexKey::from(key.to_string())])).unwrap()
+                    .ref_array().unwrap().first_float().unwrap();
+                let values = (0..90).filter(|i| i % 3 == offset).map(|i| (i % 10) as Float).collect::<Vec<Float>>();
+                let actual = match *statistic {
+                    "sum" => values.iter().sum::<Float>(),
+                    _ => values.iter().sum::<Float>() / values.len() as Float
+                };
+                assert!((estimate - actual).abs() < 1., "{}: {} should be near {}", statistic, estimate, actual);
+            });
+        }
+    }
+
+    #[test]
+    fn test_release_partition() {
+        use smartnoise_validator::base::{IndexKey, Release};
+
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((90, 1), |(i, _)| (i % 10) as Float).into_dyn().into())
+            .value_public(true)
+            .build();
+        let by = analysis.literal()
+            .value(Array::from_shape_fn((90, 1), |(i, _)| ["a", "b", "c"][i % 3].to_string()).into_dyn().into())
+            .value_public(true)
+            .build();
+        let partitioned = analysis.partition(data).by(by).build();
+        let sums = analysis.sum(partitioned).build();
+        // consumes the partitioned sums
+        let sum_a = index_by_name(&mut analysis, sums, "a");
+
+        let (full_release, _) = crate::release(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            analysis.release.clone(),
+            proto::FilterLevel::All).unwrap();
+
+        let mut batched_release: Release = analysis.release.clone();
+        for key in &["c", "a", "b"] {
+            batched_release = crate::release_partition(
+                Some(analysis.privacy_definition.clone()),
+                analysis.components.clone(),
+                batched_release,
+                IndexKey::Tuple(vec![IndexKey::from(key.to_string())]),
+                proto::FilterLevel::All).unwrap().0;
+        }
+        // the consumer of the partitions is not evaluated until all partitions are released
+        assert!(!batched_release.contains_key(&sum_a));
+
+        for node_id in &[partitioned, sums] {
+            let full = full_release.get(node_id).unwrap().value.ref_partitions().unwrap();
+            let batched = batched_release.get(node_id).unwrap().value.ref_partitions().unwrap();
+            assert_eq!(full.len(), 3);
+            assert_eq!(batched.len(), 3);
+            full.iter().for_each(|(key, value)| assert!(batched.get(key).unwrap() == value));
+        }
+
+        let (batched_release, _) = crate::release(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            batched_release,
+            proto::FilterLevel::All).unwrap();
+        assert!(batched_release.get(&sum_a).unwrap().value == full_release.get(&sum_a).unwrap().value);
+
+        // a partition may only be released once
+        assert!(crate::release_partition(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            batched_release,
+            IndexKey::Tuple(vec![IndexKey::from("a".to_string())]),
+            proto::FilterLevel::All).is_err());
+    }
+
+    #[test]
+    fn test_partition_shared_input() {
+        use smartnoise_validator::utilities::privacy::get_epsilon;
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((90, 1), |(i, _)| (i % 10) as Float).into_dyn().into())
+            .value_public(true)
+            .build();
+        let by = analysis.literal()
+            .value(Array::from_shape_fn((90, 1), |(i, _)| ["a", "b"][i % 2].to_string()).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let partitioned = analysis.partition(data).by(by).build();
+        let indexed = index_by_name(&mut analysis, partitioned, "a");
+        let clamped = analysis.clamp(indexed).lower(lower).upper(upper).build();
+        analysis.dp_sum(clamped, privacy_usage(1., 0.)).mechanism("Laplace".to_string()).build();
+
+        // the unpartitioned sum shares its bounds with the partitioned sum
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let dp_sum = analysis.dp_sum(clamped, privacy_usage(1., 0.)).mechanism("Laplace".to_string()).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            analysis.release.clone(),
+            proto::FilterLevel::All).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert!(release.contains_key(&dp_sum));
+
+        // the public bounds do not connect the sums, so the unpartitioned sum is composed sequentially with the partition
+        let usage = smartnoise_validator::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, release).unwrap();
+        assert!((get_epsilon(&usage).unwrap() - 2.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_partition_by_composite_key() {
+        use smartnoise_validator::base::{IndexKey, Value};
+
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((12, 1), |(i, _)| i as Float).into_dyn().into())
+            .value_public(true)
+            .build();
+        // keys have mixed types, so the columns are passed as a dataframe
+        let by = analysis.literal()
+            .value(Value::Dataframe(indexmap::indexmap![
+                "state".into() => Array::from_shape_fn(12, |i| ["CA", "WA", "OR"][i % 3].to_string()).into_dyn().into(),
+                "member".into() => Array::from_shape_fn(12, |i| i % 2 == 0).into_dyn().into()
+            ]))
+            .value_public(true)
+            .build();
+        let partitioned = analysis.partition(data).by(by).build();
+        let names = Value::Dataframe(indexmap::indexmap![
+            "state".into() => "WA".to_string().into(),
+            "member".into() => false.into()
+        ]);
+        let indexed = index_by_value(&mut analysis, partitioned, names);
+
+        let (release, _) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+
+        let partitions = release.get(&partitioned).unwrap().value.ref_partitions().unwrap();
+        // one partition for each combination of categories
+        assert_eq!(partitions.len(), 6);
+        let key = IndexKey::Tuple(vec!["WA".into(), false.into()]);
+        assert!(partitions.get(&key).unwrap() == &release.get(&indexed).unwrap().value);
+
+        match release.get(&indexed).unwrap().value.ref_array().unwrap() {
+            smartnoise_validator::base::Array::Float(indexed) =>
+                assert_eq!(indexed.iter().cloned().collect::<Vec<Float>>(), vec![1., 7.]),
+            _ => panic!("the indexed partition must be float")
+        };
+    }
+
+    #[test]
+    fn test_dp_median_absolute_deviation() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        // uniform on [0, 100), so the median is near 50 and the median absolute deviation is near 25
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((1000, 1), |(i, _)| (i % 100) as Float).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(100.0.into()).value_public(true).build();
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+        let dp_mad = analysis.dp_median_absolute_deviation(data, privacy_usage(1e4, 0.))
+            .mechanism("Laplace".to_string())
+            .build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::Public).unwrap();
+        let mad = release.get(&dp_mad).unwrap_or_else(|| panic!("{:?}", warnings))
+            .value.ref_array().unwrap().first_float().unwrap();
+        assert!((mad - 25.).abs() < 1., "{} should be near 25", mad);
+    }
+
+    #[test]
+    fn test_dp_mean_joint_gaussian() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((100, 3), |(i, j)| ((i % 10) + j) as Float).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(12.0.into()).value_public(true).build();
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+        let usage = privacy_usage(100., 1e-6);
+        let dp_mean = analysis.dp_mean(data, usage.clone())
+            .mechanism("AnalyticGaussian".to_string())
+            .joint(true)
+            .build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+        let release_node = release.get(&dp_mean).unwrap_or_else(|| panic!("{:?}", warnings));
+
+        // the entire usage is spent on all columns at once
+        assert_eq!(release_node.privacy_usages.as_ref().unwrap().len(), 1);
+        let means = release_node.value.ref_array().unwrap().clone().float().unwrap();
+        means.iter().zip(&[4.5, 5.5, 6.5])
+            .for_each(|(mean, expected)| assert!((mean - expected).abs() < 0.5, "{} should be near {}", mean, expected));
+    }
+
+    #[test]
+    fn test_dp_sum_truncated_laplace() {
+        use smartnoise_validator::components::truncated_laplace_mechanism::get_truncated_laplace_bound;
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((100, 1), |(i, _)| (i % 10) as Float).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+        let usage = privacy_usage(1., 1e-6);
+        let dp_sum = analysis.dp_sum(data, usage)
+            .mechanism("TruncatedLaplace".to_string())
+            .build();
+
+        // the sensitivity of the sum is at most the largest magnitude of the bounds
+        let bound = get_truncated_laplace_bound(1., 1e-6, 10.);
+        for _ in 0..10 {
+            let (release, warnings) = crate::release(
+                Some(analysis.privacy_definition.clone()),
+                analysis.components.clone(),
+                analysis.release.clone(),
+                proto::FilterLevel::All).unwrap();
+            let release_node = release.get(&dp_sum).unwrap_or_else(|| panic!("{:?}", warnings));
+
+            let sum = release_node.value.ref_array().unwrap().clone().float().unwrap();
+            assert!((sum.first().unwrap() - 450.).abs() <= bound, "{} is not within {} of 450", sum, bound);
+
+            // the delta paid for truncation is reported
+            let usages = release_node.privacy_usages.as_ref().unwrap();
+            assert_eq!(smartnoise_validator::utilities::privacy::get_delta(&usages[0]).unwrap(), 1e-6);
+        }
+
+        // one sensitivity is needed for each statistic
+        use crate::components::Evaluable;
+        let mechanism = proto::TruncatedLaplaceMechanism { privacy_usage: privacy_usage(1., 1e-6) };
+        let arguments: crate::NodeArguments = indexmap::indexmap![
+            "data".into() => ndarray::arr2(&[[1., 2.]]).into_dyn().into(),
+            "sensitivity".into() => ndarray::arr2(&[[1.]]).into_dyn().into()];
+        assert!(mechanism.evaluate(&None, arguments).is_err());
+    }
+
+    #[test]
+    fn test_dp_histogram_stability() {
+        use smartnoise_validator::base::{IndexKey, Value};
+
+        let mut analysis = Analysis::new();
+        // two frequent categories, and many categories with a single record each
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((1100, 1), |(i, _)| match i {
+                0..=599 => "a".to_string(),
+                600..=999 => "b".to_string(),
+                _ => format!("rare {}", i)
+            }).into_dyn().into())
+            .value_public(true)
+            .build();
+        // the bounds on the counts are not needed by the stability mechanism
+        let dp_histogram = analysis.dp_histogram(data, privacy_usage(1., 1e-6)).mechanism("Stability".to_string()).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+        let release_node = release.get(&dp_histogram).unwrap_or_else(|| panic!("{:?}", warnings));
+
+        let columns = match &release_node.value {
+            Value::Dataframe(columns) => columns,
+            _ => panic!("the stability mechanism must release a dataframe")
+        };
+        let categories = columns.get(&IndexKey::from("categories")).unwrap().ref_array().unwrap().ref_string().unwrap();
+        let counts = columns.get(&IndexKey::from("counts")).unwrap().ref_array().unwrap().ref_int().unwrap();
+
+        // rare categories are suppressed
+        assert_eq!(categories.iter().cloned().collect::<Vec<String>>(), vec!["a".to_string(), "b".to_string()]);
+        counts.iter().zip(&[600, 400])
+            .for_each(|(count, expected)| assert!((count - expected).abs() < 30, "{} should be near {}", count, expected));
+
+        // the delta spent on thresholding is reported
+        let usages = release_node.privacy_usages.as_ref().unwrap();
+        assert_eq!(smartnoise_validator::utilities::privacy::get_delta(&usages[0]).unwrap(), 1e-6);
+
+        // NaN has no place among the sorted categories
+        use crate::components::Evaluable;
+        let mechanism = proto::StabilityMechanism { privacy_usage: privacy_usage(1., 1e-6) };
+        let arguments: crate::NodeArguments = indexmap::indexmap![
+            "data".into() => ndarray::arr1(&[1., Float::NAN]).into_dyn().into()];
+        assert!(mechanism.evaluate(&Some(Analysis::new().privacy_definition), arguments).is_err());
+    }
+
+    #[test]
+    fn test_dp_histogram_integer_range() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((100, 1), |(i, _)| (i % 10) as i64).into_dyn().into())
+            .value_public(false)
+            .build();
+        let number_rows = analysis.literal().value(100.into()).value_public(true).build();
+        let number_columns = analysis.literal().value(1.into()).value_public(true).build();
+        let lower = analysis.literal().value(0.into()).value_public(true).build();
+        let upper = analysis.literal().value(9.into()).value_public(true).build();
+        let data = analysis.to_int(data, lower, upper).build();
+        let data = analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .lower(lower).upper(upper)
+            .build();
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+
+        let inclusive_left = analysis.literal().value(true.into()).value_public(true).build();
+        let dp_histogram = analysis.dp_histogram(data, privacy_usage(1., 0.)).lower(lower).inclusive_left(inclusive_left).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All).unwrap();
+        let counts = release.get(&dp_histogram).unwrap_or_else(|| panic!("{:?}", warnings))
+            .value.ref_array().unwrap().ref_int().unwrap().clone();
+
+        // one count for each integer in [0, 9], without a null cell
+        assert_eq!(counts.len(), 10);
+        counts.iter().for_each(|count| assert!((count - 10).abs() < 10, "{} should be near 10", count));
+    }
+
+    #[test]
+    fn test_dp_quantile_function() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((100, 1), |(i, _)| (i % 50) as Float).into_dyn().into())
+            .value_public(true)
+            .build();
+        let candidates = analysis.literal()
+            .value(Array::from_shape_fn((26, 1), |(i, _)| i as Float * 2.).into_dyn().into())
+            .value_public(true)
+            .build();
+        // every 5th percentile
+        let alphas = analysis.literal()
+            .value(Array::from_shape_fn(19, |i| (i + 1) as Float * 0.05).into_dyn().into())
+            .value_public(true)
+            .build();
+        // a small budget makes individual estimates noisy, so that monotonicity relies on postprocessing
+        let dp_quantile_function = analysis.dp_quantile_function(data, candidates, alphas, privacy_usage(0.1, 0.))
+            .build();
+
+        for _ in 0..10 {
+            let (release, warnings) = crate::release(
+                Some(analysis.privacy_definition.clone()),
+                analysis.components.clone(),
+                analysis.release.clone(),
+                proto::FilterLevel::All).unwrap();
+
+            let quantiles = release.get(&dp_quantile_function).unwrap_or_else(|| panic!("{:?}", warnings)).value
+                .ref_array().unwrap().clone()
+                .cast_float().unwrap()
+                .iter().copied().collect::<Vec<Float>>();
+
+            assert_eq!(quantiles.len(), 19);
+            assert!(quantiles.windows(2).all(|w| w[0] <= w[1]), "quantile function must be non-decreasing: {:?}", quantiles);
+        }
+    }
+
+    #[test]
+    fn test_bin_edges() {
+        use smartnoise_validator::utilities::privacy::get_epsilon;
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((100, 1), |(i, _)| (i % 50) as Float).into_dyn().into())
+            .value_public(true)
+            .build();
+        let candidates = analysis.literal()
+            .value(Array::from_shape_fn((26, 1), |(i, _)| i as Float * 2.).into_dyn().into())
+            .value_public(true)
+            .build();
+        let alphas = analysis.literal()
+            .value(ndarray::arr1(&[0.25, 0.5, 0.75]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(50.0.into()).value_public(true).build();
+
+        // the first stage releases cut-points, which become the edges of the second stage
+        let cut_points = analysis.dp_quantile_function(data, candidates, alphas, privacy_usage(0.5, 0.))
+            .build();
+        let edges = analysis.bin_edges(cut_points).lower(lower).upper(upper).build();
+
+        let count_lower = analysis.literal().value(0.into()).value_public(true).build();
+        let inclusive_left = analysis.literal().value(true.into()).value_public(true).build();
+        let dp_histogram = analysis.dp_histogram(data, privacy_usage(1., 0.)).lower(count_lower).inclusive_left(inclusive_left)
+            .edges(edges)
+            .mechanism("Laplace".to_string())
+            .build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            analysis.release.clone(),
+            proto::FilterLevel::All).unwrap();
+
+        let edges_node = release.get(&edges).unwrap_or_else(|| panic!("{:?}", warnings));
+        assert!(edges_node.public);
+        let edges_value = match &edges_node.value {
+            smartnoise_validator::base::Value::Jagged(smartnoise_validator::base::Jagged::Float(edges)) => edges[0].clone(),
+            other => panic!("unexpected edges: {:?}", other)
+        };
+        assert_eq!(edges_value.first(), Some(&0.));
+        assert_eq!(edges_value.last(), Some(&50.));
+        assert!(edges_value.windows(2).all(|w| w[0] < w[1]));
+
+        // the data lies within the outermost edges, so there is one count for each bin between edges
+        let counts = release.get(&dp_histogram).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap().num_records().unwrap();
+        assert_eq!(counts, edges_value.len() - 1);
+
+        // the budget of the two stages composes sequentially
+        let usage = smartnoise_validator::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, release).unwrap();
+        assert!((get_epsilon(&usage).unwrap() - 1.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_subsample() {
+        use smartnoise_validator::utilities::privacy::get_epsilon;
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((1000, 1), |(i, _)| i as Float).into_dyn().into())
+            .value_public(false)
+            .build();
+        let proportion = analysis.literal().value(0.1.into()).value_public(true).build();
+        let subsample = analysis.subsample(data, proportion).build();
+        let count = analysis.count(subsample).build();
+        let dp_count = analysis.laplace_mechanism(count, privacy_usage(1., 0.)).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            analysis.release.clone(),
+            proto::FilterLevel::All).unwrap();
+
+        // the count of a 10% subsample of 1000 rows is well within (0, 1000)
+        let dp_count = release.get(&dp_count).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap().first_float().unwrap();
+        assert!(dp_count > 0. && dp_count < 1000., "{}", dp_count);
+
+        // the mechanism spends an amplified epsilon, but the analysis is charged the requested usage
+        let usage = smartnoise_validator::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, release).unwrap();
+        assert!((get_epsilon(&usage).unwrap() - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_weighted_sample() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.neighboring = proto::privacy_definition::Neighboring::Substitute as i32;
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((4, 1), |(i, _)| i as Float).into_dyn().into())
+            .value_public(true)
+            .build();
+        let weights = analysis.literal()
+            .value(ndarray::arr1(&[0., 1., 3., 0.5]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let size = analysis.literal().value(2.into()).value_public(true).build();
+        let sample = analysis.weighted_sample(data, weights, size).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::Public).unwrap();
+
+        // two distinct rows are drawn, and the row with zero weight is never drawn
+        let sample = release.get(&sample).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap().clone().float().unwrap().iter().copied().collect::<Vec<Float>>();
+        assert_eq!(sample.len(), 2);
+        assert_ne!(sample[0], sample[1]);
+        assert!(!sample.contains(&0.));
+    }
+
+    #[test]
+    fn test_release_submission() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((10, 1), |(i, _)| i as Float).into_dyn().into())
+            .value_public(false)
+            .build();
+        let count = analysis.count(data).build();
+        let first = analysis.laplace_mechanism(count, privacy_usage(0.5, 0.)).build();
+
+        // the second query is made in a later submission
+        analysis.submission_count += 1;
+        let count = analysis.count(data).build();
+        let second = analysis.laplace_mechanism(count, privacy_usage(0.5, 0.)).build();
+
+        let (release, warnings) = crate::release_submission(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            analysis.release.clone(),
+            proto::FilterLevel::Public, 1).unwrap();
+        assert_eq!(release.keys().copied().collect::<Vec<u32>>(), vec![second], "{:?}", warnings);
+
+        // the earlier submission, and everything it depends on
+        let (release, _) = crate::release_submission(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All, 0).unwrap();
+        assert!(release.contains_key(&first));
+        assert!(release.contains_key(&data));
+        assert!(!release.contains_key(&second));
+    }
+
+    #[test]
+    fn test_estimate_resources() {
+        use smartnoise_validator::base::Array as ArrayValue;
+
+        let path = std::env::temp_dir().join(format!("smartnoise_test_estimate_{}.csv", std::process::id()));
+        std::fs::write(&path, (0..600).map(|i| format!("{}\n", i % 10)).collect::<String>()).unwrap();
+
+        let mut analysis = Analysis::new();
+        let names = analysis.literal()
+            .value(ndarray::arr1(&["x".to_string()]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let data = analysis.materialize(path.to_str().unwrap().to_string())
+            .column_names(names)
+            .skip_row(false)
+            .build();
+        let x = index_by_name(&mut analysis, data, "x");
+        let x = analysis.to_float(x).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let x = analysis.clamp(x).lower(lower).upper(upper).build();
+        let x = analysis.impute(x).build();
+        let number_rows = analysis.literal().value(1000.into()).value_public(true).build();
+        let resized = analysis.resize(x).number_rows(number_rows).lower(lower).upper(upper).build();
+        let mean = analysis.mean(resized).build();
+
+        let (estimates, _) = crate::estimate_resources(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            analysis.release.clone()).unwrap();
+
+        // the number of records in the file is not known until it is read
+        assert!(!estimates.memory.contains_key(&data));
+        assert!(!estimates.complete);
+        assert_eq!(estimates.elements_read.get(&mean), Some(&1000));
+
+        let release = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::All);
+        std::fs::remove_file(&path).unwrap();
+        let (release, warnings) = release.unwrap();
+
+        let actual_memory = |node_id: u32| match release.get(&node_id).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap() {
+            ArrayValue::Float(array) => (array.len() * std::mem::size_of::<Float>()) as u64,
+            _ => panic!("expected float data")
+        };
+
+        for node_id in &[resized, mean] {
+            let estimate = *estimates.memory.get(node_id).unwrap();
+            let actual = actual_memory(*node_id);
+            assert!(estimate <= 2 * actual && actual <= 2 * estimate, "estimate {} should be near {}", estimate, actual);
+        }
+    }
+
+    #[test]
+    fn test_dp_proportion() {
+        use smartnoise_validator::utilities::privacy::get_epsilon;
+
+        let path = std::env::temp_dir().join(format!("smartnoise_test_proportion_{}.csv", std::process::id()));
+        std::fs::write(&path, (0..1000)
+            .map(|i| if i % 10 < 3 { "y\n" } else { "n\n" }).collect::<String>()).unwrap();
+
+        let mut analysis = Analysis::new();
+        let names = analysis.literal()
+            .value(ndarray::arr1(&["x".to_string()]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let data = analysis.materialize(path.to_str().unwrap().to_string())
+            .column_names(names)
+            .skip_row(false)
+            .build();
+        let x = index_by_name(&mut analysis, data, "x");
+        let y = analysis.literal().value("y".to_string().into()).value_public(true).build();
+        let success = analysis.equal(x, y).build();
+
+        // the number of records in the file is not known, so it is also privatized
+        let lower = analysis.literal().value(0.into()).value_public(true).build();
+        let count = analysis.dp_count(success, lower, privacy_usage(5., 0.)).build();
+        let proportion = analysis.dp_proportion(success, privacy_usage(5., 0.))
+            .count(count)
+            .build();
+
+        let release = crate::release(
+            Some(analysis.privacy_definition.clone()),
+            analysis.components.clone(),
+            analysis.release,
+            proto::FilterLevel::Public);
+        std::fs::remove_file(&path).unwrap();
+        let (release, warnings) = release.unwrap();
+
+        let value = release.get(&proportion).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap().first_float().unwrap();
+        assert!((value - 0.3).abs() < 0.05, "{} should be near 0.3", value);
+
+        // the usage is the sum of the usages of the two counts
+        let usage = smartnoise_validator::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, release).unwrap();
+        assert!((get_epsilon(&usage).unwrap() - 10.).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_dp_clamped_fraction() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((1000, 2), |(i, j)| (i % 10 + j) as Float).into_dyn().into())
+            .value_public(false)
+            .build();
+        let data = analysis.to_float(data).build();
+        let number_rows = analysis.literal().value(1000.into()).value_public(true).build();
+        let number_columns = analysis.literal().value(2.into()).value_public(true).build();
+        let data_lower = analysis.literal().value((-1000.).into()).value_public(true).build();
+        let data_upper = analysis.literal().value(1000.0.into()).value_public(true).build();
+        let data = analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .lower(data_lower).upper(data_upper)
+            .build();
+
+        // the bounds are far outside of the data, so almost nothing is clamped
+        let lower = analysis.literal().value((-100.).into()).value_public(true).build();
+        let upper = analysis.literal().value(100.0.into()).value_public(true).build();
+        let fraction = analysis.dp_clamped_fraction(data, lower, upper, privacy_usage(10., 0.))
+            .mechanism("Laplace".to_string()).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::Public).unwrap();
+
+        let fraction = release.get(&fraction).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap().ref_float().unwrap().clone();
+        assert_eq!(fraction.len(), 2);
+        assert!(fraction.iter().all(|v| (0. ..0.05).contains(v)), "{:?} should be near zero", fraction);
+    }
+
+    #[test]
+    fn test_dp_max_frequency() {
+        let mut analysis = Analysis::new();
+        // category "a" is the most frequent, with 600 of the 1000 records
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((1000, 1), |(i, _)| match i % 10 {
+                0..=5 => "a", 6..=8 => "b", _ => "c"
+            }.to_string()).into_dyn().into())
+            .value_public(false)
+            .build();
+        let data = analysis.to_string(data).build();
+        let categories = analysis.literal()
+            .value(smartnoise_validator::base::Value::Jagged(vec![vec!["a".to_string(), "b".to_string(), "c".to_string()]].into()))
+            .value_public(true)
+            .build();
+        let number_rows = analysis.literal().value(1000.into()).value_public(true).build();
+        let number_columns = analysis.literal().value(1.into()).value_public(true).build();
+        let data = analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .categories(categories)
+            .build();
+        let null_value = analysis.literal().value("c".to_string().into()).value_public(true).build();
+        let data = analysis.clamp(data).categories(categories).null_value(null_value).build();
+        let max_frequency = analysis.dp_max_frequency(data, privacy_usage(10., 0.)).build();
+
+        let (release, warnings) = crate::release(
+            Some(analysis.privacy_definition),
+            analysis.components,
+            analysis.release,
+            proto::FilterLevel::Public).unwrap();
+
+        let max_frequency = release.get(&max_frequency).unwrap_or_else(|| panic!("{:?}", warnings)).value
+            .ref_array().unwrap().first_int().unwrap();
+        assert!((590..=610).contains(&max_frequency), "{} should be near 600", max_frequency);
+    }
+
+    #[test]
+    fn test_nulls_not_aggregated() {
+        // a dp mean over data with NaN, imputed or not
+        let dp_mean = |impute: bool| {
+            let mut analysis = Analysis::new();
+            analysis.privacy_definition.protect_floating_point = false;
+            let data = analysis.literal()
+                .value(ndarray::arr2(&[[1.], [Float::NAN], [3.], [Float::NAN]]).into_dyn().into())
+                .value_public(false)
+                .build();
+            let data = analysis.to_float(data).build();
+            let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+            let upper = analysis.literal().value(4.0.into()).value_public(true).build();
+            let number_rows = analysis.literal().value(4.into()).value_public(true).build();
+            let number_columns = analysis.literal().value(1.into()).value_public(true).build();
+            let data = analysis.resize(data)
+                .number_rows(number_rows).number_columns(number_columns)
+                .lower(lower).upper(upper)
+                .build();
+            let mut data = analysis.clamp(data).lower(lower).upper(upper).build();
+            if impute {
+                data = analysis.impute(data).lower(lower).upper(upper).build();
+            }
+            let dp_mean = analysis.dp_mean(data, privacy_usage(1., 0.))
+                .mechanism("Laplace".to_string()).build();
+
+            crate::release(
+                Some(analysis.privacy_definition),
+                analysis.components,
+                analysis.release,
+                proto::FilterLevel::Public)
+                .map(|(release, _)| release.get(&dp_mean).unwrap().value
+                    .ref_array().unwrap().first_float().unwrap())
+        };
+
+        assert!(dp_mean(false).is_err());
+        assert!(dp_mean(true).unwrap().is_finite());
+
+        // the runtime also refuses to aggregate nulls, even if they are not detected statically
+        use crate::components::Evaluable;
+        let arguments: crate::NodeArguments = indexmap::indexmap![
+            "data".into() => ndarray::arr1(&[1., Float::NAN]).into_dyn().into()];
+        assert!(proto::Mean {}.evaluate(&None, arguments.clone()).is_err());
+        assert!(proto::Sum {}.evaluate(&None, arguments).is_err());
+    }
+
+    #[test]
+    fn test_dp_mean_rounding() {
+        use smartnoise_validator::base::Array;
+
+        // a dp mean over data in [0, 10], rounded to the resolution
+        let release_dp_mean = |resolution: Float| {
+            let mut analysis = Analysis::new();
+            analysis.privacy_definition.protect_floating_point = false;
+            let data = analysis.literal()
+                .value(ndarray::Array::from_shape_fn((100, 1), |(i, _)| (i % 11) as f64).into_dyn().into())
+                .value_public(true)
+                .build();
+            let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+            let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+            let data = analysis.clamp(data).lower(lower).upper(upper).build();
+            let dp_mean = analysis.dp_mean(data, privacy_usage(1., 0.))
+                .mechanism("Laplace".to_string())
+                .resolution(resolution)
+                .build();
+
+            let (release, warnings) = crate::release(
+                Some(analysis.privacy_definition),
+                analysis.components,
+                analysis.release,
+                proto::FilterLevel::Public).unwrap();
+            release.get(&dp_mean).unwrap_or_else(|| panic!("{:?}", warnings)).value.ref_array().unwrap().clone()
+        };
+
+        (0..10).for_each(|_| {
+            // the noised mean is snapped onto the grid
+            let mean = release_dp_mean(0.25).first_float().unwrap();
+            assert_eq!((mean / 0.25).fract(), 0., "{} is off the grid", mean);
+
+            // a whole-number resolution releases an integer
+            match release_dp_mean(2.) {
+                Array::Int(mean) => assert_eq!(mean.first().unwrap() % 2, 0),
+                _ => panic!("the mean must be integer-typed")
+            }
+        });
+    }
+}
@@ -0,0 +1,298 @@
+//! Load Arrow IPC and Parquet files into dataframes.
+//!
+//! Only available with the `use-arrow` feature.
+
+use std::io::{Read, Seek};
+
+use arrow_array::{Array as ArrowArray, ArrayRef, RecordBatch};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type, UInt8Type};
+use arrow_schema::{DataType as ArrowDataType, SchemaRef};
+use indexmap::IndexMap;
+use ndarray::ArrayD;
+
+use smartnoise_validator::{Float, Integer};
+use smartnoise_validator::base::{Array, DataType, IndexKey, Value};
+use smartnoise_validator::errors::*;
+
+/// Read an Arrow IPC file into a dataframe.
+///
+/// # Arguments
+/// * `reader` - source of the Arrow IPC file
+/// * `column_names` - names of the columns. If not set, names are read from the schema
+/// * `data_types` - declared atomic type of each column, used to load fields with nulls
+///
+/// # Return
+/// A dataframe with one column for each field in the schema.
+pub fn read_arrow_ipc<R: Read + Seek>(
+    reader: R, column_names: Option<Vec<IndexKey>>, data_types: Option<&[DataType]>
+) -> Result<IndexMap<IndexKey, Value>> {
+    let reader = arrow_ipc::reader::FileReader::try_new(reader, None)
+        .map_err(|e| Error::from(format!("arrow: {}", e)))?;
+    let schema = reader.schema();
+    let batches = reader.collect::<std::result::Result<Vec<RecordBatch>, _>>()
+        .map_err(|e| Error::from(format!("arrow: {}", e)))?;
+    batches_to_dataframe(schema, &batches, column_names, data_types)
+}
+
+/// Read a Parquet file into a dataframe.
+///
+/// # Arguments
+/// * `file` - the Parquet file
+/// * `column_names` - names of the columns. If not set, names are read from the schema
+/// * `data_types` - declared atomic type of each column, used to load fields with nulls
+///
+/// # Return
+/// A dataframe with one column for each field in the schema.
+pub fn read_parquet(
+    file: std::fs::File, column_names: Option<Vec<IndexKey>>, data_types: Option<&[DataType]>
+) -> Result<IndexMap<IndexKey, Value>> {
+    let builder = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)
+        .map_err(|e| Error::from(format!("parquet: {}", e)))?;
+    let schema = builder.schema().clone();
+    let batches = builder.build()
+        .map_err(|e| Error::from(format!("parquet: {}", e)))?
+        .collect::<std::result::Result<Vec<RecordBatch>, _>>()
+        .map_err(|e| Error::from(format!("parquet: {}", e)))?;
+    batches_to_dataframe(schema, &batches, column_names, data_types)
+}
+
+/// Map an Arrow type to the atomic type it is loaded as.
+///
+/// The atomic type depends only on the schema, not on the values in the file.
+pub fn arrow_to_data_type(data_type: &ArrowDataType) -> Result<DataType> {
+    Ok(match data_type {
+        ArrowDataType::Boolean => DataType::Bool,
+        ArrowDataType::Int8 | ArrowDataType::Int16 | ArrowDataType::Int32 | ArrowDataType::Int64 |
+        ArrowDataType::UInt8 | ArrowDataType::UInt16 | ArrowDataType::UInt32 => DataType::Int,
+        ArrowDataType::Float32 | ArrowDataType::Float64 => DataType::Float,
+        ArrowDataType::Utf8 | ArrowDataType::LargeUtf8 => DataType::Str,
+        other => return Err(format!("arrow type {:?} is not supported. Supported types are boolean, integers that fit in a signed 64-bit integer, floats and strings", other).into())
+    })
+}
+
+fn batches_to_dataframe(
+    schema: SchemaRef, batches: &[RecordBatch], column_names: Option<Vec<IndexKey>>, data_types: Option<&[DataType]>
+) -> Result<IndexMap<IndexKey, Value>> {
+    let column_names = match column_names {
+        Some(column_names) => {
+            if column_names.len() != schema.fields().len() {
+                return Err(format!("column_names: {} names were supplied, but the schema has {} fields",
+                                   column_names.len(), schema.fields().len()).into())
+            }
+            column_names
+        }
+        None => schema.fields().iter()
+            .map(|field| IndexKey::from(field.name().to_string())).collect()
+    };
+
+    column_names.into_iter().zip(schema.fields().iter()).enumerate()
+        .map(|(idx, (name, field))| {
+            let columns = batches.iter()
+                .map(|batch| batch.column(idx).clone())
+                .collect::<Vec<ArrayRef>>();
+            let declared = data_types.and_then(|data_types| data_types.get(idx));
+            let array = column_to_array(field.data_type(), declared, &columns)
+                .map_err(|e| Error::from(format!("{}: {}", name.to_string(), e)))?;
+            Ok((name, Value::Array(array)))
+        })
+        .collect()
+}
+
+/// Bool and int columns cannot represent nulls, so fields with nulls are loaded as their declared type.
+/// Nulls are loaded as NaN in float columns and as empty strings in string columns.
+fn column_to_array(data_type: &ArrowDataType, declared: Option<&DataType>, columns: &[ArrayRef]) -> Result<Array> {
+    let num_nulls = columns.iter().map(|column| column.null_count()).sum::<usize>();
+    let check_nulls = |data_type: DataType| if num_nulls > 0 {
+        Err(Error::from(format!("{} nulls cannot be represented in a column of type {:?}. Declare the type of the column as float or string with data_types",
+                                num_nulls, data_type)))
+    } else { Ok(()) };
+
+    macro_rules! load_integer {
+        ($arrow_type:ty) => {
+            {
+                let values = columns.iter()
+                    .flat_map(|column| column.as_primitive::<$arrow_type>().iter()
+                        .collect::<Vec<_>>())
+                    .collect::<Vec<_>>();
+                match declared {
+                    Some(DataType::Float) => Array::Float(to_array(values.into_iter()
+                        .map(|v| v.map(|v| v as Float).unwrap_or(Float::NAN)).collect())),
+                    Some(DataType::Str) => Array::Str(to_array(values.into_iter()
+                        .map(|v| v.map(|v| v.to_string()).unwrap_or_default()).collect())),
+                    _ => {
+                        check_nulls(DataType::Int)?;
+                        Array::Int(to_array(values.into_iter()
+                            .map(|v| v.unwrap() as Integer).collect()))
+                    }
+                }
+            }
+        }
+    }
+
+    macro_rules! load_float {
+        ($arrow_type:ty) => {
+            Array::Float(to_array(columns.iter()
+                .flat_map(|column| column.as_primitive::<$arrow_type>().iter()
+                    .map(|v| v.map(|v| v as Float).unwrap_or(Float::NAN))
+                    .collect::<Vec<Float>>())
+                .collect()))
+        }
+    }
+
+    // validate the type before loading
+    arrow_to_data_type(data_type)?;
+
+    Ok(match data_type {
+        ArrowDataType::Boolean => {
+            let values = columns.iter()
+                .flat_map(|column| column.as_boolean().iter().collect::<Vec<Option<bool>>>())
+                .collect::<Vec<Option<bool>>>();
+            if let Some(DataType::Str) = declared {
+                Array::Str(to_array(values.into_iter()
+                    .map(|v| v.map(|v| v.to_string()).unwrap_or_default()).collect()))
+            } else {
+                check_nulls(DataType::Bool)?;
+                Array::Bool(to_array(values.into_iter().map(Option::unwrap).collect()))
+            }
+        }
+        ArrowDataType::Int8 => load_integer!(Int8Type),
+        ArrowDataType::Int16 => load_integer!(Int16Type),
+        ArrowDataType::Int32 => load_integer!(Int32Type),
+        ArrowDataType::Int64 => load_integer!(Int64Type),
+        ArrowDataType::UInt8 => load_integer!(UInt8Type),
+        ArrowDataType::UInt16 => load_integer!(UInt16Type),
+        ArrowDataType::UInt32 => load_integer!(UInt32Type),
+        ArrowDataType::Float32 => load_float!(Float32Type),
+        ArrowDataType::Float64 => load_float!(Float64Type),
+        ArrowDataType::Utf8 => Array::Str(to_array(columns.iter()
+            .flat_map(|column| column.as_string::<i32>().iter()
+                .map(|v| v.unwrap_or_default().to_string())
+                .collect::<Vec<String>>())
+            .collect())),
+        ArrowDataType::LargeUtf8 => Array::Str(to_array(columns.iter()
+            .flat_map(|column| column.as_string::<i64>().iter()
+                .map(|v| v.unwrap_or_default().to_string())
+                .collect::<Vec<String>>())
+            .collect())),
+        _ => unreachable!()
+    })
+}
+
+fn to_array<T>(values: Vec<T>) -> ArrayD<T> {
+    ndarray::Array::from(values).into_dyn()
+}
+
+#[cfg(test)]
+mod test_arrow {
+    use std::io::Cursor;
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, BooleanArray, Float64Array, Int32Array, ListArray, RecordBatch, StringArray};
+    use arrow_array::types::Int32Type;
+    use arrow_schema::{DataType as ArrowDataType, Field, Schema};
+
+    use smartnoise_validator::base::{Array, DataType, IndexKey};
+
+    use crate::utilities::arrow::{read_arrow_ipc, read_parquet};
+
+    fn batch() -> RecordBatch {
+        RecordBatch::try_from_iter(vec![
+            ("name", Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])) as ArrayRef),
+            ("age", Arc::new(Int32Array::from(vec![34, 27, 51])) as ArrayRef),
+            ("height", Arc::new(Float64Array::from(vec![Some(1.62), None, Some(1.80)])) as ArrayRef),
+            ("member", Arc::new(BooleanArray::from(vec![true, false, true])) as ArrayRef),
+        ]).unwrap()
+    }
+
+    fn batch_with_nulls() -> RecordBatch {
+        RecordBatch::try_from_iter(vec![
+            ("visits", Arc::new(Int32Array::from(vec![Some(1), None, Some(3)])) as ArrayRef),
+            ("member", Arc::new(BooleanArray::from(vec![Some(true), None, Some(false)])) as ArrayRef),
+        ]).unwrap()
+    }
+
+    fn write_ipc(batch: &RecordBatch) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut writer = arrow_ipc::writer::FileWriter::try_new(&mut buffer, &batch.schema()).unwrap();
+        writer.write(batch).unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+        buffer
+    }
+
+    fn check_dataframe(dataframe: &indexmap::IndexMap<IndexKey, smartnoise_validator::base::Value>) {
+        assert_eq!(dataframe.keys().cloned().collect::<Vec<_>>(),
+                   vec![IndexKey::from("name"), "age".into(), "height".into(), "member".into()]);
+
+        let column = |name: &str| dataframe.get::<IndexKey>(&name.into()).unwrap().ref_array().unwrap();
+        match column("name") {
+            Array::Str(names) => assert_eq!(names.iter().cloned().collect::<Vec<_>>(), vec!["a", "", "c"]),
+            _ => panic!("name must be loaded as string")
+        }
+        match column("age") {
+            Array::Int(ages) => assert_eq!(ages.iter().cloned().collect::<Vec<_>>(), vec![34, 27, 51]),
+            _ => panic!("age must be loaded as int")
+        }
+        match column("height") {
+            Array::Float(heights) => assert!(heights[1].is_nan() && heights[2] == 1.80),
+            _ => panic!("height must be loaded as float")
+        }
+        assert!(matches!(column("member"), Array::Bool(_)));
+    }
+
+    #[test]
+    fn test_arrow_ipc_round_trip() {
+        let dataframe = read_arrow_ipc(Cursor::new(write_ipc(&batch())), None, None).unwrap();
+        check_dataframe(&dataframe);
+
+        // supplied names take precedence over the schema
+        let names = vec!["a".into(), "b".into(), "c".into(), "d".into()];
+        let dataframe = read_arrow_ipc(Cursor::new(write_ipc(&batch())), Some(names.clone()), None).unwrap();
+        assert_eq!(dataframe.keys().cloned().collect::<Vec<IndexKey>>(), names);
+    }
+
+    #[test]
+    fn test_arrow_nulls() {
+        // integers and booleans with nulls are not widened to another type
+        let error = read_arrow_ipc(Cursor::new(write_ipc(&batch_with_nulls())), None, None).unwrap_err();
+        assert!(error.to_string().starts_with("visits: 1 nulls cannot be represented in a column of type Int"), "{}", error);
+
+        // the declared type determines how nulls are loaded
+        let dataframe = read_arrow_ipc(Cursor::new(write_ipc(&batch_with_nulls())), None,
+                                       Some(&[DataType::Float, DataType::Str])).unwrap();
+        match dataframe.get::<IndexKey>(&"visits".into()).unwrap().ref_array().unwrap() {
+            Array::Float(visits) => assert!(visits[1].is_nan() && visits[2] == 3.),
+            _ => panic!("visits must be loaded as float")
+        }
+        match dataframe.get::<IndexKey>(&"member".into()).unwrap().ref_array().unwrap() {
+            Array::Str(members) => assert_eq!(members.iter().cloned().collect::<Vec<_>>(), vec!["true", "", "false"]),
+            _ => panic!("member must be loaded as string")
+        }
+    }
+
+    #[test]
+    fn test_parquet_round_trip() {
+        let path = std::env::temp_dir().join(format!("smartnoise_test_{}.parquet", std::process::id()));
+        let batch = batch();
+        let mut writer = parquet::arrow::ArrowWriter::try_new(
+            std::fs::File::create(&path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let dataframe = read_parquet(std::fs::File::open(&path).unwrap(), None, None);
+        std::fs::remove_file(&path).unwrap();
+        check_dataframe(&dataframe.unwrap());
+    }
+
+    #[test]
+    fn test_arrow_unsupported_type() {
+        let lists = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![Some(vec![Some(1)]), None]);
+        let schema = Schema::new(vec![Field::new("lists", ArrowDataType::List(Arc::new(
+            Field::new("item", ArrowDataType::Int32, true))), true)]);
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(lists)]).unwrap();
+
+        let error = read_arrow_ipc(Cursor::new(write_ipc(&batch)), None, None).unwrap_err();
+        assert!(error.to_string().contains("lists: arrow type List"), "{}", error);
+    }
+}
@@ -1,10 +1,64 @@
 use smartnoise_validator::errors::*;
 
 use crate::utilities;
-use smartnoise_validator::Float;
+use smartnoise_validator::{Float, Integer};
 use crate::utilities::{noise};
-use smartnoise_validator::components::gaussian_mechanism::get_analytic_gaussian_sigma;
+use smartnoise_validator::components::gaussian_mechanism::{check_discrete_gaussian_scale, get_gaussian_scale};
+pub use smartnoise_validator::components::laplace_mechanism::get_grid_sensitivity;
+use smartnoise_validator::components::stability_mechanism::get_stability_threshold;
+use smartnoise_validator::components::truncated_laplace_mechanism::get_truncated_laplace_bound;
+use std::collections::BTreeMap;
 use std::ops::{Div};
+use ndarray::{ArrayView1, ArrayViewMut1};
+
+/// Number of elements above which noise is sampled in parallel, when built with the `use-rayon` feature.
+pub const PARALLEL_THRESHOLD: usize = 1024;
+
+/// Privatizes each value with a mechanism that samples independent noise for each element.
+///
+/// With the `use-rayon` feature, noise for columns longer than `PARALLEL_THRESHOLD` is sampled in parallel.
+/// Every draw takes its randomness directly from OpenSSL, which is safe to call from multiple threads,
+/// so no random state is shared between threads.
+///
+/// # Arguments
+/// * `values` - Column of statistics to be privatized in place.
+/// * `sensitivities` - Sensitivity of each statistic.
+/// * `mechanism` - Function that privatizes a value with the given sensitivity.
+///
+/// # Example
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_runtime::utilities::mechanisms::{apply_elementwise, laplace_mechanism};
+/// let mut values = arr1(&[2., 4., 8.]);
+/// let sensitivities = arr1(&[1., 1., 1.]);
+/// apply_elementwise(values.view_mut(), sensitivities.view(),
+///     |v, sens| laplace_mechanism(v, 1., sens, false)).unwrap();
+/// ```
+pub fn apply_elementwise<F>(
+    mut values: ArrayViewMut1<Float>, sensitivities: ArrayView1<Float>, mechanism: F,
+) -> Result<()> where F: Fn(Float, Float) -> Result<Float> + Sync {
+    if values.len() != sensitivities.len() {
+        return Err("values and sensitivities must have the same length".into())
+    }
+
+    #[cfg(feature = "use-rayon")] {
+        if values.len() > PARALLEL_THRESHOLD {
+            use rayon::prelude::*;
+            let pairs = values.iter().copied()
+                .zip(sensitivities.iter().copied())
+                .collect::<Vec<(Float, Float)>>();
+            let privatized = pairs.into_par_iter()
+                .map(|(value, sensitivity)| mechanism(value, sensitivity))
+                .collect::<Result<Vec<Float>>>()?;
+            values.iter_mut().zip(privatized).for_each(|(value, privatized)| *value = privatized);
+            return Ok(())
+        }
+    }
+
+    values.iter_mut().zip(sensitivities.iter())
+        .try_for_each(|(value, sensitivity)| mechanism(*value, *sensitivity)
+            .map(|privatized| *value = privatized))
+}
 
 /// Returns noise drawn according to the Laplace mechanism
 ///
@@ -40,10 +94,57 @@ pub fn laplace_mechanism(
     if epsilon <= 0. {
         return Err(format!("epsilon ({}) must be positive", epsilon).into())
     }
+    // a statistic with zero sensitivity is the same on all neighboring datasets, so no noise is needed
+    if sensitivity == 0. {
+        return Ok(value)
+    }
     let scale: f64 = sensitivity / epsilon;
     noise::sample_laplace(0., scale, enforce_constant_time).map(|n| value + n)
 }
 
+/// Returns noise drawn according to the truncated Laplace mechanism
+///
+/// Noise is drawn with scale sensitivity/epsilon and centered about 0, conditioned on lying within [-A, A],
+/// where A is chosen such that the truncated mass is paid for by delta.
+/// For more information, see [Geng et al. (2020)](https://arxiv.org/abs/1810.00877).
+///
+/// NOTE: this implementation of Laplace draws is likely non-private due to floating-point attacks
+/// See [Mironov (2012)](http://citeseerx.ist.psu.edu/viewdoc/download?doi=10.1.1.366.5957&rep=rep1&type=pdf)
+/// for more information
+///
+/// # Arguments
+/// * `value` - Statistic to be privatized.
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `delta` - Additive privacy loss parameter.
+/// * `sensitivity` - Upper bound on the L1 sensitivity of the function you want to privatize.
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Return
+/// The statistic perturbed with truncated Laplace noise, within A of the statistic.
+///
+/// # Examples
+/// ```
+/// use smartnoise_runtime::utilities::mechanisms::truncated_laplace_mechanism;
+/// let n = truncated_laplace_mechanism(22.3, 0.1, 1e-6, 2.0, false);
+/// ```
+pub fn truncated_laplace_mechanism(
+    value: f64, epsilon: f64, delta: f64, sensitivity: f64, enforce_constant_time: bool
+) -> Result<f64> {
+    if sensitivity < 0. {
+        return Err(format!("sensitivity ({}) must be non-negative", sensitivity).into());
+    }
+    if epsilon <= 0. || delta <= 0. {
+        return Err(format!("epsilon ({}) and delta ({}) must be positive", epsilon, delta).into());
+    }
+    // a statistic with zero sensitivity is the same on all neighboring datasets, so no noise is needed
+    if sensitivity == 0. {
+        return Ok(value)
+    }
+    let scale: f64 = sensitivity / epsilon;
+    let bound = get_truncated_laplace_bound(epsilon, delta, sensitivity);
+    noise::sample_laplace_truncated(-bound, bound, 0., scale, enforce_constant_time).map(|n| value + n)
+}
+
 /// Computes privatized value according to the Snapping mechanism
 ///
 /// Developed as a variant of the Laplace mechanism which does not suffer from floating-point side channel attacks.
@@ -88,6 +189,11 @@ pub fn snapping_mechanism(
         return Err("lower may not be greater than upper".into())
     }
 
+    // a statistic with zero sensitivity is the same on all neighboring datasets, so no noise is needed
+    if sensitivity == 0. {
+        return Ok(num::clamp(value, min, max))
+    }
+
     let mut b = (max - min) / 2.;
     let shift = min + b;
 
@@ -156,19 +262,120 @@ pub fn gaussian_mechanism(
     analytic: bool,
     enforce_constant_time: bool
 ) -> Result<f64> {
-    if epsilon <= 0. || delta <= 0. || sensitivity <= 0. {
-        return Err(format!("epsilon ({}), delta ({}) and sensitivity ({}) must all be positive", epsilon, delta, sensitivity).into());
+    if sensitivity < 0. {
+        return Err(format!("sensitivity ({}) must be non-negative", sensitivity).into());
+    }
+    if epsilon <= 0. || delta <= 0. {
+        return Err(format!("epsilon ({}) and delta ({}) must be positive", epsilon, delta).into());
+    }
+    // a statistic with zero sensitivity is the same on all neighboring datasets, so no noise is needed
+    if sensitivity == 0. {
+        return Ok(value)
     }
 
-    let scale = if analytic {
-        get_analytic_gaussian_sigma(epsilon, delta, sensitivity)
-    } else {
-        sensitivity * (2. * (1.25 / delta).ln()).sqrt() / epsilon
-    };
+    let scale = get_gaussian_scale(epsilon, delta, sensitivity, analytic);
     // this uses mpfr noise if available
     Ok(value + noise::sample_gaussian(0., scale, enforce_constant_time)?)
 }
 
+// index of the nearest point on the grid, rounding halves upward
+fn quantize(value: f64, resolution: f64) -> Result<i64> {
+    if resolution <= 0. || !resolution.is_finite() {
+        return Err(format!("resolution ({}) must be positive and finite", resolution).into())
+    }
+    let index = (value / resolution + 0.5).floor();
+    if !index.is_finite() || index.abs() >= 2f64.powi(62) {
+        return Err(format!("value ({}) cannot be indexed on a grid with resolution {}", value, resolution).into())
+    }
+    Ok(index as i64)
+}
+
+/// Returns a multiple of `resolution` drawn according to the discrete Laplace mechanism.
+///
+/// The value is rounded to the nearest multiple of `resolution`,
+/// and two-sided geometric noise with scale `grid_sensitivity / epsilon` is added to its index on the grid.
+/// Since the noise is an integer, the release may be transported as its index on the grid.
+/// For more information, see [Ghosh, Roughgarden, & Sundarajan (2012)](https://theory.stanford.edu/~tim/papers/priv.pdf).
+///
+/// # Arguments
+/// * `value` - Statistic to be privatized.
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `grid_sensitivity` - L1 sensitivity of the rounded statistic, in units of `resolution`. See `get_grid_sensitivity`.
+/// * `resolution` - Spacing of the grid.
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Return
+/// A multiple of `resolution`.
+///
+/// # Examples
+/// ```
+/// use smartnoise_runtime::utilities::mechanisms::discrete_laplace_mechanism;
+/// let n = discrete_laplace_mechanism(22.3, 0.1, 8., 0.25, false).unwrap();
+/// assert_eq!((n / 0.25).fract(), 0.);
+/// ```
+pub fn discrete_laplace_mechanism(
+    value: f64, epsilon: f64, grid_sensitivity: f64, resolution: f64,
+    enforce_constant_time: bool
+) -> Result<f64> {
+    if grid_sensitivity < 0. {
+        return Err(format!("sensitivity ({}) must be non-negative", grid_sensitivity).into());
+    }
+    if epsilon <= 0. {
+        return Err(format!("epsilon ({}) must be positive", epsilon).into())
+    }
+    let mut index = quantize(value, resolution)?;
+    if grid_sensitivity > 0. {
+        index += noise::sample_discrete_laplace(grid_sensitivity / epsilon, enforce_constant_time)?;
+    }
+    Ok(index as f64 * resolution)
+}
+
+/// Returns a multiple of `resolution` drawn according to the discrete Gaussian mechanism.
+///
+/// The value is rounded to the nearest multiple of `resolution`,
+/// and discrete Gaussian noise is added to its index on the grid.
+/// The noise scale is calibrated to `grid_sensitivity` in the same way as the continuous `gaussian_mechanism`.
+/// The discrete Gaussian satisfies the same privacy guarantee as the continuous Gaussian with equal scale,
+/// up to a term that is negligible for scales above one, so smaller scales are rejected.
+/// See [Canonne, Kamath & Steinke (2020)](https://arxiv.org/abs/2004.00010).
+///
+/// # Arguments
+/// * `value` - Statistic to be privatized.
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `delta` - Additive privacy loss parameter.
+/// * `grid_sensitivity` - L2 sensitivity of the rounded statistic, in units of `resolution`. See `get_grid_sensitivity`.
+/// * `resolution` - Spacing of the grid.
+/// * `analytic` - Whether to calibrate the scale with the analytic gaussian.
+///
+/// # Return
+/// A multiple of `resolution`.
+///
+/// # Examples
+/// ```
+/// use smartnoise_runtime::utilities::mechanisms::discrete_gaussian_mechanism;
+/// let n = discrete_gaussian_mechanism(22.3, 0.1, 0.0001, 8., 0.25, true).unwrap();
+/// assert_eq!((n / 0.25).fract(), 0.);
+/// ```
+pub fn discrete_gaussian_mechanism(
+    value: f64,
+    epsilon: f64, delta: f64, grid_sensitivity: f64, resolution: f64,
+    analytic: bool
+) -> Result<f64> {
+    if grid_sensitivity < 0. {
+        return Err(format!("sensitivity ({}) must be non-negative", grid_sensitivity).into());
+    }
+    if epsilon <= 0. || delta <= 0. {
+        return Err(format!("epsilon ({}) and delta ({}) must be positive", epsilon, delta).into());
+    }
+    let mut index = quantize(value, resolution)?;
+    if grid_sensitivity > 0. {
+        let scale = get_gaussian_scale(epsilon, delta, grid_sensitivity, analytic);
+        check_discrete_gaussian_scale(scale)?;
+        index += noise::sample_discrete_gaussian(scale)?;
+    }
+    Ok(index as f64 * resolution)
+}
+
 /// Returns noise drawn according to the Geometric mechanism.
 ///
 /// Uses the Geometric mechanism as originally proposed in
@@ -201,12 +408,60 @@ pub fn simple_geometric_mechanism(
     if epsilon < 0. || sensitivity < 0. {
         return Err(format!("epsilon ({}) and sensitivity ({}) must be positive", epsilon, sensitivity).into());
     }
+    // a statistic with zero sensitivity is the same on all neighboring datasets, so no noise is needed
+    if sensitivity == 0. {
+        return Ok(num::clamp(value, min, max))
+    }
     let scale: f64 = sensitivity / epsilon;
     let noised = value + noise::sample_simple_geometric_mechanism(scale, min, max, enforce_constant_time)?;
 
     Ok(if noised < min {min} else if noised > max { max } else { noised })
 }
 
+/// Returns a histogram over the categories observed in the data, according to the stability mechanism.
+///
+/// Geometric noise with scale sensitivity/epsilon is added to the count of each observed category,
+/// and categories whose noisy count falls below a threshold calibrated to delta are dropped.
+/// For more information, see Proposition 3.5 of
+/// [Vadhan (2017)](https://privacytools.seas.harvard.edu/files/privacytools/files/complexityprivacy_1.pdf).
+///
+/// # Arguments
+/// * `data` - Column of categorical data.
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `delta` - Additive privacy loss parameter.
+/// * `sensitivity` - L1 sensitivity of the counts.
+///
+/// # Return
+/// The released categories, in sorted order, and their noisy counts.
+///
+/// # Examples
+/// ```
+/// use ndarray::arr1;
+/// use smartnoise_runtime::utilities::mechanisms::stability_mechanism;
+/// let data = arr1(&["a", "a", "b"]);
+/// let (categories, counts) = stability_mechanism(data.view(), 1., 1e-6, 1.).unwrap();
+/// assert_eq!(categories.len(), counts.len());
+/// ```
+pub fn stability_mechanism<T: Clone + Ord>(
+    data: ArrayView1<T>, epsilon: f64, delta: f64, sensitivity: f64,
+) -> Result<(Vec<T>, Vec<Integer>)> {
+    let threshold = get_stability_threshold(epsilon, delta, sensitivity)?;
+
+    let mut counts = BTreeMap::<&T, Integer>::new();
+    data.iter().for_each(|v| *counts.entry(v).or_insert(0) += 1);
+
+    let mut categories = Vec::new();
+    let mut noised_counts = Vec::new();
+    for (category, count) in counts {
+        let noised = simple_geometric_mechanism(count, epsilon, sensitivity, 0, Integer::MAX, false)?;
+        if noised >= threshold {
+            categories.push(category.clone());
+            noised_counts.push(noised);
+        }
+    }
+    Ok((categories, noised_counts))
+}
+
 /// Returns data element according to the Exponential mechanism.
 ///
 /// # Arguments
@@ -217,6 +472,8 @@ pub fn simple_geometric_mechanism(
 /// * `utility` - Utility function used within the exponential mechanism.
 /// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
 ///
+/// Candidates with equal utility are selected uniformly, and the selection distribution does not depend on the order of the candidates.
+///
 /// NOTE: This implementation is likely non-private because of the difference between theory on
 ///       the real numbers and floating-point numbers. See [Ilvento 2019](https://arxiv.org/abs/1912.04222) for
 ///       more information on the problem and a proposed fix.
@@ -279,4 +536,200 @@ pub fn exponential_mechanism<T>(
 
     // sample element relative to probability
     utilities::sample_from_set(candidate_set, &weight_vec, enforce_constant_time)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_mechanisms {
+    use ndarray::Array1;
+
+    use crate::utilities::mechanisms::{apply_elementwise, laplace_mechanism, gaussian_mechanism, truncated_laplace_mechanism, PARALLEL_THRESHOLD, discrete_laplace_mechanism, discrete_gaussian_mechanism, get_grid_sensitivity, get_gaussian_scale, exponential_mechanism, simple_geometric_mechanism, snapping_mechanism};
+    use crate::utilities::noise::{SeededEntropy, with_entropy_source};
+
+    fn variance(values: &[f64]) -> f64 {
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+    }
+
+    #[test]
+    fn test_elementwise_variance() {
+        let n = 100 * PARALLEL_THRESHOLD;
+        let sensitivities = Array1::from_elem(n, 1.);
+
+        // noise on long columns is sampled in parallel when built with use-rayon
+        let mut parallel = Array1::zeros(n);
+        apply_elementwise(parallel.view_mut(), sensitivities.view(),
+                          |v, sens| laplace_mechanism(v, 1., sens, false)).unwrap();
+        let serial = (0..n)
+            .map(|_| laplace_mechanism(0., 1., 1., false))
+            .collect::<Result<Vec<f64>, _>>().unwrap();
+
+        // the variance of Laplace(1) noise is 2
+        let (parallel, serial) = (variance(parallel.as_slice().unwrap()), variance(&serial));
+        assert!((parallel - 2.).abs() < 0.1, "parallel variance: {}", parallel);
+        assert!((serial - 2.).abs() < 0.1, "serial variance: {}", serial);
+        assert!((parallel - serial).abs() < 0.15);
+
+        let mut parallel = Array1::zeros(n);
+        apply_elementwise(parallel.view_mut(), sensitivities.view(),
+                          |v, sens| gaussian_mechanism(v, 1., 1e-6, sens, false, false)).unwrap();
+        let serial = (0..n)
+            .map(|_| gaussian_mechanism(0., 1., 1e-6, 1., false, false))
+            .collect::<Result<Vec<f64>, _>>().unwrap();
+
+        let (parallel, serial) = (variance(parallel.as_slice().unwrap()), variance(&serial));
+        assert!((parallel / serial - 1.).abs() < 0.05, "parallel: {}, serial: {}", parallel, serial);
+    }
+
+    #[test]
+    fn test_constant_time() {
+        use crate::utilities::count_bytes_drawn;
+        use crate::utilities::noise::sample_gaussian_truncated;
+
+        // with the flag on, every sample draws the same number of random bytes, regardless of its value
+        let assert_constant = |sampler: &dyn Fn() -> f64| {
+            let draws = (0..1000)
+                .map(|_| count_bytes_drawn(sampler))
+                .collect::<Vec<(f64, usize)>>();
+            assert!(draws.iter().any(|(v, _)| *v > 1.) && draws.iter().any(|(v, _)| *v < -1.));
+            assert!(draws.iter().all(|(_, count)| *count == draws[0].1), "{:?}", draws[0]);
+        };
+        assert_constant(&|| laplace_mechanism(0., 1., 1., true).unwrap());
+        assert_constant(&|| gaussian_mechanism(0., 1., 1e-6, 1., false, true).unwrap());
+        assert_constant(&|| gaussian_mechanism(0., 1., 1e-6, 1., true, true).unwrap());
+        assert_constant(&|| sample_gaussian_truncated(-3., 3., 0., 2., true).unwrap());
+        assert_constant(&|| truncated_laplace_mechanism(0., 1., 1e-6, 1., true).unwrap());
+        assert_constant(&|| discrete_laplace_mechanism(0., 1., 4., 0.5, true).unwrap());
+
+        // truncated samples remain within the bounds
+        (0..1000).for_each(|_| {
+            let sample = sample_gaussian_truncated(1., 2., 0., 1., true).unwrap();
+            assert!((1. ..=2.).contains(&sample));
+        });
+    }
+
+    #[test]
+    fn test_truncated_laplace() {
+        use smartnoise_validator::components::truncated_laplace_mechanism::{get_truncated_laplace_bound, get_truncated_laplace_delta};
+
+        let (epsilon, delta, sensitivity) = (0.5, 1e-3, 2.);
+        let bound = get_truncated_laplace_bound(epsilon, delta, sensitivity);
+
+        // the delta charged is the delta implied by the mass of Laplace noise beyond the bound
+        let truncated_mass = (-bound * epsilon / sensitivity).exp();
+        let implied_delta = epsilon.exp_m1() / 2. * truncated_mass / (1. - truncated_mass);
+        assert!((implied_delta - delta).abs() <= delta * 1e-8, "{} != {}", implied_delta, delta);
+        assert!((get_truncated_laplace_delta(epsilon, sensitivity, bound) - delta).abs() <= delta * 1e-8);
+
+        let samples = (0..10_000)
+            .map(|_| truncated_laplace_mechanism(10., epsilon, delta, sensitivity, false))
+            .collect::<Result<Vec<f64>, _>>().unwrap();
+        assert!(samples.iter().all(|v| (v - 10.).abs() <= bound), "noise must lie within {}", bound);
+        // the bound is loose enough to not bind in practice
+        assert!(samples.iter().any(|v| (v - 10.).abs() > bound / 2.));
+
+        // truncation requires a positive delta
+        assert!(truncated_laplace_mechanism(10., epsilon, 0., sensitivity, false).is_err());
+    }
+
+    #[test]
+    fn test_discrete_mechanisms() {
+        let resolution = 0.25;
+        let grid_sensitivity = get_grid_sensitivity(1., resolution, 1, 1);
+        (0..1000).for_each(|_| {
+            let laplace = discrete_laplace_mechanism(22.3, 1., grid_sensitivity, resolution, false).unwrap();
+            let gaussian = discrete_gaussian_mechanism(22.3, 1., 1e-6, grid_sensitivity, resolution, true).unwrap();
+            assert_eq!((laplace / resolution).fract(), 0., "{} is off the grid", laplace);
+            assert_eq!((gaussian / resolution).fract(), 0., "{} is off the grid", gaussian);
+        });
+
+        // without noise, the value is rounded to the nearest point on the grid
+        assert_eq!(discrete_laplace_mechanism(22.3, 1., 0., resolution, false).unwrap(), 22.25);
+
+        // as the resolution shrinks, the variance approaches that of the continuous mechanism
+        let samples = |sampler: &dyn Fn(f64) -> f64, resolution: f64| (0..5000)
+            .map(|_| sampler(resolution)).collect::<Vec<f64>>();
+        let laplace = |resolution| discrete_laplace_mechanism(
+            0., 1., get_grid_sensitivity(1., resolution, 1, 1), resolution, false).unwrap();
+        let gaussian = |resolution| discrete_gaussian_mechanism(
+            0., 1., 1e-6, get_grid_sensitivity(1., resolution, 1, 2), resolution, true).unwrap();
+
+        let fine = variance(&samples(&laplace, 1. / 64.));
+        assert!((fine / 2. - 1.).abs() < 0.15, "laplace variance: {}", fine);
+        // rounding the sensitivity up to the grid widens the noise at coarse resolutions
+        let coarse = variance(&samples(&laplace, 0.3));
+        assert!(coarse > 2. * 1.2, "laplace variance: {}", coarse);
+
+        let expected = get_gaussian_scale(1., 1e-6, 1., true).powi(2);
+        let fine = variance(&samples(&gaussian, 1. / 64.));
+        assert!((fine / expected - 1.).abs() < 0.1, "gaussian variance: {}, expected: {}", fine, expected);
+
+        // the discrete gaussian may not be sampled with a scale below one grid point
+        assert!(get_gaussian_scale(20., 1e-6, 1., true) < 1.);
+        assert!(discrete_gaussian_mechanism(0., 20., 1e-6, 1., 1., true).is_err());
+    }
+
+    #[test]
+    fn test_geometric_coverage() {
+        use smartnoise_validator::components::simple_geometric_mechanism::geometric_scale_to_accuracy;
+
+        let (epsilon, alpha, n) = (0.5, 0.05, 20_000);
+        let accuracy = geometric_scale_to_accuracy(1. / epsilon, alpha).unwrap() as i64;
+        let errors = (0..n)
+            .map(|_| simple_geometric_mechanism(0, epsilon, 1., -1_000_000, 1_000_000, false).map(i64::abs))
+            .collect::<Result<Vec<i64>, _>>().unwrap();
+        let coverage = |a: i64| errors.iter().filter(|e| **e <= a).count() as f64 / n as f64;
+
+        // the accuracy covers at least 1 - alpha of the samples, and any smaller accuracy does not
+        assert!(coverage(accuracy) > 1. - alpha, "coverage: {}", coverage(accuracy));
+        assert!(coverage(accuracy - 1) < 1. - alpha, "coverage: {}", coverage(accuracy - 1));
+    }
+
+    #[test]
+    fn test_grid_sensitivity() {
+        // a set of statistics can each round one grid point further apart
+        assert_eq!(get_grid_sensitivity(1., 0.5, 1, 1), 2.);
+        assert_eq!(get_grid_sensitivity(1., 0.5, 3, 1), 4.);
+        assert_eq!(get_grid_sensitivity(1., 0.5, 4, 2), 4.);
+    }
+
+    #[test]
+    fn test_zero_sensitivity() {
+        // constant statistics are released as-is, rather than sampling noise with zero scale
+        assert_eq!(laplace_mechanism(3., 1., 0., false).unwrap(), 3.);
+        assert_eq!(laplace_mechanism(3., 1., 0., true).unwrap(), 3.);
+        assert_eq!(truncated_laplace_mechanism(3., 1., 1e-6, 0., false).unwrap(), 3.);
+        assert_eq!(snapping_mechanism(3., 1., 0., 0., 10., None, false).unwrap(), 3.);
+        assert_eq!(gaussian_mechanism(3., 1., 1e-6, 0., false, false).unwrap(), 3.);
+        assert_eq!(discrete_laplace_mechanism(3., 1., 0., 0.5, false).unwrap(), 3.);
+        assert_eq!(discrete_gaussian_mechanism(3., 1., 1e-6, 0., 0.5, true).unwrap(), 3.);
+        assert_eq!(simple_geometric_mechanism(3, 1., 0., 0, 10, false).unwrap(), 3);
+        // negative sensitivities are still rejected
+        assert!(gaussian_mechanism(3., 1., 1e-6, -1., false, false).is_err());
+    }
+
+    #[test]
+    fn test_elementwise_errors() {
+        let mut values = Array1::zeros(2 * PARALLEL_THRESHOLD);
+        // epsilon must be positive
+        assert!(apply_elementwise(values.view_mut(), Array1::ones(2 * PARALLEL_THRESHOLD).view(),
+                                  |v, sens| laplace_mechanism(v, 0., sens, false)).is_err());
+        // lengths must match
+        assert!(apply_elementwise(values.view_mut(), Array1::ones(1).view(),
+                                  |v, sens| laplace_mechanism(v, 1., sens, false)).is_err());
+    }
+
+    #[test]
+    fn test_exponential_candidate_order() {
+        let candidates = vec!["a", "b", "c", "d"];
+        let utilities = vec![1., 4., 2., 3.];
+
+        // the same randomness selects the same candidate, regardless of the order of the candidates
+        (0..100).for_each(|seed| {
+            let draw = |candidates: Vec<&'static str>, utilities: Vec<f64>| with_entropy_source(
+                SeededEntropy::new(seed).unwrap(),
+                || exponential_mechanism(1., 1., &candidates, utilities, false).unwrap());
+            assert_eq!(draw(candidates.clone(), utilities.clone()),
+                       draw(candidates.iter().rev().copied().collect(), utilities.iter().rev().copied().collect()));
+        });
+    }
+}
@@ -2,15 +2,18 @@ use std::cmp::Ordering;
 use std::ops::AddAssign;
 
 use ieee754::Ieee754;
-use ndarray::{ArrayD, Axis, Zip};
+use ndarray::{ArrayD, ArrayViewD, Axis, Zip};
 use ndarray::prelude::IxDyn;
-use openssl::rand::rand_bytes;
 
 use smartnoise_validator::errors::*;
+use smartnoise_validator::{proto, Float};
 use smartnoise_validator::utilities::array::{slow_select, slow_stack};
 
 pub mod mechanisms;
 pub mod noise;
+pub mod nulls;
+#[cfg(feature = "use-arrow")]
+pub mod arrow;
 
 ///  Accepts an ndarray and returns the number of columns.
 ///
@@ -35,6 +38,31 @@ pub fn get_num_rows<T>(data: &ArrayD<T>) -> Result<i64> {
 }
 
 
+/// Check that an aggregate is finite, when overflow is protected.
+///
+/// Float aggregates may overflow to infinity when summing values near the maximum float,
+/// and infinities may combine into NaN. A non-finite aggregate may leak information about the data or crash downstream computations,
+/// so when `protect_overflow` is set, it is an error instead of a release.
+///
+/// # Arguments
+/// * `privacy_definition` - definition of privacy, which may enable overflow protections
+/// * `aggregate` - aggregated values to check
+/// * `name` - name of the aggregate, for the error message
+pub fn check_overflow(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    aggregate: &ArrayD<Float>,
+    name: &str
+) -> Result<()> {
+    let protect_overflow = privacy_definition.as_ref()
+        .map(|privacy_definition| privacy_definition.protect_overflow)
+        .unwrap_or(false);
+
+    if protect_overflow && !aggregate.iter().all(|v| v.is_finite()) {
+        return Err(format!("{}: aggregate is not finite, likely due to overflow. Reduce the bounds on the data to avoid overflow.", name).into())
+    }
+    Ok(())
+}
+
 /// Broadcast left and right to match each other, and map an operator over the pairs.
 ///
 /// # Arguments
@@ -65,23 +93,15 @@ pub fn broadcast_map<T, U>(
         Ordering::Greater => left.shape()
     }.to_vec();
 
-   // println!("shape {:?}", shape);
-   // println!("left shape {:?}", left.shape());
-   // println!("right shape {:?}", right.shape());
-
-    let left = to_nd(left, shape.len())?;
-    let right = to_nd(right, shape.len())?;
-
-   // println!("shape {:?}", shape);
-   // println!("left shape {:?}", left.shape());
-   // println!("right shape {:?}", right.shape());
-   // println!();
+    // reshape views of the operands, so that the underlying data is not copied
+    let left = view_nd(left.view(), shape.len())?;
+    let right = view_nd(right.view(), shape.len())?;
 
     let mut output: ArrayD<U> = ndarray::Array::default(shape.clone());
     Zip::from(&mut output)
         .and(left.broadcast(shape.clone()).ok_or("could not broadcast left argument")?)
         .and(right.broadcast(shape).ok_or("could not broadcast right argument")?)
-        .apply(|acc, l, r| *acc = operator(&l, &r));
+        .apply(|acc, l, r| *acc = operator(l, r));
 
     Ok(output)
 }
@@ -93,6 +113,29 @@ mod test_broadcast_map {
 
     use crate::utilities::broadcast_map;
 
+    #[test]
+    fn test_broadcast_views() {
+        let data2d = arr2(&[[2., 4.], [3., 7.], [5., 2.]]).into_dyn();
+
+        // a column is broadcast across every column
+        assert_eq!(
+            broadcast_map(data2d.clone(), arr2(&[[1.], [-1.], [0.5]]).into_dyn(), &|l, r| l * 10. + r).unwrap(),
+            arr2(&[[21., 41.], [29., 69.], [50.5, 20.5]]).into_dyn());
+
+        // a row is broadcast across every row
+        assert_eq!(
+            broadcast_map(arr2(&[[1., 8.]]).into_dyn(), data2d.clone(), &|l, r| l * 10. + r).unwrap(),
+            arr2(&[[12., 84.], [13., 87.], [15., 82.]]).into_dyn());
+
+        // a single element is broadcast over every element
+        assert_eq!(
+            broadcast_map(arr1(&[7.]).into_dyn(), arr1(&[2., 3., 5.]).into_dyn(), &|l, r| l * 10. + r).unwrap(),
+            arr1(&[72., 73., 75.]).into_dyn());
+        assert_eq!(
+            broadcast_map(data2d, arr0(1.).into_dyn(), &|l, r| l * 10. + r).unwrap(),
+            arr2(&[[21., 41.], [31., 71.], [51., 21.]]).into_dyn());
+    }
+
     #[test]
     fn test_broadcasting() {
         let data0d = arr0(2.).into_dyn();
@@ -134,6 +177,38 @@ mod test_broadcast_map {
     }
 }
 
+/// Reshape a view to have `ndim` dimensions, by adding or removing trailing singleton axes.
+///
+/// Behaves like `to_nd`, but does not take ownership of the data.
+///
+/// # Arguments
+/// * `array` - View to be reshaped.
+/// * `ndim` - Number of dimensions of the reshaped view.
+///
+/// # Return
+/// A view of the same data, with `ndim` dimensions.
+pub fn view_nd<T>(mut array: ArrayViewD<T>, ndim: usize) -> Result<ArrayViewD<T>> {
+    match (ndim as i32) - (array.ndim() as i32) {
+        0 => {}
+        // must remove i axes
+        i if i < 0 => for _ in 0..-i {
+            if array.shape().last().ok_or_else(|| Error::from("ndim may not be negative"))? != &1 {
+                return Err("cannot remove non-singleton trailing axis".into())
+            }
+            let axis = Axis(array.ndim() - 1);
+            array = array.index_axis_move(axis, 0);
+        },
+        // must add i axes
+        i if i > 0 => for _ in 0..i {
+            let axis = Axis(array.ndim());
+            array = array.insert_axis(axis);
+        },
+        _ => return Err("invalid dimensionality".into())
+    };
+
+    Ok(array)
+}
+
 pub fn to_nd<T>(mut array: ArrayD<T>, ndim: usize) -> Result<ArrayD<T>> {
     match (ndim as i32) - (array.ndim() as i32) {
         0 => {}
@@ -182,10 +257,78 @@ pub fn standardize_columns<T: Default + Clone>(array: ArrayD<T>, column_len: usi
 }
 
 
+/// Random bits read from OpenSSL, packed eight to a byte.
+///
+/// Bits are ordered from the most significant bit of the first byte.
+#[derive(Clone, Debug)]
+pub struct RandomBits {
+    buffer: Vec<u8>,
+    length: usize,
+}
+
+impl RandomBits {
+    /// Number of bits.
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Whether there are no bits.
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// The bit at `index`, or None if out of bounds.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index >= self.length { return None }
+        Some(self.buffer[index / 8] & (0b1000_0000 >> (index % 8)) != 0)
+    }
+
+    /// Iterate over the bits, in order.
+    pub fn iter(&self) -> impl Iterator<Item=bool> + '_ {
+        (0..self.length).map(move |index| self.buffer[index / 8] & (0b1000_0000 >> (index % 8)) != 0)
+    }
+
+    /// The underlying bytes. Bits past the length in the final byte are zero.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+/// Return random bits, without allocating a character per bit.
+///
+/// # Arguments
+/// * `n_bits` - The number of random bits you wish to read from OpenSSL.
+///
+/// # Return
+/// `n_bits` uniformly random bits.
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::utilities::get_bits;
+/// let bits = get_bits(12).unwrap();
+/// assert_eq!(bits.len(), 12);
+/// assert_eq!(bits.as_bytes().len(), 2);
+/// assert!(bits.get(12).is_none());
+/// ```
+pub fn get_bits(n_bits: usize) -> Result<RandomBits> {
+    // read random bytes from OpenSSL
+    let mut buffer = vec!(0_u8; n_bits.div_ceil(8));
+    fill_bytes(&mut buffer)?;
+
+    // zero the unused trailing bits of the final byte
+    let remainder = n_bits % 8;
+    if remainder > 0 {
+        if let Some(last) = buffer.last_mut() {
+            *last &= !(0xFF_u8 >> remainder);
+        }
+    }
+
+    Ok(RandomBits { buffer, length: n_bits })
+}
+
 /// Return bytes of binary data as `String`.
 ///
-/// Reads bytes from OpenSSL, converts them into a string,
-/// concatenates them, and returns the combined string.
+/// Prefer `get_bits`, which does not allocate a character for each bit.
 ///
 /// # Arguments
 /// * `n_bytes` - The number of random bytes you wish to read from OpenSSL.
@@ -193,26 +336,36 @@ pub fn standardize_columns<T: Default + Clone>(array: ArrayD<T>, column_len: usi
 /// # Return
 /// The `String` representation of the bytes.
 pub fn get_bytes(n_bytes: usize) -> Result<String> {
-    // read random bytes from OpenSSL
-    let mut buffer = vec!(0_u8; n_bytes);
-    fill_bytes(&mut buffer)?;
+    Ok(get_bits(8 * n_bytes)?.iter()
+        .map(|bit| if bit { '1' } else { '0' })
+        .collect())
+}
 
-    // create new buffer of binary representations, rather than u8
-    let new_buffer = buffer.into_iter()
-        .map(|v| format!("{:08b}", v))
-        .collect::<Vec<String>>();
+/// Fill `buffer` with random bytes from the entropy source of the current thread.
+///
+/// Outside of tests, the entropy source is always the OpenSSL CSPRNG.
+/// See `noise::EntropySource`.
+pub fn fill_bytes(buffer: &mut [u8]) -> Result<()> {
+    #[cfg(test)]
+    BYTES_DRAWN.with(|count| count.set(count.get() + buffer.len()));
 
-    // combine binary representations into single string and subset mantissa, and return
-    Ok(new_buffer.concat())
+    noise::fill_from_entropy_source(buffer)
 }
 
-// TODO: substitute implementation with different generators
-pub fn fill_bytes(mut buffer: &mut [u8]) -> Result<()> {
-    if let Err(e) = rand_bytes(&mut buffer) {
-        Err(format!("OpenSSL Error: {}", e).into())
-    } else { Ok(()) }
+
+// count the random bytes drawn by each thread, to check that samplers run in constant time
+#[cfg(test)]
+thread_local! {
+    pub static BYTES_DRAWN: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
 }
 
+/// Count the random bytes drawn while evaluating `function`.
+#[cfg(test)]
+pub fn count_bytes_drawn<T>(function: impl FnOnce() -> T) -> (T, usize) {
+    let start = BYTES_DRAWN.with(|count| count.get());
+    let value = function();
+    (value, BYTES_DRAWN.with(|count| count.get()) - start)
+}
 
 /// Converts an `f64` to `String` of length 64, yielding the IEEE-754 binary representation of the `f64`.
 ///
@@ -226,16 +379,8 @@ pub fn fill_bytes(mut buffer: &mut [u8]) -> Result<()> {
 /// # Return
 /// A string showing the IEEE-754 binary representation of `num`.
 pub fn f64_to_binary(num: f64) -> String {
-    // decompose num into component parts
-    let (sign, exponent, mantissa) = num.decompose_raw();
-
-    // convert each component into strings
-    let sign_string = (sign as i64).to_string();
-    let mantissa_string = format!("{:052b}", mantissa);
-    let exponent_string = format!("{:011b}", exponent);
-
-    // join component strings and return string representation
-    vec![sign_string, exponent_string, mantissa_string].concat()
+    // the raw bits are formatted directly, so that NaN payloads, infinities and subnormals are exact
+    format!("{:064b}", num.to_bits())
 }
 
 /// Converts `String` of length 64 to `f64`, yielding the floating-point number represented by the `String`.
@@ -249,21 +394,13 @@ pub fn f64_to_binary(num: f64) -> String {
 ///
 /// # Return
 /// * `num`: f64 version of the String
+///
+/// Returns an error if the string does not consist of exactly 64 `0` or `1` characters.
 pub fn binary_to_f64(binary_string: &str) -> Result<f64> {
-    // get sign and convert to bool as recompose expects
-    let sign = &binary_string[0..1];
-    let sign_bool = sign.parse::<i32>()? != 0;
+    check_binary(binary_string, 64, "binary string")?;
 
-    // convert exponent to int
-    let exponent = &binary_string[1..12];
-    let exponent_int = u16::from_str_radix(exponent, 2)?;
-
-    // convert mantissa to int
-    let mantissa = &binary_string[12..];
-    let mantissa_int = u64::from_str_radix(mantissa, 2)?;
-
-    // combine elements into f64 and return
-    Ok(f64::recompose_raw(sign_bool, exponent_int, mantissa_int))
+    // combine bits into f64 and return
+    Ok(f64::from_bits(u64::from_str_radix(binary_string, 2)?))
 }
 
 /// Takes `String` of form `{0,1}^64` and splits it into a sign, exponent, and mantissa
@@ -274,8 +411,11 @@ pub fn binary_to_f64(binary_string: &str) -> Result<f64> {
 ///
 /// # Return
 /// (sign, exponent, mantissa) - where each is a `String`.
-pub fn split_ieee_into_components(binary_string: String) -> (String, String, String) {
-    return (binary_string[0..1].to_string(), binary_string[1..12].to_string(), binary_string[12..].to_string());
+///
+/// Returns an error if the string does not consist of exactly 64 `0` or `1` characters.
+pub fn split_ieee_into_components(binary_string: String) -> Result<(String, String, String)> {
+    check_binary(&binary_string, 64, "binary string")?;
+    Ok((binary_string[0..1].to_string(), binary_string[1..12].to_string(), binary_string[12..].to_string()))
 }
 
 /// Combines `String` versions of sign, exponent, and mantissa into
@@ -288,14 +428,60 @@ pub fn split_ieee_into_components(binary_string: String) -> (String, String, Str
 ///
 /// # Return
 /// Concatenation of sign, exponent, and mantissa.
+///
+/// Returns an error if any component has the wrong length, or contains characters other than `0` or `1`.
 pub fn combine_components_into_ieee(
     (sign, exponent, mantissa): (String, String, String)
-) -> String {
-    vec![sign, exponent, mantissa].concat()
+) -> Result<String> {
+    check_binary(&sign, 1, "sign")?;
+    check_binary(&exponent, 11, "exponent")?;
+    check_binary(&mantissa, 52, "mantissa")?;
+    Ok([sign, exponent, mantissa].concat())
+}
+
+/// Checks that `binary_string` consists of exactly `length` `0` or `1` characters.
+fn check_binary(binary_string: &str, length: usize, name: &str) -> Result<()> {
+    if binary_string.len() != length {
+        return Err(format!("{} must have length {}, but has length {}", name, length, binary_string.len()).into())
+    }
+    if let Some(character) = binary_string.chars().find(|c| *c != '0' && *c != '1') {
+        return Err(format!("{} may only contain 0 and 1, but contains {:?}", name, character).into())
+    }
+    Ok(())
+}
+
+/// Checks that weights describe a valid distribution over a candidate set.
+///
+/// # Arguments
+/// * `num_candidates` - The number of elements in the candidate set.
+/// * `weights` - Sampling weights for each element.
+///
+/// # Return
+/// An error if the set is empty, the lengths differ, any weight is negative or non-finite, or all weights are zero.
+fn check_sampling_weights(num_candidates: usize, weights: &[smartnoise_validator::Float]) -> Result<()> {
+    if num_candidates == 0 {
+        return Err("candidate set must not be empty".into())
+    }
+    if num_candidates != weights.len() {
+        return Err(format!("the number of weights ({}) must match the number of candidates ({})",
+                           weights.len(), num_candidates).into())
+    }
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.) {
+        return Err("weights must be finite and non-negative".into())
+    }
+    if weights.iter().all(|w| *w == 0.) {
+        return Err("weights must not all be zero".into())
+    }
+    Ok(())
 }
 
 /// Samples a single element from a set according to provided weights.
 ///
+/// The selection distribution does not depend on the order of the candidates.
+/// Candidates are grouped into classes of equal weight, which are scanned in increasing order of weight:
+/// a class is selected in proportion to its total weight, and then a candidate is selected uniformly within the class.
+/// When the uniform sample falls on the boundary between two classes, the lighter class is selected.
+///
 /// # Arguments
 /// * `candidate_set` - The set from which you want to sample.
 /// * `weights` - Sampling weights for each element.
@@ -307,26 +493,34 @@ pub fn sample_from_set<T>(
     candidate_set: &[T], weights: &[smartnoise_validator::Float],
     _enforce_constant_time: bool
 ) -> Result<T> where T: Clone {
+    check_sampling_weights(candidate_set.len(), weights)?;
+    if candidate_set.len() == 1 {
+        return Ok(candidate_set[0].clone())
+    }
+
     macro_rules! to_rug {($v:expr) => {rug::Float::with_val(53, $v)}}
 
-    // generate sum of weights
-    let weights_rug: Vec<rug::Float> = weights.iter().map(|w| to_rug!(w)).collect();
+    let classes = weight_classes(weights);
+    // generate total weight of each class
+    let totals_rug: Vec<rug::Float> = classes.iter()
+        .map(|(weight, indices)| to_rug!(to_rug!(weight) * indices.len() as u32))
+        .collect();
 
     // generate uniform random number on [0,1)
     let sample: rug::Float = noise::sample_uniform_mpfr(
         0.,
-        to_rug!(rug::Float::sum(weights_rug.iter())).to_f64())?;
+        to_rug!(rug::Float::sum(totals_rug.iter())).to_f64())?;
 
     let mut cum_prob = to_rug!(0.);
-    // sample an element relative to its probability
-    for (i, weight) in weights_rug.into_iter().enumerate() {
-        cum_prob.add_assign(weight);
+    // sample a class relative to its probability
+    for ((_, indices), total) in classes.iter().zip(totals_rug) {
+        cum_prob.add_assign(total);
         if cum_prob >= sample {
-            return Ok(candidate_set[i].clone())
+            return sample_from_class(candidate_set, indices)
         }
     }
     // this should only ever be reachable from floating-point instability
-    return Ok(candidate_set[candidate_set.len() - 1].clone())
+    sample_from_class(candidate_set, &classes.last().unwrap().1)
 }
 
 #[cfg(not(feature="use-mpfr"))]
@@ -334,33 +528,72 @@ pub fn sample_from_set<T>(
     candidate_set: &[T], weights: &[smartnoise_validator::Float],
     enforce_constant_time: bool
 ) -> Result<T> where T: Clone {
+    check_sampling_weights(candidate_set.len(), weights)?;
+    if candidate_set.len() == 1 {
+        return Ok(candidate_set[0].clone())
+    }
+
+    let classes = weight_classes(weights);
+    let totals: Vec<f64> = classes.iter()
+        .map(|(weight, indices)| weight * indices.len() as f64)
+        .collect();
 
     // generate uniform random number on [0,sum(weights))
-    let sample: f64 = noise::sample_uniform(0., weights.iter().sum(), enforce_constant_time)?;
+    let sample: f64 = noise::sample_uniform(0., totals.iter().sum(), enforce_constant_time)?;
 
     // return once the cumulative weight reaches the uniform sample
     let mut cumulative = 0.;
-    let mut return_index: usize = 0;
-    loop {
-        cumulative += weights[return_index];
-        if cumulative >= sample { break }
-        return_index += 1;
+    for ((_, indices), total) in classes.iter().zip(totals) {
+        cumulative += total;
+        if cumulative >= sample {
+            return sample_from_class(candidate_set, indices)
+        }
     }
-    Ok(candidate_set[return_index].clone())
+    // this should only ever be reachable from floating-point instability
+    sample_from_class(candidate_set, &classes.last().unwrap().1)
+}
+
+/// Partition the indices of candidates with positive weight into classes of equal weight,
+/// in increasing order of weight.
+///
+/// The classes depend only on the multiset of weights, not the order of the candidates.
+/// When rounding leaves the cumulative weight just short of the sample,
+/// the sample fell in the mass of the last class, which is the heaviest class that can be drawn.
+fn weight_classes(weights: &[smartnoise_validator::Float]) -> Vec<(smartnoise_validator::Float, Vec<usize>)> {
+    let mut indices = (0..weights.len()).filter(|i| weights[*i] > 0.).collect::<Vec<usize>>();
+    indices.sort_by(|l, r| weights[*l].partial_cmp(&weights[*r]).unwrap());
+
+    indices.into_iter().fold(Vec::new(), |mut classes, index| {
+        match classes.last_mut() {
+            Some((weight, members)) if *weight == weights[index] => members.push(index),
+            _ => classes.push((weights[index], vec![index]))
+        }
+        classes
+    })
+}
+
+/// Sample a candidate uniformly from a class of candidates with equal weight.
+fn sample_from_class<T: Clone>(candidate_set: &[T], indices: &[usize]) -> Result<T> {
+    let position = noise::sample_uniform_int(0, indices.len() as i64 - 1)?;
+    Ok(candidate_set[indices[position as usize]].clone())
 }
 
 /// Accepts set and element weights and returns a subset of size k (without replacement).
 ///
-/// Weights are (after being normalized) the probability of drawing each element on the first draw (they sum to 1)
-/// Based on Algorithm A from Raimidis PS, Spirakis PG (2006). “Weighted random sampling with a reservoir.”
+/// Weights are (after being normalized) the probability of drawing each element on the first draw (they sum to 1).
+/// Each subsequent draw is made from the remaining elements, with probability proportional to their weights.
+/// Based on the exponential key method (Algorithm A-ES) from Efraimidis PS, Spirakis PG (2006). “Weighted random sampling with a reservoir.”
+/// Each element is keyed by `ln(u) / weight`, for a uniform sample `u`, and the elements with the k largest keys are kept.
+/// Keys are compared on the log scale, so that they do not underflow when the weights are small relative to their sum.
 ///
 /// # Arguments
 /// * `set` - Set of elements for which you would like to create a subset
 /// * `weights` - Weight for each element in the set, corresponding to the probability it is drawn on the first draw.
 /// * `k` - The size of the desired subset
+/// * `enforce_constant_time` - Whether to force the sampling of each key to run in constant time.
 ///
 /// # Return
-/// subset of size k sampled according to weights
+/// subset of size k sampled according to weights, in the order the elements were drawn
 ///
 /// # Example
 /// ```
@@ -370,80 +603,48 @@ pub fn sample_from_set<T>(
 /// let k = 3;
 /// let subset = create_subset(&set, &weights, k, false);
 /// # subset.unwrap();
+///
+/// // elements with zero weight are never drawn
+/// let subset = create_subset(&set, &[0., 0., 0., 0., 1., 1.], 2, false).unwrap();
+/// assert!(subset.contains(&5) && subset.contains(&6));
 /// ```
-#[cfg(feature="use-mpfr")]
 pub fn create_subset<T>(
     set: &[T], weights: &[f64], k: usize,
-    _enforce_constant_time: bool
+    enforce_constant_time: bool
 ) -> Result<Vec<T>> where T: Clone {
     if k > set.len() { return Err("k must be less than the set length".into()); }
+    if weights.len() != set.len() {
+        return Err("weights: must contain one weight for each element in the set".into())
+    }
+    if weights.iter().any(|weight| !weight.is_finite() || *weight < 0.) {
+        return Err("weights: must be non-negative and finite".into())
+    }
+    if k > weights.iter().filter(|weight| **weight > 0.).count() {
+        return Err("k may not be greater than the number of elements with positive weight".into())
+    }
 
-    use rug::Float;
-    use rug::ops::Pow;
-
-    // generate sum of weights
-    let weights_rug: Vec<rug::Float> = weights.iter().map(|w| Float::with_val(53, w)).collect();
-    let weights_sum: rug::Float = Float::with_val(53, Float::sum(weights_rug.iter()));
-
-    // convert weights to probabilities
-    let probabilities: Vec<rug::Float> = weights_rug.iter().map(|w| w / weights_sum.clone()).collect();
-
-    // generate keys and identify top k indices
-    //
-
-    // generate key/index tuples
-    let mut key_vec = probabilities.into_iter()
-        .take(set.len()).enumerate()
-        .map(|(i, prob)| Ok((noise::sample_uniform_mpfr(0., 1.)?.pow(1. / prob), i)))
-        .collect::<Result<Vec<(rug::Float, usize)>>>()?;
+    // generate key/index tuples. Elements with zero weight have a key of -inf, and are never selected
+    let mut key_vec = weights.iter().enumerate()
+        .map(|(i, weight)| Ok((sample_log_uniform(enforce_constant_time)? / weight, i)))
+        .collect::<Result<Vec<(f64, usize)>>>()?;
 
     // sort key/index tuples by key and identify top k indices
-    let mut top_indices: Vec<usize> = Vec::with_capacity(k);
     key_vec.sort_by(|a, b|
         b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
-    top_indices.extend(key_vec.iter()
-        .take(k).map(|v| v.1));
 
     // subsample based on top k indices
-    let mut subset: Vec<T> = Vec::with_capacity(k);
-    for value in top_indices.iter().map(|&index| set[index].clone()) {
-        subset.push(value);
-    }
+    Ok(key_vec.iter().take(k).map(|v| set[v.1].clone()).collect())
+}
 
-    Ok(subset)
+/// Sample the natural logarithm of a uniform random variable on [0, 1).
+#[cfg(feature="use-mpfr")]
+fn sample_log_uniform(_enforce_constant_time: bool) -> Result<f64> {
+    Ok(noise::sample_uniform_mpfr(0., 1.)?.ln().to_f64())
 }
 
 #[cfg(not(feature="use-mpfr"))]
-pub fn create_subset<T>(
-    set: &[T], weights: &[f64], k: usize,
-    enforce_constant_time: bool
-) -> Result<Vec<T>> where T: Clone {
-    if k > set.len() { return Err("k must be less than the set length".into()); }
-
-    // generate sum of weights
-    let weights_sum: f64 = weights.iter().sum();
-
-    // convert weights to probabilities
-    let probabilities: Vec<f64> = weights.iter().map(|w| w / weights_sum).collect();
-
-    // generate keys and identify top k indices
-    //
-
-    // generate key/index tuples
-    let mut key_vec = (0..set.len())
-        .map(|i| Ok((
-            noise::sample_uniform(0., 1., enforce_constant_time)?
-                .powf(1. / probabilities[i]),
-            i
-        )))
-        .collect::<Result<Vec<(f64, usize)>>>()?;
-
-    // sort key/index tuples by key and identify top k indices
-    key_vec.sort_by(|a, b|
-        b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
-
-    // subsample based on top k indices
-    Ok(key_vec.iter().take(k).map(|v| set[v.1].clone()).collect())
+fn sample_log_uniform(enforce_constant_time: bool) -> Result<f64> {
+    Ok(noise::sample_uniform(0., 1., enforce_constant_time)?.ln())
 }
 
 
@@ -527,4 +728,248 @@ mod test_get_closest_multiple_of_lambda {
                 })
         });
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_binary {
+    use crate::utilities::{binary_to_f64, combine_components_into_ieee, f64_to_binary, fill_bytes, split_ieee_into_components};
+
+    #[test]
+    fn test_malformed_binary() {
+        assert!(binary_to_f64("").is_err());
+        assert!(binary_to_f64(&"0".repeat(63)).is_err());
+        assert!(binary_to_f64(&"0".repeat(65)).is_err());
+        assert!(binary_to_f64(&format!("2{}", "0".repeat(63))).is_err());
+        assert!(binary_to_f64(&format!("+{}", "1".repeat(63))).is_err());
+        // 64 bytes long, but not 64 binary characters
+        assert!(binary_to_f64(&format!("é{}", "0".repeat(62))).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let round_trip = |value: f64| binary_to_f64(&f64_to_binary(value)).unwrap().to_bits();
+
+        vec![
+            0., -0., 1., -2.5, f64::MAX, f64::MIN,
+            f64::INFINITY, f64::NEG_INFINITY, f64::NAN, -f64::NAN,
+            f64::MIN_POSITIVE, f64::from_bits(1), -f64::from_bits(1), f64::from_bits(0x000f_ffff_ffff_ffff),
+            f64::from_bits(0x7ff0_0000_0000_0001)
+        ].into_iter().for_each(|value| assert_eq!(round_trip(value), value.to_bits()));
+
+        assert_eq!(f64_to_binary(-0.), format!("1{}", "0".repeat(63)));
+        assert_eq!(f64_to_binary(f64::from_bits(1)), format!("{}1", "0".repeat(63)));
+    }
+
+    #[test]
+    fn test_random_round_trip() {
+        let mut buffer = [0u8; 8];
+        (0..10_000).for_each(|_| {
+            fill_bytes(&mut buffer).unwrap();
+            let value = f64::from_bits(u64::from_le_bytes(buffer));
+
+            let binary = f64_to_binary(value);
+            assert_eq!(binary_to_f64(&binary).unwrap().to_bits(), value.to_bits());
+
+            let components = split_ieee_into_components(binary.clone()).unwrap();
+            assert_eq!(combine_components_into_ieee(components).unwrap(), binary);
+        });
+    }
+
+    #[test]
+    fn test_malformed_components() {
+        assert!(split_ieee_into_components("0".repeat(63)).is_err());
+        assert!(split_ieee_into_components(format!("{}2", "0".repeat(63))).is_err());
+
+        let components = |sign: &str, exponent: &str, mantissa: &str|
+            combine_components_into_ieee((sign.to_string(), exponent.to_string(), mantissa.to_string()));
+        assert!(components("0", &"1".repeat(11), &"0".repeat(52)).is_ok());
+        assert!(components("", &"1".repeat(11), &"0".repeat(52)).is_err());
+        assert!(components("0", &"1".repeat(12), &"0".repeat(51)).is_err());
+        assert!(components("0", &"1".repeat(11), &"0".repeat(53)).is_err());
+        assert!(components("x", &"1".repeat(11), &"0".repeat(52)).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_sample_from_set {
+    use crate::utilities::noise::{SeededEntropy, with_entropy_source};
+    use crate::utilities::sample_from_set;
+
+    #[test]
+    fn test_degenerate_weights() {
+        let candidates = vec![1, 2, 3];
+
+        // empty candidate set
+        assert!(sample_from_set::<i64>(&[], &[], false).is_err());
+        // all weights are zero
+        assert!(sample_from_set(&candidates, &[0., 0., 0.], false).is_err());
+        // negative weight
+        assert!(sample_from_set(&candidates, &[1., -1., 1.], false).is_err());
+        // non-finite weights
+        assert!(sample_from_set(&candidates, &[1., f64::NAN, 1.], false).is_err());
+        assert!(sample_from_set(&candidates, &[1., f64::INFINITY, 1.], false).is_err());
+        // mismatched lengths
+        assert!(sample_from_set(&candidates, &[1., 1.], false).is_err());
+    }
+
+    #[test]
+    fn test_single_candidate() {
+        assert_eq!(sample_from_set(&[7], &[0.5], false).unwrap(), 7);
+        assert!(sample_from_set(&[7], &[0.], false).is_err());
+    }
+
+    #[test]
+    fn test_zero_weights_never_sampled() {
+        let candidates = vec![1, 2, 3, 4];
+        (0..100).for_each(|_| {
+            let sample = sample_from_set(&candidates, &[0., 1., 0., 1e-300], false).unwrap();
+            assert!(sample == 2 || sample == 4);
+            // the final element must not be drawn when it has no weight
+            assert_ne!(sample_from_set(&candidates, &[0., 1., 1e-300, 0.], false).unwrap(), 4);
+        });
+    }
+
+    #[test]
+    fn test_permutation_invariance() {
+        let candidates = vec![0, 1, 2, 3, 4];
+        let weights = vec![0.5, 3., 1e-3, 2., 0.25];
+        let permutation = [3, 0, 4, 2, 1];
+        let permuted_candidates = permutation.iter().map(|i| candidates[*i]).collect::<Vec<_>>();
+        let permuted_weights = permutation.iter().map(|i| weights[*i]).collect::<Vec<_>>();
+
+        // with distinct weights, the same randomness selects the same candidate in any order
+        (0..100).for_each(|seed| {
+            let draw = |candidates: &[i32], weights: &[f64]| with_entropy_source(
+                SeededEntropy::new(seed).unwrap(), || sample_from_set(candidates, weights, false).unwrap());
+            assert_eq!(draw(&candidates, &weights), draw(&permuted_candidates, &permuted_weights));
+        });
+    }
+
+    #[test]
+    fn test_tied_weights_distribution() {
+        let n = 20_000;
+        let expected = [0.1, 0.1, 0.2, 0.2, 0.4];
+        let frequencies = |candidates: Vec<usize>, weights: Vec<f64>| {
+            let mut counts = vec![0; 5];
+            (0..n).for_each(|_| counts[sample_from_set(&candidates, &weights, false).unwrap()] += 1);
+            counts.into_iter().map(|count| count as f64 / n as f64).collect::<Vec<f64>>()
+        };
+
+        // the empirical selection distribution is the same under permutations, and ties are selected uniformly
+        vec![
+            (vec![0, 1, 2, 3, 4], vec![1., 1., 2., 2., 4.]),
+            (vec![4, 3, 1, 2, 0], vec![4., 2., 1., 2., 1.]),
+        ].into_iter().for_each(|(candidates, weights)| {
+            frequencies(candidates, weights).iter().zip(expected.iter())
+                // the standard deviation of each frequency is at most 0.0035
+                .for_each(|(actual, expected)| assert!((actual - expected).abs() < 0.02, "{} {}", actual, expected))
+        });
+    }
+}
+
+#[cfg(test)]
+mod test_create_subset {
+    use crate::utilities::create_subset;
+
+    #[test]
+    fn test_first_draw_distribution() {
+        let n = 20_000;
+        let mut counts = [0; 4];
+        (0..n).for_each(|_| counts[create_subset(&[0, 1, 2, 3], &[1., 2., 3., 4.], 1, false).unwrap()[0]] += 1);
+
+        // the first draw is proportional to the weights, with standard deviation at most 0.0035
+        counts.iter().zip([0.1, 0.2, 0.3, 0.4].iter())
+            .for_each(|(count, expected)| assert!((*count as f64 / n as f64 - expected).abs() < 0.02));
+    }
+
+    #[test]
+    fn test_inclusion_frequencies() {
+        let n = 20_000;
+        let mut counts = [0; 3];
+        (0..n).for_each(|_| create_subset(&[0, 1, 2], &[1., 1., 2.], 2, false).unwrap()
+            .into_iter().for_each(|index| counts[index] += 1));
+
+        // each draw is made from the remaining elements, proportionally to their weights
+        counts.iter().zip([7. / 12., 7. / 12., 5. / 6.].iter())
+            .for_each(|(count, expected)| assert!((*count as f64 / n as f64 - expected).abs() < 0.02));
+    }
+
+    #[test]
+    fn test_without_replacement() {
+        let set = (0..10).collect::<Vec<usize>>();
+        let weights = vec![1e-3, 5., 1., 1., 0.5, 2., 1e3, 1., 3., 1.];
+        (0..100).for_each(|_| {
+            let mut subset = create_subset(&set, &weights, 6, false).unwrap();
+            subset.sort_unstable();
+            subset.dedup();
+            assert_eq!(subset.len(), 6);
+        });
+
+        // the full set is a permutation
+        let mut subset = create_subset(&set, &weights, 10, false).unwrap();
+        subset.sort_unstable();
+        assert_eq!(subset, set);
+    }
+
+    #[test]
+    fn test_small_weights() {
+        // the keys of many elements with uniform weight must not underflow to a tie
+        let n = 2_000;
+        let set = (0..n).collect::<Vec<usize>>();
+        let subset = create_subset(&set, &vec![1.; n], n / 2, false).unwrap();
+        let mean = subset.iter().sum::<usize>() as f64 / (n / 2) as f64;
+        // the mean of the selected indices has standard deviation ~13
+        assert!((mean - (n - 1) as f64 / 2.).abs() < 100.);
+    }
+
+    #[test]
+    fn test_invalid_weights() {
+        assert!(create_subset(&[1, 2, 3], &[1., 0., 0.], 2, false).is_err());
+        assert!(create_subset(&[1, 2, 3], &[1., -1., 1.], 1, false).is_err());
+        assert!(create_subset(&[1, 2, 3], &[1., 1.], 1, false).is_err());
+        assert_eq!(create_subset(&[1, 2, 3], &[0., 0., 1.], 1, false).unwrap(), vec![3]);
+    }
+}
+
+#[cfg(test)]
+mod test_get_bits {
+    use crate::utilities::{get_bits, get_bytes};
+
+    #[test]
+    fn test_bit_layout() {
+        let bits = get_bits(13).unwrap();
+        assert_eq!(bits.len(), 13);
+        assert_eq!(bits.iter().count(), 13);
+        assert_eq!(bits.as_bytes().len(), 2);
+        // unused trailing bits are zero
+        assert_eq!(bits.as_bytes()[1] & 0b0000_0111, 0);
+        bits.iter().enumerate().for_each(|(i, bit)| assert_eq!(bits.get(i), Some(bit)));
+        assert!(get_bits(0).unwrap().is_empty());
+
+        let bytes = get_bytes(3).unwrap();
+        assert_eq!(bytes.len(), 24);
+        assert!(bytes.chars().all(|c| c == '0' || c == '1'));
+    }
+
+    #[test]
+    fn test_bits_uniform() {
+        let n = 100_000;
+        let bits = get_bits(n).unwrap();
+
+        // the proportion of ones is 1/2, with standard deviation ~0.0016
+        let ones = bits.iter().filter(|bit| *bit).count();
+        assert!((ones as f64 / n as f64 - 0.5).abs() < 0.01);
+
+        // each bit position within a byte is equally likely to be set
+        (0..8).for_each(|offset| {
+            let ones = bits.iter().skip(offset).step_by(8).filter(|bit| *bit).count();
+            assert!((ones as f64 / (n / 8) as f64 - 0.5).abs() < 0.025);
+        });
+
+        // consecutive pairs of bits are uniform over the four outcomes
+        let mut pairs = [0usize; 4];
+        bits.iter().collect::<Vec<bool>>().chunks(2)
+            .for_each(|pair| pairs[pair[0] as usize * 2 + pair[1] as usize] += 1);
+        pairs.iter().for_each(|count| assert!((*count as f64 / (n / 2) as f64 - 0.25).abs() < 0.01));
+    }
+}
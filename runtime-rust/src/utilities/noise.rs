@@ -1,9 +1,13 @@
+#[cfg(test)]
+use std::cell::RefCell;
 use std::{cmp, f64::consts, mem};
 
 use ieee754::Ieee754;
 use noisy_float::types::n64;
-use probability::distribution::{Inverse, Laplace};
-#[cfg(not(feature="use-mpfr"))]
+use openssl::rand::rand_bytes;
+#[cfg(test)]
+use openssl::symm::{Cipher, Crypter, Mode};
+use probability::distribution::{Distribution, Inverse, Laplace};
 use probability::prelude::Gaussian;
 #[cfg(feature="use-mpfr")]
 use rug::{Float, rand::{ThreadRandGen, ThreadRandState}};
@@ -15,7 +19,85 @@ use smartnoise_validator::Integer;
 use crate::utilities;
 use crate::utilities::get_closest_multiple_of_lambda;
 
-// Give MPFR ability to draw randomness from OpenSSL
+/// A source of uniformly random bytes, from which all noise is sampled.
+pub trait EntropySource {
+    /// Fill `buffer` with random bytes.
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<()>;
+}
+
+/// Draws random bytes from the OpenSSL CSPRNG, which is seeded by the operating system.
+///
+/// This is the only entropy source available outside of tests.
+pub struct OpenSSLEntropy;
+
+impl EntropySource for OpenSSLEntropy {
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<()> {
+        rand_bytes(buffer).map_err(|e| format!("OpenSSL Error: {}", e).into())
+    }
+}
+
+/// A seeded CSPRNG, for reproducible sampler tests.
+///
+/// The byte stream is the AES-256 keystream in counter mode, keyed by the seed.
+#[cfg(test)]
+pub struct SeededEntropy(Crypter);
+
+#[cfg(test)]
+impl SeededEntropy {
+    pub fn new(seed: u64) -> Result<SeededEntropy> {
+        let mut key = [0u8; 32];
+        key[..8].copy_from_slice(&seed.to_le_bytes());
+        Crypter::new(Cipher::aes_256_ctr(), Mode::Encrypt, &key, Some(&[0u8; 16]))
+            .map(SeededEntropy)
+            .map_err(|e| format!("OpenSSL Error: {}", e).into())
+    }
+}
+
+#[cfg(test)]
+impl EntropySource for SeededEntropy {
+    fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<()> {
+        // the keystream is the encryption of zeros
+        let zeros = vec![0u8; buffer.len()];
+        let mut keystream = vec![0u8; buffer.len() + Cipher::aes_256_ctr().block_size()];
+        let count = self.0.update(&zeros, &mut keystream)
+            .map_err(|e| Error::from(format!("OpenSSL Error: {}", e)))?;
+        if count != buffer.len() {
+            return Err("keystream is shorter than the buffer".into())
+        }
+        buffer.copy_from_slice(&keystream[..count]);
+        Ok(())
+    }
+}
+
+// replaces the entropy source of the current thread while evaluating `with_entropy_source`
+#[cfg(test)]
+thread_local! {
+    static ENTROPY_SOURCE: RefCell<Option<Box<dyn EntropySource>>> = const { RefCell::new(None) };
+}
+
+/// Evaluate `function` while the current thread draws all of its randomness from `source`.
+///
+/// Only available in tests, so that releases can never be made with a chosen seed.
+#[cfg(test)]
+pub fn with_entropy_source<T>(source: impl EntropySource + 'static, function: impl FnOnce() -> T) -> T {
+    let previous = ENTROPY_SOURCE.with(|current| current.replace(Some(Box::new(source))));
+    let value = function();
+    ENTROPY_SOURCE.with(|current| current.replace(previous));
+    value
+}
+
+/// Fill `buffer` from the entropy source of the current thread.
+pub(crate) fn fill_from_entropy_source(buffer: &mut [u8]) -> Result<()> {
+    #[cfg(test)]
+    if let Some(result) = ENTROPY_SOURCE.with(|current| current.borrow_mut().as_mut()
+        .map(|source| source.fill_bytes(buffer))) {
+        return result
+    }
+
+    OpenSSLEntropy.fill_bytes(buffer)
+}
+
+// Give MPFR ability to draw randomness from the entropy source
 #[cfg(feature="use-mpfr")]
 struct GeneratorOpenSSL;
 
@@ -43,15 +125,13 @@ impl ThreadRandGen for GeneratorOpenSSL {
 pub fn censored_specific_geom(enforce_constant_time: bool) -> Result<i16> {
 
     Ok(if enforce_constant_time {
-        let mut buffer = vec!(0_u8; 128);
-        utilities::fill_bytes(&mut buffer)?;
-
-        cmp::min(buffer.into_iter().enumerate()
-            // ignore samples that contain no events
-            .filter(|(_, sample)| sample > &0)
-            // compute the index of the smallest event in the batch
-            .map(|(i, sample)| 8 * i + sample.leading_zeros() as usize)
-            // retrieve the smallest index
+        let bits = utilities::get_bits(1024)?;
+
+        cmp::min(bits.iter().enumerate()
+            // ignore trials that are not events
+            .filter(|(_, bit)| *bit)
+            // retrieve the smallest index of an event, after inspecting every trial
+            .map(|(i, _)| i)
             .min()
             // return 1022 if no events occurred (slight dp violation w.p. ~2^-52)
             .unwrap_or(1022) as i16, 1022)
@@ -493,6 +573,10 @@ pub fn sample_gaussian(shift: f64, scale: f64, enforce_constant_time: bool) -> R
 /// Otherwise, the return will be the result of a composition of two operations that
 /// respect exact rounding (though the result will not necessarily).
 ///
+/// MPFR draws normal samples by rejection, so the number of random draws depends on the sample.
+/// When `enforce_constant_time` is set, the sample is instead drawn by inverting the CDF at a uniform draw,
+/// which always draws the same number of random bits, but does not respect exact rounding.
+///
 /// # Arguments
 /// * `shift` - The expectation of the Gaussian distribution.
 /// * `scale` - The scaling parameter (standard deviation) of the Gaussian distribution.
@@ -506,7 +590,12 @@ pub fn sample_gaussian(shift: f64, scale: f64, enforce_constant_time: bool) -> R
 /// let gaussian = sample_gaussian(0.0, 1.0, false);
 /// ```
 #[cfg(feature = "use-mpfr")]
-pub fn sample_gaussian(shift: f64, scale: f64, _enforce_constant_time: bool) -> Result<f64> {
+pub fn sample_gaussian(shift: f64, scale: f64, enforce_constant_time: bool) -> Result<f64> {
+    if enforce_constant_time {
+        let probability: f64 = sample_uniform(0., 1., enforce_constant_time)?;
+        return Ok(Gaussian::new(shift, scale).inverse(probability))
+    }
+
     // initialize 64-bit floats within mpfr/rug
     // NOTE: We square the scale here because we ask for the standard deviation as the function input, but
     //       the mpfr library wants the variance. We ask for std. dev. to be consistent with the rest of the library.
@@ -528,6 +617,9 @@ pub fn sample_gaussian(shift: f64, scale: f64, _enforce_constant_time: bool) ->
 /// This means that values outside of the truncation bounds are ignored, rather
 /// than pushed to the bounds (as they would be for a censored distribution).
 ///
+/// The number of rejections depends on the sample, so when `enforce_constant_time` is set,
+/// the sample is instead drawn by inverting the CDF at a uniform draw between the CDF at each bound.
+///
 /// # Arguments
 ///
 /// * `shift` - The expectation of the untruncated Gaussian distribution.
@@ -551,6 +643,12 @@ pub fn sample_gaussian_truncated(
     if min > max {return Err("lower may not be greater than upper".into());}
     if scale <= 0.0 {return Err("scale must be greater than zero".into());}
 
+    if enforce_constant_time {
+        let gaussian = Gaussian::new(shift, scale);
+        let probability = sample_uniform(gaussian.distribution(min), gaussian.distribution(max), enforce_constant_time)?;
+        return Ok(gaussian.inverse(probability).max(min).min(max))
+    }
+
     // return draw from distribution only if it is in correct range
     loop {
         let trunc_gauss = sample_gaussian(shift, scale, enforce_constant_time)?;
@@ -560,6 +658,109 @@ pub fn sample_gaussian_truncated(
     }
 }
 
+/// Sample from truncated Laplace distribution.
+///
+/// The sample is drawn by inverting the CDF at a uniform draw between the CDF at each bound,
+/// so the number of draws does not depend on the sample.
+///
+/// # Arguments
+///
+/// * `min` - The minimum value you want to allow to be sampled.
+/// * `max` - The maximum value you want to allow to be sampled.
+/// * `shift` - The expectation of the untruncated Laplace distribution.
+/// * `scale` - The scaling parameter of the untruncated Laplace distribution.
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Return
+/// A draw from a Laplace(shift, scale) truncated to [min, max].
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::utilities::noise::sample_laplace_truncated;
+/// let n = sample_laplace_truncated(-1.0, 2.0, 0.0, 1.0, false).unwrap();
+/// assert!(-1. <= n && n <= 2.);
+/// ```
+pub fn sample_laplace_truncated(
+    min: f64, max: f64, shift: f64, scale: f64,
+    enforce_constant_time: bool
+) -> Result<f64> {
+    if min > max {return Err("lower may not be greater than upper".into());}
+    if scale <= 0.0 {return Err("scale must be greater than zero".into());}
+
+    let laplace = Laplace::new(shift, scale);
+    let probability = sample_uniform(laplace.distribution(min), laplace.distribution(max), enforce_constant_time)?;
+    Ok(laplace.inverse(probability).max(min).min(max))
+}
+
+/// Sample from an exponential distribution starting at `min`, truncated at `max`.
+///
+/// Draws are made by inverting the CDF of the truncated distribution.
+///
+/// # Arguments
+/// * `min` - Lower bound of the support, where the density is greatest.
+/// * `max` - Upper bound of the support.
+/// * `scale` - The mean of the untruncated exponential distribution.
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Return
+/// A draw from the truncated exponential distribution.
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::utilities::noise::sample_exponential_truncated;
+/// let n = sample_exponential_truncated(0.0, 10.0, 2.0, false).unwrap();
+/// assert!(0. <= n && n <= 10.);
+/// ```
+pub fn sample_exponential_truncated(
+    min: f64, max: f64, scale: f64,
+    enforce_constant_time: bool
+) -> Result<f64> {
+    if min > max {return Err("lower may not be greater than upper".into());}
+    if scale <= 0.0 {return Err("scale must be greater than zero".into());}
+
+    // probability mass of the untruncated distribution within [min, max]
+    let mass = -(-(max - min) / scale).exp_m1();
+    let uniform = sample_uniform(0., 1., enforce_constant_time)?;
+    let sample = min - scale * (-uniform * mass).ln_1p();
+    Ok(num::clamp(sample, min, max))
+}
+
+/// Sample from a triangular distribution on [`min`, `max`] with peak density at `mode`.
+///
+/// # Arguments
+/// * `min` - Lower bound of the support.
+/// * `max` - Upper bound of the support.
+/// * `mode` - The most likely value. Must be within [`min`, `max`].
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Return
+/// A draw from the triangular distribution.
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::utilities::noise::sample_triangular;
+/// let n = sample_triangular(0.0, 10.0, 2.0, false).unwrap();
+/// assert!(0. <= n && n <= 10.);
+/// ```
+pub fn sample_triangular(
+    min: f64, max: f64, mode: f64,
+    enforce_constant_time: bool
+) -> Result<f64> {
+    if min > max {return Err("lower may not be greater than upper".into());}
+    if mode < min || mode > max {return Err("mode must be within lower and upper".into());}
+
+    let uniform = sample_uniform(0., 1., enforce_constant_time)?;
+    let width = max - min;
+    // probability mass to the left of the mode
+    let mode_mass = if width > 0. { (mode - min) / width } else { 0. };
+    let sample = if uniform < mode_mass {
+        min + (uniform * width * (mode - min)).sqrt()
+    } else {
+        max - ((1. - uniform) * width * (max - mode)).sqrt()
+    };
+    Ok(num::clamp(sample, min, max))
+}
+
 /// Sample from the censored geometric distribution with parameter "prob" and maximum
 /// number of trials "max_trials".
 ///
@@ -655,6 +856,80 @@ pub fn sample_simple_geometric_mechanism(
     })
 }
 
+/// Sample from the discrete Laplace (two-sided geometric) distribution on the integers.
+///
+/// The probability of each integer `z` is proportional to `exp(-|z| / scale)`.
+/// The magnitude is sampled with coin flips, as in `sample_simple_geometric_mechanism`.
+/// When `enforce_constant_time` is set, the geometric is censored after enough trials
+/// that the censored tail has probability mass below 2^-64.
+///
+/// # Arguments
+/// * `scale` - scale parameter
+/// * `enforce_constant_time` - Whether or not to enforce the algorithm to run in constant time
+///
+/// # Return
+/// A draw from the discrete Laplace distribution.
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::utilities::noise::sample_discrete_laplace;
+/// let n = sample_discrete_laplace(2.0, false);
+/// # n.unwrap();
+/// ```
+pub fn sample_discrete_laplace(scale: f64, enforce_constant_time: bool) -> Result<i64> {
+    if scale <= 0. || !scale.is_finite() {
+        return Err(format!("scale ({}) must be positive and finite", scale).into())
+    }
+    let alpha: f64 = (-1. / scale).exp();
+    // alpha^trials = exp(-trials / scale) falls below 2^-64
+    let max_trials = if enforce_constant_time { (64. * consts::LN_2 * scale).ceil() as i64 } else { i64::MAX };
+
+    // return 0 noise with probability (1-alpha) / (1+alpha), otherwise sample from geometric
+    let unif: f64 = sample_uniform(0., 1., enforce_constant_time)?;
+    let is_zero = unif < (1. - alpha) / (1. + alpha);
+    if is_zero && !enforce_constant_time {
+        return Ok(0)
+    }
+    // in constant time, the sign and magnitude are drawn even when unused
+    let sign: i64 = 2 * sample_bit()? as i64 - 1;
+    let geom: i64 = sample_geometric_censored(1. - alpha, max_trials, enforce_constant_time)?;
+    Ok(if is_zero { 0 } else { sign * geom })
+}
+
+/// Sample from the discrete Gaussian distribution on the integers.
+///
+/// The probability of each integer `z` is proportional to `exp(-z^2 / (2 scale^2))`.
+/// Samples are drawn by rejection from the discrete Laplace, following Algorithm 3 of
+/// [Canonne, Kamath & Steinke (2020)](https://arxiv.org/abs/2004.00010).
+/// The number of rejections depends on the sample, so the sampler does not run in constant time.
+///
+/// # Arguments
+/// * `scale` - The scaling parameter of the discrete Gaussian distribution.
+///
+/// # Return
+/// A draw from the discrete Gaussian distribution.
+///
+/// # Example
+/// ```
+/// use smartnoise_runtime::utilities::noise::sample_discrete_gaussian;
+/// let n = sample_discrete_gaussian(2.0);
+/// # n.unwrap();
+/// ```
+pub fn sample_discrete_gaussian(scale: f64) -> Result<i64> {
+    if scale <= 0. || !scale.is_finite() {
+        return Err(format!("scale ({}) must be positive and finite", scale).into())
+    }
+    let laplace_scale = scale.floor() + 1.;
+    let variance = scale.powi(2);
+    loop {
+        let candidate = sample_discrete_laplace(laplace_scale, false)?;
+        let exponent = (candidate.abs() as f64 - variance / laplace_scale).powi(2) / (2. * variance);
+        if sample_bit_prob((-exponent).exp(), false)? {
+            return Ok(candidate)
+        }
+    }
+}
+
 /// Apply noise to value according to the Snapping mechanism.
 /// Sensitivity is assumed to be 1 in L1 space.
 ///
@@ -755,3 +1030,38 @@ pub fn shuffle<T>(vector: Vec<T>, enforce_constant_time: bool) -> Result<Vec<T>>
     vector.sort_unstable_by_key(|v| v.1);
     Ok(vector.into_iter().map(|(v, _)| v).collect())
 }
+
+#[cfg(test)]
+mod test_entropy_source {
+    use crate::utilities::noise::{EntropySource, SeededEntropy, sample_gaussian, sample_laplace, with_entropy_source};
+
+    fn seeded_samples(seed: u64, sampler: impl Fn() -> f64) -> Vec<f64> {
+        with_entropy_source(SeededEntropy::new(seed).unwrap(), || (0..4).map(|_| sampler()).collect())
+    }
+
+    #[test]
+    fn test_keystream() {
+        // first bytes of the AES-256 encryption of a zero block under a zero key
+        let mut buffer = [0u8; 8];
+        SeededEntropy::new(0).unwrap().fill_bytes(&mut buffer).unwrap();
+        assert_eq!(buffer, [0xdc, 0x95, 0xc0, 0x78, 0xa2, 0x40, 0x89, 0x89]);
+    }
+
+    #[test]
+    fn test_laplace_sequence() {
+        let sampler = || sample_laplace(0., 1., false).unwrap();
+        assert_eq!(seeded_samples(1, sampler),
+                   [-1.2374246926966872, -0.09063550435178844, 1.9543671134656062, -0.4606414198979346]);
+        assert_ne!(seeded_samples(1, sampler), seeded_samples(2, sampler));
+    }
+
+    #[test]
+    fn test_gaussian_sequence() {
+        assert_eq!(seeded_samples(1, || sample_gaussian(0., 1., true).unwrap()),
+                   [-1.0578355294550401, 1.062157536435254, 0.6083090493389366, -0.23292503098123465]);
+
+        #[cfg(feature = "use-mpfr")]
+        assert_eq!(seeded_samples(1, || sample_gaussian(0., 1., false).unwrap()),
+                   [1.5231487527647358, 0.7896581224846975, -0.1618832524173407, -1.0270238335228956]);
+    }
+}
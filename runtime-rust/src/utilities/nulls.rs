@@ -0,0 +1,78 @@
+//! A uniform model of missing values.
+//!
+//! Floats have a dedicated null sentinel, `NaN`. Every NaN is null, regardless of its payload.
+//! Integers, strings and booleans have no value that can be set aside to mean "missing",
+//! so values of these types are only null if they match one of the `null_values` passed to a component.
+//!
+//! Row-wise components follow the same rules:
+//! * `Clamp` leaves nulls in place when clamping numerically, and maps them to the `null_value` when clamping categorically.
+//! * `Impute` is the only component that replaces nulls with non-null values.
+//! * `Resize` keeps the nulls in the records it retains, and only adds non-null records.
+//! * `Count` counts every record, null or not. Distinct counts place all nulls in one bucket.
+//!
+//! Aggregates that combine values (like `Sum` and `Mean`) reject nulls, so that a NaN is never silently released.
+
+use ndarray::ArrayD;
+use noisy_float::types::N64;
+
+use smartnoise_validator::{Float, Integer};
+use smartnoise_validator::errors::*;
+
+pub trait Nullable: Clone + PartialEq {
+    /// True if the value is the null sentinel of its type.
+    fn is_null(&self) -> bool;
+
+    /// True if the value is either the null sentinel, or one of the `null_values`.
+    fn is_null_in(&self, null_values: &[Self]) -> bool {
+        self.is_null() || null_values.contains(self)
+    }
+}
+
+impl Nullable for Float {
+    fn is_null(&self) -> bool { self.is_nan() }
+}
+
+macro_rules! impl_nullable_without_sentinel {
+    ($($ty:ty),*) => {
+        $(impl Nullable for $ty {
+            fn is_null(&self) -> bool { false }
+        })*
+    }
+}
+
+// noisy floats exclude NaN, so they cannot hold a null
+impl_nullable_without_sentinel!(Integer, String, bool, N64);
+
+/// Error if any value in `data` is a null sentinel.
+///
+/// # Arguments
+/// * `data` - Data that is about to be aggregated.
+/// * `component` - Name of the aggregating component, for the error message.
+pub fn assert_non_null<T: Nullable>(data: &ArrayD<T>, component: &str) -> Result<()> {
+    if data.iter().any(Nullable::is_null) {
+        return Err(format!("{}: data may not contain nulls. Impute the data before aggregating", component).into())
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_nulls {
+    use ndarray::arr1;
+
+    use smartnoise_validator::Float;
+
+    use crate::utilities::nulls::{assert_non_null, Nullable};
+
+    #[test]
+    fn test_sentinels() {
+        assert!(Float::NAN.is_null());
+        assert!(!"".to_string().is_null());
+        assert!(!1.5.is_null_in(&[]));
+        assert!(1.5.is_null_in(&[1.5]));
+        assert!("".to_string().is_null_in(&["".to_string()]));
+        assert!(!false.is_null_in(&[true]));
+
+        assert!(assert_non_null(&arr1(&[1., Float::NAN]).into_dyn(), "Sum").is_err());
+        assert!(assert_non_null(&arr1(&[1., 2.]).into_dyn(), "Sum").is_ok());
+    }
+}
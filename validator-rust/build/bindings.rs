@@ -16,7 +16,7 @@ pub fn build_bindings(
     let mut bindings_builders = Vec::new();
 
     components.iter().for_each(|component| {
-        if component.id == "Map" || component.id == "Union" {
+        if component.id == "Map" || component.id == "MapColumns" || component.id == "Union" {
             return
         }
 
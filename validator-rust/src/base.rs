@@ -12,8 +12,9 @@ use ndarray::{ArrayD, arr0, Dimension, arr1};
 use crate::utilities::{standardize_categorical_argument, deduplicate, get_common_value};
 use indexmap::IndexMap;
 use crate::utilities::serial::{parse_argument_node_ids, serialize_index_key};
-use std::ops::{Add, Div, Mul};
+use std::ops::{Add, Div, Mul, Sub};
 use itertools::Itertools;
+use noisy_float::types::{n64, N64};
 
 /// The universal data representation.
 ///
@@ -39,6 +40,17 @@ pub enum Value {
 }
 
 impl Value {
+    /// Convert single precision floats to double precision, including within dataframes and partitions
+    pub fn to_double(self) -> Value {
+        match self {
+            Value::Array(array) => Value::Array(array.to_double()),
+            Value::Dataframe(dataframe) => Value::Dataframe(dataframe.into_iter()
+                .map(|(k, v)| (k, v.to_double())).collect()),
+            Value::Partitions(partitions) => Value::Partitions(partitions.into_iter()
+                .map(|(k, v)| (k, v.to_double())).collect()),
+            value => value
+        }
+    }
     /// Retrieve an Array from a Value, assuming the Value contains an Array
     pub fn array(self) -> Result<Array> {
         match self {
@@ -115,7 +127,10 @@ impl Value {
                     IndexKey::Str(v) => v,
                     _ => unreachable!()
                 }).collect::<Vec<_>>()).into_dyn().into(),
-                _ => return Err("index key tuples may not currently have mixed types".into())
+                // a composite key with mixed types is a dataframe of scalars, one column per element
+                _ => Value::Dataframe(key.into_iter().enumerate()
+                    .map(|(idx, v)| Ok((IndexKey::from(idx as Integer), Value::from_index_key(v)?)))
+                    .collect::<Result<IndexMap<IndexKey, Value>>>()?)
             }
         })
     }
@@ -125,22 +140,67 @@ impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Value::Array(lhs), Value::Array(rhs)) => lhs == rhs,
+            // columns of a dataframe are ordered
+            (Value::Dataframe(lhs), Value::Dataframe(rhs)) =>
+                lhs.len() == rhs.len() && lhs.iter().zip(rhs.iter()).all(|(l, r)| l == r),
+            (Value::Partitions(lhs), Value::Partitions(rhs)) => lhs == rhs,
+            (Value::Jagged(lhs), Value::Jagged(rhs)) => lhs == rhs,
+            (Value::Function(lhs), Value::Function(rhs)) => lhs == rhs,
             _ => false
         }
     }
 }
 
+impl Value {
+    /// Compare values, where floats may differ by at most `epsilon`.
+    ///
+    /// Other data types are compared exactly, and null floats are equal to each other.
+    pub fn approx_eq(&self, other: &Value, epsilon: Float) -> bool {
+        match (self, other) {
+            (Value::Array(lhs), Value::Array(rhs)) => lhs.approx_eq(rhs, epsilon),
+            (Value::Dataframe(lhs), Value::Dataframe(rhs)) =>
+                lhs.len() == rhs.len() && lhs.iter().zip(rhs.iter())
+                    .all(|((l_key, l), (r_key, r))| l_key == r_key && l.approx_eq(r, epsilon)),
+            (Value::Partitions(lhs), Value::Partitions(rhs)) =>
+                lhs.len() == rhs.len() && lhs.iter()
+                    .all(|(key, l)| rhs.get(key).map(|r| l.approx_eq(r, epsilon)).unwrap_or(false)),
+            (Value::Jagged(lhs), Value::Jagged(rhs)) => lhs.approx_eq(rhs, epsilon),
+            _ => self == other
+        }
+    }
+}
+
 impl PartialEq for Array {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Array::Bool(lhs), Array::Bool(rhs)) => lhs == rhs,
             (Array::Float(lhs), Array::Float(rhs)) => lhs == rhs,
+            (Array::F32(lhs), Array::F32(rhs)) => lhs == rhs,
             (Array::Int(lhs), Array::Int(rhs)) => lhs == rhs,
+            (Array::Str(lhs), Array::Str(rhs)) => lhs == rhs,
             _ => false
         }
     }
 }
 
+impl Array {
+    /// Compare arrays of the same shape, where floats may differ by at most `epsilon`.
+    pub fn approx_eq(&self, other: &Array, epsilon: Float) -> bool {
+        match (self, other) {
+            (Array::Float(lhs), Array::Float(rhs)) => lhs.shape() == rhs.shape() && lhs.iter().zip(rhs.iter())
+                .all(|(l, r)| float_approx_eq(*l, *r, epsilon)),
+            (Array::F32(lhs), Array::F32(rhs)) => lhs.shape() == rhs.shape() && lhs.iter().zip(rhs.iter())
+                .all(|(l, r)| float_approx_eq(*l as Float, *r as Float, epsilon)),
+            _ => self == other
+        }
+    }
+}
+
+fn float_approx_eq(lhs: Float, rhs: Float, epsilon: Float) -> bool {
+    // equality also covers matching infinities
+    lhs == rhs || (lhs.is_nan() && rhs.is_nan()) || (lhs - rhs).abs() <= epsilon
+}
+
 // build Value from other types with .into()
 impl From<bool> for Value {
     fn from(value: bool) -> Self {
@@ -187,6 +247,13 @@ impl<T> From<ndarray::Array<Float, ndarray::Dim<T>>> for Value
     }
 }
 
+impl<T> From<ndarray::Array<f32, ndarray::Dim<T>>> for Value
+    where ndarray::Dim<T>: Dimension {
+    fn from(value: ndarray::Array<f32, ndarray::Dim<T>>) -> Self {
+        Value::Array(Array::F32(value.into_dyn()))
+    }
+}
+
 impl<T> From<ndarray::Array<String, ndarray::Dim<T>>> for Value
     where ndarray::Dim<T>: Dimension {
     fn from(value: ndarray::Array<String, ndarray::Dim<T>>) -> Self {
@@ -230,6 +297,9 @@ pub enum Array {
     Bool(ArrayD<bool>),
     Int(ArrayD<Integer>),
     Float(ArrayD<Float>),
+    /// Single precision floats, to reduce the memory footprint of data before aggregation.
+    /// Data is converted to double precision before it reaches any other component.
+    F32(ArrayD<f32>),
     Str(ArrayD<String>),
 }
 
@@ -238,6 +308,7 @@ impl Array {
     pub fn float(self) -> Result<ArrayD<Float>> {
         match self {
             Array::Float(x) => Ok(x),
+            Array::F32(x) => Ok(x.mapv(Float::from)),
             Array::Int(_) => Err("atomic type: expected float, got integer".into()),
             Array::Bool(_) => Err("atomic type: expected float, got bool".into()),
             Array::Str(_) => Err("atomic type: expected float, got string".into()),
@@ -246,6 +317,7 @@ impl Array {
     pub fn cast_float(self) -> Result<ArrayD<f64>> {
         match self {
             Array::Float(x) => Ok(x),
+            Array::F32(x) => Ok(x.mapv(Float::from)),
             Array::Int(x) => Ok(x.mapv(|v| v as Float)),
             Array::Bool(_) => Err("atomic type: expected float, got bool".into()),
             Array::Str(_) => Err("atomic type: expected float, got string".into()),
@@ -254,6 +326,7 @@ impl Array {
     pub fn ref_float(&self) -> Result<&ArrayD<Float>> {
         match self {
             Array::Float(x) => Ok(x),
+            Array::F32(_) => Err("atomic type: expected float, got single precision float".into()),
             Array::Int(_) => Err("atomic type: expected float, got integer".into()),
             Array::Bool(_) => Err("atomic type: expected float, got bool".into()),
             Array::Str(_) => Err("atomic type: expected float, got string".into()),
@@ -267,6 +340,12 @@ impl Array {
                 }
                 Ok(x.first().unwrap().to_owned())
             }
+            Array::F32(x) => {
+                if x.len() != 1 {
+                    return Err("non-singleton array passed for an argument that must be scalar".into());
+                }
+                Ok(Float::from(*x.first().unwrap()))
+            }
             _ => Err("value must be float".into())
         }
     }
@@ -282,11 +361,21 @@ impl Array {
             _ => Err(err_msg)
         }
     }
+    /// Retrieve the single precision float ndarray, assuming the data type of the ArrayND is f32
+    pub fn f32(self) -> Result<ArrayD<f32>> {
+        match self {
+            Array::F32(x) => Ok(x),
+            Array::Float(_) => Err("atomic type: expected single precision float, got float".into()),
+            Array::Int(_) => Err("atomic type: expected single precision float, got integer".into()),
+            Array::Bool(_) => Err("atomic type: expected single precision float, got bool".into()),
+            Array::Str(_) => Err("atomic type: expected single precision float, got string".into()),
+        }
+    }
     /// Retrieve the i64 ndarray, assuming the data type of the ArrayND is i64
     pub fn int(self) -> Result<ArrayD<Integer>> {
         match self {
             Array::Int(x) => Ok(x),
-            Array::Float(_) => Err("atomic type: expected integer, got float".into()),
+            Array::Float(_) | Array::F32(_) => Err("atomic type: expected integer, got float".into()),
             Array::Bool(_) => Err("atomic type: expected integer, got bool".into()),
             Array::Str(_) => Err("atomic type: expected integer, got string".into()),
         }
@@ -295,7 +384,7 @@ impl Array {
     pub fn ref_int(&self) -> Result<&ArrayD<Integer>> {
         match self {
             Array::Int(x) => Ok(x),
-            Array::Float(_) => Err("atomic type: expected integer, got float".into()),
+            Array::Float(_) | Array::F32(_) => Err("atomic type: expected integer, got float".into()),
             Array::Bool(_) => Err("atomic type: expected integer, got bool".into()),
             Array::Str(_) => Err("atomic type: expected integer, got string".into()),
         }
@@ -329,7 +418,7 @@ impl Array {
             Array::Str(x) => Ok(x),
             Array::Int(_) => Err("atomic type: expected string, got integer".into()),
             Array::Bool(_) => Err("atomic type: expected string, got bool".into()),
-            Array::Float(_) => Err("atomic type: expected string, got float".into()),
+            Array::Float(_) | Array::F32(_) => Err("atomic type: expected string, got float".into()),
         }
     }
     pub fn ref_string(&self) -> Result<&ArrayD<String>> {
@@ -337,7 +426,7 @@ impl Array {
             Array::Str(x) => Ok(x),
             Array::Int(_) => Err("atomic type: expected string, got integer".into()),
             Array::Bool(_) => Err("atomic type: expected string, got bool".into()),
-            Array::Float(_) => Err("atomic type: expected string, got float".into()),
+            Array::Float(_) | Array::F32(_) => Err("atomic type: expected string, got float".into()),
         }
     }
     pub fn first_string(&self) -> Result<String> {
@@ -357,7 +446,7 @@ impl Array {
             Array::Bool(x) => Ok(x),
             Array::Int(_) => Err("atomic type: expected bool, got integer".into()),
             Array::Str(_) => Err("atomic type: expected bool, got string".into()),
-            Array::Float(_) => Err("atomic type: expected bool, got float".into()),
+            Array::Float(_) | Array::F32(_) => Err("atomic type: expected bool, got float".into()),
         }
     }
     pub fn ref_bool(&self) -> Result<&ArrayD<bool>> {
@@ -365,7 +454,7 @@ impl Array {
             Array::Bool(x) => Ok(x),
             Array::Int(_) => Err("atomic type: expected bool, got integer".into()),
             Array::Str(_) => Err("atomic type: expected bool, got string".into()),
-            Array::Float(_) => Err("atomic type: expected bool, got float".into()),
+            Array::Float(_) | Array::F32(_) => Err("atomic type: expected bool, got float".into()),
         }
     }
     pub fn first_bool(&self) -> Result<bool> {
@@ -380,10 +469,19 @@ impl Array {
         }
     }
 
+    /// Convert single precision floats to double precision. Other atomic types are unchanged.
+    pub fn to_double(self) -> Array {
+        match self {
+            Array::F32(x) => Array::Float(x.mapv(Float::from)),
+            array => array
+        }
+    }
+
     pub fn shape(&self) -> Vec<usize> {
         match self {
             Array::Bool(array) => array.shape().to_owned(),
             Array::Float(array) => array.shape().to_owned(),
+            Array::F32(array) => array.shape().to_owned(),
             Array::Int(array) => array.shape().to_owned(),
             Array::Str(array) => array.shape().to_owned()
         }
@@ -405,6 +503,15 @@ impl Array {
             _ => Err("arrays may have max dimensionality of 2".into())
         }
     }
+    pub fn data_type(&self) -> DataType {
+        match self {
+            Array::Bool(_) => DataType::Bool,
+            Array::Float(_) => DataType::Float,
+            Array::F32(_) => DataType::F32,
+            Array::Int(_) => DataType::Int,
+            Array::Str(_) => DataType::Str
+        }
+    }
 }
 
 /// The universal jagged array representation.
@@ -413,7 +520,7 @@ impl Array {
 /// In practice, use is limited to public categories over multiple columns, and the upper triangular covariance matrix
 ///
 /// Jagged has a one-to-one mapping to a protobuf Vector2DJagged.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Jagged {
     Bool(Vec<Vec<bool>>),
     Int(Vec<Vec<Integer>>),
@@ -422,6 +529,15 @@ pub enum Jagged {
 }
 
 impl Jagged {
+    /// Compare jagged matrices, where floats may differ by at most `epsilon`.
+    pub fn approx_eq(&self, other: &Jagged, epsilon: Float) -> bool {
+        match (self, other) {
+            (Jagged::Float(lhs), Jagged::Float(rhs)) => lhs.len() == rhs.len() && lhs.iter().zip(rhs.iter())
+                .all(|(l, r)| l.len() == r.len() && l.iter().zip(r.iter())
+                    .all(|(l, r)| float_approx_eq(*l, *r, epsilon))),
+            _ => self == other
+        }
+    }
     /// Retrieve the f64 jagged matrix, assuming the data type of the jagged matrix is f64, and assuming all columns are defined
     pub fn float(&self) -> Result<Vec<Vec<Float>>> {
         match self {
@@ -473,8 +589,9 @@ impl Jagged {
 
     pub fn deduplicate(&self) -> Result<Jagged> {
         match self.to_owned() {
-            Jagged::Float(_) =>
-                Err("float data may not be categorical".into()),
+            Jagged::Float(categories) => Ok(float_categories_to_n64(categories)?.into_iter()
+                .map(|v| v.into_iter().unique().map(|v| v.raw()).collect())
+                .collect::<Vec<Vec<Float>>>().into()),
             Jagged::Int(categories) => Ok(categories.into_iter()
                 .map(|v| v.into_iter().unique().collect())
                 .collect::<Vec<Vec<Integer>>>().into()),
@@ -489,8 +606,11 @@ impl Jagged {
 
     pub fn standardize(self, num_columns: i64) -> Result<Jagged> {
         match self {
-            Jagged::Float(_) =>
-                Err("float data may not be categorical".into()),
+            Jagged::Float(categories) =>
+                Ok(standardize_categorical_argument(float_categories_to_n64(categories)?, num_columns)?
+                    .into_iter()
+                    .map(|v| v.into_iter().map(|v| v.raw()).collect())
+                    .collect::<Vec<Vec<Float>>>().into()),
             Jagged::Int(categories) =>
                 Ok(standardize_categorical_argument(categories, num_columns)?.into()),
             Jagged::Bool(categories) =>
@@ -528,6 +648,16 @@ impl Jagged {
     }
 }
 
+/// Floats are only totally ordered when NaN is excluded, so NaN may not be a category.
+fn float_categories_to_n64(categories: Vec<Vec<Float>>) -> Result<Vec<Vec<N64>>> {
+    if categories.iter().flatten().any(|v| v.is_nan()) {
+        return Err("float categories may not be NaN".into())
+    }
+    Ok(categories.into_iter()
+        .map(|v| v.into_iter().map(n64).collect())
+        .collect())
+}
+
 
 impl From<Vec<Vec<Float>>> for Jagged {
     fn from(value: Vec<Vec<Float>>) -> Self {
@@ -856,14 +986,40 @@ impl ArrayProperties {
     pub fn assert_is_releasable(&self) -> Result<()> {
         if self.releasable { Ok(()) } else { Err("data is not releasable when releasability is required".into()) }
     }
+    /// A column whose lower and upper bounds are equal is constant, so spread statistics over it have zero sensitivity.
+    ///
+    /// Such a statistic needs no privatization, so these columns are rejected rather than spending budget on them.
+    pub fn assert_bounds_not_degenerate(&self) -> Result<()> {
+        let column = match self.data_type {
+            // integer bounds are compared exactly, rather than after casting to floats
+            DataType::Int => match (self.lower_int(), self.upper_int()) {
+                (Ok(lower), Ok(upper)) => lower.iter().zip(upper.iter()).position(|(l, u)| l == u),
+                _ => None
+            },
+            _ => match (self.lower_float(), self.upper_float()) {
+                (Ok(lower), Ok(upper)) => lower.iter().zip(upper.iter()).position(|(l, u)| l == u),
+                _ => None
+            }
+        };
+        match column {
+            Some(column) => Err(format!("the lower and upper bounds of column {} are equal. The column is constant, so its spread is zero and need not be privatized", column).into()),
+            None => Ok(())
+        }
+    }
     pub fn num_columns(&self) -> Result<i64> {
         self.num_columns.ok_or_else(|| "Number of columns is not defined. Use a data resize to acquire this property.".into())
     }
     pub fn num_records(&self) -> Result<i64> {
         self.num_records.ok_or_else(|| "Number of records is not defined. Use a data resize to acquire this property.".into())
     }
+    /// Aggregates may not be transformed before they are privatized by a mechanism.
+    ///
+    /// Callers only apply this check to data that is not releasable,
+    /// as transformations of releasable data are postprocessing, and preserve privacy.
     pub fn assert_is_not_aggregated(&self) -> Result<()> {
-        if self.aggregator.is_some() { Err("aggregated data may not be manipulated".into()) } else { Ok(()) }
+        if self.aggregator.is_some() {
+            Err("aggregated data may not be manipulated until it has been privatized by a mechanism".into())
+        } else { Ok(()) }
     }
     pub fn assert_is_not_sampled(&self) -> Result<()> {
         if self.sample_proportion.unwrap_or(1.) != 1. {
@@ -880,6 +1036,9 @@ pub enum DataType {
     Str,
     Float,
     Int,
+    F32,
+    /// seconds since the unix epoch, stored in an Int array
+    DateTime,
 }
 
 
@@ -988,6 +1147,61 @@ pub enum SensitivitySpace {
     Exponential,
 }
 
+/// Strategy for interpolating a quantile that lies between two order statistics.
+///
+/// Given the order statistics `lower` and `higher` that bracket the quantile,
+/// and the fraction of the distance between them at which the quantile lies:
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// `lower`
+    Lower,
+    /// `higher`
+    Higher,
+    /// whichever of `lower` or `higher` is nearest, `higher` when equidistant
+    Nearest,
+    /// `(lower + higher) / 2`
+    Midpoint,
+    /// `lower + (higher - lower) * fraction`
+    Linear,
+}
+
+impl std::str::FromStr for Interpolation {
+    type Err = Error;
+
+    fn from_str(interpolation: &str) -> Result<Self> {
+        Ok(match interpolation.to_lowercase().as_str() {
+            "lower" => Interpolation::Lower,
+            // "upper" is accepted for compatibility
+            "higher" | "upper" => Interpolation::Higher,
+            "nearest" => Interpolation::Nearest,
+            "midpoint" => Interpolation::Midpoint,
+            "linear" => Interpolation::Linear,
+            _ => bail!("interpolation: {:?} is not one of [\"lower\", \"higher\", \"nearest\", \"midpoint\", \"linear\"]", interpolation)
+        })
+    }
+}
+
+/// Policy for digitizing elements outside of the range spanned by the bin edges.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutOfRange {
+    /// map to the null value
+    Null,
+    /// map to the nearest end bin
+    Clamp,
+}
+
+impl std::str::FromStr for OutOfRange {
+    type Err = Error;
+
+    fn from_str(out_of_range: &str) -> Result<Self> {
+        Ok(match out_of_range.to_lowercase().as_str() {
+            "null" => OutOfRange::Null,
+            "clamp" => OutOfRange::Clamp,
+            _ => bail!("out_of_range: {:?} is not one of [\"null\", \"clamp\"]", out_of_range)
+        })
+    }
+}
+
 /// A release consists of Values for each node id.
 pub type Release = HashMap<u32, ReleaseNode>;
 
@@ -997,7 +1211,12 @@ pub struct GroupId {
     pub index: IndexKey
 }
 
-#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+/// Key of a column in a dataframe, or of a partition.
+///
+/// Composite keys, like those from partitioning by more than one column, are tuples.
+/// Keys are ordered first by variant, in the order declared, and then by value.
+/// Tuples are ordered lexicographically.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Hash)]
 pub enum IndexKey {
     Str(String),
     Int(Integer),
@@ -1045,7 +1264,32 @@ impl IndexKey {
                     _ => Err("Indexing keys may not be created from 2+ dimensional arrays.".into())
                 }
             }
-            Array::Float(_) => Err("Floats may not be index keys, because they are not comparable".into())
+            Array::Float(_) | Array::F32(_) => Err("Floats may not be index keys, because they are not comparable".into())
+        }
+    }
+
+    /// Create a key from an array, or a composite key from a dataframe of scalars.
+    ///
+    /// The elements of a composite key are taken from the columns of the dataframe, in column order,
+    /// so that the elements may have differing types.
+    pub fn from_value(value: Value) -> Result<IndexKey> {
+        match value {
+            Value::Array(array) => IndexKey::new(array),
+            Value::Dataframe(dataframe) => Ok(IndexKey::Tuple(dataframe.into_iter()
+                .map(|(_, column)| {
+                    let column = column.array()?;
+                    if column.num_records()? != 1 {
+                        return Err("each column of a composite key must contain exactly one element".into())
+                    }
+                    Ok(match column {
+                        Array::Int(column) => IndexKey::Int(*column.first().unwrap()),
+                        Array::Str(column) => IndexKey::Str(column.first().unwrap().clone()),
+                        Array::Bool(column) => IndexKey::Bool(*column.first().unwrap()),
+                        Array::Float(_) | Array::F32(_) => return Err("Floats may not be index keys, because they are not comparable".into())
+                    })
+                })
+                .collect::<Result<Vec<IndexKey>>>()?)),
+            _ => Err("index keys must be created from arrays or dataframes".into())
         }
     }
 }
@@ -1110,6 +1354,32 @@ impl ComponentExpansion {
             Err("computation graph patch must be same length as the number of properties".into())
         } else { Ok(()) }
     }
+
+    /// Insert a public literal into the expansion, with the next unused node id.
+    pub fn insert_literal(&mut self, maximum_id: &mut u32, value: Value, submission: u32) -> Result<u32> {
+        *maximum_id += 1;
+        let (patch_node, release) = crate::utilities::get_literal(value, submission)?;
+        self.computation_graph.insert(*maximum_id, patch_node);
+        self.properties.insert(*maximum_id, crate::utilities::inference::infer_property(&release.value, None, *maximum_id)?);
+        self.releases.insert(*maximum_id, release);
+        Ok(*maximum_id)
+    }
+
+    /// Insert an omitted component into the expansion, with the next unused node id, to be traversed.
+    pub fn insert_component(
+        &mut self, maximum_id: &mut u32,
+        arguments: IndexMap<IndexKey, u32>, variant: proto::component::Variant, submission: u32,
+    ) -> u32 {
+        *maximum_id += 1;
+        self.computation_graph.insert(*maximum_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(arguments)),
+            variant: Some(variant),
+            omit: true,
+            submission,
+        });
+        self.traversal.push(*maximum_id);
+        *maximum_id
+    }
 }
 
 impl proto::Component {
@@ -1154,6 +1424,14 @@ pub type NodeProperties = IndexMap<base::IndexKey, ValueProperties>;
 
 
 impl proto::PrivacyUsage {
+    /// Converts an actual privacy usage into the effective usage a mechanism may spend on a single record.
+    ///
+    /// Let `c = c_stability * group_size` be the number of records an individual may influence,
+    /// and `s_c = min(1, c * s)` bound the probability that any of these records are subsampled with proportion `s`.
+    /// Without subsampling (`s_c = 1`), by group privacy the effective epsilon is `epsilon / c`.
+    /// Otherwise the effective epsilon is `ln((exp(epsilon) - 1) / s_c + 1) / c`,
+    /// the inverse of privacy amplification by subsampling.
+    /// Delta is scaled by the inverse of the delta scaling in [`effective_to_actual`](#method.effective_to_actual).
     pub(crate) fn actual_to_effective(&self, s: f64, mut c_stability: u32, group_size: u32) -> Result<Self> {
         if group_size == 0 {
             return Err(Error::from("group size must be greater than zero"))
@@ -1161,22 +1439,40 @@ impl proto::PrivacyUsage {
         use proto::privacy_usage::{DistanceApproximate, Distance::Approximate};
 
         c_stability *= group_size;
+        let s = group_sample_proportion(s, c_stability);
         Ok(proto::PrivacyUsage {
             distance: Some(match self.distance.as_ref().ok_or_else(|| "distance must be defined")? {
-                Approximate(DistanceApproximate { epsilon, delta }) =>
-                    Approximate(DistanceApproximate {
-                    epsilon: match s {
+                Approximate(DistanceApproximate { epsilon, delta }) => {
+                    let effective_epsilon = match s {
                         s if s == 1. => epsilon / c_stability as f64,
                         _ if *epsilon > 100. =>
                             return Err(Error::from("large epsilon (>100) with privacy amplification by subsampling is numerically unstable")),
                         s => (((epsilon.exp() - 1.) / s) + 1.).ln() / c_stability as f64
-                    },
-                    delta: delta / s / ((c_stability as f64 * epsilon).exp() - 1.) / (epsilon.exp() - 1.),
-                })
+                    };
+                    // inverse of the delta scaling in effective_to_actual, with respect to the effective epsilon
+                    let delta_scale = match c_stability {
+                        1 => 1.,
+                        c_stability if effective_epsilon == 0. => 1. / c_stability as f64,
+                        c_stability => {
+                            let scale = effective_epsilon.exp_m1() / (c_stability as f64 * effective_epsilon).exp_m1();
+                            // both terms overflow for large epsilon, where the ratio tends to exp(-(c - 1) epsilon)
+                            if scale.is_nan() { (-(c_stability as f64 - 1.) * effective_epsilon).exp() } else { scale }
+                        }
+                    };
+                    Approximate(DistanceApproximate {
+                        epsilon: effective_epsilon,
+                        delta: delta / s * delta_scale,
+                    })
+                }
             })
         })
     }
 
+    /// Converts the effective privacy usage spent by a mechanism into the actual usage over groups of records.
+    ///
+    /// With `c = c_stability * group_size` and `s_c = min(1, c * s)`, the actual epsilon is `c * epsilon`
+    /// without subsampling (`s_c = 1`), or `ln((exp(c * epsilon) - 1) * s_c + 1)` under subsampling with proportion `s`.
+    /// The actual delta is `s_c * delta * (exp(c * epsilon) - 1) / (exp(epsilon) - 1)`.
     pub(crate) fn effective_to_actual(&self, s: f64, mut c_stability: u32, group_size: u32) -> Result<Self> {
         if group_size == 0 {
             return Err(Error::from("group size must be greater than zero"))
@@ -1184,6 +1480,7 @@ impl proto::PrivacyUsage {
         use proto::privacy_usage::{DistanceApproximate, Distance::Approximate};
 
         c_stability *= group_size;
+        let s = group_sample_proportion(s, c_stability);
         Ok(proto::PrivacyUsage {
             distance: Some(match self.distance.as_ref().ok_or_else(|| "distance must be defined")? {
                 Approximate(DistanceApproximate { epsilon, delta }) => Approximate(DistanceApproximate {
@@ -1200,6 +1497,13 @@ impl proto::PrivacyUsage {
     }
 }
 
+/// Upper bound on the probability that any of `c_stability` records are kept when subsampling with proportion `s`.
+///
+/// Amplification is applied to the group of records an individual may influence, not to a single record,
+/// so the proportion is widened by a union bound over the group.
+fn group_sample_proportion(s: f64, c_stability: u32) -> f64 {
+    (s * c_stability as f64).min(1.)
+}
 
 impl Add<proto::PrivacyUsage> for proto::PrivacyUsage {
     type Output = Result<proto::PrivacyUsage>;
@@ -1221,6 +1525,26 @@ impl Add<proto::PrivacyUsage> for proto::PrivacyUsage {
 }
 
 
+impl Sub<proto::PrivacyUsage> for proto::PrivacyUsage {
+    type Output = Result<proto::PrivacyUsage>;
+
+    fn sub(mut self, rhs: proto::PrivacyUsage) -> Self::Output {
+        let left_distance = self.distance.ok_or("distance must be defined")?;
+        let right_distance = rhs.distance.ok_or("distance must be defined")?;
+
+        use proto::privacy_usage::Distance;
+
+        self.distance = Some(match (left_distance, right_distance) {
+            (Distance::Approximate(lhs), Distance::Approximate(rhs)) => proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: lhs.epsilon - rhs.epsilon,
+                delta: lhs.delta - rhs.delta,
+            })
+        });
+        Ok(self)
+    }
+}
+
+
 impl Mul<f64> for proto::PrivacyUsage {
     type Output = Result<proto::PrivacyUsage>;
 
@@ -1338,3 +1662,89 @@ pub mod test_data {
         ]).into()
     }
 }
+
+#[cfg(test)]
+mod test_value {
+    use indexmap::IndexMap;
+    use ndarray::{arr1, arr2};
+
+    use crate::base::{IndexKey, Jagged, Value};
+    use crate::base::test_data::*;
+    use crate::Float;
+
+    #[test]
+    fn test_cross_type() {
+        // the same elements under different types are not equal
+        let int: Value = arr1(&[1, 2]).into_dyn().into();
+        let float: Value = arr1(&[1., 2.]).into_dyn().into();
+        let string: Value = arr1(&["1".to_string(), "2".to_string()]).into_dyn().into();
+        assert!(int != float && float != string && int != string);
+        assert!(!int.approx_eq(&float, 1.));
+
+        assert!(array1d_string_10_uniform() == array1d_string_10_uniform());
+        assert!(array1d_string_0() != array1d_f64_0());
+        assert!(Value::Jagged(Jagged::Int(vec![vec![1]])) != Value::Jagged(Jagged::Float(vec![vec![1.]])));
+
+        // partitions and dataframes with the same content differ
+        let mut map = IndexMap::<IndexKey, Value>::new();
+        map.insert("a".into(), int.clone());
+        assert!(Value::Dataframe(map.clone()) == Value::Dataframe(map.clone()));
+        assert!(Value::Dataframe(map.clone()) != Value::Partitions(map));
+    }
+
+    #[test]
+    fn test_float_tolerance() {
+        let lhs: Value = arr2(&[[0.1 + 0.2, Float::NAN], [Float::INFINITY, 1.]]).into_dyn().into();
+        let rhs: Value = arr2(&[[0.3, Float::NAN], [Float::INFINITY, 1.]]).into_dyn().into();
+        assert!(lhs != rhs);
+        assert!(lhs.approx_eq(&rhs, 1e-12));
+        assert!(!lhs.approx_eq(&rhs, 0.));
+
+        // shapes must match
+        let flat: Value = arr1(&[0.3, Float::NAN, Float::INFINITY, 1.]).into_dyn().into();
+        assert!(!rhs.approx_eq(&flat, 1.));
+
+        let lhs = Jagged::Float(vec![vec![1., 2.], vec![3.]]);
+        assert!(lhs.approx_eq(&Jagged::Float(vec![vec![1.05, 2.], vec![3.]]), 0.1));
+        assert!(!lhs.approx_eq(&Jagged::Float(vec![vec![1., 2.], vec![3., 4.]]), 0.1));
+
+        // dataframe columns are compared in order
+        let column = |v: Float| -> Value { arr1(&[v]).into_dyn().into() };
+        let mut lhs = IndexMap::<IndexKey, Value>::new();
+        lhs.insert("a".into(), column(1.));
+        lhs.insert("b".into(), column(2.));
+        let mut rhs = IndexMap::<IndexKey, Value>::new();
+        rhs.insert("b".into(), column(2.));
+        rhs.insert("a".into(), column(1. + 1e-14));
+        assert!(!Value::Dataframe(lhs.clone()).approx_eq(&Value::Dataframe(rhs.clone()), 1e-12));
+        assert!(Value::Partitions(lhs).approx_eq(&Value::Partitions(rhs), 1e-12));
+    }
+}
+
+#[cfg(test)]
+mod test_privacy_usage {
+    use crate::proto;
+
+    #[test]
+    fn test_effective_round_trip() {
+        use proto::privacy_usage::{Distance, DistanceApproximate};
+        let usage = proto::PrivacyUsage {
+            distance: Some(Distance::Approximate(DistanceApproximate { epsilon: 0.5, delta: 1e-6 }))
+        };
+        let unpack = |usage: &proto::PrivacyUsage| match usage.distance.clone().unwrap() {
+            Distance::Approximate(approximate) => (approximate.epsilon, approximate.delta)
+        };
+
+        // without amplification or stability, the effective usage is the actual usage
+        assert_eq!(usage.actual_to_effective(1., 1, 1).unwrap(), usage);
+
+        vec![(1., 2, 1), (0.5, 1, 1), (0.3, 2, 3)].into_iter()
+            .for_each(|(s, c_stability, group_size)| {
+                let round_trip = usage.actual_to_effective(s, c_stability, group_size).unwrap()
+                    .effective_to_actual(s, c_stability, group_size).unwrap();
+                let ((epsilon, delta), (expected_epsilon, expected_delta)) = (unpack(&round_trip), unpack(&usage));
+                assert!((epsilon - expected_epsilon).abs() < 1e-12);
+                assert!((delta - expected_delta).abs() < 1e-18);
+            });
+    }
+}
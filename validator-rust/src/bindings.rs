@@ -50,7 +50,9 @@ impl Analysis {
                 protect_elapsed_time: false,
                 protect_memory_utilization: false,
                 protect_floating_point: true,
-                protect_sensitivity: true
+                protect_sensitivity: true,
+                candidate_spacing_ratio: 0.,
+                default_mechanism: proto::privacy_definition::MechanismPreference::Pure as i32,
             },
             components: HashMap::new(),
             component_count: 0,
@@ -76,6 +78,15 @@ impl Analysis {
     }
 }
 
+/// Privacy usage of `epsilon` and `delta`, as passed to the builders.
+pub fn privacy_usage(epsilon: f64, delta: f64) -> Vec<proto::PrivacyUsage> {
+    vec![proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+            epsilon, delta
+        }))
+    }]
+}
+
 include!(concat!(env!("OUT_DIR"), "/bindings_analysis.rs"));
 
 pub mod builders {
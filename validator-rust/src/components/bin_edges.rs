@@ -0,0 +1,97 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto, Warnable};
+use crate::base::{DataType, IndexKey, JaggedProperties, Value, ValueProperties};
+use crate::components::Component;
+use crate::errors::*;
+use crate::utilities::prepend;
+
+impl Component for proto::BinEdges {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        // the edges are public arguments downstream, so they may only be derived from a release
+        if !data_property.releasable {
+            return Err("data: must be releasable, because bin edges are public".into())
+        }
+
+        if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
+            return Err("data: atomic type must be numeric".into())
+        }
+
+        let get_edge = |name: &str| -> Result<Option<f64>> {
+            if properties.contains_key::<IndexKey>(&name.into()) {
+                let edge = public_arguments.get::<IndexKey>(&name.into())
+                    .ok_or_else(|| Error::from(format!("{}: must be public", name)))?
+                    .ref_array()?;
+                if edge.data_type() != data_property.data_type {
+                    return Err(format!("{}: atomic type must match the data", name).into())
+                }
+                let edge = edge.clone().cast_float()?;
+                if edge.len() != 1 || edge.iter().any(|v| v.is_nan()) {
+                    return Err(format!("{}: must be a non-NaN scalar", name).into())
+                }
+                Ok(edge.first().copied())
+            } else { Ok(None) }
+        };
+
+        if let (Some(lower), Some(upper)) = (get_edge("lower")?, get_edge("upper")?) {
+            if lower >= upper {
+                return Err("lower must be less than upper".into())
+            }
+        }
+
+        // duplicate cut-points are removed, so the number of edges is only known once the cut-points are released
+        Ok(ValueProperties::Jagged(JaggedProperties {
+            num_records: None,
+            nullity: false,
+            aggregator: None,
+            nature: None,
+            data_type: data_property.data_type.clone(),
+            releasable: true,
+        }).into())
+    }
+}
+
+#[cfg(test)]
+mod test_bin_edges {
+    use crate::bindings::Analysis;
+    use ndarray::arr1;
+
+    #[test]
+    fn test_private_data() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr1(&[1., 2.]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let edges = analysis.bin_edges(data).build();
+        assert!(analysis.properties(edges).is_err());
+    }
+
+    #[test]
+    fn test_edge_order() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr1(&[1., 2.]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(5.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(0.0.into()).value_public(true).build();
+
+        let edges = analysis.bin_edges(data).lower(lower).upper(upper).build();
+        assert!(analysis.properties(edges).is_err());
+
+        let edges = analysis.bin_edges(data).lower(upper).upper(lower).build();
+        let properties = analysis.properties(edges).unwrap();
+        assert!(properties.jagged().unwrap().releasable);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::errors::*;
+
+use crate::components::Component;
+use crate::base::{Value, ValueProperties, DataType, IndexKey};
+use crate::utilities::prepend;
+use crate::{base, Warnable};
+use crate::proto;
+use crate::components::transforms::propagate_binary_shape;
+use indexmap::map::IndexMap;
+
+impl Component for proto::BoundContribution {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        let key_property = properties.get::<IndexKey>(&"key".into())
+            .ok_or("key: missing")?.array()
+            .map_err(prepend("key:"))?.clone();
+
+        if !key_property.releasable {
+            key_property.assert_is_not_aggregated()?;
+        }
+
+        match key_property.data_type {
+            DataType::Int | DataType::Str | DataType::Bool | DataType::DateTime => (),
+            _ => return Err("key: atomic type must be integer, string or boolean".into())
+        }
+
+        if key_property.num_columns()? != 1 {
+            return Err("key: number of columns must be one".into())
+        }
+
+        propagate_binary_shape(&data_property, &key_property)?;
+
+        if self.max_contributions == 0 {
+            return Err("max_contributions: must be positive".into())
+        }
+
+        // each of the at most max_contributions rows of an individual may already be influenced by c_stability records
+        data_property.c_stability = data_property.c_stability.checked_mul(self.max_contributions)
+            .ok_or_else(|| Error::from("c_stability: overflow when bounding contributions"))?;
+
+        // the number of records is not known after truncating rows
+        data_property.num_records = None;
+
+        // This exists to prevent binary ops on non-conformable arrays from being approved
+        data_property.dataset_id = Some(node_id as i64);
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+#[cfg(test)]
+mod test_bound_contribution {
+    use ndarray::{arr0, arr1, arr2};
+
+    use crate::base::Value;
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::proto;
+    use crate::utilities::privacy::get_epsilon;
+
+    /// private data where individual 1 contributes four rows, truncated to `max_contributions` rows per individual
+    fn bounded_data(analysis: &mut Analysis, max_contributions: u32) -> u32 {
+        let data = analysis.literal()
+            .value(arr1(&[1, 1, 1, 1, 2, 3]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let lower = analysis.literal().value(1.into()).value_public(true).build();
+        let upper = analysis.literal().value(3.into()).value_public(true).build();
+        let data = analysis.to_int(data, lower, upper).build();
+        let number_rows = analysis.literal().value(6.into()).value_public(true).build();
+        let number_columns = analysis.literal().value(1.into()).value_public(true).build();
+        let data = analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .lower(lower).upper(upper)
+            .build();
+        // each value identifies the individual that contributed it
+        analysis.bound_contribution(data, data, max_contributions).build()
+    }
+
+    #[test]
+    fn test_c_stability() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[1.], [2.], [3.]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let key = analysis.literal()
+            .value(arr2(&[["a".to_string()], ["a".to_string()], ["b".to_string()]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let bounded = analysis.bound_contribution(data, key, 2).build();
+
+        let properties = analysis.properties(bounded).unwrap();
+        let properties = properties.array().unwrap();
+        assert_eq!(properties.c_stability, 2);
+        assert_eq!(properties.num_records, None);
+
+        // float keys are not permitted
+        let bounded = analysis.bound_contribution(data, data, 2).build();
+        assert!(analysis.properties(bounded).is_err());
+
+        // an earlier stability factor is scaled, not discarded
+        let data = analysis.literal()
+            .value(arr2(&[[1], [1], [2]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let bounded = analysis.bound_contribution(data, data, 2).build();
+        let bounded = analysis.bound_contribution(bounded, bounded, 3).build();
+        assert_eq!(analysis.properties(bounded).unwrap().array().unwrap().c_stability, 6);
+    }
+
+    /// epsilon spent by the laplace mechanism in the expanded graph, and the epsilon charged to the analysis
+    fn laplace_epsilon(analysis: Analysis) -> (f64, f64) {
+        let mut graph = analysis.components.clone();
+        let mut release = analysis.release.clone();
+        crate::utilities::propagate_properties(
+            &Some(analysis.privacy_definition.clone()), &mut graph, &mut release, None, false).unwrap();
+        let effective = graph.values()
+            .find_map(|component| match &component.variant {
+                Some(proto::component::Variant::LaplaceMechanism(mechanism)) => mechanism.privacy_usage.first().cloned(),
+                _ => None
+            }).unwrap();
+
+        let actual = crate::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        (get_epsilon(&effective).unwrap(), get_epsilon(&actual).unwrap())
+    }
+
+    #[test]
+    fn test_count_sensitivity() {
+        // when each individual contributes at most k rows, the noise is scaled as if the count had sensitivity k
+        (1..=3).for_each(|max_contributions| {
+            let mut analysis = Analysis::new();
+            analysis.privacy_definition.protect_floating_point = false;
+            let data = bounded_data(&mut analysis, max_contributions);
+            let lower = analysis.literal().value(arr0(0).into_dyn().into()).value_public(true).build();
+            analysis.dp_count(data, lower, privacy_usage(0.5, 0.))
+                .mechanism("Laplace".to_string())
+                .build();
+
+            let (effective, actual) = laplace_epsilon(analysis);
+            assert_eq!(effective, 0.5 / max_contributions as f64);
+            assert_eq!(actual, 0.5);
+        })
+    }
+
+    #[test]
+    fn test_histogram_sensitivity() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = bounded_data(&mut analysis, 2);
+        let categories = analysis.literal()
+            .value(Value::Jagged(vec![vec![1, 2, 3]].into()))
+            .value_public(true)
+            .build();
+        let null_value = analysis.literal().value(arr0(0).into_dyn().into()).value_public(true).build();
+        let lower = analysis.literal().value(arr0(0).into_dyn().into()).value_public(true).build();
+        let inclusive_left = analysis.literal().value(arr0(true).into_dyn().into()).value_public(true).build();
+        analysis.dp_histogram(data, privacy_usage(0.5, 0.)).lower(lower).inclusive_left(inclusive_left)
+            .categories(categories).null_value(null_value)
+            .mechanism("Laplace".to_string())
+            .build();
+
+        let (effective, actual) = laplace_epsilon(analysis);
+        assert_eq!(effective, 0.25);
+        assert_eq!(actual, 0.5);
+    }
+
+    #[test]
+    fn test_sum_sensitivity() {
+        // the sum is scaled by the number of rows an individual contributes, like the count
+        (1..=3).for_each(|max_contributions| {
+            let mut analysis = Analysis::new();
+            analysis.privacy_definition.protect_floating_point = false;
+            let data = bounded_data(&mut analysis, max_contributions);
+            let lower = analysis.literal().value(arr0(1).into_dyn().into()).value_public(true).build();
+            let upper = analysis.literal().value(arr0(3).into_dyn().into()).value_public(true).build();
+            let data = analysis.clamp(data).lower(lower).upper(upper).build();
+            analysis.dp_sum(data, privacy_usage(0.5, 0.))
+                .mechanism("Laplace".to_string())
+                .build();
+
+            let (effective, actual) = laplace_epsilon(analysis);
+            assert_eq!(effective, 0.5 / max_contributions as f64);
+            assert_eq!(actual, 0.5);
+        })
+    }
+}
@@ -3,12 +3,12 @@ use crate::errors::*;
 use std::collections::HashMap;
 
 
-use crate::{proto, base, Warnable, Integer};
+use crate::{proto, base, Warnable, Integer, Float};
 use crate::hashmap;
 use crate::components::{Component, Expandable};
 
 use crate::base::{Value, NodeProperties, ValueProperties, DataType, Nature, NatureCategorical, Jagged, Vector1DNull, NatureContinuous, Array, IndexKey};
-use crate::utilities::{prepend, get_argument};
+use crate::utilities::{prepend, get_argument, parse_data_type, standardize_numeric_argument};
 use itertools::Itertools;
 use indexmap::map::IndexMap;
 
@@ -27,22 +27,59 @@ impl Component for proto::Cast {
         data_property.assert_is_not_aggregated()?;
         let prior_datatype = data_property.data_type.clone();
 
-        data_property.data_type = match self.atomic_type.to_lowercase().as_str() {
-            "float" => DataType::Float,
-            "real" => DataType::Float,
-            "int" => DataType::Int,
-            "integer" => DataType::Int,
-            "bool" => DataType::Bool,
-            "string" => DataType::Str,
-            "str" => DataType::Str,
-            _ => bail!("data type is not recognized. Must be one of \"float\", \"int\", \"bool\" or \"string\"")
-        };
+        data_property.data_type = parse_data_type(&self.atomic_type)?;
 
         match data_property.data_type {
-            DataType::Unknown => unreachable!(),
+            DataType::Unknown | DataType::F32 | DataType::DateTime => unreachable!(),
+            DataType::Bool if !properties.contains_key::<IndexKey>(&"true_label".into()) => {
+                // without a true label, numeric data is compared against a threshold
+                if !properties.contains_key::<IndexKey>(&"threshold".into()) {
+                    return Err("true_label or threshold must be defined".into())
+                }
+                let threshold = get_argument(&public_arguments, "threshold")
+                    .map_err(|_| Error::from("threshold: must be public"))?
+                    .ref_array()?.clone().cast_float()
+                    .map_err(prepend("threshold:"))?;
+
+                if !matches!(prior_datatype, DataType::Float | DataType::F32 | DataType::Int) {
+                    return Err("data: atomic type must be numeric when casting to bool with a threshold".into())
+                }
+                if threshold.iter().any(|v| v.is_nan()) {
+                    return Err("threshold: may not be NaN".into())
+                }
+
+                // check categories against the threshold
+                data_property.nature = match (data_property.nature, data_property.num_columns) {
+                    (Some(Nature::Categorical(cat_nature)), Some(num_columns)) => {
+                        let threshold = standardize_numeric_argument(threshold, num_columns)?;
+                        Some(Nature::Categorical(NatureCategorical {
+                            categories: match cat_nature.categories {
+                                Jagged::Int(cats) => Jagged::Bool(cats.iter().zip(threshold.iter())
+                                    .map(|(cats, threshold)| cats.iter().map(|v| *v as Float >= *threshold)
+                                        .unique().collect::<Vec<_>>())
+                                    .collect::<Vec<Vec<_>>>()),
+                                Jagged::Float(cats) => Jagged::Bool(cats.iter().zip(threshold.iter())
+                                    .map(|(cats, threshold)| cats.iter().map(|v| v >= threshold)
+                                        .unique().collect::<Vec<_>>())
+                                    .collect::<Vec<Vec<_>>>()),
+                                _ => return Err("data: categories must be numeric when casting to bool with a threshold".into())
+                            }
+                        }))
+                    },
+                    _ => data_property.num_columns
+                        .map(|num_columns| Nature::Categorical(NatureCategorical {
+                            categories: Jagged::Bool((0..num_columns).map(|_| vec![true, false]).collect())
+                        }))
+                };
+
+                // NaN values are mapped to false
+                data_property.nullity = false;
+            },
             DataType::Bool => {
                 // true label must be defined
-                let true_label = get_argument(&public_arguments, "true_label")?.clone().array()?;
+                let true_label = get_argument(&public_arguments, "true_label")
+                    .map_err(|_| Error::from("true_label: must be public"))?
+                    .clone().array()?;
 
                 // check categories for equality with true_label
                 data_property.nature = match data_property.nature {
@@ -84,11 +121,14 @@ impl Component for proto::Cast {
             },
             DataType::Int => {
                 // lower must be defined, for imputation of values that won't cast
-                get_argument(&public_arguments, "lower")?.ref_array()?.first_int()
+                let lower = get_argument(&public_arguments, "lower")?.ref_array()?.first_int()
                     .map_err(prepend("type:"))?;
                 // max must be defined
-                get_argument(&public_arguments, "upper")?.ref_array()?.first_int()
+                let upper = get_argument(&public_arguments, "upper")?.ref_array()?.first_int()
                     .map_err(prepend("type:"))?;
+                if lower > upper {
+                    return Err("lower may not be greater than upper".into())
+                }
 
                 data_property.nature = match data_property.nature {
                     Some(nature) => match nature.clone() {
@@ -162,7 +202,8 @@ impl Component for proto::Cast {
                 data_property.nature = None;
                 data_property.nullity = match prior_datatype {
                     DataType::Float => data_property.nullity,
-                    DataType::Bool => false,
+                    DataType::Bool | DataType::Int => false,
+                    // strings that cannot be parsed are mapped to NaN
                     _ => true
                 }
             }
@@ -247,7 +288,14 @@ pub mod test_cast {
         pub fn analysis_bool(value: Value, true_label: Value) -> (Analysis, u32) {
             let (mut analysis, literal) = test_literal::analysis_literal(value, true);
             let true_label = analysis.literal().value(true_label).value_public(true).build();
-            let cast = analysis.to_bool(literal, true_label).build();
+            let cast = analysis.to_bool(literal).true_label(true_label).build();
+            (analysis, cast)
+        }
+
+        pub fn analysis_bool_threshold(value: Value, threshold: Value) -> (Analysis, u32) {
+            let (mut analysis, literal) = test_literal::analysis_literal(value, true);
+            let threshold = analysis.literal().value(threshold).value_public(true).build();
+            let cast = analysis.to_bool(literal).threshold(threshold).build();
             (analysis, cast)
         }
     }
@@ -283,4 +331,83 @@ pub mod test_cast {
         array1d_string_10_uniform: "a".to_string().into(),
         array1d_bool_10_uniform: true.into(),
     );
+
+    #[test]
+    fn test_bool_threshold() {
+        use crate::base::{Jagged, Nature, Value};
+        use crate::bindings::Analysis;
+
+        let (analysis, cast) = utilities::analysis_bool_threshold(
+            test_data::array1d_f64_10_uniform(), 0.5.into());
+        let properties = analysis.properties(cast).unwrap();
+        let properties = properties.array().unwrap();
+        assert!(!properties.nullity);
+        match properties.nature.as_ref().unwrap() {
+            Nature::Categorical(nature) => match &nature.categories {
+                Jagged::Bool(categories) => assert_eq!(categories, &vec![vec![true, false]]),
+                _ => panic!("categories must be bool")
+            },
+            _ => panic!("nature must be categorical")
+        }
+
+        // categories are compared against the threshold
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(ndarray::arr2(&[[1], [2], [3]]).into_dyn().into())
+            .value_public(true).build();
+        let categories = analysis.literal()
+            .value(Value::Jagged(vec![vec![1, 2, 3]].into()))
+            .value_public(true).build();
+        let null_value = analysis.literal().value(0.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).categories(categories).null_value(null_value).build();
+        let threshold = analysis.literal().value(5.into()).value_public(true).build();
+        let cast = analysis.to_bool(clamped).threshold(threshold).build();
+        match analysis.properties(cast).unwrap().array().unwrap().nature.as_ref().unwrap() {
+            Nature::Categorical(nature) => match &nature.categories {
+                Jagged::Bool(categories) => assert_eq!(categories, &vec![vec![false]]),
+                _ => panic!("categories must be bool")
+            },
+            _ => panic!("nature must be categorical")
+        }
+
+        // strings may not be compared against a threshold
+        let (analysis, cast) = utilities::analysis_bool_threshold(
+            test_data::array1d_string_10_uniform(), 0.5.into());
+        assert!(analysis.properties(cast).is_err());
+
+        // one of true_label or threshold must be set
+        let (mut analysis, literal) = crate::components::literal::test_literal::analysis_literal(
+            test_data::array1d_f64_10_uniform(), true);
+        let cast = analysis.to_bool(literal).build();
+        assert!(analysis.properties(cast).is_err());
+
+        // single precision floats may be compared against a threshold
+        let (analysis, cast) = utilities::analysis_bool_threshold(
+            ndarray::arr1(&[0.25f32, 0.75]).into(), 0.5.into());
+        assert!(analysis.properties(cast).is_ok());
+    }
+
+    #[test]
+    fn test_bool_private_true_label() {
+        let (mut analysis, literal) = crate::components::literal::test_literal::analysis_literal(
+            test_data::array1d_i64_10_uniform(), true);
+        let true_label = analysis.literal().value(1.into()).value_public(false).build();
+        let cast = analysis.to_bool(literal).true_label(true_label).build();
+        let error = analysis.properties(cast).unwrap_err();
+        assert!(error.to_string().contains("true_label: must be public"), "{}", error);
+    }
+
+    #[test]
+    fn test_unparseable_strings() {
+        let (analysis, cast) = utilities::analysis_f64(test_data::array1d_string_10_uniform());
+        assert!(analysis.properties(cast).unwrap().array().unwrap().nullity);
+
+        // strings that cannot be parsed are imputed when casting to integers
+        let (analysis, cast) = utilities::analysis_i64(test_data::array1d_string_10_uniform(), None, None);
+        assert!(!analysis.properties(cast).unwrap().array().unwrap().nullity);
+
+        let (analysis, cast) = utilities::analysis_i64(
+            test_data::array1d_string_10_uniform(), Some(10.into()), Some(0.into()));
+        assert!(analysis.properties(cast).is_err());
+    }
 }
@@ -3,7 +3,7 @@ use crate::errors::*;
 use crate::base::{Nature, Vector1DNull, Array, ValueProperties, NatureCategorical, Jagged, DataType};
 
 use crate::{proto, base, Warnable};
-use crate::utilities::{prepend, get_literal, standardize_null_target_argument};
+use crate::utilities::{prepend, get_literal, standardize_null_target_argument, inferred_argument_warning};
 use crate::components::{Component, Expandable};
 
 use crate::base::{IndexKey, Value, NatureContinuous};
@@ -39,6 +39,10 @@ impl Component for proto::Clamp {
             let mut categories = categories.ref_jagged()?.clone();
             match (&mut categories, null) {
                 (Jagged::Float(jagged), Array::Float(null)) => {
+                    // categories are compared under a total ordering, which excludes NaN
+                    if jagged.iter().flatten().chain(null.iter()).any(|v| v.is_nan()) {
+                        return Err("float categories and null_value may not be NaN".into())
+                    }
                     let null_target = standardize_null_target_argument(null.clone(), num_columns)?;
                     jagged.iter_mut().zip(null_target.into_iter())
                         .for_each(|(cats, null)| cats.push(null))
@@ -227,6 +231,7 @@ impl Expandable for proto::Clamp {
             expansion.computation_graph.insert(id_lower, patch_node);
             expansion.releases.insert(id_lower, release);
             component.insert_argument(&"lower".into(), id_lower);
+            expansion.warnings.push(inferred_argument_warning("lower"));
         }
 
         if !has_categorical && !properties.contains_key::<IndexKey>(&"upper".into()) {
@@ -240,6 +245,7 @@ impl Expandable for proto::Clamp {
             expansion.computation_graph.insert(id_upper, patch_node);
             expansion.releases.insert(id_upper, release);
             component.insert_argument(&"upper".into(), id_upper);
+            expansion.warnings.push(inferred_argument_warning("upper"));
         }
 
         expansion.computation_graph.insert(component_id, component);
@@ -316,6 +322,24 @@ pub mod test_clamp {
             (analysis, clamped)
         }
 
+        pub fn analysis_f64_cat(value: Value, categories: Value, null_value: Value) -> (Analysis, u32) {
+            let (mut analysis, casted) = test_cast::utilities::analysis_f64(value);
+
+            let categories = analysis.literal()
+                .value(categories).value_public(true)
+                .build();
+            let null_value = analysis.literal()
+                .value(null_value).value_public(true)
+                .build();
+
+            let clamped = analysis.clamp(casted)
+                .categories(categories)
+                .null_value(null_value)
+                .build();
+
+            (analysis, clamped)
+        }
+
         pub fn analysis_string_cat(value: Value, categories: Option<Value>, null_value: Option<Value>) -> (Analysis, u32) {
             let (mut analysis, casted) = test_cast::utilities::analysis_string(value);
 
@@ -372,6 +396,58 @@ pub mod test_clamp {
         array1d_f64_10_uniform; Some(0.0.into()); Some(10.0.into()),
     );
 
+    #[test]
+    fn test_inferred_bounds() {
+        let get_properties = |analysis: &crate::bindings::Analysis| crate::get_properties(
+            Some(analysis.privacy_definition.clone()), analysis.components.clone(), analysis.release.clone(), vec![])
+            .unwrap();
+        let count_inferred = |warnings: &Vec<crate::errors::Error>| warnings.iter()
+            .filter(|warning| warning.to_string().contains("inferred from the properties of the data"))
+            .count();
+
+        // bounds are supplied by the analyst
+        let (mut analysis, clamped) = utilities::analysis_f64_cont(
+            test_data::array1d_f64_10_uniform(), Some(0.0.into()), Some(10.0.into()));
+        assert_eq!(count_inferred(&get_properties(&analysis).1), 0);
+
+        // bounds are taken from the prior clamp
+        let reclamped = analysis.clamp(clamped).build();
+        let (properties, warnings) = get_properties(&analysis);
+        assert_eq!(properties.get(&reclamped).unwrap().array().unwrap().lower_float().unwrap(), vec![0.]);
+        assert_eq!(count_inferred(&warnings), 2);
+    }
+
+    #[test]
+    fn test_f64_categorical() {
+        use crate::base::{Jagged, Nature, Value};
+
+        let (analysis, clamped) = utilities::analysis_f64_cat(
+            test_data::array1d_f64_10_uniform(),
+            Value::Jagged(vec![vec![0.2642, 0.5]].into()),
+            (-1.).into());
+        let properties = analysis.properties(clamped).unwrap();
+        // the null value is added to the categories
+        match properties.array().unwrap().nature.as_ref().unwrap() {
+            Nature::Categorical(nature) => match &nature.categories {
+                Jagged::Float(categories) => assert_eq!(categories, &vec![vec![0.2642, 0.5, -1.]]),
+                _ => panic!("categories must be float")
+            },
+            _ => panic!("nature must be categorical")
+        }
+
+        // NaN may not be a category or null value
+        let (analysis, clamped) = utilities::analysis_f64_cat(
+            test_data::array1d_f64_10_uniform(),
+            Value::Jagged(vec![vec![0.5, f64::NAN]].into()),
+            (-1.).into());
+        assert!(analysis.properties(clamped).is_err());
+        let (analysis, clamped) = utilities::analysis_f64_cat(
+            test_data::array1d_f64_10_uniform(),
+            Value::Jagged(vec![vec![0.5]].into()),
+            f64::NAN.into());
+        assert!(analysis.properties(clamped).is_err());
+    }
+
     macro_rules! test_i64 {
         ( $( $variant:ident; $lower:expr; $upper:expr; $categories:expr; $null_value:expr, )*) => {
             $(
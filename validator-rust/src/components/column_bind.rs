@@ -34,8 +34,9 @@ impl Component for proto::ColumnBind {
                 .ok_or_else(|| Error::from("dataset_id must be known for private datasets"))?)
         };
 
-        if array_props.iter().any(|v| v.aggregator.is_some()) {
-            return Err(Error::from("arguments must not be aggregated"))
+        // binding released aggregates is postprocessing
+        if !releasable {
+            array_props.iter().try_for_each(|v| v.assert_is_not_aggregated())?;
         }
 
         Ok(Warnable::new(ValueProperties::Array(ArrayProperties {
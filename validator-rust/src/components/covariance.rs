@@ -3,7 +3,7 @@ use ndarray::prelude::*;
 
 use crate::{base, Float, proto, Warnable};
 use crate::base::{AggregatorProperties, DataType, IndexKey, Nature, NatureContinuous, NodeProperties, SensitivitySpace, Value, ValueProperties, Vector1DNull};
-use crate::components::{Component, Sensitivity};
+use crate::components::{Component, Named, Sensitivity};
 use crate::errors::*;
 use crate::utilities::prepend;
 
@@ -24,6 +24,7 @@ impl Component for proto::Covariance {
 
             if !data_property.releasable {
                 data_property.assert_is_not_aggregated()?;
+                data_property.assert_bounds_not_degenerate().map_err(prepend("data:"))?;
             }
 
             let num_columns = data_property.num_columns()?;
@@ -84,10 +85,12 @@ impl Component for proto::Covariance {
 
             if !left_property.releasable {
                 left_property.assert_is_not_aggregated()?;
+                left_property.assert_bounds_not_degenerate().map_err(prepend("left:"))?;
             }
 
             if !right_property.releasable {
                 right_property.assert_is_not_aggregated()?;
+                right_property.assert_bounds_not_degenerate().map_err(prepend("right:"))?;
             }
 
             if !left_property.releasable && !right_property.releasable && left_property.group_id != right_property.group_id {
@@ -225,11 +228,16 @@ impl Sensitivity for proto::Covariance {
                 let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
                     .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
 
-                let scaling_constant = match k {
-                    1 | 2 => match neighboring_type {
-                        Neighboring::AddRemove => data_n / (data_n + 1.) / normalization,
-                        Neighboring::Substitute => 2. * (data_n - 1.) / data_n / normalization
-                    },
+                // Sensitivities are computed per entry of the covariance matrix.
+                // Each entry is a scalar, so its L1 and L2 sensitivities coincide.
+                // A joint L2 sensitivity over all entries is formed by the gaussian mechanism, from the per-entry sensitivities.
+                let scaling_constant = match (k, neighboring_type) {
+                    // adding or removing a record changes the sum of cross-deviations by at most n/(n + 1) times the product of ranges
+                    (1, Neighboring::AddRemove) | (2, Neighboring::AddRemove) =>
+                        data_n / (data_n + 1.) / normalization,
+                    // substituting a record changes the sum of cross-deviations by at most 2(n - 1)/n times the product of ranges
+                    (1, Neighboring::Substitute) | (2, Neighboring::Substitute) =>
+                        2. * (data_n - 1.) / data_n / normalization,
                     _ => return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
                 } as Float;
 
@@ -245,4 +253,195 @@ impl Sensitivity for proto::Covariance {
             _ => Err("Covariance sensitivity is only implemented for KNorm".into())
         }
     }
-}
\ No newline at end of file
+}
+
+impl Named for proto::Covariance {
+    fn get_names(
+        &self,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        argument_variables: IndexMap<base::IndexKey, Vec<IndexKey>>,
+        _release: Option<&Value>
+    ) -> Result<Vec<IndexKey>> {
+        let pair = |left: &IndexKey, right: &IndexKey|
+            IndexKey::from(format!("{}:{}", left.to_string(), right.to_string()));
+
+        match (
+            argument_variables.get::<IndexKey>(&"data".into()),
+            argument_variables.get::<IndexKey>(&"left".into()),
+            argument_variables.get::<IndexKey>(&"right".into())) {
+
+            // pairs in the upper triangle of the covariance matrix, in the same order as the sensitivity
+            (Some(names), _, _) => Ok(names.iter().enumerate()
+                .flat_map(|(i, left)| names.iter().skip(i)
+                    .map(move |right| pair(left, right)))
+                .collect()),
+
+            // all pairs of the cross-covariance matrix
+            (None, Some(left_names), Some(right_names)) => Ok(left_names.iter()
+                .flat_map(|left| right_names.iter()
+                    .map(move |right| pair(left, right)))
+                .collect()),
+
+            _ => Err("column names on data, or on left and right, must be known".into())
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test_covariance {
+    use indexmap::IndexMap;
+    use ndarray::Array2;
+
+    use crate::base::{IndexKey, SensitivitySpace, ValueProperties};
+    use crate::bindings::Analysis;
+    use crate::components::{Component, Named, Sensitivity};
+    use crate::proto;
+
+    #[test]
+    fn test_covariance_names() {
+        // each column alternates between zero and a distinct prime, so every pair of ranges has a unique product
+        let ranges = [2., 3., 5.];
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 3), |(i, j)| if i % 2 == 0 { 0. } else { ranges[j] })
+                .into_dyn().into())
+            .value_public(true)
+            .build();
+        let covariance = analysis.covariance().data(data).build();
+
+        let covariance_component = proto::Covariance { finite_sample_correction: true };
+
+        let mut argument_variables = IndexMap::<IndexKey, Vec<IndexKey>>::new();
+        argument_variables.insert("data".into(), vec!["a".into(), "b".into(), "c".into()]);
+        let names = covariance_component.get_names(IndexMap::new(), argument_variables, None).unwrap();
+        assert_eq!(names, ["a:a", "a:b", "a:c", "b:b", "b:c", "c:c"].iter()
+            .map(|name| IndexKey::from(*name)).collect::<Vec<_>>());
+
+        let properties = analysis.properties(covariance).unwrap();
+        let aggregator = properties.array().unwrap().aggregator.clone().unwrap();
+        let sensitivity = covariance_component.compute_sensitivity(
+            &analysis.privacy_definition,
+            &aggregator.properties,
+            &SensitivitySpace::KNorm(1)).unwrap()
+            .array().unwrap().cast_float().unwrap();
+        let sensitivity = sensitivity.iter().collect::<Vec<_>>();
+        assert_eq!(sensitivity.len(), names.len());
+
+        // the sensitivity of each cell is proportional to the product of the ranges of its pair of columns
+        let index = |name: &str| ["a", "b", "c"].iter().position(|v| *v == name).unwrap();
+        let scales = names.iter().zip(sensitivity.iter())
+            .map(|(name, sensitivity)| {
+                let name = name.to_string();
+                let mut pair = name.split(':');
+                let (left, right) = (pair.next().unwrap(), pair.next().unwrap());
+                *sensitivity / (ranges[index(left)] * ranges[index(right)])
+            })
+            .collect::<Vec<_>>();
+        assert!(scales[0] > 0.);
+        assert!(scales.iter().all(|scale| (scale - scales[0]).abs() < 1e-12), "{:?}", scales);
+    }
+
+    #[test]
+    fn test_cross_covariance_names() {
+        let mut argument_variables = IndexMap::<IndexKey, Vec<IndexKey>>::new();
+        argument_variables.insert("left".into(), vec!["a".into(), "b".into()]);
+        argument_variables.insert("right".into(), vec!["c".into(), "d".into(), "e".into()]);
+        let names = proto::Covariance { finite_sample_correction: true }
+            .get_names(IndexMap::new(), argument_variables, None).unwrap();
+        assert_eq!(names, ["a:c", "a:d", "a:e", "b:c", "b:d", "b:e"].iter()
+            .map(|name| IndexKey::from(*name)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_l2_sensitivity() {
+        use crate::proto::privacy_definition::Neighboring;
+
+        // columns are bounded in [0, 2] and [-1, 2]
+        let sensitivity = |neighboring: Neighboring, k: u32| {
+            let mut analysis = Analysis::new();
+            analysis.privacy_definition.neighboring = neighboring as i32;
+            let data = analysis.literal()
+                .value(Array2::from_shape_fn((10, 2), |(i, j)| if i % 2 == 0 { [0., -1.][j] } else { 2. })
+                    .into_dyn().into())
+                .value_public(true)
+                .build();
+            let lower = analysis.literal().value(ndarray::arr1(&[0., -1.]).into_dyn().into()).value_public(true).build();
+            let upper = analysis.literal().value(ndarray::arr1(&[2., 2.]).into_dyn().into()).value_public(true).build();
+            let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+            let covariance = analysis.covariance().data(clamped).build();
+
+            let properties = analysis.properties(covariance).unwrap();
+            let aggregator = properties.array().unwrap().aggregator.clone().unwrap();
+            aggregator.component.compute_sensitivity(
+                &analysis.privacy_definition,
+                &aggregator.properties,
+                &SensitivitySpace::KNorm(k)).unwrap()
+                .array().unwrap().cast_float().unwrap()
+                .iter().copied().collect::<Vec<f64>>()
+        };
+
+        // products of ranges for the entries [0:0, 0:1, 1:1]
+        let products = [4., 6., 9.];
+        let (n, normalization) = (10., 9.);
+
+        for k in &[1, 2] {
+            // n/(n + 1) * (M_i - m_i)(M_j - m_j) / (n - 1)
+            sensitivity(Neighboring::AddRemove, *k).iter().zip(products.iter())
+                .for_each(|(actual, product)|
+                    assert!((actual - n / (n + 1.) * product / normalization).abs() < 1e-12));
+            // 2(n - 1)/n * (M_i - m_i)(M_j - m_j) / (n - 1)
+            sensitivity(Neighboring::Substitute, *k).iter().zip(products.iter())
+                .for_each(|(actual, product)|
+                    assert!((actual - 2. * (n - 1.) / n * product / normalization).abs() < 1e-12));
+        }
+    }
+
+    #[test]
+    fn test_dp_covariance_gaussian() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 2), |(i, j)| (i * j) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let dp_covariance = analysis.dp_covariance(vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 1e-6
+            }))
+        }]).data(clamped).mechanism("Gaussian".to_string()).build();
+
+        assert!(analysis.properties(dp_covariance).unwrap().array().unwrap().releasable);
+    }
+
+    /// Properties of private data in two columns, where the bounds of the second column are a single value.
+    pub fn constant_column_properties() -> ValueProperties {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 2), |(i, j)| if j == 0 { i as f64 } else { 1. }).into_dyn().into())
+            .value_public(true)
+            .build();
+
+        let mut properties = analysis.properties(data).unwrap();
+        if let ValueProperties::Array(properties) = &mut properties {
+            properties.releasable = false;
+        }
+        properties
+    }
+
+    #[test]
+    fn test_constant_column() {
+        let properties = constant_column_properties();
+        let covariance = proto::Covariance { finite_sample_correction: true };
+
+        let error = covariance.propagate_property(
+            &None, IndexMap::new(), indexmap!["data".into() => properties.clone()], 0).unwrap_err();
+        assert!(error.to_string().contains("bounds of column 1 are equal"), "{}", error);
+
+        let error = covariance.propagate_property(
+            &None, IndexMap::new(), indexmap!["left".into() => properties.clone(), "right".into() => properties], 0).unwrap_err();
+        assert!(error.to_string().starts_with("left:"), "{}", error);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::errors::*;
+
+use crate::{proto, base, Warnable};
+use crate::components::Component;
+use crate::base::{IndexKey, Value, NodeProperties, ValueProperties, DataType, Nature, NatureCategorical, NatureContinuous, Jagged, Vector1DNull};
+use crate::utilities::{prepend, standardize_null_target_argument};
+use crate::utilities::datetime::{parse_timezone, parse_datetime, floor_datetime, check_unit};
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+impl Component for proto::ParseDateTime {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        if data_property.data_type != DataType::Str {
+            return Err("data: atomic type must be string".into())
+        }
+
+        let timezone = parse_timezone(&self.timezone)?;
+        let num_columns = data_property.num_columns()?;
+
+        let null = public_arguments.get::<IndexKey>(&"null_value".into())
+            .ok_or_else(|| Error::from("null_value: missing, must be public"))?
+            .ref_array()?.clone().int()
+            .map_err(prepend("null_value:"))?;
+        let null = standardize_null_target_argument(null, num_columns)?;
+
+        // parse categories, if they are known
+        data_property.nature = match data_property.nature.take() {
+            Some(Nature::Categorical(NatureCategorical { categories: Jagged::Str(categories) })) =>
+                Some(Nature::Categorical(NatureCategorical {
+                    categories: Jagged::Int(categories.iter().zip(null)
+                        .map(|(categories, null)| categories.iter()
+                            .map(|category| parse_datetime(category, &self.format, &timezone).unwrap_or(null))
+                            .unique().collect())
+                        .collect())
+                })),
+            _ => None
+        };
+        data_property.data_type = DataType::DateTime;
+        // strings that cannot be parsed are replaced with the null value
+        data_property.nullity = false;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::DateBin {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        if data_property.data_type != DataType::DateTime {
+            return Err("data: atomic type must be a timestamp".into())
+        }
+
+        check_unit(&self.unit)?;
+        let timezone = parse_timezone(&self.timezone)?;
+        let floor = |timestamp| floor_datetime(timestamp, &self.unit, &timezone);
+
+        // flooring is monotonic, so bounds and categories are floored too
+        data_property.nature = match data_property.nature.take() {
+            Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::Int(lower), upper: Vector1DNull::Int(upper)
+            })) => Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::Int(lower.into_iter()
+                    .map(|v| v.map(floor).transpose())
+                    .collect::<Result<_>>()?),
+                upper: Vector1DNull::Int(upper.into_iter()
+                    .map(|v| v.map(floor).transpose())
+                    .collect::<Result<_>>()?),
+            })),
+            Some(Nature::Categorical(NatureCategorical { categories: Jagged::Int(categories) })) =>
+                Some(Nature::Categorical(NatureCategorical {
+                    categories: Jagged::Int(categories.into_iter()
+                        .map(|categories| categories.into_iter()
+                            .map(floor)
+                            .collect::<Result<Vec<_>>>()
+                            .map(|categories| categories.into_iter().unique().collect()))
+                        .collect::<Result<_>>()?)
+                })),
+            _ => None
+        };
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+#[cfg(test)]
+mod test_datetime {
+    use ndarray::{arr1, arr2};
+
+    use crate::base::{DataType, Jagged, Nature};
+    use crate::bindings::Analysis;
+
+    #[test]
+    fn test_parse_and_bin() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[["2020-03-15T13:45:30".to_string()], ["2020-03-15T01:00:00".to_string()], ["unknown".to_string()]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let null_value = analysis.literal()
+            .value(arr1(&[0]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let timestamps = analysis.parse_date_time(data, null_value, "%Y-%m-%dT%H:%M:%S".to_string()).build();
+        let days = analysis.date_bin(timestamps, "day".to_string()).build();
+
+        let properties = analysis.properties(days).unwrap();
+        let properties = properties.array().unwrap();
+        assert_eq!(properties.data_type, DataType::DateTime);
+        assert!(!properties.nullity);
+        // the categories of public data are parsed and binned
+        match properties.nature.as_ref().unwrap() {
+            Nature::Categorical(nature) => match &nature.categories {
+                Jagged::Int(categories) => assert_eq!(categories[0], vec![1584230400, 0]),
+                _ => panic!("categories must be timestamps")
+            },
+            _ => panic!("nature must be categorical")
+        }
+    }
+
+    #[test]
+    fn test_data_types() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[1584230400], [1584279930]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let null_value = analysis.literal()
+            .value(arr1(&[0]).into_dyn().into())
+            .value_public(true)
+            .build();
+        // integers must be parsed into timestamps before binning
+        let days = analysis.date_bin(data, "day".to_string()).build();
+        assert!(analysis.properties(days).is_err());
+        // only strings may be parsed
+        let timestamps = analysis.parse_date_time(data, null_value, "%Y".to_string()).build();
+        assert!(analysis.properties(timestamps).is_err());
+    }
+}
@@ -1,9 +1,9 @@
 use crate::errors::*;
 
-use crate::base::{IndexKey, Nature, NodeProperties, NatureCategorical, Jagged, ValueProperties, DataType, Array};
+use crate::base::{IndexKey, Nature, NodeProperties, NatureCategorical, Jagged, ValueProperties, DataType, Array, OutOfRange};
 
-use crate::{proto, base, Warnable, Integer};
-use crate::utilities::{prepend, standardize_categorical_argument, standardize_null_target_argument, deduplicate, standardize_float_argument, get_literal};
+use crate::{proto, base, Warnable, Integer, Float};
+use crate::utilities::{prepend, standardize_categorical_argument, standardize_null_target_argument, deduplicate, standardize_float_argument, standardize_numeric_argument, get_literal};
 use crate::components::{Component, Expandable};
 
 use crate::base::Value;
@@ -41,52 +41,67 @@ impl Component for proto::Digitize {
             data_property.assert_is_not_aggregated()?;
         }
 
-        public_arguments.remove::<IndexKey>(&"edges".into())
-            .ok_or_else(|| Error::from("edges: missing, must be public"))
-            .and_then(|v| v.clone().jagged())
-            .and_then(|v| match v {
-                Jagged::Float(edges) => {
-                    let null = standardize_null_target_argument(null, num_columns)?;
-                    let edges = standardize_float_argument(edges, num_columns)?;
-                    data_property.nature = Some(Nature::Categorical(NatureCategorical {
-                        categories: Jagged::Int(edges.into_iter().zip(null.into_iter())
-                            .map(|(col, null)| {
-                                // mandate that edges be sorted
-                                if !col.windows(2).all(|w| w[0] <= w[1]) {
-                                    return Err("edges must be sorted".into());
-                                }
-
-                                let mut categories = (0..(col.len() - 1) as Integer).collect::<Vec<Integer>>();
-                                categories.push(null);
-                                Ok(deduplicate(categories))
-                            }).collect::<Result<_>>()?),
-                    }));
-                    Ok(())
-                }
-                Jagged::Int(edges) => {
-                    let null = standardize_null_target_argument(null, num_columns)?;
-                    let edges = standardize_categorical_argument(edges, num_columns)?;
-                    data_property.nature = Some(Nature::Categorical(NatureCategorical {
-                        categories: Jagged::Int(edges.into_iter().zip(null.into_iter())
-                            .map(|(col, null)| {
-                                if !col.windows(2).all(|w| w[0] <= w[1]) {
-                                    return Err("edges must be sorted".into());
-                                }
-
-                                let original_length = col.len();
-                                if deduplicate(col).len() < original_length {
-                                    return Err("edges must not contain duplicates".into())
-                                }
-
-                                let mut categories = (0..(original_length - 1) as Integer).collect::<Vec<Integer>>();
-                                categories.push(null);
-                                Ok(deduplicate(categories))
-                            }).collect::<Result<_>>()?),
-                    }));
-                    Ok(())
+        let out_of_range = self.out_of_range.parse::<OutOfRange>()?;
+
+        let inclusive_left = match public_arguments.get::<IndexKey>(&"inclusive_left".into()) {
+            Some(&v) => v.ref_array()?.clone().bool().map_err(prepend("inclusive_left:"))?,
+            None => arr0(true).into_dyn()
+        };
+        let inclusive_left = standardize_numeric_argument(inclusive_left, num_columns)?.into_raw_vec();
+        let null = standardize_null_target_argument(null, num_columns)?;
+
+        // bounds on the data are used to determine if the data may fall outside of the bin range
+        let lower = data_property.lower_float_option()
+            .unwrap_or_else(|_| vec![None; num_columns as usize]);
+        let upper = data_property.upper_float_option()
+            .unwrap_or_else(|_| vec![None; num_columns as usize]);
+
+        let edges = match public_arguments.remove::<IndexKey>(&"edges".into())
+            .ok_or_else(|| Error::from("edges: missing, must be public"))?.clone().jagged()? {
+            Jagged::Float(edges) => standardize_float_argument(edges, num_columns)?,
+            Jagged::Int(edges) => {
+                let edges = standardize_categorical_argument(edges, num_columns)?;
+                if edges.iter().any(|col| deduplicate(col.clone()).len() < col.len()) {
+                    return Err("edges must not contain duplicates".into())
                 }
-                _ => Err("edges: must be numeric".into())
-            })?;
+                edges.into_iter()
+                    .map(|col| col.into_iter().map(|v| v as Float).collect())
+                    .collect()
+            }
+            _ => return Err("edges: must be numeric".into())
+        };
+
+        data_property.nature = Some(Nature::Categorical(NatureCategorical {
+            categories: Jagged::Int(edges.iter().enumerate()
+                .map(|(column, edges)| {
+                    // mandate that edges be sorted
+                    if !edges.windows(2).all(|w| w[0] <= w[1]) {
+                        return Err("edges must be sorted".into());
+                    }
+                    if out_of_range == OutOfRange::Clamp && edges.len() < 2 {
+                        return Err("edges: at least two edges are necessary to clamp to the end bins".into())
+                    }
+
+                    let mut categories = (0..edges.len().saturating_sub(1) as Integer).collect::<Vec<Integer>>();
+
+                    // NaN is never assigned a bin
+                    let mut may_be_null = data_property.nullity;
+                    if out_of_range == OutOfRange::Null {
+                        may_be_null |= match (edges.first(), edges.last(), lower[column], upper[column]) {
+                            (Some(first), Some(last), Some(lower), Some(upper)) => if inclusive_left[column] {
+                                lower < *first || upper >= *last
+                            } else {
+                                lower <= *first || upper > *last
+                            },
+                            _ => true
+                        };
+                    }
+                    if may_be_null {
+                        categories.push(null[column]);
+                    }
+                    Ok(deduplicate(categories))
+                }).collect::<Result<_>>()?),
+        }));
 
         data_property.data_type = DataType::Int;
         Ok(ValueProperties::Array(data_property).into())
@@ -132,4 +147,54 @@ impl Expandable for proto::Digitize {
 
         Ok(expansion)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_digitize {
+    use ndarray::arr2;
+
+    use crate::base::{Jagged, Nature, Value};
+    use crate::bindings::Analysis;
+
+    /// Digitize data clamped to [lower, upper] into the bins [0, 1), [1, 2), [2, 3).
+    fn categories(lower: f64, upper: f64, inclusive_left: bool, out_of_range: &str) -> crate::errors::Result<Vec<i64>> {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[-10.], [10.]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(lower.into()).value_public(true).build();
+        let upper = analysis.literal().value(upper.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let edges = analysis.literal()
+            .value(Value::Jagged(vec![vec![0., 1., 2., 3.]].into()))
+            .value_public(true)
+            .build();
+        let inclusive_left = analysis.literal().value(inclusive_left.into()).value_public(true).build();
+        let digitized = analysis.digitize(clamped, edges, inclusive_left)
+            .out_of_range(out_of_range.to_string())
+            .build();
+
+        match analysis.properties(digitized)?.array()?.nature.clone().unwrap() {
+            Nature::Categorical(nature) => match nature.categories {
+                Jagged::Int(categories) => Ok(categories[0].clone()),
+                _ => panic!("categories must be integers")
+            },
+            _ => panic!("nature must be categorical")
+        }
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        // the null value is only a category when the data may fall outside of the bins
+        assert_eq!(categories(0., 2.5, true, "null").unwrap(), vec![0, 1, 2]);
+        assert_eq!(categories(0., 3., true, "null").unwrap(), vec![0, 1, 2, -1]);
+        assert_eq!(categories(0.5, 3., false, "null").unwrap(), vec![0, 1, 2]);
+        assert_eq!(categories(0., 3., false, "null").unwrap(), vec![0, 1, 2, -1]);
+        assert_eq!(categories(-1., 4., true, "null").unwrap(), vec![0, 1, 2, -1]);
+
+        // data beyond both ends is assigned to the end bins
+        assert_eq!(categories(-1., 4., true, "clamp").unwrap(), vec![0, 1, 2]);
+        assert!(categories(0., 2.5, true, "overflow").is_err());
+    }
+}
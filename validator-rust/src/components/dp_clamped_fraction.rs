@@ -0,0 +1,195 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto, Float};
+use crate::base::{IndexKey, NodeProperties, Value};
+use crate::components::{Expandable, Report};
+use crate::errors::*;
+use crate::utilities::{array::get_ith_column, prepend};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+use crate::utilities::privacy::spread_privacy_usage;
+
+impl Expandable for proto::DpClampedFraction {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+        let argument_ids = component.arguments();
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if data_property.num_records.is_none() {
+            return Err("data: number of records must be known. Consider resizing".into())
+        }
+        spread_privacy_usage(&self.privacy_usage, data_property.num_columns()? as usize)
+            .map_err(prepend("privacy_usage:"))?;
+
+        let get_argument = |name: &str| argument_ids.get::<IndexKey>(&name.into()).copied()
+            .ok_or_else(|| Error::from(format!("{} must be provided as an argument", name)));
+        let (id_data, id_lower, id_upper) = (get_argument("data")?, get_argument("lower")?, get_argument("upper")?);
+
+        // indicator of whether each value would be modified by clamping
+        let id_below = expansion.insert_component(&mut maximum_id,
+            indexmap!["left".into() => id_data, "right".into() => id_lower],
+            proto::component::Variant::LessThan(proto::LessThan {}), component.submission);
+        let id_above = expansion.insert_component(&mut maximum_id,
+            indexmap!["left".into() => id_data, "right".into() => id_upper],
+            proto::component::Variant::GreaterThan(proto::GreaterThan {}), component.submission);
+        let id_outside = expansion.insert_component(&mut maximum_id,
+            indexmap!["left".into() => id_below, "right".into() => id_above],
+            proto::component::Variant::LogicalOr(proto::Or {}), component.submission);
+        let id_indicator = expansion.insert_component(&mut maximum_id,
+            indexmap!["data".into() => id_outside],
+            proto::component::Variant::ToFloat(proto::ToFloat {}), component.submission);
+
+        // bounds on the indicators are needed for the sensitivity of the mean
+        let id_zero = expansion.insert_literal(&mut maximum_id, (0. as Float).into(), component.submission)?;
+        let id_one = expansion.insert_literal(&mut maximum_id, (1. as Float).into(), component.submission)?;
+        let id_indicator = expansion.insert_component(&mut maximum_id,
+            indexmap!["data".into() => id_indicator, "lower".into() => id_zero, "upper".into() => id_one],
+            proto::component::Variant::Clamp(proto::Clamp {}), component.submission);
+
+        let id_dp_mean = expansion.insert_component(&mut maximum_id,
+            indexmap!["data".into() => id_indicator],
+            proto::component::Variant::DpMean(proto::DpMean {
+                implementation: "resize".to_string(),
+                mechanism: self.mechanism.clone(),
+                privacy_usage: self.privacy_usage.clone(),
+                joint: false,
+                resolution: 0.
+            }), component.submission);
+
+        // noise may push the fraction outside of [0, 1]
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                "data".into() => id_dp_mean,
+                "lower".into() => id_zero,
+                "upper".into() => id_one
+            ])),
+            variant: Some(proto::component::Variant::Clamp(proto::Clamp {})),
+            omit: component.omit,
+            submission: component.submission,
+        });
+        expansion.traversal.push(component_id);
+
+        Ok(expansion)
+    }
+}
+
+impl Report for proto::DpClampedFraction {
+    fn summarize(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        node_id: u32,
+        component: &proto::Component,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get::<base::IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        let num_columns = data_property.num_columns()?;
+        let privacy_usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
+        let release = release.ref_array()?.ref_float()?;
+
+        // the bounds are only reported if they are public
+        let get_bound = |name: &str, column_number: usize| public_arguments.get::<IndexKey>(&name.into())
+            .and_then(|bound| bound.ref_array().ok()?.clone().float().ok())
+            .and_then(|bound| bound.iter().nth(column_number % bound.len().max(1)).copied());
+
+        (0..num_columns as usize).map(|column_number| {
+            let variable_name = variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".into());
+
+            Ok(JSONRelease {
+                description: "DP release information".to_string(),
+                statistic: "DPClampedFraction".to_string(),
+                variables: serde_json::json!(variable_name.to_string()),
+                release_info: value_to_json(&get_ith_column(release, column_number)?.into())?,
+                privacy_loss: privacy_usage_to_json(&privacy_usages[column_number]),
+                accuracy: None,
+                submission: component.submission,
+                node_id,
+                postprocess: false,
+                algorithm_info: AlgorithmInfo {
+                    name: "Mean of clamping indicators".to_string(),
+                    cite: "https://doi.org/10.1007/11681878_14".to_string(),
+                    mechanism: self.mechanism.clone(),
+                    argument: serde_json::json!({
+                        "n": data_property.num_records,
+                        "constraint": {
+                            "lowerbound": get_bound("lower", column_number),
+                            "upperbound": get_bound("upper", column_number)
+                        }
+                    }),
+                },
+                ..Default::default()
+            })
+        }).collect::<Result<Vec<JSONRelease>>>().map(Some)
+    }
+}
+
+#[cfg(test)]
+mod test_dp_clamped_fraction {
+    use ndarray::arr2;
+
+    use crate::bindings::{Analysis, privacy_usage};
+
+    /// private, two-column float data with a known number of records
+    fn resized_data(analysis: &mut Analysis) -> u32 {
+        let data = analysis.literal()
+            .value(arr2(&[[1., -5.], [2., 15.], [3., 7.]]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let data = analysis.to_float(data).build();
+        let number_rows = analysis.literal().value(3.into()).value_public(true).build();
+        let number_columns = analysis.literal().value(2.into()).value_public(true).build();
+        let lower = analysis.literal().value((-100.).into()).value_public(true).build();
+        let upper = analysis.literal().value(100.0.into()).value_public(true).build();
+        analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .lower(lower).upper(upper)
+            .build()
+    }
+
+    #[test]
+    fn test_nature() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = resized_data(&mut analysis);
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let fraction = analysis.dp_clamped_fraction(data, lower, upper, privacy_usage(1., 0.))
+            .mechanism("Laplace".to_string()).build();
+
+        let properties = analysis.properties(fraction).unwrap().array().unwrap().clone();
+        assert_eq!(properties.num_columns, Some(2));
+        assert!(properties.releasable);
+        assert_eq!(properties.lower_float().unwrap(), vec![0., 0.]);
+        assert_eq!(properties.upper_float().unwrap(), vec![1., 1.]);
+    }
+
+    #[test]
+    fn test_unknown_num_records() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[1.], [2.]]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let fraction = analysis.dp_clamped_fraction(data, lower, upper, privacy_usage(1., 0.)).build();
+        assert!(analysis.properties(fraction).is_err());
+    }
+}
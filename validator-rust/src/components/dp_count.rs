@@ -6,6 +6,7 @@ use crate::base::{IndexKey, NodeProperties, Value, ValueProperties};
 use crate::components::{Expandable, Report};
 use crate::errors::*;
 use crate::utilities::{get_literal};
+use crate::utilities::privacy::get_automatic_mechanism;
 use crate::utilities::inference::infer_property;
 use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
 
@@ -24,10 +25,12 @@ impl Expandable for proto::DpCount {
         let privacy_definition = privacy_definition.as_ref()
             .ok_or_else(|| Error::from("privacy_definition must be known"))?;
 
-        let mechanism = if self.mechanism.to_lowercase().as_str() == "automatic" {
-            if privacy_definition.protect_floating_point
-            { "snapping" } else { "laplace" }.to_string()
-        } else { self.mechanism.to_lowercase() };
+        let mechanism = match self.mechanism.to_lowercase().as_str() {
+            "automatic" => get_automatic_mechanism(privacy_definition, &self.privacy_usage),
+            // the geometric mechanism is the discrete analogue of laplace, and the natural choice for counts
+            "geometric" => "simplegeometric".to_string(),
+            mechanism => mechanism.to_string()
+        };
 
         // count
         maximum_id += 1;
@@ -111,15 +114,23 @@ impl Expandable for proto::DpCount {
 
             let variant = Some(match mechanism.as_str() {
                 "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+                    privacy_usage: self.privacy_usage.clone(),
+                    resolution: 0.
+                }),
+                "truncatedlaplace" => proto::component::Variant::TruncatedLaplaceMechanism(proto::TruncatedLaplaceMechanism {
                     privacy_usage: self.privacy_usage.clone()
                 }),
                 "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
                     privacy_usage: self.privacy_usage.clone(),
-                    analytic: false
+                    analytic: false,
+                    joint: false,
+                    resolution: 0.
                 }),
                 "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
                     privacy_usage: self.privacy_usage.clone(),
-                    analytic: true
+                    analytic: true,
+                    joint: false,
+                    resolution: 0.
                 }),
                 "snapping" => {
                     argument_ids.get::<IndexKey>(&"lower".into())
@@ -149,6 +160,7 @@ impl Expandable for proto::DpCount {
 impl Report for proto::DpCount {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -176,6 +188,68 @@ impl Report for proto::DpCount {
                     "distinct": self.distinct
                 }),
             },
+            ..Default::default()
         }]))
     }
 }
+
+#[cfg(test)]
+mod test_dp_count {
+    use ndarray::{arr0, Array2};
+
+    use crate::base::DataType;
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::proto;
+
+    fn dp_count(mechanism: &str, protect_floating_point: bool, usage: Vec<proto::PrivacyUsage>) -> (Analysis, u32) {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = protect_floating_point;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| i as i64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(arr0(0).into_dyn().into()).value_public(true).build();
+        let count = analysis.dp_count(data, lower, usage)
+            .mechanism(mechanism.to_string())
+            .build();
+        (analysis, count)
+    }
+
+    #[test]
+    fn test_mechanisms() {
+        vec![
+            ("Geometric", true, privacy_usage(0.5, 0.)),
+            ("SimpleGeometric", true, privacy_usage(0.5, 0.)),
+            ("Laplace", false, privacy_usage(0.5, 0.)),
+            ("Gaussian", false, privacy_usage(0.5, 1e-6)),
+            ("AnalyticGaussian", false, privacy_usage(0.5, 1e-6)),
+        ].into_iter().for_each(|(mechanism, protect_floating_point, usage)| {
+            let (analysis, count) = dp_count(mechanism, protect_floating_point, usage.clone());
+
+            let properties = analysis.properties(count)
+                .unwrap_or_else(|e| panic!("{}: {:?}", mechanism, e));
+            let properties = properties.array().unwrap();
+            assert!(properties.releasable, "{}", mechanism);
+            assert!(properties.data_type == DataType::Int || properties.data_type == DataType::Float);
+
+            // the usage of the expanded mechanism is exactly the usage requested
+            let total = crate::compute_privacy_usage(
+                analysis.privacy_definition.clone(), analysis.components.clone(), analysis.release.clone()).unwrap();
+            assert_eq!(total, usage[0], "{}", mechanism);
+        })
+    }
+
+    #[test]
+    fn test_invalid_mechanisms() {
+        // gaussian mechanisms are not available under floating-point protections
+        let (analysis, count) = dp_count("Gaussian", true, privacy_usage(0.5, 1e-6));
+        assert!(analysis.properties(count).is_err());
+
+        // the gaussian mechanism requires a nonzero delta
+        let (analysis, count) = dp_count("Gaussian", false, privacy_usage(0.5, 0.));
+        assert!(analysis.properties(count).is_err());
+
+        let (analysis, count) = dp_count("Exponential", false, privacy_usage(0.5, 0.));
+        assert!(analysis.properties(count).is_err());
+    }
+}
@@ -8,6 +8,7 @@ use crate::components::{Expandable, Report};
 use crate::errors::*;
 use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
 use crate::utilities::prepend;
+use crate::utilities::privacy::get_automatic_mechanism;
 
 impl Expandable for proto::DpCovariance {
     fn expand_component(
@@ -24,9 +25,7 @@ impl Expandable for proto::DpCovariance {
         let mechanism = if self.mechanism.to_lowercase().as_str() == "automatic" {
             let privacy_definition = privacy_definition.as_ref()
                 .ok_or_else(|| Error::from("privacy_definition must be known"))?;
-
-            if privacy_definition.protect_floating_point
-            { "snapping" } else { "laplace" }.to_string()
+            get_automatic_mechanism(privacy_definition, &self.privacy_usage)
         } else { self.mechanism.to_lowercase() };
 
         let argument_ids = component.arguments();
@@ -85,15 +84,20 @@ impl Expandable for proto::DpCovariance {
         let mut arguments = indexmap!["data".into() => id_covariance];
         let variant = Some(match mechanism.as_str() {
             "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
-                privacy_usage: self.privacy_usage.clone()
+                privacy_usage: self.privacy_usage.clone(),
+                resolution: 0.
             }),
             "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
                 privacy_usage: self.privacy_usage.clone(),
                 analytic: false,
+                joint: self.joint,
+                resolution: 0.
             }),
             "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
                 privacy_usage: self.privacy_usage.clone(),
                 analytic: true,
+                joint: self.joint,
+                resolution: 0.
             }),
             "snapping" => {
                 argument_ids.get::<IndexKey>(&"lower".into())
@@ -135,6 +139,7 @@ impl Expandable for proto::DpCovariance {
 impl Report for proto::DpCovariance {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -201,7 +206,66 @@ impl Report for proto::DpCovariance {
                 cite: "".to_string(),
                 mechanism: self.mechanism.clone(),
                 argument
-            }
+            },
+            ..Default::default()
         }]))
     }
 }
+
+#[cfg(test)]
+mod test_dp_covariance {
+    use indexmap::IndexMap;
+    use ndarray::Array2;
+
+    use crate::base::IndexKey;
+    use crate::bindings::Analysis;
+    use crate::components::Accuracy;
+    use crate::proto;
+
+    /// Sum of the accuracies of the noise added to each entry of the covariance matrix of six columns
+    fn total_accuracy(mechanism: &str, joint: bool) -> f64 {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((20, 6), |(i, j)| ((i + j) % 10) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        analysis.dp_covariance(vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 0.9, delta: 1e-6
+            }))
+        }]).data(clamped).mechanism(mechanism.to_string()).joint(joint).build();
+
+        let mut graph = analysis.components.clone();
+        let mut release = analysis.release.clone();
+        crate::utilities::propagate_properties(
+            &Some(analysis.privacy_definition.clone()), &mut graph, &mut release, None, false).unwrap();
+
+        graph.values()
+            .filter_map(|component| {
+                let sensitivity = release.get(component.arguments().get::<IndexKey>(&"sensitivity".into())?)?;
+                let mut public_arguments = IndexMap::new();
+                public_arguments.insert(IndexKey::from("sensitivity"), &sensitivity.value);
+                Some(match component.variant.as_ref()? {
+                    proto::component::Variant::LaplaceMechanism(variant) =>
+                        variant.privacy_usage_to_accuracy(public_arguments, 0.05),
+                    proto::component::Variant::GaussianMechanism(variant) =>
+                        variant.privacy_usage_to_accuracy(public_arguments, 0.05),
+                    _ => return None
+                })
+            })
+            .map(|accuracies| accuracies.unwrap().unwrap().iter().map(|accuracy| accuracy.value).sum::<f64>())
+            .sum()
+    }
+
+    #[test]
+    fn test_joint_gaussian() {
+        // the 21 distinct entries share one L2-calibrated gaussian, rather than each using a fraction of the budget
+        let joint = total_accuracy("Gaussian", true);
+        assert!(joint < total_accuracy("Gaussian", false));
+        assert!(joint < total_accuracy("Laplace", false));
+    }
+}
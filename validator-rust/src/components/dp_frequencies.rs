@@ -0,0 +1,231 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto, Float};
+use crate::base::{IndexKey, Value};
+use crate::components::Expandable;
+use crate::components::dp_histogram::DEFAULT_MAX_CATEGORIES;
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+
+impl Expandable for proto::DpFrequencies {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+        let argument_ids = component.arguments();
+
+        let data_id = *argument_ids.get::<IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data is a required argument to DPFrequencies"))?;
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        // the stability histogram is a dataframe, and the snapping mechanism requires bounds on the counts
+        match self.mechanism.to_lowercase().as_str() {
+            "simplegeometric" | "laplace" | "gaussian" | "analyticgaussian" => (),
+            _ => bail!("Unexpected invalid token {:?}", self.mechanism.as_str())
+        }
+
+        // dp histogram
+        maximum_id += 1;
+        let id_dp_histogram = maximum_id;
+        let mut histogram_arguments = indexmap!["data".into() => data_id];
+        vec!["categories", "null_value", "edges", "inclusive_left"].into_iter()
+            .map(|name| name.into())
+            .for_each(|name| {
+                argument_ids.get(&name)
+                    .map(|v| histogram_arguments.insert(name, *v));
+            });
+        expansion.computation_graph.insert(id_dp_histogram, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(histogram_arguments)),
+            variant: Some(proto::component::Variant::DpHistogram(proto::DpHistogram {
+                mechanism: self.mechanism.clone(),
+                privacy_usage: self.privacy_usage.clone(),
+                max_categories: DEFAULT_MAX_CATEGORIES,
+            })),
+            omit: true,
+            submission: component.submission,
+        });
+        expansion.traversal.push(id_dp_histogram);
+
+        // to float
+        maximum_id += 1;
+        let id_histogram = maximum_id;
+        expansion.computation_graph.insert(id_histogram, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(
+                indexmap!["data".into() => id_dp_histogram])),
+            variant: Some(proto::component::Variant::ToFloat(proto::ToFloat {})),
+            omit: true,
+            submission: component.submission,
+        });
+        expansion.traversal.push(id_histogram);
+
+        // count
+        maximum_id += 1;
+        let id_count = maximum_id;
+        match argument_ids.get::<IndexKey>(&"count".into()) {
+            Some(id_count_argument) => {
+                let count_property = properties.get::<IndexKey>(&"count".into())
+                    .ok_or("count: missing")?.array()
+                    .map_err(prepend("count:"))?;
+                // dividing by a private count would not be postprocessing
+                if !count_property.releasable {
+                    return Err("count: must be releasable, either public or differentially private".into())
+                }
+                expansion.computation_graph.insert(id_count, proto::Component {
+                    arguments: Some(proto::ArgumentNodeIds::new(
+                        indexmap!["data".into() => *id_count_argument])),
+                    variant: Some(proto::component::Variant::ToFloat(proto::ToFloat {})),
+                    omit: true,
+                    submission: component.submission,
+                });
+                expansion.traversal.push(id_count);
+            }
+            None => {
+                let num_records = data_property.num_records
+                    .ok_or_else(|| Error::from("count: must be supplied when the number of records is unknown"))?;
+                let (patch_node, count_release) = get_literal((num_records as Float).into(), component.submission)?;
+                expansion.computation_graph.insert(id_count, patch_node);
+                expansion.properties.insert(id_count, infer_property(&count_release.value, None, id_count)?);
+                expansion.releases.insert(id_count, count_release);
+            }
+        }
+
+        // one
+        maximum_id += 1;
+        let id_one = maximum_id;
+        let (patch_node, one_release) = get_literal((1. as Float).into(), component.submission)?;
+        expansion.computation_graph.insert(id_one, patch_node);
+        expansion.properties.insert(id_one, infer_property(&one_release.value, None, id_one)?);
+        expansion.releases.insert(id_one, one_release);
+
+        // a noisy count may be near zero, so the denominator is kept at least one
+        maximum_id += 1;
+        let id_denominator = maximum_id;
+        expansion.computation_graph.insert(id_denominator, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(
+                indexmap!["left".into() => id_count, "right".into() => id_one])),
+            variant: Some(proto::component::Variant::RowMax(proto::RowMax {})),
+            omit: true,
+            submission: component.submission,
+        });
+        expansion.traversal.push(id_denominator);
+
+        // normalize
+        maximum_id += 1;
+        let id_frequencies = maximum_id;
+        expansion.computation_graph.insert(id_frequencies, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(
+                indexmap!["left".into() => id_histogram, "right".into() => id_denominator])),
+            variant: Some(proto::component::Variant::Divide(proto::Divide {})),
+            omit: true,
+            submission: component.submission,
+        });
+        expansion.traversal.push(id_frequencies);
+
+        if self.project {
+            expansion.computation_graph.insert(component_id, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(
+                    indexmap!["data".into() => id_frequencies])),
+                variant: Some(proto::component::Variant::SimplexProjection(proto::SimplexProjection {})),
+                omit: component.omit,
+                submission: component.submission,
+            });
+            return Ok(expansion)
+        }
+
+        // noisy frequencies may fall outside of [0, 1]
+        let mut clamp_arguments = indexmap!["data".into() => id_frequencies];
+        for (name, bound) in [("lower", 0.), ("upper", 1.)] {
+            maximum_id += 1;
+            let id_bound = maximum_id;
+            let (patch_node, bound_release) = get_literal((bound as Float).into(), component.submission)?;
+            expansion.computation_graph.insert(id_bound, patch_node);
+            expansion.properties.insert(id_bound, infer_property(&bound_release.value, None, id_bound)?);
+            expansion.releases.insert(id_bound, bound_release);
+            clamp_arguments.insert(name.into(), id_bound);
+        }
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(clamp_arguments)),
+            variant: Some(proto::component::Variant::Clamp(proto::Clamp {})),
+            omit: component.omit,
+            submission: component.submission,
+        });
+
+        Ok(expansion)
+    }
+}
+
+#[cfg(test)]
+mod test_dp_frequencies {
+    use ndarray::Array2;
+
+    use crate::base::{Jagged, Value};
+    use crate::bindings::{Analysis, privacy_usage};
+
+    #[test]
+    fn test_dp_frequencies() {
+        for project in &[false, true] {
+            let mut analysis = Analysis::new();
+            let data = analysis.literal()
+                .value(Array2::from_shape_fn((20, 1), |(i, _)| i as f64 * 0.5).into_dyn().into())
+                .value_public(true)
+                .build();
+            let edges = analysis.literal()
+                .value(Value::Jagged(Jagged::Float(vec![vec![0., 2.5, 5., 7.5, 10.]])))
+                .value_public(true)
+                .build();
+            let dp_frequencies = analysis.dp_frequencies(data, privacy_usage(1., 0.))
+                .edges(edges)
+                .project(*project)
+                .build();
+
+            let properties = analysis.properties(dp_frequencies).unwrap();
+            let properties = properties.array().unwrap();
+            assert!(properties.releasable);
+            assert_eq!(properties.lower_float().unwrap(), vec![0.]);
+            assert_eq!(properties.upper_float().unwrap(), vec![1.]);
+
+            // normalizing is postprocessing, so only the histogram uses privacy
+            let usage = crate::compute_privacy_usage(
+                analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+            assert_eq!(usage, privacy_usage(1., 0.)[0]);
+        }
+    }
+
+    #[test]
+    fn test_private_count() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((20, 1), |(i, _)| i as f64 * 0.5).into_dyn().into())
+            .value_public(true)
+            .build();
+        let edges = analysis.literal()
+            .value(Value::Jagged(Jagged::Float(vec![vec![0., 2.5, 5., 7.5, 10.]])))
+            .value_public(true)
+            .build();
+
+        // the count must be releasable, or the histogram would be normalized by a private value
+        let count = analysis.literal().value(20.into()).value_public(false).build();
+        let dp_frequencies = analysis.dp_frequencies(data, privacy_usage(1., 0.))
+            .edges(edges).count(count)
+            .build();
+        let error = analysis.properties(dp_frequencies).unwrap_err();
+        assert!(error.to_string().contains("count: must be releasable"), "{}", error);
+
+        let lower = analysis.literal().value(0.into()).value_public(true).build();
+        let dp_count = analysis.dp_count(data, lower, privacy_usage(1., 0.)).build();
+        let dp_frequencies = analysis.dp_frequencies(data, privacy_usage(1., 0.))
+            .edges(edges).count(dp_count)
+            .build();
+        assert!(analysis.properties(dp_frequencies).unwrap().array().unwrap().releasable);
+    }
+}
@@ -2,7 +2,7 @@ use indexmap::map::IndexMap;
 
 use crate::{base, proto, Warnable};
 use crate::base::{Array, ArrayProperties, DataType, IndexKey, NodeProperties, Value, ValueProperties};
-use crate::components::{Component, Expandable, Report};
+use crate::components::{Component, Expandable, Mechanism, Report};
 use crate::errors::*;
 use crate::utilities::{array::get_ith_column, prepend, privacy::spread_privacy_usage};
 use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
@@ -89,9 +89,30 @@ impl Expandable for proto::DpGumbelMedian {
     }
 }
 
+impl Mechanism for proto::DpGumbelMedian {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        Some(release_usage.unwrap_or(&self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
 impl Report for proto::DpGumbelMedian {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -140,6 +161,7 @@ impl Report for proto::DpGumbelMedian {
                         }
                     }),
                 },
+                ..Default::default()
             });
         }
         Ok(Some(releases))
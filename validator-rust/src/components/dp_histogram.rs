@@ -2,13 +2,16 @@ use indexmap::map::IndexMap;
 use ndarray::arr0;
 
 use crate::{base, Integer, proto};
-use crate::base::{IndexKey, NodeProperties, Value};
+use crate::base::{ArrayProperties, DataType, IndexKey, NodeProperties, Value};
 use crate::components::{Expandable, Report};
 use crate::errors::*;
 use crate::utilities::{array::get_ith_column, get_literal, prepend, privacy::spread_privacy_usage};
 use crate::utilities::inference::infer_property;
 use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
 
+/// The default cap on the number of categories inferred from the bounds of integer data.
+pub(crate) const DEFAULT_MAX_CATEGORIES: u32 = 1000;
+
 impl Expandable for proto::DpHistogram {
     fn expand_component(
         &self,
@@ -33,6 +36,27 @@ impl Expandable for proto::DpHistogram {
         let privacy_definition = privacy_definition.as_ref()
             .ok_or_else(|| Error::from("privacy_definition must be known"))?;
 
+        // usages are either spread evenly, or allocated to each column
+        spread_privacy_usage(&self.privacy_usage, data_property.num_columns()? as usize)
+            .map_err(prepend("privacy_usage:"))?;
+
+        // the stability mechanism counts categories as they are observed, so no histogram is computed
+        if self.mechanism.to_lowercase() == "stability" {
+            if argument_ids.contains_key::<IndexKey>(&"categories".into()) || argument_ids.contains_key::<IndexKey>(&"edges".into()) {
+                return Err("categories and edges may not be supplied to the stability mechanism, which discovers the categories".into())
+            }
+
+            expansion.computation_graph.insert(component_id, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => data_id])),
+                variant: Some(proto::component::Variant::StabilityMechanism(proto::StabilityMechanism {
+                    privacy_usage: self.privacy_usage.clone()
+                })),
+                omit: component.omit,
+                submission: component.submission,
+            });
+            return Ok(expansion)
+        }
+
         // histogram
         maximum_id += 1;
         let id_histogram = maximum_id;
@@ -44,6 +68,19 @@ impl Expandable for proto::DpHistogram {
                     .map(|v| histogram_arguments.insert(name, *v));
             });
 
+        // integer data with known bounds is histogrammed over every integer in its range
+        if let Some(categories) = self.infer_integer_categories(&argument_ids, data_property)? {
+            let null_value = arr0(categories[0]).into_dyn().into();
+            for (name, value) in [("categories", Value::Jagged(vec![categories].into())), ("null_value", null_value)] {
+                maximum_id += 1;
+                let (patch_node, release) = get_literal(value, component.submission)?;
+                expansion.computation_graph.insert(maximum_id, patch_node);
+                expansion.properties.insert(maximum_id, infer_property(&release.value, None, maximum_id)?);
+                expansion.releases.insert(maximum_id, release);
+                histogram_arguments.insert(name.into(), maximum_id);
+            }
+        }
+
         expansion.computation_graph.insert(id_histogram, proto::Component {
             arguments: Some(proto::ArgumentNodeIds::new(histogram_arguments)),
             variant: Some(proto::component::Variant::Histogram(proto::Histogram {})),
@@ -107,15 +144,20 @@ impl Expandable for proto::DpHistogram {
             let mut arguments = indexmap!["data".into() => id_histogram];
             let variant = Some(match self.mechanism.to_lowercase().as_str() {
                 "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
-                    privacy_usage: self.privacy_usage.clone()
+                    privacy_usage: self.privacy_usage.clone(),
+                    resolution: 0.
                 }),
                 "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
                     privacy_usage: self.privacy_usage.clone(),
-                    analytic: false
+                    analytic: false,
+                    joint: false,
+                    resolution: 0.
                 }),
                 "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
                     privacy_usage: self.privacy_usage.clone(),
-                    analytic: true
+                    analytic: true,
+                    joint: false,
+                    resolution: 0.
                 }),
                 "snapping" => {
                     argument_ids.get::<IndexKey>(&"lower".into())
@@ -141,9 +183,45 @@ impl Expandable for proto::DpHistogram {
     }
 }
 
+impl proto::DpHistogram {
+    /// Enumerate the integers between the bounds of the data, if the categories must be inferred from them.
+    ///
+    /// The data already lies within its bounds, so the null value (the lower bound) is never substituted,
+    /// and the histogram has exactly one cell for each integer.
+    fn infer_integer_categories(
+        &self, argument_ids: &IndexMap<IndexKey, u32>, data_property: &ArrayProperties,
+    ) -> Result<Option<Vec<Integer>>> {
+        if argument_ids.contains_key::<IndexKey>(&"categories".into())
+            || argument_ids.contains_key::<IndexKey>(&"edges".into())
+            || data_property.data_type != DataType::Int
+            || data_property.categories().is_ok() {
+            return Ok(None)
+        }
+        let (lower, upper) = match (data_property.lower_int(), data_property.upper_int()) {
+            (Ok(lower), Ok(upper)) => (lower, upper),
+            // without bounds, the histogram reports that categories or edges must be supplied
+            _ => return Ok(None)
+        };
+        if lower.len() != 1 {
+            return Err("data must contain one column".into())
+        }
+        let (lower, upper) = (lower[0], upper[0]);
+
+        let num_categories = upper.checked_sub(lower).and_then(|range| range.checked_add(1))
+            .ok_or_else(|| Error::from("data: the range of the data overflows"))?;
+        if num_categories > self.max_categories as Integer {
+            return Err(format!(
+                "data: the range [{}, {}] spans {} integers, which exceeds max_categories ({}). Supply categories or edges, or increase max_categories",
+                lower, upper, num_categories, self.max_categories).into())
+        }
+        Ok(Some((lower..=upper).collect()))
+    }
+}
+
 impl Report for proto::DpHistogram {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -161,6 +239,35 @@ impl Report for proto::DpHistogram {
         let variable_names = variable_names.cloned()
             .unwrap_or_else(|| (0..num_columns).map(|_| "[Unknown]".into()).collect());
 
+        // the stability mechanism releases the categories it observed alongside their counts
+        if let Value::Dataframe(release) = release {
+            let get_column = |name: &str| release.get::<IndexKey>(&name.into())
+                .ok_or_else(|| Error::from(format!("{}: missing from release", name)))
+                .and_then(value_to_json);
+
+            return Ok(Some(vec![JSONRelease {
+                description: "DP release information".to_string(),
+                statistic: "DPHistogram".to_string(),
+                variables: serde_json::json!(variable_names.first().map(|name| name.to_string())),
+                release_info: serde_json::json!({
+                    "categories": get_column("categories")?,
+                    "counts": get_column("counts")?
+                }),
+                privacy_loss: privacy_usage_to_json(&privacy_usages[0]),
+                accuracy: None,
+                submission: component.submission,
+                node_id,
+                postprocess: false,
+                algorithm_info: AlgorithmInfo {
+                    name: "Stability-based histogram".to_string(),
+                    cite: "https://privacytools.seas.harvard.edu/files/privacytools/files/complexityprivacy_1.pdf".to_string(),
+                    mechanism: self.mechanism.clone(),
+                    argument: serde_json::json!({}),
+                },
+                ..Default::default()
+            }]))
+        }
+
         let release = release.ref_array()?.ref_int()?;
 
         Ok(Some(privacy_usages.into_iter()
@@ -186,6 +293,7 @@ impl Report for proto::DpHistogram {
                         mechanism: self.mechanism.clone(),
                         argument: serde_json::json!({}),
                     },
+                    ..Default::default()
                 }))
             .collect::<Result<Vec<JSONRelease>>>()?))
     }
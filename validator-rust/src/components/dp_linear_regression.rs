@@ -93,7 +93,7 @@ impl Expandable for proto::DpLinearRegression {
         let id_slope_index = maximum_id;
         expansion.computation_graph.insert(id_slope_index, proto::Component {
             arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => id_theil_sen, "names".into() => id_slope_name])),
-            variant: Some(proto::component::Variant::Index(proto::Index {})),
+            variant: Some(proto::component::Variant::Index(proto::Index { as_dataframe: false })),
             omit: true,
             submission: component.submission
         });
@@ -132,7 +132,7 @@ impl Expandable for proto::DpLinearRegression {
         let id_intercept_index = maximum_id;
         expansion.computation_graph.insert(id_intercept_index, proto::Component {
             arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => id_theil_sen, "names".into() => id_intercept_name])),
-            variant: Some(proto::component::Variant::Index(proto::Index {})),
+            variant: Some(proto::component::Variant::Index(proto::Index { as_dataframe: false })),
             omit: true,
             submission: component.submission
         });
@@ -177,6 +177,7 @@ impl Expandable for proto::DpLinearRegression {
 impl Report for proto::DpLinearRegression {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -206,6 +207,7 @@ impl Report for proto::DpLinearRegression {
                     }
                 }),
             },
+            ..Default::default()
         };
         Ok(Some(vec![release]))
     }
@@ -0,0 +1,196 @@
+use indexmap::map::IndexMap;
+use ndarray::arr1;
+
+use crate::{base, proto};
+use crate::base::{IndexKey, Jagged, NodeProperties, Value};
+use crate::components::{Expandable, Report};
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Expandable for proto::DpMaxFrequency {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let id_data = *component.arguments().get::<IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain one column".into())
+        }
+        let categories = data_property.categories()
+            .map_err(|_| Error::from("data: must be categorical. Use a clamp to restrict the data categories"))?;
+
+        if self.privacy_usage.len() != 1 {
+            return Err("privacy_usage: must be of length one".into())
+        }
+        // selecting the category and counting it compose sequentially
+        let privacy_usage = vec![(self.privacy_usage[0].clone() / 2.)?];
+
+        // the count of each category is its utility
+        let id_histogram = expansion.insert_component(&mut maximum_id,
+            indexmap!["data".into() => id_data],
+            proto::component::Variant::Histogram(proto::Histogram {}), component.submission);
+
+        maximum_id += 1;
+        let id_candidates = maximum_id;
+        let candidates = match categories {
+            Jagged::Int(jagged) => arr1(&jagged[0]).into_dyn().into(),
+            Jagged::Float(jagged) => arr1(&jagged[0]).into_dyn().into(),
+            Jagged::Bool(jagged) => arr1(&jagged[0]).into_dyn().into(),
+            Jagged::Str(jagged) => arr1(&jagged[0]).into_dyn().into(),
+        };
+        let (patch_node, candidates_release) = get_literal(candidates, component.submission)?;
+        expansion.computation_graph.insert(id_candidates, patch_node);
+        expansion.properties.insert(id_candidates, infer_property(&candidates_release.value, None, id_candidates)?);
+        expansion.releases.insert(id_candidates, candidates_release);
+
+        let id_mode = expansion.insert_component(&mut maximum_id,
+            indexmap!["utilities".into() => id_histogram, "candidates".into() => id_candidates],
+            proto::component::Variant::ExponentialMechanism(proto::ExponentialMechanism {
+                privacy_usage: privacy_usage.clone()
+            }), component.submission);
+
+        // the records in the selected category
+        let id_mask = expansion.insert_component(&mut maximum_id,
+            indexmap!["left".into() => id_data, "right".into() => id_mode],
+            proto::component::Variant::Equal(proto::Equal {}), component.submission);
+        let id_filtered = expansion.insert_component(&mut maximum_id,
+            indexmap!["data".into() => id_data, "mask".into() => id_mask],
+            proto::component::Variant::Filter(proto::Filter {}), component.submission);
+
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => id_filtered])),
+            variant: Some(proto::component::Variant::DpCount(proto::DpCount {
+                distinct: false,
+                mechanism: self.mechanism.clone(),
+                privacy_usage,
+            })),
+            omit: component.omit,
+            submission: component.submission,
+        });
+        expansion.traversal.push(component_id);
+
+        Ok(expansion)
+    }
+}
+
+impl Report for proto::DpMaxFrequency {
+    fn summarize(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        _properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let variable_name = variable_names
+            .and_then(|names| names.first()).cloned()
+            .unwrap_or_else(|| "[Unknown]".into());
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPMaxFrequency".to_string(),
+            variables: serde_json::json!(variable_name.to_string()),
+            release_info: value_to_json(release)?,
+            privacy_loss: privacy_usage_to_json(&self.privacy_usage[0]),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({}),
+            },
+            ..Default::default()
+        }]))
+    }
+}
+
+#[cfg(test)]
+mod test_dp_max_frequency {
+    use ndarray::arr1;
+
+    use crate::base::Value;
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::proto;
+    use crate::utilities::privacy::get_epsilon;
+
+    /// private string data, resized and clamped to the categories `a`, `b` and `c`
+    fn categorical_data(analysis: &mut Analysis) -> u32 {
+        let data = analysis.literal()
+            .value(arr1(&["a", "b", "a"]).mapv(|v| v.to_string()).into_dyn().into())
+            .value_public(false)
+            .build();
+        let data = analysis.to_string(data).build();
+        let number_rows = analysis.literal().value(3.into()).value_public(true).build();
+        let number_columns = analysis.literal().value(1.into()).value_public(true).build();
+        let categories = analysis.literal()
+            .value(Value::Jagged(vec![vec!["a".to_string(), "b".to_string(), "c".to_string()]].into()))
+            .value_public(true)
+            .build();
+        let data = analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .categories(categories)
+            .build();
+        let null_value = analysis.literal().value("a".to_string().into()).value_public(true).build();
+        analysis.clamp(data).categories(categories).null_value(null_value).build()
+    }
+
+    #[test]
+    fn test_privacy_usage() {
+        let mut analysis = Analysis::new();
+        let data = categorical_data(&mut analysis);
+        let dp_max_frequency = analysis.dp_max_frequency(data, privacy_usage(1., 0.)).build();
+
+        let mut graph = analysis.components.clone();
+        let mut release = analysis.release.clone();
+        let properties = crate::utilities::propagate_properties(
+            &Some(analysis.privacy_definition.clone()), &mut graph, &mut release, None, false).unwrap().0;
+        assert!(properties.get(&dp_max_frequency).unwrap().array().unwrap().releasable);
+
+        // the selection and the count each use half of the budget
+        let usages = graph.values()
+            .filter_map(|component| match &component.variant {
+                Some(proto::component::Variant::ExponentialMechanism(mechanism)) => Some(mechanism.privacy_usage.clone()),
+                Some(proto::component::Variant::SimpleGeometricMechanism(mechanism)) => Some(mechanism.privacy_usage.clone()),
+                _ => None
+            })
+            .map(|usage| get_epsilon(&usage[0]).unwrap())
+            .collect::<Vec<f64>>();
+        assert_eq!(usages, vec![0.5, 0.5]);
+
+        let usage = crate::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        assert_eq!(get_epsilon(&usage).unwrap(), 1.);
+    }
+
+    #[test]
+    fn test_not_categorical() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr1(&[1., 2., 3.]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let data = analysis.to_float(data).build();
+        let dp_max_frequency = analysis.dp_max_frequency(data, privacy_usage(1., 0.)).build();
+        assert!(analysis.properties(dp_max_frequency).is_err());
+    }
+}
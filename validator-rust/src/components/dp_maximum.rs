@@ -25,7 +25,7 @@ impl Expandable for proto::DpMaximum {
             arguments: component.arguments.clone(),
             variant: Some(proto::component::Variant::DpQuantile(proto::DpQuantile {
                 alpha: 1.,
-                interpolation: "upper".to_string(),
+                interpolation: "higher".to_string(),
                 mechanism: self.mechanism.clone(),
                 privacy_usage: self.privacy_usage.clone()
             })),
@@ -41,6 +41,7 @@ impl Expandable for proto::DpMaximum {
 impl Report for proto::DpMaximum {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -90,6 +91,7 @@ impl Report for proto::DpMaximum {
                         }
                     }),
                 },
+                ..Default::default()
             });
         }
         Ok(Some(releases))
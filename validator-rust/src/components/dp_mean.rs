@@ -1,11 +1,13 @@
 use indexmap::map::IndexMap;
+use itertools::Itertools;
+use ndarray::{arr1, Axis};
 
-use crate::{base, proto};
-use crate::base::{IndexKey, NodeProperties, Value};
-use crate::components::{Expandable, Report};
+use crate::{base, Float, Integer, proto};
+use crate::base::{Array, ArrayProperties, IndexKey, NodeProperties, Value};
+use crate::components::{Accuracy, Expandable, Report};
 use crate::errors::*;
-use crate::utilities::{array::get_ith_column, prepend, privacy::spread_privacy_usage, get_literal};
-use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+use crate::utilities::{array::get_ith_column, prepend, privacy::{get_automatic_mechanism, spread_privacy_usage}, get_literal, get_mechanism_variant, get_explicit_mechanism};
+use crate::utilities::json::{Accuracy as JSONAccuracy, AlgorithmInfo, DEFAULT_ALPHA, JSONRelease, privacy_usage_to_json, value_to_json};
 use crate::utilities::inference::infer_property;
 
 impl Expandable for proto::DpMean {
@@ -29,19 +31,42 @@ impl Expandable for proto::DpMean {
         let mut expansion = base::ComponentExpansion::default();
         let argument_ids = component.arguments();
 
+        let num_columns = properties.get::<base::IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.num_columns()?;
+        // usages are either spread evenly, or allocated to each column
+        spread_privacy_usage(&self.privacy_usage, num_columns as usize)
+            .map_err(prepend("privacy_usage:"))?;
+
         let mechanism = if self.mechanism.to_lowercase().as_str() == "automatic" {
             let privacy_definition = privacy_definition.as_ref()
                 .ok_or_else(|| Error::from("privacy_definition must be known"))?;
-            if privacy_definition.protect_floating_point
-            { "snapping" } else { "laplace" }.to_string()
+            get_automatic_mechanism(privacy_definition, &self.privacy_usage)
         } else { self.mechanism.to_lowercase() };
 
+        if self.resolution < 0. || !self.resolution.is_finite() {
+            return Err("resolution: must be non-negative and finite".into())
+        }
+        // when rounding, the noised mean is an intermediate node, and the rounded mean takes the component id
+        let id_noised = if self.resolution > 0. {
+            maximum_id += 1;
+            maximum_id
+        } else { component_id };
+        let omit_noised = self.resolution > 0. || component.omit;
+
         if self.implementation.to_lowercase() == "plug-in" {
 
             let data_property = properties.get::<base::IndexKey>(&"data".into())
                 .ok_or("data: missing")?.array()
                 .map_err(prepend("data:"))?;
-            let num_columns = data_property.num_columns()? as f64;
+            let num_columns = num_columns as f64;
+
+            // the count is shared by every column, so it is released with a share of the total usage.
+            // A joint sum is a single release, so the count and sum split the usage evenly
+            let count_share = if self.joint { 0.5 } else { num_columns / (num_columns + 1.) };
+            let total_usage = self.privacy_usage.iter().cloned().map(Ok)
+                .fold1(|l, r| l? + r?)
+                .ok_or_else(|| Error::from("privacy_usage: must be defined"))??;
 
             let id_data = *argument_ids.get::<base::IndexKey>(&"data".into())
                 .ok_or_else(|| Error::from("data must be provided as an argument"))?;
@@ -55,9 +80,7 @@ impl Expandable for proto::DpMean {
                 variant: Some(proto::component::Variant::DpCount(proto::DpCount {
                     distinct: false,
                     mechanism: "SimpleGeometric".to_string(),
-                    privacy_usage: self.privacy_usage.iter().cloned()
-                        .map(|v| v * (num_columns / (num_columns + 1.)))
-                        .collect::<Result<Vec<proto::PrivacyUsage>>>()?
+                    privacy_usage: vec![(total_usage * count_share)?]
                 })),
                 omit: true,
                 submission: component.submission,
@@ -154,8 +177,9 @@ impl Expandable for proto::DpMean {
                 variant: Some(proto::component::Variant::DpSum(proto::DpSum {
                     mechanism: self.mechanism.clone(),
                     privacy_usage: self.privacy_usage.iter().cloned()
-                        .map(|v| v / (num_columns + 1.))
-                        .collect::<Result<Vec<proto::PrivacyUsage>>>()?
+                        .map(|v| v * (1. - count_share))
+                        .collect::<Result<Vec<proto::PrivacyUsage>>>()?,
+                    joint: self.joint
                 })),
                 omit: true,
                 submission: component.submission,
@@ -163,17 +187,15 @@ impl Expandable for proto::DpMean {
             expansion.traversal.push(id_dp_sum);
 
             // divide
-            expansion.computation_graph.insert(component_id, proto::Component {
+            expansion.computation_graph.insert(id_noised, proto::Component {
                 arguments: Some(proto::ArgumentNodeIds::new(indexmap![
                     "left".into() => id_dp_sum,
                     "right".into() => id_dp_count
                 ])),
                 variant: Some(proto::component::Variant::Divide(proto::Divide {})),
-                omit: component.omit,
+                omit: omit_noised,
                 submission: component.submission,
             });
-
-            Ok(expansion)
         }
 
         else if self.implementation.to_lowercase() == "resize" {
@@ -192,44 +214,140 @@ impl Expandable for proto::DpMean {
 
             // noising
             let mut arguments = indexmap!["data".into() => id_mean];
-            let variant = Some(match mechanism.as_str() {
-                "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
-                    privacy_usage: self.privacy_usage.clone()
-                }),
-                "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
-                    privacy_usage: self.privacy_usage.clone(),
-                    analytic: false
-                }),
-                "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
-                    privacy_usage: self.privacy_usage.clone(),
-                    analytic: true
-                }),
-                "snapping" => {
-                    argument_ids.get::<IndexKey>(&"lower".into())
-                        .map(|lower| arguments.insert("lower".into(), *lower));
-                    argument_ids.get::<IndexKey>(&"upper".into())
-                        .map(|upper| arguments.insert("upper".into(), *upper));
-
-                    proto::component::Variant::SnappingMechanism(proto::SnappingMechanism {
-                        privacy_usage: self.privacy_usage.clone()
-                    })
-                },
-                _ => bail!("Unexpected invalid token {:?}", self.mechanism.as_str())
-            });
+            if mechanism.as_str() == "snapping" {
+                argument_ids.get::<IndexKey>(&"lower".into())
+                    .map(|lower| arguments.insert("lower".into(), *lower));
+                argument_ids.get::<IndexKey>(&"upper".into())
+                    .map(|upper| arguments.insert("upper".into(), *upper));
+            }
 
-            expansion.computation_graph.insert(component_id, proto::Component {
+            expansion.computation_graph.insert(id_noised, proto::Component {
                 arguments: Some(proto::ArgumentNodeIds::new(arguments)),
-                variant,
-                omit: component.omit,
+                variant: Some(get_mechanism_variant(&mechanism, &self.privacy_usage, self.joint)?),
+                omit: omit_noised,
                 submission: component.submission,
             });
-
-            Ok(expansion)
         }
 
         else {
             bail!("`{}` is not recognized as a valid implementation. Must be one of [`resize`, `plug-in`]", self.implementation)
         }
+
+        if self.resolution > 0. {
+            expansion.traversal.push(id_noised);
+
+            let data_property = properties.get::<base::IndexKey>(&"data".into())
+                .ok_or("data: missing")?.array()
+                .map_err(prepend("data:"))?;
+            self.expand_rounding(
+                &mut expansion, component, data_property,
+                id_noised, component_id, maximum_id)?;
+        }
+
+        Ok(expansion)
+    }
+}
+
+impl proto::DpMean {
+    /// The mechanism of the resize implementation, if it does not depend on the privacy definition.
+    ///
+    /// The plug-in implementation privatizes a sum and a count, so its accuracy is not that of a single mechanism.
+    fn explicit_mechanism(&self) -> Result<Option<proto::Component>> {
+        if self.implementation.to_lowercase() != "resize" {
+            return Ok(None)
+        }
+        get_explicit_mechanism(&self.mechanism, &self.privacy_usage, self.joint)
+    }
+
+    /// Round the noised mean at `id_noised` to the nearest multiple of the resolution.
+    ///
+    /// The rounded mean is inserted at `component_id`.
+    /// The mean is scaled to units of the resolution, rounded, and scaled back.
+    /// A whole-number resolution rounds by casting to an integer, so that the released mean is integer-typed.
+    fn expand_rounding(
+        &self,
+        expansion: &mut base::ComponentExpansion,
+        component: &proto::Component,
+        data_property: &ArrayProperties,
+        id_noised: u32,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<()> {
+        // mean in units of the resolution
+        let id_resolution = expansion.insert_literal(&mut maximum_id, Value::from(self.resolution), component.submission)?;
+        let id_scaled = expansion.insert_component(&mut maximum_id,
+            indexmap!["left".into() => id_noised, "right".into() => id_resolution],
+            proto::component::Variant::Divide(proto::Divide {}), component.submission);
+
+        let (id_rounded, id_resolution) = if self.resolution.fract() == 0. {
+            // the cast only imputes nulls, so its bounds are those of the scaled statistic
+            let lower = data_property.lower_float()?.into_iter().fold(Float::INFINITY, Float::min);
+            let upper = data_property.upper_float()?.into_iter().fold(Float::NEG_INFINITY, Float::max);
+            let id_lower = expansion.insert_literal(&mut maximum_id, Value::from((lower / self.resolution).floor() as Integer), component.submission)?;
+            let id_upper = expansion.insert_literal(&mut maximum_id, Value::from((upper / self.resolution).ceil() as Integer), component.submission)?;
+
+            let id_rounded = expansion.insert_component(&mut maximum_id,
+                indexmap!["data".into() => id_scaled, "lower".into() => id_lower, "upper".into() => id_upper],
+                proto::component::Variant::Cast(proto::Cast { atomic_type: "int".to_string() }), component.submission);
+            (id_rounded, expansion.insert_literal(&mut maximum_id, Value::from(self.resolution as Integer), component.submission)?)
+        } else {
+            let id_rounded = expansion.insert_component(&mut maximum_id,
+                indexmap!["data".into() => id_scaled],
+                proto::component::Variant::Round(proto::Round {}), component.submission);
+            (id_rounded, id_resolution)
+        };
+
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                "left".into() => id_rounded, "right".into() => id_resolution])),
+            variant: Some(proto::component::Variant::Multiply(proto::Multiply {})),
+            omit: component.omit,
+            submission: component.submission,
+        });
+
+        Ok(())
+    }
+}
+
+/// The accuracy of the mean is the accuracy of the mechanism, widened by the rounding error.
+///
+/// Rounding moves the release by at most half of the resolution, in either direction.
+impl Accuracy for proto::DpMean {
+    fn accuracy_to_privacy_usage(
+        &self,
+        accuracies: &proto::Accuracies,
+        public_arguments: IndexMap<base::IndexKey, &Value>
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let mechanism = match self.explicit_mechanism()? {
+            Some(mechanism) => mechanism,
+            None => return Ok(None)
+        };
+        let rounding_error = self.resolution / 2.;
+        let accuracies = proto::Accuracies {
+            values: accuracies.values.iter()
+                .map(|accuracy| if accuracy.value > rounding_error {
+                    Ok(proto::Accuracy { value: accuracy.value - rounding_error, alpha: accuracy.alpha })
+                } else {
+                    Err(Error::from(format!("accuracy: must be greater than the rounding error, {}", rounding_error)))
+                })
+                .collect::<Result<Vec<proto::Accuracy>>>()?
+        };
+        mechanism.accuracy_to_privacy_usage(&accuracies, public_arguments)
+    }
+
+    fn privacy_usage_to_accuracy(
+        &self,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        alpha: f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+        let mechanism = match self.explicit_mechanism()? {
+            Some(mechanism) => mechanism,
+            None => return Ok(None)
+        };
+        Ok(mechanism.privacy_usage_to_accuracy(public_arguments, alpha)?
+            .map(|accuracies| accuracies.into_iter()
+                .map(|accuracy| proto::Accuracy { value: accuracy.value + self.resolution / 2., alpha: accuracy.alpha })
+                .collect()))
     }
 }
 
@@ -237,6 +355,7 @@ impl Report for proto::DpMean {
     /// summarize results
     /// # Arguments
     /// * `&self` - this
+    /// * `privacy_definition` - the definition of privacy under which the computation takes place
     /// * `node_id` - identifier for node
     /// * `component` - component from prototypes/components.proto
     /// * `public_arguments` - HashMap of String, Value public arguments
@@ -244,9 +363,10 @@ impl Report for proto::DpMean {
     /// * `release` - JSONRelease containing DP release information
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
-        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
         release: &Value,
         variable_names: Option<&Vec<base::IndexKey>>,
@@ -265,7 +385,14 @@ impl Report for proto::DpMean {
         let num_columns = data_property.num_columns()?;
         let privacy_usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
 
-        let release = release.ref_array()?.ref_float()?;
+        let sensitivity: Value = arr1(&lower.iter().zip(upper.iter())
+            .map(|(lower, upper)| (upper - lower) / num_records as Float * data_property.c_stability as Float)
+            .collect::<Vec<Float>>()).insert_axis(Axis(0)).into_dyn().into();
+        let mut accuracy_arguments = public_arguments.clone();
+        accuracy_arguments.insert("sensitivity".into(), &sensitivity);
+        // the snapping mechanism also needs bounds on the statistic, so its accuracy is omitted without them
+        let accuracies = self.privacy_usage_to_accuracy(accuracy_arguments, DEFAULT_ALPHA)
+            .ok().flatten();
 
         for column_number in 0..(num_columns as usize) {
             let variable_name = variable_names
@@ -276,12 +403,16 @@ impl Report for proto::DpMean {
                 description: "DP release information".to_string(),
                 statistic: "DPMean".to_string(),
                 variables: serde_json::json!(variable_name.to_string()),
-                release_info: value_to_json(&get_ith_column(
-                    release,
-                    column_number as usize
-                )?.into())?,
+                release_info: match release.ref_array()? {
+                    Array::Float(v) => value_to_json(&get_ith_column(v, column_number)?.into())?,
+                    Array::Int(v) => value_to_json(&get_ith_column(v, column_number)?.into())?,
+                    _ => return Err("mean must be numeric".into())
+                },
                 privacy_loss: privacy_usage_to_json(&privacy_usages[column_number].clone()),
-                accuracy: None,
+                accuracy: accuracies.as_ref().map(|accuracies| JSONAccuracy {
+                    accuracy_value: accuracies[column_number].value,
+                    alpha: accuracies[column_number].alpha,
+                }),
                 submission: component.submission,
                 node_id,
                 postprocess: false,
@@ -298,9 +429,186 @@ impl Report for proto::DpMean {
                             "upperbound": upper[column_number]
                         }
                     })
-                }
+                },
+                ..Default::default()
             });
         }
         Ok(Some(releases))
     }
 }
+
+#[cfg(test)]
+mod test_dp_mean {
+    use indexmap::IndexMap;
+    use ndarray::{arr1, arr2, Array2};
+
+    use crate::base::{DataType, IndexKey, Value};
+    use crate::bindings::Analysis;
+    use crate::components::Accuracy;
+    use crate::proto;
+    use crate::utilities::privacy::get_epsilon;
+
+    /// A dp mean over two columns with shared bounds, with one usage for each epsilon
+    fn dp_mean(epsilons: &[f64], implementation: &str, resolution: f64) -> (Analysis, u32) {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 2), |(i, j)| (i + j) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(arr1(&[0., 0.]).into_dyn().into()).value_public(true).build();
+        let upper = analysis.literal().value(arr1(&[12., 12.]).into_dyn().into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let usages = epsilons.iter().map(|epsilon| proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: *epsilon, delta: 0.
+            }))
+        }).collect();
+        let dp_mean = analysis.dp_mean(clamped, usages)
+            .mechanism("Laplace".to_string())
+            .implementation(implementation.to_string())
+            .resolution(resolution)
+            .build();
+        (analysis, dp_mean)
+    }
+
+    #[test]
+    fn test_column_allocation() {
+        vec!["resize", "plug-in"].into_iter().for_each(|implementation| {
+            let (analysis, _) = dp_mean(&[0.2, 0.8], implementation, 0.);
+            // the usages allocated to each column sum to the total
+            let usage = crate::compute_privacy_usage(
+                analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+            assert!((get_epsilon(&usage).unwrap() - 1.).abs() < 1e-12, "{}", implementation);
+        });
+
+        // the column with four times the epsilon has a quarter of the noise
+        let (analysis, dp_mean) = dp_mean(&[0.2, 0.8], "resize", 0.);
+        let accuracies = crate::analysis_accuracies(
+            analysis.privacy_definition, analysis.components, analysis.release, 0.05).unwrap();
+        let accuracies = &accuracies.values.get(&dp_mean).unwrap().accuracies.as_ref().unwrap().values;
+        assert!((accuracies[0].value / accuracies[1].value - 4.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_plug_in_joint() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((100, 3), |(i, j)| ((i % 10) + j) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(12.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 1e-6
+            }))
+        }];
+        analysis.dp_mean(clamped, usage)
+            .mechanism("Gaussian".to_string())
+            .implementation("plug-in".to_string())
+            .joint(true)
+            .build();
+
+        let mut graph = analysis.components.clone();
+        let mut release = analysis.release.clone();
+        crate::utilities::propagate_properties(
+            &Some(analysis.privacy_definition.clone()), &mut graph, &mut release, None, false).unwrap();
+
+        // the count and the joint sum are two releases, so each spends half of the usage
+        let epsilons = graph.values().filter_map(|component| match component.variant.as_ref().unwrap() {
+            proto::component::Variant::SimpleGeometricMechanism(variant) => Some(&variant.privacy_usage),
+            proto::component::Variant::GaussianMechanism(variant) => Some(&variant.privacy_usage),
+            _ => None
+        }).flatten().map(|usage| get_epsilon(usage).unwrap()).collect::<Vec<f64>>();
+        assert_eq!(epsilons.len(), 2);
+        epsilons.iter().for_each(|epsilon| assert!((epsilon - 0.5).abs() < 1e-12, "{}", epsilon));
+    }
+
+    #[test]
+    fn test_column_allocation_length() {
+        let (analysis, dp_mean) = dp_mean(&[0.2, 0.3, 0.5], "resize", 0.);
+        let error = analysis.properties(dp_mean).unwrap_err();
+        assert!(error.to_string().contains("privacy_usage:"), "{}", error);
+    }
+
+    #[test]
+    fn test_rounding() {
+        vec![(0.5, DataType::Float), (2., DataType::Int)].into_iter().for_each(|(resolution, data_type)| {
+            let (analysis, dp_mean) = dp_mean(&[0.2, 0.8], "resize", resolution);
+
+            let mut graph = analysis.components.clone();
+            let mut release = analysis.release.clone();
+            let properties = crate::utilities::propagate_properties(
+                &Some(analysis.privacy_definition.clone()), &mut graph, &mut release, None, false).unwrap().0;
+            let property = properties.get(&dp_mean).unwrap().array().unwrap();
+            assert!(property.releasable);
+            assert_eq!(property.data_type, data_type);
+
+            // the rounding is applied to the output of the mechanism
+            let mut id_node = dp_mean;
+            let mut variant = graph.get(&id_node).unwrap().variant.clone().unwrap();
+            while !matches!(variant, proto::component::Variant::LaplaceMechanism(_)) {
+                id_node = *graph.get(&id_node).unwrap().arguments().values().next().unwrap();
+                variant = graph.get(&id_node).unwrap().variant.clone().unwrap();
+            }
+            assert_ne!(id_node, dp_mean);
+
+            // the calibration of the mechanism is unchanged
+            if let proto::component::Variant::LaplaceMechanism(mechanism) = variant {
+                let epsilons = mechanism.privacy_usage.iter()
+                    .map(|usage| get_epsilon(usage).unwrap()).collect::<Vec<f64>>();
+                assert_eq!(epsilons, vec![0.2, 0.8]);
+            }
+            let usage = crate::compute_privacy_usage(
+                analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+            assert!((get_epsilon(&usage).unwrap() - 1.).abs() < 1e-12);
+        });
+
+        let (analysis, dp_mean) = dp_mean(&[1.], "resize", -1.);
+        assert!(analysis.properties(dp_mean).is_err());
+    }
+
+    #[test]
+    fn test_rounding_accuracy() {
+        let make = |resolution: f64| proto::DpMean {
+            implementation: "resize".to_string(),
+            mechanism: "Laplace".to_string(),
+            privacy_usage: vec![proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                    epsilon: 1., delta: 0.
+                }))
+            }],
+            joint: false,
+            resolution,
+        };
+        let sensitivity: Value = arr2(&[[0.1]]).into_dyn().into();
+        let arguments = || {
+            let mut arguments = IndexMap::<IndexKey, &Value>::new();
+            arguments.insert("sensitivity".into(), &sensitivity);
+            arguments
+        };
+
+        // rounding widens the accuracy by half of the resolution
+        let exact = make(0.).privacy_usage_to_accuracy(arguments(), 0.05).unwrap().unwrap();
+        let rounded = make(0.5).privacy_usage_to_accuracy(arguments(), 0.05).unwrap().unwrap();
+        assert!((rounded[0].value - exact[0].value - 0.25).abs() < 1e-12);
+
+        // and the same accuracy is reached under the same privacy usage
+        let usage = make(0.5).accuracy_to_privacy_usage(
+            &proto::Accuracies { values: rounded }, arguments()).unwrap().unwrap();
+        assert!((get_epsilon(&usage[0]).unwrap() - 1.).abs() < 1e-9);
+
+        // an accuracy finer than the rounding cannot be reached
+        assert!(make(0.5).accuracy_to_privacy_usage(&proto::Accuracies {
+            values: vec![proto::Accuracy { value: 0.2, alpha: 0.05 }]
+        }, arguments()).is_err());
+
+        // the plug-in implementation does not have a single mechanism
+        let mut plug_in = make(0.5);
+        plug_in.implementation = "plug-in".to_string();
+        assert!(plug_in.privacy_usage_to_accuracy(arguments(), 0.05).unwrap().is_none());
+    }
+}
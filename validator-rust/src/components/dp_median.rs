@@ -6,14 +6,14 @@ use crate::components::{Expandable, Report};
 
 use crate::base::{NodeProperties, Value, Array, IndexKey};
 use crate::utilities::json::{JSONRelease, value_to_json, privacy_usage_to_json, AlgorithmInfo};
-use crate::utilities::{prepend, privacy::spread_privacy_usage, array::get_ith_column};
+use crate::utilities::{prepend, privacy::{prefers_gaussian, spread_privacy_usage}, array::get_ith_column};
 use indexmap::map::IndexMap;
 
 
 impl Expandable for proto::DpMedian {
     fn expand_component(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         component: &proto::Component,
         _public_arguments: &IndexMap<IndexKey, &Value>,
         properties: &base::NodeProperties,
@@ -23,8 +23,11 @@ impl Expandable for proto::DpMedian {
         let mut expansion = base::ComponentExpansion::default();
 
         let mechanism = if self.mechanism.to_lowercase().as_str() == "automatic" {
-            if properties.contains_key::<IndexKey>(&"candidates".into())
-            { "exponential" } else { "laplace" }.to_string()
+            if properties.contains_key::<IndexKey>(&"candidates".into()) { "exponential" }
+            else if privacy_definition.as_ref()
+                .map(|privacy_definition| prefers_gaussian(privacy_definition, &self.privacy_usage))
+                .unwrap_or(false) { "gaussian" }
+            else { "laplace" }.to_string()
         } else {
             self.mechanism.to_lowercase()
         };
@@ -58,6 +61,7 @@ impl Expandable for proto::DpMedian {
 impl Report for proto::DpMedian {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -107,8 +111,66 @@ impl Report for proto::DpMedian {
                         }
                     }),
                 },
+                ..Default::default()
             });
         }
         Ok(Some(releases))
     }
 }
+
+#[cfg(test)]
+mod test_dp_median {
+    use ndarray::{arr1, Array2};
+
+    use crate::bindings::Analysis;
+    use crate::proto;
+
+    #[test]
+    fn test_dp_median() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| (i % 10) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(arr1(&[0.]).into_dyn().into()).value_public(true).build();
+        let upper = analysis.literal().value(arr1(&[10.]).into_dyn().into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+
+        // the median expands into a mechanism at the same node id,
+        //     which must not be expanded again once it has been passed its sensitivity
+        let dp_median = analysis.dp_median(clamped, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }]).mechanism("Laplace".to_string()).build();
+
+        let properties = analysis.properties(dp_median).unwrap();
+        assert!(properties.array().unwrap().releasable);
+    }
+
+    #[test]
+    fn test_dp_median_gumbel() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| (i % 10) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(arr1(&[0.]).into_dyn().into()).value_public(true).build();
+        let upper = analysis.literal().value(arr1(&[10.]).into_dyn().into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let dp_median = analysis.dp_median(clamped, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }]).mechanism("Gumbel".to_string()).build();
+
+        let properties = analysis.properties(dp_median).unwrap();
+        assert!(properties.array().unwrap().releasable);
+
+        // the gumbel median is a mechanism, so its usage is counted
+        let usage = crate::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        assert!((crate::utilities::privacy::get_epsilon(&usage).unwrap() - 1.).abs() < 1e-12);
+    }
+}
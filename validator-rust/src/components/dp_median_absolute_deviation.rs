@@ -0,0 +1,203 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto, Float, Integer};
+use crate::base::{DataType, IndexKey, Value};
+use crate::components::Expandable;
+use crate::errors::*;
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::privacy::prefers_gaussian;
+use crate::utilities::inference::infer_property;
+
+impl Expandable for proto::DpMedianAbsoluteDeviation {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+        let argument_ids = component.arguments();
+
+        let id_data = *argument_ids.get::<IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        // absolute deviations from any median are at most the range of the data
+        let range: Value = match data_property.data_type {
+            DataType::Float => ndarray::Array::from(data_property.lower_float()?.iter()
+                .zip(data_property.upper_float()?.iter())
+                .map(|(lower, upper)| upper - lower)
+                .collect::<Vec<_>>()).into_dyn().into(),
+            DataType::Int => ndarray::Array::from(data_property.lower_int()?.iter()
+                .zip(data_property.upper_int()?.iter())
+                .map(|(lower, upper)| upper.checked_sub(*lower)
+                    .ok_or_else(|| Error::from("data: the range of the data overflows")))
+                .collect::<Result<Vec<_>>>()?).into_dyn().into(),
+            _ => return Err("data: atomic type must be numeric".into())
+        };
+        let zero: Value = match data_property.data_type {
+            DataType::Float => ndarray::ArrayD::<Float>::zeros(range.ref_array()?.shape()).into(),
+            _ => ndarray::ArrayD::<Integer>::zeros(range.ref_array()?.shape()).into(),
+        };
+
+        let mechanism = if self.mechanism.to_lowercase().as_str() == "automatic" {
+            if argument_ids.contains_key::<IndexKey>(&"candidates".into()) { "exponential" }
+            else if privacy_definition.as_ref()
+                .map(|privacy_definition| prefers_gaussian(privacy_definition, &self.privacy_usage))
+                .unwrap_or(false) { "gaussian" }
+            else { "laplace" }.to_string()
+        } else {
+            self.mechanism.to_lowercase()
+        };
+        if mechanism == "exponential" && !argument_ids.contains_key::<IndexKey>(&"deviation_candidates".into()) {
+            return Err("deviation_candidates: must be supplied for the Exponential mechanism".into())
+        }
+
+        // the budget is split evenly between the two medians
+        let privacy_usage = self.privacy_usage.iter().cloned()
+            .map(|v| v * 0.5)
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()?;
+
+        // dp median
+        maximum_id += 1;
+        let id_median = maximum_id;
+        let mut median_arguments = indexmap!["data".into() => id_data];
+        if let Some(id_candidates) = argument_ids.get::<IndexKey>(&"candidates".into()) {
+            median_arguments.insert("candidates".into(), *id_candidates);
+        }
+        expansion.computation_graph.insert(id_median, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(median_arguments)),
+            variant: Some(proto::component::Variant::DpMedian(proto::DpMedian {
+                mechanism: mechanism.clone(),
+                privacy_usage: privacy_usage.clone(),
+                interpolation: self.interpolation.clone(),
+            })),
+            omit: true,
+            submission: component.submission,
+        });
+        expansion.traversal.push(id_median);
+
+        // center the data on the released median
+        maximum_id += 1;
+        let id_centered = maximum_id;
+        expansion.computation_graph.insert(id_centered, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                "left".into() => id_data, "right".into() => id_median])),
+            variant: Some(proto::component::Variant::Subtract(proto::Subtract {})),
+            omit: true,
+            submission: component.submission,
+        });
+        expansion.traversal.push(id_centered);
+
+        // absolute deviations
+        maximum_id += 1;
+        let id_deviations = maximum_id;
+        expansion.computation_graph.insert(id_deviations, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => id_centered])),
+            variant: Some(proto::component::Variant::Abs(proto::Abs {})),
+            omit: true,
+            submission: component.submission,
+        });
+        expansion.traversal.push(id_deviations);
+
+        // bound the deviations by [0, range]
+        maximum_id += 1;
+        let id_lower = maximum_id;
+        let (patch_node, release) = get_literal(zero, component.submission)?;
+        expansion.computation_graph.insert(id_lower, patch_node);
+        expansion.properties.insert(id_lower, infer_property(&release.value, None, id_lower)?);
+        expansion.releases.insert(id_lower, release);
+
+        maximum_id += 1;
+        let id_upper = maximum_id;
+        let (patch_node, release) = get_literal(range, component.submission)?;
+        expansion.computation_graph.insert(id_upper, patch_node);
+        expansion.properties.insert(id_upper, infer_property(&release.value, None, id_upper)?);
+        expansion.releases.insert(id_upper, release);
+
+        maximum_id += 1;
+        let id_clamped = maximum_id;
+        expansion.computation_graph.insert(id_clamped, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                "data".into() => id_deviations,
+                "lower".into() => id_lower,
+                "upper".into() => id_upper
+            ])),
+            variant: Some(proto::component::Variant::Clamp(proto::Clamp {})),
+            omit: true,
+            submission: component.submission,
+        });
+        expansion.traversal.push(id_clamped);
+
+        // dp median of the absolute deviations
+        let mut deviation_arguments = indexmap!["data".into() => id_clamped];
+        if let Some(id_candidates) = argument_ids.get::<IndexKey>(&"deviation_candidates".into()) {
+            deviation_arguments.insert("candidates".into(), *id_candidates);
+        }
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(deviation_arguments)),
+            variant: Some(proto::component::Variant::DpMedian(proto::DpMedian {
+                mechanism,
+                privacy_usage,
+                interpolation: self.interpolation.clone(),
+            })),
+            omit: component.omit,
+            submission: component.submission,
+        });
+        expansion.traversal.push(component_id);
+
+        Ok(expansion)
+    }
+}
+
+#[cfg(test)]
+mod test_dp_median_absolute_deviation {
+    use ndarray::{arr1, Array2};
+
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::proto;
+
+    #[test]
+    fn test_dp_median_absolute_deviation() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| (i % 10) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(arr1(&[-5.]).into_dyn().into()).value_public(true).build();
+        let upper = analysis.literal().value(arr1(&[15.]).into_dyn().into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+
+        let dp_mad = analysis.dp_median_absolute_deviation(clamped, privacy_usage(1., 0.))
+            .mechanism("Laplace".to_string())
+            .build();
+
+        // deviations are non-negative, and no larger than the range of the data
+        let properties = analysis.properties(dp_mad).unwrap();
+        let properties = properties.array().unwrap();
+        assert!(properties.releasable);
+        assert!(properties.lower_float().unwrap()[0] >= 0.);
+        assert!(properties.upper_float().unwrap()[0] <= 20.);
+
+        // the budget is the sum of the usages of the two medians
+        let usage = crate::compute_privacy_usage(
+            analysis.privacy_definition.clone(), analysis.components.clone(), analysis.release.clone()).unwrap();
+        let epsilon = match usage.distance.unwrap() {
+            proto::privacy_usage::Distance::Approximate(approximate) => approximate.epsilon
+        };
+        assert!((epsilon - 1.).abs() < 1e-12);
+
+        // the exponential mechanism must be given candidates for the deviations
+        let candidates = analysis.literal().value(arr1(&[0., 5., 10.]).into_dyn().into()).value_public(true).build();
+        let dp_mad = analysis.dp_median_absolute_deviation(clamped, privacy_usage(1., 0.))
+            .candidates(candidates)
+            .build();
+        assert!(analysis.properties(dp_mad).is_err());
+    }
+}
@@ -44,6 +44,7 @@ impl Expandable for proto::DpMinimum {
 impl Report for proto::DpMinimum {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -91,7 +92,8 @@ impl Report for proto::DpMinimum {
                             "upperbound": upper[column_number]
                         }
                     }),
-                }
+                },
+                ..Default::default()
             });
         }
         Ok(Some(releases))
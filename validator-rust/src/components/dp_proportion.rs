@@ -0,0 +1,400 @@
+use indexmap::map::IndexMap;
+use statrs::function::erf;
+
+use crate::{base, proto, Float, Integer};
+use crate::base::{Array, DataType, IndexKey, NodeProperties, Value};
+use crate::components::{Expandable, Report};
+use crate::errors::*;
+use crate::utilities::prepend;
+use crate::utilities::json::{AlgorithmInfo, DEFAULT_ALPHA, Interval, JSONRelease, privacy_usage_to_json, value_to_json};
+use crate::utilities::privacy::{get_epsilon, spread_privacy_usage};
+
+impl Expandable for proto::DpProportion {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if data_property.data_type != DataType::Bool {
+            return Err("data: atomic type must be boolean".into())
+        }
+        if data_property.num_columns()? != 1 {
+            return Err("data: must be a single column".into())
+        }
+        let mechanism = get_mechanism(&self.mechanism)?;
+        let privacy_usage = spread_privacy_usage(&self.privacy_usage, 1)
+            .map_err(prepend("privacy_usage:"))?.remove(0);
+
+        let argument_ids = component.arguments();
+        let id_data = *argument_ids.get::<IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+
+        // count of successes
+        let id_successes = expansion.insert_component(&mut maximum_id,
+            indexmap!["data".into() => id_data, "mask".into() => id_data],
+            proto::component::Variant::Filter(proto::Filter {}), component.submission);
+        let mut count_arguments = indexmap!["data".into() => id_successes];
+        if let Some(num_records) = data_property.num_records {
+            count_arguments.insert("upper".into(), expansion.insert_literal(&mut maximum_id, (num_records as Integer).into(), component.submission)?);
+        }
+        let id_dp_count = expansion.insert_component(&mut maximum_id, count_arguments,
+            proto::component::Variant::DpCount(proto::DpCount {
+                distinct: false,
+                mechanism: mechanism.to_string(),
+                privacy_usage: vec![privacy_usage],
+            }), component.submission);
+        let id_numerator = expansion.insert_component(&mut maximum_id, indexmap!["data".into() => id_dp_count],
+            proto::component::Variant::ToFloat(proto::ToFloat {}), component.submission);
+
+        // count of records
+        let id_denominator = match argument_ids.get::<IndexKey>(&"count".into()) {
+            Some(id_count) => {
+                let count_property = properties.get::<IndexKey>(&"count".into())
+                    .ok_or("count: missing")?.array()
+                    .map_err(prepend("count:"))?;
+                // dividing by a private count would not be postprocessing
+                if !count_property.releasable {
+                    return Err("count: must be releasable, either public or differentially private".into())
+                }
+                expansion.insert_component(&mut maximum_id, indexmap!["data".into() => *id_count],
+                    proto::component::Variant::ToFloat(proto::ToFloat {}), component.submission)
+            }
+            None => {
+                let num_records = data_property.num_records
+                    .ok_or_else(|| Error::from("count: must be supplied when the number of records is unknown"))?;
+                expansion.insert_literal(&mut maximum_id, (num_records as Float).into(), component.submission)?
+            }
+        };
+
+        // a noisy count of records may be near zero, so the denominator is kept at least one
+        let id_one = expansion.insert_literal(&mut maximum_id, (1. as Float).into(), component.submission)?;
+        let id_denominator = expansion.insert_component(&mut maximum_id,
+            indexmap!["left".into() => id_denominator, "right".into() => id_one],
+            proto::component::Variant::RowMax(proto::RowMax {}), component.submission);
+
+        let id_ratio = expansion.insert_component(&mut maximum_id,
+            indexmap!["left".into() => id_numerator, "right".into() => id_denominator],
+            proto::component::Variant::Divide(proto::Divide {}), component.submission);
+
+        // noise may push the ratio outside of [0, 1]
+        let id_zero = expansion.insert_literal(&mut maximum_id, (0. as Float).into(), component.submission)?;
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                "data".into() => id_ratio,
+                "lower".into() => id_zero,
+                "upper".into() => id_one
+            ])),
+            variant: Some(proto::component::Variant::Clamp(proto::Clamp {})),
+            omit: component.omit,
+            submission: component.submission,
+        });
+        expansion.traversal.push(component_id);
+
+        Ok(expansion)
+    }
+}
+
+impl Report for proto::DpProportion {
+    /// The summary includes a Wilson-style interval over the public or differentially private count of records.
+    fn summarize(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        node_id: u32,
+        component: &proto::Component,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy_definition must be defined")?;
+        let data_property = properties.get::<base::IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        let privacy_usage = spread_privacy_usage(&self.privacy_usage, 1)?.remove(0);
+        let proportion = release.ref_array()?.first_float()?;
+
+        // the released count of records, if supplied, otherwise the number of records
+        let num_records = match public_arguments.get::<base::IndexKey>(&"count".into()) {
+            Some(count) => Some(Array::Float(count.ref_array()?.clone().cast_float()?).first_float()?),
+            None => data_property.num_records.map(|num_records| num_records as Float)
+        };
+
+        let interval = match num_records {
+            Some(num_records) => {
+                // the count of successes is noised with the usage available to a single record
+                let effective_usage = privacy_usage.actual_to_effective(
+                    data_property.sample_proportion.unwrap_or(1.),
+                    data_property.c_stability,
+                    privacy_definition.group_size)?;
+                let variance = count_noise_variance(get_mechanism(&self.mechanism)?, get_epsilon(&effective_usage)?);
+                let (lower, upper) = wilson_interval(proportion, num_records, variance, DEFAULT_ALPHA)?;
+                Some(Interval {
+                    lower: serde_json::json!(lower),
+                    upper: serde_json::json!(upper),
+                    alpha: DEFAULT_ALPHA,
+                })
+            }
+            None => None
+        };
+
+        let variable_name = variable_names
+            .and_then(|names| names.first()).cloned()
+            .unwrap_or_else(|| "[Unknown]".into());
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPProportion".to_string(),
+            variables: serde_json::json!(variable_name.to_string()),
+            release_info: value_to_json(release)?,
+            privacy_loss: privacy_usage_to_json(&privacy_usage),
+            accuracy: None,
+            interval,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "Wilson score interval".to_string(),
+                cite: "https://doi.org/10.1080/01621459.1927.10502953".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({
+                    "n": num_records
+                }),
+            },
+        }]))
+    }
+}
+
+fn get_mechanism(mechanism: &str) -> Result<&'static str> {
+    match mechanism.to_lowercase().as_str() {
+        "simplegeometric" | "geometric" => Ok("SimpleGeometric"),
+        "laplace" => Ok("Laplace"),
+        _ => Err(format!("mechanism: `{}` is not recognized. Must be one of [`SimpleGeometric`, `Laplace`]", mechanism).into())
+    }
+}
+
+/// Variance of the noise added to a count with sensitivity one.
+fn count_noise_variance(mechanism: &str, epsilon: Float) -> Float {
+    match mechanism {
+        // two-sided geometric distribution with parameter e^-epsilon
+        "SimpleGeometric" => {
+            let alpha = (-epsilon).exp();
+            2. * alpha / (1. - alpha).powi(2)
+        }
+        _ => 2. / epsilon.powi(2)
+    }
+}
+
+/// Wilson-style confidence interval for a proportion that has been released with noise.
+///
+/// The Wilson score interval is the set of proportions `p` that are within `z` standard deviations of the release,
+/// where `z` is the two-sided normal quantile at `alpha`.
+/// The standard deviation accounts for binomial sampling, as well as the noise added to the count of successes:
+///
+/// `(p̂ - p)^2 <= z^2 (p (1 - p) / n + v_s / n^2)`
+///
+/// A differentially private count of records is taken as released.
+/// Unlike an interval that is symmetric about the release, the interval is always within [0, 1].
+/// As the count of records approaches zero, the interval widens to [0, 1].
+///
+/// # Arguments
+/// * `proportion` - released proportion
+/// * `num_records` - number of records the proportion is over, or its released estimate
+/// * `variance` - variance of the noise added to the count of successes
+/// * `alpha` - the interval contains the actual proportion with confidence 100(1 - alpha)%
+///
+/// # Return
+/// Lower and upper bounds of the interval
+pub fn wilson_interval(
+    proportion: Float, num_records: Float, variance: Float, alpha: Float,
+) -> Result<(Float, Float)> {
+    if !(0. < alpha && alpha < 1.) {
+        return Err("alpha: must be within (0, 1)".into())
+    }
+    if variance < 0. {
+        return Err("variance: must be non-negative".into())
+    }
+    if proportion.is_nan() || num_records.is_nan() {
+        return Err("proportion and num_records must not be NaN".into())
+    }
+    if num_records <= 0. {
+        return Ok((0., 1.))
+    }
+    let proportion = proportion.clamp(0., 1.);
+    // squared two-sided normal quantile
+    let z2 = 2. * erf::erf_inv(1. - alpha).powi(2);
+    let n = num_records;
+
+    // coefficients of the quadratic in p, whose roots are the bounds of the interval
+    let a = 1. + z2 / n;
+    let b = -(2. * proportion + z2 / n);
+    let c = proportion.powi(2) - z2 * variance / n.powi(2);
+
+    // the discriminant is non-negative for proportions in [0, 1]
+    let discriminant = b.powi(2) - 4. * a * c;
+    let lower = (-b - discriminant.sqrt()) / (2. * a);
+    let upper = (-b + discriminant.sqrt()) / (2. * a);
+    Ok((lower.clamp(0., 1.), upper.clamp(0., 1.)))
+}
+
+#[cfg(test)]
+mod test_dp_proportion {
+    use ndarray::{arr2, Array};
+
+    use crate::base::{ReleaseNode, Value};
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::components::dp_proportion::{count_noise_variance, wilson_interval};
+    use crate::utilities::privacy::get_epsilon;
+
+    #[test]
+    fn test_wilson_interval() {
+        let epsilons = [0.01, 0.1, 1., 10.];
+        for proportion in &[0., 0.02, 0.5, 0.97, 1.] {
+            let widths = epsilons.iter()
+                .map(|epsilon| {
+                    let variance = count_noise_variance("Laplace", *epsilon);
+                    let (lower, upper) = wilson_interval(*proportion, 100., variance, 0.05).unwrap();
+                    assert!(0. <= lower && lower <= upper && upper <= 1.);
+                    assert!(lower <= *proportion && *proportion <= upper);
+                    upper - lower
+                })
+                .collect::<Vec<f64>>();
+            // the interval narrows as epsilon grows
+            assert!(widths.windows(2).all(|pair| pair[1] < pair[0]), "{:?}", widths);
+        }
+    }
+
+    #[test]
+    fn test_wilson_interval_small_denominator() {
+        let variance = count_noise_variance("SimpleGeometric", 1.);
+        // the interval widens as the number of records shrinks, up to the entire unit interval
+        let widths = [1000., 100., 10., 1., 0.1, 0., -3.].iter()
+            .map(|num_records| {
+                let (lower, upper) = wilson_interval(0.3, *num_records, variance, 0.05).unwrap();
+                assert!(0. <= lower && lower <= upper && upper <= 1.);
+                upper - lower
+            })
+            .collect::<Vec<f64>>();
+        assert!(widths.windows(2).all(|pair| pair[1] >= pair[0]), "{:?}", widths);
+        assert_eq!(widths.last(), Some(&1.));
+        assert!(wilson_interval(0.3, 100., variance, 1.).is_err());
+    }
+
+    #[test]
+    fn test_expansion() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[true], [false], [true], [true]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let proportion = analysis.dp_proportion(data, privacy_usage(1., 0.)).build();
+
+        let properties = analysis.properties(proportion).unwrap();
+        let properties = properties.array().unwrap();
+        assert!(properties.releasable);
+        assert_eq!(properties.lower_float().unwrap(), vec![0.]);
+        assert_eq!(properties.upper_float().unwrap(), vec![1.]);
+
+        // the number of records is known, so the entire usage is spent on the count of successes
+        let usage = crate::compute_privacy_usage(
+            analysis.privacy_definition.clone(), analysis.components.clone(), analysis.release.clone()).unwrap();
+        assert!((get_epsilon(&usage).unwrap() - 1.).abs() < 1e-12);
+
+        // non-boolean data is rejected
+        let data = analysis.literal()
+            .value(arr2(&[[1.], [0.]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let proportion = analysis.dp_proportion(data, privacy_usage(1., 0.)).build();
+        assert!(analysis.properties(proportion).is_err());
+
+        // a count of records must be supplied when the number of records is unknown
+        let data = analysis.literal()
+            .value(arr2(&[[true], [false]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let data = analysis.filter(data, data).build();
+        let proportion = analysis.dp_proportion(data, privacy_usage(1., 0.)).build();
+        let error = analysis.properties(proportion).unwrap_err();
+        assert!(error.to_string().contains("count: must be supplied"), "{}", error);
+    }
+
+    #[test]
+    fn test_report_interval() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((50, 1), |(i, _)| i % 10 < 3).into_dyn().into())
+            .value_public(true)
+            .build();
+        let proportion = analysis.dp_proportion(data, privacy_usage(1., 0.)).build();
+        analysis.release.insert(proportion, ReleaseNode::new(Value::from(0.02)));
+
+        let report = crate::generate_report(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+        // the interval is asymmetric about a release near zero, but does not extend below zero
+        let interval = &report[0]["interval"];
+        let (lower, upper) = (interval["lower"].as_f64().unwrap(), interval["upper"].as_f64().unwrap());
+        assert!((0. ..0.02).contains(&lower) && 0.02 < upper && upper <= 1.);
+        assert!(upper - 0.02 > 0.02 - lower);
+    }
+
+    /// width of the reported interval about a proportion of 0.3
+    fn report_width(group_size: u32, count: Option<i64>) -> f64 {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.group_size = group_size;
+        let data = analysis.literal()
+            .value(Array::from_shape_fn((50, 1), |(i, _)| i % 10 < 3).into_dyn().into())
+            .value_public(true)
+            .build();
+        let proportion = match count {
+            Some(count) => {
+                let lower = analysis.literal().value(0.into()).value_public(true).build();
+                let dp_count = analysis.dp_count(data, lower, privacy_usage(1., 0.)).build();
+                analysis.release.insert(dp_count, ReleaseNode {
+                    value: Value::from(count),
+                    privacy_usages: Some(privacy_usage(1., 0.)),
+                    public: true
+                });
+                analysis.dp_proportion(data, privacy_usage(1., 0.)).count(dp_count).build()
+            }
+            None => analysis.dp_proportion(data, privacy_usage(1., 0.)).build()
+        };
+        analysis.release.insert(proportion, ReleaseNode::new(Value::from(0.3)));
+
+        let report = crate::generate_report(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+        let interval = &report.as_array().unwrap().iter()
+            .find(|summary| summary["nodeID"].as_u64() == Some(proportion as u64)).unwrap()["interval"];
+        interval["upper"].as_f64().unwrap() - interval["lower"].as_f64().unwrap()
+    }
+
+    #[test]
+    fn test_report_dp_count() {
+        // the interval widens as the released count of records shrinks, up to the entire unit interval
+        let widths = [1000, 100, 10, 1, 0, -5].iter()
+            .map(|count| report_width(1, Some(*count)))
+            .collect::<Vec<f64>>();
+        assert!(widths.windows(2).all(|pair| pair[1] >= pair[0]), "{:?}", widths);
+        assert_eq!(widths.last(), Some(&1.));
+    }
+
+    #[test]
+    fn test_report_group_size() {
+        // each record of a group gets half of the usage, so the count of successes is noisier
+        assert!(report_width(2, None) > report_width(1, None));
+    }
+}
@@ -4,7 +4,7 @@ use crate::{base, proto};
 use crate::base::{Array, IndexKey, NodeProperties, Value};
 use crate::components::{Expandable, Report};
 use crate::errors::*;
-use crate::utilities::{array::get_ith_column, prepend, privacy::spread_privacy_usage};
+use crate::utilities::{array::get_ith_column, prepend, privacy::{get_automatic_mechanism, spread_privacy_usage}};
 use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
 
 impl Expandable for proto::DpQuantile {
@@ -26,12 +26,12 @@ impl Expandable for proto::DpQuantile {
 
         let mechanism = if self.mechanism.to_lowercase().as_str() == "automatic" {
             if properties.contains_key::<IndexKey>(&"candidates".into()) {
-                "exponential"
+                "exponential".to_string()
             } else {
                 let privacy_definition = privacy_definition.as_ref()
                     .ok_or_else(|| Error::from("privacy_definition must be known"))?;
-                if privacy_definition.protect_floating_point { "snapping" } else { "laplace" }
-            }.to_string()
+                get_automatic_mechanism(privacy_definition, &self.privacy_usage)
+            }
         } else {
             self.mechanism.to_lowercase()
         };
@@ -67,15 +67,20 @@ impl Expandable for proto::DpQuantile {
 
         let variant = Some(match mechanism.as_str() {
             "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
-                privacy_usage: self.privacy_usage.clone()
+                privacy_usage: self.privacy_usage.clone(),
+                resolution: 0.
             }),
             "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
                 privacy_usage: self.privacy_usage.clone(),
-                analytic: false
+                analytic: false,
+                joint: false,
+                resolution: 0.
             }),
             "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
                 privacy_usage: self.privacy_usage.clone(),
-                analytic: true
+                analytic: true,
+                joint: false,
+                resolution: 0.
             }),
             "exponential" => proto::component::Variant::ExponentialMechanism(proto::ExponentialMechanism {
                 privacy_usage: self.privacy_usage.clone()
@@ -107,6 +112,7 @@ impl Expandable for proto::DpQuantile {
 impl Report for proto::DpQuantile {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -156,6 +162,7 @@ impl Report for proto::DpQuantile {
                         }
                     }),
                 },
+                ..Default::default()
             });
         }
         Ok(Some(releases))
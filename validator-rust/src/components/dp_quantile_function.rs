@@ -0,0 +1,235 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, Float, Integer, proto};
+use crate::base::{IndexKey, NodeProperties, Value};
+use crate::components::{Expandable, Report};
+use crate::errors::*;
+use crate::utilities::prepend;
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Expandable for proto::DpQuantileFunction {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let argument_ids = component.arguments();
+
+        let data_id = *argument_ids.get::<IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data is a required argument to DPQuantileFunction"))?;
+        let candidates_id = *argument_ids.get::<IndexKey>(&"candidates".into())
+            .ok_or_else(|| Error::from("candidates is a required argument to DPQuantileFunction"))?;
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain exactly one column".into())
+        }
+
+        let alphas = alphas(public_arguments)?;
+
+        // the budget is composed sequentially over the grid
+        let privacy_usage = self.privacy_usage.iter().cloned()
+            .map(|usage| usage / alphas.len() as f64)
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()?;
+
+        let mut bind_arguments = IndexMap::new();
+        let mut id_previous: Option<u32> = None;
+
+        for (index, alpha) in alphas.iter().enumerate() {
+            // utilities of the candidates are scored at each probability
+            maximum_id += 1;
+            let id_quantile = maximum_id;
+            expansion.computation_graph.insert(id_quantile, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                    "data".into() => data_id,
+                    "candidates".into() => candidates_id
+                ])),
+                variant: Some(proto::component::Variant::Quantile(proto::Quantile {
+                    alpha: *alpha,
+                    interpolation: self.interpolation.clone(),
+                })),
+                omit: true,
+                submission: component.submission,
+            });
+            expansion.traversal.push(id_quantile);
+
+            maximum_id += 1;
+            let id_exponential = maximum_id;
+            expansion.computation_graph.insert(id_exponential, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                    "utilities".into() => id_quantile,
+                    "candidates".into() => candidates_id
+                ])),
+                variant: Some(proto::component::Variant::ExponentialMechanism(proto::ExponentialMechanism {
+                    privacy_usage: privacy_usage.clone()
+                })),
+                omit: true,
+                submission: component.submission,
+            });
+            expansion.traversal.push(id_exponential);
+
+            // postprocess into a monotone non-decreasing function, via a running maximum
+            let id_monotone = match id_previous {
+                Some(id_previous) => {
+                    maximum_id += 1;
+                    expansion.computation_graph.insert(maximum_id, proto::Component {
+                        arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                            "left".into() => id_previous,
+                            "right".into() => id_exponential
+                        ])),
+                        variant: Some(proto::component::Variant::RowMax(proto::RowMax {})),
+                        omit: true,
+                        submission: component.submission,
+                    });
+                    expansion.traversal.push(maximum_id);
+                    maximum_id
+                }
+                None => id_exponential
+            };
+
+            bind_arguments.insert(IndexKey::from(index as Integer), id_monotone);
+            id_previous = Some(id_monotone);
+        }
+
+        // one column per probability
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(bind_arguments)),
+            variant: Some(proto::component::Variant::ColumnBind(proto::ColumnBind {})),
+            omit: component.omit,
+            submission: component.submission,
+        });
+
+        Ok(expansion)
+    }
+}
+
+impl Report for proto::DpQuantileFunction {
+    fn summarize(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        node_id: u32,
+        component: &proto::Component,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        _properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let alphas = alphas(&public_arguments)?;
+
+        let variable_name = variable_names
+            .and_then(|names| names.first().cloned())
+            .unwrap_or_else(|| "[Unknown]".into());
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPQuantileFunction".to_string(),
+            variables: serde_json::json!(variable_name.to_string()),
+            release_info: value_to_json(release)?,
+            privacy_loss: privacy_usage_to_json(self.privacy_usage.first()
+                .ok_or_else(|| Error::from("privacy_usage: must be defined"))?),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: "Exponential".to_string(),
+                argument: serde_json::json!({
+                    "alphas": alphas
+                }),
+            },
+            ..Default::default()
+        }]))
+    }
+}
+
+/// Retrieve the public grid of probabilities, which must be non-empty, non-decreasing and within [0, 1].
+fn alphas(public_arguments: &IndexMap<IndexKey, &Value>) -> Result<Vec<Float>> {
+    let alphas = public_arguments.get::<IndexKey>(&"alphas".into())
+        .ok_or_else(|| Error::from("alphas: missing, must be public"))?
+        .ref_array()?.clone().cast_float()?
+        .iter().copied().collect::<Vec<Float>>();
+
+    if alphas.is_empty() {
+        return Err("alphas: must contain at least one probability".into())
+    }
+    if !alphas.iter().all(|alpha| (0. ..=1.).contains(alpha)) {
+        return Err("alphas: probabilities must be within [0, 1]".into())
+    }
+    if alphas.windows(2).any(|w| w[0] > w[1]) {
+        return Err("alphas: probabilities must be non-decreasing".into())
+    }
+    Ok(alphas)
+}
+
+#[cfg(test)]
+mod test_dp_quantile_function {
+    use ndarray::{arr1, Array2};
+
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::proto;
+
+    #[test]
+    fn test_dp_quantile_function() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((20, 1), |(i, _)| i as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let candidates = analysis.literal()
+            .value(Array2::from_shape_fn((11, 1), |(i, _)| i as f64 * 2.).into_dyn().into())
+            .value_public(true)
+            .build();
+        let alphas = analysis.literal()
+            .value(arr1(&[0.1, 0.25, 0.5, 0.75, 0.9]).into_dyn().into())
+            .value_public(true)
+            .build();
+
+        let dp_quantile_function = analysis.dp_quantile_function(data, candidates, alphas, privacy_usage(1., 0.))
+            .build();
+
+        let properties = analysis.properties(dp_quantile_function).unwrap();
+        let properties = properties.array().unwrap();
+        assert!(properties.releasable);
+        assert_eq!(properties.num_records, Some(1));
+        assert_eq!(properties.num_columns().unwrap(), 5);
+
+        // the budget is divided over the grid, so the entire release only uses the requested budget
+        let usage = crate::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        let epsilon = match usage.distance.unwrap() {
+            proto::privacy_usage::Distance::Approximate(approximate) => approximate.epsilon
+        };
+        assert!((epsilon - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_dp_quantile_function_alphas() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((20, 1), |(i, _)| i as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let candidates = analysis.literal()
+            .value(Array2::from_shape_fn((11, 1), |(i, _)| i as f64 * 2.).into_dyn().into())
+            .value_public(true)
+            .build();
+        let alphas = analysis.literal()
+            .value(arr1(&[0.5, 0.25]).into_dyn().into())
+            .value_public(true)
+            .build();
+
+        let dp_quantile_function = analysis.dp_quantile_function(data, candidates, alphas, privacy_usage(1., 0.))
+            .build();
+        assert!(analysis.properties(dp_quantile_function).is_err());
+    }
+}
@@ -1,11 +1,12 @@
 use indexmap::map::IndexMap;
+use ndarray::{arr1, Axis};
 
-use crate::{base, proto};
+use crate::{base, Float, proto};
 use crate::base::{Array, NodeProperties, Value, IndexKey};
-use crate::components::{Expandable, Report};
+use crate::components::{Accuracy, Expandable, Report};
 use crate::errors::*;
-use crate::utilities::{array::get_ith_column, prepend, privacy::spread_privacy_usage};
-use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+use crate::utilities::{array::get_ith_column, prepend, privacy::{get_automatic_mechanism, spread_privacy_usage}, get_mechanism_variant, get_explicit_mechanism};
+use crate::utilities::json::{Accuracy as JSONAccuracy, AlgorithmInfo, DEFAULT_ALPHA, JSONRelease, privacy_usage_to_json, value_to_json};
 
 impl Expandable for proto::DpRawMoment {
     fn expand_component(
@@ -42,35 +43,17 @@ impl Expandable for proto::DpRawMoment {
         let mechanism = if self.mechanism.to_lowercase().as_str() == "automatic" {
             let privacy_definition = privacy_definition.as_ref()
                 .ok_or_else(|| Error::from("privacy_definition must be known"))?;
-            if privacy_definition.protect_floating_point
-            { "snapping" } else { "laplace" }.to_string()
+            get_automatic_mechanism(privacy_definition, &self.privacy_usage)
         } else { self.mechanism.to_lowercase() };
 
         let mut arguments = indexmap!["data".into() => id_moment];
-        let variant = Some(match mechanism.as_str() {
-            "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
-                privacy_usage: self.privacy_usage.clone()
-            }),
-            "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
-                privacy_usage: self.privacy_usage.clone(),
-                analytic: false
-            }),
-            "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
-                privacy_usage: self.privacy_usage.clone(),
-                analytic: true
-            }),
-            "snapping" => {
-                argument_ids.get::<IndexKey>(&"lower".into())
-                    .map(|lower| arguments.insert("lower".into(), *lower));
-                argument_ids.get::<IndexKey>(&"upper".into())
-                    .map(|upper| arguments.insert("upper".into(), *upper));
-
-                proto::component::Variant::SnappingMechanism(proto::SnappingMechanism {
-                    privacy_usage: self.privacy_usage.clone()
-                })
-            },
-            _ => bail!("Unexpected invalid token {:?}", self.mechanism.as_str()),
-        });
+        if mechanism.as_str() == "snapping" {
+            argument_ids.get::<IndexKey>(&"lower".into())
+                .map(|lower| arguments.insert("lower".into(), *lower));
+            argument_ids.get::<IndexKey>(&"upper".into())
+                .map(|upper| arguments.insert("upper".into(), *upper));
+        }
+        let variant = Some(get_mechanism_variant(&mechanism, &self.privacy_usage, false)?);
         expansion.computation_graph.insert(component_id, proto::Component {
             arguments: Some(proto::ArgumentNodeIds::new(arguments)),
             variant,
@@ -83,12 +66,42 @@ impl Expandable for proto::DpRawMoment {
 }
 
 
+/// The accuracy of the raw moment is the accuracy of the mechanism that privatizes it.
+///
+/// The `sensitivity` public argument is the sensitivity of the raw moment.
+/// An `Automatic` mechanism is only resolved once the privacy definition is known, so its accuracy is not defined.
+impl Accuracy for proto::DpRawMoment {
+    fn accuracy_to_privacy_usage(
+        &self,
+        accuracies: &proto::Accuracies,
+        public_arguments: IndexMap<base::IndexKey, &Value>
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        match get_explicit_mechanism(&self.mechanism, &self.privacy_usage, false)? {
+            Some(mechanism) => mechanism.accuracy_to_privacy_usage(accuracies, public_arguments),
+            None => Ok(None)
+        }
+    }
+
+    fn privacy_usage_to_accuracy(
+        &self,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        alpha: f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+        match get_explicit_mechanism(&self.mechanism, &self.privacy_usage, false)? {
+            Some(mechanism) => mechanism.privacy_usage_to_accuracy(public_arguments, alpha),
+            None => Ok(None)
+        }
+    }
+}
+
+
 impl Report for proto::DpRawMoment {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
-        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
         release: &Value,
         variable_names: Option<&Vec<base::IndexKey>>,
@@ -106,6 +119,15 @@ impl Report for proto::DpRawMoment {
         let num_columns = data_property.num_columns()?;
         let privacy_usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
 
+        // the sensitivity of the raw moment, scaled by the number of records that may change when an individual changes
+        let sensitivity: Value = arr1(&minimums.iter().zip(maximums.iter())
+            .map(|(lower, upper)| (upper - lower).powi(self.order as i32) / num_records as Float
+                * data_property.c_stability as Float)
+            .collect::<Vec<Float>>()).insert_axis(Axis(0)).into_dyn().into();
+        let mut accuracy_arguments = public_arguments.clone();
+        accuracy_arguments.insert("sensitivity".into(), &sensitivity);
+        let accuracies = self.privacy_usage_to_accuracy(accuracy_arguments, DEFAULT_ALPHA)?;
+
         for column_number in 0..(num_columns as usize) {
             let variable_name = variable_names
                 .and_then(|names| names.get(column_number)).cloned()
@@ -121,7 +143,10 @@ impl Report for proto::DpRawMoment {
                     _ => return Err("maximum must be numeric".into())
                 },
                 privacy_loss: privacy_usage_to_json(&privacy_usages[column_number].clone()),
-                accuracy: None,
+                accuracy: accuracies.as_ref().map(|accuracies| JSONAccuracy {
+                    accuracy_value: accuracies[column_number].value,
+                    alpha: accuracies[column_number].alpha,
+                }),
                 submission: component.submission,
                 node_id,
                 postprocess: false,
@@ -137,8 +162,74 @@ impl Report for proto::DpRawMoment {
                             }
                         }),
                 },
+                ..Default::default()
             });
         }
         Ok(Some(releases))
     }
 }
+
+#[cfg(test)]
+mod test_dp_raw_moment {
+    use ndarray::arr2;
+
+    use crate::{generate_report, proto};
+    use crate::base::{IndexKey, ReleaseNode, Value};
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::components::Accuracy;
+    use crate::utilities::privacy::get_epsilon;
+
+    #[test]
+    fn test_accuracy_round_trip() {
+        let sensitivity = Value::from(arr2(&[[0.5]]).into_dyn());
+        let public_arguments = || indexmap![IndexKey::from("sensitivity") => &sensitivity];
+        let dp_raw_moment = |mechanism: &str, epsilon: f64| proto::DpRawMoment {
+            order: 2, mechanism: mechanism.to_string(), privacy_usage: privacy_usage(epsilon, 0.),
+        };
+
+        let accuracies = dp_raw_moment("Laplace", 0.5)
+            .privacy_usage_to_accuracy(public_arguments(), 0.05).unwrap().unwrap();
+        let usages = dp_raw_moment("Laplace", 1.)
+            .accuracy_to_privacy_usage(&proto::Accuracies { values: accuracies }, public_arguments())
+            .unwrap().unwrap();
+        assert!((get_epsilon(&usages[0]).unwrap() - 0.5).abs() < 1e-10);
+
+        // the mechanism is unknown until the privacy definition is known
+        assert!(dp_raw_moment("Automatic", 1.)
+            .privacy_usage_to_accuracy(public_arguments(), 0.05).unwrap().is_none());
+    }
+
+    /// accuracy of a raw moment of data in [0.5, 2], as summarized in the report
+    fn report_accuracy(order: u32, group_size: u32) -> f64 {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        analysis.privacy_definition.group_size = group_size;
+        let data = analysis.literal()
+            .value(arr2(&[[0.5], [1.], [1.5], [2.]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.5.into()).value_public(true).build();
+        let upper = analysis.literal().value(2.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let dp_raw_moment = analysis.dp_raw_moment(clamped, order, privacy_usage(1., 0.))
+            .mechanism("Laplace".to_string()).build();
+        analysis.release.insert(dp_raw_moment, ReleaseNode::new(Value::from(1.)));
+
+        let report = generate_report(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+        report[0]["accuracy"]["accuracyValue"].as_f64().unwrap()
+    }
+
+    #[test]
+    fn test_accuracy_widens_with_order() {
+        assert!(report_accuracy(1, 1) < report_accuracy(2, 1));
+        assert!(report_accuracy(2, 1) < report_accuracy(3, 1));
+    }
+
+    #[test]
+    fn test_accuracy_group_size() {
+        // the mechanism is released with a smaller effective usage when individuals contribute to a group of records
+        assert!((report_accuracy(2, 2) - 2. * report_accuracy(2, 1)).abs() < 1e-10);
+    }
+}
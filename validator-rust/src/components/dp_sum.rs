@@ -1,37 +1,104 @@
 use indexmap::map::IndexMap;
+use ndarray::{arr1, Axis};
 
-use crate::{base, proto};
+use crate::{base, proto, Float, Integer};
 use crate::base::{Array, ArrayProperties, DataType, IndexKey, NodeProperties, Value};
-use crate::components::{Expandable, Report};
+use crate::components::{Accuracy, Expandable, Report};
 use crate::errors::*;
-use crate::utilities::{array::get_ith_column, prepend, privacy::spread_privacy_usage};
-use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+use crate::utilities::{array::get_ith_column, prepend, privacy::{get_automatic_mechanism, spread_privacy_usage}, get_literal, get_mechanism_variant, get_explicit_mechanism};
+use crate::utilities::json::{Accuracy as JSONAccuracy, AlgorithmInfo, DEFAULT_ALPHA, JSONRelease, privacy_usage_to_json, value_to_json};
+use crate::utilities::inference::infer_property;
 
 impl Expandable for proto::DpSum {
     fn expand_component(
         &self,
         privacy_definition: &Option<proto::PrivacyDefinition>,
         component: &proto::Component,
-        _public_arguments: &IndexMap<IndexKey, &Value>,
+        public_arguments: &IndexMap<IndexKey, &Value>,
         properties: &base::NodeProperties,
         component_id: u32,
         mut maximum_id: u32,
     ) -> Result<base::ComponentExpansion> {
 
         let mut expansion = base::ComponentExpansion::default();
-        let argument_ids = component.arguments();
+        let mut argument_ids = component.arguments();
 
         let data_property = properties.get::<base::IndexKey>(&"data".into())
             .ok_or("data: missing")?.array()
             .map_err(prepend("data:"))?.clone();
 
+        let mut id_data = *argument_ids.get::<base::IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+
+        // if an offset is supplied, the data is shifted before the sum,
+        //     and the sum is corrected by num_records * offset after the mechanism
+        let mut id_correction = None;
+        if argument_ids.contains_key::<IndexKey>(&"offset".into()) {
+            let offset = public_arguments.get::<IndexKey>(&"offset".into())
+                .ok_or_else(|| Error::from("offset: must be public"))?.ref_array()?;
+
+            let num_records = data_property.num_records()
+                .map_err(|_| Error::from("data: the number of records must be known to correct for an offset"))?;
+            let (offset, correction) = get_offset_correction(
+                offset, num_records, data_property.num_columns()?)?;
+
+            // offset
+            maximum_id += 1;
+            let id_offset = maximum_id;
+            let (patch_node, offset_release) = get_literal(offset, component.submission)?;
+            expansion.computation_graph.insert(id_offset, patch_node);
+            expansion.properties.insert(id_offset, infer_property(&offset_release.value, None, id_offset)?);
+            expansion.releases.insert(id_offset, offset_release);
+
+            // shift data
+            maximum_id += 1;
+            let id_shifted = maximum_id;
+            expansion.computation_graph.insert(id_shifted, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                    "left".into() => id_data,
+                    "right".into() => id_offset
+                ])),
+                variant: Some(proto::component::Variant::Subtract(proto::Subtract {})),
+                omit: true,
+                submission: component.submission,
+            });
+            expansion.traversal.push(id_shifted);
+            id_data = id_shifted;
+
+            // correction
+            maximum_id += 1;
+            let id_correction_literal = maximum_id;
+            let (patch_node, correction_release) = get_literal(correction, component.submission)?;
+            expansion.computation_graph.insert(id_correction_literal, patch_node);
+            expansion.properties.insert(id_correction_literal, infer_property(&correction_release.value, None, id_correction_literal)?);
+            expansion.releases.insert(id_correction_literal, correction_release);
+            id_correction = Some(id_correction_literal);
+
+            // estimated bounds on the statistic are shifted by the correction
+            for bound in &["lower", "upper"] {
+                if let Some(id_bound) = argument_ids.get::<IndexKey>(&(*bound).into()).cloned() {
+                    maximum_id += 1;
+                    expansion.computation_graph.insert(maximum_id, proto::Component {
+                        arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                            "left".into() => id_bound,
+                            "right".into() => id_correction_literal
+                        ])),
+                        variant: Some(proto::component::Variant::Subtract(proto::Subtract {})),
+                        omit: true,
+                        submission: component.submission,
+                    });
+                    expansion.traversal.push(maximum_id);
+                    argument_ids.insert((*bound).into(), maximum_id);
+                }
+            }
+        }
+
         // sum
         maximum_id += 1;
         let id_sum = maximum_id;
         expansion.computation_graph.insert(id_sum, proto::Component {
             arguments: Some(proto::ArgumentNodeIds::new(indexmap![
-                "data".into() => *argument_ids.get::<base::IndexKey>(&"data".into())
-                    .ok_or_else(|| Error::from("data must be provided as an argument"))?])),
+                "data".into() => id_data])),
             variant: Some(proto::component::Variant::Sum(proto::Sum {})),
             omit: true,
             submission: component.submission,
@@ -41,7 +108,18 @@ impl Expandable for proto::DpSum {
         let privacy_definition = privacy_definition.as_ref()
             .ok_or_else(|| Error::from("privacy_definition must be known"))?;
         let mechanism = get_mechanism(
-            &data_property, &self.mechanism, privacy_definition.protect_floating_point)?;
+            &data_property, &self.mechanism, privacy_definition, &self.privacy_usage)?;
+
+        // when correcting for an offset, the mechanism is not the final node
+        let id_noised = match id_correction {
+            Some(_) => {
+                maximum_id += 1;
+                expansion.traversal.push(maximum_id);
+                maximum_id
+            },
+            None => component_id
+        };
+        let omit_noised = id_correction.is_some() || component.omit;
 
         if mechanism.as_str() == "simplegeometric" {
             let sum_max_id = *argument_ids.get::<IndexKey>(&"upper".into())
@@ -50,65 +128,87 @@ impl Expandable for proto::DpSum {
                 .ok_or_else(|| Error::from("lower must be defined for geometric mechanism"))?;
 
             // noising
-            expansion.computation_graph.insert(component_id, proto::Component {
+            expansion.computation_graph.insert(id_noised, proto::Component {
                 arguments: Some(proto::ArgumentNodeIds::new(indexmap![
                     "data".into() => id_sum,
                     "lower".into() => sum_min_id,
                     "upper".into() => sum_max_id
                 ])),
-                variant: Some(proto::component::Variant::SimpleGeometricMechanism(proto::SimpleGeometricMechanism {
-                    privacy_usage: self.privacy_usage.clone()
-                })),
-                omit: component.omit,
+                variant: Some(get_mechanism_variant(&mechanism, &self.privacy_usage, self.joint)?),
+                omit: omit_noised,
                 submission: component.submission,
             });
         } else {
 
             // noising
             let mut arguments = indexmap!["data".into() => id_sum];
-            let variant = Some(match mechanism.as_str() {
-                "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
-                    privacy_usage: self.privacy_usage.clone()
-                }),
-                "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
-                    privacy_usage: self.privacy_usage.clone(),
-                    analytic: false
-                }),
-                "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
-                    privacy_usage: self.privacy_usage.clone(),
-                    analytic: true
-                }),
-                "snapping" => {
-                    argument_ids.get::<IndexKey>(&"lower".into())
-                        .map(|lower| arguments.insert("lower".into(), *lower));
-                    argument_ids.get::<IndexKey>(&"upper".into())
-                        .map(|upper| arguments.insert("upper".into(), *upper));
-
-                    proto::component::Variant::SnappingMechanism(proto::SnappingMechanism {
-                        privacy_usage: self.privacy_usage.clone()
-                    })
-                },
-                _ => bail!("Unexpected invalid token {:?}", self.mechanism.as_str()),
+            if mechanism.as_str() == "snapping" {
+                argument_ids.get::<IndexKey>(&"lower".into())
+                    .map(|lower| arguments.insert("lower".into(), *lower));
+                argument_ids.get::<IndexKey>(&"upper".into())
+                    .map(|upper| arguments.insert("upper".into(), *upper));
+            }
+
+            expansion.computation_graph.insert(id_noised, proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(arguments)),
+                variant: Some(get_mechanism_variant(&mechanism, &self.privacy_usage, self.joint)?),
+                omit: omit_noised,
+                submission: component.submission,
             });
+        };
 
+        // correct for the offset
+        if let Some(id_correction) = id_correction {
             expansion.computation_graph.insert(component_id, proto::Component {
-                arguments: Some(proto::ArgumentNodeIds::new(arguments)),
-                variant,
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                    "left".into() => id_noised,
+                    "right".into() => id_correction
+                ])),
+                variant: Some(proto::component::Variant::Add(proto::Add {})),
                 omit: component.omit,
                 submission: component.submission,
             });
-        };
+        }
 
         Ok(expansion)
     }
 }
 
+/// The accuracy of the sum is the accuracy of the mechanism that privatizes it.
+///
+/// The `sensitivity` public argument is the sensitivity of the sum.
+/// An `Automatic` mechanism is only resolved once the data type and privacy definition are known, so its accuracy is not defined.
+impl Accuracy for proto::DpSum {
+    fn accuracy_to_privacy_usage(
+        &self,
+        accuracies: &proto::Accuracies,
+        public_arguments: IndexMap<base::IndexKey, &Value>
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        match get_explicit_mechanism(&self.mechanism, &self.privacy_usage, self.joint)? {
+            Some(mechanism) => mechanism.accuracy_to_privacy_usage(accuracies, public_arguments),
+            None => Ok(None)
+        }
+    }
+
+    fn privacy_usage_to_accuracy(
+        &self,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        alpha: f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+        match get_explicit_mechanism(&self.mechanism, &self.privacy_usage, self.joint)? {
+            Some(mechanism) => mechanism.privacy_usage_to_accuracy(public_arguments, alpha),
+            None => Ok(None)
+        }
+    }
+}
+
 impl Report for proto::DpSum {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
-        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: NodeProperties,
         release: &Value,
         variable_names: Option<&Vec<base::IndexKey>>,
@@ -117,6 +217,13 @@ impl Report for proto::DpSum {
             .ok_or("data: missing")?.array()
             .map_err(prepend("data:"))?.clone();
 
+        let offsets = match public_arguments.get::<IndexKey>(&"offset".into()) {
+            Some(offset) => Some(get_offset_correction(
+                offset.ref_array()?, data_property.num_records()?, data_property.num_columns()?)?
+                .0.ref_array()?.clone().cast_float()?.iter().copied().collect::<Vec<Float>>()),
+            None => None
+        };
+
         let mut releases = Vec::new();
 
         let minimums = data_property.lower_float()?;
@@ -125,6 +232,21 @@ impl Report for proto::DpSum {
         let num_columns = data_property.num_columns()?;
         let privacy_usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
 
+        // the neighboring definition is not known when summarizing,
+        //     so the sensitivity is the larger of the sensitivities under either definition
+        let sensitivity: Value = arr1(&minimums.iter().zip(maximums.iter()).enumerate()
+            .map(|(column_number, (lower, upper))| {
+                let offset = offsets.as_ref().map(|offsets| offsets[column_number]).unwrap_or(0.);
+                let (lower, upper) = (lower - offset, upper - offset);
+                (upper - lower).max(lower.abs().max(upper.abs())) * data_property.c_stability as Float
+            })
+            .collect::<Vec<Float>>()).insert_axis(Axis(0)).into_dyn().into();
+        let mut accuracy_arguments = public_arguments.clone();
+        accuracy_arguments.insert("sensitivity".into(), &sensitivity);
+        // the snapping mechanism also needs bounds on the statistic, so its accuracy is omitted without them
+        let accuracies = self.privacy_usage_to_accuracy(accuracy_arguments, DEFAULT_ALPHA)
+            .ok().flatten();
+
         for column_number in 0..(num_columns as usize) {
             let variable_name = variable_names
                 .and_then(|names| names.get(column_number)).cloned()
@@ -140,7 +262,10 @@ impl Report for proto::DpSum {
                     _ => return Err("maximum must be numeric".into())
                 },
                 privacy_loss: privacy_usage_to_json(&privacy_usages[column_number].clone()),
-                accuracy: None,
+                accuracy: accuracies.as_ref().map(|accuracies| JSONAccuracy {
+                    accuracy_value: accuracies[column_number].value,
+                    alpha: accuracies[column_number].alpha,
+                }),
                 submission: component.submission,
                 node_id,
                 postprocess: false,
@@ -152,9 +277,11 @@ impl Report for proto::DpSum {
                             "constraint": {
                                 "lowerbound": minimums[column_number],
                                 "upperbound": maximums[column_number]
-                            }
+                            },
+                            "offset": offsets.as_ref().map(|offsets| offsets[column_number])
                         }),
                 },
+                ..Default::default()
             });
         }
 
@@ -162,17 +289,199 @@ impl Report for proto::DpSum {
     }
 }
 
-fn get_mechanism(data_property: &ArrayProperties, mechanism: &str, protect_floating_point: bool) -> Result<String> {
+fn get_mechanism(
+    data_property: &ArrayProperties, mechanism: &str,
+    privacy_definition: &proto::PrivacyDefinition, privacy_usage: &[proto::PrivacyUsage],
+) -> Result<String> {
     let mechanism = mechanism.to_lowercase();
 
     Ok(if mechanism == "automatic" {
         match data_property.data_type {
-            DataType::Int => "simplegeometric",
-            DataType::Float => if protect_floating_point { "snapping" } else { "laplace" },
+            // integer sums keep their data type under the geometric mechanism
+            DataType::Int => "simplegeometric".to_string(),
+            DataType::Float => get_automatic_mechanism(privacy_definition, privacy_usage),
             _ => return Err("cannot sum non-integer data".into())
-        }.to_string()
+        }
     } else {
         mechanism
     })
 
-}
\ No newline at end of file
+}
+
+/// Broadcast a public offset to one value per column, and compute the correction `num_records * offset`.
+///
+/// Both are returned as values with a single row.
+fn get_offset_correction(offset: &Array, num_records: i64, num_columns: i64) -> Result<(Value, Value)> {
+    fn broadcast<T: Clone>(offset: Vec<T>, num_columns: i64) -> Result<Vec<T>> {
+        match offset.len() as i64 {
+            1 => Ok((0..num_columns).map(|_| offset[0].clone()).collect()),
+            length if length == num_columns => Ok(offset),
+            _ => Err("offset: must be a scalar or have one value for each column".into())
+        }
+    }
+    let shape = (1, num_columns as usize);
+
+    Ok(match offset {
+        Array::Float(offset) => {
+            let offset = broadcast(offset.iter().copied().collect::<Vec<Float>>(), num_columns)?;
+            let correction = offset.iter()
+                .map(|offset| offset * num_records as Float)
+                .collect::<Vec<Float>>();
+            (ndarray::Array::from_shape_vec(shape, offset)?.into_dyn().into(),
+             ndarray::Array::from_shape_vec(shape, correction)?.into_dyn().into())
+        }
+        Array::Int(offset) => {
+            let offset = broadcast(offset.iter().copied().collect::<Vec<Integer>>(), num_columns)?;
+            let correction = offset.iter()
+                .map(|offset| offset.checked_mul(num_records)
+                    .ok_or_else(|| Error::from("offset: correction may result in overflow")))
+                .collect::<Result<Vec<Integer>>>()?;
+            (ndarray::Array::from_shape_vec(shape, offset)?.into_dyn().into(),
+             ndarray::Array::from_shape_vec(shape, correction)?.into_dyn().into())
+        }
+        _ => return Err("offset: must be numeric".into())
+    })
+}
+
+#[cfg(test)]
+pub mod test_dp_sum {
+    use crate::base::test_data;
+    use crate::bindings::privacy_usage;
+    use crate::components::Accuracy;
+    use crate::components::resize::test_resize;
+    use crate::proto;
+    use crate::utilities::get_public_arguments;
+    use crate::utilities::privacy::get_epsilon;
+
+    #[test]
+    fn test_dp_sum_offset() {
+        let (mut analysis, resized) = test_resize::utilities::analysis_f64_cont(
+            test_data::array1d_f64_10_uniform(), 10.into(), None, None);
+        analysis.privacy_definition.protect_floating_point = false;
+
+        let offset = analysis.literal()
+            .value(5.0.into()).value_public(true)
+            .build();
+        let dp_sum = analysis.dp_sum(resized, privacy_usage(1., 0.))
+            .mechanism("Laplace".to_string())
+            .offset(offset)
+            .build();
+
+        let properties = analysis.properties(dp_sum).unwrap();
+        let properties = properties.array().unwrap();
+        assert!(properties.releasable);
+        assert_eq!(properties.num_columns().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_accuracy() {
+        for mechanism in &["Laplace", "Gaussian"] {
+            let (mut analysis, resized) = test_resize::utilities::analysis_f64_cont(
+                test_data::array1d_f64_10_uniform(), 10.into(), None, None);
+            analysis.privacy_definition.protect_floating_point = false;
+            let mut usage = privacy_usage(1., 0.);
+            if let Some(proto::privacy_usage::Distance::Approximate(distance)) = &mut usage[0].distance {
+                distance.delta = 1e-6;
+            }
+            let dp_sum = analysis.dp_sum(resized, usage)
+                .mechanism(mechanism.to_string())
+                .build();
+            let component = match analysis.components.get(&dp_sum).unwrap().variant.clone() {
+                Some(proto::component::Variant::DpSum(component)) => component,
+                _ => panic!("expected a DpSum")
+            };
+
+            // the mechanism takes the node id of the sum it was expanded from
+            let mut graph = analysis.components.clone();
+            let mut release = analysis.release.clone();
+            crate::utilities::propagate_properties(
+                &Some(analysis.privacy_definition.clone()), &mut graph, &mut release, None, false).unwrap();
+            let mechanism_node = graph.get(&dp_sum).unwrap();
+            let public_arguments = get_public_arguments(mechanism_node, &release).unwrap();
+            let expected = mechanism_node.privacy_usage_to_accuracy(public_arguments.clone(), 0.05)
+                .unwrap().unwrap();
+
+            // the sum forwards to the mechanism, given the same sensitivity
+            let accuracies = component.privacy_usage_to_accuracy(public_arguments.clone(), 0.05)
+                .unwrap().unwrap();
+            assert_eq!(accuracies, expected);
+
+            let usages = component.accuracy_to_privacy_usage(
+                &proto::Accuracies { values: accuracies.clone() }, public_arguments.clone()).unwrap().unwrap();
+            let expected_usages = mechanism_node.accuracy_to_privacy_usage(
+                &proto::Accuracies { values: accuracies }, public_arguments).unwrap().unwrap();
+            assert_eq!(usages, expected_usages);
+            assert!((get_epsilon(&usages[0]).unwrap() - 1.).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_accuracy_automatic() {
+        let component = proto::DpSum {
+            mechanism: "Automatic".to_string(),
+            privacy_usage: privacy_usage(1., 0.),
+            joint: false,
+        };
+        // the mechanism is only known once the data type is known
+        assert!(component.privacy_usage_to_accuracy(indexmap::IndexMap::new(), 0.05).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_default_mechanism() {
+        use proto::privacy_definition::MechanismPreference;
+
+        let expanded_mechanism = |preference: MechanismPreference, delta: f64| {
+            let (mut analysis, resized) = test_resize::utilities::analysis_f64_cont(
+                test_data::array1d_f64_10_uniform(), 10.into(), None, None);
+            analysis.privacy_definition.protect_floating_point = false;
+            analysis.privacy_definition.default_mechanism = preference as i32;
+            let mut usage = privacy_usage(1., 0.);
+            if let Some(proto::privacy_usage::Distance::Approximate(distance)) = &mut usage[0].distance {
+                distance.delta = delta;
+            }
+            let dp_sum = analysis.dp_sum(resized, usage).build();
+
+            let mut graph = analysis.components.clone();
+            let mut release = analysis.release.clone();
+            crate::utilities::propagate_properties(
+                &Some(analysis.privacy_definition.clone()), &mut graph, &mut release, None, false).unwrap();
+            graph.get(&dp_sum).unwrap().variant.clone().unwrap()
+        };
+
+        assert!(matches!(expanded_mechanism(MechanismPreference::Pure, 1e-6),
+                         proto::component::Variant::LaplaceMechanism(_)));
+        assert!(matches!(expanded_mechanism(MechanismPreference::Approximate, 1e-6),
+                         proto::component::Variant::GaussianMechanism(_)));
+        // without a delta budget, the preference falls back to a pure mechanism
+        assert!(matches!(expanded_mechanism(MechanismPreference::Approximate, 0.),
+                         proto::component::Variant::LaplaceMechanism(_)));
+    }
+
+    #[test]
+    fn test_summary_c_stability() {
+        use crate::base::{Value, ValueProperties};
+        use crate::components::Report;
+
+        let (mut analysis, resized) = test_resize::utilities::analysis_f64_cont(
+            test_data::array1d_f64_10_uniform(), 10.into(), None, None);
+        analysis.privacy_definition.protect_floating_point = false;
+        let dp_sum = analysis.dp_sum(resized, privacy_usage(1., 0.))
+            .mechanism("Laplace".to_string())
+            .build();
+        let component = analysis.components.get(&dp_sum).unwrap().clone();
+        let data_property = analysis.properties(resized).unwrap().array().unwrap().clone();
+
+        let summary_accuracy = |c_stability: u32| {
+            let mut data_property = data_property.clone();
+            data_property.c_stability = c_stability;
+            let properties = indexmap!["data".into() => ValueProperties::Array(data_property)];
+            let summaries = component.summarize(
+                &Some(analysis.privacy_definition.clone()), dp_sum, &component,
+                indexmap::IndexMap::new(), properties, &Value::from(5.), None).unwrap().unwrap();
+            summaries[0].accuracy.as_ref().unwrap().accuracy_value
+        };
+
+        // a record that influences two rows doubles the sensitivity of the sum
+        assert!((summary_accuracy(2) - 2. * summary_accuracy(1)).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,308 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, Float, Integer, proto};
+use crate::base::{IndexKey, Jagged, NodeProperties, Value, DataType};
+use crate::components::{Expandable, Report};
+use crate::components::dp_histogram::DEFAULT_MAX_CATEGORIES;
+use crate::errors::*;
+use crate::utilities::prepend;
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Expandable for proto::DpSurvivalCounts {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let argument_ids = component.arguments();
+
+        let data_id = *argument_ids.get::<IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data is a required argument to DPSurvivalCounts"))?;
+        let event_id = *argument_ids.get::<IndexKey>(&"event".into())
+            .ok_or_else(|| Error::from("event is a required argument to DPSurvivalCounts"))?;
+        let edges_id = *argument_ids.get::<IndexKey>(&"edges".into())
+            .ok_or_else(|| Error::from("edges is a required argument to DPSurvivalCounts"))?;
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+        if data_property.num_columns()? != 1 {
+            return Err("data: must contain exactly one column of times".into())
+        }
+
+        let event_property = properties.get::<IndexKey>(&"event".into())
+            .ok_or("event: missing")?.array()
+            .map_err(prepend("event:"))?;
+        if event_property.data_type != DataType::Bool {
+            return Err("event: atomic type must be boolean".into())
+        }
+        if event_property.num_columns()? != 1 {
+            return Err("event: must contain exactly one column".into())
+        }
+
+        let edges = public_arguments.get::<IndexKey>(&"edges".into())
+            .ok_or_else(|| Error::from("edges: missing, must be public"))?
+            .ref_jagged()?;
+        if edges.num_columns() != 1 {
+            return Err("edges: must be defined for exactly one column".into())
+        }
+        // times before the first edge would be mapped to the final bin, and counted as survivors
+        let first_edge = match edges {
+            Jagged::Float(edges) => edges[0].first().copied(),
+            Jagged::Int(edges) => edges[0].first().map(|edge| *edge as Float),
+            _ => return Err("edges: must be numeric".into())
+        }.ok_or_else(|| Error::from("edges: must not be empty"))?;
+        let inclusive_left = match public_arguments.get::<IndexKey>(&"inclusive_left".into()) {
+            Some(inclusive_left) => inclusive_left.ref_array()?.first_bool().map_err(prepend("inclusive_left:"))?,
+            None => true
+        };
+        let lower = data_property.lower_float()
+            .map_err(|_| Error::from("data: times must be bounded below by the first edge. Use a clamp to set data bounds"))?[0];
+        let before_first_bin = if inclusive_left { lower < first_edge } else { lower <= first_edge };
+        if before_first_bin {
+            return Err(format!("data: the lower bound on the times ({}) must be within the first bin, which starts at {}", lower, first_edge).into())
+        }
+
+        // times past the last edge are mapped to a final bin
+        let num_bins = (edges.num_records()[0] - 1).max(0) as Integer;
+
+        let id_bin_null = expansion.insert_literal(&mut maximum_id, num_bins.into(), component.submission)?;
+        let id_two = expansion.insert_literal(&mut maximum_id, (2 as Integer).into(), component.submission)?;
+        let id_cast_lower = expansion.insert_literal(&mut maximum_id, (0 as Integer).into(), component.submission)?;
+        let id_cast_upper = expansion.insert_literal(&mut maximum_id, (1 as Integer).into(), component.submission)?;
+        let id_categories = expansion.insert_literal(&mut maximum_id, Value::Jagged(Jagged::Int(vec![(0..2 * (num_bins + 1)).collect()])), component.submission)?;
+        let id_cell_null = expansion.insert_literal(&mut maximum_id, (2 * num_bins + 1).into(), component.submission)?;
+
+        // bin index of each time
+        let mut digitize_arguments = indexmap![
+            "data".into() => data_id,
+            "edges".into() => edges_id,
+            "null_value".into() => id_bin_null
+        ];
+        argument_ids.get::<IndexKey>(&"inclusive_left".into())
+            .map(|v| digitize_arguments.insert("inclusive_left".into(), *v));
+        let id_digitize = expansion.insert_component(&mut maximum_id,
+            digitize_arguments,
+            proto::component::Variant::Digitize(proto::Digitize {
+                out_of_range: "null".to_string()
+            }), component.submission);
+
+        // event indicator of each subject
+        let id_indicator = expansion.insert_component(&mut maximum_id,
+            indexmap![
+                "data".into() => event_id,
+                "lower".into() => id_cast_lower,
+                "upper".into() => id_cast_upper
+            ],
+            proto::component::Variant::Cast(proto::Cast { atomic_type: "int".to_string() }), component.submission);
+
+        // each subject falls into exactly one cell, indexed by 2 * bin + event
+        let id_doubled = expansion.insert_component(&mut maximum_id,
+            indexmap!["left".into() => id_digitize, "right".into() => id_two],
+            proto::component::Variant::Multiply(proto::Multiply {}), component.submission);
+        let id_cells = expansion.insert_component(&mut maximum_id,
+            indexmap!["left".into() => id_doubled, "right".into() => id_indicator],
+            proto::component::Variant::Add(proto::Add {}), component.submission);
+
+        // the cells are disjoint, so the histogram composes in parallel over the cells
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                "data".into() => id_cells,
+                "categories".into() => id_categories,
+                "null_value".into() => id_cell_null
+            ])),
+            variant: Some(proto::component::Variant::DpHistogram(proto::DpHistogram {
+                mechanism: self.mechanism.clone(),
+                privacy_usage: self.privacy_usage.clone(),
+                max_categories: DEFAULT_MAX_CATEGORIES,
+            })),
+            omit: component.omit,
+            submission: component.submission,
+        });
+
+        Ok(expansion)
+    }
+}
+
+impl Report for proto::DpSurvivalCounts {
+    fn summarize(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        _properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let cells = release.ref_array()?.clone().cast_float()?
+            .iter().copied().collect::<Vec<Float>>();
+        let (at_risk, events) = survival_counts(&cells)?;
+
+        let variable_name = variable_names
+            .and_then(|names| names.first().cloned())
+            .unwrap_or_else(|| "[Unknown]".into());
+
+        Ok(Some(vec![JSONRelease {
+            description: "DP release information".to_string(),
+            statistic: "DPSurvivalCounts".to_string(),
+            variables: serde_json::json!(variable_name.to_string()),
+            release_info: serde_json::json!({
+                "at_risk": value_to_json(&ndarray::arr1(&at_risk).into_dyn().into())?,
+                "events": value_to_json(&ndarray::arr1(&events).into_dyn().into())?,
+            }),
+            privacy_loss: privacy_usage_to_json(self.privacy_usage.first()
+                .ok_or_else(|| Error::from("privacy_usage: must be defined"))?),
+            accuracy: None,
+            submission: component.submission,
+            node_id,
+            postprocess: false,
+            algorithm_info: AlgorithmInfo {
+                name: "".to_string(),
+                cite: "".to_string(),
+                mechanism: self.mechanism.clone(),
+                argument: serde_json::json!({}),
+            },
+            ..Default::default()
+        }]))
+    }
+}
+
+/// Recover the number of subjects at risk and the number of events in each time bin from a DPSurvivalCounts release.
+///
+/// Noisy counts are post-processed to be consistent:
+/// counts are non-negative, the number at risk never increases, and events never exceed the number at risk.
+/// The final pair of cells, for times past the last edge, only contributes to the number of subjects at risk.
+///
+/// # Arguments
+/// * `cells` - interleaved censored and event counts for each time bin, as released by DPSurvivalCounts
+///
+/// # Return
+/// The number of subjects at risk and the number of events, for each time bin
+pub fn survival_counts(cells: &[Float]) -> Result<(Vec<Float>, Vec<Float>)> {
+    if cells.is_empty() || cells.len() % 2 == 1 {
+        return Err("cells: must contain a censored and event count for each time bin".into())
+    }
+
+    let cells = cells.iter().map(|v| v.max(0.)).collect::<Vec<Float>>();
+    let num_bins = cells.len() / 2 - 1;
+
+    let mut at_risk = Vec::with_capacity(num_bins);
+    let mut events = Vec::with_capacity(num_bins);
+    let mut remaining: Float = cells.iter().sum();
+
+    cells.iter().tuples().take(num_bins).for_each(|(censored, event)| {
+        let event = event.min(remaining);
+        at_risk.push(remaining);
+        events.push(event);
+        remaining = (remaining - censored - event).max(0.);
+    });
+
+    Ok((at_risk, events))
+}
+
+/// Compute the Kaplan-Meier estimate of the survival function at the end of each time bin.
+///
+/// # Arguments
+/// * `at_risk` - number of subjects at risk at the start of each time bin
+/// * `events` - number of events within each time bin
+///
+/// # Return
+/// The estimated probability of survival past each time bin
+pub fn kaplan_meier(at_risk: &[Float], events: &[Float]) -> Result<Vec<Float>> {
+    if at_risk.len() != events.len() {
+        return Err("at_risk and events must be the same length".into())
+    }
+
+    let mut survival = 1.;
+    Ok(at_risk.iter().zip(events.iter())
+        .map(|(at_risk, events)| {
+            if *at_risk > 0. {
+                survival *= 1. - (events / at_risk).clamp(0., 1.);
+            }
+            survival
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test_dp_survival_counts {
+    use ndarray::Array2;
+
+    use crate::base::{Jagged, Value};
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::components::dp_survival_counts::{kaplan_meier, survival_counts};
+
+    #[test]
+    fn test_dp_survival_counts() {
+        let mut analysis = Analysis::new();
+        let times = analysis.literal()
+            .value(Array2::from_shape_fn((12, 1), |(i, _)| i as f64 * 0.8).into_dyn().into())
+            .value_public(true)
+            .build();
+        let events = analysis.literal()
+            .value(Array2::from_shape_fn((12, 1), |(i, _)| i % 3 != 0).into_dyn().into())
+            .value_public(true)
+            .build();
+        let edges = analysis.literal()
+            .value(Value::Jagged(Jagged::Float(vec![vec![0., 2.5, 5., 7.5, 10.]])))
+            .value_public(true)
+            .build();
+
+        let dp_survival_counts = analysis.dp_survival_counts(times, events, edges, privacy_usage(1., 0.))
+            .build();
+
+        let properties = analysis.properties(dp_survival_counts).unwrap();
+        let properties = properties.array().unwrap();
+        assert!(properties.releasable);
+        // four bins, and a final bin for times past the last edge, each with a censored and event count
+        assert_eq!(properties.num_records, Some(10));
+        assert_eq!(properties.num_columns().unwrap(), 1);
+
+        // the bins compose in parallel, so the entire release only uses the requested budget
+        let usage = crate::compute_privacy_usage(
+            analysis.privacy_definition.clone(), analysis.components.clone(), analysis.release.clone()).unwrap();
+        assert_eq!(usage, privacy_usage(1., 0.)[0]);
+
+        // times before the first edge may not be counted as survivors
+        let late_edges = analysis.literal()
+            .value(Value::Jagged(Jagged::Float(vec![vec![1., 5., 10.]])))
+            .value_public(true)
+            .build();
+        let dp_survival_counts = analysis.dp_survival_counts(times, events, late_edges, privacy_usage(1., 0.))
+            .build();
+        assert!(analysis.properties(dp_survival_counts).unwrap_err().to_string().contains("first bin"));
+    }
+
+    #[test]
+    fn test_survival_counts() {
+        // censored and event counts for three bins, and a final bin past the last edge
+        let cells = [1., 2., 0., 3., 2., 1., 1., 0.];
+        let (at_risk, events) = survival_counts(&cells).unwrap();
+        assert_eq!(at_risk, vec![10., 7., 4.]);
+        assert_eq!(events, vec![2., 3., 1.]);
+
+        // noisy counts are made consistent
+        let cells = [-1., 5., 0., 3., 2., 1., -2., 0.];
+        let (at_risk, events) = survival_counts(&cells).unwrap();
+        assert_eq!(at_risk, vec![11., 6., 3.]);
+        assert_eq!(events, vec![5., 3., 1.]);
+    }
+
+    #[test]
+    fn test_kaplan_meier() {
+        let survival = kaplan_meier(&[10., 7., 4., 0.], &[2., 3., 1., 0.]).unwrap();
+        assert_eq!(survival.len(), 4);
+        assert!((survival[0] - 0.8).abs() < 1e-12);
+        assert!((survival[1] - 0.8 * 4. / 7.).abs() < 1e-12);
+        assert!(survival.windows(2).all(|w| w[0] >= w[1]));
+        assert!(survival.iter().all(|v| (0. ..=1.).contains(v)));
+    }
+}
@@ -0,0 +1,158 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto};
+use crate::base::{IndexKey, NodeProperties, Value};
+use crate::components::{Expandable, Report};
+use crate::errors::*;
+use crate::utilities::{array::get_ith_column, prepend, privacy::spread_privacy_usage};
+use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
+
+impl Expandable for proto::DpTruncatedMean {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        _properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+        let argument_ids = component.arguments();
+
+        let id_data = *argument_ids.get::<IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+        let id_key = *argument_ids.get::<IndexKey>(&"key".into())
+            .ok_or_else(|| Error::from("key must be provided as an argument"))?;
+
+        // truncate the rows of each individual
+        maximum_id += 1;
+        let id_bounded = maximum_id;
+        expansion.computation_graph.insert(id_bounded, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                "data".into() => id_data,
+                "key".into() => id_key
+            ])),
+            variant: Some(proto::component::Variant::BoundContribution(proto::BoundContribution {
+                max_contributions: self.max_contributions
+            })),
+            omit: true,
+            submission: component.submission,
+        });
+        expansion.traversal.push(id_bounded);
+
+        // the plug-in mean releases a dp sum and dp count with split budget, and divides them
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => id_bounded])),
+            variant: Some(proto::component::Variant::DpMean(proto::DpMean {
+                implementation: "plug-in".to_string(),
+                mechanism: self.mechanism.clone(),
+                privacy_usage: self.privacy_usage.clone(),
+                joint: false,
+                resolution: 0.,
+            })),
+            omit: component.omit,
+            submission: component.submission,
+        });
+
+        Ok(expansion)
+    }
+}
+
+impl Report for proto::DpTruncatedMean {
+    fn summarize(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        node_id: u32,
+        component: &proto::Component,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        release: &Value,
+        variable_names: Option<&Vec<base::IndexKey>>,
+    ) -> Result<Option<Vec<JSONRelease>>> {
+        let data_property = properties.get::<base::IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let lower = data_property.lower_float()?;
+        let upper = data_property.upper_float()?;
+
+        let num_columns = data_property.num_columns()?;
+        let privacy_usages = spread_privacy_usage(&self.privacy_usage, num_columns as usize)?;
+
+        let release = release.ref_array()?.ref_float()?;
+
+        (0..num_columns as usize).map(|column_number| {
+            let variable_name = variable_names
+                .and_then(|names| names.get(column_number)).cloned()
+                .unwrap_or_else(|| "[Unknown]".into());
+
+            Ok(JSONRelease {
+                description: "DP release information".to_string(),
+                statistic: "DPTruncatedMean".to_string(),
+                variables: serde_json::json!(variable_name.to_string()),
+                release_info: value_to_json(&get_ith_column(release, column_number)?.into())?,
+                privacy_loss: privacy_usage_to_json(&privacy_usages[column_number].clone()),
+                accuracy: None,
+                submission: component.submission,
+                node_id,
+                postprocess: false,
+                algorithm_info: AlgorithmInfo {
+                    name: "".to_string(),
+                    cite: "".to_string(),
+                    mechanism: self.mechanism.clone(),
+                    argument: serde_json::json!({
+                        "max_contributions": self.max_contributions,
+                        "constraint": {
+                            "lowerbound": lower[column_number],
+                            "upperbound": upper[column_number]
+                        }
+                    })
+                },
+                ..Default::default()
+            })
+        }).collect::<Result<Vec<JSONRelease>>>().map(Some)
+    }
+}
+
+#[cfg(test)]
+mod test_dp_truncated_mean {
+    use ndarray::{arr1, Array2};
+
+    use crate::bindings::Analysis;
+    use crate::proto;
+
+    #[test]
+    fn test_dp_truncated_mean() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| (i % 10) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let key = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| (i / 4) as i64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(arr1(&[0.]).into_dyn().into()).value_public(true).build();
+        let upper = analysis.literal().value(arr1(&[10.]).into_dyn().into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+
+        let dp_truncated_mean = analysis.dp_truncated_mean(clamped, key, 2, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }]).mechanism("Laplace".to_string()).build();
+
+        let properties = analysis.properties(dp_truncated_mean).unwrap();
+        assert!(properties.array().unwrap().releasable);
+
+        // the budget is split between the count and sum
+        let usage = crate::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        let epsilon = match usage.distance.unwrap() {
+            proto::privacy_usage::Distance::Approximate(approximate) => approximate.epsilon
+        };
+        assert!((epsilon - 1.).abs() < 1e-12);
+    }
+}
@@ -6,7 +6,7 @@ use crate::components::{Expandable, Report};
 use crate::errors::*;
 use crate::utilities::{array::get_ith_column, prepend};
 use crate::utilities::json::{AlgorithmInfo, JSONRelease, privacy_usage_to_json, value_to_json};
-use crate::utilities::privacy::spread_privacy_usage;
+use crate::utilities::privacy::{get_automatic_mechanism, spread_privacy_usage};
 
 impl Expandable for proto::DpVariance {
     fn expand_component(
@@ -43,22 +43,26 @@ impl Expandable for proto::DpVariance {
         let mechanism = if self.mechanism.to_lowercase().as_str() == "automatic" {
             let privacy_definition = privacy_definition.as_ref()
                 .ok_or_else(|| Error::from("privacy_definition must be known"))?;
-            if privacy_definition.protect_floating_point
-            { "snapping" } else { "laplace" }.to_string()
+            get_automatic_mechanism(privacy_definition, &self.privacy_usage)
         } else { self.mechanism.to_lowercase() };
 
         let mut arguments = indexmap!["data".into() => id_variance];
         let variant = Some(match mechanism.as_str() {
             "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
-                privacy_usage: self.privacy_usage.clone()
+                privacy_usage: self.privacy_usage.clone(),
+                resolution: 0.
             }),
             "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
                 privacy_usage: self.privacy_usage.clone(),
-                analytic: false
+                analytic: false,
+                joint: false,
+                resolution: 0.
             }),
             "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
                 privacy_usage: self.privacy_usage.clone(),
-                analytic: true
+                analytic: true,
+                joint: false,
+                resolution: 0.
             }),
             "snapping" => {
                 argument_ids.get::<IndexKey>(&"lower".into())
@@ -87,6 +91,7 @@ impl Expandable for proto::DpVariance {
 impl Report for proto::DpVariance {
     fn summarize(
         &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         _public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -138,6 +143,7 @@ impl Report for proto::DpVariance {
                             }
                         }),
                 },
+                ..Default::default()
             });
         }
         Ok(Some(releases))
@@ -1,7 +1,7 @@
 use indexmap::map::IndexMap;
 use itertools::Itertools;
 
-use crate::{base, proto, Warnable};
+use crate::{base, Float, proto, Warnable};
 use crate::base::{ArrayProperties, DataType, IndexKey, NodeProperties, SensitivitySpace, Value, ValueProperties};
 use crate::components::{Component, Expandable, Mechanism, Sensitivity};
 use crate::errors::*;
@@ -29,8 +29,8 @@ impl Component for proto::ExponentialMechanism {
             .ok_or("utilities: missing")?.array()
             .map_err(prepend("utilities:"))?.clone();
 
-        if utilities_property.data_type != DataType::Float {
-            return Err("utilities: data_type must be float".into());
+        if utilities_property.data_type != DataType::Float && utilities_property.data_type != DataType::Int {
+            return Err("utilities: data_type must be numeric".into());
         }
 
         let candidates_property: ArrayProperties = properties
@@ -62,7 +62,7 @@ impl Component for proto::ExponentialMechanism {
             &SensitivitySpace::Exponential)?;
 
         // make sure sensitivities are an f64 array
-        sensitivity_values.array()?.float()?;
+        let sensitivity = sensitivity_values.array()?.float()?;
 
         let output_property = ArrayProperties {
             num_records: Some(1),
@@ -86,11 +86,22 @@ impl Component for proto::ExponentialMechanism {
             .fold1(|l, r| l? + r?)
             .ok_or_else(|| "privacy_usage: must be defined")??;
 
-        let warnings = privacy_usage_check(
+        let mut warnings = privacy_usage_check(
             &privacy_usage,
             output_property.num_records,
             privacy_definition.strict_parameter_checks)?;
 
+        if let proto::component::Variant::Quantile(_) = aggregator.component {
+            if let Some(data_property) = aggregator.properties.get::<IndexKey>(&"data".into()) {
+                warnings.extend(candidate_spacing_check(
+                    privacy_definition.candidate_spacing_ratio,
+                    data_property.array()?,
+                    candidates_property.num_records()?,
+                    sensitivity.iter().cloned().fold(0., Float::max),
+                    &privacy_usage)?);
+            }
+        }
+
         Ok(Warnable(output_property.into(), warnings))
     }
 }
@@ -168,15 +179,105 @@ impl Mechanism for proto::ExponentialMechanism {
         release_usage: Option<&Vec<proto::PrivacyUsage>>,
         properties: &NodeProperties,
     ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
-        let data_property = properties.get::<IndexKey>(&"data".into())
-            .ok_or("data: missing")?.array()
-            .map_err(prepend("data:"))?;
+        let utilities_property = properties.get::<IndexKey>(&"utilities".into())
+            .ok_or("utilities: missing")?.array()
+            .map_err(prepend("utilities:"))?;
 
         Some(release_usage.unwrap_or_else(|| &self.privacy_usage).iter()
             .map(|usage| usage.effective_to_actual(
-                data_property.sample_proportion.unwrap_or(1.),
-                data_property.c_stability,
+                utilities_property.sample_proportion.unwrap_or(1.),
+                utilities_property.c_stability,
                 privacy_definition.group_size))
             .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
     }
 }
+
+
+/// Warn when quantile candidates are spaced more finely than the exponential mechanism can resolve.
+///
+/// With probability 0.95, the released candidate has a utility within `2 * sensitivity * ln(m / 0.05) / epsilon`
+/// ranks of the best candidate, where `m` is the number of candidates.
+/// If the data were spread evenly over the clamp range, each rank would span `range / n`,
+/// while evenly spaced candidates are `range / (m - 1)` apart.
+///
+/// # Arguments
+/// * `candidate_spacing_ratio` - warn when the spacing is less than this fraction of the resolvable accuracy. Zero disables the check
+/// * `data_property` - properties of the data the quantile is computed on
+/// * `num_candidates` - number of candidates
+/// * `sensitivity` - sensitivity of the quantile utilities
+/// * `privacy_usage` - privacy usage of the release
+///
+/// # Return
+/// A warning, if the candidate spacing is finer than the resolvable accuracy.
+pub fn candidate_spacing_check(
+    candidate_spacing_ratio: f64,
+    data_property: &ArrayProperties,
+    num_candidates: i64,
+    sensitivity: Float,
+    privacy_usage: &proto::PrivacyUsage,
+) -> Result<Option<Error>> {
+    // both the range and number of records must be known to relate ranks to the clamp range
+    let (lower, upper, num_records) = match (
+        data_property.lower_float(), data_property.upper_float(), data_property.num_records) {
+        (Ok(lower), Ok(upper), Some(num_records)) => (lower[0], upper[0], num_records),
+        _ => return Ok(None)
+    };
+    if candidate_spacing_ratio <= 0. || num_candidates < 2 || num_records < 1 || upper <= lower {
+        return Ok(None)
+    }
+
+    let epsilon = match privacy_usage.distance.as_ref()
+        .ok_or_else(|| Error::from("distance must be defined"))? {
+        proto::privacy_usage::Distance::Approximate(approximate) => approximate.epsilon
+    };
+
+    let range = upper - lower;
+    let spacing = range / (num_candidates - 1) as f64;
+    let accuracy = 2. * sensitivity * (num_candidates as f64 / 0.05).ln() / epsilon
+        * range / num_records as f64;
+
+    Ok(if spacing < candidate_spacing_ratio * accuracy {
+        Some(format!(
+            "Warning: candidates are spaced {} apart, but the exponential mechanism can only resolve quantiles to within about {} at epsilon = {}. Consider using fewer candidates.",
+            spacing, accuracy, epsilon).into())
+    } else { None })
+}
+
+#[cfg(test)]
+mod test_exponential_mechanism {
+    use ndarray::Array2;
+
+    use crate::bindings::{Analysis, privacy_usage};
+
+    /// warnings from a dp median over 100 records in [0, 10], with evenly spaced candidates
+    fn candidate_spacing_warnings(num_candidates: usize) -> Vec<String> {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.candidate_spacing_ratio = 0.1;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| (i % 11) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let candidates = analysis.literal()
+            .value(Array2::from_shape_fn((num_candidates, 1), |(i, _)| i as f64 * 10. / (num_candidates - 1) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let dp_quantile = analysis.dp_quantile(data, 0.5, privacy_usage(1., 0.))
+            .candidates(candidates)
+            .mechanism("Exponential".to_string())
+            .build();
+
+        let (_, warnings) = crate::get_properties(
+            Some(analysis.privacy_definition), analysis.components, analysis.release, vec![dp_quantile]).unwrap();
+        warnings.iter().map(|warning| format!("{:?}", warning)).collect()
+    }
+
+    #[test]
+    fn test_candidate_spacing_warning() {
+        // a grid of 11 candidates may be resolved by 100 records at epsilon = 1
+        assert!(candidate_spacing_warnings(11).is_empty());
+
+        let warnings = candidate_spacing_warnings(10_001);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("candidates are spaced"), "{}", warnings[0]);
+    }
+}
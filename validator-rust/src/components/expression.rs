@@ -0,0 +1,225 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, Float, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, Nature, NatureContinuous, Value, ValueProperties, Vector1DNull};
+use crate::components::Component;
+use crate::errors::*;
+use crate::utilities::expression::{Expression, Operator};
+use crate::utilities::prepend;
+
+/// Per-column bounds on a value, where `None` is unbounded
+type Bounds = Vec<Option<(Float, Float)>>;
+
+impl Component for proto::Expression {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let expression = Expression::parse(&self.expression)?;
+
+        let variable_properties = expression.variables().into_iter()
+            .map(|name| {
+                let property = properties.get::<IndexKey>(&name.as_str().into())
+                    .ok_or_else(|| Error::from(format!("{}: missing", name)))?.array()
+                    .map_err(prepend(&format!("{}:", name)))?.clone();
+
+                // only releasable data may be postprocessed
+                if !property.releasable {
+                    return Err(format!("{}: must be releasable", name).into())
+                }
+                if !(property.data_type == DataType::Float || property.data_type == DataType::Int) {
+                    return Err(format!("{}: atomic type must be numeric", name).into())
+                }
+                Ok((name, property))
+            })
+            .collect::<Result<IndexMap<String, ArrayProperties>>>()?;
+
+        // arguments with one column or one record are broadcast
+        let broadcast = |lengths: Vec<Option<i64>>, name: &str| -> Result<Option<i64>> {
+            let lengths = lengths.into_iter().collect::<Option<Vec<i64>>>();
+            Ok(match lengths {
+                Some(lengths) => {
+                    let length = lengths.iter().copied().max().unwrap_or(1);
+                    if lengths.iter().any(|v| *v != 1 && *v != length) {
+                        return Err(format!("{} must be the same for all arguments, or broadcastable", name).into())
+                    }
+                    Some(length)
+                }
+                None => None
+            })
+        };
+        let num_columns = broadcast(variable_properties.values()
+            .map(|v| v.num_columns).collect(), "number of columns")?
+            .ok_or_else(|| Error::from("number of columns must be known"))?;
+        let num_records = broadcast(variable_properties.values()
+            .map(|v| v.num_records).collect(), "number of records")?;
+
+        let bounds = expression.evaluate(
+            &|value| Ok(vec![Some((value, value)); num_columns as usize]),
+            &|name| {
+                let property = variable_properties.get(name)
+                    .ok_or_else(|| Error::from(format!("{}: missing", name)))?;
+                let bounds = property.lower_float_option().ok()
+                    .zip(property.upper_float_option().ok())
+                    .map(|(lower, upper)| lower.into_iter().zip(upper)
+                        .map(|(lower, upper)| lower.zip(upper))
+                        .collect::<Bounds>())
+                    .unwrap_or_else(|| vec![None; property.num_columns.unwrap_or(1) as usize]);
+                Ok(broadcast_bounds(bounds, num_columns as usize))
+            },
+            &|bounds| Ok(bounds.into_iter()
+                .map(|bound| bound.map(|(lower, upper)| (-upper, -lower)))
+                .collect()),
+            &|operator, left, right| Ok(left.into_iter().zip(right)
+                .map(|(left, right)| propagate_bounds(operator, left?, right?))
+                .collect()))?;
+
+        let nature = bounds.iter().copied().collect::<Option<Vec<(Float, Float)>>>()
+            .map(|bounds| Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::Float(bounds.iter().map(|(lower, _)| Some(*lower)).collect()),
+                upper: Vector1DNull::Float(bounds.iter().map(|(_, upper)| Some(*upper)).collect()),
+            }));
+
+        Ok(ValueProperties::Array(ArrayProperties {
+            num_records,
+            num_columns: Some(num_columns),
+            // division may produce non-finite values
+            nullity: variable_properties.values().any(|v| v.nullity) || nature.is_none(),
+            releasable: true,
+            c_stability: 1,
+            aggregator: None,
+            nature,
+            data_type: DataType::Float,
+            dataset_id: None,
+            node_id: node_id as i64,
+            is_not_empty: variable_properties.values().all(|v| v.is_not_empty),
+            dimensionality: variable_properties.values()
+                .map(|v| v.dimensionality).max().unwrap_or(Some(0)),
+            group_id: vec![],
+            naturally_ordered: true,
+            sample_proportion: None,
+        }).into())
+    }
+}
+
+fn broadcast_bounds(bounds: Bounds, num_columns: usize) -> Bounds {
+    if bounds.len() == 1 { vec![bounds[0]; num_columns] } else { bounds }
+}
+
+/// Interval arithmetic on the bounds of a single column
+fn propagate_bounds(operator: Operator, left: (Float, Float), right: (Float, Float)) -> Option<(Float, Float)> {
+    let (right_lower, right_upper) = right;
+    let right = match operator {
+        // the denominator may not span zero
+        Operator::Divide if right_lower <= 0. && 0. <= right_upper => return None,
+        Operator::Divide => (1. / right_upper, 1. / right_lower),
+        Operator::Subtract => (-right_upper, -right_lower),
+        _ => right
+    };
+
+    let (lower, upper) = match operator {
+        Operator::Add | Operator::Subtract => (left.0 + right.0, left.1 + right.1),
+        Operator::Multiply | Operator::Divide => {
+            let candidates = [left.0 * right.0, left.0 * right.1, left.1 * right.0, left.1 * right.1];
+            (candidates.iter().copied().fold(Float::INFINITY, Float::min),
+             candidates.iter().copied().fold(Float::NEG_INFINITY, Float::max))
+        }
+    };
+    if lower.is_finite() && upper.is_finite() { Some((lower, upper)) } else { None }
+}
+
+#[cfg(test)]
+mod test_expression {
+    use ndarray::{arr1, arr2};
+
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::base::{IndexKey, ReleaseNode, Value};
+
+    /// add an Expression component over the named arguments
+    fn expression(analysis: &mut Analysis, text: &str, arguments: &[(&str, u32)]) -> u32 {
+        let builder = analysis.expression(text.to_string());
+        arguments.iter().for_each(|(name, id)| builder.component.insert_argument(&IndexKey::from(*name), *id));
+        builder.build()
+    }
+
+    fn public_literal(analysis: &mut Analysis, value: Value) -> u32 {
+        analysis.literal().value(value).value_public(true).build()
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut analysis = Analysis::new();
+        let a = public_literal(&mut analysis, arr2(&[[1., 4.], [3., 6.]]).into_dyn().into());
+        let b = public_literal(&mut analysis, 2.0.into());
+        let c = public_literal(&mut analysis, arr2(&[[0.5, 2.]]).into_dyn().into());
+        let result = expression(&mut analysis, "(a - b) / c", &[("a", a), ("b", b), ("c", c)]);
+
+        let properties = analysis.properties(result).unwrap().array().unwrap().clone();
+        assert!(properties.releasable);
+        assert_eq!(properties.num_columns, Some(2));
+        assert_eq!(properties.num_records, Some(2));
+        assert_eq!(properties.lower_float().unwrap(), vec![-2., 1.]);
+        assert_eq!(properties.upper_float().unwrap(), vec![2., 2.]);
+
+        // the denominator spans zero, so bounds are lost
+        let zero = public_literal(&mut analysis, arr1(&[0., 1.]).into_dyn().into());
+        let a = public_literal(&mut analysis, arr1(&[1., 4.]).into_dyn().into());
+        let result = expression(&mut analysis, "a / z", &[("a", a), ("z", zero)]);
+        assert!(analysis.properties(result).unwrap().array().unwrap().nature.is_none());
+    }
+
+    #[test]
+    fn test_private() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr1(&[1., 2.]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let result = expression(&mut analysis, "2 * x", &[("x", data)]);
+        assert!(analysis.properties(result).is_err());
+
+        // every variable must be an argument
+        let a = public_literal(&mut analysis, 1.0.into());
+        let result = expression(&mut analysis, "a + y", &[("a", a)]);
+        assert!(analysis.properties(result).is_err());
+    }
+
+    #[test]
+    fn test_postprocess() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[1.], [2.], [3.], [4.]]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let data = analysis.to_float(data).build();
+        let lower = public_literal(&mut analysis, 0.0.into());
+        let upper = public_literal(&mut analysis, 4.0.into());
+        let number_rows = public_literal(&mut analysis, 4.into());
+        let number_columns = public_literal(&mut analysis, 1.into());
+        let data = analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .lower(lower).upper(upper)
+            .build();
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+        let data = analysis.impute(data).lower(lower).upper(upper).build();
+        let mean = analysis.dp_mean(data, privacy_usage(1., 0.))
+            .mechanism("Laplace".to_string()).build();
+        let two = public_literal(&mut analysis, 2.0.into());
+        let result = expression(&mut analysis, "(m - 1) / two", &[("m", mean), ("two", two)]);
+
+        analysis.release.insert(mean, ReleaseNode {
+            value: Value::from(2.5),
+            privacy_usages: Some(privacy_usage(1., 0.)),
+            public: true
+        });
+        analysis.release.insert(result, ReleaseNode::new(Value::from(0.75)));
+
+        // the expression does not consume budget beyond the mean
+        let usage = crate::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        assert_eq!(crate::utilities::privacy::get_epsilon(&usage).unwrap(), 1.);
+    }
+}
@@ -53,4 +53,45 @@ impl Component for proto::Filter {
 
         Ok(ValueProperties::Array(data_property).into())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_filter {
+    use ndarray::{arr0, arr2};
+
+    use crate::bindings::Analysis;
+
+    #[test]
+    fn test_predicate_mask() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[1., 2.], [6., 7.], [9., 10.]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let a = analysis.literal()
+            .value(arr2(&[[1.], [6.], [9.]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let b = analysis.literal()
+            .value(arr2(&[["x"], ["x"], ["y"]]).mapv(|v| v.to_string()).into_dyn().into())
+            .value_public(true)
+            .build();
+        let five = analysis.literal().value(arr0(5.).into_dyn().into()).value_public(true).build();
+        let x = analysis.literal().value(arr0("x".to_string()).into_dyn().into()).value_public(true).build();
+
+        let greater_than = analysis.greater_than(a, five).build();
+        let equal = analysis.equal(b, x).build();
+        let mask = analysis.logical_and(greater_than, equal).build();
+        let filtered = analysis.filter(data, mask).build();
+
+        let properties = analysis.properties(filtered).unwrap();
+        let properties = properties.array().unwrap();
+        assert_eq!(properties.num_records, None);
+        assert_eq!(properties.dataset_id, Some(filtered as i64));
+        assert_eq!(properties.num_columns, Some(2));
+
+        // the mask must be boolean
+        let filtered = analysis.filter(data, a).build();
+        assert!(analysis.properties(filtered).is_err());
+    }
+}
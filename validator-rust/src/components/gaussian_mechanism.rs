@@ -7,6 +7,7 @@ use crate::{base, proto, Warnable};
 use crate::base::{DataType, IndexKey, NodeProperties, SensitivitySpace, Value, ValueProperties};
 use crate::components::{Accuracy, Mechanism, Sensitivity};
 use crate::components::{Component, Expandable};
+use crate::components::laplace_mechanism::get_grid_sensitivity;
 use crate::errors::*;
 use crate::utilities::{expand_mechanism, prepend};
 use crate::utilities::privacy::{get_delta, get_epsilon, privacy_usage_check, spread_privacy_usage};
@@ -37,11 +38,19 @@ impl Component for proto::GaussianMechanism {
         if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
             return Err("data: atomic type must be numeric".into());
         }
+
+        if self.resolution < 0. || !self.resolution.is_finite() {
+            return Err("resolution: must be non-negative and finite".into())
+        }
+
+        if self.resolution > 0. && privacy_definition.protect_elapsed_time {
+            return Err("resolution: the discrete gaussian is sampled by rejection, so it cannot run in constant time".into())
+        }
         let aggregator = data_property.aggregator.clone()
             .ok_or_else(|| Error::from("aggregator: missing"))?;
 
         // sensitivity must be computable
-        aggregator.component.compute_sensitivity(
+        let sensitivity = aggregator.component.compute_sensitivity(
             privacy_definition,
             &aggregator.properties,
             &SensitivitySpace::KNorm(2))?.array()?.cast_float()?;
@@ -71,6 +80,26 @@ impl Component for proto::GaussianMechanism {
             return Err("delta: may not be zero".into())
         }
 
+        if self.resolution > 0. {
+            // a joint sensitivity is shared by every statistic when rounded to the grid
+            let num_statistics = sensitivity.len();
+            let usages = spread_privacy_usage(
+                &self.privacy_usage, if self.joint { 1 } else { sensitivity.gencolumns().into_iter().count() })?;
+
+            sensitivity.gencolumns().into_iter()
+                .zip(usages.iter().cycle())
+                .try_for_each(|(sensitivity_col, usage)| {
+                    let (epsilon, delta) = (get_epsilon(usage)?, get_delta(usage)?);
+                    let count = if self.joint { num_statistics } else { sensitivity_col.len() };
+                    sensitivity_col.iter()
+                        .map(|sensitivity| get_grid_sensitivity(*sensitivity, self.resolution, count, 2))
+                        // zero-sensitivity statistics are released without noise
+                        .filter(|sensitivity| *sensitivity > 0.)
+                        .try_for_each(|sensitivity| check_discrete_gaussian_scale(
+                            get_gaussian_scale(epsilon, delta, sensitivity, self.analytic)))
+                })?;
+        }
+
         data_property.releasable = true;
         data_property.aggregator = None;
 
@@ -136,6 +165,14 @@ impl Accuracy for proto::GaussianMechanism {
             .map(|sensitivity_col| sensitivity_col.into_iter().copied().fold1(|l, r| l.max(r)).unwrap())
             .collect();
 
+        if self.joint {
+            return Err(Error::from("converting to privacy usage is not implemented for the joint gaussian"))
+        }
+
+        if self.resolution > 0. {
+            return Err(Error::from("converting to privacy usage is not implemented for the discrete gaussian"))
+        }
+
         let usages = spread_privacy_usage(&self.privacy_usage, sensitivities.len())?;
         let delta = usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?;
         let iter = izip!(sensitivities.into_iter(), accuracies.values.iter(), delta.into_iter());
@@ -163,20 +200,36 @@ impl Accuracy for proto::GaussianMechanism {
         mut public_arguments: IndexMap<base::IndexKey, &Value>,
         alpha: f64,
     ) -> Result<Option<Vec<proto::Accuracy>>> {
-        // take max sensitivity of each column
-        let sensitivities: Vec<_> = public_arguments.remove(&IndexKey::from("sensitivity"))
+        let sensitivity = public_arguments.remove(&IndexKey::from("sensitivity"))
             .ok_or_else(|| Error::from("sensitivity: missing in accuracy"))?.clone()
-            .array()?.cast_float()?
+            .array()?.cast_float()?;
+        // a joint sensitivity is shared by every statistic when rounded to the grid
+        let num_statistics = sensitivity.len();
+
+        // take max sensitivity of each column
+        let sensitivities: Vec<_> = sensitivity
             .gencolumns().into_iter()
-            .map(|sensitivity_col| sensitivity_col.into_iter().copied().fold1(|l, r| l.max(r)).unwrap())
+            .map(|sensitivity_col| (
+                sensitivity_col.into_iter().copied().fold1(|l, r| l.max(r)).unwrap(),
+                if self.joint { num_statistics } else { sensitivity_col.len() }))
             .collect();
 
-        let usages = spread_privacy_usage(&self.privacy_usage, sensitivities.len())?;
+        // the joint gaussian spends the entire usage on every column
+        let usages = if self.joint {
+            vec![spread_privacy_usage(&self.privacy_usage, 1)?.remove(0); sensitivities.len()]
+        } else {
+            spread_privacy_usage(&self.privacy_usage, sensitivities.len())?
+        };
         let epsilons = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
         let deltas = usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?;
         let iter = izip!(sensitivities.into_iter(), epsilons.into_iter(), deltas.into_iter());
 
-        Ok(Some(iter.map(|(sensitivity, epsilon, delta)| {
+        Ok(Some(iter.map(|((sensitivity, count), epsilon, delta)| {
+            // the discrete gaussian is calibrated to the sensitivity in grid units
+            let sensitivity = if self.resolution > 0. {
+                get_grid_sensitivity(sensitivity, self.resolution, count, 2)
+            } else { sensitivity };
+
             let sigma: f64 = if self.analytic {
                 get_analytic_gaussian_sigma(epsilon, delta, sensitivity as f64)
             } else {
@@ -184,7 +237,13 @@ impl Accuracy for proto::GaussianMechanism {
             };
 
             proto::Accuracy {
-                value: sigma * 2.0_f64.sqrt() * erf::erf_inv(1.0_f64 - alpha),
+                value: if self.resolution > 0. {
+                    // the discrete gaussian is subgaussian, so P(|Z| >= t) <= 2 exp(-t^2 / 2 sigma^2) (Canonne et al., 2020).
+                    // The noise is integral, and rounding to the grid contributes up to half a grid point
+                    ((sigma * (2. * (2. / alpha).ln()).sqrt()).floor() + 0.5) * self.resolution
+                } else {
+                    sigma * 2.0_f64.sqrt() * erf::erf_inv(1.0_f64 - alpha)
+                },
                 alpha,
             }
         }).collect()))
@@ -324,6 +383,36 @@ pub fn get_analytic_gaussian_sigma(epsilon: f64, delta: f64, sensitivity: f64) -
     alpha * sensitivity / (2. * epsilon).sqrt()
 }
 
+/// Compute the scale of the gaussian mechanism.
+///
+/// # Arguments
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `delta` - Additive privacy loss parameter.
+/// * `sensitivity` - Upper bound on the L2 sensitivity of the function you want to privatize.
+/// * `analytic` - Whether to calibrate the scale with the analytic gaussian.
+pub fn get_gaussian_scale(epsilon: f64, delta: f64, sensitivity: f64, analytic: bool) -> f64 {
+    if analytic {
+        get_analytic_gaussian_sigma(epsilon, delta, sensitivity)
+    } else {
+        sensitivity * (2. * (1.25 / delta).ln()).sqrt() / epsilon
+    }
+}
+
+/// Check that the discrete gaussian is sampled with a scale of at least one grid point.
+///
+/// The discrete gaussian only matches the privacy guarantee of the continuous gaussian
+/// up to a term that is negligible when the scale is at least one.
+/// See [Canonne, Kamath & Steinke (2020)](https://arxiv.org/abs/2004.00010).
+///
+/// # Arguments
+/// * `scale` - Scale of the discrete gaussian, in units of the resolution.
+pub fn check_discrete_gaussian_scale(scale: f64) -> Result<()> {
+    if scale < 1. {
+        return Err(format!("resolution: the discrete gaussian scale ({}) must be at least one grid point. Use a finer resolution or a smaller privacy usage", scale).into())
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod test_analytic_gaussian {
     use crate::components::gaussian_mechanism::get_analytic_gaussian_sigma;
@@ -332,4 +421,117 @@ mod test_analytic_gaussian {
     fn test_analytic_gaussian_sigma() {
         println!("{:?}", get_analytic_gaussian_sigma(0.5, 1E-10, 1.))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_gaussian_mechanism {
+    use indexmap::IndexMap;
+    use ndarray::{arr1, arr2, Array2};
+
+    use crate::base::{IndexKey, SensitivitySpace, Value, ValueProperties};
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::components::Accuracy;
+    use crate::proto;
+    use crate::utilities::expand_mechanism;
+
+    /// Sum of the accuracies of a gaussian mechanism over three columns of means with shared bounds
+    fn total_accuracy(joint: bool) -> f64 {
+        let usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 0.9, delta: 1e-6
+            }))
+        }];
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 3), |(i, j)| (i + j) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(arr1(&[0., 0., 0.]).into_dyn().into()).value_public(true).build();
+        let upper = analysis.literal().value(arr1(&[12., 12., 12.]).into_dyn().into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let mean = analysis.mean(clamped).build();
+        let gaussian = analysis.gaussian_mechanism(mean, usage.clone())
+            .analytic(false).joint(joint)
+            .build();
+
+        let mut properties = IndexMap::<IndexKey, ValueProperties>::new();
+        properties.insert("data".into(), analysis.properties(mean).unwrap());
+        let expansion = expand_mechanism(
+            &SensitivitySpace::KNorm(2),
+            &Some(analysis.privacy_definition.clone()),
+            &usage,
+            analysis.components.get(&gaussian).unwrap(),
+            &properties,
+            gaussian, 1000).unwrap();
+
+        let noise_component = expansion.computation_graph.get(&gaussian).unwrap();
+        let variant = match noise_component.variant.as_ref().unwrap() {
+            proto::component::Variant::GaussianMechanism(variant) => variant.clone(),
+            _ => panic!("expected a gaussian mechanism")
+        };
+        // the joint gaussian spends the entire usage on all columns
+        assert_eq!(variant.privacy_usage.len(), if joint { 1 } else { 3 });
+
+        let sensitivity = &expansion.releases.values().next().unwrap().value;
+        let mut public_arguments = IndexMap::new();
+        public_arguments.insert(IndexKey::from("sensitivity"), sensitivity);
+        variant.privacy_usage_to_accuracy(public_arguments, 0.05).unwrap().unwrap()
+            .iter().map(|accuracy| accuracy.value).sum()
+    }
+
+    #[test]
+    fn test_discrete_scale() {
+        let release = |epsilon: f64| {
+            let mut analysis = Analysis::new();
+            analysis.privacy_definition.protect_floating_point = false;
+            let data = analysis.literal()
+                .value(arr2(&[[0.], [1.], [2.]]).into_dyn().into())
+                .value_public(true)
+                .build();
+            let lower = analysis.literal().value(arr1(&[0.]).into_dyn().into()).value_public(true).build();
+            let upper = analysis.literal().value(arr1(&[1.]).into_dyn().into()).value_public(true).build();
+            let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+            let sum = analysis.sum(clamped).build();
+            let gaussian = analysis.gaussian_mechanism(sum, privacy_usage(epsilon, 1e-6))
+                .analytic(true).resolution(0.5)
+                .build();
+            analysis.properties(gaussian)
+        };
+
+        release(1.).unwrap();
+        // a large epsilon calibrates the discrete gaussian to less than one grid point of noise
+        assert!(format!("{:?}", release(20.).unwrap_err()).contains("at least one grid point"));
+    }
+
+    #[test]
+    fn test_joint_calibration() {
+        assert!(total_accuracy(true) < total_accuracy(false));
+    }
+
+    #[test]
+    fn test_accuracy_resolution() {
+        let sensitivity: Value = arr2(&[[1.]]).into_dyn().into();
+        let arguments = indexmap![IndexKey::from("sensitivity") => &sensitivity];
+        let mechanism = |resolution| proto::GaussianMechanism {
+            privacy_usage: vec![proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                    epsilon: 1., delta: 1e-6
+                }))
+            }],
+            analytic: false,
+            joint: false,
+            resolution
+        };
+
+        let continuous = mechanism(0.).privacy_usage_to_accuracy(arguments.clone(), 0.05).unwrap().unwrap()[0].value;
+        let discrete = mechanism(0.25).privacy_usage_to_accuracy(arguments.clone(), 0.05).unwrap().unwrap()[0].value;
+        // the release is off by a whole number of grid points, plus up to half a grid point of rounding
+        assert_eq!((discrete / 0.25).fract(), 0.5);
+        assert!(continuous < discrete);
+
+        assert!(mechanism(0.25).accuracy_to_privacy_usage(
+            &proto::Accuracies { values: vec![proto::Accuracy { value: discrete, alpha: 0.05 }] }, arguments).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto};
+use crate::base::{IndexKey, Value};
+use crate::components::Expandable;
+use crate::errors::*;
+
+impl Expandable for proto::GroupBy {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        _properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+        let argument_ids = component.arguments();
+
+        let id_data = *argument_ids.get::<IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+        let id_by = *argument_ids.get::<IndexKey>(&"by".into())
+            .ok_or_else(|| Error::from("by must be provided as an argument"))?;
+
+        let variant = match self.statistic.to_lowercase().as_str() {
+            "count" => proto::component::Variant::DpCount(proto::DpCount {
+                distinct: false,
+                mechanism: self.mechanism.clone(),
+                privacy_usage: self.privacy_usage.clone(),
+            }),
+            "sum" => proto::component::Variant::DpSum(proto::DpSum {
+                mechanism: self.mechanism.clone(),
+                privacy_usage: self.privacy_usage.clone(),
+                joint: false,
+            }),
+            // the size of each group is unknown, so the mean is estimated from a dp sum and a dp count
+            "mean" => proto::component::Variant::DpMean(proto::DpMean {
+                implementation: "plug-in".to_string(),
+                mechanism: self.mechanism.clone(),
+                privacy_usage: self.privacy_usage.clone(),
+                joint: false,
+                resolution: 0.,
+            }),
+            _ => return Err("statistic: must be one of [count, sum, mean]".into())
+        };
+
+        // split the rows by the public categories of the key
+        maximum_id += 1;
+        let id_partition = maximum_id;
+        expansion.computation_graph.insert(id_partition, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                "data".into() => id_data,
+                "by".into() => id_by
+            ])),
+            variant: Some(proto::component::Variant::Partition(proto::Partition {})),
+            omit: true,
+            submission: component.submission,
+        });
+        expansion.traversal.push(id_partition);
+
+        // the statistic is mapped over each partition.
+        // Partitions are disjoint, so by parallel composition, each group may use the full privacy usage
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => id_partition])),
+            variant: Some(variant),
+            omit: component.omit,
+            submission: component.submission,
+        });
+        expansion.traversal.push(component_id);
+
+        Ok(expansion)
+    }
+}
+
+#[cfg(test)]
+mod test_group_by {
+    use ndarray::{arr1, Array2};
+
+    use crate::bindings::Analysis;
+    use crate::proto;
+
+    fn analysis_group_by(statistic: &str) -> (Analysis, u32) {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| (i % 10) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        // string keys of public data are inferred to be categorical
+        let by = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| ["a", "b", "c"][i % 3].to_string()).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(arr1(&[0.]).into_dyn().into()).value_public(true).build();
+        let upper = analysis.literal().value(arr1(&[10.]).into_dyn().into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+
+        let group_by = analysis.group_by(clamped, by, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }]).statistic(statistic.to_string()).mechanism("Laplace".to_string()).build();
+        (analysis, group_by)
+    }
+
+    #[test]
+    fn test_total_usage() {
+        for statistic in &["count", "sum", "mean"] {
+            let (analysis, group_by) = analysis_group_by(statistic);
+
+            let properties = analysis.properties(group_by).unwrap();
+            // one release for each of the three keys
+            assert_eq!(properties.partitions().unwrap().children.len(), 3);
+
+            // the groups are disjoint partitions, so the total usage is the configured usage
+            let usage = crate::compute_privacy_usage(
+                analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+            let epsilon = match usage.distance.unwrap() {
+                proto::privacy_usage::Distance::Approximate(approximate) => approximate.epsilon
+            };
+            assert!((epsilon - 1.).abs() < 1e-12, "{}: epsilon {} should be 1", statistic, epsilon);
+        }
+    }
+
+    #[test]
+    fn test_statistic() {
+        let (analysis, group_by) = analysis_group_by("median");
+        assert!(analysis.properties(group_by).is_err());
+    }
+}
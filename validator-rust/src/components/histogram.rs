@@ -1,9 +1,9 @@
 use crate::errors::*;
 
-use crate::{proto, Warnable, base};
+use crate::{proto, Warnable, base, Float};
 
 use crate::components::{Component, Sensitivity, Expandable};
-use crate::base::{IndexKey, Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, NatureContinuous, Nature, Vector1DNull, Jagged};
+use crate::base::{IndexKey, Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, NatureContinuous, NatureCategorical, Nature, Vector1DNull, Jagged, ArrayProperties};
 use crate::utilities::{prepend, get_literal};
 use ndarray::{arr1, Array};
 use indexmap::map::IndexMap;
@@ -31,13 +31,21 @@ impl Component for proto::Histogram {
             return Err("data_type must be known".into())
         }
 
-        let categories = data_property.categories()?;
+        let categories = get_categories(&data_property)?;
 
         if categories.num_columns() != 1 {
             return Err("data must contain one column".into())
         }
         let num_columns = data_property.num_columns()?;
 
+        // boolean data without categories is histogrammed over the inferred categories
+        let mut properties = properties;
+        if data_property.categories().is_err() {
+            let mut categorical_property = data_property.clone();
+            categorical_property.nature = Some(Nature::Categorical(NatureCategorical { categories: categories.clone() }));
+            properties.insert("data".into(), ValueProperties::Array(categorical_property));
+        }
+
         // save a snapshot of the state when aggregating
         data_property.aggregator = Some(AggregatorProperties::new(
             proto::component::Variant::Histogram(self.clone()),
@@ -97,7 +105,9 @@ impl Expandable for proto::Histogram {
                 let id_digitize = maximum_id;
                 expansion.computation_graph.insert(id_digitize, proto::Component {
                     arguments: Some(proto::ArgumentNodeIds::new(arguments)),
-                    variant: Some(proto::component::Variant::Digitize(proto::Digitize {})),
+                    variant: Some(proto::component::Variant::Digitize(proto::Digitize {
+                        out_of_range: "null".to_string()
+                    })),
                     omit: true,
                     submission: component.submission,
                 });
@@ -131,14 +141,11 @@ impl Expandable for proto::Histogram {
                     .ok_or("data: missing")?.array()
                     .map_err(prepend("data:"))?.clone();
 
-                if data_property.categories().is_err() {
-                    return Err("either edges or categories must be supplied".into())
-                }
+                let categories = get_categories(&data_property)
+                    .map_err(|_| Error::from("either edges or categories must be supplied"))?;
 
                 maximum_id += 1;
                 let id_categories = maximum_id;
-                let categories = properties.get::<IndexKey>(&"data".into())
-                    .ok_or("data: missing")?.array()?.categories()?;
                 let value = match categories {
                     Jagged::Int(jagged) => arr1(&jagged[0]).into_dyn().into(),
                     Jagged::Float(jagged) => arr1(&jagged[0]).into_dyn().into(),
@@ -161,6 +168,15 @@ impl Expandable for proto::Histogram {
     }
 }
 
+/// Retrieve the categories of the data, or infer `{false, true}` for non-null boolean data.
+fn get_categories(data_property: &ArrayProperties) -> Result<Jagged> {
+    match data_property.categories() {
+        Err(_) if data_property.data_type == DataType::Bool && !data_property.nullity =>
+            Ok(Jagged::Bool(vec![vec![false, true]; data_property.num_columns()? as usize])),
+        categories => categories
+    }
+}
+
 
 impl Sensitivity for proto::Histogram {
     /// Histogram sensitivities [are backed by the the proofs here](https://github.com/opendp/smartnoise-core/blob/master/whitepapers/sensitivities/counts/counts.pdf).
@@ -219,18 +235,157 @@ impl Sensitivity for proto::Histogram {
                     // two categories, known N
                     (AddRemove, 2, Some(_)) => wrap!(1),
 
-                    // over two categories, N either known or unknown. Record may switch from one bin to another.
+                    // over two categories, N either known or unknown. Record may switch from one bin to another,
+                    //     so two bins change by one: the L1 distance is 2, and the L2 distance is sqrt(2)
                     (Substitute, _, _) => match k {
                         1 => wrap!(2),
                         2 => wrap!(2.0_f64.sqrt()),
                         _ => Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
-                    } ,
-                    // over two categories, N either known or unknown. Only one bin may be edited.
-                    (AddRemove, _, _) => wrap!(1),
+                    },
+                    // over two categories, N either known or unknown. Only one bin may be edited,
+                    //     so the sensitivity is 1 in both L1 and L2
+                    (AddRemove, _, _) => match k {
+                        1 | 2 => wrap!(1),
+                        _ => Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
+                    },
                 }
 
             },
-            _ => Err("Histogram sensitivity is only implemented for KNorm".into())
+            // adding, removing or substituting a record changes the count of any one category by at most one
+            SensitivitySpace::Exponential =>
+                Ok(Array::from(vec![1. as Float; data_property.num_columns()? as usize]).into_dyn().into()),
+            _ => Err("Histogram sensitivity is only implemented for KNorm and Exponential".into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_histogram {
+    use indexmap::IndexMap;
+    use ndarray::{arr0, arr1, arr2};
+
+    use crate::base::{IndexKey, SensitivitySpace, ValueProperties};
+    use crate::bindings::Analysis;
+    use crate::components::{Component, Expandable, Sensitivity};
+    use crate::proto;
+    use crate::proto::privacy_definition::Neighboring;
+    use crate::components::literal::test_literal;
+    use crate::components::resize::test_resize;
+    use crate::base::test_data;
+
+    fn analysis_categorical(neighboring: Neighboring) -> (Analysis, u32) {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.neighboring = neighboring as i32;
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(arr2(&[["a"], ["b"], ["c"], ["a"]]).mapv(|v| v.to_string()).into_dyn().into())
+            .value_public(true)
+            .build();
+        (analysis, data)
+    }
+
+    fn sensitivity(neighboring: Neighboring, k: u32) -> Vec<f64> {
+        let (analysis, data) = analysis_categorical(neighboring);
+        let mut properties = IndexMap::new();
+        properties.insert(IndexKey::from("data"), analysis.properties(data).unwrap());
+
+        proto::Histogram {}
+            .compute_sensitivity(&analysis.privacy_definition, &properties, &SensitivitySpace::KNorm(k)).unwrap()
+            .array().unwrap().cast_float().unwrap()
+            .iter().copied().collect()
+    }
+
+    #[test]
+    fn test_sensitivity() {
+        // adding or removing a record changes one bin
+        assert_eq!(sensitivity(Neighboring::AddRemove, 1), vec![1.; 3]);
+        assert_eq!(sensitivity(Neighboring::AddRemove, 2), vec![1.; 3]);
+
+        // substituting a record moves it from one bin to another
+        assert_eq!(sensitivity(Neighboring::Substitute, 1), vec![2.; 3]);
+        assert_eq!(sensitivity(Neighboring::Substitute, 2), vec![2_f64.sqrt(); 3]);
+    }
+
+    #[test]
+    fn test_gaussian_histogram() {
+        for neighboring in &[Neighboring::AddRemove, Neighboring::Substitute] {
+            let (mut analysis, data) = analysis_categorical(*neighboring);
+            let lower = analysis.literal().value(arr0(0).into_dyn().into()).value_public(true).build();
+            let inclusive_left = analysis.literal().value(arr0(true).into_dyn().into()).value_public(true).build();
+            let dp_histogram = analysis.dp_histogram(data, vec![proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                    epsilon: 1., delta: 1e-6
+                }))
+            }]).lower(lower).inclusive_left(inclusive_left).mechanism("Gaussian".to_string()).build();
+
+            let properties = analysis.properties(dp_histogram).unwrap();
+            assert!(properties.array().unwrap().releasable);
         }
     }
+
+    /// private integer data, clamped to `[0, upper]`, and histogrammed without categories
+    fn integer_range_histogram(upper: i64, max_categories: Option<u32>) -> crate::errors::Result<ValueProperties> {
+        let (mut analysis, data) = test_resize::utilities::analysis_i64_cont(
+            test_data::array1d_i64_10_uniform(), 10.into(), Some(0.into()), Some(upper.into()));
+
+        let count_lower = analysis.literal().value(arr0(0).into_dyn().into()).value_public(true).build();
+        let inclusive_left = analysis.literal().value(arr0(true).into_dyn().into()).value_public(true).build();
+        let mut builder = analysis.dp_histogram(data, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }]).lower(count_lower).inclusive_left(inclusive_left);
+        if let Some(max_categories) = max_categories {
+            builder = builder.max_categories(max_categories);
+        }
+        let dp_histogram = builder.build();
+        analysis.properties(dp_histogram)
+    }
+
+    #[test]
+    fn test_integer_range_histogram() {
+        // one bin for each integer in [0, 9]
+        let properties = integer_range_histogram(9, None).unwrap();
+        let properties = properties.array().unwrap();
+        assert!(properties.releasable);
+        assert_eq!(properties.num_records, Some(10));
+
+        // the range is capped
+        let capped = |upper: i64, max_categories: Option<u32>| format!("{:?}",
+            integer_range_histogram(upper, max_categories).unwrap_err()).contains("max_categories");
+        assert!(capped(9, Some(5)));
+        assert!(capped(1_000_000, None));
+    }
+
+    #[test]
+    fn test_bool_histogram() {
+        let (analysis, data) = test_literal::analysis_literal(test_data::array1d_bool_10_uniform(), true);
+
+        // discard the categories inferred from the public values
+        let mut data_property = analysis.properties(data).unwrap().array().unwrap().clone();
+        data_property.nature = None;
+        let mut properties = IndexMap::new();
+        properties.insert(IndexKey::from("data"), ValueProperties::Array(data_property));
+
+        let histogram_property = proto::Histogram {}
+            .propagate_property(&None, IndexMap::new(), properties.clone(), data).unwrap()
+            .0.array().unwrap().clone();
+        assert_eq!(histogram_property.num_records, Some(2));
+
+        // the inferred categories are carried into the aggregator for the sensitivity computation
+        let aggregator = histogram_property.aggregator.unwrap();
+        let sensitivity = aggregator.component
+            .compute_sensitivity(&analysis.privacy_definition, &aggregator.properties, &SensitivitySpace::KNorm(1)).unwrap()
+            .array().unwrap().cast_float().unwrap();
+        assert_eq!(sensitivity.shape(), &[2, 1]);
+
+        let expansion = proto::Histogram {}
+            .expand_component(&None, &proto::Component {
+                arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => data])),
+                variant: Some(proto::component::Variant::Histogram(proto::Histogram {})),
+                omit: false,
+                submission: 0,
+            }, &IndexMap::new(), &properties, 1, 1).unwrap();
+        assert_eq!(expansion.releases.get(&2).unwrap().value, arr1(&[false, true]).into_dyn().into());
+    }
 }
@@ -5,7 +5,7 @@ use crate::base::{DataType, IndexKey, Nature, NatureContinuous, Value, ValueProp
 use crate::components::{Component, Expandable};
 use crate::errors::*;
 use crate::proto;
-use crate::utilities::{get_argument, get_literal, prepend, standardize_categorical_argument, standardize_null_candidates_argument};
+use crate::utilities::{get_argument, get_literal, inferred_argument_warning, prepend, standardize_categorical_argument, standardize_null_candidates_argument};
 use crate::utilities::inference::infer_property;
 
 impl Component for proto::Impute {
@@ -52,7 +52,7 @@ impl Component for proto::Impute {
 
             data_property.nature = match data_property.nature {
                 Some(Nature::Categorical(NatureCategorical { categories: prior })) => Some(Nature::Categorical(NatureCategorical {
-                    categories: match (prior, categories.clone().jagged()?, null_values) {
+                    categories: Jagged::deduplicate(&match (prior, categories.clone().jagged()?, null_values) {
                         (Jagged::Int(prior), Jagged::Int(categories), Jagged::Int(nulls)) =>
                             standardize_categorical_argument(prior, num_columns)?.into_iter()
                                 .zip(standardize_categorical_argument(categories, num_columns)?.into_iter())
@@ -75,7 +75,7 @@ impl Component for proto::Impute {
                                     .filter(|p| !nulls.contains(p)).chain(cands).collect::<Vec<_>>())
                                 .collect::<Vec<_>>().into(),
                         _ => return Err("categories may not be float".into())
-                    }
+                    })?
                 })),
                 _ => None
             };
@@ -176,6 +176,7 @@ impl Expandable for proto::Impute {
                 expansion.properties.insert(id_lower, infer_property(&release.value, None, id_lower)?);
                 expansion.releases.insert(id_lower, release);
                 component.insert_argument(&"lower".into(), id_lower);
+                expansion.warnings.push(inferred_argument_warning("lower"));
             }
 
             if !properties.contains_key::<IndexKey>(&"upper".into()) {
@@ -188,6 +189,7 @@ impl Expandable for proto::Impute {
                 expansion.properties.insert(id_upper, infer_property(&release.value, None, id_upper)?);
                 expansion.releases.insert(id_upper, release);
                 component.insert_argument(&"upper".into(), id_upper);
+                expansion.warnings.push(inferred_argument_warning("upper"));
             }
         }
 
@@ -385,4 +387,25 @@ pub mod test_impute {
         array1d_bool_0,
         array1d_bool_10_uniform,
     );
+
+    #[test]
+    fn test_categorical_nature() {
+        use crate::base::{Jagged, Nature, Value};
+
+        let categories = |values: Vec<&str>| Some(Value::Jagged(
+            vec![values.into_iter().map(String::from).collect::<Vec<String>>()].into()));
+
+        // nulls are replaced by candidates, and candidates already in the prior are not repeated
+        let (analysis, imputed) = utilities::analysis_string_cat(
+            test_data::array1d_string_10_uniform(),
+            categories(vec!["a", "b", "c", "d"]),
+            categories(vec!["e"]));
+        match analysis.properties(imputed).unwrap().array().unwrap().nature.as_ref().unwrap() {
+            Nature::Categorical(nature) => match &nature.categories {
+                Jagged::Str(categories) => assert_eq!(categories[0], vec!["a", "b", "c", "d"]),
+                _ => panic!("categories must be strings")
+            },
+            _ => panic!("nature must be categorical")
+        }
+    }
 }
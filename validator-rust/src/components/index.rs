@@ -1,13 +1,13 @@
 use crate::errors::*;
 
-use crate::base::{Array, Value, ValueProperties, IndexKey};
+use crate::base::{Array, DataframeProperties, Value, ValueProperties, IndexKey};
 
 use crate::{proto, base, Warnable};
 use crate::components::{Component, Named};
 
 use ndarray::ArrayD;
 use ndarray::prelude::*;
-use crate::utilities::{get_argument};
+use crate::utilities::{get_argument, prepend};
 use indexmap::map::IndexMap;
 use crate::utilities::properties::{select_properties, stack_properties};
 
@@ -24,54 +24,65 @@ impl Component for proto::Index {
 
         let mut dimensionality = None;
 
+        if self.as_dataframe && !matches!(data_property, ValueProperties::Dataframe(_)) {
+            return Err("as_dataframe: only dataframes may be indexed into a dataframe".into())
+        }
+
         let properties = match data_property {
-            ValueProperties::Dataframe(data_property) => if let Some(column_names) = public_arguments.get::<IndexKey>(&"names".into()) {
-                let column_names = column_names.ref_array()?;
-                dimensionality = Some(column_names.shape().len() as i64 + 1);
-                match column_names.to_owned() {
-                    Array::Float(_) => return Err("floats are not valid indexes".into()),
-                    Array::Int(names) => to_name_vec(names)?.into_iter()
-                        .map(|v| data_property.children.get::<IndexKey>(&v.into()).cloned())
-                        .collect::<Option<Vec<ValueProperties>>>(),
-                    Array::Str(names) => to_name_vec(names)?.into_iter()
-                        .map(|v| data_property.children.get::<IndexKey>(&v.into()).cloned())
-                        .collect::<Option<Vec<ValueProperties>>>(),
-                    Array::Bool(names) => to_name_vec(names)?.into_iter()
-                        .map(|v| data_property.children.get::<IndexKey>(&v.into()).cloned())
-                        .collect::<Option<Vec<ValueProperties>>>(),
-                }
-                    .ok_or_else(|| Error::from("columns: unknown column in index"))
-
-            } else if let Some(indices) = public_arguments.remove::<IndexKey>(&"indices".into()) {
-                let indices = indices.clone().array()?.int()?;
-                dimensionality = Some(indices.shape().len() as i64 + 1);
-                to_name_vec(indices)?.into_iter()
-                    .map(|idx| data_property.children.get_index(idx as usize).map(|v| v.1.clone()))
-                    .collect::<Option<Vec<ValueProperties>>>()
-                    .ok_or_else(|| Error::from("index does not exist"))
-
-            } else if let Some(mask) = public_arguments.remove::<IndexKey>(&"mask".into()) {
-                let mask = mask.clone().array()?.bool()?;
-                if mask.shape().len() != 1 {
-                    return Err("mask: must be 1-dimensional".into())
-                }
-                dimensionality = Some(2);
-                let mask = to_name_vec(mask)?;
-                if mask.len() != data_property.children.len() {
-                    return Err("mask: must be same length as the number of columns".into())
+            ValueProperties::Dataframe(data_property) => {
+                let column_names = if let Some(names) = public_arguments.get::<IndexKey>(&"names".into()) {
+                    let names = names.ref_array()?;
+                    dimensionality = Some(names.shape().len() as i64 + 1);
+                    to_index_keys(names.to_owned()).map_err(prepend("names:"))?
+
+                } else if let Some(indices) = public_arguments.remove::<IndexKey>(&"indices".into()) {
+                    let indices = indices.clone().array()?.int()?;
+                    dimensionality = Some(indices.shape().len() as i64 + 1);
+                    to_name_vec(indices)?.into_iter()
+                        .map(|idx| data_property.children.get_index(idx as usize).map(|v| v.0.clone())
+                            .ok_or_else(|| Error::from(format!(
+                                "indices: index {} is out of bounds for a dataframe with {} columns",
+                                idx, data_property.children.len()))))
+                        .collect::<Result<Vec<IndexKey>>>()?
+
+                } else if let Some(mask) = public_arguments.remove::<IndexKey>(&"mask".into()) {
+                    let mask = mask.clone().array()?.bool()?;
+                    if mask.shape().len() != 1 {
+                        return Err("mask: must be 1-dimensional".into())
+                    }
+                    dimensionality = Some(2);
+                    let mask = to_name_vec(mask)?;
+                    if mask.len() != data_property.children.len() {
+                        return Err("mask: must be same length as the number of columns".into())
+                    }
+                    data_property.children.keys().zip(mask)
+                        .filter(|(_, m)| *m).map(|(name, _)| name.clone())
+                        .collect::<Vec<IndexKey>>()
+                } else {
+                    return Err("one of names, indices or mask must be supplied".into())
+                };
+
+                assert_columns_exist(&data_property.children, &column_names)?;
+                let selected = column_names.iter()
+                    .map(|name| (name.clone(), data_property.children.get(name).unwrap().clone()))
+                    .collect::<Vec<(IndexKey, ValueProperties)>>();
+
+                if self.as_dataframe {
+                    let num_selected = selected.len();
+                    let children = selected.into_iter().collect::<IndexMap<IndexKey, ValueProperties>>();
+                    if children.len() != num_selected {
+                        return Err("a column may only be selected once into a dataframe".into())
+                    }
+                    return Ok(ValueProperties::Dataframe(DataframeProperties { children }).into())
                 }
-                Ok(data_property.children.into_iter().zip(mask)
-                    .filter(|(_, m)| *m).map(|(v, _)| v.1)
-                    .collect::<Vec<ValueProperties>>())
-            } else {
-                return Err("one of names, indices or mask must be supplied".into())
+                Ok(selected.into_iter().map(|(_, property)| property).collect::<Vec<ValueProperties>>())
             }
 
             ValueProperties::Partitions(data_property) => {
-                let names = get_argument(&public_arguments, "names")?
-                    .to_owned().array()?;
+                // composite keys with mixed types are passed as a dataframe
+                let names = get_argument(&public_arguments, "names")?.to_owned();
 
-                let partition_key = IndexKey::new(names)?;
+                let partition_key = IndexKey::from_value(names)?;
                 let part_properties = data_property.children.get::<IndexKey>(&partition_key)
                     .ok_or_else(|| format!("unknown partition index: {:?}", partition_key))?.clone();
 
@@ -158,6 +169,32 @@ impl Named for proto::Index {
     }
 }
 
+/// Standardize a scalar or vector of column names into keys.
+pub fn to_index_keys(names: Array) -> Result<Vec<IndexKey>> {
+    Ok(match names {
+        Array::Float(_) | Array::F32(_) => return Err("floats are not valid indexes".into()),
+        Array::Int(names) => to_name_vec(names)?.into_iter().map(IndexKey::from).collect(),
+        Array::Str(names) => to_name_vec(names)?.into_iter().map(IndexKey::from).collect(),
+        Array::Bool(names) => to_name_vec(names)?.into_iter().map(IndexKey::from).collect(),
+    })
+}
+
+/// Check that at least one column is selected, and that every selected column exists.
+///
+/// The error for a missing column lists the columns that are available.
+pub fn assert_columns_exist<T>(columns: &IndexMap<IndexKey, T>, names: &[IndexKey]) -> Result<()> {
+    if names.is_empty() {
+        return Err("names: at least one column must be selected".into())
+    }
+    match names.iter().find(|name| !columns.contains_key(*name)) {
+        Some(name) => Err(format!("names: column {:?} does not exist. Available columns are: {}",
+            name.to_string(), columns.keys()
+                .map(|name| format!("{:?}", name.to_string()))
+                .collect::<Vec<String>>().join(", ")).into()),
+        None => Ok(())
+    }
+}
+
 pub fn to_name_vec<T: Clone>(columns: ArrayD<T>) -> Result<Vec<T>> {
     match columns.ndim() {
         0 => Ok(vec![columns.first()
@@ -169,3 +206,64 @@ pub fn to_name_vec<T: Clone>(columns: ArrayD<T>) -> Result<Vec<T>> {
         _ => Err("dimensionality of column names must be less than 2".into())
     }
 }
+
+#[cfg(test)]
+mod test_index {
+    use indexmap::IndexMap;
+    use ndarray::{arr1, Array1};
+
+    use crate::base::{IndexKey, Value, ValueProperties};
+    use crate::bindings::Analysis;
+    use crate::components::index::assert_columns_exist;
+    use crate::errors::*;
+
+    /// select columns by name from a dataframe with int columns "a" and "b", and a string column "c"
+    fn select(names: &[&str], as_dataframe: bool) -> Result<ValueProperties> {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Value::Dataframe(indexmap![
+                "a".into() => arr1(&[1, 2, 3]).into_dyn().into(),
+                "b".into() => arr1(&[4, 5, 6]).into_dyn().into(),
+                "c".into() => arr1(&["x", "y", "z"]).mapv(String::from).into_dyn().into()]))
+            .value_public(true)
+            .build();
+        let names = analysis.literal()
+            .value(Array1::from(names.iter().map(|v| v.to_string()).collect::<Vec<String>>()).into_dyn().into())
+            .value_public(true)
+            .build();
+        let selected = analysis.index(data).names(names).as_dataframe(as_dataframe).build();
+
+        analysis.properties(selected)
+    }
+
+    #[test]
+    fn test_select_subset() {
+        let properties = select(&["b", "a"], false).unwrap();
+        assert_eq!(properties.array().unwrap().num_columns, Some(2));
+
+        // columns of different types may only be selected into a dataframe
+        assert!(select(&["a", "c"], false).is_err());
+        match select(&["c", "a"], true).unwrap() {
+            ValueProperties::Dataframe(properties) => assert_eq!(
+                properties.children.keys().cloned().collect::<Vec<IndexKey>>(),
+                vec![IndexKey::from("c"), IndexKey::from("a")]),
+            _ => panic!("selection must be a dataframe")
+        }
+        assert!(select(&["a", "a"], true).is_err());
+    }
+
+    #[test]
+    fn test_select_missing() {
+        assert!(select(&["a", "d"], false).is_err());
+        assert!(select(&[], false).is_err());
+        assert!(select(&[], true).is_err());
+
+        let columns: IndexMap<IndexKey, ()> = indexmap!["a".into() => (), "b".into() => ()];
+        assert_eq!(
+            assert_columns_exist(&columns, &["a".into(), "d".into()]).unwrap_err().to_string(),
+            r#"names: column "d" does not exist. Available columns are: "a", "b""#);
+        assert_eq!(
+            assert_columns_exist(&columns, &[]).unwrap_err().to_string(),
+            "names: at least one column must be selected");
+    }
+}
@@ -4,6 +4,7 @@ use itertools::Itertools;
 use crate::{base, proto, Warnable};
 use crate::base::{DataType, IndexKey, NodeProperties, SensitivitySpace, Value, ValueProperties, ArrayProperties};
 use crate::components::{Accuracy, Component, Expandable, Mechanism, Sensitivity};
+use crate::components::simple_geometric_mechanism::{geometric_accuracy_to_scale, geometric_scale_to_accuracy};
 use crate::errors::*;
 use crate::utilities::{expand_mechanism, prepend};
 use crate::utilities::privacy::{get_epsilon, privacy_usage_check, spread_privacy_usage};
@@ -24,6 +25,10 @@ impl Component for proto::LaplaceMechanism {
             return Err("Floating-point protections are enabled. The laplace mechanism is susceptible to floating-point attacks.".into())
         }
 
+        if privacy_definition.group_size == 0 {
+            return Err("group size must be greater than zero".into())
+        }
+
         let mut data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
             .ok_or("data: missing")?.array()
             .map_err(prepend("data:"))?.clone();
@@ -32,6 +37,10 @@ impl Component for proto::LaplaceMechanism {
             return Err("data: atomic type must be numeric".into());
         }
 
+        if self.resolution < 0. || !self.resolution.is_finite() {
+            return Err("resolution: must be non-negative and finite".into())
+        }
+
         let aggregator = data_property.aggregator.clone()
             .ok_or_else(|| Error::from("aggregator: missing"))?;
 
@@ -114,17 +123,25 @@ impl Accuracy for proto::LaplaceMechanism {
             .ok_or_else(|| Error::from("sensitivity: missing in accuracy"))?.clone()
             .array()?.cast_float()?
             .gencolumns().into_iter()
-            .map(|sensitivity_col| sensitivity_col.into_iter().copied().fold1(|l, r| l.max(r)).unwrap())
+            .map(|sensitivity_col| (
+                sensitivity_col.into_iter().copied().fold1(|l, r| l.max(r)).unwrap(),
+                sensitivity_col.len()))
             .collect();
 
-        Ok(Some(sensitivities.into_iter().zip(accuracies.values.iter())
-            .map(|(sensitivity, accuracy)| proto::PrivacyUsage {
+        sensitivities.into_iter().zip(accuracies.values.iter())
+            .map(|((sensitivity, count), accuracy)| Ok(proto::PrivacyUsage {
                 distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
-                    epsilon: (1. / accuracy.alpha).ln() * (sensitivity as f64 / accuracy.value),
+                    epsilon: if self.resolution > 0. {
+                        // rounding to the grid contributes up to half a grid point of error
+                        let scale = geometric_accuracy_to_scale(accuracy.value / self.resolution - 0.5, accuracy.alpha)?;
+                        get_grid_sensitivity(sensitivity, self.resolution, count, 1) / scale
+                    } else {
+                        (1. / accuracy.alpha).ln() * (sensitivity / accuracy.value)
+                    },
                     delta: 0.,
                 }))
-            })
-            .collect()))
+            }))
+            .collect::<Result<Vec<_>>>().map(Some)
     }
 
     fn privacy_usage_to_accuracy(
@@ -138,17 +155,101 @@ impl Accuracy for proto::LaplaceMechanism {
             .ok_or_else(|| Error::from("sensitivity: missing in accuracy"))?.clone()
             .array()?.cast_float()?
             .gencolumns().into_iter()
-            .map(|sensitivity_col| sensitivity_col.into_iter().copied().fold1(|l, r| l.max(r)).unwrap())
+            .map(|sensitivity_col| (
+                sensitivity_col.into_iter().copied().fold1(|l, r| l.max(r)).unwrap(),
+                sensitivity_col.len()))
             .collect();
 
         let usages = spread_privacy_usage(&self.privacy_usage, sensitivities.len())?;
         let epsilons = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
 
-        Ok(Some(sensitivities.into_iter().zip(epsilons.into_iter())
-            .map(|(sensitivity, epsilon)| proto::Accuracy {
-                value: (1. / alpha).ln() * (sensitivity as f64 / epsilon),
+        sensitivities.into_iter().zip(epsilons)
+            .map(|((sensitivity, count), epsilon)| Ok(proto::Accuracy {
+                value: if self.resolution > 0. {
+                    // the discrete laplace noise is in grid units, and rounding to the grid contributes up to half a grid point
+                    let grid_sensitivity = get_grid_sensitivity(sensitivity, self.resolution, count, 1);
+                    (geometric_scale_to_accuracy(grid_sensitivity / epsilon, alpha)? + 0.5) * self.resolution
+                } else {
+                    (1. / alpha).ln() * (sensitivity / epsilon)
+                },
                 alpha,
-            })
-            .collect()))
+            }))
+            .collect::<Result<Vec<_>>>().map(Some)
+    }
+}
+
+/// Returns the sensitivity of a set of statistics, in units of `resolution`, after rounding them to the grid.
+///
+/// Rounding to the nearest grid point moves neighboring statistics at most one grid point further apart.
+/// A single statistic therefore has sensitivity `ceil(sensitivity / resolution)`,
+/// while a set of `count` statistics grows by at most one grid unit in each statistic.
+///
+/// # Arguments
+/// * `sensitivity` - Sensitivity of the statistics, in the k-norm.
+/// * `resolution` - Spacing of the grid.
+/// * `count` - Number of statistics the sensitivity is shared between.
+/// * `k` - The norm of the sensitivity, either 1 or 2.
+///
+/// # Return
+/// Sensitivity of the rounded statistics, in the k-norm and in units of `resolution`.
+///
+/// # Example
+/// ```
+/// use smartnoise_validator::components::laplace_mechanism::get_grid_sensitivity;
+/// assert_eq!(get_grid_sensitivity(1., 0.25, 1, 1), 4.);
+/// assert_eq!(get_grid_sensitivity(1., 0.3, 2, 1), 5.);
+/// ```
+pub fn get_grid_sensitivity(sensitivity: f64, resolution: f64, count: usize, k: u32) -> f64 {
+    let sensitivity = sensitivity / resolution;
+    match (count, k) {
+        (1, _) => sensitivity.ceil(),
+        // the rounded differences are integers, bounded by the sum of ceilings
+        (_, 1) => sensitivity.ceil() + (count - 1) as f64,
+        _ => sensitivity + (count as f64).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod test_laplace_mechanism {
+    use ndarray::arr2;
+
+    use crate::base::{IndexKey, Value};
+    use crate::components::Accuracy;
+    use crate::proto;
+    use crate::utilities::privacy::get_epsilon;
+
+    fn mechanism(epsilon: f64, resolution: f64) -> proto::LaplaceMechanism {
+        proto::LaplaceMechanism {
+            privacy_usage: vec![proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                    epsilon, delta: 0.
+                }))
+            }],
+            resolution
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_accuracy_resolution() {
+        let sensitivity: Value = arr2(&[[1.]]).into_dyn().into();
+        let arguments = indexmap![IndexKey::from("sensitivity") => &sensitivity];
+
+        // twelve grid points of two-sided geometric noise with scale 4, plus half a grid point of rounding
+        let accuracies = mechanism(1., 0.25).privacy_usage_to_accuracy(arguments.clone(), 0.05).unwrap().unwrap();
+        assert_eq!(accuracies[0].value, 3.125);
+
+        // the grid is coarser than the continuous mechanism
+        let continuous = mechanism(1., 0.).privacy_usage_to_accuracy(arguments.clone(), 0.05).unwrap().unwrap();
+        assert!(continuous[0].value < accuracies[0].value);
+
+        // the usage that attains the accuracy is no greater than the usage it was derived from
+        let usages = mechanism(1., 0.25).accuracy_to_privacy_usage(
+            &proto::Accuracies { values: accuracies }, arguments.clone()).unwrap().unwrap();
+        let epsilon = get_epsilon(&usages[0]).unwrap();
+        assert!(epsilon <= 1. && epsilon > 0.9, "epsilon: {}", epsilon);
+
+        // an accuracy finer than the grid cannot be attained
+        assert!(mechanism(1., 0.25).accuracy_to_privacy_usage(
+            &proto::Accuracies { values: vec![proto::Accuracy { value: 0.1, alpha: 0.05 }] }, arguments).is_err());
+    }
+}
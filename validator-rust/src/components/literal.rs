@@ -52,6 +52,11 @@ impl Named for proto::Literal {
                 self.to_string().into()
             }
         }
+        impl ToIndexKey for f32 {
+            fn to_index_key(self) -> IndexKey {
+                self.to_string().into()
+            }
+        }
         macro_rules! make_convertable {
             ($var_type:ty) => {
                 impl ToIndexKey for $var_type {
@@ -86,6 +91,7 @@ impl Named for proto::Literal {
                 Value::Jagged(jagged) => Ok((0..jagged.num_columns()).map(|_| "[Literal vector]".into()).collect()),
                 Value::Array(value) => match value {
                     Array::Float(array) => array_to_names(array, value.num_columns()?),
+                    Array::F32(array) => array_to_names(array, value.num_columns()?),
                     Array::Int(array) => array_to_names(array, value.num_columns()?),
                     Array::Str(array) => array_to_names(array, value.num_columns()?),
                     Array::Bool(array) => array_to_names(array, value.num_columns()?),
@@ -60,7 +60,7 @@ impl Expandable for proto::Map {
                             arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => id_data, "names".into() => id_index_name])),
                             omit: true,
                             submission: component.submission,
-                            variant: Some(proto::component::Variant::Index(proto::Index {})),
+                            variant: Some(proto::component::Variant::Index(proto::Index { as_dataframe: false })),
                         });
 
                         match (public_arguments.get(name), properties.get(name)) {
@@ -112,7 +112,8 @@ impl Expandable for proto::Map {
             omit: component.omit,
             submission: component.submission,
             variant: Some(proto::component::Variant::Union(proto::Union {
-                flatten: false
+                flatten: false,
+                by_column: false
             }))
         });
         expansion.traversal.push(component_id);
@@ -0,0 +1,202 @@
+use crate::errors::*;
+
+use crate::{proto, base};
+
+use crate::components::Expandable;
+use crate::base::{IndexKey, Value, ReleaseNode};
+use crate::utilities::{get_literal, prepend};
+use crate::utilities::inference::infer_property;
+use indexmap::map::IndexMap;
+
+
+impl Expandable for proto::MapColumns {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let mapped_component = self.component.as_ref()
+            .ok_or("component must be defined")?;
+
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.dataframe()
+            .map_err(prepend("data:"))?;
+
+        let mut arguments = component.arguments();
+        let id_data = arguments.remove::<IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data: missing from component arguments"))?;
+
+        let data_value = match public_arguments.get::<IndexKey>(&"data".into()) {
+            Some(Value::Dataframe(columns)) => Some(columns),
+            _ => None
+        };
+
+        // for each column
+        let union_arguments = data_property.children.iter()
+            .map(|(column_name, column_properties)| {
+                maximum_id += 1;
+                let id_index_name = maximum_id;
+                let (patch_node, release) = get_literal(Value::from_index_key(column_name.clone())?, component.submission)?;
+                expansion.computation_graph.insert(id_index_name, patch_node);
+                expansion.properties.insert(id_index_name, infer_property(&release.value, None, id_index_name)?);
+                expansion.releases.insert(id_index_name, release);
+
+                maximum_id += 1;
+                let id_index = maximum_id;
+                expansion.computation_graph.insert(id_index, proto::Component {
+                    arguments: Some(proto::ArgumentNodeIds::new(indexmap!["data".into() => id_data, "names".into() => id_index_name])),
+                    omit: true,
+                    submission: component.submission,
+                    variant: Some(proto::component::Variant::Index(proto::Index { as_dataframe: false })),
+                });
+
+                // columns of public data are already known, and do not need to be indexed at runtime
+                match data_value.and_then(|columns| columns.get(column_name)) {
+                    Some(column_value) => {
+                        expansion.releases.insert(id_index, ReleaseNode {
+                            value: column_value.clone(),
+                            privacy_usages: None,
+                            public: column_properties.is_public()
+                        });
+                        expansion.properties.insert(id_index, column_properties.clone());
+                    },
+                    None => expansion.traversal.push(id_index)
+                }
+
+                // the column takes the place of the data argument, and all other arguments are shared
+                let mut inner_arguments = indexmap![IndexKey::from("data") => id_index];
+                inner_arguments.extend(arguments.clone());
+
+                maximum_id += 1;
+                let id_inner_component = maximum_id;
+                let mut inner_component = *mapped_component.clone();
+
+                inner_component.arguments = Some(proto::ArgumentNodeIds::new(inner_arguments));
+                inner_component.omit = true;
+
+                expansion.computation_graph.insert(id_inner_component, inner_component);
+                expansion.traversal.push(id_inner_component);
+
+                Ok((column_name.clone(), id_inner_component))
+            })
+            .collect::<Result<IndexMap<IndexKey, u32>>>()?;
+
+        // reassemble the outputs of the inner components into an indexmap keyed by column name
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(union_arguments)),
+            omit: component.omit,
+            submission: component.submission,
+            variant: Some(proto::component::Variant::Union(proto::Union {
+                flatten: false,
+                by_column: false
+            }))
+        });
+        expansion.traversal.push(component_id);
+
+        Ok(expansion)
+    }
+}
+
+#[cfg(test)]
+pub mod test_map_columns {
+    use crate::bindings::Analysis;
+    use crate::base::{IndexKey, Value};
+    use crate::proto;
+    use ndarray::{arr1, arr2};
+
+    /// Add a MapColumns component that applies `variant` to each column of `data`, sharing the `arguments`.
+    pub fn map_columns(
+        analysis: &mut Analysis, data: u32, arguments: Vec<(&str, u32)>, variant: proto::component::Variant
+    ) -> u32 {
+        let mut arguments = arguments.into_iter()
+            .map(|(name, id)| (IndexKey::from(name), id))
+            .collect::<indexmap::IndexMap<IndexKey, u32>>();
+        arguments.insert("data".into(), data);
+
+        analysis.component_count += 1;
+        analysis.components.insert(analysis.component_count, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(arguments)),
+            variant: Some(proto::component::Variant::MapColumns(Box::new(proto::MapColumns {
+                component: Some(Box::new(proto::Component {
+                    arguments: None,
+                    variant: Some(variant),
+                    omit: true,
+                    submission: analysis.submission_count,
+                }))
+            }))),
+            omit: false,
+            submission: analysis.submission_count,
+        });
+        analysis.component_count
+    }
+
+    #[test]
+    fn test_clamp_mean() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Value::Dataframe(indexmap![
+                "a".into() => arr2(&[[-1.], [2.], [4.], [12.]]).into_dyn().into(),
+                "b".into() => arr2(&[[3.], [5.], [7.], [9.]]).into_dyn().into()
+            ]))
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+
+        let clamped = map_columns(&mut analysis, data, vec![("lower", lower), ("upper", upper)],
+                                  proto::component::Variant::Clamp(proto::Clamp {}));
+        let means = analysis.mean(clamped).build();
+
+        let clamped_properties = analysis.properties(clamped).unwrap();
+        let columns = &clamped_properties.partitions().unwrap().children;
+        assert_eq!(columns.keys().cloned().collect::<Vec<_>>(), vec![IndexKey::from("a"), IndexKey::from("b")]);
+        // each column is clamped independently, so bounds tighter than the clamp are kept
+        columns.values().zip(&[(0., 10.), (3., 9.)]).for_each(|(column, (lower, upper))| {
+            let column = column.array().unwrap();
+            assert_eq!(column.lower_float().unwrap(), vec![*lower]);
+            assert_eq!(column.upper_float().unwrap(), vec![*upper]);
+        });
+
+        // the mean is mapped over each of the clamped columns
+        let mean_properties = analysis.properties(means).unwrap();
+        let columns = &mean_properties.partitions().unwrap().children;
+        assert_eq!(columns.len(), 2);
+        assert!(columns.values().all(|column| column.array().unwrap().num_records == Some(1)));
+    }
+
+    #[test]
+    fn test_inferred_bounds() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Value::Dataframe(indexmap![
+                "a".into() => arr2(&[[-1.], [2.]]).into_dyn().into(),
+                "b".into() => arr2(&[[3.], [5.]]).into_dyn().into()
+            ]))
+            .value_public(true)
+            .build();
+        map_columns(&mut analysis, data, vec![], proto::component::Variant::Clamp(proto::Clamp {}));
+
+        // the clamps on each column are inserted by the expansion, so their inferred bounds are not warned about
+        let (_, warnings) = crate::get_properties(
+            Some(analysis.privacy_definition), analysis.components, analysis.release, vec![]).unwrap();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+    }
+
+    #[test]
+    fn test_not_dataframe() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr1(&[1., 2.]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let mapped = map_columns(&mut analysis, data, vec![],
+                                 proto::component::Variant::Mean(proto::Mean {}));
+        assert!(analysis.properties(mapped).is_err());
+    }
+}
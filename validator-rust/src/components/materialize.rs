@@ -5,21 +5,50 @@ use crate::{proto, base, Warnable};
 use crate::components::{Component, Named};
 use crate::base::{Value, ValueProperties, ArrayProperties, DataType, IndexKey, DataframeProperties};
 use indexmap::map::IndexMap;
+use crate::utilities::{parse_data_type, prepend};
 
 impl Component for proto::Materialize {
     fn propagate_property(
         &self,
         _privacy_definition: &Option<proto::PrivacyDefinition>,
         public_arguments: IndexMap<base::IndexKey, &Value>,
-        _properties: base::NodeProperties,
+        properties: base::NodeProperties,
         node_id: u32
     ) -> Result<Warnable<ValueProperties>> {
 
-        let column_names = self.get_names(public_arguments, IndexMap::new(), None)?;
+        // names of inline records are read from their keys, but their types are read like a csv
+        let has_records = properties.contains_key::<IndexKey>(&"records".into());
+        let has_schema = !has_records && match self.file_format.to_lowercase().as_str() {
+            "csv" => false,
+            "parquet" | "arrow" => true,
+            _ => return Err(format!("file_format: must be one of csv, parquet or arrow, found {:?}", self.file_format).into())
+        };
+
+        // the atomic type inferred for a column depends on its values, so it would reveal private data
+        if self.infer_types && !self.public {
+            return Err("infer_types: types may only be inferred from public data. Declare the types of private data with data_types".into())
+        }
+
+        let data_types = public_arguments.get::<IndexKey>(&"data_types".into())
+            .map(|data_types| parse_data_types(data_types)).transpose()?;
+
+        let column_names = if self.public && (self.skip_row || has_schema || has_records)
+            && !public_arguments.contains_key::<IndexKey>(&"column_names".into())
+            && !public_arguments.contains_key::<IndexKey>(&"num_columns".into()) {
+            // column names of public data are read from the header or schema when the file is loaded
+            Vec::new()
+        } else {
+            self.get_names(public_arguments, IndexMap::new(), None)?
+        };
+
+        // column names of public data read from the header or schema are checked once the data is loaded
+        if let (Some(data_types), false) = (&data_types, column_names.is_empty()) {
+            check_data_types(&column_names, data_types)?;
+        }
 
         Ok(ValueProperties::Dataframe(DataframeProperties {
-            children: column_names.into_iter()
-                .map(|name| (name, ValueProperties::Array(ArrayProperties {
+            children: column_names.into_iter().enumerate()
+                .map(|(idx, name)| (name, ValueProperties::Array(ArrayProperties {
                     num_records: None,
                     num_columns: Some(1),
                     nullity: true,
@@ -27,7 +56,11 @@ impl Component for proto::Materialize {
                     c_stability: 1,
                     aggregator: None,
                     nature: None,
-                    data_type: DataType::Str,
+                    // inferred types and types read from a schema are only known once the data is loaded
+                    data_type: match &data_types {
+                        Some(data_types) => data_types[idx].clone(),
+                        None => if self.infer_types || has_schema { DataType::Unknown } else { DataType::Str }
+                    },
                     dataset_id: Some(node_id as i64),
                     node_id: node_id as i64,
                     // this is a library-wide assumption - that datasets initially have more than zero rows
@@ -41,12 +74,40 @@ impl Component for proto::Materialize {
     }
 }
 
+/// Parse the declared atomic type of each column.
+pub fn parse_data_types(data_types: &Value) -> Result<Vec<DataType>> {
+    let data_types = data_types.ref_array()?.ref_string()
+        .map_err(prepend("data_types:"))?;
+    if data_types.ndim() > 1 {
+        return Err("data_types: dimensionality may not be greater than one".into())
+    }
+    data_types.iter().enumerate()
+        .map(|(idx, data_type)| parse_data_type(data_type)
+            .map_err(prepend(&format!("data_types: column {}:", idx))))
+        .collect()
+}
+
+/// Check that exactly one atomic type is declared for each column.
+///
+/// The error names the first column that is missing a type, or the first type that has no column.
+pub fn check_data_types(column_names: &[IndexKey], data_types: &[DataType]) -> Result<()> {
+    if let Some(name) = column_names.get(data_types.len()) {
+        return Err(format!("data_types: no type is declared for column {} ({}). {} types were declared for {} columns",
+                           data_types.len(), name.to_string(), data_types.len(), column_names.len()).into())
+    }
+    if data_types.len() > column_names.len() {
+        return Err(format!("data_types: a type is declared for column {}, but there is no such column. {} types were declared for {} columns",
+                           column_names.len(), data_types.len(), column_names.len()).into())
+    }
+    Ok(())
+}
+
 impl Named for proto::Materialize {
     fn get_names(
         &self,
         public_arguments: IndexMap<base::IndexKey, &Value>,
         _argument_variables: IndexMap<base::IndexKey, Vec<IndexKey>>,
-        _release: Option<&Value>
+        release: Option<&Value>
     ) -> Result<Vec<IndexKey>> {
 
         let column_names = public_arguments.get::<base::IndexKey>(&"column_names".into())
@@ -78,7 +139,131 @@ impl Named for proto::Materialize {
                 _ => return Err("names: unhashable type".into())
             },
             (None, Some(num_columns)) => (0..num_columns).map(|idx| idx.into()).collect(),
+            (None, None) => match release {
+                Some(Value::Dataframe(dataframe)) => dataframe.keys().cloned().collect(),
+                _ => return Err("either column_names or num_columns must be specified".into())
+            },
             _ => return Err("either column_names or num_columns must be specified".into())
         })
     }
 }
+
+#[cfg(test)]
+mod test_materialize {
+    use ndarray::arr1;
+
+    use crate::base::DataType;
+    use crate::bindings::Analysis;
+
+    #[test]
+    fn test_materialize_infer_types() {
+        let mut analysis = Analysis::new();
+        let column_names = analysis.literal()
+            .value(arr1(&["a".to_string(), "b".to_string()]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let data = analysis.materialize("data.csv".to_string())
+            .column_names(column_names)
+            .infer_types(true)
+            .public(true)
+            .build();
+
+        let properties = analysis.properties(data).unwrap();
+        let properties = properties.dataframe().unwrap();
+        assert_eq!(properties.children.len(), 2);
+        assert!(properties.children.values()
+            .all(|child| child.array().unwrap().data_type == DataType::Unknown));
+
+        // the inferred types of private data would depend on private values
+        let data = analysis.materialize("data.csv".to_string())
+            .column_names(column_names)
+            .infer_types(true)
+            .build();
+        let error = analysis.properties(data).unwrap_err();
+        assert!(format!("{:?}", error).contains("types may only be inferred from public data"), "{:?}", error);
+    }
+
+    #[test]
+    fn test_materialize_names() {
+        // column names of public data may be read from the header
+        let mut analysis = Analysis::new();
+        let data = analysis.materialize("data.csv".to_string())
+            .public(true)
+            .build();
+        assert!(analysis.properties(data).is_ok());
+
+        // column names of private data must be supplied
+        let mut analysis = Analysis::new();
+        let data = analysis.materialize("data.csv".to_string()).build();
+        assert!(analysis.properties(data).is_err());
+    }
+
+    #[test]
+    fn test_materialize_file_format() {
+        // column names of public parquet data are read from the schema, even without a header row
+        let mut analysis = Analysis::new();
+        let data = analysis.materialize("data.parquet".to_string())
+            .file_format("parquet".to_string())
+            .skip_row(false)
+            .public(true)
+            .build();
+        assert!(analysis.properties(data).is_ok());
+
+        // atomic types are read from the schema
+        let mut analysis = Analysis::new();
+        let column_names = analysis.literal()
+            .value(arr1(&["a".to_string()]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let data = analysis.materialize("data.arrow".to_string())
+            .file_format("arrow".to_string())
+            .column_names(column_names)
+            .build();
+        let properties = analysis.properties(data).unwrap();
+        let properties = properties.dataframe().unwrap();
+        assert!(properties.children.values()
+            .all(|child| child.array().unwrap().data_type == DataType::Unknown));
+
+        let mut analysis = Analysis::new();
+        let data = analysis.materialize("data.xlsx".to_string())
+            .file_format("xlsx".to_string())
+            .public(true)
+            .build();
+        assert!(analysis.properties(data).is_err());
+    }
+
+    #[test]
+    fn test_materialize_data_types() {
+        let declare = |names: &[&str], data_types: &[&str]| {
+            let mut analysis = Analysis::new();
+            let column_names = analysis.literal()
+                .value(arr1(&names.iter().map(|v| v.to_string()).collect::<Vec<_>>()).into_dyn().into())
+                .value_public(true)
+                .build();
+            let data_types = analysis.literal()
+                .value(arr1(&data_types.iter().map(|v| v.to_string()).collect::<Vec<_>>()).into_dyn().into())
+                .value_public(true)
+                .build();
+            let data = analysis.materialize("data.csv".to_string())
+                .column_names(column_names)
+                .data_types(data_types)
+                .build();
+            analysis.properties(data)
+        };
+
+        // declared types are known before the data is loaded
+        let properties = declare(&["a", "b"], &["int", "float"]).unwrap();
+        let properties = properties.dataframe().unwrap();
+        assert_eq!(properties.children.values()
+                       .map(|child| child.array().unwrap().data_type.clone()).collect::<Vec<_>>(),
+                   vec![DataType::Int, DataType::Float]);
+
+        // the error names the offending column
+        let error = declare(&["a", "b", "c"], &["int", "float"]).unwrap_err();
+        assert!(format!("{:?}", error).contains("no type is declared for column 2 (c)"), "{:?}", error);
+        let error = declare(&["a"], &["int", "float"]).unwrap_err();
+        assert!(format!("{:?}", error).contains("a type is declared for column 1"), "{:?}", error);
+        let error = declare(&["a", "b"], &["int", "decimal"]).unwrap_err();
+        assert!(format!("{:?}", error).contains("column 1"), "{:?}", error);
+    }
+}
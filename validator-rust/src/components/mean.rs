@@ -4,16 +4,19 @@ use crate::{proto, base, Warnable, Float};
 
 use crate::components::{Component, Sensitivity};
 use crate::base::{Value, NodeProperties, AggregatorProperties, SensitivitySpace, ValueProperties, DataType, IndexKey};
+use crate::components::sum::get_weights;
 use crate::utilities::prepend;
+use crate::utilities::inference::infer_property;
 use ndarray::prelude::*;
 use indexmap::map::IndexMap;
+use itertools::Itertools;
 
 impl Component for proto::Mean {
     fn propagate_property(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
-        _public_arguments: IndexMap<base::IndexKey, &Value>,
-        properties: base::NodeProperties,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        mut properties: base::NodeProperties,
         node_id: u32
     ) -> Result<Warnable<ValueProperties>> {
         let mut data_property = properties.get::<IndexKey>(&"data".into())
@@ -25,6 +28,16 @@ impl Component for proto::Mean {
         }
         data_property.assert_is_not_empty()?;
 
+        // the snapshot retains the normalized weights, as only the relative weight of each record affects the mean
+        if let Some(weights) = get_weights(privacy_definition, &public_arguments, &properties, &data_property)? {
+            let weights = weights.float()?;
+            let total = weights.sum();
+            if total == 0. {
+                return Err("weights: must not sum to zero".into())
+            }
+            properties.insert("weights".into(), infer_property(&(weights / total).into(), None, node_id)?);
+        }
+
         let num_columns = data_property.num_columns()?;
         // save a snapshot of the state when aggregating
         data_property.aggregator = Some(AggregatorProperties::new(
@@ -59,6 +72,11 @@ impl Sensitivity for proto::Mean {
                 data_property.assert_is_not_aggregated()?;
                 let data_lower = data_property.lower_float()?;
                 let data_upper = data_property.upper_float()?;
+                // each record has weight 1/n, unless the records are weighted
+                let max_weight = properties.get::<IndexKey>(&"weights".into())
+                    .map(|weights_property| weights_property.array()?.upper_float()?.into_iter()
+                        .fold1(Float::max).ok_or_else(|| Error::from("weights: must have at least one column")))
+                    .transpose()?;
                 let data_n = data_property.num_records()? as Float;
 
                 // AddRemove vs. Substitute share the same bounds
@@ -66,7 +84,10 @@ impl Sensitivity for proto::Mean {
                 let row_sensitivity = match k {
                     1 | 2 => data_lower.iter()
                         .zip(data_upper.iter())
-                        .map(|(min, max)| (max - min) / data_n)
+                        .map(|(min, max)| match max_weight {
+                            Some(max_weight) => (max - min) * max_weight,
+                            None => (max - min) / data_n
+                        })
                         .collect::<Vec<Float>>(),
                     _ => return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
                 };
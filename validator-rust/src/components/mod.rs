@@ -12,53 +12,76 @@ use crate::errors::*;
 
 mod transforms;
 //mod bin;
+mod bin_edges;
+mod bound_contribution;
 mod cast;
 mod clamp;
 mod count;
 mod covariance;
 mod column_bind;
+mod datetime;
 mod digitize;
+mod dp_clamped_fraction;
 mod dp_count;
 mod dp_variance;
 mod dp_covariance;
+mod dp_frequencies;
 mod dp_gumbel_median;
 mod dp_histogram;
 mod dp_linear_regression;
+mod dp_max_frequency;
 mod dp_maximum;
 mod dp_median;
+mod dp_median_absolute_deviation;
 mod dp_minimum;
 mod dp_mean;
+pub mod dp_proportion;
 mod dp_quantile;
+mod dp_quantile_function;
 mod dp_raw_moment;
 mod dp_sum;
+pub mod dp_survival_counts;
+mod dp_truncated_mean;
+mod expression;
 mod filter;
+mod group_by;
 mod histogram;
 mod impute;
 pub mod index;
 mod raw_moment;
 mod literal;
 mod map;
-mod materialize;
+mod map_columns;
+pub mod materialize;
 pub mod partition;
 mod quantile;
+mod rank;
 mod reshape;
 mod mean;
 mod exponential_mechanism;
 pub mod gaussian_mechanism;
-mod laplace_mechanism;
-mod simple_geometric_mechanism;
+pub mod laplace_mechanism;
+pub mod simple_geometric_mechanism;
 pub mod snapping_mechanism;
+pub mod stability_mechanism;
 mod resize;
 mod theil_sen;
+pub mod truncated_laplace_mechanism;
 mod to_dataframe;
+mod simplex_projection;
+mod subsample;
 mod sum;
+mod sum_of_squares;
+pub mod synthesize_from_marginals;
+mod text;
 mod union;
 mod variance;
+mod weighted_sample;
 
 use crate::base::{IndexKey, Value, NodeProperties, SensitivitySpace, ValueProperties};
 use crate::{proto, Warnable, base};
 use crate::utilities::json::{JSONRelease};
-use crate::utilities::set_node_id;
+use crate::utilities::{set_double_precision, set_node_id};
 use indexmap::map::IndexMap;
 
 /// Universal Component trait
@@ -141,6 +164,7 @@ pub trait Mechanism {
     ///
     /// # Returns
     /// Privacy usages after group_size, c_stability and privacy amplification have been taken into account.
+    /// Mechanisms spend an effective usage, which is converted to the actual usage with `PrivacyUsage::effective_to_actual`.
     fn get_privacy_usage(
         &self,
         privacy_definition: &proto::PrivacyDefinition,
@@ -198,8 +222,10 @@ pub trait Accuracy {
 /// Reportable components correspond to a computation that a researcher may want a JSON summary for
 pub trait Report {
     /// Summarize the relevant metadata around a computation in a readable, JSON-serializable format.
+    #[allow(clippy::too_many_arguments)]
     fn summarize(
         &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -224,6 +250,39 @@ pub trait Named {
 }
 
 
+/// Components that may pass single precision data through unchanged.
+///
+/// These components only reorganize data. Every other component, including every mechanism,
+/// receives single precision data converted to double precision.
+pub fn preserves_precision(variant: &proto::component::Variant) -> bool {
+    use proto::component::Variant;
+    matches!(variant,
+        Variant::BoundContribution(_) | Variant::Cast(_) | Variant::Count(_) | Variant::Filter(_) | Variant::Index(_) | Variant::Literal(_) |
+        Variant::Materialize(_) | Variant::Partition(_) | Variant::PartitionByQuantiles(_) | Variant::Reshape(_) | Variant::ToDataframe(_))
+}
+
+/// Apply a macro to the list of components that implement `Mechanism`.
+macro_rules! mechanisms {
+    ($apply:ident) => {
+        $apply!(
+            // INSERT COMPONENT LIST
+            ExponentialMechanism, GaussianMechanism, LaplaceMechanism,
+            SimpleGeometricMechanism, SnappingMechanism, StabilityMechanism, TruncatedLaplaceMechanism, DpGumbelMedian
+        )
+    }
+}
+
+/// Components that release private data, by consuming privacy usage.
+pub fn is_mechanism(variant: &proto::component::Variant) -> bool {
+    macro_rules! is_mechanism {
+        ($( $variant:ident ),*) => {
+            matches!(variant, $(proto::component::Variant::$variant(_))|*)
+        }
+    }
+
+    mechanisms!(is_mechanism)
+}
+
 impl Component for proto::Component {
     /// Utility implementation on the component.
     ///
@@ -232,12 +291,23 @@ impl Component for proto::Component {
         &self,
         privacy_definition: &Option<proto::PrivacyDefinition>,
         public_arguments: IndexMap<base::IndexKey, &Value>,
-        properties: NodeProperties,
+        mut properties: NodeProperties,
         node_id: u32,
     ) -> Result<Warnable<ValueProperties>> {
         let variant = self.variant.as_ref()
             .ok_or_else(|| "variant: must be defined")?;
 
+        let double_arguments: IndexMap<IndexKey, Value>;
+        let mut public_arguments = public_arguments;
+        if !preserves_precision(variant) {
+            properties.values_mut().for_each(set_double_precision);
+            double_arguments = public_arguments.iter()
+                .filter(|(_, v)| matches!(v, Value::Array(base::Array::F32(_))))
+                .map(|(k, v)| (k.clone(), (*v).clone().to_double()))
+                .collect();
+            public_arguments.extend(double_arguments.iter().map(|(k, v)| (k.clone(), v)));
+        }
+
         macro_rules! propagate_property {
             ($( $variant:ident ),*) => {
                 {
@@ -257,15 +327,16 @@ impl Component for proto::Component {
 
         propagate_property!(
             // INSERT COMPONENT LIST
-            Cast, Clamp, ColumnBind, Count, Covariance, Digitize,
-            Filter, Histogram, Impute, Index, Literal, Materialize, Mean,
-            Partition, Quantile, RawMoment, Reshape, Resize, Sum, ToDataframe, Union, Variance,
+            BinEdges, BoundContribution, Cast, Clamp, ColumnBind, Count, Covariance, DateBin, Digitize,
+            Expression, Filter, Histogram, Impute, Index, Literal, Materialize, Mean,
+            ParseDateTime, Partition, Quantile, Rank, RawMoment, Reshape, Resize, SimplexProjection, StringLength, Subsample, Substring, Sum,
+            SumOfSquares, SynthesizeFromMarginals, ToDataframe, Union, Variance, WeightedSample,
 
             ExponentialMechanism, GaussianMechanism, LaplaceMechanism,
-            SimpleGeometricMechanism, SnappingMechanism,
+            SimpleGeometricMechanism, SnappingMechanism, StabilityMechanism, TruncatedLaplaceMechanism,
 
-            Abs, Add, LogicalAnd, Divide, Equal, GreaterThan, LessThan, Log, Modulo, Multiply,
-            Negate, Negative, LogicalOr, Power, RowMax, RowMin, Subtract, TheilSen, DpGumbelMedian
+            Abs, Add, LogicalAnd, Ceil, Divide, Equal, Exp, Floor, FloorDivide, GreaterThan, LessThan, Log, Modulo, Multiply,
+            Negate, Negative, LogicalOr, Power, Round, RowMax, RowMin, Sqrt, Subtract, TheilSen, DpGumbelMedian
         );
 
         Err(format!("proto component {:?} is missing its Component trait", variant).into())
@@ -288,6 +359,18 @@ impl Expandable for proto::Component {
         let variant = self.variant.as_ref()
             .ok_or_else(|| "variant: must be defined")?;
 
+        let double_properties: NodeProperties;
+        let properties = if preserves_precision(variant) { properties } else {
+            double_properties = properties.iter()
+                .map(|(k, v)| {
+                    let mut v = v.clone();
+                    set_double_precision(&mut v);
+                    (k.clone(), v)
+                })
+                .collect();
+            &double_properties
+        };
+
         macro_rules! expand_component {
             ($( $variant:ident ),*) => {
                 {
@@ -333,13 +416,14 @@ impl Expandable for proto::Component {
 
         expand_component!(
             // INSERT COMPONENT LIST
-            Clamp, Digitize, Histogram, Impute, Map, Maximum, Median, Minimum, Partition, Resize,
+            Clamp, Digitize, GroupBy, Histogram, Impute, Map, MapColumns, Maximum, Median, Minimum, Partition, PartitionByQuantiles, Resize,
 
-            DpCount, DpCovariance, DpHistogram, DpLinearRegression, DpMaximum, DpMean, DpMedian,
-            DpMinimum, DpQuantile, DpRawMoment, DpSum, DpVariance,
+            DpClampedFraction, DpCount, DpCovariance, DpFrequencies, DpHistogram, DpLinearRegression, DpMaximum, DpMean, DpMedian,
+            DpMaxFrequency, DpMedianAbsoluteDeviation, DpMinimum, DpProportion, DpQuantile, DpQuantileFunction, DpRawMoment, DpSum, DpSurvivalCounts,
+            DpTruncatedMean, DpVariance,
 
             ExponentialMechanism, GaussianMechanism, LaplaceMechanism,
-            SimpleGeometricMechanism, SnappingMechanism, DpGumbelMedian,
+            SimpleGeometricMechanism, SnappingMechanism, StabilityMechanism, TruncatedLaplaceMechanism, DpGumbelMedian,
 
             ToBool, ToFloat, ToInt, ToString
         );
@@ -373,11 +457,7 @@ impl Mechanism for proto::Component {
             }
         }
 
-        get_privacy_usage!(
-            // INSERT COMPONENT LIST
-            ExponentialMechanism, GaussianMechanism, LaplaceMechanism,
-            SimpleGeometricMechanism, SnappingMechanism
-        );
+        mechanisms!(get_privacy_usage);
 
         Ok(None)
     }
@@ -409,7 +489,7 @@ impl Sensitivity for proto::component::Variant {
 
         compute_sensitivity!(
             // INSERT COMPONENT LIST
-            Count, Covariance, Histogram, Mean, Quantile, RawMoment, Sum, Union, Variance
+            Count, Covariance, Histogram, Mean, Quantile, RawMoment, Sum, SumOfSquares, Union, Variance
         );
 
         Err(format!("sensitivity is not implemented for proto component {:?}", self).into())
@@ -433,8 +513,16 @@ impl Accuracy for proto::Component {
                 {
                     $(
                        if let proto::component::Variant::$variant(x) = variant {
-                            return x.accuracy_to_privacy_usage(accuracy, public_arguments)
-                                .chain_err(|| format!("node specification {:?}:", variant))
+                            let usages = x.accuracy_to_privacy_usage(accuracy, public_arguments.clone())
+                                .chain_err(|| format!("node specification {:?}:", variant))?;
+                            if cfg!(debug_assertions) {
+                                if let Some(usages) = &usages {
+                                    let mut component = x.clone();
+                                    component.privacy_usage = usages.clone();
+                                    assert_accuracy_attained(&component, accuracy, public_arguments);
+                                }
+                            }
+                            return Ok(usages)
                        }
                     )*
                 }
@@ -442,10 +530,14 @@ impl Accuracy for proto::Component {
         }
 
         accuracy_to_privacy_usage!(
+             DpMean,
+             DpRawMoment,
+             DpSum,
              LaplaceMechanism,
              GaussianMechanism,
              SimpleGeometricMechanism,
-             SnappingMechanism
+             SnappingMechanism,
+             TruncatedLaplaceMechanism
         );
 
         Ok(None)
@@ -476,22 +568,47 @@ impl Accuracy for proto::Component {
         }
 
         privacy_usage_to_accuracy!(
+            DpMean,
+            DpRawMoment,
+            DpSum,
             LaplaceMechanism,
             GaussianMechanism,
             SimpleGeometricMechanism,
-            SnappingMechanism
+            SnappingMechanism,
+            TruncatedLaplaceMechanism
         );
 
         Ok(None)
     }
 }
 
+/// Assert that a privacy usage derived from an accuracy attains that accuracy.
+///
+/// `component` holds the derived privacy usage. Converting it back to an accuracy may not give a larger error,
+/// which catches sign and formula errors in either direction of the conversion.
+fn assert_accuracy_attained<T: Accuracy>(
+    component: &T, accuracies: &proto::Accuracies,
+    public_arguments: IndexMap<base::IndexKey, &Value>,
+) {
+    accuracies.values.iter().enumerate().for_each(|(column_number, accuracy)| {
+        let attained = component.privacy_usage_to_accuracy(public_arguments.clone(), accuracy.alpha)
+            .expect("accuracy must be recoverable from the derived privacy usage")
+            .and_then(|attained| attained.get(column_number).map(|attained| attained.value));
+        if let Some(attained) = attained {
+            assert!(attained <= accuracy.value * (1. + 1e-8),
+                    "privacy usage derived from accuracy {} at alpha {} only attains {}",
+                    accuracy.value, accuracy.alpha, attained);
+        }
+    })
+}
+
 impl Report for proto::Component {
     /// Utility implementation on the component.
     ///
     /// This utility delegates evaluation to the concrete implementation of each component variant.
     fn summarize(
         &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
         node_id: u32,
         component: &proto::Component,
         public_arguments: IndexMap<base::IndexKey, &Value>,
@@ -507,7 +624,7 @@ impl Report for proto::Component {
                 {
                     $(
                        if let proto::component::Variant::$variant(x) = variant {
-                            return x.summarize(node_id, component, public_arguments,
+                            return x.summarize(privacy_definition, node_id, component, public_arguments,
                                  properties, release, variable_names)
                                 .chain_err(|| format!("node specification: {:?}:", variant))
                        }
@@ -518,8 +635,8 @@ impl Report for proto::Component {
 
         summarize!(
             // INSERT COMPONENT LIST
-            DpCount, DpCovariance, DpHistogram, DpMaximum, DpMean, DpMinimum, DpQuantile,
-            DpRawMoment, DpSum, DpVariance
+            DpClampedFraction, DpCount, DpCovariance, DpHistogram, DpMaxFrequency, DpMaximum, DpMean, DpMinimum, DpProportion,
+            DpQuantile, DpQuantileFunction, DpRawMoment, DpSum, DpSurvivalCounts, DpTruncatedMean, DpVariance
         );
 
         Ok(None)
@@ -552,10 +669,12 @@ impl Named for proto::Component {
             }
         }
 
-        // TODO: transforms, covariance/cross-covariance, extended indexing, columnbind
+        // TODO: extended indexing, columnbind
         get_names!(
             // INSERT COMPONENT LIST
-            ToDataframe, Index, Literal, Materialize
+            ToDataframe, Index, Literal, Materialize, Covariance,
+
+            Add, LogicalAnd, Divide, Equal, GreaterThan, LessThan, Modulo, Multiply, LogicalOr, RowMax, RowMin, Subtract
         );
 
         // default implementation
@@ -568,3 +687,133 @@ impl Named for proto::Component {
     }
 }
 
+
+#[cfg(test)]
+mod test_accuracy {
+    use indexmap::map::IndexMap;
+    use ndarray::arr2;
+
+    use crate::base::{IndexKey, Value};
+    use crate::bindings::privacy_usage;
+    use crate::components::Accuracy;
+    use crate::proto;
+    use crate::utilities::privacy::{get_delta, get_epsilon};
+
+    const EPSILONS: [f64; 4] = [0.01, 0.1, 1., 5.];
+    const ALPHAS: [f64; 3] = [0.01, 0.05, 0.5];
+    const DELTA: f64 = 1e-6;
+    const SENSITIVITY: f64 = 2.;
+
+    /// Convert a privacy usage to an accuracy, and the accuracy back to a privacy usage.
+    /// The recovered privacy usage must match the original.
+    fn assert_privacy_round_trip<T: Accuracy>(make_component: impl Fn(Vec<proto::PrivacyUsage>) -> T, delta: f64) {
+        let sensitivity = Value::from(arr2(&[[SENSITIVITY]]).into_dyn());
+
+        for epsilon in EPSILONS.iter() {
+            for alpha in ALPHAS.iter() {
+                let public_arguments = || -> IndexMap<IndexKey, &Value> {
+                    indexmap![IndexKey::from("sensitivity") => &sensitivity]
+                };
+                let component = make_component(privacy_usage(*epsilon, delta));
+
+                let accuracies = component.privacy_usage_to_accuracy(public_arguments(), *alpha)
+                    .unwrap().unwrap();
+                assert!(accuracies.iter().all(|accuracy| accuracy.value > 0.),
+                        "accuracy must be positive: {:?}", accuracies);
+
+                let usages = component.accuracy_to_privacy_usage(
+                    &proto::Accuracies { values: accuracies }, public_arguments())
+                    .unwrap().unwrap();
+
+                usages.iter().for_each(|recovered| {
+                    let recovered_epsilon = get_epsilon(recovered).unwrap();
+                    assert!((recovered_epsilon - epsilon).abs() <= epsilon * 1e-8,
+                            "epsilon {} recovered as {} at alpha {}", epsilon, recovered_epsilon, alpha);
+                    assert_eq!(get_delta(recovered).unwrap(), delta);
+                })
+            }
+        }
+    }
+
+    /// Convert an accuracy to a privacy usage, and the privacy usage back to an accuracy.
+    /// The recovered accuracy must match the original.
+    fn assert_accuracy_round_trip<T: Accuracy>(make_component: impl Fn(Vec<proto::PrivacyUsage>) -> T, delta: f64, values: &[f64]) {
+        let sensitivity = Value::from(arr2(&[[SENSITIVITY]]).into_dyn());
+
+        for value in values {
+            for alpha in ALPHAS.iter() {
+                let public_arguments = || -> IndexMap<IndexKey, &Value> {
+                    indexmap![IndexKey::from("sensitivity") => &sensitivity]
+                };
+                let accuracies = proto::Accuracies {
+                    values: vec![proto::Accuracy { value: *value, alpha: *alpha }]
+                };
+
+                // the privacy usage on the component only informs the delta
+                let usages = make_component(privacy_usage(1., delta))
+                    .accuracy_to_privacy_usage(&accuracies, public_arguments())
+                    .unwrap().unwrap();
+
+                let recovered = make_component(usages)
+                    .privacy_usage_to_accuracy(public_arguments(), *alpha)
+                    .unwrap().unwrap();
+
+                assert!((recovered[0].value - value).abs() <= value * 1e-8,
+                        "accuracy {} recovered as {} at alpha {}", value, recovered[0].value, alpha);
+                assert_eq!(recovered[0].alpha, *alpha);
+            }
+        }
+    }
+
+    #[test]
+    fn test_laplace_round_trip() {
+        let make = |privacy_usage| proto::LaplaceMechanism { privacy_usage, resolution: 0. };
+        assert_privacy_round_trip(make, 0.);
+        assert_accuracy_round_trip(make, 0., &[0.5, 1., 10., 1000.]);
+    }
+
+    #[test]
+    fn test_gaussian_round_trip() {
+        let make = |privacy_usage| proto::GaussianMechanism { privacy_usage, analytic: false, joint: false, resolution: 0. };
+        assert_privacy_round_trip(make, DELTA);
+        assert_accuracy_round_trip(make, DELTA, &[0.5, 1., 10., 1000.]);
+    }
+
+    #[test]
+    fn test_truncated_laplace_round_trip() {
+        let make = |privacy_usage| proto::TruncatedLaplaceMechanism { privacy_usage };
+        assert_privacy_round_trip(make, DELTA);
+        assert_accuracy_round_trip(make, DELTA, &[0.5, 1., 10., 1000.]);
+    }
+
+    #[test]
+    fn test_simple_geometric_round_trip() {
+        let make = |privacy_usage| proto::SimpleGeometricMechanism { privacy_usage };
+        // accuracies of the geometric mechanism are integral, so only integral accuracies round-trip
+        assert_accuracy_round_trip(make, 0., &[1., 3., 10., 1000.]);
+    }
+
+    #[test]
+    fn test_accuracy_attained() {
+        use proto::component::Variant;
+        let sensitivity = Value::from(arr2(&[[SENSITIVITY]]).into_dyn());
+
+        // conversions through the component check that each derived privacy usage attains its accuracy
+        let variants = vec![
+            Variant::LaplaceMechanism(proto::LaplaceMechanism { privacy_usage: privacy_usage(1., 0.), resolution: 0. }),
+            Variant::GaussianMechanism(proto::GaussianMechanism { privacy_usage: privacy_usage(1., DELTA), analytic: false, joint: false, resolution: 0. }),
+            Variant::SimpleGeometricMechanism(proto::SimpleGeometricMechanism { privacy_usage: privacy_usage(1., 0.) }),
+            Variant::TruncatedLaplaceMechanism(proto::TruncatedLaplaceMechanism { privacy_usage: privacy_usage(1., DELTA) }),
+        ];
+        variants.into_iter().for_each(|variant| {
+            let component = proto::Component { variant: Some(variant), ..Default::default() };
+            for value in &[1.5, 4., 25.5] {
+                for alpha in ALPHAS.iter() {
+                    let accuracies = proto::Accuracies { values: vec![proto::Accuracy { value: *value, alpha: *alpha }] };
+                    assert!(component.accuracy_to_privacy_usage(
+                        &accuracies, indexmap![IndexKey::from("sensitivity") => &sensitivity]).unwrap().is_some());
+                }
+            }
+        });
+    }
+}
@@ -1,9 +1,9 @@
 use crate::errors::*;
 
-use crate::{proto, base, Warnable, Integer};
+use crate::{proto, base, Warnable, Integer, Float};
 
 use crate::components::{Component, Expandable};
-use crate::base::{IndexKey, Value, Jagged, ValueProperties, ArrayProperties, NodeProperties, PartitionsProperties};
+use crate::base::{IndexKey, Value, Array, Jagged, DataType, ValueProperties, ArrayProperties, NodeProperties, PartitionsProperties};
 use crate::utilities::{prepend, get_literal, get_argument};
 use indexmap::map::IndexMap;
 use itertools::Itertools;
@@ -21,6 +21,12 @@ impl Component for proto::Partition {
         let data_property = properties.get::<IndexKey>(&"data".into())
             .ok_or("data: missing")?.clone();
 
+        if let ValueProperties::Array(data_property) = &data_property {
+            if !data_property.releasable {
+                data_property.assert_is_not_aggregated()?;
+            }
+        }
+
         let neighboring = proto::privacy_definition::Neighboring::from_i32(privacy_definition.as_ref()
             .ok_or_else(|| Error::from("privacy_definition must be defined"))?.neighboring)
             .ok_or_else(|| Error::from("neighboring must be defined"))?;
@@ -29,15 +35,24 @@ impl Component for proto::Partition {
 
             // propagate properties when partitioning "by" some array
             Some(by_property) => {
-                // TODO: pass non-homogeneously typed keys via indexmap (needs merge component)
-                let by_property = by_property.array()
-                    .map_err(prepend("by:"))?.clone();
-                by_property.num_columns
-                    .ok_or_else(|| Error::from("number of columns must be known on by"))?;
-                let categories = by_property.categories()
-                    .map_err(prepend("by:"))?;
+                let partition_keys = match by_property {
+                    // keys with mixed types are partitioned by the columns of a dataframe
+                    ValueProperties::Dataframe(by_property) => make_composite_partition_keys(
+                        by_property.children.iter()
+                            .map(|(name, property)| get_column_categories(property)
+                                .map_err(prepend(&format!("by: column {}:", name.to_string()))))
+                            .collect::<Result<Vec<Jagged>>>()?)?,
+                    by_property => {
+                        let by_property = by_property.array()
+                            .map_err(prepend("by:"))?.clone();
+                        by_property.num_columns
+                            .ok_or_else(|| Error::from("number of columns must be known on by"))?;
+                        let categories = by_property.categories()
+                            .map_err(prepend("by:"))?;
 
-                let partition_keys = make_dense_partition_keys(categories, by_property.dimensionality)?;
+                        make_dense_partition_keys(categories, by_property.dimensionality)?
+                    }
+                };
 
                 PartitionsProperties {
                     children: broadcast_partitions(partition_keys, &data_property, node_id, neighboring)?,
@@ -95,10 +110,16 @@ impl Expandable for proto::Partition {
 
         if let Some(by) = properties.get::<IndexKey>(&"by".into()) {
             if !properties.contains_key::<IndexKey>(&"categories".into()) {
-                let categories = by.array()?.categories()?;
+                // the categories of each column of a dataframe are stored in a dataframe
+                let categories = match by {
+                    ValueProperties::Dataframe(by) => Value::Dataframe(by.children.iter()
+                        .map(|(name, property)| Ok((name.clone(), Value::Jagged(get_column_categories(property)?))))
+                        .collect::<Result<IndexMap<IndexKey, Value>>>()?),
+                    by => Value::Jagged(by.array()?.categories()?)
+                };
                 maximum_id += 1;
                 let id_categories = maximum_id;
-                let (patch_node, release) = get_literal(Value::Jagged(categories), component.submission)?;
+                let (patch_node, release) = get_literal(categories, component.submission)?;
                 expansion.computation_graph.insert(id_categories, patch_node);
                 expansion.properties.insert(id_categories, infer_property(&release.value, None, id_categories)?);
                 expansion.releases.insert(id_categories, release);
@@ -113,6 +134,103 @@ impl Expandable for proto::Partition {
     }
 }
 
+impl Expandable for proto::PartitionByQuantiles {
+    fn expand_component(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &NodeProperties,
+        component_id: u32,
+        mut maximum_id: u32
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+        let argument_ids = component.arguments();
+
+        let id_data = *argument_ids.get::<IndexKey>(&"data".into())
+            .ok_or_else(|| Error::from("data must be provided as an argument"))?;
+        let id_by = *argument_ids.get::<IndexKey>(&"by".into())
+            .ok_or_else(|| Error::from("by must be provided as an argument"))?;
+
+        let by_property = properties.get::<IndexKey>(&"by".into())
+            .ok_or("by: missing")?.array()
+            .map_err(prepend("by:"))?;
+        if by_property.num_columns()? != 1 {
+            return Err("by: number of columns must be one".into())
+        }
+
+        let cut_points = get_argument(public_arguments, "cut_points")
+            .map_err(prepend("cut_points: must be public,"))?.ref_array()?;
+
+        // the outermost edges are the extremes of the atomic type, so that every finite row belongs to a stratum
+        let edges = match (&by_property.data_type, cut_points) {
+            (DataType::Float, Array::Float(cut_points)) =>
+                Jagged::Float(vec![make_quantile_edges(cut_points.iter().copied(), Float::MIN, Float::MAX)?]),
+            (DataType::Float, Array::Int(cut_points)) =>
+                Jagged::Float(vec![make_quantile_edges(cut_points.iter().map(|v| *v as Float), Float::MIN, Float::MAX)?]),
+            (DataType::Int, Array::Int(cut_points)) =>
+                Jagged::Int(vec![make_quantile_edges(cut_points.iter().copied(), Integer::MIN, Integer::MAX)?]),
+            (DataType::Int, _) => return Err("cut_points: must be integer when by is integer".into()),
+            _ => return Err("by: atomic type must be numeric".into())
+        };
+
+        maximum_id += 1;
+        let id_edges = maximum_id;
+        let (patch_node, release) = get_literal(Value::Jagged(edges), component.submission)?;
+        expansion.computation_graph.insert(id_edges, patch_node);
+        expansion.properties.insert(id_edges, infer_property(&release.value, None, id_edges)?);
+        expansion.releases.insert(id_edges, release);
+
+        // assign each row to the index of its stratum
+        maximum_id += 1;
+        let id_strata = maximum_id;
+        expansion.computation_graph.insert(id_strata, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                "data".into() => id_by,
+                "edges".into() => id_edges
+            ])),
+            variant: Some(proto::component::Variant::Digitize(proto::Digitize {
+                out_of_range: "null".to_string()
+            })),
+            omit: true,
+            submission: component.submission,
+        });
+        expansion.traversal.push(id_strata);
+
+        expansion.computation_graph.insert(component_id, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![
+                "data".into() => id_data,
+                "by".into() => id_strata
+            ])),
+            variant: Some(proto::component::Variant::Partition(proto::Partition {})),
+            omit: component.omit,
+            submission: component.submission,
+        });
+        expansion.traversal.push(component_id);
+
+        Ok(expansion)
+    }
+}
+
+/// Sort and deduplicate cut-points, and enclose them in the outermost edges.
+///
+/// DP-released quantiles may be out of order, and sorting them is post-processing.
+fn make_quantile_edges<T: PartialOrd + Copy>(
+    cut_points: impl Iterator<Item=T>, lower: T, upper: T
+) -> Result<Vec<T>> {
+    let mut cut_points = cut_points.collect::<Vec<T>>();
+    if cut_points.iter().any(|v| v.partial_cmp(v).is_none()) {
+        return Err("cut_points: must not be null".into())
+    }
+    cut_points.sort_by(|l, r| l.partial_cmp(r).unwrap());
+    cut_points.dedup_by(|l, r| l == r);
+
+    Ok(std::iter::once(lower)
+        .chain(cut_points.into_iter().filter(|v| *v > lower && *v < upper))
+        .chain(std::iter::once(upper))
+        .collect())
+}
+
 pub fn broadcast_partitions(
     partition_keys: Vec<IndexKey>, properties: &ValueProperties, node_id: u32,
     neighboring_definition: proto::privacy_definition::Neighboring
@@ -166,11 +284,15 @@ fn get_partition_properties(
     })
 }
 
+/// Form partition keys from the categories of each column.
+///
+/// Keys are sorted and deduplicated, so that partitions are ordered independently of the order the categories are listed in.
+/// Keys of multidimensional partitions are tuples with one element per column.
 pub fn make_dense_partition_keys(categories: Jagged, dimensionality: Option<i64>) -> Result<Vec<IndexKey>> {
     let categories = categories.to_index_keys()?;
 
     // TODO: sparse partitioning component
-    Ok(match dimensionality {
+    Ok(sort_partition_keys(match dimensionality {
         Some(0) => return Err("categories: must be defined for at least one column".into()),
         Some(1) => {
             if categories.len() != 1 {
@@ -180,7 +302,44 @@ pub fn make_dense_partition_keys(categories: Jagged, dimensionality: Option<i64>
         }
         _ => categories.into_iter().multi_cartesian_product()
             .map(IndexKey::Tuple).collect()
-    })
+    }))
+}
+
+/// Sort and deduplicate partition keys.
+fn sort_partition_keys(mut keys: Vec<IndexKey>) -> Vec<IndexKey> {
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// Retrieve the categories of a single column of a dataframe used to partition data.
+fn get_column_categories(property: &ValueProperties) -> Result<Jagged> {
+    let property = property.array()?;
+    if property.num_columns()? != 1 {
+        return Err("must have exactly one column".into())
+    }
+    property.categories()
+}
+
+/// Form composite partition keys from the categories of each column, which may have differing types.
+///
+/// Keys are tuples with one element per column.
+/// Keys are sorted lexicographically, first by the category of the first column, and so on.
+pub fn make_composite_partition_keys(categories: Vec<Jagged>) -> Result<Vec<IndexKey>> {
+    if categories.is_empty() {
+        return Err("categories: must be defined for at least one column".into())
+    }
+    Ok(sort_partition_keys(categories.into_iter()
+        .map(|categories| {
+            let mut categories = categories.to_index_keys()?;
+            if categories.len() != 1 {
+                return Err("categories: must be defined for exactly one column".into())
+            }
+            Ok(categories.remove(0))
+        })
+        .collect::<Result<Vec<Vec<IndexKey>>>>()?
+        .into_iter().multi_cartesian_product()
+        .map(IndexKey::Tuple).collect()))
 }
 
 pub fn even_split_lengths(num_records: i64, num_partitions: i64) -> Vec<i64> {
@@ -192,7 +351,11 @@ pub fn even_split_lengths(num_records: i64, num_partitions: i64) -> Vec<i64> {
 
 #[cfg(test)]
 mod test_partition {
-    use crate::components::partition::even_split_lengths;
+    use ndarray::{arr1, Array1, Array2};
+
+    use crate::base::{IndexKey, Jagged, Value};
+    use crate::bindings::Analysis;
+    use crate::components::partition::{even_split_lengths, make_composite_partition_keys, make_quantile_edges};
 
     fn vec_eq(left: &Vec<i64>, right: &Vec<i64>) -> bool {
         (left.len() == right.len()) && left.iter().zip(right)
@@ -217,4 +380,104 @@ mod test_partition {
             &even_split_lengths(2, 0),
             &vec![]));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_composite_keys() {
+        let keys = make_composite_partition_keys(vec![
+            Jagged::Str(vec![vec!["b".to_string(), "a".to_string()]]),
+            Jagged::Bool(vec![vec![true, false]])
+        ]).unwrap();
+        // keys are sorted by the first column, and then by the categories of the following columns,
+        //     regardless of the order the categories are listed in
+        assert_eq!(keys, vec![
+            IndexKey::Tuple(vec!["a".into(), false.into()]),
+            IndexKey::Tuple(vec!["a".into(), true.into()]),
+            IndexKey::Tuple(vec!["b".into(), false.into()]),
+            IndexKey::Tuple(vec!["b".into(), true.into()]),
+        ]);
+
+        // composite keys round-trip through values
+        keys.into_iter().for_each(|key| assert_eq!(
+            IndexKey::from_value(Value::from_index_key(key.clone()).unwrap()).unwrap(), key));
+    }
+
+    #[test]
+    fn test_partition_by_dataframe() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array1::from_shape_fn(10, |i| i as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let by = analysis.literal()
+            .value(Value::Dataframe(indexmap![
+                "state".into() => Array1::from_shape_fn(10, |i| ["CA", "WA"][i % 2].to_string()).into_dyn().into(),
+                "member".into() => Array1::from_shape_fn(10, |i| i % 3 == 0).into_dyn().into()
+            ]))
+            .value_public(true)
+            .build();
+        let partitioned = analysis.partition(data).by(by).build();
+
+        let properties = analysis.properties(partitioned).unwrap();
+        let children = &properties.partitions().unwrap().children;
+        assert_eq!(children.len(), 4);
+        assert!(children.contains_key(&IndexKey::Tuple(vec!["WA".into(), false.into()])));
+
+        // index back out by the composite key
+        let names = analysis.literal()
+            .value(Value::Dataframe(indexmap![
+                "state".into() => "WA".to_string().into(),
+                "member".into() => false.into()
+            ]))
+            .value_public(true)
+            .build();
+        let indexed = analysis.index(partitioned).names(names).build();
+        let indexed = analysis.properties(indexed).unwrap();
+        assert_eq!(indexed.array().unwrap().group_id[0].index, IndexKey::Tuple(vec!["WA".into(), false.into()]));
+    }
+
+    #[test]
+    fn test_quantile_edges() {
+        let edges = make_quantile_edges(vec![5., 2., 5.].into_iter(), f64::MIN, f64::MAX).unwrap();
+        assert_eq!(edges, vec![f64::MIN, 2., 5., f64::MAX]);
+        assert!(make_quantile_edges(vec![f64::NAN].into_iter(), f64::MIN, f64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_partition_by_quantiles() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 2), |(i, j)| (i + j) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let by = analysis.literal()
+            .value(Array2::from_shape_fn((10, 1), |(i, _)| i as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let cut_points = analysis.literal()
+            .value(arr1(&[6., 3.]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let partitioned = analysis.partition_by_quantiles(data, by, cut_points).build();
+
+        // three strata. The null stratum is omitted, because `by` is bounded and contains no NaN
+        let properties = analysis.properties(partitioned).unwrap();
+        let children = &properties.partitions().unwrap().children;
+        assert_eq!(children.len(), 3);
+        assert!(!children.contains_key(&IndexKey::Tuple(vec![IndexKey::from(-1)])));
+
+        // each partition is tagged with the partition it belongs to
+        children.values().for_each(|child| {
+            let child = child.array().unwrap();
+            assert_eq!(child.num_columns, Some(2));
+            assert_eq!(child.group_id.len(), 1);
+        });
+
+        // integer strata require integer cut-points
+        let by = analysis.literal()
+            .value(Array2::from_shape_fn((10, 1), |(i, _)| i as i64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let partitioned = analysis.partition_by_quantiles(data, by, cut_points).build();
+        assert!(analysis.properties(partitioned).is_err());
+    }
+}
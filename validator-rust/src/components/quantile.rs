@@ -5,7 +5,7 @@ use crate::{base, Float, proto, Warnable};
 use crate::base::{
     AggregatorProperties, DataType, IndexKey,
     NodeProperties, SensitivitySpace, Value, ValueProperties,
-    ArrayProperties, Nature, NatureContinuous, Vector1DNull
+    ArrayProperties, Interpolation, Nature, NatureContinuous, Vector1DNull
 };
 use crate::components::{Component, Expandable, Sensitivity};
 use crate::errors::*;
@@ -31,6 +31,8 @@ impl Component for proto::Quantile {
             return Err("data: atomic type must be numeric".into());
         }
 
+        self.interpolation.parse::<Interpolation>()?;
+
         let has_bounds = public_arguments.contains_key(&IndexKey::from("lower"))
             && public_arguments.contains_key(&IndexKey::from("upper"));
 
@@ -116,13 +118,31 @@ impl Sensitivity for proto::Quantile {
         data_property.assert_is_not_aggregated()?;
 
         match sensitivity_type {
+            // The sensitivity of a quantile of data bounded in [lower, upper]:
+            // * Substitute: when n is odd and the order statistics are split between lower and upper,
+            //   substituting one record moves the quantile from lower to upper. The sensitivity is upper - lower.
+            // * AddRemove: adding or removing a record shifts the rank of the quantile by at most one.
+            //   For the midpoint median of non-empty data, the median of an even-sized dataset is the midpoint of
+            //   the two middle order statistics, and the median of the neighbor is one of them, or a value between them.
+            //   The sensitivity is (upper - lower) / 2.
+            //   For any other quantile or interpolation, one shifted rank may move the quantile from lower to upper.
             SensitivitySpace::KNorm(_k) => {
                 let lower = data_property.lower_float()?;
                 let upper = data_property.upper_float()?;
 
+                use proto::privacy_definition::Neighboring;
+                let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+                    .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+                let scale = match neighboring_type {
+                    Neighboring::AddRemove if self.alpha == 0.5
+                        && self.interpolation.parse::<Interpolation>()? == Interpolation::Midpoint
+                        && data_property.is_not_empty => 0.5,
+                    Neighboring::AddRemove | Neighboring::Substitute => 1.
+                };
+
                 let row_sensitivity = lower.iter()
                     .zip(upper.iter())
-                    .map(|(min, max)| max - min)
+                    .map(|(min, max)| (max - min) * scale)
                     .collect::<Vec<Float>>();
 
                 let mut array_sensitivity = Array::from(row_sensitivity).into_dyn();
@@ -217,4 +237,67 @@ macro_rules! make_quantile {
 
 make_quantile!(Minimum, 0.0, "lower".to_string());
 make_quantile!(Median, 0.5, "midpoint".to_string());
-make_quantile!(Maximum, 1.0, "upper".to_string());
+make_quantile!(Maximum, 1.0, "higher".to_string());
+
+
+#[cfg(test)]
+mod test_quantile {
+    use ndarray::Array2;
+
+    use crate::base::{SensitivitySpace, Value};
+    use crate::bindings::Analysis;
+    use crate::components::Sensitivity;
+    use crate::proto::privacy_definition::Neighboring;
+
+    fn sensitivity(alpha: f64, interpolation: &str, neighboring: Neighboring) -> f64 {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.neighboring = neighboring as i32;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 1), |(i, _)| i as f64 * 5. - 20.).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value((-1.).into()).value_public(true).build();
+        let upper = analysis.literal().value(11.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let quantile = analysis.quantile(clamped, alpha)
+            .interpolation(interpolation.to_string())
+            .build();
+
+        let properties = analysis.properties(quantile).unwrap();
+        let aggregator = properties.array().unwrap().aggregator.clone().unwrap();
+        match aggregator.component.compute_sensitivity(
+            &analysis.privacy_definition, &aggregator.properties, &SensitivitySpace::KNorm(1)).unwrap() {
+            Value::Array(array) => array.first_float().unwrap(),
+            _ => panic!("sensitivity must be an array")
+        }
+    }
+
+    #[test]
+    fn test_sensitivity_neighboring() {
+        // the data is bounded in [-1, 11]
+        assert_eq!(sensitivity(0.5, "midpoint", Neighboring::Substitute), 12.);
+        assert_eq!(sensitivity(0.5, "midpoint", Neighboring::AddRemove), 6.);
+        assert_eq!(sensitivity(0.5, "lower", Neighboring::AddRemove), 12.);
+        assert_eq!(sensitivity(0.25, "midpoint", Neighboring::AddRemove), 12.);
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let quantile = |interpolation: &str| {
+            let mut analysis = Analysis::new();
+            let data = analysis.literal()
+                .value(Array2::from_shape_fn((10, 1), |(i, _)| i as f64).into_dyn().into())
+                .value_public(true)
+                .build();
+            let quantile = analysis.quantile(data, 0.5)
+                .interpolation(interpolation.to_string())
+                .build();
+            analysis.properties(quantile)
+        };
+
+        for interpolation in &["lower", "higher", "nearest", "midpoint", "linear", "Upper"] {
+            assert!(quantile(interpolation).is_ok());
+        }
+        assert!(quantile("cubic").is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::errors::*;
+
+use crate::{proto, base, Warnable, Float};
+use crate::components::Component;
+use crate::base::{IndexKey, Value, NodeProperties, ValueProperties, DataType, Nature, NatureContinuous, Vector1DNull};
+use crate::utilities::prepend;
+use indexmap::map::IndexMap;
+use std::convert::TryFrom;
+
+impl Component for proto::Rank {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        data_property.assert_is_not_aggregated()?;
+
+        if !matches!(data_property.data_type, DataType::Float | DataType::Int) {
+            return Err("data: atomic type must be numeric".into())
+        }
+
+        let num_columns = data_property.num_columns()?;
+
+        // one record may change the rank of every row, so every row is influenced by each record
+        if !data_property.releasable {
+            let num_records = data_property.num_records
+                .ok_or_else(|| Error::from("data: the number of records must be known, to bound the number of ranks a record may change"))?;
+            data_property.c_stability = u32::try_from(num_records).ok()
+                .and_then(|num_records| data_property.c_stability.checked_mul(num_records))
+                .ok_or_else(|| Error::from("data: too many records to bound the number of ranks a record may change"))?;
+        }
+
+        // normalized ranks are in [0, 1], otherwise ranks are in [1, n]
+        let (lower, upper) = match self.normalize {
+            true => (Some(0.), Some(1.)),
+            false => (Some(1.), data_property.num_records.map(|n| n as Float))
+        };
+        data_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::Float((0..num_columns).map(|_| lower).collect()),
+            upper: Vector1DNull::Float((0..num_columns).map(|_| upper).collect()),
+        }));
+        data_property.data_type = DataType::Float;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+#[cfg(test)]
+mod test_rank {
+    use ndarray::arr2;
+
+    use crate::base::{DataType, Nature, Vector1DNull};
+    use crate::bindings::Analysis;
+
+    #[test]
+    fn test_rank_bounds() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[2], [1], [2]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let normalized = analysis.rank(data).build();
+        let ranks = analysis.rank(data).normalize(false).build();
+
+        let bounds = |node_id| {
+            let properties = analysis.properties(node_id).unwrap();
+            let properties = properties.array().unwrap();
+            assert_eq!(properties.data_type, DataType::Float);
+            match properties.nature.clone().unwrap() {
+                Nature::Continuous(nature) => match (nature.lower, nature.upper) {
+                    (Vector1DNull::Float(lower), Vector1DNull::Float(upper)) => (lower, upper),
+                    _ => panic!("bounds must be float")
+                },
+                _ => panic!("nature must be continuous")
+            }
+        };
+        assert_eq!(bounds(normalized), (vec![Some(0.)], vec![Some(1.)]));
+        assert_eq!(bounds(ranks), (vec![Some(1.)], vec![Some(3.)]));
+
+        // strings may not be ranked
+        let data = analysis.literal()
+            .value(arr2(&[["a".to_string()]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let ranks = analysis.rank(data).build();
+        assert!(analysis.properties(ranks).is_err());
+    }
+
+    #[test]
+    fn test_rank_stability() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[2.], [1.], [2.], [4.]]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let data = analysis.to_float(data).build();
+        let number_columns = analysis.literal().value(1.into()).value_public(true).build();
+        let number_rows = analysis.literal().value(4.into()).value_public(true).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(4.0.into()).value_public(true).build();
+        let data = analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .lower(lower).upper(upper)
+            .build();
+        let ranks = analysis.rank(data).build();
+
+        // a record may change the rank of each of the four rows, so mechanisms spend a quarter of their usage per row
+        let properties = analysis.properties(ranks).unwrap();
+        assert_eq!(properties.array().unwrap().c_stability, 4);
+
+        // without a known number of records, the number of ranks a record may change is unbounded
+        let data = analysis.literal()
+            .value(arr2(&[[2.], [1.]]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let data = analysis.to_float(data).build();
+        let data = analysis.resize(data)
+            .number_columns(number_columns)
+            .lower(lower).upper(upper)
+            .build();
+        let ranks = analysis.rank(data).build();
+        let error = analysis.properties(ranks).unwrap_err();
+        assert!(format!("{:?}", error).contains("the number of records must be known"), "{:?}", error);
+    }
+}
@@ -1,8 +1,8 @@
 use crate::errors::*;
 
 use crate::components::Component;
-use crate::base::{Value, ValueProperties, IndexKey, PartitionsProperties};
-use crate::{base, Warnable};
+use crate::base::{Value, ValueProperties, IndexKey, PartitionsProperties, Nature, NatureContinuous, DataType, Vector1DNull};
+use crate::{base, Warnable, Float, Integer};
 use crate::proto;
 use crate::utilities::prepend;
 use indexmap::map::IndexMap;
@@ -20,38 +20,93 @@ impl Component for proto::Reshape {
             .ok_or("data: missing")?.array()
             .map_err(prepend("data:"))?.clone();
 
-        if !data_property.releasable {
-            data_property.assert_is_not_aggregated()?;
-        }
+        // the sensitivity of an aggregate is only known in its original shape
+        data_property.assert_is_not_aggregated()?;
+        // values from the same record are spread over several rows,
+        //    so rows of the output no longer correspond to individuals
         data_property.assert_is_releasable()?;
 
-        let partition_count = match data_property.num_records {
+        if self.layout.to_lowercase() != "row" {
+            return Err("layout: only the row layout is supported".into())
+        }
+
+        // each row of the input is reshaped into one matrix. A vector is a single row
+        let (partition_count, row_length) = match data_property.dimensionality {
+            Some(0) => (Some(1), Some(1)),
+            Some(1) => (Some(1), data_property.num_records),
+            _ => (data_property.num_records, data_property.num_columns)
+        };
+        let partition_count = match partition_count {
             Some(0) | None => return Err("number of records must be one or more".into()),
             Some(1) => None,
             Some(x) => Some(x)
         };
 
-        data_property.num_records = match self.shape.len() {
+        let (num_records, num_columns) = match (self.symmetric, self.shape.as_slice()) {
+            (true, [size]) => (*size, *size),
+            (true, [height, width]) if height == width => (*height, *width),
+            (true, [_, _]) => return Err("the width and height must match to form a triangular matrix".into()),
+            (true, _) => return Err("shape must be 1 or 2-dimensional to form a triangular matrix".into()),
             // target is 0-d, so there will be one record in output
-            0 => Some(1),
+            (false, []) => (1, 1),
             // target is 1-d or 2-d, so there will be as many rows as the value in the first axis
-            1 | 2 => Some(self.shape[0] as i64),
-            _ => return Err("dimensionality may not be greater than 2".into())
+            (false, [height]) => (*height, 1),
+            (false, [height, width]) => (*height, *width),
+            (false, _) => return Err("dimensionality may not be greater than 2".into())
         };
+        let (num_records, num_columns) = (num_records as usize, num_columns as usize);
 
-        data_property.num_columns = match self.shape.len() {
-            0 | 1 => Some(1),
-            2 => Some(self.shape[1] as i64),
-            _ => return Err("dimensionality may not be greater than 2".into())
-        };
-
-        if data_property.num_records.unwrap() < 1 {
+        if num_records < 1 {
             return Err("number of records must be greater than zero".into())
         }
-        if data_property.num_columns.unwrap() < 1 {
+        if num_columns < 1 {
             return Err("number of columns must be greater than zero".into())
         }
 
+        // index into the input row of each element of the output matrix
+        let symmetric = self.symmetric;
+        let source_index = move |row: usize, column: usize| if symmetric {
+            // elements of the upper triangle, less the arithmetic progression
+            let (row, column) = if row <= column { (row, column) } else { (column, row) };
+            row * num_columns + column - (row + 1) * row / 2
+        } else { row * num_columns + column };
+
+        let expected_length = source_index(num_records - 1, num_columns - 1) + 1;
+        if let Some(row_length) = row_length {
+            if row_length as usize != expected_length {
+                return Err(format!("data: each row must have {} elements to form the shape, but has {}", expected_length, row_length).into())
+            }
+        }
+
+        data_property.nature = match &data_property.nature {
+            // gather the bounds of the elements that form each output column
+            Some(Nature::Continuous(_)) => {
+                let lower = data_property.lower_float_option()?;
+                let upper = data_property.upper_float_option()?;
+                let gather = |bounds: &Vec<Option<Float>>, combine: fn(Float, Float) -> Float| (0..num_columns)
+                    .map(|column| (0..num_records)
+                        .map(|row| *bounds.get(source_index(row, column)).or_else(|| bounds.first())?)
+                        .collect::<Option<Vec<Float>>>()
+                        .and_then(|bounds| bounds.into_iter().reduce(combine)))
+                    .collect::<Vec<Option<Float>>>();
+                let to_vector = |bounds: Vec<Option<Float>>| match data_property.data_type {
+                    DataType::Int => Vector1DNull::Int(bounds.into_iter()
+                        .map(|bound| bound.map(|bound| bound as Integer)).collect()),
+                    _ => Vector1DNull::Float(bounds)
+                };
+                Some(Nature::Continuous(NatureContinuous {
+                    lower: to_vector(gather(&lower, Float::min)),
+                    upper: to_vector(gather(&upper, Float::max)),
+                }))
+            }
+            // categories are not tracked across columns
+            _ => None
+        };
+
+        data_property.num_records = Some(num_records as i64);
+        data_property.num_columns = Some(num_columns as i64);
+        data_property.dimensionality = Some(if self.symmetric { 2 } else { self.shape.len() as i64 });
+
         // Treat this as a new dataset, because number of rows is not necessarily the same anymore
         // This exists to prevent binary ops on non-conformable arrays from being approved
         data_property.dataset_id = Some(node_id as i64);
@@ -69,4 +124,86 @@ impl Component for proto::Reshape {
             None => matrix_properties
         }.into())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_reshape {
+    use ndarray::Array2;
+
+    use crate::bindings::{Analysis, privacy_usage};
+
+    /// private float data with `num_columns` columns, bounded to [0, 10], for release with the laplace mechanism
+    fn bounded_data(analysis: &mut Analysis, num_columns: usize) -> u32 {
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, num_columns), |(i, j)| (i + j) as f64).into_dyn().into())
+            .value_public(false)
+            .build();
+        let data = analysis.to_float(data).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let number_rows = analysis.literal().value(10.into()).value_public(true).build();
+        let number_columns = analysis.literal().value((num_columns as i64).into()).value_public(true).build();
+        let data = analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .lower(lower).upper(upper)
+            .build();
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+        analysis.impute(data).lower(lower).upper(upper).build()
+    }
+
+    #[test]
+    fn test_reshape_release() {
+        let mut analysis = Analysis::new();
+        let data = bounded_data(&mut analysis, 4);
+        let means = analysis.dp_mean(data, privacy_usage(1., 0.))
+            .mechanism("Laplace".to_string()).build();
+
+        let matrix = analysis.reshape(means, vec![2, 2]).build();
+        let properties = analysis.properties(matrix).unwrap().array().unwrap().clone();
+        assert!(properties.releasable);
+        assert_eq!(properties.num_records, Some(2));
+        assert_eq!(properties.num_columns, Some(2));
+        assert_eq!(properties.dimensionality, Some(2));
+
+        // aggregating the reshaped release is postprocessing, over the new columns
+        let sums = analysis.sum(matrix).build();
+        assert_eq!(analysis.properties(sums).unwrap().array().unwrap().num_columns, Some(2));
+
+        // the shape must account for every element of the row
+        let matrix = analysis.reshape(means, vec![3]).build();
+        assert!(analysis.properties(matrix).is_err());
+    }
+
+    #[test]
+    fn test_reshape_symmetric() {
+        let mut analysis = Analysis::new();
+        let data = bounded_data(&mut analysis, 3);
+        let means = analysis.dp_mean(data, privacy_usage(1., 0.))
+            .mechanism("Laplace".to_string()).build();
+
+        let matrix = analysis.reshape(means, vec![2]).symmetric(true).build();
+        let properties = analysis.properties(matrix).unwrap().array().unwrap().clone();
+        assert_eq!(properties.num_records, Some(2));
+        assert_eq!(properties.num_columns, Some(2));
+
+        let matrix = analysis.reshape(means, vec![2, 3]).symmetric(true).build();
+        assert!(analysis.properties(matrix).is_err());
+    }
+
+    #[test]
+    fn test_reshape_private() {
+        let mut analysis = Analysis::new();
+        let data = bounded_data(&mut analysis, 4);
+
+        // values of a record would be spread over several rows
+        let matrix = analysis.reshape(data, vec![2, 2]).build();
+        assert!(analysis.properties(matrix).is_err());
+
+        // the sensitivity of the aggregate would no longer match its shape
+        let means = analysis.mean(data).build();
+        let matrix = analysis.reshape(means, vec![2, 2]).build();
+        let means = analysis.laplace_mechanism(matrix, privacy_usage(1., 0.)).build();
+        assert!(analysis.properties(means).is_err());
+    }
+}
@@ -5,7 +5,7 @@ use crate::base::{DataType, IndexKey, Jagged, Nature, NatureCategorical, NatureC
 use crate::components::{Component, Expandable};
 use crate::errors::*;
 use crate::proto;
-use crate::utilities::{get_literal, prepend, standardize_categorical_argument};
+use crate::utilities::{get_literal, inferred_argument_warning, prepend, standardize_categorical_argument};
 use crate::utilities::inference::infer_property;
 
 impl Component for proto::Resize {
@@ -52,6 +52,10 @@ impl Component for proto::Resize {
                 return Err("number_rows: must be greater than zero".into());
             }
 
+            // rows may be sampled or padded, so a row no longer corresponds to a fixed record
+            if data_property.num_records != Some(num_records) {
+                data_property.naturally_ordered = false;
+            }
             data_property.num_records = Some(num_records as i64);
             data_property.is_not_empty = num_records > 0;
         }
@@ -88,7 +92,12 @@ impl Component for proto::Resize {
                                 .zip(standardize_categorical_argument(categories, num_columns)?.into_iter())
                                 .map(|(l, r)| [l, r].concat())
                                 .collect::<Vec<_>>()),
-                        _ => return Err("categories may not be float".into())
+                        (Jagged::Float(prior), Jagged::Float(categories)) =>
+                            Jagged::Float(Jagged::Float(prior).standardize(num_columns)?.float()?.into_iter()
+                                .zip(Jagged::Float(categories).standardize(num_columns)?.float()?)
+                                .map(|(l, r)| [l, r].concat())
+                                .collect::<Vec<_>>()),
+                        _ => return Err("categories must have the same atomic type as the prior categories".into())
                     }.deduplicate()?
                 })),
                 _ => None
@@ -96,6 +105,8 @@ impl Component for proto::Resize {
             return Ok(ValueProperties::Array(data_property).into())
         }
 
+        check_distribution(&data_property.data_type, &public_arguments, &properties)?;
+
         match data_property.data_type {
             DataType::Float => {
 
@@ -254,6 +265,36 @@ impl Component for proto::Resize {
     }
 }
 
+/// Check that the imputation distribution is supported for the data type,
+/// and that the arguments it is parameterized by are provided.
+fn check_distribution(
+    data_type: &DataType,
+    public_arguments: &IndexMap<base::IndexKey, &Value>,
+    properties: &base::NodeProperties,
+) -> Result<()> {
+    let distribution = match public_arguments.get::<IndexKey>(&"distribution".into()) {
+        Some(distribution) => distribution.ref_array()?.first_string()
+            .map_err(prepend("distribution:"))?.to_lowercase(),
+        None => "uniform".to_string()
+    };
+
+    let required: &[&str] = match (data_type, distribution.as_str()) {
+        (DataType::Float, "uniform") | (DataType::Int, "uniform") => &[],
+        (DataType::Float, "gaussian") | (DataType::Int, "gaussian") => &["shift", "scale"],
+        (DataType::Float, "exponential") => &["scale"],
+        (DataType::Float, "triangular") => &["shift"],
+        (DataType::Float, _) =>
+            return Err("distribution: must be one of uniform, gaussian, exponential or triangular for float data".into()),
+        (DataType::Int, _) =>
+            return Err("distribution: must be one of uniform or gaussian for integer data".into()),
+        _ => return Ok(())
+    };
+
+    required.iter()
+        .find(|name| !properties.contains_key::<IndexKey>(&(**name).into()))
+        .map_or(Ok(()), |name| Err(format!("{}: must be defined for {} imputation", name, distribution).into()))
+}
+
 impl Expandable for proto::Resize {
     fn expand_component(
         &self,
@@ -291,6 +332,7 @@ impl Expandable for proto::Resize {
                 expansion.properties.insert(id_lower, infer_property(&release.value, None, id_lower)?);
                 expansion.releases.insert(id_lower, release);
                 component.insert_argument(&"lower".into(), id_lower);
+                expansion.warnings.push(inferred_argument_warning("lower"));
             }
             if !has_upper {
                 maximum_id += 1;
@@ -301,6 +343,7 @@ impl Expandable for proto::Resize {
                 expansion.properties.insert(id_upper, infer_property(&release.value, None, id_upper)?);
                 expansion.releases.insert(id_upper, release);
                 component.insert_argument(&"upper".into(), id_upper);
+                expansion.warnings.push(inferred_argument_warning("upper"));
             }
         }
         // categorical resizing
@@ -313,6 +356,7 @@ impl Expandable for proto::Resize {
             expansion.properties.insert(id_categories, infer_property(&release.value, None, id_categories)?);
             expansion.releases.insert(id_categories, release);
             component.insert_argument(&"categories".into(), id_categories);
+            expansion.warnings.push(inferred_argument_warning("categories"));
         }
         // unknown clamping procedure
         else {
@@ -513,4 +557,108 @@ pub mod test_resize {
         array1d_bool_0; 10.into(),
         array1d_bool_10_uniform; 10.into(),
     );
+
+    #[test]
+    fn test_f64_cat() {
+        use crate::base::{Jagged, Nature, Value};
+        use crate::components::clamp::test_clamp;
+
+        let (mut analysis, clamped) = test_clamp::utilities::analysis_f64_cat(
+            test_data::array1d_f64_10_uniform(),
+            Value::Jagged(vec![vec![0.2642, 0.5]].into()),
+            (-1.).into());
+        let categories = analysis.literal()
+            .value(Value::Jagged(vec![vec![0.5, 0.75]].into()))
+            .value_public(true).build();
+        let number_rows = analysis.literal()
+            .value(10.into()).value_public(true)
+            .build();
+        let resized = analysis.resize(clamped)
+            .categories(categories)
+            .number_rows(number_rows)
+            .build();
+
+        // prior float categories are combined with the imputation categories
+        let properties = analysis.properties(resized).unwrap();
+        match properties.array().unwrap().nature.as_ref().unwrap() {
+            Nature::Categorical(nature) => match &nature.categories {
+                Jagged::Float(categories) => assert_eq!(categories, &vec![vec![0.2642, 0.5, -1., 0.75]]),
+                _ => panic!("categories must be float")
+            },
+            _ => panic!("nature must be categorical")
+        }
+    }
+
+    #[test]
+    fn test_categorical_histogram() {
+        use ndarray::arr0;
+        use crate::base::{Jagged, Nature, Value};
+        use crate::proto;
+
+        let categories = || Value::Jagged(vec![vec!["a", "b", "c", "d"].into_iter().map(String::from).collect::<Vec<String>>()].into());
+        let (mut analysis, resized) = utilities::analysis_string_cat(
+            test_data::array1d_string_10_uniform(), 10.into(), Some(categories()));
+
+        // imputation and resizing draw only from the known categories, so the category set is retained
+        // the clamp null value "e" is not imputed, so it remains a category
+        let properties = analysis.properties(resized).unwrap();
+        match properties.array().unwrap().nature.as_ref().unwrap() {
+            Nature::Categorical(nature) => match &nature.categories {
+                Jagged::Str(categories) => assert_eq!(categories[0], vec!["a", "b", "c", "d", "e"]),
+                _ => panic!("categories must be strings")
+            },
+            _ => panic!("nature must be categorical")
+        }
+
+        // a histogram after the resize takes its categories from the data
+        let lower = analysis.literal().value(arr0(0).into_dyn().into()).value_public(true).build();
+        let inclusive_left = analysis.literal().value(arr0(true).into_dyn().into()).value_public(true).build();
+        let dp_histogram = analysis.dp_histogram(resized, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }]).lower(lower).inclusive_left(inclusive_left).build();
+        assert!(analysis.properties(dp_histogram).unwrap().array().unwrap().releasable);
+    }
+
+    #[test]
+    fn test_distribution() {
+        use crate::bindings::Analysis;
+        use crate::components::impute::test_impute;
+
+        let resize = |distribution: &str, shift: bool, scale: bool| -> (Analysis, u32) {
+            let (mut analysis, imputed) = test_impute::utilities::analysis_f64_cont(
+                test_data::array1d_f64_10_uniform(), None, None);
+            let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+            let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+            let number_rows = analysis.literal().value(10.into()).value_public(true).build();
+            let distribution = analysis.literal()
+                .value(distribution.to_string().into()).value_public(true).build();
+
+            let mut builder = analysis.resize(imputed)
+                .number_rows(number_rows).lower(lower).upper(upper)
+                .distribution(distribution);
+            if shift {
+                builder = builder.shift(lower);
+            }
+            if scale {
+                builder = builder.scale(upper);
+            }
+            let resized = builder.build();
+            (analysis, resized)
+        };
+
+        let (analysis, resized) = resize("Exponential", false, true);
+        analysis.properties(resized).unwrap();
+        let (analysis, resized) = resize("triangular", true, false);
+        analysis.properties(resized).unwrap();
+
+        // unknown distributions are rejected at validation
+        let (analysis, resized) = resize("cauchy", true, true);
+        assert!(analysis.properties(resized).is_err());
+
+        // the gaussian requires a scale
+        let (analysis, resized) = resize("gaussian", true, false);
+        assert!(analysis.properties(resized).is_err());
+    }
 }
@@ -111,7 +111,7 @@ impl Expandable for proto::SimpleGeometricMechanism {
             }
 
             if let Some(upper_id) = upper_id {
-                let (patch_node, release) = get_literal(Value::Array(data_property.lower()
+                let (patch_node, release) = get_literal(Value::Array(data_property.upper()
                     .map_err(|_| Error::from("upper bound on the statistic is unknown for the simple geometric mechanism. Either pass upper as an argument or sufficiently preprocess the data to make an upper bound inferrable."))?), component.submission)?;
                 expansion.computation_graph.insert(upper_id, patch_node);
                 expansion.properties.insert(upper_id, infer_property(&release.value, None, upper_id)?);
@@ -160,14 +160,15 @@ impl Accuracy for proto::SimpleGeometricMechanism {
             .map(|sensitivity_col| sensitivity_col.into_iter().copied().fold1(|l, r| l.max(r)).unwrap())
             .collect();
 
+        // the bounds only narrow the error, so the usage is computed without them
         Ok(Some(sensitivities.into_iter().zip(accuracies.values.iter())
-            .map(|(sensitivity, accuracy)| proto::PrivacyUsage {
+            .map(|(sensitivity, accuracy)| Ok(proto::PrivacyUsage {
                 distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
-                    epsilon: (1. / accuracy.alpha).ln() * (sensitivity as f64 / accuracy.value),
+                    epsilon: sensitivity / geometric_accuracy_to_scale(accuracy.value, accuracy.alpha)?,
                     delta: 0.,
                 }))
-            })
-            .collect()))
+            }))
+            .collect::<Result<Vec<_>>>()?))
     }
 
     fn privacy_usage_to_accuracy(
@@ -186,11 +187,194 @@ impl Accuracy for proto::SimpleGeometricMechanism {
         let usages = spread_privacy_usage(&self.privacy_usage, sensitivities.len())?;
         let epsilon = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
 
-        Ok(Some(sensitivities.into_iter().zip(epsilon.into_iter())
-            .map(|(sensitivity, epsilon)| proto::Accuracy {
-                value: ((1. / alpha).ln() * (sensitivity / epsilon) as f64).ceil(),
-                alpha
+        // releases are clamped to the bounds, so the error never exceeds their width
+        let widths = match (public_arguments.get(&IndexKey::from("lower")), public_arguments.get(&IndexKey::from("upper"))) {
+            (Some(lower), Some(upper)) => {
+                let lower = broadcast_bound(lower, sensitivities.len())?;
+                let upper = broadcast_bound(upper, sensitivities.len())?;
+                Some(upper.iter().zip(lower.iter()).map(|(u, l)| u - l).collect::<Vec<f64>>())
+            },
+            _ => None
+        };
+
+        Ok(Some(sensitivities.into_iter().zip(epsilon).enumerate()
+            .map(|(column_number, (sensitivity, epsilon))| {
+                let accuracy = geometric_scale_to_accuracy(sensitivity / epsilon, alpha)?;
+                Ok(proto::Accuracy {
+                    value: match &widths {
+                        Some(widths) => accuracy.min(widths[column_number].max(0.)),
+                        None => accuracy
+                    },
+                    alpha
+                })
             })
-            .collect()))
+            .collect::<Result<Vec<_>>>()?))
+    }
+}
+
+/// Broadcast a bound on the statistic to one value per column.
+fn broadcast_bound(bound: &Value, num_columns: usize) -> Result<Vec<f64>> {
+    let bound = bound.ref_array()?.clone().cast_float()?.iter().copied().collect::<Vec<f64>>();
+    match bound.len() {
+        1 => Ok(vec![bound[0]; num_columns]),
+        length if length == num_columns => Ok(bound),
+        _ => Err("bounds: must be a scalar, or have one value for each column".into())
+    }
+}
+
+/// Compute the two-sided accuracy of the geometric mechanism.
+///
+/// Noise is drawn from the discrete Laplace (two-sided geometric) distribution,
+/// where `P(Z = z) = (1 - q) / (1 + q) * q^|z|` and `q = exp(-1 / scale)`.
+/// The tail is `P(|Z| > a) = 2 q^(a + 1) / (1 + q)` for a non-negative integer `a`,
+/// so the accuracy is the smallest integer `a` for which the tail is at most `alpha`.
+/// This is at most one less than the continuous approximation `ceil(scale * ln(1 / alpha))`.
+///
+/// # Arguments
+/// * `scale` - Ratio of the sensitivity to epsilon.
+/// * `alpha` - Probability that the error exceeds the accuracy.
+///
+/// # Return
+/// Smallest integer `a` for which `|Z| <= a` with probability at least `1 - alpha`.
+pub fn geometric_scale_to_accuracy(scale: f64, alpha: f64) -> Result<f64> {
+    if !(alpha > 0. && alpha < 1.) {
+        return Err("alpha: must be within (0, 1)".into())
+    }
+    if scale == 0. {
+        return Ok(0.)
+    }
+    let q = (-1. / scale).exp();
+    let tail = |accuracy: f64| 2. * q.powf(accuracy + 1.) / (1. + q);
+
+    // the tail at a is below alpha when a + 1 >= scale * ln(2 / (alpha * (1 + q)))
+    let mut accuracy = ((scale * (2. / (alpha * (1. + q))).ln()).ceil() - 1.).max(0.);
+    // correct for floating-point error in the closed form, so that the tail is within alpha
+    while accuracy > 0. && tail(accuracy - 1.) <= alpha { accuracy -= 1. }
+    while tail(accuracy) > alpha { accuracy += 1. }
+    Ok(accuracy)
+}
+
+/// Compute the largest scale of the geometric mechanism that attains an accuracy.
+///
+/// Since the noise is integral, an accuracy `a` is attained when `P(|Z| > floor(a)) <= alpha`.
+/// The tail is increasing in the scale, so the scale is found by bisection.
+///
+/// # Arguments
+/// * `accuracy` - Desired bound on the absolute error.
+/// * `alpha` - Probability that the error exceeds the accuracy.
+///
+/// # Return
+/// Largest scale (the ratio of the sensitivity to epsilon) at which the accuracy is attained.
+pub fn geometric_accuracy_to_scale(accuracy: f64, alpha: f64) -> Result<f64> {
+    if !(alpha > 0. && alpha < 1.) {
+        return Err("alpha: must be within (0, 1)".into())
+    }
+    if accuracy.is_nan() || accuracy < 1. || accuracy.is_infinite() {
+        return Err("accuracy: must be at least one, as the noise is integral".into())
+    }
+    let accuracy = accuracy.floor();
+    let tail = |scale: f64| {
+        let q = (-1. / scale).exp();
+        2. * q.powf(accuracy + 1.) / (1. + q)
+    };
+
+    // the continuous approximation brackets the scale
+    let (mut lower, mut upper) = (accuracy / (2. / alpha).ln(), (accuracy + 1.) / (1. / alpha).ln());
+    (0..100).for_each(|_| {
+        let middle = (lower + upper) / 2.;
+        if tail(middle) <= alpha { lower = middle } else { upper = middle }
+    });
+    // the lower end of the bracket always attains the accuracy
+    Ok(lower)
+}
+
+#[cfg(test)]
+mod test_simple_geometric_mechanism {
+    use ndarray::arr2;
+
+    use crate::base::{IndexKey, Value};
+    use crate::components::{Accuracy, Expandable};
+    use crate::components::simple_geometric_mechanism::{geometric_accuracy_to_scale, geometric_scale_to_accuracy};
+    use crate::proto;
+
+    fn mechanism(epsilon: f64) -> proto::SimpleGeometricMechanism {
+        proto::SimpleGeometricMechanism {
+            privacy_usage: vec![proto::PrivacyUsage {
+                distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                    epsilon, delta: 0.
+                }))
+            }]
+        }
+    }
+
+    #[test]
+    fn test_discrete_tail() {
+        let (scale, alpha) = (2., 0.05);
+        let accuracy = geometric_scale_to_accuracy(scale, alpha).unwrap();
+
+        // the tail just beyond the accuracy is within alpha, but the tail at the accuracy is not
+        let q = (-1. / scale).exp();
+        let tail = |a: f64| 2. * q.powf(a + 1.) / (1. + q);
+        assert_eq!(accuracy, 6.);
+        assert!(tail(accuracy) <= alpha && tail(accuracy - 1.) > alpha);
+        // never wider than the continuous approximation
+        assert!(accuracy <= (scale * (1. / alpha).ln()).ceil());
+
+        // the scale recovered from an accuracy attains the accuracy
+        (1..50).for_each(|accuracy| {
+            let scale = geometric_accuracy_to_scale(accuracy as f64, alpha).unwrap();
+            assert_eq!(geometric_scale_to_accuracy(scale, alpha).unwrap(), accuracy as f64);
+            assert!(geometric_scale_to_accuracy(scale * 1.001, alpha).unwrap() > accuracy as f64);
+        });
+
+        assert!(geometric_scale_to_accuracy(scale, 1.).is_err());
+        assert!(geometric_accuracy_to_scale(0.5, alpha).is_err());
+    }
+
+    #[test]
+    fn test_accuracy() {
+        let sensitivity: Value = arr2(&[[1.]]).into_dyn().into();
+        let (lower, upper): (Value, Value) = (0.into(), 4.into());
+
+        let mut arguments = indexmap![IndexKey::from("sensitivity") => &sensitivity];
+        let accuracies = mechanism(0.5).privacy_usage_to_accuracy(arguments.clone(), 0.05).unwrap().unwrap();
+        assert_eq!(accuracies[0].value, 6.);
+
+        let usages = mechanism(0.5).accuracy_to_privacy_usage(
+            &proto::Accuracies { values: accuracies }, arguments.clone()).unwrap().unwrap();
+        let epsilon = crate::utilities::privacy::get_epsilon(&usages[0]).unwrap();
+        assert!(epsilon <= 0.5 && epsilon > 0.45, "epsilon: {}", epsilon);
+
+        // clamping to the bounds caps the error at their width
+        arguments.insert(IndexKey::from("lower"), &lower);
+        arguments.insert(IndexKey::from("upper"), &upper);
+        let accuracies = mechanism(0.5).privacy_usage_to_accuracy(arguments, 0.05).unwrap().unwrap();
+        assert_eq!(accuracies[0].value, 4.);
+    }
+
+    #[test]
+    fn test_inferred_bounds() {
+        let mut analysis = crate::bindings::Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[1], [5], [8]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let sum = analysis.sum(data).build();
+        let properties = indexmap![IndexKey::from("data") => analysis.properties(sum).unwrap()];
+
+        // neither bound is passed, so both are inferred from the bounds on the sum of the records in [1, 8]
+        let component = proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(indexmap![IndexKey::from("data") => sum])),
+            variant: Some(proto::component::Variant::SimpleGeometricMechanism(mechanism(1.))),
+            omit: false,
+            submission: 0,
+        };
+        let expansion = mechanism(1.).expand_component(
+            &Some(analysis.privacy_definition.clone()), &component, &indexmap![], &properties, 10, 10).unwrap();
+
+        let expanded = expansion.computation_graph.get(&10).unwrap();
+        let bound = |name: &str| expansion.releases.get(expanded.arguments().get(&IndexKey::from(name)).unwrap()).unwrap()
+            .value.ref_array().unwrap().first_int().unwrap();
+        assert_eq!((bound("lower"), bound("upper")), (3, 24));
     }
 }
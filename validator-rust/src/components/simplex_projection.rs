@@ -0,0 +1,40 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto, Warnable};
+use crate::base::{DataType, IndexKey, Nature, NatureContinuous, Value, ValueProperties, Vector1DNull};
+use crate::components::Component;
+use crate::errors::*;
+use crate::utilities::prepend;
+
+impl Component for proto::SimplexProjection {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        // every element of a column influences every element of the projection
+        if !data_property.releasable {
+            return Err("data: must be releasable, because the simplex projection may only postprocess a release".into())
+        }
+
+        if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
+            return Err("data: atomic type must be numeric".into())
+        }
+
+        let num_columns = data_property.num_columns()?;
+        data_property.data_type = DataType::Float;
+        data_property.nullity = false;
+        data_property.nature = Some(Nature::Continuous(NatureContinuous {
+            lower: Vector1DNull::Float((0..num_columns).map(|_| Some(0.)).collect()),
+            upper: Vector1DNull::Float((0..num_columns).map(|_| Some(1.)).collect()),
+        }));
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
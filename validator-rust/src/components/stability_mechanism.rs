@@ -0,0 +1,252 @@
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, Integer, proto, Warnable};
+use crate::base::{ArrayProperties, DataframeProperties, DataType, IndexKey, NodeProperties, Value, ValueProperties};
+use crate::components::{Component, Expandable, Mechanism};
+use crate::errors::*;
+use crate::utilities::prepend;
+use crate::utilities::privacy::{get_delta, privacy_usage_check, spread_privacy_usage};
+
+impl Component for proto::StabilityMechanism {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy_definition must be defined")?;
+
+        if privacy_definition.protect_elapsed_time {
+            return Err("Elapsed time protections are enabled. The number of categories the stability mechanism samples noise for depends on the data.".into())
+        }
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if data_property.data_type == DataType::Unknown {
+            return Err("data_type must be known".into())
+        }
+
+        if data_property.num_columns()? != 1 {
+            return Err("data must contain one column".into())
+        }
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or("privacy_usage: must be defined")??;
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        // the threshold is calibrated to delta
+        if get_delta(&privacy_usage)? == 0.0 {
+            return Err("delta: may not be zero".into())
+        }
+
+        // the released categories are a data-dependent subset of the observed categories
+        let released_property = |data_type: DataType| ValueProperties::Array(ArrayProperties {
+            num_records: None,
+            num_columns: Some(1),
+            nullity: false,
+            releasable: true,
+            c_stability: 1,
+            aggregator: None,
+            nature: None,
+            data_type,
+            dataset_id: Some(node_id as i64),
+            node_id: node_id as i64,
+            is_not_empty: false,
+            dimensionality: Some(1),
+            group_id: data_property.group_id.clone(),
+            naturally_ordered: true,
+            sample_proportion: None,
+        });
+
+        Ok(Warnable(ValueProperties::Dataframe(DataframeProperties {
+            children: indexmap![
+                "categories".into() => released_property(data_property.data_type.clone()),
+                "counts".into() => released_property(DataType::Int)
+            ]
+        }), warnings))
+    }
+}
+
+impl Expandable for proto::StabilityMechanism {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        _maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        let mut expansion = base::ComponentExpansion::default();
+
+        let data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy definition must be defined")?;
+
+        // convert to effective usage
+        let mut noise_component = component.clone();
+        if let Some(proto::component::Variant::StabilityMechanism(variant)) = &mut noise_component.variant {
+            variant.privacy_usage = spread_privacy_usage(&self.privacy_usage, 1)?.into_iter()
+                .map(|usage| usage.actual_to_effective(
+                    data_property.sample_proportion.unwrap_or(1.),
+                    data_property.c_stability,
+                    privacy_definition.group_size))
+                .collect::<Result<Vec<proto::PrivacyUsage>>>()?;
+        } else { return Err(Error::from("Variant must be defined")) }
+        expansion.computation_graph.insert(component_id, noise_component);
+
+        Ok(expansion)
+    }
+}
+
+impl Mechanism for proto::StabilityMechanism {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        // the delta spent on thresholding is charged alongside epsilon
+        Some(release_usage.unwrap_or(&self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+/// Compute the L1 sensitivity of the counts in a stability histogram.
+///
+/// This is also the number of categories that may be present in only one of two neighboring datasets.
+pub fn get_stability_sensitivity(privacy_definition: &proto::PrivacyDefinition) -> Result<f64> {
+    use proto::privacy_definition::Neighboring;
+    Ok(match Neighboring::from_i32(privacy_definition.neighboring) {
+        // adding or removing a record changes the count of one category
+        Some(Neighboring::AddRemove) => 1.,
+        // substituting a record moves it from one category to another
+        Some(Neighboring::Substitute) => 2.,
+        None => return Err("neighboring definition must be either \"AddRemove\" or \"Substitute\"".into())
+    })
+}
+
+/// Compute the smallest noisy count at which a category may be released by the stability mechanism.
+///
+/// Counts are perturbed with geometric noise of scale sensitivity/epsilon.
+/// A category that is present in only one of two neighboring datasets has a count of at least one,
+/// so it is released with probability at most P(noise >= threshold - 1) = alpha^(threshold - 1) / (1 + alpha),
+/// where alpha = exp(-epsilon / sensitivity). The threshold is chosen such that this probability,
+/// summed over each of the `sensitivity` categories that may be unique to one dataset, is at most delta.
+///
+/// # Arguments
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `delta` - Additive privacy loss parameter.
+/// * `sensitivity` - L1 sensitivity of the counts.
+pub fn get_stability_threshold(epsilon: f64, delta: f64, sensitivity: f64) -> Result<Integer> {
+    if epsilon <= 0. || delta <= 0. || sensitivity <= 0. {
+        return Err(format!("epsilon ({}), delta ({}) and sensitivity ({}) must all be positive", epsilon, delta, sensitivity).into())
+    }
+    let alpha = (-epsilon / sensitivity).exp();
+    let exponent = ((delta * (1. + alpha) / sensitivity).ln() / alpha.ln()).ceil().max(1.);
+    if exponent >= Integer::MAX as f64 {
+        return Err("threshold: delta is too small to be represented".into())
+    }
+    Ok(exponent as Integer + 1)
+}
+
+/// Compute the delta spent by releasing categories whose noisy count is at least `threshold`.
+///
+/// This is the inverse of `get_stability_threshold`, up to rounding of the threshold.
+pub fn get_stability_delta(epsilon: f64, sensitivity: f64, threshold: Integer) -> f64 {
+    let alpha = (-epsilon / sensitivity).exp();
+    sensitivity * alpha.powf((threshold - 1) as f64) / (1. + alpha)
+}
+
+#[cfg(test)]
+mod test_stability_mechanism {
+    use ndarray::{arr1, arr2};
+
+    use crate::base::ValueProperties;
+    use crate::bindings::Analysis;
+    use crate::components::stability_mechanism::{get_stability_delta, get_stability_threshold};
+    use crate::proto;
+
+    fn dp_histogram(delta: f64, categories_supplied: bool) -> crate::errors::Result<ValueProperties> {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[["a"], ["b"], ["c"], ["a"]]).mapv(|v| v.to_string()).into_dyn().into())
+            .value_public(true)
+            .build();
+        let categories = analysis.literal()
+            .value(arr1(&["a".to_string(), "b".to_string()]).into_dyn().into())
+            .value_public(true).build();
+        let mut builder = analysis.dp_histogram(data, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta
+            }))
+        }]).mechanism("Stability".to_string());
+        if categories_supplied {
+            builder = builder.categories(categories);
+        }
+        let dp_histogram = builder.build();
+        analysis.properties(dp_histogram)
+    }
+
+    #[test]
+    fn test_stability_histogram() {
+        // categories and counts are both releasable
+        match dp_histogram(1e-6, false).unwrap() {
+            ValueProperties::Dataframe(dataframe) => assert!(dataframe.children.values()
+                .all(|column| column.array().unwrap().releasable)),
+            _ => panic!("the stability mechanism must release a dataframe")
+        }
+
+        // delta pays for the threshold
+        assert!(dp_histogram(0., false).is_err());
+        // the categories are discovered by the mechanism
+        assert!(dp_histogram(1e-6, true).is_err());
+    }
+
+    #[test]
+    fn test_threshold() {
+        for epsilon in &[0.1, 1., 5.] {
+            for delta in &[1e-9, 1e-6, 1e-2] {
+                for sensitivity in &[1., 2.] {
+                    let threshold = get_stability_threshold(*epsilon, *delta, *sensitivity).unwrap();
+                    assert!(threshold >= 2);
+
+                    // the threshold spends no more than delta, and is the smallest threshold to do so
+                    assert!(get_stability_delta(*epsilon, *sensitivity, threshold) <= *delta);
+                    if threshold > 2 {
+                        assert!(get_stability_delta(*epsilon, *sensitivity, threshold - 1) > *delta);
+                    }
+                }
+            }
+        }
+
+        // a smaller delta requires a larger threshold
+        assert!(get_stability_threshold(1., 1e-9, 1.).unwrap() > get_stability_threshold(1., 1e-3, 1.).unwrap());
+        assert!(get_stability_threshold(1., 0., 1.).is_err());
+    }
+}
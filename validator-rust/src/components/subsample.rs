@@ -0,0 +1,184 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto, Warnable};
+use crate::base::{IndexKey, Value, ValueProperties};
+use crate::components::Component;
+use crate::errors::*;
+use crate::utilities::prepend;
+
+impl Component for proto::Subsample {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        let proportion = public_arguments.get::<IndexKey>(&"proportion".into())
+            .ok_or_else(|| Error::from("proportion: missing, must be public"))?
+            .ref_array()?.first_float()
+            .map_err(prepend("proportion:"))?;
+
+        if !(proportion > 0. && proportion <= 1.) {
+            return Err("proportion: must be within (0, 1]".into())
+        }
+
+        data_property.sample_proportion = match data_property.sample_proportion {
+            Some(_) => return Err(Error::from("multiple samplings is not currently supported")),
+            None => Some(proportion)
+        };
+
+        // the number of records is not known after sampling rows
+        data_property.num_records = None;
+
+        // the subsample is not row-aligned with other data derived from the same source
+        data_property.dataset_id = Some(node_id as i64);
+
+        // every row may be dropped
+        data_property.is_not_empty = false;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+#[cfg(test)]
+mod test_subsample {
+    use indexmap::IndexMap;
+    use ndarray::Array2;
+
+    use crate::base::{IndexKey, SensitivitySpace, ValueProperties};
+    use crate::bindings::Analysis;
+    use crate::components::Mechanism;
+    use crate::proto;
+    use crate::utilities::expand_mechanism;
+    use crate::utilities::privacy::get_epsilon;
+
+    /// Effective epsilon spent by a laplace mechanism on a count with an actual usage of epsilon 1,
+    /// and the actual epsilon the mechanism reports.
+    fn epsilons(proportion: Option<f64>, group_size: u32) -> (f64, f64) {
+        let usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }];
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        analysis.privacy_definition.group_size = group_size;
+        let mut data = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| i as f64).into_dyn().into())
+            .value_public(false)
+            .build();
+        if let Some(proportion) = proportion {
+            let proportion = analysis.literal().value(proportion.into()).value_public(true).build();
+            data = analysis.subsample(data, proportion).build();
+        }
+        let count = analysis.count(data).build();
+        let laplace = analysis.laplace_mechanism(count, usage.clone()).build();
+
+        let mut properties = IndexMap::<IndexKey, ValueProperties>::new();
+        properties.insert("data".into(), analysis.properties(count).unwrap());
+        let expansion = expand_mechanism(
+            &SensitivitySpace::KNorm(1),
+            &Some(analysis.privacy_definition.clone()),
+            &usage,
+            analysis.components.get(&laplace).unwrap(),
+            &properties,
+            laplace, 1000).unwrap();
+
+        let variant = match expansion.computation_graph.get(&laplace).unwrap().variant.clone().unwrap() {
+            proto::component::Variant::LaplaceMechanism(variant) => variant,
+            _ => panic!("expected a laplace mechanism")
+        };
+        let actual_usage = variant.get_privacy_usage(&analysis.privacy_definition, None, &properties)
+            .unwrap().unwrap();
+        (get_epsilon(&variant.privacy_usage[0]).unwrap(), get_epsilon(&actual_usage[0]).unwrap())
+    }
+
+    #[test]
+    fn test_amplification() {
+        let (unamplified, actual) = epsilons(None, 1);
+        assert!((unamplified - 1.).abs() < 1e-12);
+        assert!((actual - 1.).abs() < 1e-12);
+
+        // subsampling with proportion q lets the mechanism spend ln((e - 1) / q + 1)
+        let (amplified, actual) = epsilons(Some(0.1), 1);
+        assert!((amplified - ((1f64.exp() - 1.) / 0.1 + 1.).ln()).abs() < 1e-12);
+        assert!(amplified > unamplified);
+        assert!((actual - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_amplification_with_group_size() {
+        // a group of two rows is protected, and either may be kept, with probability at most 2q
+        let (amplified, actual) = epsilons(Some(0.1), 2);
+        assert!((amplified - ((1f64.exp() - 1.) / 0.2 + 1.).ln() / 2.).abs() < 1e-12);
+        assert!((actual - 1.).abs() < 1e-12);
+
+        // once 2q reaches one, there is no amplification
+        let (amplified, _) = epsilons(Some(0.5), 2);
+        let (unamplified, _) = epsilons(None, 2);
+        assert!((amplified - unamplified).abs() < 1e-12);
+        assert!((amplified - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_shared_subsample() {
+        let usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }];
+        let subsampled = |num_mechanisms: usize| {
+            let mut analysis = Analysis::new();
+            analysis.privacy_definition.protect_floating_point = false;
+            let data = analysis.literal()
+                .value(Array2::from_shape_fn((100, 1), |(i, _)| i as f64).into_dyn().into())
+                .value_public(false)
+                .build();
+            let proportion = analysis.literal().value(0.1.into()).value_public(true).build();
+            let subsample = analysis.subsample(data, proportion).build();
+            (0..num_mechanisms).for_each(|_| {
+                let count = analysis.count(subsample).build();
+                analysis.laplace_mechanism(count, usage.clone()).build();
+            });
+            analysis
+        };
+
+        let analysis = subsampled(1);
+        let privacy_usage = crate::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        assert!((get_epsilon(&privacy_usage).unwrap() - 1.).abs() < 1e-12);
+
+        // the composition of two mechanisms on one subsample costs more than the sum of their amplified usages
+        let analysis = subsampled(2);
+        let (_, warnings) = crate::get_properties(
+            Some(analysis.privacy_definition.clone()), analysis.components.clone(), analysis.release.clone(), vec![]).unwrap();
+        assert_eq!(warnings.len(), 1);
+        let error = crate::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap_err();
+        assert!(format!("{:?}", error).contains("use privacy on subsample"), "{:?}", error);
+    }
+
+    #[test]
+    fn test_invalid_proportion() {
+        vec![0., 1.5, -0.5, f64::NAN].into_iter().for_each(|proportion| {
+            let mut analysis = Analysis::new();
+            let data = analysis.literal()
+                .value(Array2::from_shape_fn((10, 1), |(i, _)| i as f64).into_dyn().into())
+                .value_public(false)
+                .build();
+            let proportion = analysis.literal().value(proportion.into()).value_public(true).build();
+            let subsample = analysis.subsample(data, proportion).build();
+            assert!(analysis.properties(subsample).is_err());
+        });
+    }
+}
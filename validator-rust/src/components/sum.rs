@@ -1,8 +1,9 @@
 use indexmap::map::IndexMap;
+use itertools::Itertools;
 use ndarray::prelude::*;
 
 use crate::{base, Float, proto, Warnable};
-use crate::base::{AggregatorProperties, DataType, IndexKey, Nature, NatureContinuous, NodeProperties, SensitivitySpace, Value, ValueProperties, Vector1DNull};
+use crate::base::{AggregatorProperties, ArrayProperties, DataType, IndexKey, Nature, NatureContinuous, NodeProperties, SensitivitySpace, Value, ValueProperties, Vector1DNull};
 use crate::components::{Component, Sensitivity};
 use crate::errors::*;
 use crate::utilities::prepend;
@@ -10,8 +11,8 @@ use crate::utilities::prepend;
 impl Component for proto::Sum {
     fn propagate_property(
         &self,
-        _privacy_definition: &Option<proto::PrivacyDefinition>,
-        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         node_id: u32
     ) -> Result<Warnable<ValueProperties>> {
@@ -23,6 +24,8 @@ impl Component for proto::Sum {
             data_property.assert_is_not_aggregated()?;
         }
 
+        let weights = get_weights(privacy_definition, &public_arguments, &properties, &data_property)?;
+
         let num_columns = data_property.num_columns()?;
         // save a snapshot of the state when aggregating
         data_property.aggregator = Some(AggregatorProperties::new(
@@ -31,22 +34,27 @@ impl Component for proto::Sum {
         if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
             return Err("data: atomic type must be numeric".into())
         }
-        data_property.nature = data_property.num_records.and_then(|n| Some(Nature::Continuous(NatureContinuous {
-            lower: match data_property.data_type {
-                DataType::Int => Vector1DNull::Int(data_property
+        // the sum is bounded by the bounds of the data, scaled by the total weight
+        let (total_int, total_float) = match &weights {
+            Some(base::Array::Int(weights)) => (Some(weights.sum()), None),
+            Some(base::Array::Float(weights)) => (None, Some(weights.sum())),
+            _ => (data_property.num_records, data_property.num_records.map(|n| n as Float))
+        };
+        data_property.nature = match data_property.data_type {
+            DataType::Int => total_int.and_then(|n| Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::Int(data_property
                     .lower_int().ok()?.iter().map(|l| Some(l * n)).collect()),
-                DataType::Float => Vector1DNull::Float(data_property
-                    .lower_float().ok()?.iter().map(|l| Some(l * (n as Float))).collect()),
-                _ => unreachable!()
-            },
-            upper: match data_property.data_type {
-                DataType::Int => Vector1DNull::Int(data_property
+                upper: Vector1DNull::Int(data_property
                     .upper_int().ok()?.iter().map(|u| Some(u * n)).collect()),
-                DataType::Float => Vector1DNull::Float(data_property
-                    .upper_float().ok()?.iter().map(|u| Some(u * (n as Float))).collect()),
-                _ => unreachable!()
-            },
-        })));
+            }))),
+            DataType::Float => total_float.and_then(|n| Some(Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::Float(data_property
+                    .lower_float().ok()?.iter().map(|l| Some(l * n)).collect()),
+                upper: Vector1DNull::Float(data_property
+                    .upper_float().ok()?.iter().map(|u| Some(u * n)).collect()),
+            }))),
+            _ => unreachable!()
+        };
         data_property.num_records = Some(1);
         data_property.dataset_id = Some(node_id as i64);
 
@@ -79,7 +87,7 @@ impl Sensitivity for proto::Sum {
                     .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
 
                 macro_rules! compute_sensitivity {
-                    ($lower:expr, $upper:expr) => {
+                    ($lower:expr, $upper:expr, $max_weight:expr) => {
                         {
                             let row_sensitivity = match k {
                                 1 | 2 => match neighboring_type {
@@ -95,6 +103,15 @@ impl Sensitivity for proto::Sum {
                                 _ => return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
                             };
 
+                            // each record contributes at most the maximum weight times its value
+                            let row_sensitivity = match properties.get::<IndexKey>(&"weights".into()) {
+                                Some(weights_property) => {
+                                    let max_weight = $max_weight(weights_property.array()?)?;
+                                    row_sensitivity.into_iter().map(|v| v * max_weight).collect()
+                                },
+                                None => row_sensitivity
+                            };
+
                             let mut array_sensitivity = Array::from(row_sensitivity).into_dyn();
                             array_sensitivity.insert_axis_inplace(Axis(0));
 
@@ -104,12 +121,211 @@ impl Sensitivity for proto::Sum {
                 }
 
                 match data_property.data_type {
-                    DataType::Int => compute_sensitivity!(data_property.lower_int()?, data_property.upper_int()?),
-                    DataType::Float => compute_sensitivity!(data_property.lower_float()?, data_property.upper_float()?),
+                    DataType::Int => compute_sensitivity!(
+                        data_property.lower_int()?, data_property.upper_int()?,
+                        |weights: &ArrayProperties| weights.upper_int()?.into_iter().max()
+                            .ok_or_else(|| Error::from("weights: must have at least one column"))),
+                    DataType::Float => compute_sensitivity!(
+                        data_property.lower_float()?, data_property.upper_float()?,
+                        |weights: &ArrayProperties| weights.upper_float()?.into_iter().fold1(Float::max)
+                            .ok_or_else(|| Error::from("weights: must have at least one column"))),
                     _ => return Err(Error::from("sum data must be numeric"))
                 }
             }
             _ => Err("Sum sensitivity is only implemented for KNorm".into())
         }
     }
-}
\ No newline at end of file
+}
+
+
+/// Check that each row of the data is a fixed record, so that public per-row weights apply to fixed records.
+///
+/// Adding or removing a record, or resizing, sampling or combining rows,
+/// may shift which weight applies to every other row.
+pub fn check_fixed_rows(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    data_property: &ArrayProperties
+) -> Result<()> {
+    use proto::privacy_definition::Neighboring;
+    if let Some(privacy_definition) = privacy_definition {
+        if Neighboring::from_i32(privacy_definition.neighboring) != Some(Neighboring::Substitute) {
+            return Err("weights: records may only be weighted under the Substitute neighboring definition, \
+                as adding or removing a record shifts which weight applies to each row".into())
+        }
+    }
+    if !data_property.naturally_ordered {
+        return Err("weights: the rows of the data must be in their original order, so that each weight applies to a fixed record".into())
+    }
+    Ok(())
+}
+
+/// Retrieve the weights of a weighted aggregation, if provided.
+///
+/// Weights must be public, non-negative, share the atomic type of the data,
+/// and consist of exactly one weight for each record.
+///
+/// The sensitivity of a weighted aggregate is bounded by the maximum weight
+/// only when each weight applies to a fixed record, as checked by `check_fixed_rows`.
+pub fn get_weights(
+    privacy_definition: &Option<proto::PrivacyDefinition>,
+    public_arguments: &IndexMap<IndexKey, &Value>,
+    properties: &NodeProperties,
+    data_property: &ArrayProperties
+) -> Result<Option<base::Array>> {
+    if !properties.contains_key::<IndexKey>(&"weights".into()) {
+        return Ok(None)
+    }
+
+    check_fixed_rows(privacy_definition, data_property)?;
+
+    let weights = public_arguments.get::<IndexKey>(&"weights".into())
+        .ok_or_else(|| Error::from("weights: must be public"))?.ref_array()?;
+
+    if weights.shape().len() == 2 && weights.shape()[1] != 1 {
+        return Err("weights: must consist of a single column".into())
+    }
+    if weights.shape().len() > 2 {
+        return Err("weights: must be at most two-dimensional".into())
+    }
+
+    let num_records = data_property.num_records
+        .ok_or_else(|| Error::from("data: the number of records must be known when weighting"))?;
+    if weights.num_records()? as i64 != num_records {
+        return Err("weights: must have one weight for each record".into())
+    }
+
+    match (weights, &data_property.data_type) {
+        (base::Array::Float(weights), DataType::Float) => if weights.iter().any(|w| !w.is_finite() || *w < 0.) {
+            return Err("weights: must be finite and non-negative".into())
+        },
+        (base::Array::Int(weights), DataType::Int) => if weights.iter().any(|w| *w < 0) {
+            return Err("weights: must be non-negative".into())
+        },
+        _ => return Err("weights: atomic type must match the atomic type of the data".into())
+    }
+
+    Ok(Some(weights.clone()))
+}
+
+#[cfg(test)]
+mod test_sum {
+    use ndarray::{arr1, arr2};
+
+    use crate::base::{SensitivitySpace, Value};
+    use crate::bindings::Analysis;
+    use crate::components::Sensitivity;
+    use crate::proto::privacy_definition::Neighboring;
+
+    #[test]
+    fn test_weighted_sensitivity() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.neighboring = Neighboring::Substitute as i32;
+        let data = analysis.literal()
+            .value(arr2(&[[0.], [1.], [5.]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(2.0.into()).value_public(true).build();
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+
+        let sensitivity = |analysis: &Analysis, node_id: u32| {
+            let properties = analysis.properties(node_id).unwrap();
+            let aggregator = properties.array().unwrap().aggregator.clone().unwrap();
+            match aggregator.component.compute_sensitivity(
+                &analysis.privacy_definition, &aggregator.properties, &SensitivitySpace::KNorm(1)).unwrap() {
+                Value::Array(array) => array.first_float().unwrap(),
+                _ => panic!("sensitivity must be an array")
+            }
+        };
+
+        let sum = analysis.sum(data).build();
+        let baseline = sensitivity(&analysis, sum);
+
+        // sensitivity scales with the maximum weight
+        let weights = analysis.literal()
+            .value(arr1(&[0.5, 3., 1.]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let weighted_sum = analysis.sum(data).weights(weights).build();
+        assert_eq!(sensitivity(&analysis, weighted_sum), baseline * 3.);
+
+        // the upper bound scales with the total weight
+        let properties = analysis.properties(weighted_sum).unwrap();
+        assert_eq!(properties.array().unwrap().upper_float().unwrap(), vec![9.]);
+
+        // the weighted mean scales with the maximum normalized weight
+        let mean = analysis.mean(data).build();
+        let weighted_mean = analysis.mean(data).weights(weights).build();
+        assert_eq!(sensitivity(&analysis, mean), 2. / 3.);
+        assert_eq!(sensitivity(&analysis, weighted_mean), 2. * 3. / 4.5);
+
+        // weights must be non-negative, and one per record
+        let negative = analysis.literal()
+            .value(arr1(&[-1., 3., 1.]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let weighted_sum = analysis.sum(data).weights(negative).build();
+        assert!(analysis.properties(weighted_sum).is_err());
+
+        let short = analysis.literal()
+            .value(arr1(&[1., 3.]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let weighted_sum = analysis.sum(data).weights(short).build();
+        assert!(analysis.properties(weighted_sum).is_err());
+    }
+
+    #[test]
+    fn test_weights_require_fixed_records() {
+        let build = |neighboring: Neighboring, number_rows: Option<i64>| {
+            let mut analysis = Analysis::new();
+            analysis.privacy_definition.neighboring = neighboring as i32;
+            let mut data = analysis.literal()
+                .value(arr2(&[[0.], [1.], [5.]]).into_dyn().into())
+                .value_public(true)
+                .build();
+            let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+            let upper = analysis.literal().value(2.0.into()).value_public(true).build();
+            data = analysis.clamp(data).lower(lower).upper(upper).build();
+            if let Some(number_rows) = number_rows {
+                let number_rows = analysis.literal().value(number_rows.into()).value_public(true).build();
+                data = analysis.resize(data).number_rows(number_rows).lower(lower).upper(upper).build();
+            }
+            let weights = analysis.literal()
+                .value(arr1(&[0.5, 3., 1.]).into_dyn().into())
+                .value_public(true)
+                .build();
+            let weighted_sum = analysis.sum(data).weights(weights).build();
+            analysis.properties(weighted_sum)
+        };
+
+        build(Neighboring::Substitute, None).unwrap();
+        // resizing to the same number of rows leaves each row in place
+        build(Neighboring::Substitute, Some(3)).unwrap();
+
+        // adding or removing a record shifts the weight of every later row
+        let error = build(Neighboring::AddRemove, None).unwrap_err();
+        assert!(error.to_string().contains("Substitute neighboring definition"), "{}", error);
+
+        // resizing to four rows pads the data, so a row no longer corresponds to a fixed record
+        let error = build(Neighboring::Substitute, Some(4)).unwrap_err();
+        assert!(error.to_string().contains("original order"), "{}", error);
+    }
+
+    #[test]
+    fn test_private_weights() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.neighboring = Neighboring::Substitute as i32;
+        let data = analysis.literal()
+            .value(arr2(&[[0.], [1.], [5.]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let weights = analysis.literal()
+            .value(arr1(&[0.5, 3., 1.]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let weighted_sum = analysis.sum(data).weights(weights).build();
+        let error = analysis.properties(weighted_sum).unwrap_err();
+        assert!(error.to_string().contains("weights: must be public"));
+    }
+}
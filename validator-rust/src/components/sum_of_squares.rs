@@ -0,0 +1,191 @@
+use indexmap::map::IndexMap;
+use ndarray::prelude::*;
+
+use crate::{base, Float, Integer, proto, Warnable};
+use crate::base::{AggregatorProperties, DataType, IndexKey, Nature, NatureContinuous, NodeProperties, SensitivitySpace, Value, ValueProperties, Vector1DNull};
+use crate::components::{Component, Sensitivity};
+use crate::errors::*;
+use crate::utilities::prepend;
+
+impl Component for proto::SumOfSquares {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        let num_columns = data_property.num_columns()?;
+        // save a snapshot of the state when aggregating
+        data_property.aggregator = Some(AggregatorProperties::new(
+            proto::component::Variant::SumOfSquares(self.clone()), properties, num_columns));
+
+        if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
+            return Err("data: atomic type must be numeric".into())
+        }
+        data_property.nature = data_property.num_records.and_then(|n| Some(Nature::Continuous(match data_property.data_type {
+            DataType::Int => {
+                let bounds = data_property.lower_int().ok()?.iter()
+                    .zip(data_property.upper_int().ok()?.iter())
+                    .map(|(l, u)| square_bounds_int(*l, *u))
+                    .collect::<Vec<Option<(Integer, Integer)>>>();
+                NatureContinuous {
+                    lower: Vector1DNull::Int(bounds.iter()
+                        .map(|b| b.and_then(|(l, _)| l.checked_mul(n))).collect()),
+                    upper: Vector1DNull::Int(bounds.iter()
+                        .map(|b| b.and_then(|(_, u)| u.checked_mul(n))).collect()),
+                }
+            },
+            DataType::Float => {
+                let bounds = data_property.lower_float().ok()?.iter()
+                    .zip(data_property.upper_float().ok()?.iter())
+                    .map(|(l, u)| square_bounds_float(*l, *u))
+                    .collect::<Vec<(Float, Float)>>();
+                NatureContinuous {
+                    lower: Vector1DNull::Float(bounds.iter()
+                        .map(|(l, _)| Some(l * n as Float)).collect()),
+                    upper: Vector1DNull::Float(bounds.iter()
+                        .map(|(_, u)| Some(u * n as Float)).collect()),
+                }
+            },
+            _ => unreachable!()
+        })));
+        data_property.num_records = Some(1);
+        data_property.dataset_id = Some(node_id as i64);
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Sensitivity for proto::SumOfSquares {
+    /// The sensitivity of a sum of squares is the sensitivity of a sum over the squared data,
+    /// where the bounds on the squared data follow from the bounds on the data.
+    fn compute_sensitivity(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        properties: &NodeProperties,
+        sensitivity_type: &SensitivitySpace,
+    ) -> Result<Value> {
+
+        match sensitivity_type {
+
+            SensitivitySpace::KNorm(k) => {
+
+                let data_property = properties.get::<IndexKey>(&"data".into())
+                    .ok_or("data: missing")?.array()
+                    .map_err(prepend("data:"))?.clone();
+
+                data_property.assert_is_not_aggregated()?;
+                data_property.assert_non_null()?;
+
+                use proto::privacy_definition::Neighboring;
+                let neighboring_type = Neighboring::from_i32(privacy_definition.neighboring)
+                    .ok_or_else(|| Error::from("neighboring definition must be either \"AddRemove\" or \"Substitute\""))?;
+
+                if *k != 1 && *k != 2 {
+                    return Err("KNorm sensitivity is only supported in L1 and L2 spaces".into())
+                }
+
+                // squares are non-negative, so when adding or removing a row the sum changes by at most the largest square
+                let row_sensitivity: Value = match data_property.data_type {
+                    DataType::Int => data_property.lower_int()?.iter()
+                        .zip(data_property.upper_int()?.iter())
+                        .map(|(l, u)| square_bounds_int(*l, *u)
+                            .and_then(|(min, max)| match neighboring_type {
+                                Neighboring::AddRemove => Some(max),
+                                Neighboring::Substitute => max.checked_sub(min)
+                            }))
+                        .collect::<Option<Vec<Integer>>>()
+                        .ok_or_else(|| Error::from("data: the square of the bounds overflows"))
+                        .map(|v| Array::from(v).insert_axis(Axis(0)).into_dyn().into())?,
+                    DataType::Float => Array::from(data_property.lower_float()?.iter()
+                        .zip(data_property.upper_float()?.iter())
+                        .map(|(l, u)| {
+                            let (min, max) = square_bounds_float(*l, *u);
+                            match neighboring_type {
+                                Neighboring::AddRemove => max,
+                                Neighboring::Substitute => max - min
+                            }
+                        })
+                        .collect::<Vec<Float>>()).insert_axis(Axis(0)).into_dyn().into(),
+                    _ => return Err("data: atomic type must be numeric".into())
+                };
+                Ok(row_sensitivity)
+            }
+            _ => Err("SumOfSquares sensitivity is only implemented for KNorm".into())
+        }
+    }
+}
+
+/// Bounds on the square of a float in [lower, upper].
+pub fn square_bounds_float(lower: Float, upper: Float) -> (Float, Float) {
+    let max = lower.powi(2).max(upper.powi(2));
+    // the square is smallest at zero, if zero is within the bounds
+    let min = if lower <= 0. && 0. <= upper { 0. } else { lower.powi(2).min(upper.powi(2)) };
+    (min, max)
+}
+
+/// Bounds on the square of an integer in [lower, upper], if the squares do not overflow.
+pub fn square_bounds_int(lower: Integer, upper: Integer) -> Option<(Integer, Integer)> {
+    let (lower_square, upper_square) = (lower.checked_mul(lower)?, upper.checked_mul(upper)?);
+    let max = lower_square.max(upper_square);
+    let min = if lower <= 0 && 0 <= upper { 0 } else { lower_square.min(upper_square) };
+    Some((min, max))
+}
+
+
+#[cfg(test)]
+mod test_sum_of_squares {
+    use ndarray::arr2;
+
+    use crate::base::{SensitivitySpace, Value};
+    use crate::bindings::Analysis;
+    use crate::components::Sensitivity;
+    use crate::components::sum_of_squares::{square_bounds_float, square_bounds_int};
+    use crate::proto;
+
+    #[test]
+    fn test_square_bounds() {
+        assert_eq!(square_bounds_float(-2., 3.), (0., 9.));
+        assert_eq!(square_bounds_float(-5., -2.), (4., 25.));
+        assert_eq!(square_bounds_int(2, 3), Some((4, 9)));
+        assert_eq!(square_bounds_int(i64::MIN, 0), None);
+    }
+
+    #[test]
+    fn test_sensitivity() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[0.], [1.], [5.]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(1.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(3.0.into()).value_public(true).build();
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+        let sum_of_squares = analysis.sum_of_squares(data).build();
+
+        let properties = analysis.properties(sum_of_squares).unwrap();
+        // the largest squared bound
+        let sensitivity = |neighboring: proto::privacy_definition::Neighboring| {
+            let mut privacy_definition = analysis.privacy_definition.clone();
+            privacy_definition.neighboring = neighboring as i32;
+            let aggregator = properties.array().unwrap().aggregator.clone().unwrap();
+            match aggregator.component.compute_sensitivity(
+                &privacy_definition, &aggregator.properties, &SensitivitySpace::KNorm(1)).unwrap() {
+                Value::Array(array) => array.first_float().unwrap(),
+                _ => panic!("sensitivity must be an array")
+            }
+        };
+        assert_eq!(sensitivity(proto::privacy_definition::Neighboring::AddRemove), 9.);
+        // the square may change from the smallest to the largest squared bound
+        assert_eq!(sensitivity(proto::privacy_definition::Neighboring::Substitute), 8.);
+    }
+}
@@ -0,0 +1,135 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, DataframeProperties, DataType, IndexKey, Jagged, Nature, NatureCategorical, Value, ValueProperties};
+use crate::components::Component;
+use crate::errors::*;
+use crate::utilities::{get_argument, prepend};
+
+impl Component for proto::SynthesizeFromMarginals {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let categories = get_argument(&public_arguments, "categories")?.ref_jagged()
+            .map_err(prepend("categories:"))?;
+        let number_rows = get_argument(&public_arguments, "number_rows")?.ref_array()?.first_int()
+            .map_err(prepend("number_rows:"))?;
+        if number_rows < 1 {
+            return Err("number_rows: must be positive".into())
+        }
+
+        let marginals = properties.iter()
+            .filter(|(name, _)| !is_reserved(name))
+            .collect::<Vec<_>>();
+        if marginals.is_empty() {
+            return Err("at least one marginal must be supplied".into())
+        }
+        if categories.num_columns() != marginals.len() as i64 {
+            return Err("categories: must contain one set of categories for each marginal".into())
+        }
+
+        let children = marginals.into_iter().zip(categories.num_records()).enumerate()
+            .map(|(index, ((name, property), num_categories))| {
+                let property = property.array()
+                    .map_err(prepend(&format!("{}:", name.to_string())))?;
+
+                // only releasable counts may be postprocessed
+                if !property.releasable {
+                    return Err(format!("{}: must be releasable", name.to_string()).into())
+                }
+                if !matches!(property.data_type, DataType::Float | DataType::Int) {
+                    return Err(format!("{}: counts must be numeric", name.to_string()).into())
+                }
+                if let Some(num_counts) = property.num_records {
+                    if num_counts != num_categories {
+                        return Err(format!("{}: has {} counts, but {} categories",
+                                           name.to_string(), num_counts, num_categories).into())
+                    }
+                }
+
+                Ok((name.clone(), ValueProperties::Array(ArrayProperties {
+                    num_records: Some(number_rows),
+                    num_columns: Some(1),
+                    nullity: false,
+                    releasable: true,
+                    c_stability: 1,
+                    aggregator: None,
+                    nature: Some(Nature::Categorical(NatureCategorical {
+                        categories: select_categories(categories, index)
+                    })),
+                    data_type: categories.data_type(),
+                    // columns of the synthetic dataset share a row index
+                    dataset_id: Some(node_id as i64),
+                    node_id: node_id as i64,
+                    is_not_empty: true,
+                    dimensionality: Some(1),
+                    group_id: vec![],
+                    naturally_ordered: true,
+                    sample_proportion: None,
+                })))
+            })
+            .collect::<Result<IndexMap<IndexKey, ValueProperties>>>()?;
+
+        Ok(ValueProperties::Dataframe(DataframeProperties { children }).into())
+    }
+}
+
+/// True if the argument is a parameter, rather than a marginal.
+pub fn is_reserved(name: &IndexKey) -> bool {
+    name == &IndexKey::from("categories") || name == &IndexKey::from("number_rows")
+}
+
+fn select_categories(categories: &Jagged, index: usize) -> Jagged {
+    match categories {
+        Jagged::Bool(categories) => Jagged::Bool(vec![categories[index].clone()]),
+        Jagged::Int(categories) => Jagged::Int(vec![categories[index].clone()]),
+        Jagged::Float(categories) => Jagged::Float(vec![categories[index].clone()]),
+        Jagged::Str(categories) => Jagged::Str(vec![categories[index].clone()]),
+    }
+}
+
+#[cfg(test)]
+mod test_synthesize_from_marginals {
+    use ndarray::arr1;
+
+    use crate::base::{IndexKey, Value, ValueProperties};
+    use crate::bindings::Analysis;
+    use crate::errors::*;
+
+    /// synthesize a column "x" with categories "a" and "b" from the counts
+    fn synthesize(counts: Value, counts_public: bool) -> Result<ValueProperties> {
+        let mut analysis = Analysis::new();
+        let counts = analysis.literal().value(counts).value_public(counts_public).build();
+        let categories = analysis.literal()
+            .value(Value::Jagged(vec![vec!["a".to_string(), "b".to_string()]].into()))
+            .value_public(true)
+            .build();
+        let number_rows = analysis.literal().value(100.into()).value_public(true).build();
+        let builder = analysis.synthesize_from_marginals(categories, number_rows);
+        builder.component.insert_argument(&"x".into(), counts);
+        let synthetic = builder.build();
+        analysis.properties(synthetic)
+    }
+
+    #[test]
+    fn test_properties() {
+        match synthesize(arr1(&[3., 7.]).into_dyn().into(), true).unwrap() {
+            ValueProperties::Dataframe(properties) => {
+                let column = properties.children.get(&IndexKey::from("x")).unwrap().array().unwrap();
+                assert!(column.releasable);
+                assert_eq!(column.num_records, Some(100));
+                assert_eq!(column.categories().unwrap().num_records(), vec![2]);
+            }
+            _ => panic!("synthetic data must be a dataframe")
+        }
+
+        // one count per category
+        assert!(synthesize(arr1(&[3., 7., 1.]).into_dyn().into(), true).is_err());
+        // the counts must already be released
+        assert!(synthesize(arr1(&[3., 7.]).into_dyn().into(), false).is_err());
+    }
+}
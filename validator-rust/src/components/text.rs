@@ -0,0 +1,182 @@
+use crate::errors::*;
+
+use crate::{proto, base, Warnable, Integer};
+use crate::components::Component;
+use crate::base::{IndexKey, Value, NodeProperties, ValueProperties, DataType, Nature, NatureCategorical, NatureContinuous, Jagged, Vector1DNull};
+use crate::utilities::prepend;
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+impl Component for proto::StringLength {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        if data_property.data_type != DataType::Str {
+            return Err("data: atomic type must be string".into())
+        }
+
+        let num_columns = data_property.num_columns()?;
+
+        // lengths of known categories, otherwise lengths are only known to be non-negative
+        data_property.nature = Some(match data_property.nature.take() {
+            Some(Nature::Categorical(NatureCategorical { categories: Jagged::Str(categories) })) =>
+                Nature::Categorical(NatureCategorical {
+                    categories: Jagged::Int(categories.iter()
+                        .map(|categories| categories.iter()
+                            .map(|category| category.chars().count() as Integer)
+                            .unique().collect())
+                        .collect())
+                }),
+            _ => Nature::Continuous(NatureContinuous {
+                lower: Vector1DNull::Int((0..num_columns).map(|_| Some(0)).collect()),
+                upper: Vector1DNull::Int((0..num_columns).map(|_| None).collect()),
+            })
+        });
+        data_property.data_type = DataType::Int;
+        data_property.nullity = false;
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+impl Component for proto::Substring {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        if data_property.data_type != DataType::Str {
+            return Err("data: atomic type must be string".into())
+        }
+
+        // substrings of known categories
+        data_property.nature = match data_property.nature.take() {
+            Some(Nature::Categorical(NatureCategorical { categories: Jagged::Str(categories) })) =>
+                Some(Nature::Categorical(NatureCategorical {
+                    categories: Jagged::Str(categories.iter()
+                        .map(|categories| categories.iter()
+                            .map(|category| category.chars()
+                                .skip(self.start as usize)
+                                .take(self.length as usize)
+                                .collect::<String>())
+                            .unique().collect())
+                        .collect())
+                })),
+            _ => None
+        };
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+#[cfg(test)]
+mod test_text {
+    use indexmap::map::IndexMap;
+    use ndarray::arr2;
+
+    use crate::{proto, Warnable};
+    use crate::base::{DataType, IndexKey, Jagged, Nature, Value, ValueProperties, Vector1DNull};
+    use crate::bindings::Analysis;
+    use crate::components::Component;
+
+    #[test]
+    fn test_string_length() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[["héllo".to_string()], ["añ".to_string()]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lengths = analysis.string_length(data).build();
+
+        // the lengths of public strings are known, counting multibyte characters once
+        let properties = analysis.properties(lengths).unwrap();
+        let mut properties = properties.array().unwrap().clone();
+        assert_eq!(properties.data_type, DataType::Int);
+        match properties.nature.as_ref().unwrap() {
+            Nature::Categorical(nature) => match &nature.categories {
+                Jagged::Int(categories) => assert_eq!(categories, &vec![vec![5, 2]]),
+                _ => panic!("categories must be integers")
+            },
+            _ => panic!("nature must be categorical")
+        }
+
+        // otherwise lengths are only known to be non-negative
+        properties.data_type = DataType::Str;
+        properties.nature = None;
+        let mut arguments = IndexMap::<IndexKey, ValueProperties>::new();
+        arguments.insert("data".into(), ValueProperties::Array(properties));
+        let Warnable(properties, _) = proto::StringLength {}
+            .propagate_property(&None, IndexMap::new(), arguments, 0).unwrap();
+        match properties.array().unwrap().nature.as_ref().unwrap() {
+            Nature::Continuous(nature) => match (&nature.lower, &nature.upper) {
+                (Vector1DNull::Int(lower), Vector1DNull::Int(upper)) => {
+                    assert_eq!(lower, &vec![Some(0)]);
+                    assert_eq!(upper, &vec![None]);
+                },
+                _ => panic!("bounds must be integers")
+            },
+            _ => panic!("nature must be continuous")
+        }
+
+        // lengths may not be taken of numbers
+        let lengths = analysis.string_length(lengths).build();
+        assert!(analysis.properties(lengths).is_err());
+    }
+
+    #[test]
+    fn test_substring_categories() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[["ab".to_string()], ["abc".to_string()]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let categories = analysis.literal()
+            .value(Value::Jagged(vec![vec!["straße", "strand", "é"].into_iter().map(String::from).collect::<Vec<String>>()].into()))
+            .value_public(true)
+            .build();
+        let null_value = analysis.literal()
+            .value("".to_string().into())
+            .value_public(true)
+            .build();
+        let clamped = analysis.clamp(data).categories(categories).null_value(null_value).build();
+        let prefixes = analysis.substring(clamped, 3, 10).build();
+        let lengths = analysis.string_length(clamped).build();
+
+        match analysis.properties(prefixes).unwrap().array().unwrap().nature.as_ref().unwrap() {
+            Nature::Categorical(nature) => match &nature.categories {
+                Jagged::Str(categories) => assert_eq!(categories, &vec![vec!["aße".to_string(), "and".to_string(), "".to_string()]]),
+                _ => panic!("categories must be strings")
+            },
+            _ => panic!("nature must be categorical")
+        }
+        match analysis.properties(lengths).unwrap().array().unwrap().nature.as_ref().unwrap() {
+            Nature::Categorical(nature) => match &nature.categories {
+                Jagged::Int(categories) => assert_eq!(categories, &vec![vec![6, 1, 0]]),
+                _ => panic!("categories must be integers")
+            },
+            _ => panic!("nature must be categorical")
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use crate::errors::*;
 
-use crate::base::{Nature, NatureCategorical, Vector1DNull, Jagged, ArrayProperties, ValueProperties, DataType, GroupId};
+use crate::base::{Nature, NatureCategorical, Vector1DNull, Jagged, ArrayProperties, ValueProperties, DataType, GroupId, AggregatorProperties};
 
 use crate::{proto, base, Warnable, Integer, Float};
 
 use crate::utilities::{prepend};
 
-use crate::components::{Component};
+use crate::components::{Component, Named};
 
 use crate::base::{IndexKey, Value, NatureContinuous};
 use num::{CheckedAdd, CheckedSub, Zero};
@@ -130,8 +130,8 @@ impl Component for proto::Add {
             num_records,
             aggregator: None,
             group_id: propagate_binary_group_id(&left_property, &right_property)?,
-            data_type: left_property.data_type,
-            dataset_id: left_property.dataset_id,
+            data_type: left_property.data_type.clone(),
+            dataset_id: propagate_binary_dataset_id(&left_property, &right_property),
             node_id: node_id as i64,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
@@ -172,6 +172,9 @@ impl Component for proto::And {
             return Err("left and right arguments must share the same data types".into())
         }
 
+        // the dataset and partition are taken from the private arguments, before releasability is updated
+        left_property.dataset_id = propagate_binary_dataset_id(&left_property, &right_property);
+        left_property.group_id = propagate_binary_group_id(&left_property, &right_property)?;
         left_property.releasable = left_property.releasable && right_property.releasable;
         left_property.nature = propagate_binary_nature(
             &left_property, &right_property,
@@ -192,18 +195,29 @@ impl Component for proto::And {
         left_property.dimensionality = left_property.dimensionality
             .max(right_property.dimensionality);
 
-        left_property.group_id = propagate_binary_group_id(&left_property, &right_property)?;
-
         Ok(ValueProperties::Array(left_property).into())
     }
 }
 
+impl Component for proto::Ceil {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        // ceil is non-decreasing, so bounds are rounded up
+        Ok(ValueProperties::Array(propagate_non_decreasing_float(properties, Float::ceil)?).into())
+    }
+}
+
 impl Component for proto::Divide {
     #[allow(clippy::float_cmp)]
     fn propagate_property(
         &self,
         _privacy_definition: &Option<proto::PrivacyDefinition>,
-        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
         properties: base::NodeProperties,
         node_id: u32
     ) -> Result<Warnable<ValueProperties>> {
@@ -214,8 +228,13 @@ impl Component for proto::Divide {
             .ok_or("right: missing")?.array()
             .map_err(prepend("right:"))?.clone();
 
+        let right_public = public_arguments.get(&IndexKey::from("right")).cloned();
+
+        // aggregates may be divided by public constants
         if !left_property.releasable {
-            left_property.assert_is_not_aggregated()?;
+            if right_public.is_none() {
+                left_property.assert_is_not_aggregated()?;
+            }
             left_property.assert_is_not_sampled()?;
         }
         if !right_property.releasable {
@@ -228,91 +247,93 @@ impl Component for proto::Divide {
             return Err("left and right arguments must share the same data types".into())
         }
 
-        let float_denominator_may_span_zero = match right_property.clone().nature {
-            Some(nature) => match nature {
-                Nature::Continuous(nature) => nature.lower.float()
-                    .map(|min| nature.upper.float()
-                        .map(|max| min.iter().zip(max.iter())
-                            .any(|(min, max)| min
-                                .map(|min| max
-                                    .map(|max| min < 0. && max > 0.)
-                                    // if max is not known
-                                    .unwrap_or(min > 0.))
-                                // if min is not known
-                                .unwrap_or_else(|| max.map(|max| max < 0.)
-                                    .unwrap_or(true))))
-                        // if max is not float
-                        .unwrap_or(false))
-                    // if min is not float
-                    .unwrap_or(false),
-                Nature::Categorical(nature) => nature.categories.float()
-                    .map(|categories| categories.iter()
-                        .any(|column| column.iter()
-                            .any(|category| category.is_nan() || category == &0.)))
-                    // if categories are not known, a category could be zero or NAN
-                    .unwrap_or(false)
+        // division by c is 1/|c|-Lipschitz
+        let aggregator = match right_public {
+            Some(constant) if left_property.aggregator.is_some() =>
+                propagate_lipschitz(&left_property, constant, num_columns, |c| if c == 0. {
+                    Err("aggregated data may not be divided by zero".into())
+                } else { Ok(1. / c.abs()) })?,
+            _ => None
+        };
+
+        // a zero denominator makes a float quotient null, and is an error for integer quotients
+        let denominator_may_be_zero = match &right_property.nature {
+            Some(Nature::Continuous(_)) => match (right_property.lower_float_option(), right_property.upper_float_option()) {
+                (Ok(lower), Ok(upper)) => lower.iter().zip(upper.iter())
+                    .any(|(lower, upper)| lower.map(|l| l <= 0.).unwrap_or(true)
+                        && upper.map(|u| u >= 0.).unwrap_or(true)),
+                _ => true
+            },
+            Some(Nature::Categorical(nature)) => match &nature.categories {
+                Jagged::Float(categories) => categories.iter().flatten()
+                    .any(|category| category.is_nan() || category == &0.),
+                Jagged::Int(categories) => categories.iter().flatten()
+                    .any(|category| category == &0),
+                _ => true
             },
-            // if nature is not known, data could span zero
-            _ => true
+            // if nature is not known, data could be zero
+            None => true
         };
+        if right_property.data_type == DataType::Int && denominator_may_be_zero {
+            return Err("right: integer divisors may not be zero. Bound the divisor away from zero, or cast to float".into())
+        }
 
-        // minimize and maximize b / e when a <= b <= c and d <= e <= f
+        // minimize and maximize b / e when a <= b <= c and d <= e <= f, where e != 0
         fn optimize<T: PartialOrd + Div<Output=T> + Zero + Copy>(
             a: T, c: T, d: T, f: T
         ) -> Result<(Option<T>, Option<T>)> {
             let compare = |x: &T, y: &T| x.partial_cmp(y).unwrap_or(Ordering::Equal);
-            // if denominator interval does not contain zero
+            // the quotient is monotonic in each argument when the denominator interval excludes zero,
+            //     so the bounds are attained at the corners
             if T::zero() < d || f < T::zero() {
                 let (min, max) = vec![a / f, a / d, c / f, c / d].into_iter()
                     .minmax_by(compare).into_option().unwrap();
                 return Ok((Some(min), Some(max)))
             }
+            if d.is_zero() && f.is_zero() {
+                return Err("denominator is always zero".into())
+            }
+
+            // a numerator of zero is zero for every nonzero denominator
+            if a.is_zero() && c.is_zero() {
+                return Ok((Some(T::zero()), Some(T::zero())))
+            }
 
-            // if one arm of denominator is zero
-            if d.is_zero() && !f.is_zero() {
-                return Ok((None, vec![a / f, c / f].into_iter().max_by(compare)))
+            // the denominator approaches zero from one side, so the quotient diverges in the direction of the numerator
+            //     0 <= b: b / e in [b / f, inf) when 0 < e <= f, and (-inf, b / d] when d <= e < 0
+            //     b <= 0: b / e in (-inf, b / f] when 0 < e <= f, and [b / d, inf) when d <= e < 0
+            let non_negative = T::zero() <= a;
+            let non_positive = c <= T::zero();
+            if d.is_zero() {
+                return Ok(if non_negative {
+                    (Some(a / f), None)
+                } else if non_positive {
+                    (None, Some(c / f))
+                } else { (None, None) })
             }
-            if !d.is_zero() && f.is_zero() {
-                return Ok((vec![a / d, c / d].into_iter().min_by(compare), None))
+            if f.is_zero() {
+                return Ok(if non_negative {
+                    (None, Some(a / d))
+                } else if non_positive {
+                    (Some(c / d), None)
+                } else { (None, None) })
             }
+
+            // the denominator approaches zero from both sides
             Ok((None, None))
         }
 
         fn optimize_wrapper<T: PartialOrd + Div<Output=T> + Zero + Copy>(
             bounds: BinaryBounds<T>
         ) -> Result<(Option<T>, Option<T>)> {
-
-            let a = match bounds.left_lower {
-                Some(v) => *v,
-                None => return Ok((None, None))
-            };
-            let c = match bounds.left_upper {
-                Some(v) => *v,
-                None => return Ok((None, None))
-            };
-            let d = match bounds.right_lower {
-                Some(v) => *v,
-                None => {
-                    if bounds.right_upper.map(|v| v >= T::zero()).unwrap_or(true) {
-                        return Err("potential division by zero".into())
-                    }
-                    return Ok((None, None))
-                }
-            };
-            let f = match bounds.right_upper {
-                Some(v) => *v,
-                None => {
-                    if bounds.right_lower.map(|v| v <= T::zero()).unwrap_or(true) {
-                        return Err("potential division by zero".into())
-                    }
-                    return Ok((None, None))
-                }
-            };
-            optimize(a, c, d, f)
+            match (bounds.left_lower, bounds.left_upper, bounds.right_lower, bounds.right_upper) {
+                (Some(a), Some(c), Some(d), Some(f)) => optimize(*a, *c, *d, *f),
+                _ => Ok((None, None))
+            }
         }
 
         Ok(ValueProperties::Array(ArrayProperties {
-            nullity: left_property.nullity || right_property.nullity || float_denominator_may_span_zero,
+            nullity: left_property.nullity || right_property.nullity || denominator_may_be_zero,
             releasable: left_property.releasable && right_property.releasable,
             nature: propagate_binary_nature(&left_property, &right_property, &BinaryOperators {
                 float: Some(Box::new(|l: &Float, r: &Float| {
@@ -335,10 +356,10 @@ impl Component for proto::Divide {
                 .max(right_property.c_stability),
             num_columns: Some(num_columns),
             num_records,
-            aggregator: None,
+            aggregator,
             group_id: propagate_binary_group_id(&left_property, &right_property)?,
-            data_type: left_property.data_type,
-            dataset_id: left_property.dataset_id,
+            data_type: left_property.data_type.clone(),
+            dataset_id: propagate_binary_dataset_id(&left_property, &right_property),
             node_id: node_id as i64,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
@@ -393,7 +414,7 @@ impl Component for proto::Equal {
             num_records,
             aggregator: None,
             data_type: DataType::Bool,
-            dataset_id: left_property.dataset_id,
+            dataset_id: propagate_binary_dataset_id(&left_property, &right_property),
             node_id: node_id as i64,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality.max(right_property.dimensionality),
@@ -406,6 +427,32 @@ impl Component for proto::Equal {
 }
 
 
+impl Component for proto::Exp {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        // exp is increasing, so bounds are exponentiated
+        Ok(ValueProperties::Array(propagate_non_decreasing_float(properties, Float::exp)?).into())
+    }
+}
+
+impl Component for proto::Floor {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        // floor is non-decreasing, so bounds are rounded down
+        Ok(ValueProperties::Array(propagate_non_decreasing_float(properties, Float::floor)?).into())
+    }
+}
+
 impl Component for proto::GreaterThan {
     fn propagate_property(
         &self,
@@ -455,7 +502,7 @@ impl Component for proto::GreaterThan {
             num_records,
             aggregator: None,
             data_type: DataType::Bool,
-            dataset_id: left_property.dataset_id,
+            dataset_id: propagate_binary_dataset_id(&left_property, &right_property),
             node_id: node_id as i64,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
@@ -518,7 +565,7 @@ impl Component for proto::LessThan {
             num_records,
             aggregator: None,
             data_type: DataType::Bool,
-            dataset_id: left_property.dataset_id,
+            dataset_id: propagate_binary_dataset_id(&left_property, &right_property),
             node_id: node_id as i64,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
@@ -621,47 +668,63 @@ impl Component for proto::Modulo {
             right_property.assert_is_not_sampled()?;
         }
 
+        let (num_columns, num_records) = propagate_binary_shape(&left_property, &right_property)?;
+        if right_property.nature.is_none() {
+            return Err("right: divisor must be bounded".into())
+        }
+
         match (left_property.data_type.clone(), right_property.data_type.clone()) {
             (DataType::Float, DataType::Float) => {
-
-                if !right_property.lower_float()?.iter().all(|v| v > &0.) {
-                    return Err("divisor must be greater than zero".into())
-                }
-
                 left_property.nature = propagate_binary_nature(
                     &left_property, &right_property,
                     &BinaryOperators {
-                        float: Some(Box::new(|l, r| Ok(l.rem_euclid(*r)))),
+                        float: Some(Box::new(|l, r| if r == &0. {
+                            Err("divisor may not be zero".into())
+                        } else { Ok(l.rem_euclid(*r)) })),
                         int: None,
                         bool: None,
                         str: None,
                     },
                     &OptimizeBinaryOperators {
-                        // TODO: this could be tighter
-                        float: Some(&|bounds| Ok((Some(0.), *bounds.right_upper))),
+                        float: Some(&|bounds| {
+                            let (smallest, largest) = divisor_magnitudes(bounds.right_lower, bounds.right_upper)?;
+                            Ok(match (bounds.left_lower, bounds.left_upper) {
+                                // the remainder is the dividend, if the dividend is non-negative and smaller than the divisor
+                                (Some(lower), Some(upper)) if *lower >= 0. && *upper < smallest => (Some(*lower), Some(*upper)),
+                                _ => (Some(0.), Some(largest))
+                            })
+                        }),
                         int: None
-                    }, left_property.num_columns()?)?;
+                    }, num_columns)?;
             },
             (DataType::Int, DataType::Int) => {
-                if !right_property.lower_int()?.iter().all(|v| v > &0) {
-                    return Err("divisor must be greater than zero".into())
-                }
                 left_property.nature = propagate_binary_nature(
                     &left_property, &right_property,
                     &BinaryOperators {
                         float: None,
-                        int: Some(Box::new(|l, r| Ok(l.rem_euclid(*r)))),
+                        int: Some(Box::new(|l, r| l.checked_rem_euclid(*r)
+                            .ok_or_else(|| Error::from("divisor may not be zero")))),
                         bool: None,
                         str: None,
                     },
                     &OptimizeBinaryOperators {
                         float: None,
-                        int: Some(&|bounds| Ok((Some(0), bounds.right_upper.map(|v| v - 1)))),
-                    }, left_property.num_columns()?)?;
+                        int: Some(&|bounds| {
+                            let (smallest, largest) = divisor_magnitudes(bounds.right_lower, bounds.right_upper)?;
+                            Ok(match (bounds.left_lower, bounds.left_upper) {
+                                (Some(lower), Some(upper)) if *lower >= 0 && *upper < smallest => (Some(*lower), Some(*upper)),
+                                _ => (Some(0), Some(largest - 1))
+                            })
+                        }),
+                    }, num_columns)?;
             },
-            _ => return Err("arguments for power must be numeric and homogeneously typed".into())
+            _ => return Err("arguments for modulo must be numeric and homogeneously typed".into())
         };
 
+        left_property.releasable = left_property.releasable && right_property.releasable;
+        left_property.num_columns = Some(num_columns);
+        left_property.num_records = num_records;
+
         left_property.is_not_empty = left_property.is_not_empty && right_property.is_not_empty;
         left_property.dimensionality = left_property.dimensionality
             .max(right_property.dimensionality);
@@ -671,7 +734,21 @@ impl Component for proto::Modulo {
 }
 
 
-impl Component for proto::Multiply {
+/// The smallest and largest magnitudes of a divisor, when the bounds on the divisor exclude zero
+fn divisor_magnitudes<T: PartialOrd + Zero + Copy + num::Signed>(
+    lower: &Option<T>, upper: &Option<T>
+) -> Result<(T, T)> {
+    match (lower, upper) {
+        (Some(lower), Some(upper)) if *lower > T::zero() || *upper < T::zero() => {
+            let (lower, upper) = (lower.abs(), upper.abs());
+            Ok(if lower < upper { (lower, upper) } else { (upper, lower) })
+        }
+        (Some(_), Some(_)) => Err("divisor may not be zero".into()),
+        _ => Err("divisor must be bounded".into())
+    }
+}
+
+impl Component for proto::FloorDivide {
     fn propagate_property(
         &self,
         _privacy_definition: &Option<proto::PrivacyDefinition>,
@@ -679,7 +756,7 @@ impl Component for proto::Multiply {
         properties: base::NodeProperties,
         node_id: u32
     ) -> Result<Warnable<ValueProperties>> {
-        let left_property: ArrayProperties = properties.get(&IndexKey::from("left"))
+        let mut left_property: ArrayProperties = properties.get(&IndexKey::from("left"))
             .ok_or("left: missing")?.array()
             .map_err(prepend("left:"))?.clone();
         let right_property: ArrayProperties = properties.get::<IndexKey>(&"right".into())
@@ -695,11 +772,111 @@ impl Component for proto::Multiply {
             right_property.assert_is_not_sampled()?;
         }
 
+        let (num_columns, num_records) = propagate_binary_shape(&left_property, &right_property)?;
+        if right_property.nature.is_none() {
+            return Err("right: divisor must be bounded".into())
+        }
+
+        // the floored quotient is monotonic in each argument when the divisor does not span zero,
+        //     so the bounds are attained at the corners of the bounds on the arguments
+        fn optimize<T: PartialOrd + Zero + Copy + num::Signed>(
+            bounds: BinaryBounds<T>, floor_divide: &dyn Fn(T, T) -> T
+        ) -> Result<(Option<T>, Option<T>)> {
+            divisor_magnitudes(bounds.right_lower, bounds.right_upper)?;
+            let (a, c, d, f) = match (bounds.left_lower, bounds.left_upper, bounds.right_lower, bounds.right_upper) {
+                (Some(a), Some(c), Some(d), Some(f)) => (*a, *c, *d, *f),
+                _ => return Ok((None, None))
+            };
+            let compare = |x: &T, y: &T| x.partial_cmp(y).unwrap_or(Ordering::Equal);
+            let (min, max) = vec![floor_divide(a, d), floor_divide(a, f), floor_divide(c, d), floor_divide(c, f)]
+                .into_iter().minmax_by(compare).into_option().unwrap();
+            Ok((Some(min), Some(max)))
+        }
+
+        match (left_property.data_type.clone(), right_property.data_type.clone()) {
+            (DataType::Float, DataType::Float) | (DataType::Int, DataType::Int) => (),
+            _ => return Err("arguments for floor divide must be numeric and homogeneously typed".into())
+        }
+
+        left_property.nature = propagate_binary_nature(
+            &left_property, &right_property,
+            &BinaryOperators {
+                float: Some(Box::new(|l: &Float, r: &Float| if r == &0. {
+                    Err("divisor may not be zero".into())
+                } else { Ok((l / r).floor()) })),
+                int: Some(Box::new(|l: &Integer, r: &Integer| if r == &0 {
+                    Err("divisor may not be zero".into())
+                } else { Ok(num::Integer::div_floor(l, r)) })),
+                bool: None,
+                str: None,
+            },
+            &OptimizeBinaryOperators {
+                float: Some(&|bounds| optimize(bounds, &|l: Float, r| (l / r).floor())),
+                int: Some(&|bounds| optimize(bounds, &|l: Integer, r| num::Integer::div_floor(&l, &r))),
+            }, num_columns)?;
+
+        left_property.releasable = left_property.releasable && right_property.releasable;
+        left_property.num_columns = Some(num_columns);
+        left_property.num_records = num_records;
+        left_property.c_stability = left_property.c_stability.max(right_property.c_stability);
+        left_property.is_not_empty = left_property.is_not_empty && right_property.is_not_empty;
+        left_property.dimensionality = left_property.dimensionality
+            .max(right_property.dimensionality);
+        left_property.group_id = propagate_binary_group_id(&left_property, &right_property)?;
+        left_property.dataset_id = propagate_binary_dataset_id(&left_property, &right_property);
+        left_property.node_id = node_id as i64;
+        left_property.naturally_ordered = true;
+        Ok(ValueProperties::Array(left_property).into())
+    }
+}
+
+
+impl Component for proto::Multiply {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let left_property: ArrayProperties = properties.get(&IndexKey::from("left"))
+            .ok_or("left: missing")?.array()
+            .map_err(prepend("left:"))?.clone();
+        let right_property: ArrayProperties = properties.get::<IndexKey>(&"right".into())
+            .ok_or("right: missing")?.array()
+            .map_err(prepend("right:"))?.clone();
+
+        let left_public = public_arguments.get(&IndexKey::from("left")).cloned();
+        let right_public = public_arguments.get(&IndexKey::from("right")).cloned();
+
+        // aggregates may be scaled by public constants
+        if !left_property.releasable {
+            if right_public.is_none() {
+                left_property.assert_is_not_aggregated()?;
+            }
+            left_property.assert_is_not_sampled()?;
+        }
+        if !right_property.releasable {
+            if left_public.is_none() {
+                right_property.assert_is_not_aggregated()?;
+            }
+            right_property.assert_is_not_sampled()?;
+        }
+
         let (num_columns, num_records) = propagate_binary_shape(&left_property, &right_property)?;
         if left_property.data_type != right_property.data_type {
             return Err("left and right arguments must share the same data types".into())
         }
 
+        // multiplication by c is |c|-Lipschitz
+        let aggregator = match (right_public, left_public) {
+            (Some(constant), _) if left_property.aggregator.is_some() =>
+                propagate_lipschitz(&left_property, constant, num_columns, |c| Ok(c.abs()))?,
+            (_, Some(constant)) if right_property.aggregator.is_some() =>
+                propagate_lipschitz(&right_property, constant, num_columns, |c| Ok(c.abs()))?,
+            _ => None
+        };
+
         /// compute minimum and maximum of b * e when a <= b <= c and d <= e <= f
         fn optimize<T: PartialOrd + Mul<Output=T> + Zero + Copy>(
             a: T, c: T, d: T, f: T
@@ -759,10 +936,10 @@ impl Component for proto::Multiply {
                 .max(right_property.c_stability),
             num_columns: Some(num_columns),
             group_id: propagate_binary_group_id(&left_property, &right_property)?,
-            data_type: left_property.data_type,
+            data_type: left_property.data_type.clone(),
             num_records,
-            aggregator: None,
-            dataset_id: left_property.dataset_id,
+            aggregator,
+            dataset_id: propagate_binary_dataset_id(&left_property, &right_property),
             node_id: node_id as i64,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
@@ -817,9 +994,7 @@ impl Component for proto::Negative {
             .ok_or("data: missing")?.array()
             .map_err(prepend("data:"))?.clone();
 
-        if !data_property.releasable {
-            data_property.assert_is_not_aggregated()?;
-        }
+        // negation is 1-Lipschitz, so the aggregator passes through unchanged
 
         data_property.nature = propagate_unary_nature(
             &data_property,
@@ -870,6 +1045,9 @@ impl Component for proto::Or {
             return Err("left and right arguments must share the same data types".into())
         }
 
+        // the dataset and partition are taken from the private arguments, before releasability is updated
+        left_property.dataset_id = propagate_binary_dataset_id(&left_property, &right_property);
+        left_property.group_id = propagate_binary_group_id(&left_property, &right_property)?;
         left_property.releasable = left_property.releasable && right_property.releasable;
         left_property.nature = propagate_binary_nature(
             &left_property, &right_property,
@@ -882,7 +1060,7 @@ impl Component for proto::Or {
             num_columns)?;
         // checks to ensure this is correct are made in propagate_binary_shape
         left_property.c_stability = left_property.c_stability
-            .max(left_property.c_stability);
+            .max(right_property.c_stability);
         left_property.num_columns = Some(num_columns);
         left_property.num_records = num_records;
 
@@ -890,8 +1068,6 @@ impl Component for proto::Or {
         left_property.dimensionality = left_property.dimensionality
             .max(right_property.dimensionality);
 
-        left_property.group_id = propagate_binary_group_id(&left_property, &right_property)?;
-
         Ok(ValueProperties::Array(left_property).into())
     }
 }
@@ -970,6 +1146,19 @@ impl Component for proto::Power {
 }
 
 
+impl Component for proto::Round {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        // round is non-decreasing, so bounds are rounded
+        Ok(ValueProperties::Array(propagate_non_decreasing_float(properties, Float::round)?).into())
+    }
+}
+
 impl Component for proto::RowMax {
     fn propagate_property(
         &self,
@@ -1042,8 +1231,8 @@ impl Component for proto::RowMax {
             num_records,
             aggregator: None,
             group_id: propagate_binary_group_id(&left_property, &right_property)?,
-            data_type: left_property.data_type,
-            dataset_id: left_property.dataset_id,
+            data_type: left_property.data_type.clone(),
+            dataset_id: propagate_binary_dataset_id(&left_property, &right_property),
             node_id: node_id as i64,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
@@ -1127,8 +1316,8 @@ impl Component for proto::RowMin {
             num_records,
             aggregator: None,
             group_id: propagate_binary_group_id(&left_property, &right_property)?,
-            data_type: left_property.data_type,
-            dataset_id: left_property.dataset_id,
+            data_type: left_property.data_type.clone(),
+            dataset_id: propagate_binary_dataset_id(&left_property, &right_property),
             node_id: node_id as i64,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
@@ -1140,6 +1329,42 @@ impl Component for proto::RowMin {
     }
 }
 
+impl Component for proto::Sqrt {
+    fn propagate_property(
+        &self,
+        _privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32
+    ) -> Result<Warnable<ValueProperties>> {
+        let data_property: ArrayProperties = properties.get(&IndexKey::from("data"))
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        let non_negative = match &data_property.nature {
+            Some(Nature::Continuous(_)) => data_property.lower_float_option()?.iter()
+                .all(|lower| lower.map(|lower| lower >= 0.).unwrap_or(false)),
+            Some(Nature::Categorical(_)) => data_property.categories()?.float()
+                .map(|categories| categories.iter().flatten().all(|v| *v >= 0.))
+                .unwrap_or(false),
+            None => false
+        };
+
+        // sqrt is increasing on [0, inf), where the bounds are known to be
+        let mut data_property = propagate_non_decreasing_float(properties, |v| v.max(0.).sqrt())?;
+
+        if !non_negative {
+            if !data_property.releasable {
+                return Err("data may potentially be less than zero".into())
+            }
+            // public data may be transformed, but negative values become NaN
+            data_property.nullity = true;
+        }
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
 impl Component for proto::Subtract {
     fn propagate_property(
         &self,
@@ -1205,8 +1430,8 @@ impl Component for proto::Subtract {
             num_records,
             aggregator: None,
             group_id: propagate_binary_group_id(&left_property, &right_property)?,
-            data_type: left_property.data_type,
-            dataset_id: left_property.dataset_id,
+            data_type: left_property.data_type.clone(),
+            dataset_id: propagate_binary_dataset_id(&left_property, &right_property),
             node_id: node_id as i64,
             is_not_empty: left_property.is_not_empty && right_property.is_not_empty,
             dimensionality: left_property.dimensionality
@@ -1218,6 +1443,111 @@ impl Component for proto::Subtract {
     }
 }
 
+/// Propagate properties through an element-wise, non-decreasing operator on floats.
+///
+/// Bounds and categories are mapped through the operator.
+/// Bounds that are not finite after the operator are no longer known.
+fn propagate_non_decreasing_float(
+    properties: base::NodeProperties,
+    operator: fn(Float) -> Float,
+) -> Result<ArrayProperties> {
+    let mut data_property: ArrayProperties = properties.get(&IndexKey::from("data"))
+        .ok_or("data: missing")?.array()
+        .map_err(prepend("data:"))?.clone();
+
+    if !data_property.releasable {
+        data_property.assert_is_not_aggregated()?;
+    }
+
+    if data_property.data_type != DataType::Float {
+        return Err("data: atomic type must be float".into())
+    }
+
+    let bound = move |v: &Option<Float>| v.map(operator).filter(|v| v.is_finite());
+    data_property.nature = propagate_unary_nature(
+        &data_property,
+        &UnaryOperators {
+            float: Some(Box::new(move |v| Ok(operator(*v)))),
+            int: None,
+            bool: None,
+            str: None,
+        },
+        &OptimizeUnaryOperators {
+            float: Some(Box::new(move |bounds| Ok((bound(bounds.lower), bound(bounds.upper))))),
+            int: None,
+        }, data_property.num_columns()?)?;
+
+    Ok(data_property)
+}
+
+macro_rules! make_named_binary {
+    ($variant:ident, $symbol:expr) => {
+        impl Named for proto::$variant {
+            fn get_names(
+                &self,
+                public_arguments: IndexMap<base::IndexKey, &Value>,
+                argument_variables: IndexMap<base::IndexKey, Vec<IndexKey>>,
+                _release: Option<&Value>
+            ) -> Result<Vec<IndexKey>> {
+                propagate_binary_names(&public_arguments, &argument_variables, $symbol)
+            }
+        }
+    }
+}
+
+make_named_binary!(Add, "+");
+make_named_binary!(And, "&");
+make_named_binary!(Divide, "/");
+make_named_binary!(Equal, "==");
+make_named_binary!(GreaterThan, ">");
+make_named_binary!(LessThan, "<");
+make_named_binary!(Modulo, "%");
+make_named_binary!(Multiply, "*");
+make_named_binary!(Or, "|");
+make_named_binary!(RowMax, "max");
+make_named_binary!(RowMin, "min");
+make_named_binary!(Subtract, "-");
+
+/// Propagate names through an element-wise binary operation.
+///
+/// Public arguments are broadcast against the data, so names are taken from the other argument.
+/// When names on both arguments are known and differ, they are combined column-wise, i.e. "a + b".
+pub fn propagate_binary_names(
+    public_arguments: &IndexMap<IndexKey, &Value>,
+    argument_variables: &IndexMap<IndexKey, Vec<IndexKey>>,
+    symbol: &str
+) -> Result<Vec<IndexKey>> {
+    let left_key = IndexKey::from("left");
+    let right_key = IndexKey::from("right");
+
+    let left_names = argument_variables.get(&left_key)
+        .filter(|_| !public_arguments.contains_key(&left_key));
+    let right_names = argument_variables.get(&right_key)
+        .filter(|_| !public_arguments.contains_key(&right_key));
+
+    let (left_names, right_names) = match (left_names, right_names) {
+        (Some(left_names), Some(right_names)) => (left_names, right_names),
+        (Some(names), None) | (None, Some(names)) => return Ok(names.clone()),
+        (None, None) => return argument_variables.get(&left_key)
+            .or_else(|| argument_variables.get(&right_key)).cloned()
+            .ok_or_else(|| "names on left and right are unknown".into())
+    };
+
+    let combine = |left: &IndexKey, right: &IndexKey| if left == right {
+        left.clone()
+    } else {
+        IndexKey::from(format!("{} {} {}", left.to_string(), symbol, right.to_string()))
+    };
+
+    Ok(match (left_names.len(), right_names.len()) {
+        (l, r) if l == r => left_names.iter().zip(right_names.iter())
+            .map(|(left, right)| combine(left, right)).collect(),
+        (1, _) => right_names.iter().map(|right| combine(&left_names[0], right)).collect(),
+        (_, 1) => left_names.iter().map(|left| combine(left, &right_names[0])).collect(),
+        _ => return Err("names on left and right must be the same length, or one must be broadcastable".into())
+    })
+}
+
 type UnaryOperator<T> = Option<Box<dyn Fn(&T) -> Result<T>>>;
 pub struct UnaryOperators {
     pub float: UnaryOperator<Float>,
@@ -1554,6 +1884,49 @@ pub fn propagate_binary_group_id(
     Ok(left_property.group_id.clone())
 }
 
+/// The dataset of a binary operation is the dataset of its private arguments.
+///
+/// Public arguments may be broadcast against any dataset,
+/// so the dataset id of a public left argument must not mask the dataset id of a private right argument.
+pub fn propagate_binary_dataset_id(
+    left_property: &ArrayProperties, right_property: &ArrayProperties
+) -> Option<i64> {
+    if left_property.releasable && !right_property.releasable {
+        return right_property.dataset_id
+    }
+    left_property.dataset_id
+}
+
+/// Scale the lipschitz constants of an aggregated property by a function of a public constant.
+///
+/// The constant may be a scalar, or have one value per column.
+fn propagate_lipschitz(
+    aggregated_property: &ArrayProperties,
+    constant: &Value,
+    num_columns: i64,
+    scale: impl Fn(Float) -> Result<Float>
+) -> Result<Option<AggregatorProperties>> {
+    let mut aggregator = match aggregated_property.aggregator.clone() {
+        Some(aggregator) => aggregator,
+        None => return Ok(None)
+    };
+
+    let constants = constant.clone().array()?.cast_float()
+        .map_err(prepend("constant:"))?.iter()
+        .map(|c| scale(*c)).collect::<Result<Vec<Float>>>()?;
+    let lipschitz = aggregator.lipschitz_constants.clone().array()?.cast_float()?
+        .iter().cloned().collect::<Vec<Float>>();
+
+    let lipschitz = broadcast(&lipschitz, num_columns)?.into_iter()
+        .zip(broadcast(&constants, num_columns)
+            .map_err(|_| Error::from("constant must be a scalar or have one value per column"))?)
+        .map(|(l, c)| l * c).collect::<Vec<Float>>();
+
+    aggregator.lipschitz_constants = ndarray::Array::from_shape_vec(
+        vec![1, num_columns as usize], lipschitz)?.into_dyn().into();
+    Ok(Some(aggregator))
+}
+
 fn broadcast<T: Clone>(data: &[T], length: i64) -> Result<Vec<T>> {
     if data.len() as i64 == length {
         return Ok(data.to_owned());
@@ -1565,3 +1938,392 @@ fn broadcast<T: Clone>(data: &[T], length: i64) -> Result<Vec<T>> {
 
     Ok((0..length).map(|_| data[0].clone()).collect())
 }
+
+#[cfg(test)]
+mod test_transforms {
+    use ndarray::{arr0, arr1, arr2, Array2};
+    use indexmap::IndexMap;
+
+    use crate::base::{IndexKey, ReleaseNode, Value, ValueProperties};
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::components::Component;
+    use crate::base::SensitivitySpace;
+    use crate::components::transforms::propagate_binary_names;
+    use crate::utilities::expand_mechanism;
+    use crate::{proto, generate_report};
+
+    /// Select column `name` from a public two-column dataframe with columns "a" and "b"
+    fn analysis_column(name: &str) -> (Analysis, u32) {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 2), |(i, j)| (i + j + 1) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let column_names = analysis.literal()
+            .value(arr1(&["a".to_string(), "b".to_string()]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let dataframe = analysis.to_dataframe(data, column_names).build();
+
+        let names = analysis.literal()
+            .value(arr1(&[name.to_string()]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let column = analysis.index(dataframe).names(names).build();
+
+        (analysis, column)
+    }
+
+    #[test]
+    fn test_log_mean_report_names() {
+        let (mut analysis, column) = analysis_column("b");
+
+        let base = analysis.literal()
+            .value(2.0.into()).value_public(true)
+            .build();
+        let logged = analysis.log(column, base).build();
+
+        let lower = analysis.literal()
+            .value(0.0.into()).value_public(true)
+            .build();
+        let upper = analysis.literal()
+            .value(4.0.into()).value_public(true)
+            .build();
+        let clamped = analysis.clamp(logged)
+            .lower(lower).upper(upper)
+            .build();
+
+        let one = analysis.literal()
+            .value(1.0.into()).value_public(true)
+            .build();
+        let shifted = analysis.add(clamped, one).build();
+
+        let dp_mean = analysis.dp_mean(shifted, privacy_usage(1., 0.))
+            .mechanism("Laplace".to_string())
+            .build();
+        analysis.release.insert(dp_mean, ReleaseNode::new(Value::from(2.0)));
+
+        let report = generate_report(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+        assert_eq!(report[0]["variables"], serde_json::json!("b"));
+    }
+
+    #[test]
+    fn test_binary_names() {
+        let mut argument_variables = IndexMap::<IndexKey, Vec<IndexKey>>::new();
+        argument_variables.insert("left".into(), vec!["a".into(), "b".into()]);
+        argument_variables.insert("right".into(), vec!["a".into(), "c".into()]);
+
+        let names = propagate_binary_names(&IndexMap::new(), &argument_variables, "*").unwrap();
+        assert_eq!(names, vec![IndexKey::from("a"), IndexKey::from("b * c")]);
+
+        argument_variables.insert("right".into(), vec!["c".into()]);
+        let names = propagate_binary_names(&IndexMap::new(), &argument_variables, "-").unwrap();
+        assert_eq!(names, vec![IndexKey::from("a - c"), IndexKey::from("b - c")]);
+    }
+
+    /// Clamp two public float records between lower and upper
+    fn analysis_clamped(data: Vec<f64>, lower: f64, upper: f64) -> (Analysis, u32) {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(ndarray::Array::from(data).into_shape((2, 1)).unwrap().into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(lower.into()).value_public(true).build();
+        let upper = analysis.literal().value(upper.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        (analysis, clamped)
+    }
+
+    fn bounds(analysis: &Analysis, node_id: u32) -> (Vec<Option<f64>>, Vec<Option<f64>>) {
+        let properties = analysis.properties(node_id).unwrap();
+        let properties = properties.array().unwrap();
+        (properties.lower_float_option().unwrap(), properties.upper_float_option().unwrap())
+    }
+
+    #[test]
+    fn test_unary_bounds() {
+        let (mut analysis, clamped) = analysis_clamped(vec![-1.5, 2.5], -1.5, 2.5);
+        let ceil = analysis.ceil(clamped).build();
+        let floor = analysis.floor(clamped).build();
+        let round = analysis.round(clamped).build();
+        let exp = analysis.exp(clamped).build();
+
+        assert_eq!(bounds(&analysis, ceil), (vec![Some(-1.)], vec![Some(3.)]));
+        assert_eq!(bounds(&analysis, floor), (vec![Some(-2.)], vec![Some(2.)]));
+        assert_eq!(bounds(&analysis, round), (vec![Some(-2.)], vec![Some(3.)]));
+        assert_eq!(bounds(&analysis, exp), (vec![Some((-1.5f64).exp())], vec![Some(2.5f64.exp())]));
+
+        // bounds that overflow are no longer known
+        let (mut analysis, clamped) = analysis_clamped(vec![0., 1000.], 0., 1000.);
+        let exp = analysis.exp(clamped).build();
+        assert_eq!(bounds(&analysis, exp), (vec![Some(1.)], vec![None]));
+    }
+
+    #[test]
+    fn test_sqrt_domain() {
+        let (mut analysis, clamped) = analysis_clamped(vec![1., 9.], 1., 9.);
+        let sqrt = analysis.sqrt(clamped).build();
+        assert_eq!(bounds(&analysis, sqrt), (vec![Some(1.)], vec![Some(3.)]));
+        assert!(!analysis.properties(sqrt).unwrap().array().unwrap().nullity);
+
+        // public data may be negative, but negative values become null
+        let (mut analysis, clamped) = analysis_clamped(vec![-1., 4.], -1., 9.);
+        let sqrt = analysis.sqrt(clamped).build();
+        assert!(analysis.properties(sqrt).unwrap().array().unwrap().nullity);
+
+        // private data must be known to be non-negative
+        let propagate_private = |analysis: &Analysis, node_id: u32| {
+            let mut data_property = analysis.properties(node_id).unwrap().array().unwrap().clone();
+            data_property.releasable = false;
+            let mut properties = IndexMap::<IndexKey, ValueProperties>::new();
+            properties.insert("data".into(), ValueProperties::Array(data_property));
+            proto::Sqrt {}.propagate_property(&None, IndexMap::new(), properties, 0)
+        };
+        assert!(propagate_private(&analysis, clamped).is_err());
+        let (analysis, clamped) = analysis_clamped(vec![0., 9.], 0., 9.);
+        assert!(propagate_private(&analysis, clamped).is_ok());
+    }
+
+    /// Sensitivity of a laplace mechanism applied to `node_id`, as computed in its expansion
+    fn laplace_sensitivity(analysis: &mut Analysis, node_id: u32) -> Vec<f64> {
+        let laplace = analysis.laplace_mechanism(node_id, privacy_usage(1., 0.)).build();
+        let mut properties = IndexMap::<IndexKey, ValueProperties>::new();
+        properties.insert("data".into(), analysis.properties(node_id).unwrap());
+        let expansion = expand_mechanism(
+            &SensitivitySpace::KNorm(1),
+            &Some(analysis.privacy_definition.clone()),
+            &privacy_usage(1., 0.),
+            analysis.components.get(&laplace).unwrap(),
+            &properties,
+            laplace, 1000).unwrap();
+        expansion.releases.values().next().unwrap()
+            .value.ref_array().unwrap().clone().cast_float().unwrap()
+            .iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_lipschitz_scaling() {
+        // scaling before aggregation widens the bounds of the data
+        let (mut analysis, clamped) = analysis_clamped(vec![0., 1.], 0., 1.);
+        let mean = analysis.mean(clamped).build();
+        let baseline = laplace_sensitivity(&mut analysis, mean);
+
+        let scale = analysis.literal().value((-3.).into()).value_public(true).build();
+        let scaled = analysis.multiply(clamped, scale).build();
+        let mean = analysis.mean(scaled).build();
+        assert_eq!(laplace_sensitivity(&mut analysis, mean), vec![baseline[0] * 3.]);
+
+        // scaling after aggregation updates the lipschitz constants of the aggregate
+        let mean = analysis.mean(clamped).build();
+        let scaled = analysis.multiply(scale, mean).build();
+        assert_eq!(laplace_sensitivity(&mut analysis, scaled), vec![baseline[0] * 3.]);
+
+        let half = analysis.literal().value(2.0.into()).value_public(true).build();
+        let divided = analysis.divide(scaled, half).build();
+        assert_eq!(laplace_sensitivity(&mut analysis, divided), vec![baseline[0] * 1.5]);
+
+        let negated = analysis.negative(divided).build();
+        assert_eq!(laplace_sensitivity(&mut analysis, negated), vec![baseline[0] * 1.5]);
+
+        // nonlinear transformations of aggregates are rejected
+        let zero = analysis.literal().value(0.0.into()).value_public(true).build();
+        let divided = analysis.divide(mean, zero).build();
+        assert!(analysis.properties(divided).is_err());
+        let inverted = analysis.divide(half, mean).build();
+        let inverted = analysis.laplace_mechanism(inverted, privacy_usage(1., 0.)).build();
+        assert!(analysis.properties(inverted).is_err());
+    }
+
+    #[test]
+    fn test_aggregated_transforms() {
+        let (mut analysis, clamped) = analysis_clamped(vec![1., 9.], 1., 9.);
+        let mean = analysis.mean(clamped).build();
+        let mean_property = analysis.properties(mean).unwrap().array().unwrap().clone();
+
+        let propagate = |releasable: bool, variant: proto::component::Variant, arguments: &[&str]| {
+            let mut data_property = mean_property.clone();
+            data_property.releasable = releasable;
+            let properties = arguments.iter()
+                .map(|name| (IndexKey::from(*name), ValueProperties::Array(data_property.clone())))
+                .collect::<IndexMap<IndexKey, ValueProperties>>();
+            let component = proto::Component {
+                arguments: None, omit: false, submission: 0,
+                variant: Some(variant),
+            };
+            component.propagate_property(&Some(analysis.privacy_definition.clone()), IndexMap::new(), properties, 0)
+        };
+
+        use proto::component::Variant;
+        let cases = vec![
+            (Variant::Abs(proto::Abs {}), vec!["data"]),
+            (Variant::Exp(proto::Exp {}), vec!["data"]),
+            (Variant::Round(proto::Round {}), vec!["data"]),
+            (Variant::Add(proto::Add {}), vec!["left", "right"]),
+            (Variant::Multiply(proto::Multiply {}), vec!["left", "right"]),
+            (Variant::ColumnBind(proto::ColumnBind {}), vec!["0", "1"]),
+        ];
+
+        cases.into_iter().for_each(|(variant, arguments)| {
+            // transforming an aggregate before it is privatized is not permitted
+            let error = propagate(false, variant.clone(), &arguments).unwrap_err();
+            assert!(error.iter().any(|e| e.to_string().contains("privatized by a mechanism")), "{:?}: {:?}", variant, error);
+
+            // transforming a releasable aggregate is postprocessing
+            assert!(propagate(true, variant.clone(), &arguments).is_ok(), "{:?}", variant);
+        });
+    }
+
+    /// private integer data bounded to [lower, upper], passed through `transform` with a public divisor
+    fn divide_int(
+        lower: i64, upper: i64, divisor: i64,
+        transform: &dyn Fn(&mut Analysis, u32, u32) -> u32
+    ) -> (Analysis, u32, crate::errors::Result<ValueProperties>) {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(arr2(&[[1], [5], [7], [12]]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let lower = analysis.literal().value(lower.into()).value_public(true).build();
+        let upper = analysis.literal().value(upper.into()).value_public(true).build();
+        let data = analysis.to_int(data, lower, upper).build();
+        let number_rows = analysis.literal().value(4.into()).value_public(true).build();
+        let number_columns = analysis.literal().value(1.into()).value_public(true).build();
+        let data = analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .lower(lower).upper(upper)
+            .build();
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+        let data = analysis.impute(data).lower(lower).upper(upper).build();
+        let divisor = analysis.literal().value(divisor.into()).value_public(true).build();
+        let result = transform(&mut analysis, data, divisor);
+        let properties = analysis.properties(result);
+        (analysis, result, properties)
+    }
+
+    #[test]
+    fn test_modulo_histogram() {
+        // the remainders of a constant divisor are bounded by the divisor
+        for divisor in &[3, -3] {
+            let (mut analysis, remainder, properties) = divide_int(-10, 10, *divisor,
+                &|analysis, data, divisor| analysis.modulo(data, divisor).build());
+            let properties = properties.unwrap().array().unwrap().clone();
+            assert_eq!(properties.lower_int().unwrap(), vec![0]);
+            assert_eq!(properties.upper_int().unwrap(), vec![2]);
+
+            // a histogram over the remainders finds the categories without any being supplied
+            let lower = analysis.literal().value(arr0(0).into_dyn().into()).value_public(true).build();
+            let inclusive_left = analysis.literal().value(arr0(true).into_dyn().into()).value_public(true).build();
+            let dp_histogram = analysis.dp_histogram(remainder, privacy_usage(1., 0.)).lower(lower).inclusive_left(inclusive_left).build();
+            let histogram = analysis.properties(dp_histogram).unwrap().array().unwrap().clone();
+            assert!(histogram.releasable);
+            assert_eq!(histogram.num_records, Some(3));
+        }
+
+        // dividends smaller than the divisor are unchanged
+        let (_, _, properties) = divide_int(0, 2, 5, &|analysis, data, divisor| analysis.modulo(data, divisor).build());
+        let properties = properties.unwrap().array().unwrap().clone();
+        assert_eq!((properties.lower_int().unwrap(), properties.upper_int().unwrap()), (vec![0], vec![2]));
+
+        // zero divisors are rejected
+        let (_, _, properties) = divide_int(0, 10, 0, &|analysis, data, divisor| analysis.modulo(data, divisor).build());
+        assert!(properties.is_err());
+    }
+
+    #[test]
+    fn test_modulo_sum() {
+        // the remainders keep continuous bounds, so they may be clamped and summed
+        let (mut analysis, remainder, _) = divide_int(-10, 10, 3,
+            &|analysis, data, divisor| analysis.modulo(data, divisor).build());
+        let lower = analysis.literal().value(arr0(0).into_dyn().into()).value_public(true).build();
+        let upper = analysis.literal().value(arr0(2).into_dyn().into()).value_public(true).build();
+        let clamped = analysis.clamp(remainder).lower(lower).upper(upper).build();
+        let sum = analysis.sum(clamped).build();
+        let properties = analysis.properties(sum).unwrap().array().unwrap().clone();
+        assert_eq!(properties.lower_int().unwrap(), vec![0]);
+        assert_eq!(properties.upper_int().unwrap(), vec![8]);
+    }
+
+    #[test]
+    fn test_divide_bounds() {
+        let mut analysis = Analysis::new();
+        let clamped = |analysis: &mut Analysis, lower: f64, upper: f64| {
+            let data = analysis.literal()
+                .value(arr2(&[[lower], [upper]]).into_dyn().into())
+                .value_public(true)
+                .build();
+            // the bounds of constant data are inferred from the literal
+            if lower == upper { return data }
+            let lower = analysis.literal().value(lower.into()).value_public(true).build();
+            let upper = analysis.literal().value(upper.into()).value_public(true).build();
+            analysis.clamp(data).lower(lower).upper(upper).build()
+        };
+        let cases = vec![
+            // denominators that exclude zero are bounded by the corners
+            ((1., 4.), (2., 8.), (Some(0.125), Some(2.)), false),
+            ((-2., 4.), (-4., -1.), (Some(-4.), Some(2.)), false),
+            ((-2., 4.), (1., 2.), (Some(-2.), Some(4.)), false),
+            // denominators with zero as an endpoint diverge in the direction of the numerator
+            ((1., 4.), (0., 2.), (Some(0.5), None), true),
+            ((-4., -1.), (0., 2.), (None, Some(-0.5)), true),
+            ((1., 4.), (-2., 0.), (None, Some(-0.5)), true),
+            ((-1., 1.), (0., 2.), (None, None), true),
+            // denominators that straddle zero are unbounded
+            ((1., 4.), (-1., 1.), (None, None), true),
+            ((0., 0.), (-1., 1.), (Some(0.), Some(0.)), true),
+        ];
+        let nodes = cases.iter()
+            .map(|((a, c), (d, f), _, _)| (clamped(&mut analysis, *a, *c), clamped(&mut analysis, *d, *f)))
+            .collect::<Vec<(u32, u32)>>();
+
+        cases.into_iter().zip(nodes).for_each(|(case, (left, right))| {
+            let quotient = analysis.divide(left, right).build();
+            assert_eq!(bounds(&analysis, quotient), (vec![(case.2).0], vec![(case.2).1]), "{:?}", case);
+            // a zero denominator makes the quotient null
+            assert_eq!(analysis.properties(quotient).unwrap().array().unwrap().nullity, case.3, "{:?}", case);
+        });
+
+        // a denominator that is always zero is rejected
+        let (left, right) = (clamped(&mut analysis, 1., 4.), clamped(&mut analysis, 0., 0.));
+        let quotient = analysis.divide(left, right).build();
+        assert!(analysis.properties(quotient).is_err());
+
+        // integer quotients truncate, and integer divisors may not be zero
+        let (_, _, properties) = divide_int(-7, 10, 3, &|analysis, data, divisor| analysis.divide(data, divisor).build());
+        let properties = properties.unwrap().array().unwrap().clone();
+        assert_eq!((properties.lower_int().unwrap(), properties.upper_int().unwrap()), (vec![-2], vec![3]));
+        let (_, _, properties) = divide_int(-7, 10, 0, &|analysis, data, divisor| analysis.divide(data, divisor).build());
+        assert!(properties.unwrap_err().to_string().contains("may not be zero"));
+
+        // integer divisors of unknown bounds may be zero
+        let mut analysis = Analysis::new();
+        let left = analysis.literal().value(arr2(&[[6], [8]]).into_dyn().into()).value_public(true).build();
+        let left = analysis.properties(left).unwrap().array().unwrap().clone();
+        let mut right = left.clone();
+        right.nature = None;
+        let properties = indexmap!["left".into() => ValueProperties::Array(left), "right".into() => ValueProperties::Array(right)];
+        let error = proto::Divide {}.propagate_property(&None, IndexMap::new(), properties, 0).unwrap_err();
+        assert!(error.to_string().contains("may not be zero"));
+    }
+
+    #[test]
+    fn test_floor_divide() {
+        let (_, _, properties) = divide_int(-7, 10, 3, &|analysis, data, divisor| analysis.floor_divide(data, divisor).build());
+        let properties = properties.unwrap().array().unwrap().clone();
+        assert_eq!(properties.lower_int().unwrap(), vec![-3]);
+        assert_eq!(properties.upper_int().unwrap(), vec![3]);
+
+        // a negative divisor reverses the bounds
+        let (_, _, properties) = divide_int(-7, 10, -3, &|analysis, data, divisor| analysis.floor_divide(data, divisor).build());
+        let properties = properties.unwrap().array().unwrap().clone();
+        assert_eq!(properties.lower_int().unwrap(), vec![-4]);
+        assert_eq!(properties.upper_int().unwrap(), vec![2]);
+
+        let (_, _, properties) = divide_int(-7, 10, 0, &|analysis, data, divisor| analysis.floor_divide(data, divisor).build());
+        assert!(properties.is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use ::itertools::izip;
+use indexmap::map::IndexMap;
+use itertools::Itertools;
+
+use crate::{base, proto, Warnable};
+use crate::base::{ArrayProperties, DataType, IndexKey, NodeProperties, SensitivitySpace, Value, ValueProperties};
+use crate::components::{Accuracy, Component, Expandable, Mechanism, Sensitivity};
+use crate::errors::*;
+use crate::utilities::{expand_mechanism, prepend};
+use crate::utilities::privacy::{get_delta, get_epsilon, privacy_usage_check, spread_privacy_usage};
+
+impl Component for proto::TruncatedLaplaceMechanism {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        _public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        _node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+
+        let privacy_definition = privacy_definition.as_ref()
+            .ok_or("privacy_definition must be defined")?;
+
+        if privacy_definition.protect_floating_point {
+            return Err("Floating-point protections are enabled. The truncated laplace mechanism is susceptible to floating-point attacks.".into())
+        }
+
+        if privacy_definition.group_size == 0 {
+            return Err("group size must be greater than zero".into())
+        }
+
+        let mut data_property: ArrayProperties = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if data_property.data_type != DataType::Float && data_property.data_type != DataType::Int {
+            return Err("data: atomic type must be numeric".into());
+        }
+
+        let aggregator = data_property.aggregator.clone()
+            .ok_or_else(|| Error::from("aggregator: missing"))?;
+
+        // sensitivity must be computable
+        aggregator.component.compute_sensitivity(
+            privacy_definition,
+            &aggregator.properties,
+            &SensitivitySpace::KNorm(1))?.array()?.cast_float()?;
+
+        // make sure lipschitz constants are available as a float array
+        aggregator.lipschitz_constants.array()?.cast_float()?;
+
+        let privacy_usage = self.privacy_usage.iter().cloned().map(Ok)
+            .fold1(|l, r| l? + r?).ok_or("privacy_usage: must be defined")??;
+
+        let warnings = privacy_usage_check(
+            &privacy_usage,
+            data_property.num_records,
+            privacy_definition.strict_parameter_checks)?;
+
+        // the truncated mass is paid for with delta
+        if get_delta(&privacy_usage)? == 0.0 {
+            return Err("delta: may not be zero".into())
+        }
+
+        data_property.releasable = true;
+        data_property.aggregator = None;
+
+        Ok(Warnable(data_property.into(), warnings))
+    }
+}
+
+
+impl Expandable for proto::TruncatedLaplaceMechanism {
+    fn expand_component(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        component: &proto::Component,
+        _public_arguments: &IndexMap<IndexKey, &Value>,
+        properties: &base::NodeProperties,
+        component_id: u32,
+        maximum_id: u32,
+    ) -> Result<base::ComponentExpansion> {
+        expand_mechanism(
+            &SensitivitySpace::KNorm(1),
+            privacy_definition,
+            self.privacy_usage.as_ref(),
+            component,
+            properties,
+            component_id,
+            maximum_id
+        )
+    }
+}
+
+impl Mechanism for proto::TruncatedLaplaceMechanism {
+    fn get_privacy_usage(
+        &self,
+        privacy_definition: &proto::PrivacyDefinition,
+        release_usage: Option<&Vec<proto::PrivacyUsage>>,
+        properties: &NodeProperties
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        let data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?;
+
+        // the effective delta covers the truncated mass, so it is charged alongside epsilon
+        Some(release_usage.unwrap_or(&self.privacy_usage).iter()
+            .map(|usage| usage.effective_to_actual(
+                data_property.sample_proportion.unwrap_or(1.),
+                data_property.c_stability,
+                privacy_definition.group_size))
+            .collect::<Result<Vec<proto::PrivacyUsage>>>()).transpose()
+    }
+}
+
+
+impl Accuracy for proto::TruncatedLaplaceMechanism {
+    fn accuracy_to_privacy_usage(
+        &self,
+        accuracies: &proto::Accuracies,
+        mut public_arguments: IndexMap<base::IndexKey, &Value>
+    ) -> Result<Option<Vec<proto::PrivacyUsage>>> {
+        // take max sensitivity of each column
+        let sensitivities: Vec<_> = public_arguments.remove(&IndexKey::from("sensitivity"))
+            .ok_or_else(|| Error::from("sensitivity: missing in accuracy"))?.clone()
+            .array()?.cast_float()?
+            .gencolumns().into_iter()
+            .map(|sensitivity_col| sensitivity_col.into_iter().copied().fold1(|l, r| l.max(r)).unwrap())
+            .collect();
+
+        let usages = spread_privacy_usage(&self.privacy_usage, sensitivities.len())?;
+        let deltas = usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?;
+        let iter = izip!(sensitivities.into_iter(), accuracies.values.iter(), deltas.into_iter());
+
+        use proto::privacy_usage::{Distance, DistanceApproximate};
+
+        iter.map(|(sensitivity, accuracy, delta)| Ok(proto::PrivacyUsage {
+            distance: Some(Distance::Approximate(DistanceApproximate {
+                epsilon: get_truncated_laplace_epsilon(accuracy.value, accuracy.alpha, delta, sensitivity)?,
+                delta,
+            }))
+        })).collect::<Result<Vec<_>>>().map(Some)
+    }
+
+    fn privacy_usage_to_accuracy(
+        &self,
+        mut public_arguments: IndexMap<base::IndexKey, &Value>,
+        alpha: f64
+    ) -> Result<Option<Vec<proto::Accuracy>>> {
+
+        // take max sensitivity of each column
+        let sensitivities: Vec<_> = public_arguments.remove(&IndexKey::from("sensitivity"))
+            .ok_or_else(|| Error::from("sensitivity: missing in accuracy"))?.clone()
+            .array()?.cast_float()?
+            .gencolumns().into_iter()
+            .map(|sensitivity_col| sensitivity_col.into_iter().copied().fold1(|l, r| l.max(r)).unwrap())
+            .collect();
+
+        let usages = spread_privacy_usage(&self.privacy_usage, sensitivities.len())?;
+        let epsilons = usages.iter().map(get_epsilon).collect::<Result<Vec<f64>>>()?;
+        let deltas = usages.iter().map(get_delta).collect::<Result<Vec<f64>>>()?;
+        let iter = izip!(sensitivities.into_iter(), epsilons.into_iter(), deltas.into_iter());
+
+        Ok(Some(iter
+            .map(|(sensitivity, epsilon, delta)| proto::Accuracy {
+                value: get_truncated_laplace_accuracy(epsilon, delta, sensitivity, alpha),
+                alpha,
+            })
+            .collect()))
+    }
+}
+
+/// Compute the bound on the magnitude of truncated Laplace noise.
+///
+/// Laplace noise with scale sensitivity/epsilon, conditioned on [-bound, bound], satisfies (epsilon, delta)-DP.
+/// See Theorem 1 in [Geng et al. (2020)](https://arxiv.org/abs/1810.00877).
+///
+/// # Arguments
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `delta` - Additive privacy loss parameter.
+/// * `sensitivity` - L1 sensitivity of the function being privatized.
+///
+/// # Return
+/// The bound A, such that noise lies within [-A, A].
+pub fn get_truncated_laplace_bound(epsilon: f64, delta: f64, sensitivity: f64) -> f64 {
+    sensitivity / epsilon * (epsilon.exp_m1() / (2. * delta)).ln_1p()
+}
+
+/// Compute the delta paid for truncating Laplace noise to [-bound, bound].
+///
+/// This is the inverse of `get_truncated_laplace_bound` with respect to delta.
+///
+/// # Arguments
+/// * `epsilon` - Multiplicative privacy loss parameter.
+/// * `sensitivity` - L1 sensitivity of the function being privatized.
+/// * `bound` - Noise is truncated to [-bound, bound].
+pub fn get_truncated_laplace_delta(epsilon: f64, sensitivity: f64, bound: f64) -> f64 {
+    epsilon.exp_m1() / (2. * (bound * epsilon / sensitivity).exp_m1())
+}
+
+/// Compute the smallest a such that |noise| <= a with probability at least 1 - alpha.
+fn get_truncated_laplace_accuracy(epsilon: f64, delta: f64, sensitivity: f64, alpha: f64) -> f64 {
+    let scale = sensitivity / epsilon;
+    let bound = get_truncated_laplace_bound(epsilon, delta, sensitivity);
+    // P(|noise| > a) = (e^{-a/b} - e^{-A/b}) / (1 - e^{-A/b})
+    let truncated_mass = (-bound / scale).exp();
+    -scale * (alpha + (1. - alpha) * truncated_mass).ln()
+}
+
+/// Find the smallest epsilon at which the truncated Laplace mechanism has the given accuracy.
+///
+/// The accuracy is monotonically decreasing in epsilon, so epsilon is found via bisection.
+fn get_truncated_laplace_epsilon(accuracy: f64, alpha: f64, delta: f64, sensitivity: f64) -> Result<f64> {
+    if accuracy <= 0. {
+        return Err("accuracy: must be positive".into())
+    }
+    if alpha <= 0. || alpha >= 1. {
+        return Err("alpha: must be within (0, 1)".into())
+    }
+
+    let (mut lower, mut upper) = (f64::EPSILON, 1.);
+    while get_truncated_laplace_accuracy(upper, delta, sensitivity, alpha) > accuracy {
+        lower = upper;
+        upper *= 2.;
+        if !upper.is_finite() {
+            return Err("accuracy: no epsilon achieves the requested accuracy".into())
+        }
+    }
+
+    // bisect on the representation of the float, which converges in at most 64 steps
+    while lower < upper && lower.to_bits() + 1 < upper.to_bits() {
+        let mid = lower + (upper - lower) / 2.;
+        if get_truncated_laplace_accuracy(mid, delta, sensitivity, alpha) > accuracy {
+            lower = mid
+        } else {
+            upper = mid
+        }
+    }
+    Ok(upper)
+}
+
+#[cfg(test)]
+mod test_truncated_laplace_mechanism {
+    use crate::components::truncated_laplace_mechanism::{get_truncated_laplace_accuracy, get_truncated_laplace_bound, get_truncated_laplace_delta};
+
+    #[test]
+    fn test_truncation_mass() {
+        for epsilon in &[0.01, 0.1, 1., 5.] {
+            for delta in &[1e-9, 1e-6, 1e-2] {
+                let bound = get_truncated_laplace_bound(*epsilon, *delta, 2.);
+                assert!(bound > 0.);
+                let recovered = get_truncated_laplace_delta(*epsilon, 2., bound);
+                assert!((recovered - delta).abs() <= delta * 1e-6,
+                        "delta {} recovered as {} at epsilon {}", delta, recovered, epsilon);
+
+                // accuracy is never looser than the truncation bound
+                assert!(get_truncated_laplace_accuracy(*epsilon, *delta, 2., 0.) <= bound * (1. + 1e-8));
+            }
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use indexmap::map::IndexMap;
-use ndarray::{ArrayViewD, Axis, stack};
+use ndarray::{ArrayD, ArrayViewD, Axis, stack};
 
 use crate::{base, Float, proto, Warnable};
-use crate::base::{AggregatorProperties, ArrayProperties, IndexKey, NodeProperties, PartitionsProperties, SensitivitySpace, Value, ValueProperties};
+use crate::base::{AggregatorProperties, ArrayProperties, GroupId, IndexKey, Jagged, Nature, NatureCategorical, NatureContinuous, NodeProperties, PartitionsProperties, SensitivitySpace, Value, ValueProperties, Vector1DNull};
 use crate::components::{Component, Sensitivity};
 use crate::errors::*;
 use crate::utilities::get_common_value;
-use crate::utilities::privacy::{get_c_stability_multiplier, get_group_id_path};
+use crate::utilities::privacy::{get_c_stability_multiplier, get_group_id_path, is_disjoint};
 
 // given a partitional indexmap, output the concatenation of all partitions
 
@@ -19,6 +19,10 @@ impl Component for proto::Union {
         node_id: u32,
     ) -> Result<Warnable<ValueProperties>> {
 
+        if self.by_column {
+            return propagate_columns(self, properties, node_id).map(Warnable::new)
+        }
+
         Ok(Warnable::new(if self.flatten {
             // all partitions must be arrays
             let array_props: Vec<&ArrayProperties> = properties.values()
@@ -87,6 +91,120 @@ impl Component for proto::Union {
     }
 }
 
+/// Properties of the column-wise concatenation of arrays from the same dataset
+fn propagate_columns(union: &proto::Union, properties: NodeProperties, node_id: u32) -> Result<ValueProperties> {
+    let array_props: Vec<&ArrayProperties> = properties.values()
+        .map(|v| v.array()).collect::<Result<_>>()?;
+    if array_props.is_empty() {
+        return Err("union must have at least one argument".into())
+    }
+
+    let releasable = get_common_value(&array_props.iter().map(|v| v.releasable).collect())
+        .ok_or_else(|| Error::from("arguments must all be releasable, or all be private"))?;
+
+    if array_props.iter().any(|v| v.sample_proportion.is_some()) {
+        return Err(Error::from("cannot currently union columns of sampled data"))
+    }
+
+    let aggregators = array_props.iter()
+        .map(|prop| prop.aggregator.clone())
+        .collect::<Vec<Option<AggregatorProperties>>>();
+    let aggregator = if releasable || aggregators.iter().all(Option::is_none) { None } else {
+        let aggregators = aggregators.into_iter().collect::<Option<Vec<AggregatorProperties>>>()
+            .ok_or_else(|| Error::from("arguments must all be aggregated, or all be unaggregated"))?;
+        Some(AggregatorProperties {
+            component: proto::component::Variant::Union(union.clone()),
+            properties: properties.clone(),
+            lipschitz_constants: stack(
+                Axis(1),
+                &aggregators.iter()
+                    .map(|v| Ok(v.lipschitz_constants.ref_array()?.ref_float()?.view()))
+                    .collect::<Result<Vec<ArrayViewD<Float>>>>()?)?.into(),
+        })
+    };
+
+    // rows of private arrays may only be aligned if they are from the same dataset
+    let common_id = get_common_value(&array_props.iter().map(|v| v.dataset_id).collect());
+    let dataset_id = if releasable {
+        common_id.and_then(|v| v)
+    } else if aggregator.is_some() {
+        // aggregates are a new dataset with one record
+        Some(node_id as i64)
+    } else {
+        Some(common_id
+            .ok_or_else(|| Error::from("private arrays must share the same dataset id"))?
+            .ok_or_else(|| Error::from("dataset_id must be known for private arrays"))?)
+    };
+
+    Ok(ValueProperties::Array(ArrayProperties {
+        num_records: Some(get_common_value(&array_props.iter()
+            .map(|v| v.num_records).collect())
+            .ok_or_else(|| Error::from("all record lengths must match"))?
+            .ok_or_else(|| Error::from("num_records must be known when unioning columns"))?),
+        num_columns: array_props.iter()
+            .try_fold(0, |sum, v| v.num_columns.map(|v| sum + v)),
+        nullity: array_props.iter().any(|v| v.nullity),
+        releasable,
+        c_stability: array_props.iter().map(|v| v.c_stability).max()
+            .ok_or_else(|| Error::from("union must have at least one argument"))?,
+        aggregator,
+        nature: concatenate_natures(array_props.iter().map(|v| v.nature.as_ref()).collect()),
+        data_type: get_common_value(&array_props.iter().map(|v| v.data_type.clone()).collect())
+            .ok_or_else(|| Error::from("data_types must be equivalent when unioning columns"))?,
+        dataset_id,
+        node_id: node_id as i64,
+        is_not_empty: array_props.iter().any(|v| v.is_not_empty),
+        dimensionality: Some(2),
+        group_id: get_common_value(&array_props.iter().map(|v| v.group_id.clone()).collect())
+            .ok_or_else(|| Error::from("group id must be shared among arguments"))?,
+        naturally_ordered: get_common_value(&array_props.iter().map(|v| v.naturally_ordered).collect())
+            .ok_or_else(|| Error::from("natural ordering must be shared among arguments"))?,
+        sample_proportion: None,
+    }))
+}
+
+/// Concatenate the per-column natures of arrays, if every array has the same kind of nature
+fn concatenate_natures(natures: Vec<Option<&Nature>>) -> Option<Nature> {
+    let natures = natures.into_iter().collect::<Option<Vec<&Nature>>>()?;
+
+    macro_rules! concatenate {
+        ($natures:expr, $variant:ident) => {
+            $natures.into_iter()
+                .map(|v| match v { $variant::Bool(v) => Some(v.clone()), _ => None })
+                .collect::<Option<Vec<_>>>().map(|v| $variant::Bool(v.concat()))
+                .or_else(|| $natures.into_iter()
+                    .map(|v| match v { $variant::Int(v) => Some(v.clone()), _ => None })
+                    .collect::<Option<Vec<_>>>().map(|v| $variant::Int(v.concat())))
+                .or_else(|| $natures.into_iter()
+                    .map(|v| match v { $variant::Float(v) => Some(v.clone()), _ => None })
+                    .collect::<Option<Vec<_>>>().map(|v| $variant::Float(v.concat())))
+                .or_else(|| $natures.into_iter()
+                    .map(|v| match v { $variant::Str(v) => Some(v.clone()), _ => None })
+                    .collect::<Option<Vec<_>>>().map(|v| $variant::Str(v.concat())))
+        }
+    }
+
+    if let Some(natures) = natures.iter()
+        .map(|v| v.continuous().ok()).collect::<Option<Vec<&NatureContinuous>>>() {
+        let lower = natures.iter().map(|v| &v.lower).collect::<Vec<&Vector1DNull>>();
+        let upper = natures.iter().map(|v| &v.upper).collect::<Vec<&Vector1DNull>>();
+        return Some(Nature::Continuous(NatureContinuous {
+            lower: concatenate!(lower.iter().copied(), Vector1DNull)?,
+            upper: concatenate!(upper.iter().copied(), Vector1DNull)?,
+        }))
+    }
+
+    if let Some(natures) = natures.iter()
+        .map(|v| v.categorical().ok()).collect::<Option<Vec<&NatureCategorical>>>() {
+        let categories = natures.iter().map(|v| &v.categories).collect::<Vec<&Jagged>>();
+        return Some(Nature::Categorical(NatureCategorical {
+            categories: concatenate!(categories.iter().copied(), Jagged)?
+        }))
+    }
+
+    None
+}
+
 impl Sensitivity for proto::Union {
     fn compute_sensitivity(
         &self,
@@ -105,14 +223,176 @@ impl Sensitivity for proto::Union {
             })
             .collect::<Result<Vec<Value>>>()?;
 
+        // each column has its own sensitivity
+        if self.by_column {
+            let column_sensitivities = partition_sensitivities.into_iter()
+                .map(|v| v.array()?.float().map(|v| match v.ndim() {
+                    0 => v.insert_axis(Axis(0)).insert_axis(Axis(0)),
+                    1 => v.insert_axis(Axis(0)),
+                    _ => v
+                }))
+                .collect::<Result<Vec<ArrayD<Float>>>>()?;
+            return Ok(stack(Axis(1), &column_sensitivities.iter()
+                .map(|v| v.view()).collect::<Vec<ArrayViewD<Float>>>())?.into())
+        }
+
         Ok(if self.flatten {
+            let partition_sensitivities = partition_sensitivities.into_iter()
+                .map(|v| v.array()?.float())
+                .collect::<Result<Vec<ArrayD<Float>>>>()?;
+
+            let disjoint = is_disjoint(properties.values()
+                .map(|v| Ok(v.array()?.group_id.clone()))
+                .collect::<Result<Vec<Vec<GroupId>>>>()?)?;
+
+            // an individual may only influence one of a set of disjoint partitions (parallel composition),
+            //     but may influence every one of a set of overlapping partitions (sequential composition)
+            let combined = partition_sensitivities.iter().skip(1)
+                .try_fold(partition_sensitivities.first()
+                    .ok_or_else(|| Error::from("union must have at least one argument"))?.clone(),
+                    |mut combined, sensitivity| {
+                        if combined.shape() != sensitivity.shape() {
+                            return Err(Error::from("sensitivities of all partitions must share the same shape"))
+                        }
+                        combined.zip_mut_with(sensitivity, |l, r| *l = if disjoint { l.max(*r) } else { *l + *r });
+                        Ok(combined)
+                    })?;
+
             stack(Axis(0), &partition_sensitivities.iter()
-                .map(|v| Ok(v.ref_array()?.ref_float()?.view()))
-                .collect::<Result<Vec<ArrayViewD<Float>>>>()?)?.into()
+                .map(|_| combined.view())
+                .collect::<Vec<ArrayViewD<Float>>>())?.into()
         } else {
             Value::Partitions(properties.keys()
                 .cloned().zip(partition_sensitivities)
                 .collect::<IndexMap<IndexKey, Value>>())
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test_union {
+    use indexmap::IndexMap;
+    use ndarray::{arr1, arr2};
+
+    use crate::base::{ArrayProperties, GroupId, IndexKey, SensitivitySpace, ValueProperties};
+    use crate::bindings::Analysis;
+    use crate::components::Sensitivity;
+    use crate::proto;
+
+    /// properties of a sum over data clamped to [0, upper], in the given part of a partition
+    fn sum_properties(analysis: &mut Analysis, upper: f64, index: Option<&str>) -> ValueProperties {
+        let data = analysis.literal()
+            .value(arr2(&[[0.], [upper]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(arr1(&[0.]).into_dyn().into()).value_public(true).build();
+        let upper = analysis.literal().value(arr1(&[upper]).into_dyn().into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let sum = analysis.sum(clamped).build();
+
+        let mut properties: ArrayProperties = analysis.properties(sum).unwrap().array().unwrap().clone();
+        properties.group_id = index.into_iter()
+            .map(|index| GroupId { partition_id: 0, index: index.into() })
+            .collect();
+        ValueProperties::Array(properties)
+    }
+
+    fn union_sensitivity(indexes: &[Option<&str>]) -> crate::errors::Result<Vec<f64>> {
+        let mut analysis = Analysis::new();
+        let properties = indexes.iter().zip(&[2., 5.])
+            .enumerate()
+            .map(|(i, (index, upper))| (IndexKey::from(i as i64), sum_properties(&mut analysis, *upper, *index)))
+            .collect::<IndexMap<IndexKey, ValueProperties>>();
+
+        Ok(proto::Union { flatten: true, by_column: false }
+            .compute_sensitivity(&analysis.privacy_definition, &properties, &SensitivitySpace::KNorm(1))?
+            .array()?.float()?.iter().copied().collect())
+    }
+
+    #[test]
+    fn test_sensitivity() {
+        // disjoint partitions compose in parallel
+        assert_eq!(union_sensitivity(&[Some("a"), Some("b")]).unwrap(), vec![5., 5.]);
+        // overlapping partitions compose sequentially
+        assert_eq!(union_sensitivity(&[Some("a"), Some("a")]).unwrap(), vec![7., 7.]);
+        // disjointness is unknown for data that is not partitioned
+        assert!(union_sensitivity(&[None, None]).is_err());
+    }
+
+    /// private, single-column float data clamped to [0, 10]
+    fn private_column(analysis: &mut Analysis) -> u32 {
+        let data = analysis.literal()
+            .value(arr2(&[[1.], [2.], [3.]]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let data = analysis.to_float(data).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let number_rows = analysis.literal().value(3.into()).value_public(true).build();
+        let number_columns = analysis.literal().value(1.into()).value_public(true).build();
+        let data = analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .lower(lower).upper(upper)
+            .build();
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+        analysis.impute(data).lower(lower).upper(upper).build()
+    }
+
+    /// add a column-wise Union of the given arrays
+    fn union_columns(analysis: &mut Analysis, arrays: &[u32]) -> u32 {
+        analysis.component_count += 1;
+        analysis.components.insert(analysis.component_count, proto::Component {
+            arguments: Some(proto::ArgumentNodeIds::new(arrays.iter().enumerate()
+                .map(|(i, id)| (IndexKey::from(i as i64), *id))
+                .collect())),
+            variant: Some(proto::component::Variant::Union(proto::Union {
+                flatten: false,
+                by_column: true
+            })),
+            omit: false,
+            submission: analysis.submission_count,
+        });
+        analysis.component_count
+    }
+
+    #[test]
+    fn test_by_column() {
+        let mut analysis = Analysis::new();
+        let data = private_column(&mut analysis);
+
+        // clamp a second column of the same dataset to a tighter range
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(5.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+
+        let union = union_columns(&mut analysis, &[data, clamped]);
+        let properties = analysis.properties(union).unwrap().array().unwrap().clone();
+        assert_eq!(properties.num_columns, Some(2));
+        assert_eq!(properties.num_records, Some(3));
+
+        let nature = properties.nature.unwrap();
+        let continuous = nature.continuous().unwrap();
+        assert_eq!(continuous.lower.float().unwrap(), &vec![Some(0.), Some(0.)]);
+        assert_eq!(continuous.upper.float().unwrap(), &vec![Some(10.), Some(5.)]);
+
+        // each column of a union of aggregates keeps its own sensitivity
+        let sum_a = analysis.sum(data).build();
+        let sum_b = analysis.sum(clamped).build();
+        let union = union_columns(&mut analysis, &[sum_a, sum_b]);
+        let aggregator = analysis.properties(union).unwrap().array().unwrap().aggregator.clone().unwrap();
+        let sensitivity = aggregator.component
+            .compute_sensitivity(&analysis.privacy_definition, &aggregator.properties, &SensitivitySpace::KNorm(1))
+            .unwrap().array().unwrap().float().unwrap();
+        assert_eq!(sensitivity.iter().copied().collect::<Vec<f64>>(), vec![10., 5.]);
+    }
+
+    #[test]
+    fn test_by_column_dataset_mismatch() {
+        let mut analysis = Analysis::new();
+        let data = private_column(&mut analysis);
+        let other = private_column(&mut analysis);
+
+        let union = union_columns(&mut analysis, &[data, other]);
+        assert!(analysis.properties(union).is_err());
+    }
+}
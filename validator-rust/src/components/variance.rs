@@ -24,6 +24,7 @@ impl Component for proto::Variance {
 
         if !data_property.releasable {
             data_property.assert_is_not_aggregated()?;
+            data_property.assert_bounds_not_degenerate().map_err(prepend("data:"))?;
         }
 
         data_property.assert_is_not_empty()?;
@@ -104,3 +105,43 @@ impl Sensitivity for proto::Variance {
         }
     }
 }
+
+#[cfg(test)]
+mod test_variance {
+    use indexmap::IndexMap;
+    use ndarray::Array2;
+
+    use crate::base::ValueProperties;
+    use crate::bindings::Analysis;
+    use crate::components::Component;
+    use crate::components::covariance::test_covariance::constant_column_properties;
+    use crate::proto;
+
+    #[test]
+    fn test_constant_column() {
+        let variance = proto::Variance { finite_sample_correction: true };
+
+        // a constant column has zero variance sensitivity, which noise cannot be scaled to
+        let error = variance.propagate_property(
+            &None, IndexMap::new(), indexmap!["data".into() => constant_column_properties()], 0).unwrap_err();
+        assert!(error.to_string().contains("bounds of column 1 are equal"), "{}", error);
+    }
+
+    #[test]
+    fn test_constant_int_column() {
+        let mut analysis = Analysis::new();
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 2), |(i, j)| if j == 0 { i as i64 } else { 1 }).into_dyn().into())
+            .value_public(true)
+            .build();
+        let mut properties = analysis.properties(data).unwrap();
+        if let ValueProperties::Array(properties) = &mut properties {
+            properties.releasable = false;
+        }
+
+        let variance = proto::Variance { finite_sample_correction: true };
+        let error = variance.propagate_property(
+            &None, IndexMap::new(), indexmap!["data".into() => properties], 0).unwrap_err();
+        assert!(error.to_string().contains("bounds of column 1 are equal"), "{}", error);
+    }
+}
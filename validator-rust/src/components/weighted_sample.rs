@@ -0,0 +1,126 @@
+use indexmap::map::IndexMap;
+
+use crate::{base, proto, Warnable};
+use crate::base::{IndexKey, Value, ValueProperties};
+use crate::components::Component;
+use crate::components::sum::check_fixed_rows;
+use crate::errors::*;
+use crate::utilities::prepend;
+
+impl Component for proto::WeightedSample {
+    fn propagate_property(
+        &self,
+        privacy_definition: &Option<proto::PrivacyDefinition>,
+        public_arguments: IndexMap<base::IndexKey, &Value>,
+        properties: base::NodeProperties,
+        node_id: u32,
+    ) -> Result<Warnable<ValueProperties>> {
+        let mut data_property = properties.get::<IndexKey>(&"data".into())
+            .ok_or("data: missing")?.array()
+            .map_err(prepend("data:"))?.clone();
+
+        if !data_property.releasable {
+            data_property.assert_is_not_aggregated()?;
+        }
+
+        // the rows drawn depend only on the weights, so a substituted record changes at most one drawn row
+        check_fixed_rows(privacy_definition, &data_property)?;
+
+        let weights = public_arguments.get::<IndexKey>(&"weights".into())
+            .ok_or_else(|| Error::from("weights: missing, must be public"))?
+            .ref_array()?.clone().cast_float()
+            .map_err(prepend("weights:"))?;
+        if weights.ndim() > 2 || (weights.ndim() == 2 && weights.shape()[1] != 1) {
+            return Err("weights: must consist of a single column".into())
+        }
+        if weights.iter().any(|weight| !weight.is_finite() || *weight < 0.) {
+            return Err("weights: must be finite and non-negative".into())
+        }
+
+        let num_records = data_property.num_records
+            .ok_or_else(|| Error::from("data: the number of records must be known when weighting"))?;
+        if weights.len() as i64 != num_records {
+            return Err("weights: must have one weight for each record".into())
+        }
+
+        let size = public_arguments.get::<IndexKey>(&"size".into())
+            .ok_or_else(|| Error::from("size: missing, must be public"))?
+            .ref_array()?.first_int()
+            .map_err(prepend("size:"))?;
+        if size < 0 {
+            return Err("size: must be non-negative".into())
+        }
+        if size as usize > weights.iter().filter(|weight| **weight > 0.).count() {
+            return Err("size: may not be greater than the number of rows with positive weight".into())
+        }
+
+        data_property.num_records = Some(size);
+        data_property.is_not_empty = size > 0;
+
+        // rows are released in the order they were drawn
+        data_property.naturally_ordered = false;
+
+        // the sample is not row-aligned with other data derived from the same source
+        data_property.dataset_id = Some(node_id as i64);
+
+        Ok(ValueProperties::Array(data_property).into())
+    }
+}
+
+#[cfg(test)]
+mod test_weighted_sample {
+    use ndarray::{arr1, Array2};
+
+    use crate::bindings::Analysis;
+    use crate::proto::privacy_definition::Neighboring;
+
+    fn analysis_weighted_sample(neighboring: Neighboring, weights: Vec<f64>, size: i64) -> (Analysis, u32) {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.neighboring = neighboring as i32;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((4, 1), |(i, _)| i as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let weights = analysis.literal().value(arr1(&weights).into_dyn().into()).value_public(true).build();
+        let size = analysis.literal().value(size.into()).value_public(true).build();
+        let sample = analysis.weighted_sample(data, weights, size).build();
+        (analysis, sample)
+    }
+
+    #[test]
+    fn test_weighted_sample() {
+        let (analysis, sample) = analysis_weighted_sample(
+            Neighboring::Substitute, vec![0., 1., 3., 0.5], 2);
+        let properties = analysis.properties(sample).unwrap();
+        let properties = properties.array().unwrap();
+        assert_eq!(properties.num_records, Some(2));
+        assert!(!properties.naturally_ordered);
+        assert_eq!(properties.c_stability, 1);
+    }
+
+    #[test]
+    fn test_weighted_sample_add_remove() {
+        // adding a record shifts which weight applies to every later row
+        let (analysis, sample) = analysis_weighted_sample(
+            Neighboring::AddRemove, vec![0., 1., 3., 0.5], 2);
+        assert!(analysis.properties(sample).is_err());
+    }
+
+    #[test]
+    fn test_weighted_sample_invalid() {
+        // too few rows with positive weight
+        let (analysis, sample) = analysis_weighted_sample(
+            Neighboring::Substitute, vec![0., 1., 3., 0.], 3);
+        assert!(analysis.properties(sample).is_err());
+
+        // one weight is missing
+        let (analysis, sample) = analysis_weighted_sample(
+            Neighboring::Substitute, vec![1., 1., 1.], 2);
+        assert!(analysis.properties(sample).is_err());
+
+        // negative weight
+        let (analysis, sample) = analysis_weighted_sample(
+            Neighboring::Substitute, vec![1., -1., 1., 1.], 2);
+        assert!(analysis.properties(sample).is_err());
+    }
+}
@@ -24,6 +24,7 @@ use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 
 use indexmap::map::IndexMap;
+use itertools::Itertools;
 
 #[doc(hidden)]
 pub use errors::*;
@@ -32,7 +33,8 @@ use crate::base::{IndexKey, Release, Value, ValueProperties};
 // import all trait implementations
 use crate::components::*;
 use crate::utilities::get_public_arguments;
-use crate::utilities::privacy::compute_graph_privacy_usage;
+use crate::utilities::serial::serialize_index_key;
+use crate::utilities::privacy::{compute_graph_privacy_usage, get_node_privacy_usages};
 
 #[doc(hidden)]
 pub mod errors {
@@ -85,6 +87,7 @@ pub type Integer = i64;
 ///
 /// Checks that the graph is a DAG.
 /// Checks that static properties are met on all components.
+/// Checks that the privacy usage of every mechanism downstream of a partition can be composed in parallel.
 ///
 /// Useful for static validation of an analysis.
 /// Since some components require public arguments, mechanisms that depend on other mechanisms cannot be verified until the components they depend on have been validated.
@@ -96,12 +99,21 @@ pub fn validate_analysis(
     mut computation_graph: HashMap<u32, proto::Component>,
     mut release: base::Release
 ) -> Result<()> {
-    utilities::propagate_properties(
+    let (properties, _) = utilities::propagate_properties(
         &privacy_definition,
         &mut computation_graph,
         &mut release,
         None,
         false)?;
+
+    if let Some(privacy_definition) = privacy_definition.as_ref() {
+        let privacy_usages = utilities::privacy::get_node_privacy_usages(
+            &computation_graph, privacy_definition, &properties, &release, computation_graph.keys())?;
+        if let Some(error) = utilities::privacy::check_parallel_composition(
+            &computation_graph, &properties, &privacy_usages).into_iter().next() {
+            return Err(error)
+        }
+    }
     Ok(())
 }
 
@@ -110,6 +122,7 @@ pub fn validate_analysis(
 ///
 /// The privacy usage is sum of the privacy usages for each node.
 /// The Release's actual privacy usage, if defined, takes priority over the maximum allowable privacy usage defined in the Analysis.
+/// Fails if a node uses privacy on both partitioned data and private data from outside the partition, as its usage would be under-counted.
 pub fn compute_privacy_usage(
     privacy_definition: proto::PrivacyDefinition,
     mut computation_graph: HashMap<u32, proto::Component>,
@@ -130,68 +143,135 @@ pub fn compute_privacy_usage(
 }
 
 
+/// Compute the actual privacy usage of each released node.
+///
+/// The usage a mechanism reports in the release is the usage of the mechanism itself,
+/// which may differ from the usage charged to the analysis, for example when privacy is amplified by sampling.
+/// The usages are keyed by node id.
+/// Usages of nodes downstream of a partition compose in parallel over its categories,
+/// so the privacy usage of the analysis, from `compute_privacy_usage`, is not in general their sum.
+pub fn compute_node_privacy_usages(
+    privacy_definition: proto::PrivacyDefinition,
+    mut computation_graph: HashMap<u32, proto::Component>,
+    mut release: base::Release
+) -> Result<HashMap<u32, Vec<proto::PrivacyUsage>>> {
+
+    let properties = utilities::propagate_properties(
+        &Some(privacy_definition.clone()),
+        &mut computation_graph,
+        &mut release, None, false)?.0;
+
+    let released_ids = release.iter()
+        .filter(|(node_id, release_node)| release_node.privacy_usages.is_some()
+            && computation_graph.contains_key(node_id))
+        .map(|(node_id, _)| *node_id)
+        .collect::<Vec<u32>>();
+
+    get_node_privacy_usages(
+        &computation_graph, &privacy_definition, &properties, &release, released_ids.iter())
+}
+
+
 /// Generate a json string with a summary/report of the Analysis and Release
 pub fn generate_report(
     privacy_definition: proto::PrivacyDefinition,
     computation_graph: HashMap<u32, proto::Component>,
-    mut release: base::Release
+    release: base::Release
 ) -> Result<String> {
+    summarize_releases(privacy_definition, computation_graph, release, None)
+}
 
-    let graph_properties = utilities::propagate_properties(
-        &Some(privacy_definition),
-        &mut computation_graph.clone(),
-        &mut release, None, false)?.0;
 
-    // variable names
-    let mut nodes_varnames: HashMap<u32, Vec<IndexKey>> = HashMap::new();
-
-    utilities::get_traversal(&computation_graph)?.iter().for_each(|node_id| {
-        let component: proto::Component = computation_graph.get(&node_id).unwrap().to_owned();
-        let public_arguments = match utilities::get_public_arguments(&component, &release) {
-            Ok(v) => v, Err(_) => return
-        };
-
-        // variable names for argument nodes
-        let mut arguments_vars: IndexMap<base::IndexKey, Vec<IndexKey>> = IndexMap::new();
-
-        // iterate through argument nodes
-        for (field_id, field) in &component.arguments() {
-            // get variable names corresponding to that argument
-            if let Some(arg_vars) = nodes_varnames.get(field) {
-                arguments_vars.insert(field_id.clone(), arg_vars.clone());
-            }
-        }
+/// Generate a json string with a summary/report of the Analysis and Release, including confidence intervals
+///
+/// Releases of mechanisms that can estimate their accuracy
+/// are summarized with their accuracy and a two-sided confidence interval at `alpha`.
+pub fn generate_report_with_intervals(
+    privacy_definition: proto::PrivacyDefinition,
+    computation_graph: HashMap<u32, proto::Component>,
+    release: base::Release,
+    alpha: f64
+) -> Result<String> {
+    summarize_releases(privacy_definition, computation_graph, release, Some(alpha))
+}
+
+
+fn summarize_releases(
+    privacy_definition: proto::PrivacyDefinition,
+    computation_graph: HashMap<u32, proto::Component>,
+    mut release: base::Release,
+    alpha: Option<f64>
+) -> Result<String> {
 
-        // get variable names for this node
-        let node_vars = component.get_names(
-            public_arguments,
-            arguments_vars,
-            release.get(node_id).map(|v| v.value.clone()).as_ref());
+    let privacy_definition = Some(privacy_definition);
 
-        // update names in indexmap
-        node_vars.map(|v| nodes_varnames.insert(*node_id, v)).ok();
+    // mechanisms take the node id of the component they were expanded from
+    let mut expanded_graph = computation_graph.clone();
+    let graph_properties = utilities::propagate_properties(
+        &privacy_definition,
+        &mut expanded_graph,
+        &mut release, None, false)?.0;
 
-    });
+    // variable names
+    let nodes_varnames = utilities::get_variable_names(&computation_graph, &release)?;
 
     // generate summaries for any component that has a release, and has summarize implemented on it
+    //     summaries are ordered by node id, so that reports of the same analysis are identical across runs
     let release_schemas = computation_graph.iter()
+        .sorted_by_key(|(node_id, _)| **node_id)
         .map(|(node_id, component)| {
             let public_arguments = utilities::get_public_arguments(&component, &release)?;
             let input_properties = utilities::get_input_properties(&component, &graph_properties)?;
             let variable_names = nodes_varnames.get(&node_id);
             // ignore nodes without released values
             let node_release = match release.get(node_id) {
-                Some(node_release) => node_release.value.clone(),
+                Some(node_release) => node_release,
                 None => return Ok(None)
             };
-            component.summarize(
+
+            // postprocessing consumes only releasable data, and does not invoke a mechanism
+            let postprocess = !input_properties.is_empty()
+                && input_properties.values().all(ValueProperties::is_public)
+                && node_release.privacy_usages.is_none();
+
+            let mut summaries = match component.summarize(
+                &privacy_definition,
                 *node_id,
                 &component,
                 public_arguments,
                 input_properties,
-                &node_release,
+                &node_release.value,
                 variable_names,
-            )
+            )? {
+                Some(summaries) => summaries,
+                None => return Ok(None)
+            };
+            summaries.iter_mut().for_each(|summary| summary.postprocess = postprocess);
+            let node_release = node_release.value.clone();
+
+            // summaries that estimate their own accuracy are re-estimated from the expanded mechanism,
+            //     whose privacy usage accounts for the group size and stability of the data
+            let alpha = alpha.or_else(|| summaries.iter()
+                .find_map(|summary| summary.accuracy.as_ref().map(|accuracy| accuracy.alpha)));
+            if let (Some(alpha), Some(mechanism)) = (alpha, expanded_graph.get(node_id)) {
+                let accuracies = mechanism.privacy_usage_to_accuracy(
+                    get_public_arguments(mechanism, &release)?, alpha)
+                    .chain_err(|| format!("at node_id {:?}", node_id))?;
+
+                // summaries are made per column, and accuracies are estimated per column
+                if let Some(accuracies) = accuracies.filter(|accuracies| accuracies.len() == summaries.len()) {
+                    let intervals = utilities::json::accuracies_to_intervals(&node_release, &accuracies)?;
+                    summaries.iter_mut().zip(accuracies.into_iter().zip(intervals))
+                        .for_each(|(summary, (accuracy, interval))| {
+                            summary.accuracy = Some(utilities::json::Accuracy {
+                                accuracy_value: accuracy.value,
+                                alpha: accuracy.alpha,
+                            });
+                            summary.interval = Some(interval);
+                        });
+                }
+            }
+            Ok(Some(summaries))
         })
         .collect::<Result<Vec<Option<Vec<utilities::json::JSONRelease>>>>>()?.into_iter()
         .filter_map(|v| v).flat_map(|v| v)
@@ -303,6 +383,40 @@ pub fn privacy_usage_to_accuracy(
     })
 }
 
+/// Estimate the accuracy of the release of every mechanism in the analysis, based on the privacy usages of the mechanisms.
+///
+/// Each node that releases an estimate is reported, alongside the names of the variables it releases, if they are known.
+pub fn analysis_accuracies(
+    privacy_definition: proto::PrivacyDefinition,
+    mut computation_graph: HashMap<u32, proto::Component>,
+    mut release: base::Release,
+    alpha: f64
+) -> Result<proto::AnalysisAccuracies> {
+    let nodes_varnames = utilities::get_variable_names(&computation_graph, &release)?;
+
+    utilities::propagate_properties(
+        &Some(privacy_definition),
+        &mut computation_graph,
+        &mut release, None, false)?;
+
+    // mechanisms take the node id of the component they were expanded from
+    Ok(proto::AnalysisAccuracies {
+        values: computation_graph.iter()
+            .map(|(node_id, component)| Ok(component.privacy_usage_to_accuracy(
+                get_public_arguments(component, &release)?,
+                alpha,
+            ).chain_err(|| format!("at node_id {:?}", node_id))?
+                .map(|accuracies| (*node_id, proto::NodeAccuracies {
+                    accuracies: Some(proto::Accuracies { values: accuracies }),
+                    variables: nodes_varnames.get(node_id).cloned().unwrap_or_else(Vec::new)
+                        .into_iter().map(serialize_index_key).collect(),
+                }))))
+            .collect::<Result<Vec<Option<(u32, proto::NodeAccuracies)>>>>()?
+            .into_iter().flatten()
+            .collect()
+    })
+}
+
 /// Expand a component that may be representable as smaller components, and propagate its properties.
 ///
 /// This is function may be called interactively from the runtime as the runtime executes the computational graph, to allow for dynamic graph validation.
@@ -315,19 +429,7 @@ pub fn expand_component(
     component_id: u32,
     maximum_id: u32,
 ) -> Result<base::ComponentExpansion> {
-    let argument_ids = component.arguments();
-
-    for (k, v) in &public_arguments {
-        if !v.public {
-            return Err("private data should not be sent to the validator".into())
-        }
-        let node_id = argument_ids.get(k)
-            .ok_or_else(|| Error::from(format!("unrecognized argument: {:?}", k)))?;
-
-        properties.insert(
-            k.clone(),
-            utilities::inference::infer_property(&v.value, properties.get(k), *node_id)?);
-    }
+    infer_public_properties(&component, &mut properties, &public_arguments)?;
 
     let public_values = public_arguments.iter()
         .map(|(name, release_node)| (name.clone(), &release_node.value))
@@ -369,6 +471,54 @@ pub fn expand_component(
     Ok(result)
 }
 
+/// Propagate properties through a single component, in isolation from the rest of the graph.
+///
+/// The properties of the arguments are provided directly, so a component may be validated as it is built,
+/// before the full computation graph exists.
+/// Components that are only defined via expansion have no propagation of their own, and cannot be validated this way.
+pub fn validate_component(
+    component: proto::Component,
+    mut properties: IndexMap<IndexKey, ValueProperties>,
+    public_arguments: IndexMap<IndexKey, base::ReleaseNode>,
+    privacy_definition: Option<proto::PrivacyDefinition>,
+    component_id: u32,
+) -> Result<(ValueProperties, Vec<Error>)> {
+    infer_public_properties(&component, &mut properties, &public_arguments)?;
+
+    let public_values = public_arguments.iter()
+        .map(|(name, release_node)| (name.clone(), &release_node.value))
+        .collect::<IndexMap<IndexKey, &Value>>();
+
+    let Warnable(propagated_property, warnings) = component
+        .propagate_property(&privacy_definition, public_values, properties, component_id)
+        .chain_err(|| format!("at node_id {:?}", component_id))?;
+
+    Ok((propagated_property, warnings.into_iter()
+        .map(|err| err.chain_err(|| format!("at node_id {:?}", component_id)))
+        .collect()))
+}
+
+/// Overwrite the properties of public arguments with properties inferred from their values.
+fn infer_public_properties(
+    component: &proto::Component,
+    properties: &mut IndexMap<IndexKey, ValueProperties>,
+    public_arguments: &IndexMap<IndexKey, base::ReleaseNode>,
+) -> Result<()> {
+    let argument_ids = component.arguments();
+
+    for (k, v) in public_arguments {
+        if !v.public {
+            return Err("private data should not be sent to the validator".into())
+        }
+        let node_id = argument_ids.get(k)
+            .ok_or_else(|| Error::from(format!("unrecognized argument: {:?}", k)))?;
+
+        let property = utilities::inference::infer_property(&v.value, properties.get(k), *node_id)?;
+        properties.insert(k.clone(), property);
+    }
+    Ok(())
+}
+
 
 /// Retrieve the static properties from every reachable node on the graph.
 pub fn get_properties(
@@ -402,11 +552,23 @@ pub fn get_properties(
     // don't return all properties- only those in the original graph
     let keep_ids = HashSet::<u32>::from_iter(computation_graph.keys().cloned());
 
-    let (mut properties, warnings) = utilities::propagate_properties(
+    let (mut properties, mut warnings) = utilities::propagate_properties(
         &privacy_definition, &mut computation_graph,
         &mut release, None, true,
     )?;
 
+    // privacy usages are only known once every node has propagated
+    if let Some(privacy_definition) = privacy_definition.as_ref().filter(|_| warnings.is_empty()) {
+        let privacy_usages = utilities::privacy::get_node_privacy_usages(
+            &computation_graph, privacy_definition, &properties, &release, computation_graph.keys())?;
+        // reject the graph before any of it is released, as the accountant could not account for its usage
+        if let Some(error) = utilities::privacy::check_parallel_composition(
+            &computation_graph, &properties, &privacy_usages).into_iter().next() {
+            return Err(error)
+        }
+        warnings.extend(utilities::privacy::check_subsample_composition(&computation_graph, &privacy_usages));
+    }
+
     properties.retain(|node_id, _| keep_ids.contains(node_id));
     Ok((properties, warnings))
 }
@@ -0,0 +1,160 @@
+//! Utilities for parsing and binning timestamps.
+//!
+//! Timestamps are represented as integer seconds since the unix epoch (1970-01-01T00:00:00Z).
+//! Time zones are always explicit, and are given as fixed offsets from UTC.
+
+use chrono::{Datelike, DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone};
+
+use crate::errors::*;
+use crate::Integer;
+
+/// Parse a time zone into a fixed offset from UTC.
+///
+/// # Arguments
+/// * `timezone` - either "UTC", "Z", or an offset of the form "+HH:MM" or "-HH:MM"
+///
+/// # Example
+/// ```
+/// use smartnoise_validator::utilities::datetime::parse_timezone;
+/// assert_eq!(parse_timezone("-05:00").unwrap().local_minus_utc(), -5 * 3600);
+/// assert_eq!(parse_timezone("UTC").unwrap().local_minus_utc(), 0);
+/// assert!(parse_timezone("America/New_York").is_err());
+/// ```
+pub fn parse_timezone(timezone: &str) -> Result<FixedOffset> {
+    let timezone = timezone.trim();
+    if timezone.eq_ignore_ascii_case("utc") || timezone == "Z" {
+        return Ok(FixedOffset::east_opt(0).unwrap())
+    }
+
+    let error = || Error::from(format!(
+        "timezone: {:?} is not recognized. Must be \"UTC\" or a fixed offset of the form \"+HH:MM\"", timezone));
+
+    let (sign, offset) = match timezone.chars().next() {
+        Some('+') => (1, &timezone[1..]),
+        Some('-') => (-1, &timezone[1..]),
+        _ => return Err(error())
+    };
+    let mut parts = offset.splitn(2, ':');
+    let hours = parts.next().and_then(|v| v.parse::<i32>().ok()).ok_or_else(error)?;
+    let minutes = match parts.next() {
+        Some(minutes) => minutes.parse::<i32>().map_err(|_| error())?,
+        None => 0
+    };
+    if !(0..24).contains(&hours) || !(0..60).contains(&minutes) {
+        return Err(error())
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(error)
+}
+
+/// Parse a string into seconds since the unix epoch.
+///
+/// If the format contains an offset, then the offset in the string is used.
+/// Otherwise the string is interpreted as a local time in `timezone`.
+/// Formats that only specify a date are interpreted as midnight.
+///
+/// # Arguments
+/// * `value` - string to parse
+/// * `format` - strftime-style format string, for example "%Y-%m-%dT%H:%M:%S"
+/// * `timezone` - offset of local times in the string
+///
+/// # Return
+/// Seconds since the unix epoch, or None if the string could not be parsed.
+///
+/// # Example
+/// ```
+/// use smartnoise_validator::utilities::datetime::{parse_datetime, parse_timezone};
+/// let utc = parse_timezone("UTC").unwrap();
+/// assert_eq!(parse_datetime("1970-01-02", "%Y-%m-%d", &utc), Some(86400));
+/// assert_eq!(parse_datetime("1970-01-01T01:00:00+01:00", "%Y-%m-%dT%H:%M:%S%:z", &utc), Some(0));
+/// assert_eq!(parse_datetime("yesterday", "%Y-%m-%d", &utc), None);
+/// ```
+pub fn parse_datetime(value: &str, format: &str, timezone: &FixedOffset) -> Option<Integer> {
+    if let Ok(datetime) = DateTime::parse_from_str(value, format) {
+        return Some(datetime.timestamp())
+    }
+    let naive = NaiveDateTime::parse_from_str(value, format).ok()
+        .or_else(|| NaiveDate::parse_from_str(value, format).ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0)))?;
+    timezone.from_local_datetime(&naive).single().map(|datetime| datetime.timestamp())
+}
+
+/// Check that a unit of time is supported for binning.
+pub fn check_unit(unit: &str) -> Result<()> {
+    match unit {
+        "day" | "week" | "month" | "year" => Ok(()),
+        _ => Err(format!("unit: {:?} is not recognized. Must be one of \"day\", \"week\", \"month\" or \"year\"", unit).into())
+    }
+}
+
+/// Round a timestamp down to the start of the day, week, month or year containing it.
+///
+/// Boundaries are midnight in the local time of `timezone`, and weeks start on Monday.
+///
+/// # Arguments
+/// * `timestamp` - seconds since the unix epoch
+/// * `unit` - one of "day", "week", "month" or "year"
+/// * `timezone` - offset of the local time that boundaries are aligned to
+///
+/// # Return
+/// Seconds since the unix epoch at the start of the bin.
+///
+/// # Example
+/// ```
+/// use smartnoise_validator::utilities::datetime::{floor_datetime, parse_timezone};
+/// let utc = parse_timezone("UTC").unwrap();
+/// // 1970-01-02T12:00:00Z
+/// assert_eq!(floor_datetime(129600, "day", &utc).unwrap(), 86400);
+/// // in UTC-05:00, the day started at 1970-01-02T05:00:00Z
+/// assert_eq!(floor_datetime(129600, "day", &parse_timezone("-05:00").unwrap()).unwrap(), 104400);
+/// ```
+pub fn floor_datetime(timestamp: Integer, unit: &str, timezone: &FixedOffset) -> Result<Integer> {
+    check_unit(unit)?;
+    let date = timezone.timestamp_opt(timestamp, 0).single()
+        .ok_or_else(|| Error::from(format!("timestamp {} is out of range", timestamp)))?
+        .naive_local().date();
+
+    let date = match unit {
+        "day" => Some(date),
+        "week" => date.checked_sub_signed(Duration::days(date.weekday().num_days_from_monday() as i64)),
+        "month" => NaiveDate::from_ymd_opt(date.year(), date.month(), 1),
+        "year" => NaiveDate::from_ymd_opt(date.year(), 1, 1),
+        _ => unreachable!()
+    }.ok_or_else(|| Error::from(format!("timestamp {} is out of range", timestamp)))?;
+
+    timezone.from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap()).single()
+        .map(|datetime| datetime.timestamp())
+        .ok_or_else(|| Error::from(format!("timestamp {} is out of range", timestamp)))
+}
+
+#[cfg(test)]
+mod test_datetime {
+    use crate::utilities::datetime::{floor_datetime, parse_datetime, parse_timezone};
+
+    #[test]
+    fn test_parse_iso_8601() {
+        let utc = parse_timezone("UTC").unwrap();
+        let format = "%Y-%m-%dT%H:%M:%S";
+        // 2020-03-15T13:45:30Z
+        assert_eq!(parse_datetime("2020-03-15T13:45:30", format, &utc), Some(1584279930));
+        // local times are interpreted in the given time zone
+        assert_eq!(parse_datetime("2020-03-15T13:45:30", format, &parse_timezone("+02:00").unwrap()),
+                   Some(1584279930 - 2 * 3600));
+        // offsets in the string take precedence over the time zone
+        assert_eq!(parse_datetime("2020-03-15T13:45:30-01:00", "%Y-%m-%dT%H:%M:%S%:z", &utc),
+                   Some(1584279930 + 3600));
+        assert_eq!(parse_datetime("2020-03-15 13:45", format, &utc), None);
+    }
+
+    #[test]
+    fn test_floor() {
+        let utc = parse_timezone("UTC").unwrap();
+        // Sunday 2020-03-15T13:45:30Z
+        let timestamp = 1584279930;
+        assert_eq!(floor_datetime(timestamp, "day", &utc).unwrap(), 1584230400);
+        // Monday 2020-03-09
+        assert_eq!(floor_datetime(timestamp, "week", &utc).unwrap(), 1583712000);
+        assert_eq!(floor_datetime(timestamp, "month", &utc).unwrap(), 1583020800);
+        assert_eq!(floor_datetime(timestamp, "year", &utc).unwrap(), 1577836800);
+        assert!(floor_datetime(timestamp, "fortnight", &utc).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use crate::errors::*;
+use crate::Float;
+
+/// A parsed arithmetic expression over named variables.
+///
+/// The grammar is limited to numbers, variables, parentheses,
+/// unary negation and the binary operators `+`, `-`, `*` and `/`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+    Number(Float),
+    Variable(String),
+    Negate(Box<Expression>),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(Float),
+    Variable(String),
+    Operator(Operator),
+    Open,
+    Close,
+}
+
+impl Expression {
+    /// Parse an expression, like `(a - b) / c`.
+    pub fn parse(text: &str) -> Result<Expression> {
+        let tokens = tokenize(text)?;
+        // the depth of the expression tree is at most the number of tokens
+        if tokens.len() > MAX_TOKENS {
+            return Err(format!("expression: may not contain more than {} tokens", MAX_TOKENS).into())
+        }
+        let mut position = 0;
+        let expression = parse_sum(&tokens, &mut position, 0)?;
+        if position != tokens.len() {
+            return Err(format!("expression: unexpected token {:?}", tokens[position]).into())
+        }
+        Ok(expression)
+    }
+
+    /// Names of the variables referenced in the expression, in order of first appearance.
+    pub fn variables(&self) -> Vec<String> {
+        fn collect(expression: &Expression, names: &mut Vec<String>) {
+            match expression {
+                Expression::Number(_) => (),
+                Expression::Variable(name) => if !names.contains(name) { names.push(name.clone()) },
+                Expression::Negate(inner) => collect(inner, names),
+                Expression::Binary(_, left, right) => {
+                    collect(left, names);
+                    collect(right, names);
+                }
+            }
+        }
+        let mut names = Vec::new();
+        collect(self, &mut names);
+        names
+    }
+
+    /// Evaluate the expression over any type that numbers and variables may be lifted into.
+    ///
+    /// # Arguments
+    /// * `number` - lift a literal number
+    /// * `variable` - look up a variable by name
+    /// * `negate` - negate a value
+    /// * `binary` - apply a binary operator to two values
+    pub fn evaluate<T>(
+        &self,
+        number: &dyn Fn(Float) -> Result<T>,
+        variable: &dyn Fn(&str) -> Result<T>,
+        negate: &dyn Fn(T) -> Result<T>,
+        binary: &dyn Fn(Operator, T, T) -> Result<T>,
+    ) -> Result<T> {
+        match self {
+            Expression::Number(value) => number(*value),
+            Expression::Variable(name) => variable(name),
+            Expression::Negate(inner) => negate(inner.evaluate(number, variable, negate, binary)?),
+            Expression::Binary(operator, left, right) => binary(
+                *operator,
+                left.evaluate(number, variable, negate, binary)?,
+                right.evaluate(number, variable, negate, binary)?),
+        }
+    }
+}
+
+impl Operator {
+    pub fn apply(self, left: Float, right: Float) -> Float {
+        match self {
+            Operator::Add => left + right,
+            Operator::Subtract => left - right,
+            Operator::Multiply => left * right,
+            Operator::Divide => left / right,
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut characters = text.chars().peekable();
+
+    while let Some(&character) = characters.peek() {
+        match character {
+            c if c.is_whitespace() => { characters.next(); }
+            '+' | '-' | '*' | '/' => {
+                characters.next();
+                tokens.push(Token::Operator(match character {
+                    '+' => Operator::Add,
+                    '-' => Operator::Subtract,
+                    '*' => Operator::Multiply,
+                    _ => Operator::Divide
+                }));
+            }
+            '(' => { characters.next(); tokens.push(Token::Open); }
+            ')' => { characters.next(); tokens.push(Token::Close); }
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut literal = String::new();
+                while let Some(&c) = characters.peek() {
+                    if !(c.is_ascii_digit() || c == '.') { break }
+                    literal.push(c);
+                    characters.next();
+                }
+                tokens.push(Token::Number(literal.parse::<Float>()
+                    .map_err(|_| Error::from(format!("expression: invalid number `{}`", literal)))?));
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut name = String::new();
+                while let Some(&c) = characters.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') { break }
+                    name.push(c);
+                    characters.next();
+                }
+                tokens.push(Token::Variable(name));
+            }
+            c => return Err(format!("expression: unexpected character `{}`", c).into())
+        }
+    }
+    Ok(tokens)
+}
+
+/// Expressions with more tokens than this are rejected, bounding the depth of the expression tree
+const MAX_TOKENS: usize = 1000;
+
+/// Parentheses and negations may not be nested deeper than this, bounding the recursion of the parser
+const MAX_NESTING: usize = 100;
+
+// sum := product (('+' | '-') product)*
+fn parse_sum(tokens: &[Token], position: &mut usize, depth: usize) -> Result<Expression> {
+    let mut expression = parse_product(tokens, position, depth)?;
+    while let Some(Token::Operator(operator @ Operator::Add))
+        | Some(Token::Operator(operator @ Operator::Subtract)) = tokens.get(*position) {
+        *position += 1;
+        expression = Expression::Binary(
+            *operator, Box::new(expression), Box::new(parse_product(tokens, position, depth)?));
+    }
+    Ok(expression)
+}
+
+// product := unary (('*' | '/') unary)*
+fn parse_product(tokens: &[Token], position: &mut usize, depth: usize) -> Result<Expression> {
+    let mut expression = parse_unary(tokens, position, depth)?;
+    while let Some(Token::Operator(operator @ Operator::Multiply))
+        | Some(Token::Operator(operator @ Operator::Divide)) = tokens.get(*position) {
+        *position += 1;
+        expression = Expression::Binary(
+            *operator, Box::new(expression), Box::new(parse_unary(tokens, position, depth)?));
+    }
+    Ok(expression)
+}
+
+// unary := '-' unary | atom
+fn parse_unary(tokens: &[Token], position: &mut usize, depth: usize) -> Result<Expression> {
+    if depth > MAX_NESTING {
+        return Err(format!("expression: may not be nested more than {} levels deep", MAX_NESTING).into())
+    }
+    if let Some(Token::Operator(Operator::Subtract)) = tokens.get(*position) {
+        *position += 1;
+        return Ok(Expression::Negate(Box::new(parse_unary(tokens, position, depth + 1)?)))
+    }
+    parse_atom(tokens, position, depth)
+}
+
+// atom := number | variable | '(' sum ')'
+fn parse_atom(tokens: &[Token], position: &mut usize, depth: usize) -> Result<Expression> {
+    let token = tokens.get(*position)
+        .ok_or_else(|| Error::from("expression: unexpected end of expression"))?;
+    *position += 1;
+    match token {
+        Token::Number(value) => Ok(Expression::Number(*value)),
+        Token::Variable(name) => Ok(Expression::Variable(name.clone())),
+        Token::Open => {
+            let expression = parse_sum(tokens, position, depth + 1)?;
+            match tokens.get(*position) {
+                Some(Token::Close) => {
+                    *position += 1;
+                    Ok(expression)
+                }
+                _ => Err("expression: missing closing parenthesis".into())
+            }
+        }
+        token => Err(format!("expression: unexpected token {:?}", token).into())
+    }
+}
+
+#[cfg(test)]
+mod test_expression {
+    use crate::utilities::expression::{Expression, Operator};
+
+    fn evaluate(text: &str) -> f64 {
+        Expression::parse(text).unwrap().evaluate(
+            &|v| Ok(v),
+            &|name| Ok(match name { "a" => 7., "b" => 3., "c" => 2., _ => panic!("unknown variable") }),
+            &|v| Ok(-v),
+            &|operator: Operator, l, r| Ok(operator.apply(l, r))).unwrap()
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(evaluate("(a - b) / c"), 2.);
+        assert_eq!(evaluate("a - b / c"), 5.5);
+        assert_eq!(evaluate("a - b - c"), 2.);
+        assert_eq!(evaluate("-a * -(b + 1.5)"), 31.5);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Expression::parse("(a - b").is_err());
+        assert!(Expression::parse("a b").is_err());
+        assert!(Expression::parse("a ^ 2").is_err());
+        assert!(Expression::parse("").is_err());
+        assert_eq!(Expression::parse("b * a + b").unwrap().variables(), vec!["b".to_string(), "a".to_string()]);
+    }
+
+    #[test]
+    fn test_depth() {
+        // deeply nested expressions are rejected instead of overflowing the stack
+        assert!(Expression::parse(&"-".repeat(100_000)).is_err());
+        assert!(Expression::parse(&format!("{}a{}", "(".repeat(100_000), ")".repeat(100_000))).is_err());
+        assert!(Expression::parse(&format!("{}a", "a + ".repeat(100_000))).is_err());
+
+        assert_eq!(evaluate(&format!("{}a", "-".repeat(100))), 7.);
+        assert_eq!(evaluate(&format!("{}a{}", "(".repeat(100), ")".repeat(100))), 7.);
+    }
+}
@@ -16,7 +16,18 @@ use crate::base::{Array, Value, Jagged, Nature, Vector1DNull, NatureContinuous,
 use crate::utilities::deduplicate;
 use indexmap::map::IndexMap;
 
+/// Properties of single precision data are inferred in double precision.
+fn to_double(value: &Value) -> Option<Value> {
+    match value {
+        Value::Array(Array::F32(array)) => Some(Value::Array(Array::F32(array.clone()).to_double())),
+        _ => None
+    }
+}
+
 pub fn infer_lower(value: &Value) -> Result<Vector1DNull> {
+    if let Some(value) = to_double(value) {
+        return infer_lower(&value)
+    }
     Ok(match value {
         Value::Array(array) => {
             match array.shape().len() as i64 {
@@ -70,6 +81,9 @@ pub fn infer_lower(value: &Value) -> Result<Vector1DNull> {
 }
 
 pub fn infer_upper(value: &Value) -> Result<Vector1DNull> {
+    if let Some(value) = to_double(value) {
+        return infer_upper(&value)
+    }
     Ok(match value {
         Value::Array(array) => {
             match array.shape().len() as i64 {
@@ -123,6 +137,9 @@ pub fn infer_upper(value: &Value) -> Result<Vector1DNull> {
 }
 
 pub fn infer_categories(value: &Value) -> Result<Jagged> {
+    if let Some(value) = to_double(value) {
+        return infer_categories(&value)
+    }
     match value {
         Value::Array(array) => match array {
             Array::Bool(array) =>
@@ -141,6 +158,7 @@ pub fn infer_categories(value: &Value) -> Result<Jagged> {
                 Jagged::Str(array.gencolumns().into_iter().map(|col|
                     Ok(col.into_dyn().into_dimensionality::<Ix1>()?.to_vec()))
                     .collect::<Result<Vec<_>>>()?),
+            Array::F32(_) => unreachable!(),
         }
         Value::Jagged(jagged) => match jagged {
             Jagged::Bool(array) =>
@@ -159,6 +177,9 @@ pub fn infer_categories(value: &Value) -> Result<Jagged> {
 pub fn infer_nature(
     value: &Value, prior_property: Option<&ValueProperties>
 ) -> Result<Option<Nature>> {
+    if let Some(value) = to_double(value) {
+        return infer_nature(&value, prior_property)
+    }
     Ok(match value {
         Value::Array(array) => match array {
             Array::Float(array) => Some(Nature::Continuous(NatureContinuous {
@@ -191,6 +212,7 @@ pub fn infer_nature(
             Array::Str(array) => Some(Nature::Categorical(NatureCategorical {
                 categories: infer_categories(&array.clone().into())?,
             })),
+            Array::F32(_) => unreachable!(),
         },
         Value::Jagged(jagged) => match jagged {
             Jagged::Float(_) => None,
@@ -203,6 +225,9 @@ pub fn infer_nature(
 }
 
 pub fn infer_nullity(value: &Value) -> Result<bool> {
+    if let Some(value) = to_double(value) {
+        return infer_nullity(&value)
+    }
     match value {
         Value::Array(value) => match value {
             Array::Float(value) => Ok(value.iter().any(|v| !v.is_finite())),
@@ -235,7 +260,12 @@ pub fn infer_property(
                 data_type: match array {
                     Array::Bool(_) => DataType::Bool,
                     Array::Float(_) => DataType::Float,
-                    Array::Int(_) => DataType::Int,
+                    Array::F32(_) => DataType::F32,
+                    // timestamps are stored as integers, so the type is carried forward from prior properties
+                    Array::Int(_) => match prior_prop_arr.map(|prop| &prop.data_type) {
+                        Some(DataType::DateTime) => DataType::DateTime,
+                        _ => DataType::Int
+                    },
                     Array::Str(_) => DataType::Str,
                 },
                 dataset_id: prior_prop_arr.and_then(|p| p.dataset_id),
@@ -8,11 +8,12 @@ use crate::base;
 
 use serde_json::Value;
 use ndarray::prelude::*;
+use crate::utilities::array::get_ith_column;
 
 
 /// JSONRelease represents JSON objects in the differential privacy release schema.
 /// TODO: link to schema
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default)]
 pub struct JSONRelease {
     pub description: String,
     /// array of string that is column/s in the dataset
@@ -27,18 +28,25 @@ pub struct JSONRelease {
     pub privacy_loss: Value,
     /// optional parameter. It is a combination of the accuracy and alpha value
     pub accuracy: Option<Accuracy>,
+    /// optional parameter. Two-sided confidence interval around the released value, derived from the accuracy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<Interval>,
     /// which release the implemented statistic is originating from. This provides a tool to keep track of overall privacyLoss.
     pub submission: u32,
     /// For advanced users. Corresponds to the node of the graph this release originated from
     #[serde(rename(serialize = "nodeID", deserialize = "nodeID"))]
     pub node_id: u32,
-    /// true when the released value is derived from public/released data
+    /// true when the released value is derived only from releasable data, without invoking a mechanism.
+    /// Set automatically when the report is generated
     pub postprocess: bool,
     /// the name of the algorithm which is implemented for computation of the given statistic and the arguments of the algorithm such as n(number of observations),  range (upper and lower bound, etc.)
     #[serde(rename(serialize = "algorithmInfo", deserialize = "algorithmInfo"))]
     pub algorithm_info: AlgorithmInfo,
 }
 
+/// Alpha at which summaries report their accuracy, when no alpha is requested
+pub const DEFAULT_ALPHA: f64 = 0.05;
+
 /// Statistical accuracy summary
 ///
 /// The actual value refers to the non-privatized statistic on sample data, not the non-privatized statistic of the population
@@ -51,10 +59,57 @@ pub struct Accuracy {
     pub alpha: f64,
 }
 
+/// Two-sided confidence interval around a released value
+///
+/// Each bound has the same shape as the released value.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Interval {
+    /// released value, less the accuracy
+    pub lower: Value,
+    /// released value, plus the accuracy
+    pub upper: Value,
+    /// 100(1 - alpha)% confidence that the actual value is within the interval.
+    pub alpha: f64,
+}
+
+/// Construct a two-sided confidence interval around each column of a released value.
+///
+/// The accuracy of each column applies to every element in the column,
+/// so any mechanism that can estimate its accuracy may be summarized with intervals.
+///
+/// # Arguments
+/// * `release` - numeric value released by a mechanism
+/// * `accuracies` - accuracy of each column of the release
+///
+/// # Return
+/// One interval for each column of the release
+pub fn accuracies_to_intervals(release: &base::Value, accuracies: &[proto::Accuracy]) -> Result<Vec<Interval>> {
+    let release = release.clone().array()?.cast_float()?;
+
+    let to_interval = |column: ArrayD<f64>, accuracy: &proto::Accuracy| Ok(Interval {
+        lower: arraynd_to_json(&column.mapv(|v| v - accuracy.value))?,
+        upper: arraynd_to_json(&column.mapv(|v| v + accuracy.value))?,
+        alpha: accuracy.alpha,
+    });
+
+    match (release.ndim(), accuracies.len()) {
+        // a single column
+        (0, 1) | (1, 1) => Ok(vec![to_interval(release, &accuracies[0])?]),
+        // a vector with one element per column
+        (1, num_columns) if num_columns == release.len() => release.iter().zip(accuracies)
+            .map(|(value, accuracy)| to_interval(arr0(*value).into_dyn(), accuracy))
+            .collect(),
+        (2, num_columns) if num_columns == release.len_of(Axis(1)) => accuracies.iter().enumerate()
+            .map(|(column_number, accuracy)| to_interval(get_ith_column(&release, column_number)?, accuracy))
+            .collect(),
+        (_, num_columns) => Err(format!("the shape of the release {:?} is not compatible with {} accuracies", release.shape(), num_columns).into())
+    }
+}
+
 /// Algorithm summary
 ///
 /// Metadata about the algorithm used to compute the release value.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default)]
 pub struct AlgorithmInfo {
     // mechanism used to generate the release values, typically `Laplace`, `Exponential`, etc.
     pub mechanism: String,
@@ -70,6 +125,7 @@ pub fn value_to_json(value: &base::Value) -> Result<serde_json::Value> {
     match value {
         base::Value::Array(array) => match array {
             base::Array::Float(value) => arraynd_to_json(value),
+            base::Array::F32(value) => arraynd_to_json(value),
             base::Array::Int(value) => arraynd_to_json(value),
             base::Array::Str(value) => arraynd_to_json(value),
             base::Array::Bool(value) => arraynd_to_json(value)
@@ -97,3 +153,127 @@ pub fn privacy_usage_to_json(privacy_usage: &proto::PrivacyUsage) -> serde_json:
             serde_json::json!({"name": "approximate", "epsilon": distance.epsilon, "delta": distance.delta})
     }
 }
+
+#[cfg(test)]
+mod test_json {
+    use ndarray::{arr1, arr2};
+
+    use crate::{analysis_accuracies, generate_report, generate_report_with_intervals, proto};
+    use crate::base::{ReleaseNode, Value};
+    use crate::bindings::Analysis;
+    use crate::utilities::json::accuracies_to_intervals;
+
+    #[test]
+    fn test_laplace_mean_interval() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(arr2(&[[1.], [2.], [3.], [4.]]).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(4.0.into()).value_public(true).build();
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        let dp_mean = analysis.dp_mean(clamped, vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }]).mechanism("Laplace".to_string()).build();
+        analysis.release.insert(dp_mean, ReleaseNode::new(Value::from(2.0)));
+
+        let alpha = 0.05;
+        let accuracy = analysis_accuracies(
+            analysis.privacy_definition.clone(), analysis.components.clone(), analysis.release.clone(), alpha)
+            .unwrap().values.get(&dp_mean).unwrap()
+            .accuracies.as_ref().unwrap().values[0].value;
+
+        let report = generate_report_with_intervals(
+            analysis.privacy_definition, analysis.components, analysis.release, alpha).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+        // the half-width of the interval is the accuracy at the requested alpha
+        let interval = &report[0]["interval"];
+        let (lower, upper) = (interval["lower"].as_f64().unwrap(), interval["upper"].as_f64().unwrap());
+        assert!(((upper - lower) / 2. - accuracy).abs() < 1e-10);
+        assert!(((upper + lower) / 2. - 2.).abs() < 1e-10);
+        assert_eq!(interval["alpha"].as_f64().unwrap(), alpha);
+        assert_eq!(report[0]["accuracy"]["accuracyValue"].as_f64().unwrap(), accuracy);
+    }
+
+    #[test]
+    fn test_intervals_per_column() {
+        let accuracies = vec![
+            proto::Accuracy { value: 1., alpha: 0.05 },
+            proto::Accuracy { value: 2., alpha: 0.05 }];
+
+        let intervals = accuracies_to_intervals(&arr2(&[[1., 10.], [2., 20.]]).into_dyn().into(), &accuracies).unwrap();
+        assert_eq!(intervals[0].lower, serde_json::json!([[0.], [1.]]));
+        assert_eq!(intervals[1].upper, serde_json::json!([[12.], [22.]]));
+
+        let intervals = accuracies_to_intervals(&arr1(&[1., 10.]).into_dyn().into(), &accuracies).unwrap();
+        assert_eq!(intervals[1].lower, serde_json::json!(8.));
+
+        // every column must have an accuracy
+        assert!(accuracies_to_intervals(&arr1(&[1., 10., 100.]).into_dyn().into(), &accuracies).is_err());
+    }
+
+    #[test]
+    fn test_postprocess() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(arr2(&[[1.], [2.], [3.], [4.]]).into_dyn().into())
+            .value_public(false)
+            .build();
+        let data = analysis.to_float(data).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(4.0.into()).value_public(true).build();
+        let number_rows = analysis.literal().value(4.into()).value_public(true).build();
+        let number_columns = analysis.literal().value(1.into()).value_public(true).build();
+        let data = analysis.resize(data)
+            .number_rows(number_rows).number_columns(number_columns)
+            .lower(lower).upper(upper)
+            .build();
+        let data = analysis.clamp(data).lower(lower).upper(upper).build();
+        let data = analysis.impute(data).lower(lower).upper(upper).build();
+        let privacy_usage = proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        };
+        let dp_mean = analysis.dp_mean(data, vec![privacy_usage.clone()])
+            .mechanism("Laplace".to_string()).build();
+        let sqrt = analysis.sqrt(dp_mean).build();
+        // a summarized component of public data, released without spending privacy
+        let public_dp_mean = analysis.dp_mean(upper, vec![privacy_usage.clone()])
+            .mechanism("Laplace".to_string()).build();
+
+        analysis.release.insert(dp_mean, ReleaseNode {
+            value: Value::from(2.25),
+            privacy_usages: Some(vec![privacy_usage]),
+            public: true
+        });
+        analysis.release.insert(sqrt, ReleaseNode::new(Value::from(1.5)));
+        analysis.release.insert(public_dp_mean, ReleaseNode::new(Value::from(4.)));
+
+        let report = generate_report(
+            analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+        let summaries = report.as_array().unwrap();
+        let get_summary = |node_id: u32| summaries.iter()
+            .find(|summary| summary["nodeID"].as_u64() == Some(node_id as u64));
+
+        // the mechanism spends privacy
+        assert_eq!(get_summary(dp_mean).unwrap()["postprocess"].as_bool(), Some(false));
+
+        // only components that summarize their releases are reported
+        assert!(get_summary(sqrt).is_none());
+
+        // the release consumes only releasable data, and spends no privacy
+        assert_eq!(get_summary(public_dp_mean).unwrap()["postprocess"].as_bool(), Some(true));
+
+        // summaries are ordered by node id, so that reports are reproducible
+        assert_eq!(summaries.iter().map(|summary| summary["nodeID"].as_u64().unwrap()).collect::<Vec<u64>>(),
+                   vec![dp_mean as u64, public_dp_mean as u64]);
+    }
+}
@@ -8,7 +8,7 @@ use ndarray::prelude::*;
 use noisy_float::prelude::n64;
 
 use crate::{base, Float, proto, Warnable};
-use crate::base::{IndexKey, NodeProperties, Release, SensitivitySpace, Value, ValueProperties, ArrayProperties, Array};
+use crate::base::{IndexKey, NodeProperties, Release, SensitivitySpace, Value, ValueProperties, ArrayProperties, Array, DataType};
 // import all trait implementations
 use crate::components::*;
 use crate::errors::*;
@@ -22,6 +22,8 @@ pub mod serial;
 pub mod array;
 pub mod privacy;
 pub mod properties;
+pub mod datetime;
+pub mod expression;
 
 /// Retrieve the specified Value from the arguments to a component.
 pub fn take_argument(
@@ -70,6 +72,45 @@ pub fn get_input_properties<T>(
     Ok(properties)
 }
 
+/// Retrieve the human readable names of the variables at every node in the graph.
+///
+/// Nodes whose names cannot be determined are omitted.
+pub fn get_variable_names(
+    computation_graph: &HashMap<u32, proto::Component>,
+    release: &Release,
+) -> Result<HashMap<u32, Vec<IndexKey>>> {
+    let mut nodes_varnames: HashMap<u32, Vec<IndexKey>> = HashMap::new();
+
+    get_traversal(computation_graph)?.iter().for_each(|node_id| {
+        let component: proto::Component = computation_graph.get(node_id).unwrap().to_owned();
+        let public_arguments = match get_public_arguments(&component, release) {
+            Ok(v) => v, Err(_) => return
+        };
+
+        // variable names for argument nodes
+        let mut arguments_vars: IndexMap<base::IndexKey, Vec<IndexKey>> = IndexMap::new();
+
+        // iterate through argument nodes
+        for (field_id, field) in &component.arguments() {
+            // get variable names corresponding to that argument
+            if let Some(arg_vars) = nodes_varnames.get(field) {
+                arguments_vars.insert(field_id.clone(), arg_vars.clone());
+            }
+        }
+
+        // get variable names for this node
+        let node_vars = component.get_names(
+            public_arguments,
+            arguments_vars,
+            release.get(node_id).map(|v| v.value.clone()).as_ref());
+
+        // update names in indexmap
+        node_vars.map(|v| nodes_varnames.insert(*node_id, v)).ok();
+    });
+
+    Ok(nodes_varnames)
+}
+
 /// Given an analysis and release, attempt to propagate properties across the entire computation graph.
 ///
 /// The graph is traversed, and every node is attempted to be expanded, so that validation occurs at the most granular level.
@@ -114,6 +155,9 @@ pub fn propagate_properties(
 
     let mut warnings = Vec::new();
 
+    // nodes supplied by the user, rather than inserted by an expansion
+    let user_node_ids: HashSet<u32> = computation_graph.keys().cloned().collect();
+
     while !traversal.is_empty() {
         let node_id = *traversal.last().unwrap();
 
@@ -150,10 +194,16 @@ pub fn propagate_properties(
         computation_graph.extend(expansion.computation_graph);
         properties.extend(expansion.properties);
         release.extend(expansion.releases);
-        warnings.extend(expansion.warnings);
+        // warnings about expanded nodes are not actionable by the user
+        if user_node_ids.contains(&node_id) {
+            warnings.extend(expansion.warnings);
+        }
 
         // if patch added nodes, extend the traversal
         if !expansion.traversal.is_empty() {
+            // the expanded node is still in the traversal,
+            //     so a node that expands in place must not be revisited, or a mechanism would be expanded twice
+            expansion.traversal.retain(|id| *id != node_id);
             expansion.traversal.reverse();
             traversal.extend(expansion.traversal);
             continue;
@@ -181,8 +231,23 @@ pub fn propagate_properties(
         let release_node = release.get(&node_id);
         // println!("release node {:?}", release_node);
 
-        let propagation_result = if release_node
-            .map(|release_node| release_node.public).unwrap_or(false) {
+        let is_public = release_node.map(|release_node| release_node.public).unwrap_or(false);
+
+        // a public release may only depend on private data through a privatizing component
+        if is_public && !component.variant.as_ref().map(is_mechanism).unwrap_or(false) {
+            if let Some((name, _)) = input_properties.iter().find(|(_, property)| !property.is_public()) {
+                let err = Error::from(format!(
+                    "node is marked public, but depends on the non-releasable argument {:?}", name))
+                    .chain_err(|| format!("at node_id {:?}", node_id));
+                if dynamic {
+                    failed_ids.insert(node_id);
+                    warnings.push(err);
+                    continue
+                } else { return Err(err) }
+            }
+        }
+
+        let propagation_result = if is_public {
             // if node has already been evaluated and is public, infer properties directly from the public data
             // println!("inferring property");
             Ok(Warnable(infer_property(
@@ -318,6 +383,20 @@ pub fn set_node_id(property: &mut ValueProperties, node_id: u32) -> () {
     };
 }
 
+/// Mark single precision data as double precision, to match the conversion applied by the runtime.
+pub fn set_double_precision(property: &mut ValueProperties) {
+    match property {
+        ValueProperties::Array(array) => if array.data_type == DataType::F32 {
+            array.data_type = DataType::Float
+        },
+        ValueProperties::Dataframe(dataframe) => dataframe.children.values_mut()
+            .for_each(set_double_precision),
+        ValueProperties::Partitions(partitions) => partitions.children.values_mut()
+            .for_each(set_double_precision),
+        ValueProperties::Jagged(_) | ValueProperties::Function(_) => ()
+    };
+}
+
 /// Given an array, conduct well-formedness checks and broadcast
 ///
 /// Typically used by functions when standardizing numeric arguments, but generally applicable.
@@ -499,6 +578,14 @@ pub fn get_literal(value: Value, submission: u32) -> Result<(proto::Component, b
     ))
 }
 
+/// Warning for an argument that was not supplied, and is instead taken from the static properties of the data.
+///
+/// The properties of the data may have been derived upstream, rather than chosen by the analyst,
+/// so the warning suggests supplying a public value instead.
+pub fn inferred_argument_warning(name: &str) -> Error {
+    format!("{}: not supplied, so it is inferred from the properties of the data. Supply a public {} instead", name, name).into()
+}
+
 /// return a simple function that modifies the input string with the specified text
 /// part of a commonly used pattern to prepend the argument name to an error string
 #[doc(hidden)]
@@ -527,10 +614,16 @@ pub fn expand_mechanism(
         .ok_or("data: missing")?.array()
         .map_err(prepend("data:"))?.clone();
 
+    // a joint gaussian calibrates every column to the L2 sensitivity over all columns, with the entire usage
+    let joint = match &component.variant {
+        Some(proto::component::Variant::GaussianMechanism(variant)) => variant.joint,
+        _ => false
+    };
+
     // spread privacy usage over each column
     let spread_usages = spread_privacy_usage(
         // spread usage over each column
-        privacy_usage, data_property.num_columns()? as usize)?;
+        privacy_usage, if joint { 1 } else { data_property.num_columns()? as usize })?;
 
     // convert to effective usage
     let effective_usages = spread_usages.into_iter()
@@ -553,7 +646,7 @@ pub fn expand_mechanism(
             }
         }
     }
-    assign_usage!(LaplaceMechanism, GaussianMechanism, SimpleGeometricMechanism, SnappingMechanism);
+    assign_usage!(LaplaceMechanism, GaussianMechanism, SimpleGeometricMechanism, SnappingMechanism, TruncatedLaplaceMechanism);
 
     if let Some(sensitivity_property) = properties.get(&IndexKey::from("sensitivity")) {
         if privacy_definition.protect_sensitivity {
@@ -573,7 +666,7 @@ pub fn expand_mechanism(
         match aggregator.lipschitz_constants.clone().array()? {
             Array::Float(lipschitz) => {
                 if lipschitz.iter().any(|v| v != &1.) {
-                    let mut sensitivity = sensitivity_value.array()?.float()?;
+                    let mut sensitivity = sensitivity_value.array()?.cast_float()?;
                     sensitivity.mul_assign(&lipschitz);
                     sensitivity_value = sensitivity.into();
                 }
@@ -588,6 +681,12 @@ pub fn expand_mechanism(
             _ => return Err(Error::from("lipschitz constants must be numeric"))
         };
 
+        if joint {
+            let sensitivity = sensitivity_value.array()?.cast_float()?;
+            let norm = sensitivity.iter().map(|v| v.powi(2)).sum::<Float>().sqrt();
+            sensitivity_value = sensitivity.mapv(|_| norm).into();
+        }
+
         maximum_id += 1;
         let id_sensitivity = maximum_id;
         let (patch_node, release) = get_literal(sensitivity_value.clone(), component.submission)?;
@@ -602,6 +701,60 @@ pub fn expand_mechanism(
     Ok(expansion)
 }
 
+/// Build the variant of the mechanism named `mechanism`, which spends `privacy_usage`.
+///
+/// `joint` only applies to the gaussian mechanisms.
+pub fn get_mechanism_variant(
+    mechanism: &str, privacy_usage: &[proto::PrivacyUsage], joint: bool,
+) -> Result<proto::component::Variant> {
+    let privacy_usage = privacy_usage.to_vec();
+    Ok(match mechanism.to_lowercase().as_str() {
+        "simplegeometric" => proto::component::Variant::SimpleGeometricMechanism(proto::SimpleGeometricMechanism {
+            privacy_usage
+        }),
+        "laplace" => proto::component::Variant::LaplaceMechanism(proto::LaplaceMechanism {
+            privacy_usage,
+            resolution: 0.
+        }),
+        "truncatedlaplace" => proto::component::Variant::TruncatedLaplaceMechanism(proto::TruncatedLaplaceMechanism {
+            privacy_usage
+        }),
+        "gaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+            privacy_usage,
+            analytic: false,
+            joint,
+            resolution: 0.
+        }),
+        "analyticgaussian" => proto::component::Variant::GaussianMechanism(proto::GaussianMechanism {
+            privacy_usage,
+            analytic: true,
+            joint,
+            resolution: 0.
+        }),
+        "snapping" => proto::component::Variant::SnappingMechanism(proto::SnappingMechanism {
+            privacy_usage
+        }),
+        _ => bail!("Unexpected invalid token {:?}", mechanism),
+    })
+}
+
+/// The mechanism named `mechanism`, if it does not depend on the data type or privacy definition.
+///
+/// Accuracies of a joint gaussian depend on the sensitivity over all columns, so they are not defined.
+pub fn get_explicit_mechanism(
+    mechanism: &str, privacy_usage: &[proto::PrivacyUsage], joint: bool,
+) -> Result<Option<proto::Component>> {
+    if mechanism.to_lowercase() == "automatic" || joint {
+        return Ok(None)
+    }
+    Ok(Some(proto::Component {
+        arguments: None,
+        variant: Some(get_mechanism_variant(mechanism, privacy_usage, joint)?),
+        omit: false,
+        submission: 0,
+    }))
+}
+
 pub fn check_sensitivity_properties(
     sensitivity_property: &ArrayProperties, data_property: &ArrayProperties
 ) -> Result<()> {
@@ -618,6 +771,17 @@ pub fn check_sensitivity_properties(
     Ok(())
 }
 
+/// parse the name of an atomic type, as accepted by casting and materializing
+pub fn parse_data_type(atomic_type: &str) -> Result<DataType> {
+    Ok(match atomic_type.to_lowercase().as_str() {
+        "float" | "real" => DataType::Float,
+        "int" | "integer" => DataType::Int,
+        "bool" => DataType::Bool,
+        "string" | "str" => DataType::Str,
+        _ => bail!("data type is not recognized. Must be one of \"float\", \"int\", \"bool\" or \"string\"")
+    })
+}
+
 /// given a vector of items, return the shared item, or None, if no item is shared
 #[allow(clippy::ptr_arg)]
 pub fn get_common_value<T: Clone + Eq>(values: &Vec<T>) -> Option<T> {
@@ -656,4 +820,132 @@ mod test_utilities {
         let deduplicated = utilities::deduplicate(values.clone());
         assert!(deduplicated == vec![2, 0, 1]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_public_depends_on_private() {
+        use ndarray::{arr1, arr2};
+        use crate::base::ReleaseNode;
+        use crate::bindings::Analysis;
+
+        let build = |bound_public: bool| {
+            let mut analysis = Analysis::new();
+            let data = analysis.literal()
+                .value(arr2(&[[1.], [2.], [3.]]).into_dyn().into())
+                .value_public(true)
+                .build();
+            let lower = analysis.literal().value(arr1(&[-20.]).into_dyn().into()).value_public(true).build();
+            let bound = analysis.literal().value(arr1(&[10.]).into_dyn().into()).value_public(bound_public).build();
+            let upper = analysis.negate(bound).build();
+            // the derived bound is marked public, as if it were computed from public data
+            analysis.release.insert(upper, ReleaseNode {
+                value: arr1(&[-10.]).into_dyn().into(),
+                privacy_usages: None,
+                public: true,
+            });
+            let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+            analysis.properties(clamped)
+        };
+
+        assert!(build(true).is_ok());
+        let error = build(false).unwrap_err();
+        assert!(error.to_string().contains("depends on the non-releasable argument"), "{}", error);
+    }
+}
+
+#[cfg(test)]
+mod test_group_size {
+    use indexmap::IndexMap;
+    use ndarray::Array2;
+
+    use crate::base::{IndexKey, SensitivitySpace, ValueProperties};
+    use crate::bindings::Analysis;
+    use crate::components::Mechanism;
+    use crate::proto;
+    use crate::utilities::expand_mechanism;
+    use crate::utilities::privacy::get_epsilon;
+
+    /// Effective epsilon spent by a mechanism, and the actual epsilon it reports, with a total usage of epsilon 1.
+    fn epsilons(mechanism: &str, group_size: u32) -> (f64, f64) {
+        let usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 1e-6
+            }))
+        }];
+
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        analysis.privacy_definition.group_size = group_size;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 1), |(i, _)| i as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let count = analysis.count(data).build();
+        let (mechanism, sensitivity_type) = match mechanism {
+            "Laplace" => (analysis.laplace_mechanism(count, usage.clone()).build(), SensitivitySpace::KNorm(1)),
+            "Gaussian" => (analysis.gaussian_mechanism(count, usage.clone()).build(), SensitivitySpace::KNorm(2)),
+            "SimpleGeometric" => {
+                let lower = analysis.literal().value(0.into()).value_public(true).build();
+                let upper = analysis.literal().value(10.into()).value_public(true).build();
+                (analysis.simple_geometric_mechanism(count, lower, upper, usage.clone()).build(), SensitivitySpace::KNorm(1))
+            }
+            _ => unreachable!()
+        };
+
+        let mut properties = IndexMap::<IndexKey, ValueProperties>::new();
+        properties.insert("data".into(), analysis.properties(count).unwrap());
+        let expansion = expand_mechanism(
+            &sensitivity_type,
+            &Some(analysis.privacy_definition.clone()),
+            &usage,
+            analysis.components.get(&mechanism).unwrap(),
+            &properties,
+            mechanism, 1000).unwrap();
+
+        let variant = expansion.computation_graph.get(&mechanism).unwrap().variant.clone().unwrap();
+        let (effective_usage, actual_usage) = match variant {
+            proto::component::Variant::LaplaceMechanism(variant) => (variant.privacy_usage.clone(),
+                variant.get_privacy_usage(&analysis.privacy_definition, None, &properties)),
+            proto::component::Variant::GaussianMechanism(variant) => (variant.privacy_usage.clone(),
+                variant.get_privacy_usage(&analysis.privacy_definition, None, &properties)),
+            proto::component::Variant::SimpleGeometricMechanism(variant) => (variant.privacy_usage.clone(),
+                variant.get_privacy_usage(&analysis.privacy_definition, None, &properties)),
+            _ => panic!("unexpected mechanism")
+        };
+        (get_epsilon(&effective_usage[0]).unwrap(), get_epsilon(&actual_usage.unwrap().unwrap()[0]).unwrap())
+    }
+
+    #[test]
+    fn test_group_size_scaling() {
+        vec!["Laplace", "Gaussian", "SimpleGeometric"].into_iter().for_each(|mechanism| {
+            let (effective_single, actual_single) = epsilons(mechanism, 1);
+            let (effective_pair, actual_pair) = epsilons(mechanism, 2);
+
+            // doubling the group size halves the epsilon the mechanism may spend on each record
+            assert!((effective_single - 1.).abs() < 1e-12, "{}", mechanism);
+            assert!((effective_pair - 0.5).abs() < 1e-12, "{}", mechanism);
+            // the actual usage reported over the group is the requested usage
+            assert!((actual_single - 1.).abs() < 1e-12, "{}", mechanism);
+            assert!((actual_pair - 1.).abs() < 1e-12, "{}", mechanism);
+        });
+    }
+
+    #[test]
+    fn test_zero_group_size() {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        analysis.privacy_definition.group_size = 0;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((10, 1), |(i, _)| i as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let count = analysis.count(data).build();
+        let usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 0.
+            }))
+        }];
+        let laplace = analysis.laplace_mechanism(count, usage).build();
+        let error = analysis.properties(laplace).unwrap_err();
+        assert!(error.to_string().contains("group size must be greater than zero"), "{}", error);
+    }
+}
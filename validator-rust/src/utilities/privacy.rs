@@ -82,7 +82,9 @@ fn batch_partition<'a>(
                 .flatten()
                 .collect::<Vec<u32>>();
 
-            // consume the entire graph after the partition node
+            // consume the entire graph downstream of the partition node
+            //     inputs of the downstream nodes that do not descend from the partition, like public literals,
+            //     do not connect other mechanisms to the partition, so those mechanisms are composed sequentially
             while !blacklist_traversal.is_empty() {
                 let node_id = blacklist_traversal.pop().unwrap();
                 blacklist.insert(node_id);
@@ -97,10 +99,6 @@ fn batch_partition<'a>(
                     partition_ids.retain(|v| v != &node_id);
                 }
 
-                component.arguments().values()
-                    .filter(|id| !blacklist.contains(id))
-                    .for_each(|id| blacklist_traversal.push(*id));
-
                 if let Some(ids) = parents.get(&node_id) {
                     ids.iter()
                         .filter(|id| !blacklist.contains(id))
@@ -171,9 +169,39 @@ fn batch_partition<'a>(
     Ok((batches, partition_ids))
 }
 
+/// Compute the actual privacy usage of each of the given nodes that consumes privacy,
+///     based on the privacy definition
+///     and actual usages reported by any computed values.
+pub fn get_node_privacy_usages<'a>(
+    graph: &HashMap<u32, proto::Component>,
+    privacy_definition: &proto::PrivacyDefinition,
+    properties: &HashMap<u32, ValueProperties>,
+    release: &Release,
+    node_ids: impl Iterator<Item=&'a u32>,
+) -> Result<HashMap<u32, Vec<proto::PrivacyUsage>>> {
+    node_ids
+        .map(|node_id| {
+            let component = graph.get(node_id)
+                .ok_or_else(|| Error::from(format!("node {} is not in the graph", node_id)))?;
+            Ok((*node_id, component.get_privacy_usage(
+                privacy_definition,
+                release.get(node_id)
+                    .and_then(|v| v.privacy_usages.as_ref()),
+                &get_input_properties(component, properties)?)?))
+        })
+        .collect::<Result<Vec<(u32, Option<Vec<proto::PrivacyUsage>>)>>>()
+        .map(|usages| usages.into_iter()
+            .filter_map(|(node_id, usages)| Some((node_id, usages?)))
+            .collect())
+}
+
 /// Compute the privacy usage of a graph,
 ///     based on the privacy definition
 ///     and actual usages reported by any computed values.
+///
+/// Graphs whose usage would be under-counted are rejected,
+///     such as a mechanism on both partitioned data and private data from outside the partition.
+/// `get_properties` rejects the same graphs, so that they are not released.
 pub fn compute_graph_privacy_usage(
     graph: &HashMap<u32, proto::Component>,
     privacy_definition: &proto::PrivacyDefinition,
@@ -183,16 +211,18 @@ pub fn compute_graph_privacy_usage(
 
     // compute the privacy usage for every node in the graph
     //    include updated privacy usages for nodes that have already been released and may have actually consumed a different amount
-    let release_privacy_usages = graph.iter()
-        .map(|(node_id, component)| Ok((*node_id, component.get_privacy_usage(
-            &privacy_definition,
-            release.get(node_id)
-                .and_then(|v| v.privacy_usages.as_ref()),
-            &get_input_properties(component, &properties)?)?))
-        )
-        .collect::<Result<Vec<(u32, Option<Vec<proto::PrivacyUsage>>)>>>()?
-        .into_iter().filter_map(|(node_id, usages)| Some((node_id, usages?)))
-        .collect::<HashMap<u32, Vec<proto::PrivacyUsage>>>();
+    let release_privacy_usages = get_node_privacy_usages(
+        graph, privacy_definition, properties, release, graph.keys())?;
+
+    // the usage would be under-counted if a node were composed in parallel with a partition it is not disjoint from
+    if let Some(error) = check_parallel_composition(graph, properties, &release_privacy_usages).into_iter().next() {
+        return Err(error)
+    }
+
+    // the usage would be under-counted if amplification were applied to each of several mechanisms on one subsample
+    if let Some(error) = check_subsample_composition(graph, &release_privacy_usages).into_iter().next() {
+        return Err(error)
+    }
 
     // for any node id in the submission, list all nodes that use it
     let dependent_edges = get_dependents(graph);
@@ -227,8 +257,8 @@ pub fn compute_graph_privacy_usage(
                         .ok_or_else(|| "names argument must be specified on an index into partitions")?;
                     Ok((
                         *index_id,
-                        category == IndexKey::new(release.get(&node_id)
-                            .ok_or_else(|| "names value must be defined")?.value.clone().array()?)?
+                        category == IndexKey::from_value(release.get(&node_id)
+                            .ok_or("names value must be defined")?.value.clone())?
                     ))
                 }
                 // if a union, always keep it- the output of the union will always contain data from the partition
@@ -285,29 +315,6 @@ pub fn compute_graph_privacy_usage(
             .collect::<HashMap<u32, proto::Component>>())
     };
 
-    // return the max of the left and right privacy usages
-    let max_usage = |l: Result<proto::PrivacyUsage>, r: Result<proto::PrivacyUsage>| -> Result<proto::PrivacyUsage> {
-        let proto::privacy_usage::DistanceApproximate {
-            epsilon: eps_l, delta: del_l
-        } = match l?.distance {
-            Some(proto::privacy_usage::Distance::Approximate(x)) => Ok(x),
-            _ => Err("expected approximate privacy")
-        }?;
-        let proto::privacy_usage::DistanceApproximate {
-            epsilon: eps_r, delta: del_r
-        } = match r?.distance {
-            Some(proto::privacy_usage::Distance::Approximate(x)) => Ok(x),
-            _ => Err("expected approximate privacy")
-        }?;
-
-        Ok(proto::PrivacyUsage {
-            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
-                epsilon: eps_l.max(eps_r),
-                delta: del_l.max(del_r),
-            }))
-        })
-    };
-
     // compute privacy usage of a subset of the graph,
     //     where the subset is indicated by a collection of node ids
     let compute_all_partitions_usage = |
@@ -317,7 +324,7 @@ pub fn compute_graph_privacy_usage(
             .map(|partition_id| compute_graph_privacy_usage(
                 &get_downstream_graph(None, *partition_id)?,
                 privacy_definition, properties, release))
-            .fold1(max_usage)
+            .fold1(max_privacy_usage)
             .unwrap_or_else(|| Ok(zero_usage()))
     };
 
@@ -346,7 +353,7 @@ pub fn compute_graph_privacy_usage(
 
                     batch_usages + compute_all_partitions_usage(partition_ids)?
                 })
-                .fold1(max_usage)
+                .fold1(max_privacy_usage)
                 .unwrap_or_else(|| Ok(zero_usage()))
         })
         .fold1(|l, r| l? + r?)
@@ -360,6 +367,105 @@ pub fn compute_graph_privacy_usage(
     batch_usages + partitions_usage
 }
 
+/// Find nodes that use privacy on partitioned data, as well as on private data from outside of the partition.
+///
+/// The usage downstream of a partition is composed in parallel over its categories.
+/// A node that also uses private data that does not descend from the partition is not disjoint from the other categories,
+///     so composing it in parallel would under-count its usage.
+/// Public inputs, like literal bounds, do not hold any private data, so they may be shared with the partition.
+///
+/// Returns an error describing each such node.
+pub fn check_parallel_composition(
+    graph: &HashMap<u32, proto::Component>,
+    properties: &HashMap<u32, ValueProperties>,
+    privacy_usages: &HashMap<u32, Vec<proto::PrivacyUsage>>,
+) -> Vec<Error> {
+    let dependents = get_dependents(graph);
+    let is_partition = |component: &proto::Component|
+        matches!(component.variant, Some(proto::component::Variant::Partition(_)));
+
+    // nodes that descend from the data of a partition
+    let mut partitioned = HashSet::new();
+    let mut traversal = graph.iter()
+        .filter(|(_, component)| is_partition(component))
+        .map(|(node_id, _)| *node_id)
+        .collect::<Vec<u32>>();
+    while let Some(node_id) = traversal.pop() {
+        if partitioned.insert(node_id) {
+            traversal.extend(dependents.get(&node_id).into_iter().flatten());
+        }
+    }
+
+    partitioned.iter()
+        .filter(|node_id| privacy_usages.contains_key(node_id))
+        .sorted()
+        .filter_map(|mechanism_id| {
+            // walk up through the partitioned ancestors of the mechanism, to find private inputs from outside the partition
+            let mut visited = HashSet::new();
+            let mut traversal = vec![*mechanism_id];
+            while let Some(node_id) = traversal.pop() {
+                let component = match graph.get(&node_id) {
+                    Some(component) if visited.insert(node_id) && !is_partition(component) => component,
+                    _ => continue
+                };
+                for argument_id in component.arguments().values() {
+                    if partitioned.contains(argument_id) {
+                        traversal.push(*argument_id)
+                    } else if !properties.get(argument_id).map(ValueProperties::is_public).unwrap_or(false) {
+                        return Some((*mechanism_id, *argument_id))
+                    }
+                }
+            }
+            None
+        })
+        .map(|(node_id, argument_id)| format!(
+            "node {}: uses privacy on partitioned data, as well as on private data from node {} that is not from the partition. \
+            Its usage cannot be composed in parallel over the categories of the partition. \
+            Release the partitioned and unpartitioned data with separate mechanisms",
+            node_id, argument_id).into())
+        .collect()
+}
+
+/// Find subsamples with more than one node that uses privacy downstream.
+///
+/// Each mechanism applied to a subsample spends an amplified effective usage.
+/// Amplification holds for the composition of every mechanism applied to the same subsample,
+///     not for each mechanism separately, so summing the amplified usages would under-count the usage.
+///
+/// Returns an error describing each such subsample.
+pub fn check_subsample_composition(
+    graph: &HashMap<u32, proto::Component>,
+    privacy_usages: &HashMap<u32, Vec<proto::PrivacyUsage>>,
+) -> Vec<Error> {
+    let dependents = get_dependents(graph);
+
+    graph.iter()
+        .filter(|(_, component)| matches!(component.variant, Some(proto::component::Variant::Subsample(_))))
+        .map(|(node_id, _)| *node_id)
+        .sorted()
+        .filter_map(|subsample_id| {
+            let mut downstream = HashSet::new();
+            let mut traversal = vec![subsample_id];
+            while let Some(node_id) = traversal.pop() {
+                if downstream.insert(node_id) {
+                    traversal.extend(dependents.get(&node_id).into_iter().flatten());
+                }
+            }
+            let mechanism_ids = downstream.into_iter()
+                .filter(|node_id| privacy_usages.contains_key(node_id))
+                .sorted().collect::<Vec<u32>>();
+
+            if mechanism_ids.len() > 1 {
+                Some(format!(
+                    "nodes {:?}: use privacy on subsample {}. \
+                    Privacy amplification by subsampling is only applied to a single mechanism per subsample. \
+                    Subsample separately for each mechanism",
+                    mechanism_ids, subsample_id).into())
+            } else { None }
+        })
+        .collect()
+}
+
 // pub fn privacy_usage_reducer(
 //     left: &proto::PrivacyUsage,
 //     right: &proto::PrivacyUsage,
@@ -426,6 +532,33 @@ pub fn privacy_usage_check(
     Ok(warnings)
 }
 
+/// Return the max of the left and right privacy usages.
+///
+/// This is the privacy usage of releases made on disjoint partitions, by parallel composition.
+pub fn max_privacy_usage(
+    l: Result<proto::PrivacyUsage>, r: Result<proto::PrivacyUsage>
+) -> Result<proto::PrivacyUsage> {
+    let proto::privacy_usage::DistanceApproximate {
+        epsilon: eps_l, delta: del_l
+    } = match l?.distance {
+        Some(proto::privacy_usage::Distance::Approximate(x)) => Ok(x),
+        _ => Err("expected approximate privacy")
+    }?;
+    let proto::privacy_usage::DistanceApproximate {
+        epsilon: eps_r, delta: del_r
+    } = match r?.distance {
+        Some(proto::privacy_usage::Distance::Approximate(x)) => Ok(x),
+        _ => Err("expected approximate privacy")
+    }?;
+
+    Ok(proto::PrivacyUsage {
+        distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+            epsilon: eps_l.max(eps_r),
+            delta: del_l.max(del_r),
+        }))
+    })
+}
+
 pub fn get_epsilon(usage: &proto::PrivacyUsage) -> Result<f64> {
     match usage.distance.clone()
         .ok_or_else(|| Error::from("distance must be defined on a PrivacyUsage"))? {
@@ -442,6 +575,35 @@ pub fn get_delta(usage: &proto::PrivacyUsage) -> Result<f64> {
     }
 }
 
+/// True if the privacy definition prefers the gaussian mechanism, and every privacy usage has a delta budget to spend on it.
+///
+/// The gaussian mechanism is susceptible to floating-point attacks, so it is never preferred when floating-point protections are enabled.
+pub fn prefers_gaussian(privacy_definition: &proto::PrivacyDefinition, privacy_usage: &[proto::PrivacyUsage]) -> bool {
+    privacy_definition.default_mechanism == proto::privacy_definition::MechanismPreference::Approximate as i32
+        && !privacy_definition.protect_floating_point
+        && !privacy_usage.is_empty()
+        && privacy_usage.iter().all(|usage| get_delta(usage).map(|delta| delta > 0.).unwrap_or(false))
+}
+
+/// Resolve an `Automatic` mechanism for a real-valued statistic.
+///
+/// # Arguments
+/// * `privacy_definition` - Definition of privacy, including the preferred family of mechanism.
+/// * `privacy_usage` - Privacy usage of the mechanism.
+///
+/// # Return
+/// `gaussian` if it is preferred, otherwise `snapping` if floating-point protections are enabled, otherwise `laplace`.
+pub fn get_automatic_mechanism(privacy_definition: &proto::PrivacyDefinition, privacy_usage: &[proto::PrivacyUsage]) -> String {
+    if prefers_gaussian(privacy_definition, privacy_usage) { "gaussian" }
+    else if privacy_definition.protect_floating_point { "snapping" }
+    else { "laplace" }.to_string()
+}
+
+/// Allocate privacy usage to each of `length` columns.
+///
+/// A single usage is spread evenly over the columns.
+/// Alternatively, one usage may be given for each column, to allocate more of the budget to some columns than others.
+/// Any other number of usages is an error.
 pub fn spread_privacy_usage(usages: &[proto::PrivacyUsage], length: usize) -> Result<Vec<proto::PrivacyUsage>> {
     if usages.len() == length {
         return Ok(usages.to_owned());
@@ -519,4 +681,295 @@ pub fn get_c_stability_multiplier(arguments: Vec<Vec<GroupId>>) -> Result<u32> {
         *counts.entry(group_id.index).or_insert(0) += 1);
 
     Ok(*counts.values().max().unwrap())
-}
\ No newline at end of file
+}
+
+/// Determine if the arguments are disjoint parts of a common partition.
+///
+/// Arguments are disjoint if each comes from a different part of the same partition,
+/// and overlap if a part is repeated.
+/// Disjointness is unknown if any argument does not come from a partition.
+pub fn is_disjoint(arguments: Vec<Vec<GroupId>>) -> Result<bool> {
+    if arguments.iter().any(|group_ids| group_ids.is_empty()) {
+        return Err("disjointness is unknown for arguments that do not come from a partition".into())
+    }
+    Ok(get_c_stability_multiplier(arguments)? == 1)
+}
+
+/// Accumulates the privacy usage spent over many analyses of the same data, against a total budget.
+///
+/// The privacy usage of each analysis is composed linearly.
+/// Cloning a tracker gives an independent copy,
+/// which is useful for planning speculative analyses without spending from the original tracker.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BudgetTracker {
+    budget: proto::PrivacyUsage,
+    spent: proto::PrivacyUsage,
+}
+
+impl BudgetTracker {
+    /// Create a tracker that permits at most `budget` to be spent.
+    pub fn new(budget: proto::PrivacyUsage) -> Result<Self> {
+        privacy_usage_check(&budget, None, false)?;
+        Ok(BudgetTracker { spent: (budget.clone() * 0.)?, budget })
+    }
+
+    /// The total privacy usage permitted.
+    pub fn budget(&self) -> &proto::PrivacyUsage {
+        &self.budget
+    }
+
+    /// The privacy usage spent so far.
+    pub fn spent(&self) -> &proto::PrivacyUsage {
+        &self.spent
+    }
+
+    /// The privacy usage that may still be spent.
+    pub fn remaining(&self) -> Result<proto::PrivacyUsage> {
+        let remaining = (self.budget.clone() - self.spent.clone())?;
+        Ok(proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: get_epsilon(&remaining)?.max(0.),
+                delta: get_delta(&remaining)?.max(0.),
+            }))
+        })
+    }
+
+    /// Check if `usage` may be spent without exceeding the budget.
+    pub fn can_spend(&self, usage: &proto::PrivacyUsage) -> Result<bool> {
+        let total = (self.spent.clone() + usage.clone())?;
+        // permit rounding error when the budget is spent in many small pieces
+        let within = |total: f64, budget: f64| total <= budget * (1. + 1e-9);
+        Ok(within(get_epsilon(&total)?, get_epsilon(&self.budget)?)
+            && within(get_delta(&total)?, get_delta(&self.budget)?))
+    }
+
+    /// Record `usage` as spent.
+    ///
+    /// Nothing is spent if `usage` would exceed the remaining budget.
+    pub fn spend(&mut self, usage: proto::PrivacyUsage) -> Result<()> {
+        if !self.can_spend(&usage)? {
+            bail!("privacy usage of {:?} exceeds the remaining budget of {:?}", usage, self.remaining()?)
+        }
+        self.spent = (self.spent.clone() + usage)?;
+        Ok(())
+    }
+
+    /// Compute the privacy usage of an analysis, and record it as spent.
+    ///
+    /// Nothing is spent if the analysis would exceed the remaining budget.
+    pub fn spend_analysis(
+        &mut self,
+        privacy_definition: proto::PrivacyDefinition,
+        computation_graph: HashMap<u32, proto::Component>,
+        release: Release,
+    ) -> Result<proto::PrivacyUsage> {
+        let usage = crate::compute_privacy_usage(privacy_definition, computation_graph, release)?;
+        self.spend(usage.clone())?;
+        Ok(usage)
+    }
+
+    /// Forget all spent privacy usage.
+    pub fn reset(&mut self) {
+        self.spent = proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 0., delta: 0.
+            }))
+        };
+    }
+}
+
+#[cfg(test)]
+mod test_budget_tracker {
+    use ndarray::Array2;
+
+    use crate::bindings::Analysis;
+    use crate::proto;
+    use crate::utilities::privacy::{BudgetTracker, get_delta, get_epsilon};
+
+    fn usage(epsilon: f64, delta: f64) -> proto::PrivacyUsage {
+        proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon, delta
+            }))
+        }
+    }
+
+    /// an analysis containing a single dp sum
+    fn analysis(epsilon: f64) -> Analysis {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| (i % 10) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        analysis.dp_sum(data, vec![usage(epsilon, 0.)])
+            .mechanism("Laplace".to_string())
+            .build();
+        analysis
+    }
+
+    #[test]
+    fn test_spend_analyses() {
+        let mut tracker = BudgetTracker::new(usage(1., 1e-6)).unwrap();
+
+        for epsilon in &[0.5, 0.3] {
+            let analysis = analysis(*epsilon);
+            let spent = tracker.spend_analysis(
+                analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+            assert!((get_epsilon(&spent).unwrap() - epsilon).abs() < 1e-12);
+        }
+        assert!((get_epsilon(tracker.spent()).unwrap() - 0.8).abs() < 1e-12);
+        assert!((get_epsilon(&tracker.remaining().unwrap()).unwrap() - 0.2).abs() < 1e-12);
+        assert_eq!(get_delta(&tracker.remaining().unwrap()).unwrap(), 1e-6);
+
+        // an analysis over the remaining budget spends nothing
+        let analysis = analysis(0.3);
+        assert!(tracker.spend_analysis(
+            analysis.privacy_definition, analysis.components, analysis.release).is_err());
+        assert!((get_epsilon(tracker.spent()).unwrap() - 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_clone_and_reset() {
+        let mut tracker = BudgetTracker::new(usage(1., 0.)).unwrap();
+        tracker.spend(usage(0.4, 0.)).unwrap();
+
+        // spending from a clone does not affect the original
+        let mut plan = tracker.clone();
+        plan.spend(usage(0.6, 0.)).unwrap();
+        assert!(!plan.can_spend(&usage(0.1, 0.)).unwrap());
+        assert!((get_epsilon(tracker.spent()).unwrap() - 0.4).abs() < 1e-12);
+
+        // many small pieces may spend the entire budget, despite rounding
+        tracker.reset();
+        (0..10).for_each(|_| tracker.spend(usage(0.1, 0.)).unwrap());
+        assert!(get_epsilon(&tracker.remaining().unwrap()).unwrap() < 1e-12);
+        assert!(tracker.spend(usage(0., 1e-9)).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_automatic_mechanism {
+    use crate::bindings::Analysis;
+    use crate::proto;
+    use crate::utilities::privacy::get_automatic_mechanism;
+
+    #[test]
+    fn test_preference() {
+        let usage = vec![proto::PrivacyUsage {
+            distance: Some(proto::privacy_usage::Distance::Approximate(proto::privacy_usage::DistanceApproximate {
+                epsilon: 1., delta: 1e-6
+            }))
+        }];
+        let mut privacy_definition = Analysis::new().privacy_definition;
+        assert_eq!(get_automatic_mechanism(&privacy_definition, &usage), "snapping");
+
+        // the gaussian mechanism is not preferred while floating-point protections are enabled
+        privacy_definition.default_mechanism = proto::privacy_definition::MechanismPreference::Approximate as i32;
+        assert_eq!(get_automatic_mechanism(&privacy_definition, &usage), "snapping");
+
+        privacy_definition.protect_floating_point = false;
+        assert_eq!(get_automatic_mechanism(&privacy_definition, &usage), "gaussian");
+        assert_eq!(get_automatic_mechanism(&privacy_definition, &[]), "laplace");
+    }
+}
+
+#[cfg(test)]
+mod test_parallel_composition {
+    use ndarray::{Array1, Array2};
+
+    use crate::base::Value;
+    use crate::bindings::{Analysis, privacy_usage};
+    use crate::proto;
+    use crate::utilities::privacy::get_epsilon;
+
+    /// dp sums over each category of a partition, and over the data before it is partitioned.
+    /// If `shared`, the clamps of the partitioned and unpartitioned data share the same bounds
+    fn partitioned_analysis(shared: bool) -> Analysis {
+        let mut analysis = Analysis::new();
+        analysis.privacy_definition.protect_floating_point = false;
+        let data = analysis.literal()
+            .value(Array2::from_shape_fn((100, 1), |(i, _)| (i % 10) as f64).into_dyn().into())
+            .value_public(true)
+            .build();
+        let by = analysis.literal()
+            .value(Array1::from_shape_fn(100, |i| ["a", "b"][i % 2].to_string()).into_dyn().into())
+            .value_public(true)
+            .build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let partitioned = analysis.partition(data).by(by).build();
+
+        for name in &["a", "b"] {
+            let names = analysis.literal().value(Value::from(name.to_string())).value_public(true).build();
+            let indexed = analysis.index(partitioned).names(names).build();
+            let clamped = analysis.clamp(indexed).lower(lower).upper(upper).build();
+            analysis.dp_sum(clamped, privacy_usage(1., 0.)).mechanism("Laplace".to_string()).build();
+        }
+
+        let (lower, upper) = if shared { (lower, upper) } else {(
+            analysis.literal().value(0.0.into()).value_public(true).build(),
+            analysis.literal().value(10.0.into()).value_public(true).build()
+        )};
+        let clamped = analysis.clamp(data).lower(lower).upper(upper).build();
+        analysis.dp_sum(clamped, privacy_usage(1., 0.)).mechanism("Laplace".to_string()).build();
+        analysis
+    }
+
+    #[test]
+    fn test_parallel_composition() {
+        // the categories compose in parallel, and the unpartitioned sum composes sequentially with them
+        for shared in &[false, true] {
+            // sharing the public bounds does not connect the unpartitioned sum to the partition
+            let analysis = partitioned_analysis(*shared);
+            let (_, warnings) = crate::get_properties(
+                Some(analysis.privacy_definition.clone()), analysis.components.clone(), analysis.release.clone(), vec![]).unwrap();
+            assert!(warnings.is_empty(), "{:?}", warnings);
+            let total = crate::compute_privacy_usage(
+                analysis.privacy_definition, analysis.components, analysis.release).unwrap();
+            assert!((get_epsilon(&total).unwrap() - 2.).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_partition_mixed_with_private_data() {
+        let mut analysis = partitioned_analysis(false);
+        let partitioned = *analysis.components.iter()
+            .find(|(_, component)| matches!(component.variant, Some(proto::component::Variant::Partition(_))))
+            .unwrap().0;
+
+        // private data that does not come from the partition
+        let private = analysis.literal()
+            .value(Array2::from_shape_fn((50, 1), |(i, _)| i as f64).into_dyn().into())
+            .value_public(false)
+            .build();
+        let private = analysis.to_float(private).build();
+        let lower = analysis.literal().value(0.0.into()).value_public(true).build();
+        let upper = analysis.literal().value(10.0.into()).value_public(true).build();
+        let number_rows = analysis.literal().value(50.into()).value_public(true).build();
+        let number_columns = analysis.literal().value(1.into()).value_public(true).build();
+        let private = analysis.resize(private)
+            .number_rows(number_rows).number_columns(number_columns)
+            .lower(lower).upper(upper)
+            .build();
+
+        let names = analysis.literal().value(Value::from("a".to_string())).value_public(true).build();
+        let indexed = analysis.index(partitioned).names(names).build();
+        let indexed = analysis.resize(indexed)
+            .number_rows(number_rows)
+            .lower(lower).upper(upper)
+            .build();
+        let mixed = analysis.add(indexed, private).build();
+        let clamped = analysis.clamp(mixed).lower(lower).upper(upper).build();
+        let imputed = analysis.impute(clamped).lower(lower).upper(upper).build();
+        analysis.dp_sum(imputed, privacy_usage(1., 0.)).mechanism("Laplace".to_string()).build();
+
+        let error = crate::get_properties(
+            Some(analysis.privacy_definition.clone()), analysis.components.clone(), analysis.release.clone(), vec![]).unwrap_err();
+        assert!(error.to_string().contains("not from the partition"), "{}", error);
+        assert!(crate::validate_analysis(
+            Some(analysis.privacy_definition.clone()), analysis.components.clone(), analysis.release.clone()).is_err());
+        assert!(crate::compute_privacy_usage(
+            analysis.privacy_definition, analysis.components, analysis.release).is_err());
+    }
+}
@@ -4,8 +4,10 @@ use std::collections::HashMap;
 
 use error_chain::ChainedError;
 use indexmap::IndexMap;
+use ndarray::{ArrayD, Ix2};
 
 use crate::{Float, Integer, proto};
+use crate::errors::*;
 use crate::base::{
     AggregatorProperties, Array, ArrayProperties, ComponentExpansion, DataframeProperties, DataType, GroupId,
     IndexKey, Jagged, JaggedProperties, Nature,
@@ -81,13 +83,20 @@ pub fn parse_array1d(value: proto::Array1d) -> Vector1D {
             .into_iter().map(|v| v as Integer).collect()),
         proto::array1d::Data::F64(vector) => Vector1D::Float(parse_array1d_f64(vector)
             .into_iter().map(|v| v as Float).collect()),
+        proto::array1d::Data::F32(vector) => Vector1D::Float(vector.data
+            .into_iter().map(Float::from).collect()),
     }
 }
 
 
 pub fn parse_array(value: proto::Array) -> Array {
     let shape: Vec<usize> = value.shape.into_iter().map(|x| x as usize).collect();
-    match parse_array1d(value.flattened.unwrap()) {
+    let flattened = value.flattened.unwrap();
+    // single precision is preserved for arrays, but widened for other containers
+    if let Some(proto::array1d::Data::F32(vector)) = flattened.data {
+        return Array::F32(ndarray::Array::from_shape_vec(shape, vector.data).unwrap().into_dyn())
+    }
+    match parse_array1d(flattened) {
         Vector1D::Bool(vector) => Array::Bool(ndarray::Array::from_shape_vec(shape, vector).unwrap().into_dyn()),
         Vector1D::Int(vector) => Array::Int(ndarray::Array::from_shape_vec(shape, vector).unwrap().into_dyn()),
         Vector1D::Float(vector) => Array::Float(ndarray::Array::from_shape_vec(shape, vector).unwrap().into_dyn()),
@@ -118,6 +127,8 @@ pub fn parse_data_type(value: proto::DataType) -> DataType {
         proto::DataType::F64 => DataType::Float,
         proto::DataType::I64 => DataType::Int,
         proto::DataType::String => DataType::Str,
+        proto::DataType::F32 => DataType::F32,
+        proto::DataType::DateTime => DataType::DateTime,
     }
 }
 
@@ -129,12 +140,13 @@ pub fn parse_jagged(value: proto::Jagged) -> Jagged {
                 Vector1D::Bool(vector) => vector,
                 _ => panic!()
             }).collect::<Vec<Vec<bool>>>()),
-        proto::DataType::F64 => Jagged::Float(value.data.into_iter()
+        // jagged arrays are always widened to double precision
+        proto::DataType::F64 | proto::DataType::F32 => Jagged::Float(value.data.into_iter()
             .map(|column| match parse_array1d(column) {
                 Vector1D::Float(vector) => vector,
                 _ => panic!()
             }).collect::<Vec<Vec<Float>>>()),
-        proto::DataType::I64 => Jagged::Int(value.data.into_iter()
+        proto::DataType::I64 | proto::DataType::DateTime => Jagged::Int(value.data.into_iter()
             .map(|column| match parse_array1d(column) {
                 Vector1D::Int(vector) => vector,
                 _ => panic!()
@@ -401,6 +413,12 @@ pub fn serialize_array(value: Array) -> proto::Array {
             flattened: Some(serialize_array1d(Vector1D::Float(array.iter().copied().collect()))),
             shape: array.shape().iter().map(|y| { *y as u64 }).collect(),
         },
+        Array::F32(array) => proto::Array {
+            flattened: Some(proto::Array1d {
+                data: Some(proto::array1d::Data::F32(proto::Array1dF32 { data: array.iter().copied().collect() }))
+            }),
+            shape: array.shape().iter().map(|y| { *y as u64 }).collect(),
+        },
         Array::Int(array) => proto::Array {
             flattened: Some(serialize_array1d(Vector1D::Int(array.iter().copied().collect()))),
             shape: array.shape().iter().map(|y| { *y as u64 }).collect(),
@@ -433,6 +451,8 @@ pub fn serialize_data_type(value: DataType) -> proto::DataType {
         DataType::Float => proto::DataType::F64,
         DataType::Int => proto::DataType::I64,
         DataType::Str => proto::DataType::String,
+        DataType::F32 => proto::DataType::F32,
+        DataType::DateTime => proto::DataType::DateTime,
     }
 }
 
@@ -650,4 +670,126 @@ pub fn serialize_component_expansion(value: ComponentExpansion) -> proto::Compon
 #[doc(hidden)]
 pub fn serialize_error(err: crate::Error) -> proto::Error {
     proto::Error { message: err.display_chain().to_string() }
-}
\ No newline at end of file
+}
+
+// CSV
+
+/// Render a released Array or Dataframe as CSV text.
+///
+/// Arrays of zero or one dimension are rendered as a single column, and two-dimensional arrays as a table.
+/// Each column of a Dataframe must be a single column of the same length, and is headed by its key,
+/// unless `column_names` are provided, for example from the variable names of the node.
+/// Null floats are rendered as empty cells.
+pub fn serialize_value_csv(value: &Value, column_names: Option<&[IndexKey]>) -> Result<String> {
+    let (header, columns) = match value {
+        Value::Array(array) => (None, array_to_cells(array)?),
+        Value::Dataframe(dataframe) => (
+            Some(dataframe.keys().cloned().collect::<Vec<IndexKey>>()),
+            dataframe.iter()
+                .map(|(name, column)| {
+                    let mut cells = array_to_cells(column.ref_array()
+                        .chain_err(|| format!("column {}:", name.to_string()))?)?;
+                    if cells.len() != 1 {
+                        bail!("column {}: must have exactly one column, but has {}", name.to_string(), cells.len())
+                    }
+                    Ok(cells.remove(0))
+                })
+                .collect::<Result<Vec<Vec<String>>>>()?),
+        _ => bail!("only arrays and dataframes may be rendered as csv")
+    };
+
+    let header = match column_names {
+        Some(column_names) => Some(column_names.to_vec()),
+        None => header
+    };
+
+    let num_rows = columns.first().map(|column| column.len()).unwrap_or(0);
+    if columns.iter().any(|column| column.len() != num_rows) {
+        bail!("every column must have the same number of rows")
+    }
+
+    let mut csv = String::new();
+    if let Some(header) = header {
+        if header.len() != columns.len() {
+            bail!("{} column names were provided for {} columns", header.len(), columns.len())
+        }
+        csv.push_str(&header.iter()
+            .map(|name| escape_csv_cell(name.to_string()))
+            .collect::<Vec<String>>().join(","));
+        csv.push('\n');
+    }
+    (0..num_rows).for_each(|row_idx| {
+        csv.push_str(&columns.iter()
+            .map(|column| escape_csv_cell(column[row_idx].clone()))
+            .collect::<Vec<String>>().join(","));
+        csv.push('\n');
+    });
+    Ok(csv)
+}
+
+/// Collect the cells of each column of an array of at most two dimensions
+fn array_to_cells(array: &Array) -> Result<Vec<Vec<String>>> {
+    fn to_cells<T>(data: &ArrayD<T>, format: impl Fn(&T) -> String) -> Result<Vec<Vec<String>>> {
+        Ok(match data.ndim() {
+            0 | 1 => vec![data.iter().map(format).collect()],
+            2 => data.view().into_dimensionality::<Ix2>()?.gencolumns().into_iter()
+                .map(|column| column.iter().map(&format).collect())
+                .collect(),
+            _ => bail!("array may be at most 2-dimensional")
+        })
+    }
+
+    match array {
+        Array::Bool(data) => to_cells(data, bool::to_string),
+        Array::Int(data) => to_cells(data, Integer::to_string),
+        Array::Float(data) => to_cells(data, |v| if v.is_nan() { String::new() } else { v.to_string() }),
+        Array::F32(data) => to_cells(data, |v| if v.is_nan() { String::new() } else { v.to_string() }),
+        Array::Str(data) => to_cells(data, String::clone),
+    }
+}
+
+/// Quote a cell if it contains a delimiter, quote or line break
+fn escape_csv_cell(cell: String) -> String {
+    if cell.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else { cell }
+}
+
+#[cfg(test)]
+mod test_csv {
+    use indexmap::IndexMap;
+    use ndarray::{arr0, arr1, arr2};
+
+    use crate::base::{IndexKey, Value};
+    use crate::Float;
+    use crate::utilities::serial::serialize_value_csv;
+
+    #[test]
+    fn test_array() {
+        let value: Value = arr2(&[[1., Float::NAN], [2.5, -3.]]).into_dyn().into();
+        assert_eq!(serialize_value_csv(&value, None).unwrap(), "1,\n2.5,-3\n");
+
+        let names = vec![IndexKey::from("a"), IndexKey::from("b, c")];
+        assert_eq!(serialize_value_csv(&value, Some(&names)).unwrap(), "a,\"b, c\"\n1,\n2.5,-3\n");
+        assert!(serialize_value_csv(&value, Some(&names[..1])).is_err());
+
+        // one-dimensional arrays are a single column
+        let value: Value = arr1(&[true, false]).into_dyn().into();
+        assert_eq!(serialize_value_csv(&value, None).unwrap(), "true\nfalse\n");
+
+        let value: Value = arr0(3).into_dyn().into();
+        assert_eq!(serialize_value_csv(&value, None).unwrap(), "3\n");
+    }
+
+    #[test]
+    fn test_dataframe() {
+        let mut dataframe = IndexMap::<IndexKey, Value>::new();
+        dataframe.insert("name".into(), arr1(&["x".to_string(), "say \"y\"".to_string()]).into_dyn().into());
+        dataframe.insert("count".into(), arr2(&[[1], [2]]).into_dyn().into());
+        let value = Value::Dataframe(dataframe.clone());
+        assert_eq!(serialize_value_csv(&value, None).unwrap(), "name,count\nx,1\n\"say \"\"y\"\"\",2\n");
+
+        dataframe.insert("extra".into(), arr1(&[1., 2., 3.]).into_dyn().into());
+        assert!(serialize_value_csv(&Value::Dataframe(dataframe), None).is_err());
+    }
+}